@@ -0,0 +1,308 @@
+//! Built-in implementations of each [`Flow`](crate::Flow).
+//!
+//! Generator libraries that do not need custom behavior can register blocks with these flows
+//! instead of writing their own generate functions:
+//!
+//! ```ignore
+//! cli.netlist::<Inverter, _>("inverter", Spice, "spice")
+//!     .gds::<Inverter>("inverter", to_gds)
+//!     .drc::<Inverter>("inverter", to_gds, MAGIC_TECH_FILE)
+//!     .simulate::<Spectre, InverterTb, _>("inverter_tb", tran);
+//! ```
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use gds::GdsUnits;
+use gdsconv::GdsLayer;
+use magic::drc::{run_drc, DrcParams};
+use magic_netgen::{run_lvs, LvsParams};
+use spice::Spice;
+use substrate::arcstr::ArcStr;
+use substrate::block::registry::{FromParams, Generated};
+use substrate::context::Context;
+use substrate::error::{Error, Result};
+use substrate::layout::{CellLayer, Layout};
+use substrate::schematic::netlist::ConvertibleNetlister;
+use substrate::schematic::Schematic;
+use substrate::simulation::analysis::{AcOutput, DcOutput, NoiseOutput, TranOutput};
+use substrate::simulation::export::{write_csv, Column};
+use substrate::simulation::{Simulator, SupportedBy, Testbench};
+
+use crate::{Cli, Flow};
+
+/// A function converting a layout library to GDS layers.
+pub trait ToGds<B: Layout>:
+    Fn(&layir::Library<CellLayer<B>>) -> (layir::Library<GdsLayer>, GdsUnits) + Send + Sync + 'static
+{
+}
+
+impl<B: Layout, F> ToGds<B> for F where
+    F: Fn(&layir::Library<CellLayer<B>>) -> (layir::Library<GdsLayer>, GdsUnits)
+        + Send
+        + Sync
+        + 'static
+{
+}
+
+/// An analysis output that can be written by the [`simulate`](Cli::simulate) flow.
+pub trait SimulationOutput {
+    /// Returns the named columns of data in this output.
+    ///
+    /// All columns must have the same length.
+    fn columns(&self) -> Vec<(String, Vec<f64>)>;
+}
+
+/// Returns the entries of `map` sorted by name, so that columns are written in a stable order.
+fn sorted<V>(map: &HashMap<ArcStr, V>) -> Vec<(&str, &V)> {
+    let mut entries = map
+        .iter()
+        .map(|(name, value)| (name.as_str(), value))
+        .collect::<Vec<_>>();
+    entries.sort_by_key(|(name, _)| *name);
+    entries
+}
+
+impl SimulationOutput for TranOutput {
+    fn columns(&self) -> Vec<(String, Vec<f64>)> {
+        let mut columns = vec![("time".to_string(), self.time.to_vec())];
+        columns.extend(
+            sorted(&self.raw_values)
+                .into_iter()
+                .map(|(name, values)| (name.to_string(), values.to_vec())),
+        );
+        columns
+    }
+}
+
+impl SimulationOutput for AcOutput {
+    fn columns(&self) -> Vec<(String, Vec<f64>)> {
+        let mut columns = vec![("freq".to_string(), self.freq.to_vec())];
+        for (name, values) in sorted(&self.raw_values) {
+            columns.push((format!("re({name})"), values.iter().map(|v| v.re).collect()));
+            columns.push((format!("im({name})"), values.iter().map(|v| v.im).collect()));
+        }
+        columns
+    }
+}
+
+impl SimulationOutput for DcOutput {
+    fn columns(&self) -> Vec<(String, Vec<f64>)> {
+        sorted(&self.raw_values)
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), vec![*value]))
+            .collect()
+    }
+}
+
+impl SimulationOutput for NoiseOutput {
+    fn columns(&self) -> Vec<(String, Vec<f64>)> {
+        let mut columns = vec![
+            ("freq".to_string(), self.freq.to_vec()),
+            ("output_noise".to_string(), self.output_noise.to_vec()),
+        ];
+        // Input-referred noise is only computed if the analysis has an input source.
+        if !self.input_noise.is_empty() {
+            columns.push(("input_noise".to_string(), self.input_noise.to_vec()));
+        }
+        columns
+    }
+}
+
+fn io_error(err: std::io::Error) -> Error {
+    Error::Io(Arc::new(err))
+}
+
+fn tool_error(err: impl Into<anyhow::Error>) -> Error {
+    Error::Anyhow(Arc::new(err.into()))
+}
+
+fn write_gds<B: Layout>(
+    ctx: &Context,
+    block: B,
+    to_gds: &impl ToGds<B>,
+    path: PathBuf,
+) -> Result<PathBuf> {
+    ctx.write_layout(block, to_gds, &path)?;
+    Ok(path)
+}
+
+impl Cli {
+    /// Registers block `B` under `name` with the `netlist` flow.
+    ///
+    /// The block is netlisted by `netlister` with default options
+    /// to `<OUT>/<NAME>.<extension>`.
+    pub fn netlist<B, N>(&mut self, name: &str, netlister: N, extension: &str) -> &mut Self
+    where
+        B: Schematic + FromParams,
+        N: ConvertibleNetlister<B::Schema> + Send + Sync + 'static,
+        for<'a> N::Options<'a>: Default,
+    {
+        let file = format!("{name}.{extension}");
+        self.flow(Flow::Netlist)
+            .register(name, move |ctx: &Context, block: B, out_dir: &Path| {
+                let path = out_dir.join(&file);
+                netlister.write_netlist_to_file(ctx, block, &path, Default::default())?;
+                Ok(Generated::new().with_netlist(path))
+            });
+        self
+    }
+
+    /// Registers block `B` under `name` with the `gds` flow.
+    ///
+    /// The layout of the block is converted to GDS layers by `to_gds`
+    /// and written to `<OUT>/<NAME>.gds`.
+    pub fn gds<B: Layout + FromParams>(&mut self, name: &str, to_gds: impl ToGds<B>) -> &mut Self {
+        let file = format!("{name}.gds");
+        self.flow(Flow::Gds)
+            .register(name, move |ctx: &Context, block: B, out_dir: &Path| {
+                let gds = write_gds(ctx, block, &to_gds, out_dir.join(&file))?;
+                Ok(Generated::new().with_gds(gds))
+            });
+        self
+    }
+
+    /// Registers testbench `T` under `name` with the `simulate` flow.
+    ///
+    /// The testbench is simulated with `analysis` using simulator `S` and default options,
+    /// working in `<OUT>/sim`. The columns of the output are written to `<OUT>/<NAME>.csv` and
+    /// returned as [data](Generated::data).
+    ///
+    /// Returns an error if `S` is not installed in the [`Context`].
+    pub fn simulate<S, T, A>(&mut self, name: &str, analysis: A) -> &mut Self
+    where
+        S: Simulator<Options: Default, Error: std::error::Error + Send + Sync + 'static>,
+        T: Testbench<S> + FromParams,
+        A: SupportedBy<S> + Clone + Send + Sync + 'static,
+        A::Output: SimulationOutput,
+    {
+        let file = format!("{name}.csv");
+        self.flow(Flow::Simulate)
+            .register(name, move |ctx: &Context, block: T, out_dir: &Path| {
+                if ctx.get_installation::<S>().is_none() {
+                    return Err(tool_error(anyhow::anyhow!(
+                        "simulator `{}` is not installed",
+                        std::any::type_name::<S>()
+                    )));
+                }
+                let sim = ctx.get_sim_controller::<S, T>(block, out_dir.join("sim"))?;
+                let output = sim
+                    .simulate_default(Default::default(), analysis.clone())
+                    .map_err(|err| Error::Boxed(Arc::new(err)))?;
+
+                let columns = output.columns();
+                let path = out_dir.join(&file);
+                let out = BufWriter::new(File::create(&path).map_err(io_error)?);
+                write_csv(
+                    out,
+                    &columns
+                        .iter()
+                        .map(|(name, values)| Column::new(name, values))
+                        .collect::<Vec<_>>(),
+                )
+                .map_err(io_error)?;
+
+                Ok(columns.into_iter().fold(
+                    Generated::new().with_output("csv", path),
+                    |generated, (name, values)| generated.with_data(name, values),
+                ))
+            });
+        self
+    }
+
+    /// Registers block `B` under `name` with the `drc` flow.
+    ///
+    /// The layout of the block is written to `<OUT>/<NAME>.gds` as in the
+    /// [`gds`](Cli::gds) flow, then checked by Magic using the tech file at `tech_file`.
+    /// The DRC report is written to `<OUT>/drc_results.rpt`.
+    ///
+    /// Returns an error if any rule is violated.
+    pub fn drc<B: Layout + FromParams>(
+        &mut self,
+        name: &str,
+        to_gds: impl ToGds<B>,
+        tech_file: impl Into<PathBuf>,
+    ) -> &mut Self {
+        let file = format!("{name}.gds");
+        let tech_file = tech_file.into();
+        self.flow(Flow::Drc)
+            .register(name, move |ctx: &Context, block: B, out_dir: &Path| {
+                let cell_name = block.name();
+                let gds = write_gds(ctx, block, &to_gds, out_dir.join(&file))?;
+                let report = out_dir.join("drc_results.rpt");
+                let data = run_drc(&DrcParams {
+                    cell_name: &cell_name,
+                    work_dir: &out_dir.join("drc"),
+                    gds_path: &gds,
+                    tech_file_path: &tech_file,
+                    drc_report_path: &report,
+                })
+                .map_err(tool_error)?;
+
+                let violations = data
+                    .rule_checks
+                    .iter()
+                    .map(|check| check.num_results as usize)
+                    .sum::<usize>();
+                if violations > 0 {
+                    return Err(tool_error(anyhow::anyhow!(
+                        "found {violations} DRC violations in cell `{cell_name}`; see {}",
+                        report.display()
+                    )));
+                }
+                Ok(Generated::new()
+                    .with_gds(gds)
+                    .with_output("drc report", report))
+            });
+        self
+    }
+
+    /// Registers block `B` under `name` with the `lvs` flow.
+    ///
+    /// The layout of the block is written to `<OUT>/<NAME>.gds` as in the
+    /// [`gds`](Cli::gds) flow, extracted by Magic using the tech file at `magic_tech_file`,
+    /// and compared to the block's schematic by Netgen using the setup file at
+    /// `netgen_setup_file`. Intermediate files are written to `<OUT>/lvs`.
+    ///
+    /// Returns an error if the layout and schematic do not match.
+    pub fn lvs<B>(
+        &mut self,
+        name: &str,
+        to_gds: impl ToGds<B>,
+        magic_tech_file: impl Into<PathBuf>,
+        netgen_setup_file: impl Into<PathBuf>,
+    ) -> &mut Self
+    where
+        B: Layout + Schematic<Schema = Spice> + FromParams + Clone,
+    {
+        let file = format!("{name}.gds");
+        let magic_tech_file = magic_tech_file.into();
+        let netgen_setup_file = netgen_setup_file.into();
+        self.flow(Flow::Lvs)
+            .register(name, move |ctx: &Context, block: B, out_dir: &Path| {
+                let cell_name = block.name();
+                let gds = write_gds(ctx, block.clone(), &to_gds, out_dir.join(&file))?;
+                let work_dir = out_dir.join("lvs");
+                let output = run_lvs(LvsParams {
+                    schematic: Arc::new(block),
+                    gds_path: gds.clone(),
+                    layout_cell_name: cell_name.clone(),
+                    work_dir: work_dir.clone(),
+                    magic_tech_file_path: magic_tech_file.clone(),
+                    netgen_setup_file_path: netgen_setup_file.clone(),
+                    ctx: ctx.clone(),
+                })?;
+                if !output.matches {
+                    return Err(tool_error(anyhow::anyhow!(
+                        "layout and schematic of cell `{cell_name}` do not match; see {}",
+                        work_dir.display()
+                    )));
+                }
+                Ok(Generated::new().with_gds(gds).with_output("lvs", work_dir))
+            });
+        self
+    }
+}
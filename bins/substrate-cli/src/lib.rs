@@ -0,0 +1,284 @@
+//! A command-line interface for common Substrate flows.
+//!
+//! Generator libraries register their blocks with a [`Cli`] and invoke the [`main!`] macro
+//! to obtain a binary that can netlist, export GDS, simulate, and verify any registered block
+//! from the command line. Each [`Flow`] has a built-in implementation (see [`flows`]):
+//!
+//! ```ignore
+//! fn setup(cli: &mut substrate_cli::Cli) {
+//!     cli.context(sky130::sky130_open_ctx)
+//!         .netlist::<Inverter, _>("inverter", Spice, "spice")
+//!         .gds::<Inverter>("inverter", to_gds)
+//!         .drc::<Inverter>("inverter", to_gds, MAGIC_TECH_FILE);
+//! }
+//!
+//! substrate_cli::main!(setup);
+//! ```
+//!
+//! Flows needing custom behavior can register their own generate functions using
+//! [`Cli::flow`].
+//!
+//! The resulting binary can then be invoked as follows:
+//!
+//! ```text
+//! my-generators gds inverter -p strength=2 -o build/inverter
+//! ```
+#![warn(missing_docs)]
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use anyhow::Context as _;
+use clap::{Parser, Subcommand};
+use substrate::block::registry::{Params, Registry};
+use substrate::context::Context;
+
+pub mod flows;
+#[cfg(test)]
+mod tests;
+pub mod vdivider;
+
+pub use flows::{SimulationOutput, ToGds};
+
+/// A flow that can be run on a registered block.
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Flow {
+    /// Writes a netlist.
+    Netlist,
+    /// Writes a GDS file.
+    Gds,
+    /// Runs a simulation.
+    Simulate,
+    /// Runs design rule checking.
+    Drc,
+    /// Runs layout versus schematic checking.
+    Lvs,
+}
+
+impl Flow {
+    /// All available flows.
+    pub const ALL: [Flow; 5] = [
+        Flow::Netlist,
+        Flow::Gds,
+        Flow::Simulate,
+        Flow::Drc,
+        Flow::Lvs,
+    ];
+
+    /// Returns the flow run by the subcommand named `name`, if any.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|flow| flow.name() == name)
+    }
+
+    /// The default directory in which outputs of this flow are written for `generator`.
+    pub fn default_out_dir(&self, generator: &str) -> PathBuf {
+        PathBuf::from("build").join(generator).join(self.name())
+    }
+
+    /// The name of the subcommand that runs this flow.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Flow::Netlist => "netlist",
+            Flow::Gds => "gds",
+            Flow::Simulate => "simulate",
+            Flow::Drc => "drc",
+            Flow::Lvs => "lvs",
+        }
+    }
+}
+
+impl Display for Flow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+type ContextFn = Box<dyn Fn() -> Context + Send + Sync>;
+
+/// The set of generators available to the command-line interface.
+pub struct Cli {
+    context: ContextFn,
+    flows: HashMap<Flow, Registry>,
+}
+
+impl Default for Cli {
+    fn default() -> Self {
+        Self {
+            context: Box::new(Context::new),
+            flows: HashMap::new(),
+        }
+    }
+}
+
+impl Cli {
+    /// Creates a command-line interface with no registered generators.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the function used to create the [`Context`] in which blocks are generated.
+    ///
+    /// Defaults to [`Context::new`].
+    pub fn context(&mut self, context: impl Fn() -> Context + Send + Sync + 'static) -> &mut Self {
+        self.context = Box::new(context);
+        self
+    }
+
+    /// Returns the registry of generators that implement `flow`.
+    pub fn flow(&mut self, flow: Flow) -> &mut Registry {
+        self.flows.entry(flow).or_default()
+    }
+
+    /// Returns the registry of generators that implement `flow`, if any have been registered.
+    pub fn registry(&self, flow: Flow) -> Option<&Registry> {
+        self.flows.get(&flow)
+    }
+
+    /// Creates a new [`Context`] using the function configured by [`Cli::context`].
+    pub fn create_context(&self) -> Context {
+        (self.context)()
+    }
+
+    /// Runs the command-line interface with the given arguments.
+    ///
+    /// The first argument should be the name of the binary.
+    pub fn run_with_args<I, T>(&self, args: I) -> anyhow::Result<()>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<std::ffi::OsString> + Clone,
+    {
+        let args = Args::try_parse_from(args)?;
+        match args.command {
+            Command::List => {
+                for flow in Flow::ALL {
+                    let Some(registry) = self.flows.get(&flow) else {
+                        continue;
+                    };
+                    for name in registry.names() {
+                        println!("{flow}\t{name}");
+                    }
+                }
+            }
+            Command::Run(command) => {
+                let (flow, args) = command.into_parts();
+                let registry = self
+                    .flows
+                    .get(&flow)
+                    .filter(|registry| registry.contains(&args.generator))
+                    .with_context(|| {
+                        format!("no generator named `{}` supports `{flow}`", args.generator)
+                    })?;
+                let mut params = Params::new();
+                for param in args.params.iter() {
+                    for (name, value) in param.parse::<Params>()?.iter() {
+                        params.set(name.clone(), value.clone());
+                    }
+                }
+                let out_dir = args
+                    .out
+                    .unwrap_or_else(|| flow.default_out_dir(&args.generator));
+                let ctx = self.create_context();
+                let generated = registry
+                    .generate(&ctx, &args.generator, &params, &out_dir)
+                    .with_context(|| {
+                        format!("failed to run `{flow}` on generator `{}`", args.generator)
+                    })?;
+                for (name, path) in generated.paths() {
+                    println!("{name}: {}", path.display());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs the command-line interface with the arguments passed to the current process,
+    /// printing any errors to standard error.
+    pub fn run(&self) -> ExitCode {
+        match self.run_with_args(std::env::args_os()) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => match e.downcast_ref::<clap::Error>() {
+                Some(e) => {
+                    let _ = e.print();
+                    ExitCode::from(e.exit_code() as u8)
+                }
+                None => {
+                    eprintln!("Error: {e:?}");
+                    ExitCode::FAILURE
+                }
+            },
+        }
+    }
+}
+
+/// Defines a `main` function that runs a [`Cli`] configured by `setup`.
+///
+/// `setup` must be a function or closure taking a `&mut Cli`.
+#[macro_export]
+macro_rules! main {
+    ($setup:expr) => {
+        fn main() -> ::std::process::ExitCode {
+            let mut cli = $crate::Cli::new();
+            ($setup)(&mut cli);
+            cli.run()
+        }
+    };
+}
+
+#[derive(Parser)]
+#[command(version, about = "Run common Substrate flows on registered generators")]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Lists registered generators and the flows they support.
+    List,
+    #[command(flatten)]
+    Run(FlowCommand),
+}
+
+#[derive(Subcommand)]
+enum FlowCommand {
+    /// Writes a netlist for a generator.
+    Netlist(FlowArgs),
+    /// Writes a GDS file for a generator.
+    Gds(FlowArgs),
+    /// Runs a simulation of a generator.
+    Simulate(FlowArgs),
+    /// Runs design rule checking on a generator.
+    Drc(FlowArgs),
+    /// Runs layout versus schematic checking on a generator.
+    Lvs(FlowArgs),
+}
+
+impl FlowCommand {
+    fn into_parts(self) -> (Flow, FlowArgs) {
+        match self {
+            FlowCommand::Netlist(args) => (Flow::Netlist, args),
+            FlowCommand::Gds(args) => (Flow::Gds, args),
+            FlowCommand::Simulate(args) => (Flow::Simulate, args),
+            FlowCommand::Drc(args) => (Flow::Drc, args),
+            FlowCommand::Lvs(args) => (Flow::Lvs, args),
+        }
+    }
+}
+
+#[derive(clap::Args)]
+struct FlowArgs {
+    /// The name of the registered generator.
+    generator: String,
+    /// Parameters of the generated block, as comma-separated `name=value` pairs.
+    ///
+    /// May be specified multiple times.
+    #[arg(short, long = "param", value_name = "NAME=VALUE")]
+    params: Vec<String>,
+    /// The directory in which outputs are written.
+    ///
+    /// Defaults to `build/<GENERATOR>/<FLOW>`.
+    #[arg(short, long)]
+    out: Option<PathBuf>,
+}
@@ -0,0 +1,19 @@
+//! A `substrate-cli` binary with a small set of built-in generators.
+//!
+//! Generator libraries typically define their own binaries using [`substrate_cli::main!`].
+//! This binary registers a resistive voltage divider, which can be used to try out the
+//! command-line interface without a PDK:
+//!
+//! ```text
+//! substrate-cli netlist vdivider -p r1=100,r2=200
+//! ```
+
+use spice::Spice;
+use substrate_cli::vdivider::Vdivider;
+use substrate_cli::Cli;
+
+fn setup(cli: &mut Cli) {
+    cli.netlist::<Vdivider, _>("vdivider", Spice, "spice");
+}
+
+substrate_cli::main!(setup);
@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use spice::Spice;
+use substrate::arcstr::ArcStr;
+use substrate::block::registry::Generated;
+use substrate::context::Context;
+use substrate::simulation::analysis::TranOutput;
+
+use crate::vdivider::Vdivider;
+use crate::{Cli, Flow, SimulationOutput};
+
+const BUILD_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/build");
+
+fn get_path(test_name: &str, file_name: &str) -> PathBuf {
+    PathBuf::from(BUILD_DIR).join(test_name).join(file_name)
+}
+
+#[test]
+fn netlist_flow_writes_netlists() {
+    let mut cli = Cli::new();
+    cli.netlist::<Vdivider, _>("vdivider", Spice, "spice");
+    assert_eq!(
+        cli.registry(Flow::Netlist)
+            .unwrap()
+            .names()
+            .collect::<Vec<_>>(),
+        ["vdivider"]
+    );
+
+    let out = get_path("netlist_flow_writes_netlists", "out");
+    cli.run_with_args([
+        "substrate-cli",
+        "netlist",
+        "vdivider",
+        "-p",
+        "r1=300",
+        "-o",
+        out.to_str().unwrap(),
+    ])
+    .unwrap();
+
+    let netlist = std::fs::read_to_string(out.join("vdivider.spice")).unwrap();
+    assert!(netlist.contains("300"));
+    assert!(netlist.contains("100"));
+}
+
+#[test]
+fn missing_generators_and_flows_are_errors() {
+    let mut cli = Cli::new();
+    cli.netlist::<Vdivider, _>("vdivider", Spice, "spice");
+
+    let err = cli
+        .run_with_args(["substrate-cli", "gds", "vdivider"])
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("no generator named `vdivider` supports `gds`"));
+
+    let out = get_path("missing_generators_and_flows_are_errors", "out");
+    let err = cli
+        .run_with_args([
+            "substrate-cli",
+            "netlist",
+            "vdivider",
+            "-o",
+            out.to_str().unwrap(),
+        ])
+        .unwrap_err();
+    assert!(format!("{err:?}").contains("missing parameter `r1`"));
+}
+
+#[test]
+fn custom_flows_can_be_registered() {
+    let mut cli = Cli::new();
+    cli.context(Context::new).flow(Flow::Drc).register(
+        "vdivider",
+        |_ctx: &Context, block: Vdivider, out_dir: &std::path::Path| {
+            Ok(Generated::new().with_output("report", out_dir.join(format!("{}.rpt", block.r1))))
+        },
+    );
+    let out = get_path("custom_flows_can_be_registered", "out");
+    let generated = cli
+        .registry(Flow::Drc)
+        .unwrap()
+        .generate(
+            &cli.create_context(),
+            "vdivider",
+            &"r1=5".parse().unwrap(),
+            &out,
+        )
+        .unwrap();
+    assert_eq!(generated.outputs["report"], out.join("5.rpt"));
+}
+
+#[test]
+fn transient_outputs_are_written_in_name_order() {
+    let output = TranOutput {
+        time: Arc::new(vec![0., 1.]),
+        raw_values: HashMap::from([
+            (ArcStr::from("vout"), Arc::new(vec![0., 0.5])),
+            (ArcStr::from("vdd"), Arc::new(vec![1., 1.])),
+        ]),
+        saved_values: HashMap::new(),
+    };
+    assert_eq!(
+        output.columns(),
+        [
+            ("time".to_string(), vec![0., 1.]),
+            ("vdd".to_string(), vec![1., 1.]),
+            ("vout".to_string(), vec![0., 0.5]),
+        ]
+    );
+}
@@ -0,0 +1,62 @@
+//! A resistive voltage divider, registered by the `substrate-cli` binary.
+
+use rust_decimal::Decimal;
+use spice::{Resistor, Spice};
+use substrate::block::registry::{FromParams, Params, RegistryError};
+use substrate::block::Block;
+use substrate::error::Result;
+use substrate::schematic::{CellBuilder, Schematic};
+use substrate::types::schematic::IoNodeBundle;
+use substrate::types::{InOut, Io, Output, Signal};
+
+/// The interface of a [`Vdivider`].
+#[derive(Io, Clone, Default, Debug)]
+pub struct VdividerIo {
+    /// The top of the divider.
+    pub vdd: InOut<Signal>,
+    /// The bottom of the divider.
+    pub vss: InOut<Signal>,
+    /// The divided output.
+    pub out: Output<Signal>,
+}
+
+/// A resistive voltage divider.
+///
+/// Created from the parameters `r1` and `r2`; `r2` defaults to 100 ohms.
+#[derive(Block, Debug, Copy, Clone, Hash, PartialEq, Eq)]
+#[substrate(io = "VdividerIo")]
+pub struct Vdivider {
+    /// The top resistance.
+    pub r1: Decimal,
+    /// The bottom resistance.
+    pub r2: Decimal,
+}
+
+impl FromParams for Vdivider {
+    fn from_params(params: &Params) -> Result<Self, RegistryError> {
+        Ok(Self {
+            r1: params.get("r1")?,
+            r2: params.get_or("r2", Decimal::ONE_HUNDRED)?,
+        })
+    }
+}
+
+impl Schematic for Vdivider {
+    type Schema = Spice;
+    type NestedData = ();
+
+    fn schematic(
+        &self,
+        io: &IoNodeBundle<Self>,
+        cell: &mut CellBuilder<<Self as Schematic>::Schema>,
+    ) -> Result<Self::NestedData> {
+        let r1 = cell.instantiate(Resistor::new(self.r1));
+        let r2 = cell.instantiate(Resistor::new(self.r2));
+
+        cell.connect(io.vdd, r1.io().p);
+        cell.connect(io.out, r1.io().n);
+        cell.connect(io.out, r2.io().p);
+        cell.connect(io.vss, r2.io().n);
+        Ok(())
+    }
+}
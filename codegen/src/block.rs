@@ -12,6 +12,8 @@ pub struct BlockInputReceiver {
     ident: syn::Ident,
     generics: syn::Generics,
     io: syn::Type,
+    #[darling(default)]
+    version: Option<String>,
 }
 
 impl ToTokens for BlockInputReceiver {
@@ -21,6 +23,7 @@ impl ToTokens for BlockInputReceiver {
             ref ident,
             ref generics,
             ref io,
+            ref version,
             ..
         } = *self;
 
@@ -33,6 +36,16 @@ impl ToTokens for BlockInputReceiver {
         let (imp, ty, wher) = generics.split_for_impl();
 
         let name = ident.to_string().to_case(Case::Snake);
+        let version = version.as_ref().map(|version| {
+            quote! {
+                fn version(&self) -> ::std::option::Option<#substrate::arcstr::ArcStr> {
+                    ::std::option::Option::Some(#substrate::arcstr::literal!(#version))
+                }
+                fn parameters(&self) -> ::std::option::Option<#substrate::arcstr::ArcStr> {
+                    ::std::option::Option::Some(#substrate::arcstr::format!("{:?}", self))
+                }
+            }
+        });
 
         tokens.extend(quote! {
             impl #imp #substrate::block::Block for #ident #ty #wher {
@@ -44,6 +57,7 @@ impl ToTokens for BlockInputReceiver {
                 fn io(&self) -> <Self as #substrate::block::Block>::Io {
                     <<Self as #substrate::block::Block>::Io as ::std::default::Default>::default()
                 }
+                #version
             }
         });
     }
@@ -78,6 +78,10 @@ pub fn derive_nested_data(input: TokenStream) -> TokenStream {
 /// }
 /// ```
 ///
+/// The version of the block's generator (see `substrate::block::Block::version`) can be
+/// declared with a `#[substrate(version = "1.0.0")]` attribute. Versioned blocks must implement
+/// [`Debug`], which is used to describe their parameters.
+///
 /// This derive macro only works if you want to use the default value of the IO.
 /// If the IO type does not implement [`Default`], or you want to use a non-default
 /// value, you must implement `Block` manually.
@@ -25,6 +25,8 @@ mod tests;
 pub struct Config {
     /// Configuration for Substrate's persistent cache.
     pub cache: CacheConfig,
+    /// Configuration for external tools.
+    pub tools: ToolsConfig,
 }
 
 impl Config {
@@ -45,6 +47,7 @@ impl Config {
     fn from_raw_config(raw: &RawConfig) -> Result<Self> {
         Ok(Config {
             cache: CacheConfig::from_raw_config(raw)?,
+            tools: ToolsConfig::from_raw_config(raw)?,
         })
     }
 }
@@ -107,3 +110,19 @@ impl CacheConfig {
         Ok(builder.build())
     }
 }
+
+/// Configuration for external tools, such as simulators.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ToolsConfig {
+    /// A map from tool name (e.g. `spectre`) to the path of the tool's executable.
+    pub paths: HashMap<String, PathBuf>,
+}
+
+impl ToolsConfig {
+    fn from_raw_config(raw: &RawConfig) -> Result<Self> {
+        let paths: Option<_> = raw.get("tools.paths")?;
+        Ok(Self {
+            paths: paths.unwrap_or_default(),
+        })
+    }
+}
@@ -8,6 +8,7 @@ use substrate::block::Block;
 use substrate::schematic::{NestedData, Schematic};
 use substrate::types::schematic::{NestedNode, Node, NodeBundle};
 use substrate::types::{Array, InOut, Input, Io, Output, Signal, TestbenchIo};
+use substrate::units::Voltage;
 
 #[derive(Clone, Debug, Default, Io)]
 pub struct ColInvIo {
@@ -216,7 +217,7 @@ impl Schematic for CdsPexTb {
         }
         cell.connect(dut.io().din[31], io.vss);
 
-        let vsource = cell.instantiate(Vsource::dc(dec!(1.8)));
+        let vsource = cell.instantiate(Vsource::dc(Voltage::new(dec!(1.8))));
         cell.connect(vsource.io().p, vdd);
         cell.connect(vsource.io().n, io.vss);
 
@@ -250,7 +251,7 @@ impl Schematic for OpenPexTb {
         }
         cell.connect(dut.io().din[31], io.vss);
 
-        let vsource = cell.instantiate(ngspice::blocks::Vsource::dc(dec!(1.8)));
+        let vsource = cell.instantiate(ngspice::blocks::Vsource::dc(Voltage::new(dec!(1.8))));
         cell.connect(vsource.io().p, vdd);
         cell.connect(vsource.io().n, io.vss);
 
@@ -267,6 +268,7 @@ mod tests {
     use substrate::{
         context::Context,
         simulation::{waveform::TimeWaveform, SimController},
+        units::Time,
     };
 
     use super::*;
@@ -299,7 +301,7 @@ mod tests {
                 .simulate(
                     opts,
                     Tran {
-                        stop: dec!(2e-9),
+                        stop: Time::new(dec!(2e-9)),
                         errpreset: Some(ErrPreset::Conservative),
                         ..Default::default()
                     },
@@ -345,8 +347,8 @@ mod tests {
                 .simulate(
                     opts,
                     ngspice::tran::Tran {
-                        stop: dec!(2e-9),
-                        step: dec!(2e-11),
+                        stop: Time::new(dec!(2e-9)),
+                        step: Time::new(dec!(2e-11)),
                         ..Default::default()
                     },
                 )
@@ -26,6 +26,7 @@ use substrate::simulation::waveform::{EdgeDir, TimeWaveform};
 use substrate::simulation::Pvt;
 use substrate::types::schematic::{IoNodeBundle, Node};
 use substrate::types::{Signal, TestbenchIo};
+use substrate::units::{Time, Voltage};
 // end-code-snippet imports
 
 #[allow(dead_code)]
@@ -66,17 +67,17 @@ mod schematic_only_tb {
 
             let vdd = cell.signal("vdd", Signal);
             let dout = cell.signal("dout", Signal);
-            let vddsrc = cell.instantiate(Vsource::dc(self.pvt.voltage));
+            let vddsrc = cell.instantiate(Vsource::dc(Voltage::new(self.pvt.voltage)));
             cell.connect(vddsrc.io().p, vdd);
             cell.connect(vddsrc.io().n, io.vss);
 
             let vin = cell.instantiate(Vsource::pulse(Pulse {
                 val0: 0.into(),
                 val1: self.pvt.voltage,
-                delay: Some(dec!(0.1e-9)),
-                width: Some(dec!(1e-9)),
-                fall: Some(dec!(1e-12)),
-                rise: Some(dec!(1e-12)),
+                delay: Some(Time::new(dec!(0.1e-9))),
+                width: Some(Time::new(dec!(1e-9))),
+                fall: Some(Time::new(dec!(1e-12))),
+                rise: Some(Time::new(dec!(1e-12))),
                 period: None,
             }));
             cell.connect(inv.io().din, vin.io().p);
@@ -123,7 +124,7 @@ mod schematic_only_tb {
                     .simulate(
                         opts,
                         Tran {
-                            stop: dec!(2e-9),
+                            stop: Time::new(dec!(2e-9)),
                             errpreset: Some(spectre::ErrPreset::Conservative),
                             ..Default::default()
                         },
@@ -238,17 +239,17 @@ impl Schematic for InverterTb {
 
         let vdd = cell.signal("vdd", Signal);
         let dout = cell.signal("dout", Signal);
-        let vddsrc = cell.instantiate(Vsource::dc(self.pvt.voltage));
+        let vddsrc = cell.instantiate(Vsource::dc(Voltage::new(self.pvt.voltage)));
         cell.connect(vddsrc.io().p, vdd);
         cell.connect(vddsrc.io().n, io.vss);
 
         let vin = cell.instantiate(Vsource::pulse(Pulse {
             val0: 0.into(),
             val1: self.pvt.voltage,
-            delay: Some(dec!(0.1e-9)),
-            width: Some(dec!(1e-9)),
-            fall: Some(dec!(1e-12)),
-            rise: Some(dec!(1e-12)),
+            delay: Some(Time::new(dec!(0.1e-9))),
+            width: Some(Time::new(dec!(1e-9))),
+            fall: Some(Time::new(dec!(1e-12))),
+            rise: Some(Time::new(dec!(1e-12))),
             period: None,
         }));
         cell.connect(invio.din, vin.io().p);
@@ -313,7 +314,7 @@ impl InverterDesign {
                 .simulate(
                     opts,
                     Tran {
-                        stop: dec!(2e-9),
+                        stop: Time::new(dec!(2e-9)),
                         errpreset: Some(spectre::ErrPreset::Conservative),
                         ..Default::default()
                     },
@@ -24,6 +24,7 @@ use substrate::simulation::waveform::{EdgeDir, TimeWaveform};
 use substrate::simulation::Pvt;
 use substrate::types::schematic::{IoNodeBundle, Node};
 use substrate::types::{Signal, TestbenchIo};
+use substrate::units::{Time, Voltage};
 // end-code-snippet imports
 
 #[allow(dead_code)]
@@ -62,17 +63,17 @@ mod schematic_only_tb {
 
             let vdd = cell.signal("vdd", Signal);
             let dout = cell.signal("dout", Signal);
-            let vddsrc = cell.instantiate(Vsource::dc(self.pvt.voltage));
+            let vddsrc = cell.instantiate(Vsource::dc(Voltage::new(self.pvt.voltage)));
             cell.connect(vddsrc.io().p, vdd);
             cell.connect(vddsrc.io().n, io.vss);
 
             let vin = cell.instantiate(Vsource::pulse(Pulse {
                 val0: 0.into(),
                 val1: self.pvt.voltage,
-                delay: Some(dec!(0.1e-9)),
-                width: Some(dec!(1e-9)),
-                fall: Some(dec!(1e-12)),
-                rise: Some(dec!(1e-12)),
+                delay: Some(Time::new(dec!(0.1e-9))),
+                width: Some(Time::new(dec!(1e-9))),
+                fall: Some(Time::new(dec!(1e-12))),
+                rise: Some(Time::new(dec!(1e-12))),
                 period: None,
                 num_pulses: Some(dec!(1)),
             }));
@@ -120,8 +121,8 @@ mod schematic_only_tb {
                     .simulate(
                         opts,
                         ngspice::tran::Tran {
-                            stop: dec!(2e-9),
-                            step: dec!(1e-11),
+                            stop: Time::new(dec!(2e-9)),
+                            step: Time::new(dec!(1e-11)),
                             ..Default::default()
                         },
                     )
@@ -235,17 +236,17 @@ impl Schematic for InverterTb {
 
         let vdd = cell.signal("vdd", Signal);
         let dout = cell.signal("dout", Signal);
-        let vddsrc = cell.instantiate(Vsource::dc(self.pvt.voltage));
+        let vddsrc = cell.instantiate(Vsource::dc(Voltage::new(self.pvt.voltage)));
         cell.connect(vddsrc.io().p, vdd);
         cell.connect(vddsrc.io().n, io.vss);
 
         let vin = cell.instantiate(Vsource::pulse(Pulse {
             val0: 0.into(),
             val1: self.pvt.voltage,
-            delay: Some(dec!(0.1e-9)),
-            width: Some(dec!(1e-9)),
-            fall: Some(dec!(1e-12)),
-            rise: Some(dec!(1e-12)),
+            delay: Some(Time::new(dec!(0.1e-9))),
+            width: Some(Time::new(dec!(1e-9))),
+            fall: Some(Time::new(dec!(1e-12))),
+            rise: Some(Time::new(dec!(1e-12))),
             period: None,
             num_pulses: Some(dec!(1)),
         }));
@@ -310,8 +311,8 @@ impl InverterDesign {
                 .simulate(
                     opts,
                     ngspice::tran::Tran {
-                        stop: dec!(2e-9),
-                        step: dec!(1e-11),
+                        stop: Time::new(dec!(2e-9)),
+                        step: Time::new(dec!(1e-11)),
                         ..Default::default()
                     },
                 )
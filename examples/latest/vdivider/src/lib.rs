@@ -7,6 +7,7 @@ use spectre::Spectre;
 use substrate::block::Block;
 use substrate::schematic::{CellBuilder, Instance, NestedData, Schematic};
 use substrate::types::{Array, InOut, Io, Output, PowerIo, Signal, TestbenchIo};
+use substrate::units::Voltage;
 
 #[derive(Debug, Default, Clone, Io)]
 pub struct VdividerIo {
@@ -157,7 +158,7 @@ impl Schematic for VdividerTb {
         cell.connect(iprobe.io().p, vdd_a);
         cell.connect(iprobe.io().n, vdd);
 
-        let vsource = cell.instantiate(Vsource::dc(dec!(1.8)));
+        let vsource = cell.instantiate(Vsource::dc(Voltage::new(dec!(1.8))));
         cell.connect(vsource.io().p, vdd_a);
         cell.connect(vsource.io().n, io.vss);
 
@@ -170,7 +171,7 @@ mod tests {
     use approx::relative_eq;
     use rust_decimal_macros::dec;
     use spectre::{analysis::tran::Tran, ErrPreset};
-    use substrate::{context::Context, simulation::waveform::TimeWaveform};
+    use substrate::{context::Context, simulation::waveform::TimeWaveform, units::Time};
 
     use super::*;
     use std::path::PathBuf;
@@ -192,7 +193,7 @@ mod tests {
             .simulate(
                 Default::default(),
                 Tran {
-                    stop: dec!(1e-6),
+                    stop: Time::new(dec!(1e-6)),
                     errpreset: Some(ErrPreset::Conservative),
                     ..Default::default()
                 },
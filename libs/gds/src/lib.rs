@@ -104,7 +104,7 @@ extern crate derive_builder;
 // Internal Modules
 use read::{GdsParser, GdsScanner, GdsStructScan};
 pub use ser::{SerdeFile, SerializationFormat};
-use write::GdsWriter;
+pub use write::GdsWriter;
 
 /// An enumeration of GDS record types.
 ///
@@ -837,6 +837,22 @@ impl Default for GdsDateTimes {
     }
 }
 
+impl GdsDateTimes {
+    /// Dates and times fixed at the Unix epoch (1970-01-01 00:00:00).
+    ///
+    /// Useful for producing byte-identical GDS files across repeated exports.
+    pub fn epoch() -> Self {
+        let epoch = NaiveDate::from_ymd_opt(1970, 1, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        Self {
+            modified: epoch,
+            accessed: epoch,
+        }
+    }
+}
+
 /// A GDS struct (cell) definition
 ///
 /// GDSII's primary hierarchical layout-definition object is its "struct",
@@ -30,6 +30,29 @@ impl<'wr> GdsWriter<'wr> {
         self.encode_lib(lib)
     }
 
+    /// Writes the header of [GdsLibrary] `lib`, without any of its structs.
+    ///
+    /// Together with [GdsWriter::write_struct] and [GdsWriter::end_lib],
+    /// allows a library to be written one struct at a time,
+    /// without holding all of its structs in memory.
+    pub fn begin_lib(&mut self, lib: &GdsLibrary) -> GdsResult<()> {
+        self.encode_lib_header(lib)
+    }
+
+    /// Writes [GdsStruct] `strukt` to our destination.
+    ///
+    /// Must be called after [GdsWriter::begin_lib] and before [GdsWriter::end_lib].
+    pub fn write_struct(&mut self, strukt: &GdsStruct) -> GdsResult<()> {
+        self.encode_struct(strukt)
+    }
+
+    /// Writes the library terminator and flushes our destination.
+    pub fn end_lib(&mut self) -> GdsResult<()> {
+        self.encode_record(GdsRecord::EndLib)?;
+        self.dest.flush()?;
+        Ok(())
+    }
+
     /// Helper to write a sequence of [GdsRecord] references.
     fn write_records(&mut self, records: &[GdsRecord]) -> GdsResult<()> {
         for r in records {
@@ -250,6 +273,18 @@ trait Encode {
     /// Encodes a [GdsLibrary].
     fn encode_lib(&mut self, lib: &GdsLibrary) -> GdsResult<()> {
         // Write our header content
+        self.encode_lib_header(lib)?;
+        // Write all of our Structs/Cells
+        for strukt in lib.structs.iter() {
+            self.encode_struct(strukt)?;
+        }
+        // And finally, the library terminator
+        self.encode_record(GdsRecord::EndLib)?;
+        Ok(())
+    }
+
+    /// Encodes the header records of a [GdsLibrary].
+    fn encode_lib_header(&mut self, lib: &GdsLibrary) -> GdsResult<()> {
         self.encode_records(&[
             GdsRecord::Header {
                 version: lib.version,
@@ -259,14 +294,7 @@ trait Encode {
             },
             GdsRecord::LibName(lib.name.clone()),
             GdsRecord::Units(lib.units.0, lib.units.1),
-        ])?;
-        // Write all of our Structs/Cells
-        for strukt in lib.structs.iter() {
-            self.encode_struct(strukt)?;
-        }
-        // And finally, the library terminator
-        self.encode_record(GdsRecord::EndLib)?;
-        Ok(())
+        ])
     }
 
     /// Encodes a [GdsStruct].
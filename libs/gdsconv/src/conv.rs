@@ -27,6 +27,23 @@ pub trait FromGds: Sized {
     fn from_gds_label(layer: GdsLayer) -> Option<Self>;
 }
 
+/// A layer type that can be converted to a [`GdsLayer`].
+///
+/// Each layer may map to several GDS layers, one for each purpose: drawn shapes,
+/// pin shapes, and text labels. This is the inverse of [`FromGds`].
+pub trait ToGds {
+    /// Converts this layer to the GDS layer used for drawn shapes.
+    fn to_gds(&self) -> GdsLayer;
+    /// Converts this layer to the GDS layer used for pin shapes.
+    ///
+    /// Should return [`None`] if this layer has no pin purpose.
+    fn to_gds_pin(&self) -> Option<GdsLayer>;
+    /// Converts this layer to the GDS layer used for text labels.
+    ///
+    /// Should return [`None`] if this layer has no label purpose.
+    fn to_gds_label(&self) -> Option<GdsLayer>;
+}
+
 #[derive(Error, Debug)]
 pub enum FromGdsError {
     #[error("no layer mapping for layer {layer} in cell `{cell}`")]
@@ -43,6 +60,62 @@ pub enum FromGdsError {
     BuildError(#[from] layir::BuildError),
 }
 
+/// Convert a layout library to a GDS layout library.
+///
+/// Shapes in cells are mapped to their drawing layers, while texts in cells are mapped
+/// to their label layers. Port shapes are mapped to their pin layers, and each port
+/// text is placed on both the pin layer and the label layer so that extraction tools
+/// recognize the pin. Layers without a pin or label purpose fall back to their
+/// drawing layer.
+pub fn to_gds<L: ToGds>(
+    lib: &layir::Library<L>,
+) -> Result<layir::Library<GdsLayer>, layir::BuildError> {
+    let mut olib = LibraryBuilder::<GdsLayer>::new();
+    for cell in lib.topological_order() {
+        let cell = lib.cell(cell);
+        let mut ocell = Cell::new(cell.name());
+        for (key, value) in cell.properties() {
+            ocell.set_property(key.clone(), value.clone());
+        }
+        for elt in cell.elements() {
+            let layer = match elt {
+                Element::Shape(s) => s.layer().to_gds(),
+                Element::Text(t) => t.layer().to_gds_label().unwrap_or(t.layer().to_gds()),
+            };
+            ocell.add_element(elt.with_layer(layer));
+        }
+        for (_, inst) in cell.instances() {
+            let name = lib.cell(inst.child()).name();
+            let child_id = olib.cell_id_named(name);
+            ocell.add_instance(Instance::with_transformation(
+                child_id,
+                inst.name(),
+                inst.transformation(),
+            ));
+        }
+        for (name, port) in cell.ports() {
+            let mut oport = Port::new(port.direction());
+            for elt in port.elements() {
+                let layer = elt.layer();
+                let pin = layer.to_gds_pin().unwrap_or(layer.to_gds());
+                match elt {
+                    Element::Shape(s) => oport.add_element(s.with_layer(pin)),
+                    Element::Text(t) => {
+                        let label = layer.to_gds_label().unwrap_or(pin);
+                        oport.add_element(t.with_layer(pin));
+                        if label != pin {
+                            oport.add_element(t.with_layer(label));
+                        }
+                    }
+                }
+            }
+            ocell.add_port(name, oport);
+        }
+        olib.add_cell(ocell);
+    }
+    olib.build()
+}
+
 /// Convert a GDS layout library to a sky130 layout library.
 pub fn from_gds<L: FromGds + Hash + Eq + Clone>(
     lib: &layir::Library<GdsLayer>,
@@ -52,6 +125,9 @@ pub fn from_gds<L: FromGds + Hash + Eq + Clone>(
     for cell in cells {
         let cell = lib.cell(cell);
         let mut ocell = Cell::new(cell.name());
+        for (key, value) in cell.properties() {
+            ocell.set_property(key.clone(), value.clone());
+        }
         for elt in cell.elements() {
             let layer = *elt.layer();
             let l = L::from_gds(layer).ok_or_else(|| FromGdsError::NoLayerMapping {
@@ -1,7 +1,10 @@
 use arcstr::ArcStr;
+use std::path::Path;
+use std::sync::Arc;
+
 use gds::{
-    GdsBoundary, GdsElement, GdsLibrary, GdsPoint, GdsStrans, GdsStruct, GdsStructRef, GdsTextElem,
-    GdsUnits,
+    GdsBoundary, GdsDateTimes, GdsElement, GdsError, GdsLibrary, GdsPoint, GdsProperty, GdsResult,
+    GdsStrans, GdsStruct, GdsStructRef, GdsTextElem, GdsUnits, GdsWriter,
 };
 use geometry::{
     corner::Corner,
@@ -9,43 +12,152 @@ use geometry::{
     prelude::{Orientation, Polygon},
     rect::Rect,
 };
-use layir::{Cell, Element, Instance, Library, Shape, Text};
+use layir::{Cell, CellId, Element, Instance, Library, Shape, Text};
 
 use crate::GdsLayer;
 
+/// The layer on which Substrate writes cell properties by default.
+///
+/// See [`GdsExportOpts::property_layer`].
+pub const DEFAULT_PROPERTY_LAYER: GdsLayer = GdsLayer(255, 0);
+
 pub struct GdsExportOpts {
     /// Name of the GDS library.
     pub name: ArcStr,
     pub units: Option<GdsUnits>,
+    /// Modification and access dates recorded in the library and each struct.
+    ///
+    /// Defaults to the current time. Set this to a fixed value to produce
+    /// byte-identical GDS files across repeated exports.
+    pub dates: Option<GdsDateTimes>,
+    /// Layer on which to write cell properties.
+    ///
+    /// GDS structs cannot carry properties, so the properties of each cell are attached
+    /// to a text element at the cell origin on this layer. Cell properties are not
+    /// exported if no layer is provided.
+    pub property_layer: Option<GdsLayer>,
+}
+
+pub fn export_gds(lib: Library<GdsLayer>, opts: GdsExportOpts) -> GdsResult<GdsLibrary> {
+    let exporter = GdsExporter { opts };
+    exporter.export(&lib)
+}
+
+/// Exports a LayIR library to GDS, writing each struct to `writer` as soon as it is converted.
+///
+/// Produces the same GDS library as [`export_gds`], but never holds more than one
+/// converted struct in memory. Structs are written in topological order, so every struct
+/// is defined before it is referenced, and the order is deterministic for a given LayIR library.
+///
+/// To avoid building the whole LayIR library first, use a [`GdsStreamWriter`] instead.
+pub fn write_gds(
+    lib: &Library<GdsLayer>,
+    opts: GdsExportOpts,
+    writer: &mut GdsWriter<'_>,
+) -> GdsResult<()> {
+    let exporter = GdsExporter { opts };
+    writer.begin_lib(&exporter.header())?;
+    for id in lib.topological_order() {
+        let strukt = exporter.export_cell(lib, lib.cell(id))?;
+        writer.write_struct(&strukt)?;
+    }
+    writer.end_lib()
+}
+
+/// Exports a LayIR library to a GDS file at `path`, writing structs to disk as they are converted.
+///
+/// Creates the parent directories of `path` if they do not exist.
+/// See [`write_gds`] for details.
+pub fn save_gds(
+    lib: &Library<GdsLayer>,
+    opts: GdsExportOpts,
+    path: impl AsRef<Path>,
+) -> GdsResult<()> {
+    let mut writer = create_gds_file(path)?;
+    write_gds(lib, opts, &mut writer)
+}
+
+/// Opens a GDS file for writing, creating its parent directories if they do not exist.
+fn create_gds_file(path: impl AsRef<Path>) -> GdsResult<GdsWriter<'static>> {
+    if let Some(prefix) = path.as_ref().parent() {
+        std::fs::create_dir_all(prefix)?;
+    }
+    GdsWriter::open(path)
+}
+
+/// Writes a GDS library one cell at a time.
+///
+/// Unlike [`write_gds`], cells do not need to belong to the same LayIR library,
+/// so each cell can be converted, written, and dropped before the next one is built.
+pub struct GdsStreamWriter<'wr> {
+    writer: GdsWriter<'wr>,
+    exporter: GdsExporter,
+}
+
+impl<'wr> GdsStreamWriter<'wr> {
+    /// Writes the header of a GDS library to `writer`.
+    pub fn begin(mut writer: GdsWriter<'wr>, opts: GdsExportOpts) -> GdsResult<Self> {
+        let exporter = GdsExporter { opts };
+        writer.begin_lib(&exporter.header())?;
+        Ok(Self { writer, exporter })
+    }
+
+    /// Writes cell `id` of `lib` as a GDS struct.
+    ///
+    /// Only the named cell is written; the other cells of `lib` are only used to name
+    /// the structs it references. Cells must be written after the cells they instantiate.
+    pub fn write_cell(&mut self, lib: &Library<GdsLayer>, id: CellId) -> GdsResult<()> {
+        let strukt = self.exporter.export_cell(lib, lib.cell(id))?;
+        self.writer.write_struct(&strukt)
+    }
+
+    /// Writes the end of the GDS library.
+    pub fn finish(mut self) -> GdsResult<()> {
+        self.writer.end_lib()
+    }
 }
 
-pub fn export_gds(lib: Library<GdsLayer>, opts: GdsExportOpts) -> GdsLibrary {
-    let exporter = GdsExporter { opts, lib: &lib };
-    exporter.export()
+impl GdsStreamWriter<'static> {
+    /// Writes the header of a GDS library to a file at `path`.
+    ///
+    /// Creates the parent directories of `path` if they do not exist.
+    pub fn create(path: impl AsRef<Path>, opts: GdsExportOpts) -> GdsResult<Self> {
+        Self::begin(create_gds_file(path)?, opts)
+    }
 }
 
-struct GdsExporter<'a> {
+struct GdsExporter {
     opts: GdsExportOpts,
-    lib: &'a Library<GdsLayer>,
 }
 
-impl GdsExporter<'_> {
-    fn export(mut self) -> GdsLibrary {
-        let mut gds = if let Some(units) = self.opts.units.clone() {
+impl GdsExporter {
+    /// Returns an empty GDS library with the configured name and units.
+    fn header(&self) -> GdsLibrary {
+        let mut lib = if let Some(units) = self.opts.units.clone() {
             GdsLibrary::with_units(self.opts.name.clone(), units)
         } else {
             GdsLibrary::new(self.opts.name.clone())
         };
-        for id in self.lib.topological_order() {
-            let cell = self.lib.cell(id);
-            let strukt = self.export_cell(cell);
+        if let Some(dates) = self.opts.dates.clone() {
+            lib.dates = dates;
+        }
+        lib
+    }
+
+    fn export(self, lib: &Library<GdsLayer>) -> GdsResult<GdsLibrary> {
+        let mut gds = self.header();
+        for id in lib.topological_order() {
+            let strukt = self.export_cell(lib, lib.cell(id))?;
             gds.structs.push(strukt);
         }
-        gds
+        Ok(gds)
     }
 
-    fn export_cell(&mut self, cell: &Cell<GdsLayer>) -> GdsStruct {
+    fn export_cell(&self, lib: &Library<GdsLayer>, cell: &Cell<GdsLayer>) -> GdsResult<GdsStruct> {
         let mut gcell = GdsStruct::new(cell.name().clone());
+        if let Some(dates) = self.opts.dates.clone() {
+            gcell.dates = dates;
+        }
         for (_, port) in cell.ports() {
             for elt in port.elements() {
                 gcell.elems.push(export_element(elt));
@@ -55,9 +167,14 @@ impl GdsExporter<'_> {
             gcell.elems.push(export_element(elt));
         }
         for (_, inst) in cell.instances() {
-            gcell.elems.push(export_instance(self.lib, inst));
+            gcell.elems.push(export_instance(lib, inst));
+        }
+        if let Some(layer) = self.opts.property_layer {
+            if let Some(elem) = export_properties(cell, layer)? {
+                gcell.elems.push(elem);
+            }
         }
-        gcell
+        Ok(gcell)
     }
 }
 
@@ -137,6 +254,39 @@ fn export_text(text: &Text<GdsLayer>) -> GdsElement {
     .into()
 }
 
+/// Exports the properties of `cell` as a text element on `layer`.
+///
+/// Each property is written as a `key=value` string, numbered in insertion order
+/// starting from 1. Returns [`None`] if the cell has no properties.
+///
+/// Returns an error if the cell has more properties than can be numbered in GDS.
+fn export_properties(cell: &Cell<GdsLayer>, layer: GdsLayer) -> GdsResult<Option<GdsElement>> {
+    let properties = cell
+        .properties()
+        .enumerate()
+        .map(|(i, (key, value))| {
+            Ok(GdsProperty {
+                attr: i16::try_from(i + 1).map_err(|e| GdsError::Boxed(Arc::new(e)))?,
+                value: arcstr::format!("{key}={value}"),
+            })
+        })
+        .collect::<GdsResult<Vec<_>>>()?;
+    if properties.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(
+        GdsTextElem {
+            string: cell.name().clone(),
+            layer: layer.0 as i16,
+            texttype: layer.1 as i16,
+            xy: GdsPoint::new(0, 0),
+            properties,
+            ..Default::default()
+        }
+        .into(),
+    ))
+}
+
 fn export_orientation(orientation: Orientation) -> GdsStrans {
     GdsStrans {
         reflected: orientation.reflect_vert(),
@@ -1,11 +1,12 @@
 use std::path::PathBuf;
 
-use gds::{GdsLibrary, GdsUnits};
+use gds::{GdsDateTimes, GdsElement, GdsLibrary, GdsProperty, GdsUnits, GdsWriter};
 use geometry::{prelude::Transformation, rect::Rect, shape::Shape as GShape};
-use layir::{Cell, Element, Instance, Library, LibraryBuilder, Shape, Text};
+use layir::{Cell, Direction, Element, Instance, Library, LibraryBuilder, Port, Shape, Text};
 
 use crate::{
-    export::{export_gds, GdsExportOpts},
+    conv::{to_gds, ToGds},
+    export::{export_gds, write_gds, GdsExportOpts},
     import::{import_gds, GdsImportOpts},
     GdsLayer,
 };
@@ -74,8 +75,10 @@ fn test_export_layir_to_gds() {
     let opts = GdsExportOpts {
         name: "top".into(),
         units: Some(GdsUnits::new(1., 1e-6)),
+        dates: None,
+        property_layer: None,
     };
-    let gds = export_gds(lib, opts);
+    let gds = export_gds(lib, opts).unwrap();
 
     gds.save(get_path("test_export_layir_to_gds", "layout.gds"))
         .expect("failed to write gds");
@@ -89,6 +92,122 @@ fn test_export_layir_to_gds() {
     assert_eq!(gds.structs[3].elems.len(), 3);
 }
 
+#[test]
+fn test_write_gds_streaming() {
+    let lib = gdslib();
+    let opts = || GdsExportOpts {
+        name: "top".into(),
+        units: Some(GdsUnits::new(1., 1e-6)),
+        dates: None,
+        property_layer: None,
+    };
+
+    let mut bytes = Vec::new();
+    write_gds(&lib, opts(), &mut GdsWriter::new(&mut bytes)).expect("failed to write gds");
+    let streamed = GdsLibrary::from_bytes(bytes).expect("failed to parse GDS");
+    let mut bytes = Vec::new();
+    export_gds(lib, opts())
+        .unwrap()
+        .write(&mut bytes)
+        .expect("failed to write gds");
+    let gds = GdsLibrary::from_bytes(bytes).expect("failed to parse GDS");
+
+    assert_eq!(streamed.name, gds.name);
+    assert_eq!(streamed.units, gds.units);
+    assert_eq!(streamed.structs.len(), 4);
+    assert_eq!(streamed.structs.len(), gds.structs.len());
+    for (a, b) in streamed.structs.iter().zip(gds.structs.iter()) {
+        assert_eq!(a.name, b.name);
+        assert_eq!(a.elems, b.elems);
+    }
+}
+
+#[test]
+fn test_write_gds_with_fixed_dates_is_reproducible() {
+    let opts = || GdsExportOpts {
+        name: "top".into(),
+        units: Some(GdsUnits::new(1., 1e-6)),
+        dates: Some(GdsDateTimes::epoch()),
+        property_layer: None,
+    };
+    let write = || {
+        let mut bytes = Vec::new();
+        write_gds(&gdslib(), opts(), &mut GdsWriter::new(&mut bytes)).expect("failed to write gds");
+        bytes
+    };
+
+    let bytes = write();
+    assert_eq!(bytes, write());
+    let gds = GdsLibrary::from_bytes(bytes).expect("failed to parse GDS");
+    assert_eq!(gds.dates, GdsDateTimes::epoch());
+    assert!(gds
+        .structs
+        .iter()
+        .all(|strukt| strukt.dates == GdsDateTimes::epoch()));
+}
+
+#[test]
+fn test_export_cell_properties() {
+    let mut lib = LibraryBuilder::new();
+    let mut cell = Cell::new("top");
+    cell.add_element(Shape::new(
+        GdsLayer(1, 0),
+        GShape::Rect(Rect::from_sides(0, 0, 100, 200)),
+    ));
+    cell.set_property("substrate_generator", "top");
+    cell.set_property("substrate_version", "1.0.0");
+    lib.add_cell(cell);
+    let lib = lib.build().unwrap();
+    let opts = |property_layer| GdsExportOpts {
+        name: "top".into(),
+        units: None,
+        dates: None,
+        property_layer,
+    };
+
+    let gds = export_gds(lib.clone(), opts(None)).unwrap();
+    assert_eq!(gds.structs[0].elems.len(), 1);
+
+    let gds = export_gds(lib, opts(Some(GdsLayer(63, 0)))).unwrap();
+    assert_eq!(gds.structs[0].elems.len(), 2);
+    let GdsElement::GdsTextElem(text) = &gds.structs[0].elems[1] else {
+        panic!("expected cell properties to be exported as a text element");
+    };
+    assert_eq!((text.layer, text.texttype), (63, 0));
+    assert_eq!(
+        text.properties,
+        [
+            GdsProperty {
+                attr: 1,
+                value: "substrate_generator=top".into(),
+            },
+            GdsProperty {
+                attr: 2,
+                value: "substrate_version=1.0.0".into(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_export_too_many_cell_properties() {
+    let mut lib = LibraryBuilder::new();
+    let mut cell = Cell::new("top");
+    for i in 0..=i16::MAX {
+        cell.set_property(arcstr::format!("key{i}"), "value");
+    }
+    lib.add_cell(cell);
+    let opts = GdsExportOpts {
+        name: "top".into(),
+        units: None,
+        dates: None,
+        property_layer: Some(GdsLayer(63, 0)),
+    };
+
+    // GDS property attributes are 16-bit, so at most `i16::MAX` properties can be numbered.
+    assert!(export_gds(lib.build().unwrap(), opts).is_err());
+}
+
 #[test]
 fn test_gds_import() {
     let path = test_data("test_sky130_simple.gds");
@@ -174,8 +293,11 @@ fn test_gds_reexport() {
         GdsExportOpts {
             name: "TOP".into(),
             units: None,
+            dates: None,
+            property_layer: None,
         },
-    );
+    )
+    .unwrap();
     rawlib2.save(&gds_path).expect("failed to save GDS");
 
     let bytes = std::fs::read(&gds_path).expect("failed to read GDS");
@@ -230,3 +352,89 @@ fn test_gds_reexport() {
         1
     );
 }
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+enum Layer {
+    Met1,
+    Boundary,
+}
+
+impl ToGds for Layer {
+    fn to_gds(&self) -> GdsLayer {
+        match self {
+            Self::Met1 => GdsLayer(68, 20),
+            Self::Boundary => GdsLayer(235, 4),
+        }
+    }
+
+    fn to_gds_pin(&self) -> Option<GdsLayer> {
+        match self {
+            Self::Met1 => Some(GdsLayer(68, 16)),
+            Self::Boundary => None,
+        }
+    }
+
+    fn to_gds_label(&self) -> Option<GdsLayer> {
+        match self {
+            Self::Met1 => Some(GdsLayer(68, 5)),
+            Self::Boundary => None,
+        }
+    }
+}
+
+#[test]
+fn test_to_gds_pin_purposes() {
+    let mut lib = LibraryBuilder::new();
+    let mut cell = Cell::new("top");
+    cell.add_element(Shape::new(Layer::Met1, Rect::from_sides(0, 0, 100, 100)));
+    cell.add_element(Text::new(Layer::Met1, "note"));
+    let mut port = Port::new(Direction::InOut);
+    port.add_element(Shape::new(Layer::Met1, Rect::from_sides(0, 0, 20, 20)));
+    port.add_element(Text::with_transformation(
+        Layer::Met1,
+        "a",
+        Transformation::translate(10, 10),
+    ));
+    cell.add_port("a", port);
+    let mut port = Port::new(Direction::InOut);
+    port.add_element(Shape::new(Layer::Boundary, Rect::from_sides(0, 0, 20, 20)));
+    port.add_element(Text::new(Layer::Boundary, "b"));
+    cell.add_port("b", port);
+    lib.add_cell(cell);
+    let lib = to_gds(&lib.build().unwrap()).unwrap();
+
+    let cell = lib.cell_named("top");
+    let layers = |elts: Vec<&Element<GdsLayer>>| {
+        elts.into_iter()
+            .map(|elt| (matches!(elt, Element::Text(_)), *elt.layer()))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        layers(cell.elements().collect()),
+        [(false, GdsLayer(68, 20)), (true, GdsLayer(68, 5))]
+    );
+    assert_eq!(
+        layers(cell.port("a").elements().collect()),
+        [
+            (false, GdsLayer(68, 16)),
+            (true, GdsLayer(68, 16)),
+            (true, GdsLayer(68, 5))
+        ]
+    );
+    assert_eq!(
+        layers(cell.port("b").elements().collect()),
+        [(false, GdsLayer(235, 4)), (true, GdsLayer(235, 4))]
+    );
+
+    let gds = export_gds(
+        lib,
+        GdsExportOpts {
+            name: "top".into(),
+            units: None,
+            dates: None,
+            property_layer: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(gds.structs[0].elems.len(), 7);
+}
@@ -12,6 +12,7 @@ pub use crate::place::{PlaceBbox, PlaceMode, PlaceRect};
 pub use crate::point::Point;
 pub use crate::polygon::Polygon;
 pub use crate::rect::Rect;
+pub use crate::region::Region;
 pub use crate::side::{Side, Sides};
 pub use crate::sign::Sign;
 pub use crate::span::Span;
@@ -0,0 +1,429 @@
+//! Manhattan regions and boolean operations.
+//!
+//! A [`Region`] is an arbitrary area bounded by horizontal and vertical edges,
+//! such as the union of several rectangles or a Manhattan polygon. Regions support
+//! boolean operations (union, intersection, difference, and symmetric difference),
+//! sizing, and decomposition into disjoint rectangles.
+
+use serde::{Deserialize, Serialize};
+
+use crate::bbox::Bbox;
+use crate::point::Point;
+use crate::polygon::Polygon;
+use crate::rect::Rect;
+use crate::transform::{TransformRef, Transformation, TranslateRef};
+use crate::union::Union;
+
+/// A Manhattan region.
+///
+/// Regions are stored as a set of disjoint rectangles in a canonical form:
+/// the region is divided into maximal horizontal strips, and vertically adjacent strips
+/// with the same horizontal extent are merged. Two regions covering the same area
+/// therefore compare equal regardless of how they were constructed.
+///
+/// # Example
+///
+/// ```
+/// # use geometry::prelude::*;
+/// let a = Region::from(Rect::from_sides(0, 0, 100, 100));
+/// let b = Region::from(Rect::from_sides(50, 50, 150, 150));
+///
+/// assert_eq!(a.union(&b).area(), 17_500);
+/// assert_eq!(a.intersection(&b), Region::from(Rect::from_sides(50, 50, 100, 100)));
+/// assert_eq!(a.difference(&b).area(), 7_500);
+/// ```
+#[derive(Debug, Default, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub struct Region {
+    rects: Vec<Rect>,
+}
+
+impl Region {
+    /// Creates an empty region.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a region covering the union of the given rectangles.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use geometry::prelude::*;
+    /// let region = Region::from_rects([
+    ///     Rect::from_sides(0, 0, 100, 50),
+    ///     Rect::from_sides(0, 50, 100, 100),
+    /// ]);
+    /// assert_eq!(region.rects(), &[Rect::from_sides(0, 0, 100, 100)]);
+    /// ```
+    pub fn from_rects(rects: impl IntoIterator<Item = Rect>) -> Self {
+        let rects = rects.into_iter().collect::<Vec<_>>();
+        Self {
+            rects: boolean(&rects, &[], |a, _| a),
+        }
+    }
+
+    /// Creates a region covering the interior of a Manhattan polygon.
+    ///
+    /// Returns [`None`] if the polygon has an edge that is neither horizontal nor vertical.
+    /// Self-intersecting polygons are interpreted using the even-odd rule.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use geometry::prelude::*;
+    /// let polygon = Polygon::from_verts(vec![
+    ///     Point::new(0, 0),
+    ///     Point::new(20, 0),
+    ///     Point::new(20, 10),
+    ///     Point::new(10, 10),
+    ///     Point::new(10, 20),
+    ///     Point::new(0, 20),
+    /// ]);
+    /// let region = Region::from_polygon(&polygon).unwrap();
+    /// assert_eq!(region.area(), 300);
+    ///
+    /// let triangle = Polygon::from_verts(vec![
+    ///     Point::new(0, 0),
+    ///     Point::new(10, 10),
+    ///     Point::new(20, 0),
+    /// ]);
+    /// assert_eq!(Region::from_polygon(&triangle), None);
+    /// ```
+    pub fn from_polygon(polygon: &Polygon) -> Option<Self> {
+        let points = polygon.points();
+        let mut edges = Vec::new();
+        for (i, p0) in points.iter().enumerate() {
+            let p1 = points[(i + 1) % points.len()];
+            if p0.x == p1.x {
+                if p0.y != p1.y {
+                    edges.push((p0.x, p0.y.min(p1.y), p0.y.max(p1.y)));
+                }
+            } else if p0.y != p1.y {
+                return None;
+            }
+        }
+
+        let ys = sorted_coords(edges.iter().flat_map(|&(_, y0, y1)| [y0, y1]));
+        let mut rects = Vec::new();
+        for y in ys.windows(2) {
+            let mut xs = edges
+                .iter()
+                .filter(|&&(_, y0, y1)| y0 <= y[0] && y[1] <= y1)
+                .map(|&(x, _, _)| x)
+                .collect::<Vec<_>>();
+            xs.sort_unstable();
+            rects.extend(
+                xs.chunks_exact(2)
+                    .filter(|x| x[0] < x[1])
+                    .map(|x| Rect::from_sides(x[0], y[0], x[1], y[1])),
+            );
+        }
+        Some(Self::from_rects(rects))
+    }
+
+    /// Returns a set of disjoint rectangles covering this region.
+    pub fn rects(&self) -> &[Rect] {
+        &self.rects
+    }
+
+    /// Consumes this region, returning a set of disjoint rectangles covering it.
+    pub fn into_rects(self) -> Vec<Rect> {
+        self.rects
+    }
+
+    /// Returns `true` if this region has zero area.
+    pub fn is_empty(&self) -> bool {
+        self.rects.is_empty()
+    }
+
+    /// Returns the area of this region.
+    pub fn area(&self) -> i64 {
+        self.rects.iter().map(Rect::area).sum()
+    }
+
+    /// Returns `true` if `p` lies in the interior or on the boundary of this region.
+    pub fn contains_point(&self, p: Point) -> bool {
+        self.rects
+            .iter()
+            .any(|r| r.left() <= p.x && p.x <= r.right() && r.bot() <= p.y && p.y <= r.top())
+    }
+
+    /// Returns the union of this region with `other`.
+    pub fn union(&self, other: &Self) -> Self {
+        Self {
+            rects: boolean(&self.rects, &other.rects, |a, b| a || b),
+        }
+    }
+
+    /// Returns the intersection of this region with `other`.
+    pub fn intersection(&self, other: &Self) -> Self {
+        Self {
+            rects: boolean(&self.rects, &other.rects, |a, b| a && b),
+        }
+    }
+
+    /// Returns the part of this region that is not covered by `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        Self {
+            rects: boolean(&self.rects, &other.rects, |a, b| a && !b),
+        }
+    }
+
+    /// Returns the area covered by exactly one of this region and `other`.
+    pub fn xor(&self, other: &Self) -> Self {
+        Self {
+            rects: boolean(&self.rects, &other.rects, |a, b| a != b),
+        }
+    }
+
+    /// Grows this region by `amount` in every direction.
+    ///
+    /// The result contains every point within a square of half-width `amount`
+    /// centered at a point of this region, so convex corners remain square.
+    /// A negative `amount` shrinks the region (see [`Region::shrink`]).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use geometry::prelude::*;
+    /// let region = Region::from_rects([
+    ///     Rect::from_sides(0, 0, 10, 10),
+    ///     Rect::from_sides(14, 0, 24, 10),
+    /// ]);
+    /// assert_eq!(region.grow(2).rects(), &[Rect::from_sides(-2, -2, 26, 12)]);
+    /// assert_eq!(region.grow(2).grow(-2), Region::from(Rect::from_sides(0, 0, 24, 10)));
+    /// ```
+    pub fn grow(&self, amount: i64) -> Self {
+        match amount {
+            0 => self.clone(),
+            a if a < 0 => self.shrink(-a),
+            a => Self::from_rects(self.rects.iter().map(|r| r.expand_all(a))),
+        }
+    }
+
+    /// Shrinks this region by `amount` in every direction.
+    ///
+    /// The result contains every point whose surrounding square of half-width `amount`
+    /// lies entirely within this region. Parts of the region narrower than `2 * amount`
+    /// are removed. A negative `amount` grows the region (see [`Region::grow`]).
+    pub fn shrink(&self, amount: i64) -> Self {
+        if amount <= 0 {
+            return self.grow(-amount);
+        }
+        let Some(bbox) = self.bbox() else {
+            return Self::new();
+        };
+        let frame = Self::from(bbox.expand_all(amount));
+        self.difference(&frame.difference(self).grow(amount))
+    }
+}
+
+impl From<Rect> for Region {
+    fn from(value: Rect) -> Self {
+        Self::from_rects([value])
+    }
+}
+
+impl FromIterator<Rect> for Region {
+    fn from_iter<T: IntoIterator<Item = Rect>>(iter: T) -> Self {
+        Self::from_rects(iter)
+    }
+}
+
+impl Union<Region> for Region {
+    type Output = Region;
+
+    fn union(&self, other: &Region) -> Self::Output {
+        Region::union(self, other)
+    }
+}
+
+impl Bbox for Region {
+    fn bbox(&self) -> Option<Rect> {
+        Rect::union_all_option(self.rects.iter().copied())
+    }
+}
+
+impl TranslateRef for Region {
+    fn translate_ref(&self, p: Point) -> Self {
+        Self {
+            rects: self.rects.translate_ref(p),
+        }
+    }
+}
+
+impl TransformRef for Region {
+    fn transform_ref(&self, trans: Transformation) -> Self {
+        Self::from_rects(self.rects.iter().map(|r| r.transform_ref(trans)))
+    }
+}
+
+/// Returns the sorted, deduplicated coordinates in `coords`.
+fn sorted_coords(coords: impl IntoIterator<Item = i64>) -> Vec<i64> {
+    let mut coords = coords.into_iter().collect::<Vec<_>>();
+    coords.sort_unstable();
+    coords.dedup();
+    coords
+}
+
+/// Returns the disjoint, sorted horizontal intervals covered by the rectangles in `rects`
+/// that span the band between `y0` and `y1`.
+fn band_intervals(rects: &[Rect], y0: i64, y1: i64) -> Vec<(i64, i64)> {
+    let mut intervals = rects
+        .iter()
+        .filter(|r| r.bot() <= y0 && y1 <= r.top() && r.left() < r.right())
+        .map(|r| (r.left(), r.right()))
+        .collect::<Vec<_>>();
+    intervals.sort_unstable();
+    let mut merged: Vec<(i64, i64)> = Vec::with_capacity(intervals.len());
+    for (x0, x1) in intervals {
+        match merged.last_mut() {
+            Some(last) if x0 <= last.1 => last.1 = last.1.max(x1),
+            _ => merged.push((x0, x1)),
+        }
+    }
+    merged
+}
+
+/// Returns `true` if `x` lies in one of the sorted, disjoint `intervals`.
+fn covers(intervals: &[(i64, i64)], x: i64) -> bool {
+    intervals.iter().any(|&(x0, x1)| x0 <= x && x < x1)
+}
+
+/// Combines the areas covered by `a` and `b` using `op`, returning the result in canonical form.
+///
+/// A point is in the result if `op` returns `true` given whether the point is covered
+/// by `a` and whether it is covered by `b`.
+fn boolean(a: &[Rect], b: &[Rect], op: impl Fn(bool, bool) -> bool) -> Vec<Rect> {
+    let ys = sorted_coords(a.iter().chain(b).flat_map(|r| [r.bot(), r.top()]));
+
+    let mut out = Vec::new();
+    // Intervals of the previous band, each with the bottom of the strip it extends.
+    // Bands are contiguous, so a strip continues as long as its interval is unchanged.
+    let mut open: Vec<(i64, i64, i64)> = Vec::new();
+    let mut prev_top = None;
+    for y in ys.windows(2) {
+        let (y0, y1) = (y[0], y[1]);
+        let ia = band_intervals(a, y0, y1);
+        let ib = band_intervals(b, y0, y1);
+        let xs = sorted_coords(ia.iter().chain(ib.iter()).flat_map(|&(x0, x1)| [x0, x1]));
+
+        let mut intervals: Vec<(i64, i64)> = Vec::new();
+        for x in xs.windows(2) {
+            if op(covers(&ia, x[0]), covers(&ib, x[0])) {
+                match intervals.last_mut() {
+                    Some(last) if last.1 == x[0] => last.1 = x[1],
+                    _ => intervals.push((x[0], x[1])),
+                }
+            }
+        }
+
+        let mut next = Vec::with_capacity(intervals.len());
+        for (x0, x1) in intervals {
+            let bot = open
+                .iter()
+                .find(|&&(ox0, ox1, _)| ox0 == x0 && ox1 == x1)
+                .map(|&(_, _, bot)| bot)
+                .unwrap_or(y0);
+            next.push((x0, x1, bot));
+        }
+        for &(x0, x1, bot) in open.iter() {
+            if !next.contains(&(x0, x1, bot)) {
+                out.push(Rect::from_sides(x0, bot, x1, prev_top.unwrap()));
+            }
+        }
+        open = next;
+        prev_top = Some(y1);
+    }
+    if let Some(top) = prev_top {
+        out.extend(
+            open.into_iter()
+                .map(|(x0, x1, bot)| Rect::from_sides(x0, bot, x1, top)),
+        );
+    }
+    out.sort_unstable_by_key(|r| (r.bot(), r.left()));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::transform::TranslateRef;
+
+    #[test]
+    fn region_booleans() {
+        let a = Region::from(Rect::from_sides(0, 0, 30, 30));
+        let b = Region::from(Rect::from_sides(10, 10, 20, 20));
+
+        let ring = a.difference(&b);
+        assert_eq!(ring.area(), 800);
+        assert_eq!(ring.rects().len(), 4);
+        assert!(!ring.contains_point(Point::new(15, 15)));
+        assert!(ring.contains_point(Point::new(5, 15)));
+        assert_eq!(ring.union(&b), a);
+        assert_eq!(ring.intersection(&b).area(), 0);
+        assert!(ring.intersection(&b).is_empty());
+        assert_eq!(a.xor(&b), ring);
+        assert_eq!(b.difference(&a), Region::new());
+
+        let c = Region::from(Rect::from_sides(20, -10, 40, 10));
+        assert_eq!(a.union(&c).area(), 900 + 400 - 100);
+        assert_eq!(a.xor(&c).area(), 900 + 400 - 200);
+        assert_eq!(a.union(&c).bbox(), Some(Rect::from_sides(0, -10, 40, 30)));
+    }
+
+    #[test]
+    fn region_canonical_form() {
+        let a = Region::from_rects([
+            Rect::from_sides(0, 0, 10, 20),
+            Rect::from_sides(10, 0, 20, 10),
+            Rect::from_sides(10, 10, 20, 20),
+        ]);
+        assert_eq!(a.rects(), &[Rect::from_sides(0, 0, 20, 20)]);
+
+        let l = Region::from_rects([
+            Rect::from_sides(0, 0, 10, 20),
+            Rect::from_sides(0, 0, 20, 10),
+        ]);
+        assert_eq!(
+            l.rects(),
+            &[
+                Rect::from_sides(0, 0, 20, 10),
+                Rect::from_sides(0, 10, 10, 20)
+            ]
+        );
+        let polygon = Polygon::from_verts(vec![
+            Point::new(0, 0),
+            Point::new(20, 0),
+            Point::new(20, 10),
+            Point::new(10, 10),
+            Point::new(10, 20),
+            Point::new(0, 20),
+        ]);
+        assert_eq!(Region::from_polygon(&polygon), Some(l.clone()));
+        assert_eq!(
+            l.translate_ref(Point::new(5, 5)),
+            Region::from_polygon(&polygon.translate_ref(Point::new(5, 5))).unwrap()
+        );
+    }
+
+    #[test]
+    fn region_sizing() {
+        let l = Region::from_rects([
+            Rect::from_sides(0, 0, 10, 40),
+            Rect::from_sides(0, 0, 40, 10),
+        ]);
+        let grown = l.grow(5);
+        assert_eq!(grown.area(), 50 * 20 + 20 * 30);
+        assert_eq!(grown.shrink(5), l);
+
+        let shrunk = l.shrink(2);
+        assert_eq!(
+            shrunk,
+            Region::from_rects([Rect::from_sides(2, 2, 8, 38), Rect::from_sides(2, 2, 38, 8)])
+        );
+        assert!(l.shrink(5).is_empty());
+        assert_eq!(l.grow(0), l);
+        assert_eq!(l.grow(-2), shrunk);
+    }
+}
@@ -0,0 +1,234 @@
+//! Golden file snapshot testing for Substrate netlists.
+//!
+//! Netlists are compared against checked-in golden files after normalizing
+//! content that changes for reasons unrelated to the design, such as absolute
+//! include paths. On mismatch, a line-based diff is reported.
+//!
+//! Set the [`REGEN_ENV_VAR`] environment variable (e.g. `SUBSTRATE_REGEN_GOLDEN=1 cargo test`)
+//! to overwrite golden files with the current output instead of comparing against them.
+
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+use similar::{Algorithm, TextDiff};
+use substrate::context::Context;
+use substrate::schematic::netlist::ConvertibleNetlister;
+use substrate::schematic::Schematic;
+
+#[cfg(test)]
+mod tests;
+
+/// The environment variable that, when set to a value other than `0` or `false`,
+/// causes golden files to be regenerated rather than checked.
+pub const REGEN_ENV_VAR: &str = "SUBSTRATE_REGEN_GOLDEN";
+
+/// The number of unchanged lines shown around each changed region of a diff.
+const DIFF_CONTEXT: usize = 3;
+
+/// An error encountered while checking a golden file.
+#[derive(Debug, thiserror::Error)]
+pub enum GoldenError {
+    /// The block could not be netlisted.
+    #[error("error netlisting block: {0}")]
+    Netlist(#[from] substrate::error::Error),
+    /// The netlister produced invalid UTF-8.
+    #[error("netlist is not valid UTF-8")]
+    Utf8(#[from] std::string::FromUtf8Error),
+    /// The golden file could not be read or written.
+    #[error("error accessing golden file `{}`", .path.display())]
+    Io {
+        /// The path to the golden file.
+        path: PathBuf,
+        /// The underlying error.
+        #[source]
+        source: std::io::Error,
+    },
+    /// The output does not match the golden file.
+    #[error(
+        "output does not match golden file `{}` (rerun with `{REGEN_ENV_VAR}=1` to update it):\n{diff}",
+        .path.display()
+    )]
+    Mismatch {
+        /// The path to the golden file.
+        path: PathBuf,
+        /// A diff from the golden file to the actual output.
+        diff: String,
+    },
+}
+
+/// A result type returning [`GoldenError`] errors.
+pub type Result<T> = std::result::Result<T, GoldenError>;
+
+/// Normalizes volatile content before comparison with a golden file.
+///
+/// Rules are applied in the order in which they were added. After all rules are applied,
+/// line endings are converted to `\n`, trailing whitespace is removed from each line,
+/// and the output is terminated by exactly one newline.
+#[derive(Debug, Clone, Default)]
+pub struct Normalizer {
+    rules: Vec<(Regex, String)>,
+}
+
+impl Normalizer {
+    /// Creates a normalizer with no rules.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces all matches of the regular expression `pattern` with `replacement`.
+    ///
+    /// The replacement may refer to capture groups using `$name` syntax.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pattern` is not a valid regular expression.
+    pub fn replace(mut self, pattern: &str, replacement: impl Into<String>) -> Self {
+        let regex = Regex::new(pattern).expect("invalid normalization pattern");
+        self.rules.push((regex, replacement.into()));
+        self
+    }
+
+    /// Replaces all occurrences of the literal string `text` with `placeholder`.
+    ///
+    /// Useful for removing absolute paths, such as the workspace or build directory,
+    /// from netlist includes.
+    pub fn replace_literal(self, text: impl AsRef<str>, placeholder: impl Into<String>) -> Self {
+        let pattern = regex::escape(text.as_ref());
+        let placeholder = placeholder.into().replace('$', "$$");
+        self.replace(&pattern, placeholder)
+    }
+
+    /// Applies this normalizer to `input`.
+    pub fn normalize(&self, input: &str) -> String {
+        let mut output = input.replace("\r\n", "\n");
+        for (regex, replacement) in self.rules.iter() {
+            output = regex
+                .replace_all(&output, replacement.as_str())
+                .into_owned();
+        }
+        let mut normalized = output
+            .lines()
+            .map(str::trim_end)
+            .collect::<Vec<_>>()
+            .join("\n");
+        let len = normalized.trim_end_matches('\n').len();
+        normalized.truncate(len);
+        normalized.push('\n');
+        normalized
+    }
+}
+
+/// Returns `true` if golden files should be regenerated.
+///
+/// See [`REGEN_ENV_VAR`].
+pub fn regen_enabled() -> bool {
+    std::env::var(REGEN_ENV_VAR)
+        .map(|value| !matches!(value.as_str(), "" | "0" | "false"))
+        .unwrap_or(false)
+}
+
+/// Netlists `block` using `netlister` and returns the netlist as a string.
+pub fn netlist_block<S, N, B>(
+    ctx: &Context,
+    netlister: &N,
+    block: B,
+    opts: N::Options<'_>,
+) -> Result<String>
+where
+    S: substrate::schematic::schema::Schema + ?Sized,
+    N: ConvertibleNetlister<S>,
+    B: Schematic<Schema = S>,
+{
+    let mut buf = Vec::new();
+    netlister.write_netlist(ctx, block, &mut buf, opts)?;
+    Ok(String::from_utf8(buf)?)
+}
+
+/// Compares `actual` against the golden file at `path`.
+///
+/// Both `actual` and the golden file are normalized with `normalizer` before comparison.
+/// If [regeneration](regen_enabled) is enabled, the normalized output is written to `path`
+/// (creating parent directories as needed) and no comparison is performed.
+pub fn check_golden(actual: &str, path: impl AsRef<Path>, normalizer: &Normalizer) -> Result<()> {
+    check_golden_inner(actual, path.as_ref(), normalizer, regen_enabled())
+}
+
+pub(crate) fn check_golden_inner(
+    actual: &str,
+    path: &Path,
+    normalizer: &Normalizer,
+    regen: bool,
+) -> Result<()> {
+    let io_err = |source| GoldenError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+    let actual = normalizer.normalize(actual);
+    if regen {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(io_err)?;
+        }
+        std::fs::write(path, actual).map_err(io_err)?;
+        return Ok(());
+    }
+
+    let expected = std::fs::read_to_string(path).map_err(io_err)?;
+    let expected = normalizer.normalize(&expected);
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(GoldenError::Mismatch {
+            path: path.to_path_buf(),
+            diff: diff(&expected, &actual),
+        })
+    }
+}
+
+/// Compares `actual` against the golden file at `path`, panicking on mismatch.
+///
+/// See [`check_golden`].
+#[track_caller]
+pub fn assert_golden(actual: &str, path: impl AsRef<Path>, normalizer: &Normalizer) {
+    if let Err(e) = check_golden(actual, path, normalizer) {
+        panic!("{e}");
+    }
+}
+
+/// Netlists `block` and compares the result against the golden file at `path`,
+/// panicking on mismatch.
+///
+/// The netlist is normalized with `normalizer`.
+#[track_caller]
+pub fn assert_golden_netlist<S, N, B>(
+    ctx: &Context,
+    netlister: &N,
+    block: B,
+    opts: N::Options<'_>,
+    path: impl AsRef<Path>,
+    normalizer: &Normalizer,
+) where
+    S: substrate::schematic::schema::Schema + ?Sized,
+    N: ConvertibleNetlister<S>,
+    B: Schematic<Schema = S>,
+{
+    let netlist = match netlist_block(ctx, netlister, block, opts) {
+        Ok(netlist) => netlist,
+        Err(e) => panic!("{e}"),
+    };
+    assert_golden(&netlist, path, normalizer);
+}
+
+/// Produces a unified diff from `expected` to `actual`.
+///
+/// Lines are compared using the Myers diff algorithm.
+/// Removed lines are prefixed with `-` and added lines with `+`.
+/// Unchanged lines near a change are prefixed with a space.
+pub fn diff(expected: &str, actual: &str) -> String {
+    TextDiff::configure()
+        .algorithm(Algorithm::Myers)
+        .diff_lines(expected, actual)
+        .unified_diff()
+        .context_radius(DIFF_CONTEXT)
+        .header("expected", "actual")
+        .to_string()
+}
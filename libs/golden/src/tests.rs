@@ -0,0 +1,75 @@
+use std::path::PathBuf;
+
+use rust_decimal_macros::dec;
+use spice::netlist::NetlistOptions;
+use spice::{Resistor, Spice};
+use substrate::context::Context;
+
+use crate::{check_golden_inner, diff, netlist_block, GoldenError, Normalizer};
+
+const BUILD_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/build");
+
+#[inline]
+fn get_path(test_name: &str, file_name: &str) -> PathBuf {
+    PathBuf::from(BUILD_DIR).join(test_name).join(file_name)
+}
+
+#[test]
+fn normalizer_replaces_and_trims() {
+    let normalizer = Normalizer::new().replace_literal("/home/user/pdk", "<PDK>");
+    let input =
+        ".include /home/user/pdk/models.spice  \r\nxinst4 a b res\nxinst7 b c res\nxinst4_y\n\n\n";
+    assert_eq!(
+        normalizer.normalize(input),
+        ".include <PDK>/models.spice\nxinst4 a b res\nxinst7 b c res\nxinst4_y\n"
+    );
+}
+
+#[test]
+fn diff_shows_changed_lines_with_context() {
+    let expected = "a\nb\nc\nd\ne\nf\ng\nh\ni\n";
+    let actual = "a\nb\nc\nd\nE\nf\ng\nh\ni\n";
+    assert_eq!(
+        diff(expected, actual),
+        "--- expected\n+++ actual\n@@ -2,7 +2,7 @@\n b\n c\n d\n-e\n+E\n f\n g\n h\n"
+    );
+}
+
+#[test]
+fn golden_files_can_be_regenerated_and_checked() {
+    let path = get_path("golden_files_can_be_regenerated_and_checked", "out.spice");
+    let normalizer = Normalizer::new();
+
+    check_golden_inner("xinst0 a b\nxinst1 b c\n", &path, &normalizer, true).unwrap();
+    check_golden_inner("xinst0 a b\nxinst1 b c", &path, &normalizer, false).unwrap();
+
+    let err =
+        check_golden_inner("xinst0 a c\nxinst1 b c\n", &path, &normalizer, false).unwrap_err();
+    let GoldenError::Mismatch { diff, .. } = err else {
+        panic!("expected a mismatch, got {err:?}");
+    };
+    assert!(diff.contains("-xinst0 a b\n+xinst0 a c\n"));
+
+    // Reordered instances are reported rather than renumbered away.
+    let err =
+        check_golden_inner("xinst1 b c\nxinst0 a b\n", &path, &normalizer, false).unwrap_err();
+    assert!(matches!(err, GoldenError::Mismatch { .. }));
+}
+
+#[test]
+fn netlists_match_regenerated_golden_files() {
+    let path = get_path("netlists_match_regenerated_golden_files", "resistor.spice");
+    let ctx = Context::new();
+    let netlist = netlist_block(
+        &ctx,
+        &Spice,
+        Resistor::new(dec!(1000)),
+        NetlistOptions::default(),
+    )
+    .unwrap();
+    assert!(netlist.contains(".SUBCKT"));
+
+    let normalizer = Normalizer::new();
+    check_golden_inner(&netlist, &path, &normalizer, true).unwrap();
+    check_golden_inner(&netlist, &path, &normalizer, false).unwrap();
+}
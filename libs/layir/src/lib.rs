@@ -49,6 +49,9 @@ pub struct Cell<L> {
     instance_name_map: HashMap<ArcStr, InstanceId>,
     elements: Vec<Element<L>>,
     ports: IndexMap<ArcStr, Port<L>>,
+    /// Key-value metadata attached to this cell.
+    #[serde(default)]
+    properties: IndexMap<ArcStr, ArcStr>,
 }
 
 /// A location at which this cell should be connected.
@@ -243,6 +246,7 @@ impl<L> Cell<L> {
             instance_name_map: Default::default(),
             elements: Default::default(),
             ports: Default::default(),
+            properties: Default::default(),
         }
     }
 
@@ -262,6 +266,17 @@ impl<L> Cell<L> {
         self.ports.insert(name.into(), port);
     }
 
+    /// Sets a metadata property of this cell, replacing any existing value.
+    pub fn set_property(&mut self, key: impl Into<ArcStr>, value: impl Into<ArcStr>) {
+        self.properties.insert(key.into(), value.into());
+    }
+
+    /// Iterate over the metadata properties of this cell.
+    #[inline]
+    pub fn properties(&self) -> impl Iterator<Item = (&ArcStr, &ArcStr)> {
+        self.properties.iter()
+    }
+
     /// Get a port of this cell by name.
     ///
     /// # Panics
@@ -1,12 +1,27 @@
 //! Error handling.
 use thiserror::Error;
 
+use crate::Format;
+
 /// An error from manipulating a rawfile.
 #[derive(Debug, Error)]
 pub enum Error {
     /// A parsing error.
     #[error("parse error")]
     Parse,
+    /// The rawfile contains no `Values:` or `Binary:` data section.
+    #[error("unrecognized rawfile format: no `Values:` or `Binary:` data section found")]
+    UnknownFormat,
+    /// A plot could not be parsed.
+    #[error("error parsing plot {plot} of {format} rawfile at byte offset {offset}")]
+    Incomplete {
+        /// The detected rawfile format.
+        format: Format,
+        /// The index of the plot that could not be parsed.
+        plot: usize,
+        /// The byte offset at which the malformed plot starts.
+        offset: usize,
+    },
     /// An I/O error.
     #[error("io error: {0}")]
     Io(#[from] std::io::Error),
@@ -4,6 +4,7 @@
 use error::{Error, Result};
 use parser::Analysis;
 use serde::Serialize;
+use std::fmt::Display;
 
 pub mod error;
 pub mod parser;
@@ -42,13 +43,79 @@ pub enum ByteOrder {
     LittleEndian,
 }
 
+/// The format in which a rawfile stores its data.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum Format {
+    /// Data values are written as text following a `Values:` header.
+    Ascii,
+    /// Data values are written as raw floats following a `Binary:` header.
+    Binary,
+}
+
+impl Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ascii => write!(f, "ASCII"),
+            Self::Binary => write!(f, "binary"),
+        }
+    }
+}
+
+/// Detects the format of the given rawfile data.
+///
+/// The format is determined by the data header of the first plot.
+/// Returns [`None`] if no data header is found.
+pub fn detect_format<T>(input: &T) -> Option<Format>
+where
+    T: AsRef<[u8]>,
+{
+    input.as_ref().split(|&c| c == b'\n').find_map(|line| {
+        let line = line.trim_ascii_start();
+        if line.len() < 7 {
+            return None;
+        }
+        if line[..7].eq_ignore_ascii_case(b"Values:") {
+            Some(Format::Ascii)
+        } else if line[..7].eq_ignore_ascii_case(b"Binary:") {
+            Some(Format::Binary)
+        } else {
+            None
+        }
+    })
+}
+
 /// Parse the given rawfile data.
+///
+/// Both ASCII and binary rawfiles are supported; the format is detected automatically.
+/// A rawfile may contain any number of plots, each of which is parsed into an [`Analysis`].
+///
+/// Empty (or whitespace-only) input parses to a rawfile with no analyses.
+/// Other input without a `Values:` or `Binary:` data section
+/// returns [`Error::UnknownFormat`].
 pub fn parse<T>(input: &T, options: Options) -> Result<Rawfile<'_>>
 where
     T: AsRef<[u8]>,
 {
-    match parser::analyses(input.as_ref(), options) {
-        Ok((_, analyses)) => Ok(Rawfile { analyses }),
+    let input = input.as_ref();
+    if input.iter().all(|c| c.is_ascii_whitespace()) {
+        return Ok(Rawfile {
+            analyses: Vec::new(),
+        });
+    }
+    let format = detect_format(&input).ok_or(Error::UnknownFormat)?;
+    match parser::analyses(input, options) {
+        Ok((rest, analyses)) => {
+            // Any unparsed data indicates a malformed plot.
+            if rest.iter().all(|c| c.is_ascii_whitespace()) {
+                Ok(Rawfile { analyses })
+            } else {
+                Err(Error::Incomplete {
+                    format,
+                    plot: analyses.len(),
+                    offset: input.len() - rest.len(),
+                })
+            }
+        }
         Err(_) => Err(Error::Parse),
     }
 }
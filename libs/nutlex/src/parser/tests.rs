@@ -1,6 +1,7 @@
 use std::path::PathBuf;
 
-use crate::parse;
+use crate::error::Error;
+use crate::{detect_format, parse, Format};
 
 use super::*;
 
@@ -247,3 +248,54 @@ fn test_ngspice_analyses() {
         )
     });
 }
+
+#[test]
+fn test_detect_format() {
+    for (path, format) in [
+        ("netlist.ascii.raw", Format::Ascii),
+        ("netlist.bin.raw", Format::Binary),
+        ("rawspice_ascii.raw", Format::Ascii),
+        ("rawspice_binary.raw", Format::Binary),
+    ] {
+        let path = PathBuf::from(EXAMPLES_PATH).join(path);
+        let data = std::fs::read(path).unwrap();
+        assert_eq!(detect_format(&data), Some(format));
+    }
+    assert_eq!(detect_format(b"Title: empty\n"), None);
+}
+
+#[test]
+fn test_malformed_plot_is_reported() {
+    let path = PathBuf::from(EXAMPLES_PATH).join("netlist.ascii.raw");
+    let data = std::fs::read_to_string(path).unwrap();
+    // Truncate the file at a line boundary within the data of the second plot.
+    let second = data.match_indices("Plotname:").nth(1).unwrap().0;
+    let end = second + 1000 + data[second + 1000..].find('\n').unwrap();
+    let truncated = &data[..end];
+
+    let err = parse(&truncated, Options::default()).unwrap_err();
+    assert!(
+        matches!(
+            err,
+            Error::Incomplete {
+                format: Format::Ascii,
+                plot: 1,
+                ..
+            }
+        ),
+        "unexpected error: {err:?}"
+    );
+
+    assert!(matches!(
+        parse(b"Title: empty\n", Options::default()),
+        Err(Error::UnknownFormat)
+    ));
+}
+
+#[test]
+fn empty_rawfiles_have_no_analyses() {
+    for input in [&b""[..], b"\n  \n"] {
+        let rawfile = parse(&input, Options::default()).unwrap();
+        assert!(rawfile.analyses.is_empty());
+    }
+}
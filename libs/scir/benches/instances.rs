@@ -0,0 +1,94 @@
+//! Measures the time and memory used to build cells with many instances,
+//! with and without compact instance storage.
+//!
+//! Results are printed rather than checked, since they depend on the platform.
+//!
+//! Run with `cargo bench -p scir --bench instances`.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use scir::*;
+
+/// An allocator that tracks the number of bytes currently allocated.
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn bitcell(lib: &mut LibraryBuilder) -> CellId {
+    let mut bitcell = Cell::new("bitcell");
+    let wl = bitcell.add_node("wl");
+    let bl = bitcell.add_node("bl");
+    bitcell.expose_port(wl, Direction::Input);
+    bitcell.expose_port(bl, Direction::InOut);
+    lib.add_cell(bitcell)
+}
+
+fn array(bitcell: CellId, rows: usize, cols: usize, compact: bool) -> Cell {
+    let mut array = Cell::new("array");
+    array.set_compact_instances(compact);
+    let wl = array.add_bus("wl", rows);
+    let bl = array.add_bus("bl", cols);
+    let wl_port = arcstr::literal!("wl");
+    let bl_port = arcstr::literal!("bl");
+    array.add_array(
+        bitcell,
+        rows,
+        cols,
+        |row, col| arcstr::format!("xcell_{row}_{col}"),
+        |row, col, inst| {
+            inst.connect(wl_port.clone(), wl.index(row));
+            inst.connect(bl_port.clone(), bl.index(col));
+        },
+    );
+    array.expose_port(wl, Direction::Input);
+    array.expose_port(bl, Direction::InOut);
+    array
+}
+
+fn main() {
+    for (rows, cols, compact) in [(32, 32), (256, 256), (1024, 1024)]
+        .into_iter()
+        .flat_map(|(rows, cols)| [(rows, cols, false), (rows, cols, true)])
+    {
+        let mut lib = <LibraryBuilder>::new();
+        let bitcell = bitcell(&mut lib);
+
+        let before = ALLOCATED.load(Ordering::Relaxed);
+        let start = Instant::now();
+        let array = array(bitcell, rows, cols, compact);
+        let build = start.elapsed();
+        let bytes = ALLOCATED.load(Ordering::Relaxed) - before;
+
+        let array = lib.add_cell(array);
+        lib.set_top(array);
+        let start = Instant::now();
+        let issues = lib.validate();
+        let validate = start.elapsed();
+        assert!(!issues.has_error());
+
+        let n = rows * cols;
+        let storage = if compact { "compact" } else { "indexed" };
+        println!(
+            "{n:>8} instances ({storage}): {:>6.1} MiB ({:>5.1} B/instance), build {build:>10.2?}, validate {validate:>10.2?}",
+            bytes as f64 / (1 << 20) as f64,
+            bytes as f64 / n as f64,
+        );
+    }
+}
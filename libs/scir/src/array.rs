@@ -0,0 +1,106 @@
+//! Arrays of instances for repeated structures, such as memory arrays.
+
+use arcstr::ArcStr;
+
+use crate::{Cell, ChildId, Instance, InstanceId};
+
+/// The IDs of a two-dimensional array of instances in a cell.
+///
+/// Created by [`Cell::add_array`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct InstanceArray {
+    rows: usize,
+    cols: usize,
+    ids: Vec<InstanceId>,
+}
+
+impl InstanceArray {
+    /// The number of rows in the array.
+    #[inline]
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// The number of columns in the array.
+    #[inline]
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Returns the ID of the instance at the given row and column.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the row or column is out of bounds.
+    pub fn get(&self, row: usize, col: usize) -> InstanceId {
+        assert!(
+            row < self.rows && col < self.cols,
+            "array index ({row}, {col}) out of bounds"
+        );
+        self.ids[row * self.cols + col]
+    }
+
+    /// Iterates over the row, column, and ID of each instance in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize, InstanceId)> + '_ {
+        self.ids
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (i / self.cols, i % self.cols, *id))
+    }
+}
+
+impl Cell {
+    /// Adds a `rows` by `cols` array of instances of `child` to the cell.
+    ///
+    /// The instance at each row and column is named by `name` and connected by `connect`,
+    /// which are called once per instance in row-major order. Connections must refer to
+    /// signals of this cell, which should be added before the array:
+    ///
+    /// ```
+    /// # use scir::*;
+    /// # let mut lib = <LibraryBuilder>::new();
+    /// # let mut bitcell = Cell::new("bitcell");
+    /// # let wl = bitcell.add_node("wl");
+    /// # let bl = bitcell.add_node("bl");
+    /// # bitcell.expose_port(wl, Direction::Input);
+    /// # bitcell.expose_port(bl, Direction::InOut);
+    /// # let bitcell = lib.add_cell(bitcell);
+    /// let mut array = Cell::new("array");
+    /// let wl = array.add_bus("wl", 4);
+    /// let bl = array.add_bus("bl", 8);
+    /// let cells = array.add_array(
+    ///     bitcell,
+    ///     4,
+    ///     8,
+    ///     |row, col| format!("xcell_{row}_{col}"),
+    ///     |row, col, inst| {
+    ///         inst.connect("wl", wl.index(row));
+    ///         inst.connect("bl", bl.index(col));
+    ///     },
+    /// );
+    /// assert_eq!(array.instance(cells.get(2, 3)).name().as_str(), "xcell_2_3");
+    /// ```
+    pub fn add_array<N: Into<ArcStr>>(
+        &mut self,
+        child: impl Into<ChildId>,
+        rows: usize,
+        cols: usize,
+        mut name: impl FnMut(usize, usize) -> N,
+        mut connect: impl FnMut(usize, usize, &mut Instance),
+    ) -> InstanceArray {
+        let child = child.into();
+        let n = rows * cols;
+        self.instances.reserve(n);
+        self.instance_name_map.reserve(n);
+
+        let mut ids = Vec::with_capacity(n);
+        for row in 0..rows {
+            for col in 0..cols {
+                let mut inst = Instance::new(name(row, col), child);
+                connect(row, col, &mut inst);
+                ids.push(self.add_instance(inst));
+            }
+        }
+        InstanceArray { rows, cols, ids }
+    }
+}
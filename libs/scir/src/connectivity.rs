@@ -0,0 +1,89 @@
+//! Connectivity traversal of nets across the hierarchy of a SCIR library.
+
+use arcstr::ArcStr;
+
+use crate::schema::Schema;
+use crate::{
+    CellId, ChildId, IndexOwned, InstancePath, LibraryBuilder, SignalPathTail, SliceOne,
+    SliceOnePath,
+};
+
+/// A single bit of a port of an instance in a SCIR library.
+#[derive(Clone, Debug, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub struct Terminal {
+    /// The path to the instance.
+    pub instance: InstancePath,
+    /// The child of the instance.
+    pub child: ChildId,
+    /// The name of the port.
+    pub port: ArcStr,
+    /// The index of the bit within the port.
+    ///
+    /// Single bit ports have index 0.
+    pub index: usize,
+}
+
+impl<S: Schema + ?Sized> LibraryBuilder<S> {
+    /// Finds all terminals connected to the net addressed by `path`.
+    ///
+    /// The net is first followed upward through port bubbles, as in
+    /// [`LibraryBuilder::simplify_path`], then downward into every instance it connects to.
+    /// The traversal does not leave the top cell of `path`.
+    ///
+    /// Terminals of both cell and primitive instances are returned, sorted by instance path
+    /// and port.
+    /// Returns an empty list if the top cell of `path` is not in the library.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `path` is not a valid path in this library.
+    pub fn connected_terminals(&self, path: &SliceOnePath) -> Vec<Terminal> {
+        let path = self.simplify_path(path.clone());
+        let mut instances = path.instances().clone();
+        let Some(cell) = self.annotate_instance_path(instances.clone()).bot() else {
+            return Vec::new();
+        };
+        let bit = match path.tail() {
+            SignalPathTail::Id(bit) => *bit,
+            SignalPathTail::Name(name) => {
+                SliceOne::new(self.cell(cell).signal_named(name.signal()).id, name.index())
+            }
+        };
+
+        let mut terminals = Vec::new();
+        self.connected_terminals_in(&mut instances, cell, bit, &mut terminals);
+        terminals.sort();
+        terminals
+    }
+
+    fn connected_terminals_in(
+        &self,
+        path: &mut InstancePath,
+        cell: CellId,
+        bit: SliceOne,
+        terminals: &mut Vec<Terminal>,
+    ) {
+        for (id, inst) in self.cell(cell).instances() {
+            path.push(id);
+            for (port, conn) in inst.connections() {
+                for index in (0..conn.width()).filter(|&i| conn.index(i) == bit) {
+                    terminals.push(Terminal {
+                        instance: path.clone(),
+                        child: inst.child(),
+                        port: port.clone(),
+                        index,
+                    });
+                    if let ChildId::Cell(child) = inst.child() {
+                        let info = self.cell(child).signal_named(port);
+                        let bit = match info.width {
+                            Some(_) => info.slice().index(index),
+                            None => info.slice().slice_one().unwrap(),
+                        };
+                        self.connected_terminals_in(path, child, bit, terminals);
+                    }
+                }
+            }
+            path.pop();
+        }
+    }
+}
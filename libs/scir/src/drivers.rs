@@ -1,8 +1,11 @@
 //! SCIR driver validation.
 //!
 //! Looks for issues such as multiply-driven nets and floating nets.
+//! Also infers the directions of [`InOut`](Direction::InOut) ports from their usage,
+//! and flags ports whose connectivity contradicts their declared [`Direction`].
+//! The checks can be tuned and individual issues waived using a [`DriverConfig`].
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::{Display, Formatter};
 
 use diagnostics::{Diagnostic, IssueSet, Severity};
@@ -20,6 +23,26 @@ pub struct Net {
     idx: Option<usize>,
 }
 
+impl Net {
+    /// The name of the cell containing this net.
+    #[inline]
+    pub fn cell_name(&self) -> &ArcStr {
+        &self.cell_name
+    }
+
+    /// The name of the signal.
+    #[inline]
+    pub fn signal_name(&self) -> &ArcStr {
+        &self.signal_name
+    }
+
+    /// The signal bit index, if the signal is a bus.
+    #[inline]
+    pub fn idx(&self) -> Option<usize> {
+        self.idx
+    }
+}
+
 impl Display for Net {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}/{}", self.cell_name, self.signal_name)?;
@@ -36,6 +59,9 @@ pub struct DriverIssue {
     cause: Cause,
     severity: Severity,
     net: Net,
+    /// The generator code that created the net's signal, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source_info: Option<SourceInfo>,
 }
 
 /// The state of a net.
@@ -79,43 +105,31 @@ impl NetState {
     }
 
     /// Validates the number of drivers, taps, and inouts on the net.
-    fn validate(&self, net: Net, output: &mut IssueSet<DriverIssue>) {
+    ///
+    /// Returns the cause and default severity of each issue found.
+    fn validate(&self) -> Vec<(Cause, Severity)> {
+        let mut causes = Vec::new();
         if self.drivers > 1 {
-            output.add(DriverIssue::new_and_log(
-                Cause::MultipleDrivers,
-                net.clone(),
-                Severity::Info,
-            ));
+            causes.push((Cause::MultipleDrivers, Severity::Info));
         }
 
         if self.taps > 0 && self.inouts + self.drivers == 0 {
-            output.add(DriverIssue::new_and_log(
-                Cause::NoDrivers,
-                net.clone(),
-                Severity::Warning,
-            ));
+            causes.push((Cause::NoDrivers, Severity::Warning));
         }
 
         if self.degree() == 0 {
-            output.add(DriverIssue::new_and_log(
-                Cause::Floating,
-                net.clone(),
-                Severity::Warning,
-            ));
+            causes.push((Cause::Floating, Severity::Warning));
         }
 
         if self.taps == 0 && self.eff_drivers() == 1 {
-            output.add(DriverIssue::new_and_log(
-                Cause::NotConnected,
-                net.clone(),
-                Severity::Info,
-            ));
+            causes.push((Cause::NotConnected, Severity::Info));
         }
+        causes
     }
 }
 
 /// The cause of a driver analysis error or warning.
-#[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
 pub enum Cause {
     /// A net that is driven but not tapped.
     ///
@@ -133,6 +147,14 @@ pub enum Cause {
     ///
     /// Example: an inverter whose input port is not connected.
     NoDrivers,
+    /// An input port that is driven from within its cell.
+    ///
+    /// Example: an input port connected directly to the output of an inverter.
+    DrivenInput,
+    /// An output port that is not driven from within its cell.
+    ///
+    /// Example: an output port connected only to the input of an inverter.
+    UndrivenOutput,
 }
 
 impl Diagnostic for DriverIssue {
@@ -143,11 +165,17 @@ impl Diagnostic for DriverIssue {
 
 impl DriverIssue {
     /// Creates a new validator issue from the given cause and severity.
-    pub(crate) fn new(cause: Cause, net: Net, severity: Severity) -> Self {
+    pub(crate) fn new(
+        cause: Cause,
+        net: Net,
+        severity: Severity,
+        source_info: Option<&SourceInfo>,
+    ) -> Self {
         Self {
             cause,
             net,
             severity,
+            source_info: source_info.cloned(),
         }
     }
 
@@ -157,11 +185,28 @@ impl DriverIssue {
         &self.cause
     }
 
+    /// Gets the net on which this issue was found.
+    #[inline]
+    pub fn net(&self) -> &Net {
+        &self.net
+    }
+
+    /// The generator code that created the net's signal, if known.
+    #[inline]
+    pub fn source_info(&self) -> Option<&SourceInfo> {
+        self.source_info.as_ref()
+    }
+
     /// Creates a new validator issue and logs it immediately.
     ///
     /// The log level will be selected according to the given severity.
-    pub(crate) fn new_and_log(cause: Cause, net: Net, severity: Severity) -> Self {
-        let result = Self::new(cause, net, severity);
+    pub(crate) fn new_and_log(
+        cause: Cause,
+        net: Net,
+        severity: Severity,
+        source_info: Option<&SourceInfo>,
+    ) -> Self {
+        let result = Self::new(cause, net, severity, source_info);
         match severity {
             Severity::Info => tracing::event!(Level::INFO, issue = ?result.cause, "{}", result),
             Severity::Warning => tracing::event!(Level::WARN, issue = ?result.cause, "{}", result),
@@ -173,7 +218,11 @@ impl DriverIssue {
 
 impl Display for DriverIssue {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}: {}", self.cause, self.net)
+        write!(f, "{}: {}", self.cause, self.net)?;
+        if let Some(source_info) = &self.source_info {
+            write!(f, " (generated by {source_info})")?;
+        }
+        Ok(())
     }
 }
 
@@ -184,26 +233,306 @@ impl Display for Cause {
             Self::MultipleDrivers => write!(f, "multiple drivers on the same net"),
             Self::NoDrivers => write!(f, "net is used (i.e. read from), but has no drivers"),
             Self::NotConnected => write!(f, "net is driven but never used elsewhere"),
+            Self::DrivenInput => write!(f, "input port is driven from within its cell"),
+            Self::UndrivenOutput => write!(f, "output port is not driven from within its cell"),
         }
     }
 }
 
+/// Configuration of driver analysis.
+///
+/// See [`LibraryBuilder::set_driver_config`].
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct DriverConfig {
+    /// Cells that are excluded from driver analysis.
+    ignored_cells: HashSet<ArcStr>,
+    /// Nets, given as a cell name and a signal name, that may have multiple drivers.
+    multiple_drivers: HashSet<(ArcStr, ArcStr)>,
+    /// Severities that override the default severity of each cause.
+    severities: HashMap<Cause, Severity>,
+    /// Waivers for individual issues.
+    waivers: Vec<Waiver>,
+}
+
+impl DriverConfig {
+    /// Creates a new [`DriverConfig`] that performs all checks with default severities.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Excludes the cell named `cell` from driver analysis.
+    pub fn ignore_cell(mut self, cell: impl Into<ArcStr>) -> Self {
+        self.ignored_cells.insert(cell.into());
+        self
+    }
+
+    /// Allows the signal named `signal` in the cell named `cell` to have multiple drivers.
+    ///
+    /// [`Cause::MultipleDrivers`] is not reported for any bit of the signal.
+    pub fn allow_multiple_drivers(
+        mut self,
+        cell: impl Into<ArcStr>,
+        signal: impl Into<ArcStr>,
+    ) -> Self {
+        self.multiple_drivers.insert((cell.into(), signal.into()));
+        self
+    }
+
+    /// Reports all issues with the given cause with the given severity.
+    ///
+    /// Takes precedence over [strict directions](LibraryBuilder::set_strict_directions).
+    pub fn severity(mut self, cause: Cause, severity: Severity) -> Self {
+        self.severities.insert(cause, severity);
+        self
+    }
+
+    /// Waives the issues matched by `waiver`.
+    pub fn waive(mut self, waiver: Waiver) -> Self {
+        self.waivers.push(waiver);
+        self
+    }
+}
+
+/// A waiver for driver issues that have been reviewed and deemed acceptable.
+///
+/// Waived issues do not count as errors or warnings, but are recorded in
+/// [`Issues::waived`] along with the waiver that matched them.
+#[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Waiver {
+    cause: Cause,
+    cell: ArcStr,
+    signal: Option<ArcStr>,
+    reason: ArcStr,
+}
+
+impl Waiver {
+    /// Creates a waiver for all issues with the given cause in the cell named `cell`.
+    pub fn new(cause: Cause, cell: impl Into<ArcStr>, reason: impl Into<ArcStr>) -> Self {
+        Self {
+            cause,
+            cell: cell.into(),
+            signal: None,
+            reason: reason.into(),
+        }
+    }
+
+    /// Restricts the waiver to the signal named `signal`.
+    pub fn signal(mut self, signal: impl Into<ArcStr>) -> Self {
+        self.signal = Some(signal.into());
+        self
+    }
+
+    /// The cause of the waived issues.
+    #[inline]
+    pub fn cause(&self) -> &Cause {
+        &self.cause
+    }
+
+    /// The reason the issues were waived.
+    #[inline]
+    pub fn reason(&self) -> &ArcStr {
+        &self.reason
+    }
+
+    fn matches(&self, cause: &Cause, net: &Net) -> bool {
+        self.cause == *cause
+            && self.cell == net.cell_name
+            && self
+                .signal
+                .as_ref()
+                .is_none_or(|signal| *signal == net.signal_name)
+    }
+}
+
+/// A driver issue suppressed by a [`Waiver`].
+#[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub struct WaivedIssue {
+    issue: DriverIssue,
+    waiver: Waiver,
+}
+
+impl WaivedIssue {
+    /// The waived issue.
+    #[inline]
+    pub fn issue(&self) -> &DriverIssue {
+        &self.issue
+    }
+
+    /// The waiver that matched the issue.
+    #[inline]
+    pub fn waiver(&self) -> &Waiver {
+        &self.waiver
+    }
+}
+
+impl Display for WaivedIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (waived: {})", self.issue, self.waiver.reason)
+    }
+}
+
+/// The effective port directions of each cell in a library.
+///
+/// See [`LibraryBuilder::infer_directions`].
+pub type PortDirections = HashMap<CellId, HashMap<ArcStr, Direction>>;
+
 impl<S: Schema + ?Sized> LibraryBuilder<S> {
     /// Perform driver analysis on this library.
+    ///
+    /// Port directions are checked against the connectivity of each cell.
+    /// Contradictions are reported as warnings, or as errors if
+    /// [strict directions](LibraryBuilder::set_strict_directions) are enabled.
+    ///
+    /// Checks are configured by the library's [`DriverConfig`].
+    /// Waived issues are omitted.
     pub fn validate_drivers(&self) -> IssueSet<DriverIssue> {
+        self.validate_drivers_with_waivers().0
+    }
+
+    /// Performs driver analysis on this library, returning the waived issues separately.
+    pub(crate) fn validate_drivers_with_waivers(
+        &self,
+    ) -> (IssueSet<DriverIssue>, Vec<WaivedIssue>) {
         let _guard = span!(Level::INFO, "performing driver analysis on SCIR Library").entered();
         let mut issues = IssueSet::new();
-        self.validate_drivers_inner(&mut issues);
-        issues
+        let mut waived = Vec::new();
+        self.validate_drivers_inner(&mut issues, &mut waived);
+        (issues, waived)
     }
 
-    fn validate_drivers_inner(&self, issues: &mut IssueSet<DriverIssue>) {
+    /// Reports an issue, applying the library's [`DriverConfig`].
+    fn report_driver_issue(
+        &self,
+        cause: Cause,
+        net: Net,
+        severity: Severity,
+        source_info: Option<&SourceInfo>,
+        issues: &mut IssueSet<DriverIssue>,
+        waived: &mut Vec<WaivedIssue>,
+    ) {
+        let config = &self.driver_config;
+        if cause == Cause::MultipleDrivers
+            && config
+                .multiple_drivers
+                .contains(&(net.cell_name.clone(), net.signal_name.clone()))
+        {
+            return;
+        }
+        let severity = config.severities.get(&cause).copied().unwrap_or(severity);
+        if let Some(waiver) = config.waivers.iter().find(|w| w.matches(&cause, &net)) {
+            waived.push(WaivedIssue {
+                issue: DriverIssue::new(cause, net, severity, source_info),
+                waiver: waiver.clone(),
+            });
+            return;
+        }
+        issues.add(DriverIssue::new_and_log(cause, net, severity, source_info));
+    }
+
+    /// Infers the effective direction of every port in the library.
+    ///
+    /// Input and output ports keep their declared direction.
+    /// An inout port is inferred to be an output if every bit is driven
+    /// from within its cell, or an input if every bit is only read from within its cell.
+    /// Otherwise, the port remains an inout.
+    ///
+    /// Primitives are assumed to use all of their ports as inouts.
+    pub fn infer_directions(&self) -> PortDirections {
+        let mut dirs = PortDirections::new();
+        let mut visited = HashSet::new();
         for &id in self.cells.keys() {
-            self.validate_cell_drivers(id, issues);
+            self.infer_cell_directions(id, &mut dirs, &mut visited);
         }
+        dirs
     }
 
-    fn validate_cell_drivers(&self, id: CellId, issues: &mut IssueSet<DriverIssue>) {
+    fn infer_cell_directions(
+        &self,
+        id: CellId,
+        dirs: &mut PortDirections,
+        visited: &mut HashSet<CellId>,
+    ) {
+        // Cells that are part of an instantiation cycle fall back
+        // to their declared directions.
+        if !visited.insert(id) {
+            return;
+        }
+        let cell = self.cells.get(&id).unwrap();
+        for (_, instance) in cell.instances.iter() {
+            if let ChildId::Cell(child) = instance.child() {
+                self.infer_cell_directions(child, dirs, visited);
+            }
+        }
+
+        let usage = self.internal_usage(cell, dirs);
+        let ports = cell
+            .ports
+            .iter()
+            .map(|(name, port)| {
+                let dir = match port.direction {
+                    Direction::InOut => infer_direction(&usage[&port.signal]),
+                    dir => dir,
+                };
+                (name.clone(), dir)
+            })
+            .collect();
+        dirs.insert(id, ports);
+    }
+
+    /// Tallies the connections to each net made by the instances within `cell`.
+    ///
+    /// Ports of the cell itself are not counted, nor are connections to
+    /// ports that do not exist, which are reported by [validation](LibraryBuilder::validate).
+    fn internal_usage(
+        &self,
+        cell: &Cell,
+        dirs: &PortDirections,
+    ) -> HashMap<SignalId, Vec<NetState>> {
+        let mut net_states: HashMap<SignalId, Vec<NetState>> =
+            HashMap::from_iter(cell.signals().map(|(id, info)| {
+                let len = info.width.unwrap_or(1);
+                (id, vec![NetState::new(); len])
+            }));
+
+        for (_, instance) in cell.instances.iter() {
+            for (port, conn) in instance.connections() {
+                let dir = match instance.child() {
+                    ChildId::Cell(child) => dirs
+                        .get(&child)
+                        .and_then(|ports| ports.get(port).copied())
+                        .or_else(|| Some(self.try_cell(child)?.try_port(port)?.direction)),
+                    ChildId::Primitive(_) => Some(Direction::InOut),
+                };
+                if let Some(dir) = dir {
+                    update_conn_states(&mut net_states, conn, dir);
+                }
+            }
+        }
+
+        net_states
+    }
+
+    fn validate_drivers_inner(
+        &self,
+        issues: &mut IssueSet<DriverIssue>,
+        waived: &mut Vec<WaivedIssue>,
+    ) {
+        let dirs = self.infer_directions();
+        for (&id, cell) in self.cells.iter() {
+            if self.driver_config.ignored_cells.contains(&cell.name) {
+                continue;
+            }
+            self.validate_cell_drivers(id, issues, waived);
+            self.validate_cell_directions(id, &dirs, issues, waived);
+        }
+    }
+
+    fn validate_cell_drivers(
+        &self,
+        id: CellId,
+        issues: &mut IssueSet<DriverIssue>,
+        waived: &mut Vec<WaivedIssue>,
+    ) {
         let cell = self.cells.get(&id).unwrap();
         let _guard =
             span!(Level::INFO, "validating SCIR cell drivers", cell.id = %id, cell.name = %cell.name)
@@ -225,6 +554,11 @@ impl<S: Schema + ?Sized> LibraryBuilder<S> {
             }
         }
 
+        // Global nets may be driven and read from any cell.
+        for (id, _) in cell.signals().filter(|(_, info)| info.is_global()) {
+            net_states.get_mut(&id).unwrap()[0].inouts += 1;
+        }
+
         for (_, instance) in cell.instances.iter() {
             analyze_instance(self, &mut net_states, instance);
         }
@@ -232,19 +566,82 @@ impl<S: Schema + ?Sized> LibraryBuilder<S> {
         for (sig, list) in net_states.iter() {
             for (i, state) in list.iter().enumerate() {
                 let info = cell.signal(*sig);
-                state.validate(
-                    Net {
+                for (cause, severity) in state.validate() {
+                    let net = Net {
                         cell_name: cell.name().clone(),
                         signal_name: info.name.clone(),
                         idx: info.width.map(|_| i),
-                    },
-                    issues,
-                );
+                    };
+                    let source_info = info.source_info.as_ref().or(cell.source_info());
+                    self.report_driver_issue(cause, net, severity, source_info, issues, waived);
+                }
+            }
+        }
+    }
+
+    fn validate_cell_directions(
+        &self,
+        id: CellId,
+        dirs: &PortDirections,
+        issues: &mut IssueSet<DriverIssue>,
+        waived: &mut Vec<WaivedIssue>,
+    ) {
+        let cell = self.cells.get(&id).unwrap();
+        let _guard = span!(
+            Level::INFO,
+            "validating SCIR cell port directions",
+            cell.id = %id,
+            cell.name = %cell.name
+        )
+        .entered();
+
+        // Cells without contents, such as leaf cells and blackboxes, declare port
+        // directions that cannot be checked against their connectivity.
+        if cell.instances.is_empty() {
+            return;
+        }
+
+        let severity = if self.strict_directions {
+            Severity::Error
+        } else {
+            Severity::Warning
+        };
+        let usage = self.internal_usage(cell, dirs);
+
+        for port in cell.ports() {
+            let info = cell.signal(port.signal());
+            for (i, state) in usage[&port.signal()].iter().enumerate() {
+                let cause = match port.direction {
+                    Direction::Input if state.drivers > 0 => Cause::DrivenInput,
+                    Direction::Output if state.eff_drivers() == 0 => Cause::UndrivenOutput,
+                    _ => continue,
+                };
+                let net = Net {
+                    cell_name: cell.name().clone(),
+                    signal_name: info.name.clone(),
+                    idx: info.width.map(|_| i),
+                };
+                let source_info = info.source_info.as_ref().or(cell.source_info());
+                self.report_driver_issue(cause, net, severity, source_info, issues, waived);
             }
         }
     }
 }
 
+/// Infers the direction of an inout port from the internal usage of each of its bits.
+fn infer_direction(states: &[NetState]) -> Direction {
+    if states.iter().all(|s| s.inouts == 0 && s.drivers > 0) {
+        Direction::Output
+    } else if states
+        .iter()
+        .all(|s| s.inouts == 0 && s.drivers == 0 && s.taps > 0)
+    {
+        Direction::Input
+    } else {
+        Direction::InOut
+    }
+}
+
 fn analyze_instance<S: Schema + ?Sized>(
     lib: &LibraryBuilder<S>,
     net_states: &mut HashMap<SignalId, Vec<NetState>>,
@@ -253,18 +650,30 @@ fn analyze_instance<S: Schema + ?Sized>(
     if inst.child().is_primitive() {
         return;
     }
-    let cell = lib.cell(inst.child().unwrap_cell());
+    let Some(cell) = lib.try_cell(inst.child().unwrap_cell()) else {
+        return;
+    };
+    // Connections to ports that do not exist are reported by validation.
     for (port, conn) in inst.connections() {
-        let dir = cell.port(port).direction;
-        for part in conn.parts() {
-            let states = net_states.get_mut(&part.signal()).unwrap();
-            if let Some(range) = part.range() {
-                for idx in range {
-                    update_net_state(&mut states[idx], dir);
-                }
-            } else {
-                update_net_state(&mut states[0], dir);
+        if let Some(port) = cell.try_port(port) {
+            update_conn_states(net_states, conn, port.direction);
+        }
+    }
+}
+
+fn update_conn_states(
+    net_states: &mut HashMap<SignalId, Vec<NetState>>,
+    conn: &Concat,
+    dir: Direction,
+) {
+    for part in conn.parts() {
+        let states = net_states.get_mut(&part.signal()).unwrap();
+        if let Some(range) = part.range() {
+            for idx in range {
+                update_net_state(&mut states[idx], dir);
             }
+        } else {
+            update_net_state(&mut states[0], dir);
         }
     }
 }
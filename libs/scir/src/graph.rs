@@ -0,0 +1,271 @@
+//! Hierarchy and connectivity graphs of SCIR libraries.
+//!
+//! A [`Graph`] summarizes the cells of a library, the nets within each cell, and the
+//! instances connected to those nets. Graphs can be rendered with Graphviz using
+//! [`Graph::write_dot`] or serialized to JSON using [`Graph::to_json`], which is useful for
+//! documentation and design reviews.
+
+use std::io::Write;
+
+use super::*;
+
+/// A hierarchy and connectivity graph of a SCIR library.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Graph {
+    /// The name of the top cell, if the library has one.
+    pub top: Option<ArcStr>,
+    /// The names of the root cells of the library, including the top cell.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub roots: Vec<ArcStr>,
+    /// The cells in the library, in the order in which they were added.
+    pub cells: Vec<GraphCell>,
+}
+
+/// A cell in a [`Graph`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphCell {
+    /// The name of the cell.
+    pub name: ArcStr,
+    /// The nets declared in the cell.
+    pub nets: Vec<GraphNet>,
+    /// The instances contained in the cell.
+    pub instances: Vec<GraphInstance>,
+}
+
+/// A net in a [`GraphCell`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphNet {
+    /// The name of the net.
+    pub name: ArcStr,
+    /// The width of the net, if it is a bus.
+    pub width: Option<usize>,
+    /// The direction of the net, if it is a port of the cell.
+    pub direction: Option<Direction>,
+    /// Whether the net refers to a library-level global net.
+    pub global: bool,
+}
+
+/// The child of a [`GraphInstance`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "name", rename_all = "snake_case")]
+pub enum GraphChild {
+    /// A cell with the given name.
+    Cell(ArcStr),
+    /// A primitive, identified by its ID within the library.
+    Primitive(ArcStr),
+}
+
+impl GraphChild {
+    /// The name of the child.
+    pub fn name(&self) -> &ArcStr {
+        match self {
+            GraphChild::Cell(name) | GraphChild::Primitive(name) => name,
+        }
+    }
+}
+
+/// An instance in a [`GraphCell`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphInstance {
+    /// The name of the instance.
+    pub name: ArcStr,
+    /// The instantiated cell or primitive.
+    pub child: GraphChild,
+    /// The connections of the instance, sorted by port name.
+    pub connections: Vec<GraphConnection>,
+}
+
+/// A connection between a port of a [`GraphInstance`] and a net of the parent cell.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphConnection {
+    /// The name of the instance port.
+    pub port: ArcStr,
+    /// The name of the connected net.
+    pub net: ArcStr,
+    /// The connected range of the net, if only part of a bus is connected.
+    ///
+    /// The range start is inclusive and the end is exclusive.
+    pub range: Option<(usize, usize)>,
+}
+
+impl<S: Schema + ?Sized> LibraryBuilder<S> {
+    /// Creates a hierarchy and connectivity graph of this library.
+    pub fn to_graph(&self) -> Graph {
+        Graph {
+            top: self.top_cell().map(|id| self.cell(id).name().clone()),
+            roots: self
+                .roots()
+                .map(|id| self.cell(id).name().clone())
+                .collect(),
+            cells: self
+                .cells()
+                .map(|(_, cell)| self.graph_cell(cell))
+                .collect(),
+        }
+    }
+
+    fn graph_cell(&self, cell: &Cell) -> GraphCell {
+        let nets = cell
+            .signals()
+            .map(|(id, info)| GraphNet {
+                name: info.name.clone(),
+                width: info.width,
+                direction: cell
+                    .ports()
+                    .find(|port| port.signal() == id)
+                    .map(Port::direction),
+                global: info.global,
+            })
+            .collect();
+        let instances = cell
+            .instances()
+            .map(|(_, inst)| {
+                let child = match inst.child() {
+                    ChildId::Cell(id) => GraphChild::Cell(self.cell(id).name().clone()),
+                    ChildId::Primitive(id) => GraphChild::Primitive(arcstr::format!("{id}")),
+                };
+                let mut connections = inst
+                    .connections()
+                    .iter()
+                    .flat_map(|(port, concat)| {
+                        concat.parts().map(move |part| GraphConnection {
+                            port: port.clone(),
+                            net: cell.signal(part.signal()).name.clone(),
+                            range: part.range().map(|range| (range.start(), range.end())),
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                connections.sort_by(|a, b| a.port.cmp(&b.port));
+                GraphInstance {
+                    name: inst.name().clone(),
+                    child,
+                    connections,
+                }
+            })
+            .collect();
+        GraphCell {
+            name: cell.name().clone(),
+            nets,
+            instances,
+        }
+    }
+}
+
+/// Escapes a string for use within a double-quoted Graphviz ID.
+fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+impl Graph {
+    /// Writes this graph in the Graphviz DOT format.
+    ///
+    /// Each cell is drawn as a cluster containing a node for each of its nets and instances.
+    /// Connections are drawn as edges from instances to nets, labeled with the instance port.
+    /// Instances of cells are linked to the cluster of the instantiated cell with dashed edges.
+    pub fn write_dot<W: Write>(&self, out: &mut W) -> std::io::Result<()> {
+        writeln!(out, "digraph scir {{")?;
+        writeln!(out, "  compound=true;")?;
+        writeln!(out, "  rankdir=LR;")?;
+        for (i, cell) in self.cells.iter().enumerate() {
+            let suffix = if self.top.as_ref() == Some(&cell.name) {
+                " (top)"
+            } else if self.roots.contains(&cell.name) {
+                " (root)"
+            } else {
+                ""
+            };
+            writeln!(out, "  subgraph cluster_{i} {{")?;
+            writeln!(out, "    label=\"{}{suffix}\";", dot_escape(&cell.name),)?;
+            // An invisible anchor node allows edges to target the cluster.
+            writeln!(
+                out,
+                "    \"{}\" [shape=point, style=invis];",
+                cell_anchor(i)
+            )?;
+            for net in cell.nets.iter() {
+                let shape = if net.direction.is_some() {
+                    "cds"
+                } else {
+                    "ellipse"
+                };
+                let label = match net.width {
+                    Some(width) => format!("{}[{width}]", dot_escape(&net.name)),
+                    None => dot_escape(&net.name),
+                };
+                writeln!(
+                    out,
+                    "    \"{}\" [label=\"{label}\", shape={shape}];",
+                    net_node(i, &net.name)
+                )?;
+            }
+            for inst in cell.instances.iter() {
+                writeln!(
+                    out,
+                    "    \"{}\" [label=\"{}\\n{}\", shape=box];",
+                    inst_node(i, &inst.name),
+                    dot_escape(&inst.name),
+                    dot_escape(inst.child.name())
+                )?;
+                for conn in inst.connections.iter() {
+                    let label = match conn.range {
+                        Some((start, end)) => {
+                            format!("{}[{start}:{end}]", dot_escape(&conn.port))
+                        }
+                        None => dot_escape(&conn.port),
+                    };
+                    writeln!(
+                        out,
+                        "    \"{}\" -> \"{}\" [label=\"{label}\", dir=none];",
+                        inst_node(i, &inst.name),
+                        net_node(i, &conn.net),
+                    )?;
+                }
+            }
+            writeln!(out, "  }}")?;
+        }
+        for (i, cell) in self.cells.iter().enumerate() {
+            for inst in cell.instances.iter() {
+                let GraphChild::Cell(child) = &inst.child else {
+                    continue;
+                };
+                if let Some(j) = self.cells.iter().position(|cell| &cell.name == child) {
+                    writeln!(
+                        out,
+                        "  \"{}\" -> \"{}\" [style=dashed, lhead=cluster_{j}];",
+                        inst_node(i, &inst.name),
+                        cell_anchor(j),
+                    )?;
+                }
+            }
+        }
+        writeln!(out, "}}")?;
+        Ok(())
+    }
+
+    /// Returns this graph in the Graphviz DOT format.
+    ///
+    /// See [`Graph::write_dot`] for details.
+    pub fn to_dot(&self) -> String {
+        let mut out = Vec::new();
+        self.write_dot(&mut out)
+            .expect("writing to a vector should not fail");
+        String::from_utf8(out).expect("DOT output should be valid UTF-8")
+    }
+
+    /// Serializes this graph to pretty-printed JSON.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+fn cell_anchor(cell: usize) -> String {
+    format!("c{cell}")
+}
+
+fn net_node(cell: usize, net: &str) -> String {
+    format!("c{cell}/net/{}", dot_escape(net))
+}
+
+fn inst_node(cell: usize, inst: &str) -> String {
+    format!("c{cell}/inst/{}", dot_escape(inst))
+}
@@ -0,0 +1,97 @@
+//! Interning of names that are repeated across a library.
+//!
+//! Names such as `vdd` or the ports of a commonly used cell appear in many cells and instances.
+//! Signal names and the port names used in instance connections are interned by [`intern`]
+//! as they are created or deserialized, so every occurrence shares a single allocation.
+
+use std::collections::HashSet;
+use std::sync::{LazyLock, Mutex, PoisonError};
+
+use arcstr::ArcStr;
+use indexmap::IndexMap;
+use serde::{Deserialize, Deserializer};
+
+/// A set of interned names.
+#[derive(Debug, Clone, Default)]
+pub struct Interner {
+    names: HashSet<ArcStr>,
+}
+
+impl Interner {
+    /// Creates a new, empty interner.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the interned copy of `name`, interning `name` if no such copy exists.
+    pub fn intern(&mut self, name: impl Into<ArcStr>) -> ArcStr {
+        let name = name.into();
+        if let Some(interned) = self.names.get(&name) {
+            return interned.clone();
+        }
+        self.names.insert(name.clone());
+        name
+    }
+
+    /// Returns the interned copy of `name`, if it exists.
+    pub fn get(&self, name: &str) -> Option<&ArcStr> {
+        self.names.get(name)
+    }
+
+    /// The number of interned names.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Returns `true` if no names have been interned.
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Removes names that are no longer used outside of this interner.
+    fn prune(&mut self) {
+        self.names
+            .retain(|name| ArcStr::strong_count(name).is_none_or(|count| count > 1));
+    }
+}
+
+/// The number of names the shared interner holds before it is first pruned.
+const MIN_PRUNE_LEN: usize = 1 << 12;
+
+/// The interner shared by all libraries, along with the size at which it is next pruned.
+static SHARED: LazyLock<Mutex<(Interner, usize)>> =
+    LazyLock::new(|| Mutex::new((Interner::new(), MIN_PRUNE_LEN)));
+
+/// Returns the shared copy of `name`.
+///
+/// Names are shared by all libraries in the process. Names that are no longer used are
+/// dropped periodically, so the shared interner stays proportional to the live names.
+pub fn intern(name: impl Into<ArcStr>) -> ArcStr {
+    let mut shared = SHARED.lock().unwrap_or_else(PoisonError::into_inner);
+    let (interner, prune_at) = &mut *shared;
+    let name = interner.intern(name);
+    if interner.len() >= *prune_at {
+        interner.prune();
+        *prune_at = (2 * interner.len()).max(MIN_PRUNE_LEN);
+    }
+    name
+}
+
+/// Deserializes a name, interning it.
+pub(crate) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<ArcStr, D::Error> {
+    ArcStr::deserialize(deserializer).map(intern)
+}
+
+/// Deserializes a map keyed by names, interning the keys.
+pub(crate) fn deserialize_keys<'de, D, M, V>(deserializer: D) -> Result<M, D::Error>
+where
+    D: Deserializer<'de>,
+    M: FromIterator<(ArcStr, V)>,
+    V: Deserialize<'de>,
+{
+    // Deserializing into an `IndexMap` preserves the order of ordered maps.
+    Ok(IndexMap::<ArcStr, V>::deserialize(deserializer)?
+        .into_iter()
+        .map(|(name, value)| (intern(name), value))
+        .collect())
+}
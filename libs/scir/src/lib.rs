@@ -27,25 +27,37 @@
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt::{Display, Formatter};
 use std::ops::{Deref, DerefMut};
+use std::sync::{Mutex, MutexGuard, PoisonError};
 
 use arcstr::ArcStr;
 use diagnostics::IssueSet;
-use drivers::DriverIssue;
-use indexmap::IndexMap;
+use drivers::{DriverConfig, DriverIssue, WaivedIssue};
+use indexmap::{IndexMap, IndexSet};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use tracing::{span, Level};
 use uniquify::Names;
 
-use crate::schema::{FromSchema, NoSchema, NoSchemaError, Schema};
-use crate::validation::ValidatorIssue;
+use crate::intern::Interner;
+use crate::schema::{ConversionContext, FromSchema, NoSchema, NoSchemaError, Schema, SupportItems};
+pub use crate::source::SourceInfo;
+use crate::store::{InstanceStore, SparseMap};
+use crate::validation::{ValidationCache, ValidatorIssue};
 pub use slice::{Concat, IndexOwned, NamedSlice, NamedSliceOne, Slice, SliceOne, SliceRange};
 
+pub mod array;
+pub mod connectivity;
 pub mod drivers;
+pub mod graph;
+pub mod intern;
+pub mod mapping;
 pub mod merge;
 pub mod netlist;
+pub mod query;
 pub mod schema;
 mod slice;
+pub mod source;
+mod store;
 pub mod validation;
 
 #[cfg(test)]
@@ -82,6 +94,62 @@ impl Display for ParamValue {
     }
 }
 
+/// A parameter declared by a [`Cell`].
+///
+/// Parameters allow near-identical cells to share a single definition,
+/// with each [`Instance`] assigning its own values.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Param {
+    /// The value of the parameter if an instance does not assign one.
+    pub default: ParamValue,
+}
+
+impl Param {
+    /// Creates a new parameter with the given default value.
+    pub fn new(default: impl Into<ParamValue>) -> Self {
+        Self {
+            default: default.into(),
+        }
+    }
+}
+
+/// A parameter value assigned to an [`Instance`] of a parameterized cell.
+#[enumify::enumify(no_as_ref, no_as_mut)]
+#[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Expr {
+    /// A literal value.
+    Value(ParamValue),
+    /// The value of the named parameter of the cell containing the instance.
+    Param(ArcStr),
+}
+
+impl From<ParamValue> for Expr {
+    fn from(value: ParamValue) -> Self {
+        Self::Value(value)
+    }
+}
+
+impl From<ArcStr> for Expr {
+    fn from(value: ArcStr) -> Self {
+        Self::Value(value.into())
+    }
+}
+
+impl From<Decimal> for Expr {
+    fn from(value: Decimal) -> Self {
+        Self::Value(value.into())
+    }
+}
+
+impl Display for Expr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expr::Value(value) => write!(f, "{}", value),
+            Expr::Param(param) => write!(f, "{}", param),
+        }
+    }
+}
+
 /// An opaque signal identifier.
 ///
 /// A signal ID created in the context of one cell must
@@ -463,6 +531,11 @@ impl Display for ChildId {
 }
 
 /// A library of SCIR cells with schema `S`.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "S::Primitive: Serialize",
+    deserialize = "S::Primitive: Deserialize<'de>"
+))]
 pub struct LibraryBuilder<S: Schema + ?Sized = NoSchema> {
     /// The current cell ID counter.
     ///
@@ -492,6 +565,31 @@ pub struct LibraryBuilder<S: Schema + ?Sized = NoSchema> {
 
     /// The ID of the top cell, if there is one.
     top: Option<CellId>,
+
+    /// Additional root cells of the library, in the order in which they were added.
+    ///
+    /// See [`LibraryBuilder::add_root`].
+    roots: IndexSet<CellId>,
+
+    /// Whether port direction contradictions are treated as errors.
+    ///
+    /// See [`LibraryBuilder::set_strict_directions`].
+    strict_directions: bool,
+
+    /// The configuration of driver analysis.
+    ///
+    /// See [`LibraryBuilder::set_driver_config`].
+    #[serde(default)]
+    driver_config: DriverConfig,
+
+    /// The global nets declared in the library.
+    ///
+    /// See [`LibraryBuilder::add_global`].
+    globals: IndexSet<ArcStr>,
+
+    /// Validation results cached between calls to [`LibraryBuilder::validate`].
+    #[serde(skip)]
+    validation: Mutex<ValidationCache>,
 }
 
 impl<S: Schema + ?Sized> Default for LibraryBuilder<S> {
@@ -504,6 +602,11 @@ impl<S: Schema + ?Sized> Default for LibraryBuilder<S> {
             name_map: HashMap::new(),
             names: Names::new(),
             top: None,
+            roots: IndexSet::new(),
+            strict_directions: false,
+            driver_config: DriverConfig::default(),
+            globals: IndexSet::new(),
+            validation: Mutex::default(),
         }
     }
 }
@@ -518,6 +621,11 @@ impl<S: Schema<Primitive = impl Clone> + ?Sized> Clone for LibraryBuilder<S> {
             names: self.names.clone(),
             primitives: self.primitives.clone(),
             top: self.top,
+            roots: self.roots.clone(),
+            strict_directions: self.strict_directions,
+            driver_config: self.driver_config.clone(),
+            globals: self.globals.clone(),
+            validation: Mutex::new(self.lock_validation_cache().clone()),
         }
     }
 }
@@ -532,6 +640,8 @@ impl<S: Schema<Primitive = impl std::fmt::Debug> + ?Sized> std::fmt::Debug for L
         let _ = builder.field("names", &self.names);
         let _ = builder.field("primitives", &self.primitives);
         let _ = builder.field("top", &self.top);
+        let _ = builder.field("roots", &self.roots);
+        let _ = builder.field("globals", &self.globals);
         builder.finish()
     }
 }
@@ -559,6 +669,27 @@ where
     }
 }
 
+impl<S: Schema + ?Sized> Serialize for Library<S>
+where
+    S::Primitive: Serialize,
+{
+    fn serialize<Ser: serde::Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de, S: Schema + ?Sized> Deserialize<'de> for Library<S>
+where
+    S::Primitive: Deserialize<'de>,
+{
+    /// Deserializes a library, validating it as in [`LibraryBuilder::build`].
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        LibraryBuilder::deserialize(deserializer)?
+            .build()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
 impl<S: Schema + ?Sized> Deref for Library<S> {
     type Target = LibraryBuilder<S>;
     fn deref(&self) -> &Self::Target {
@@ -605,6 +736,10 @@ pub struct Issues {
     pub correctness: IssueSet<ValidatorIssue>,
     /// Driver connectivity issues.
     pub drivers: IssueSet<DriverIssue>,
+    /// Driver connectivity issues suppressed by [waivers](drivers::Waiver).
+    ///
+    /// Waived issues are not counted as errors or warnings.
+    pub waived: Vec<WaivedIssue>,
 }
 
 impl Display for Issues {
@@ -618,6 +753,12 @@ impl Display for Issues {
         if !self.drivers.is_empty() {
             writeln!(f, "driver issues:\n{}", self.drivers)?;
         }
+        if !self.waived.is_empty() {
+            writeln!(f, "waived driver issues:")?;
+            for issue in self.waived.iter() {
+                writeln!(f, "{issue}")?;
+            }
+        }
         Ok(())
     }
 }
@@ -636,6 +777,19 @@ impl Issues {
     }
 }
 
+/// A policy for ordering the ports of a cell.
+///
+/// See [`Cell::sort_ports`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum PortOrder {
+    /// Ports are sorted alphabetically by name.
+    Alphabetical,
+    /// Single-bit ports are listed before bus ports.
+    ///
+    /// Ports within each group keep their relative order.
+    BusGrouped,
+}
+
 /// Port directions.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Default, Serialize, Deserialize)]
 pub enum Direction {
@@ -719,6 +873,9 @@ pub struct SignalInfo {
     pub id: SignalId,
 
     /// The name of this signal.
+    ///
+    /// Signal names are [interned](intern::intern).
+    #[serde(deserialize_with = "intern::deserialize")]
     pub name: ArcStr,
 
     /// The width of this signal, if this signal is a bus.
@@ -731,6 +888,18 @@ pub struct SignalInfo {
     /// The contained `usize` represents the index at which the port
     /// corresponding to this signal starts.
     pub port: Option<usize>,
+
+    /// Whether this signal refers to a library-level global net.
+    ///
+    /// See [`LibraryBuilder::add_global`].
+    #[serde(default)]
+    pub global: bool,
+
+    /// The generator code that created this signal, if known.
+    ///
+    /// See [`Cell::set_signal_source_info`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_info: Option<SourceInfo>,
 }
 
 impl SignalInfo {
@@ -744,6 +913,11 @@ impl SignalInfo {
     pub fn is_port(&self) -> bool {
         self.port.is_some()
     }
+
+    /// Returns `true` if this signal refers to a global net.
+    pub fn is_global(&self) -> bool {
+        self.global
+    }
 }
 
 /// An instance of a child cell placed inside a parent cell.
@@ -759,7 +933,25 @@ pub struct Instance {
     ///
     /// The ports are the ports of the **child** cell.
     /// The connected signals are signals of the **parent** cell.
+    /// Port names are [interned](intern::intern).
+    #[serde(deserialize_with = "intern::deserialize_keys")]
     connections: HashMap<ArcStr, Concat>,
+    /// Tool-specific attributes of this instance.
+    ///
+    /// See [`Instance::set_attribute`].
+    #[serde(default)]
+    attributes: SparseMap<ArcStr, ArcStr>,
+    /// Values assigned to the parameters of the child cell.
+    ///
+    /// See [`Instance::set_param`].
+    #[serde(default)]
+    params: SparseMap<ArcStr, Expr>,
+    /// The generator code that created this instance.
+    ///
+    /// Boxed since most instances are not annotated.
+    /// See [`Instance::set_source_info`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source_info: Option<Box<SourceInfo>>,
 }
 
 /// The ID of an instance's child.
@@ -795,21 +987,48 @@ pub struct Cell {
     signal_id: u64,
     port_idx: usize,
     pub(crate) name: ArcStr,
+    #[serde(deserialize_with = "intern::deserialize_keys")]
     pub(crate) ports: IndexMap<ArcStr, Port>,
     pub(crate) signals: HashMap<SignalId, SignalInfo>,
     /// A map of instance name to instance ID.
     ///
     /// Signal names are only guaranteed to be unique in a validated [`Library`].
+    #[serde(deserialize_with = "intern::deserialize_keys")]
     signal_name_map: HashMap<ArcStr, SignalId>,
     /// The last instance ID assigned.
     ///
     /// Initialized to 0 upon cell creation.
     instance_id: u64,
-    pub(crate) instances: IndexMap<InstanceId, Instance>,
+    pub(crate) instances: InstanceStore,
     /// A map of instance name to instance ID.
     ///
     /// Instance names are only guaranteed to be unique in a validated [`Library`].
     instance_name_map: HashMap<ArcStr, InstanceId>,
+    /// Tool-specific attributes of this cell.
+    ///
+    /// See [`Cell::set_attribute`].
+    #[serde(default)]
+    attributes: IndexMap<ArcStr, ArcStr>,
+    /// Comments describing this cell.
+    ///
+    /// See [`Cell::add_comment`].
+    #[serde(default)]
+    comments: Vec<ArcStr>,
+    /// Whether this cell must be kept intact by library passes.
+    ///
+    /// See [`Cell::set_preserve`].
+    #[serde(default)]
+    preserve: bool,
+    /// The parameters declared by this cell.
+    ///
+    /// See [`Cell::add_param`].
+    #[serde(default)]
+    params: IndexMap<ArcStr, Param>,
+    /// The generator code that created this cell.
+    ///
+    /// See [`Cell::set_source_info`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source_info: Option<SourceInfo>,
 }
 
 /// Metadata associated with the conversion from a SCIR library to a netlist.
@@ -817,6 +1036,8 @@ pub struct Cell {
 pub struct NetlistLibConversion {
     /// Conversion metadata for each cell in the SCIR library.
     pub cells: HashMap<CellId, NetlistCellConversion>,
+    /// Netlisted names shared between cells.
+    pub names: Interner,
 }
 
 impl NetlistLibConversion {
@@ -824,6 +1045,17 @@ impl NetlistLibConversion {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Adds the conversion metadata of a cell, interning the netlisted names it contains.
+    ///
+    /// Netlisters typically derive instance and signal names from those of the SCIR library,
+    /// so the same names recur across many cells.
+    pub fn add_cell(&mut self, id: CellId, mut conv: NetlistCellConversion) {
+        for name in conv.instances.values_mut().chain(conv.signals.values_mut()) {
+            *name = self.names.intern(name.clone());
+        }
+        self.cells.insert(id, conv);
+    }
 }
 
 /// Metadata associated with the conversion from a SCIR cell to a netlisted subcircuit.
@@ -831,6 +1063,10 @@ impl NetlistLibConversion {
 pub struct NetlistCellConversion {
     /// The netlisted names of SCIR instances.
     pub instances: HashMap<InstanceId, ArcStr>,
+    /// The netlisted names of SCIR signals that were renamed during netlisting.
+    ///
+    /// Signals that are not present in this map are netlisted using their SCIR names.
+    pub signals: HashMap<SignalId, ArcStr>,
 }
 
 impl NetlistCellConversion {
@@ -851,6 +1087,7 @@ impl<S: Schema + ?Sized> LibraryBuilder<S> {
     /// Returns the ID of the newly added cell.
     pub fn add_cell(&mut self, cell: Cell) -> CellId {
         let id = self.alloc_cell_id();
+        self.validation_cache().invalidate(id);
         self.name_map.insert(cell.name.clone(), id);
         self.names.reserve_name(id, cell.name.clone());
         self.cells.insert(id, cell);
@@ -862,6 +1099,7 @@ impl<S: Schema + ?Sized> LibraryBuilder<S> {
     /// Returns the ID of the newly added cell. May rename the cell if the name is already taken.
     pub fn merge_cell(&mut self, mut cell: Cell) -> CellId {
         let id = self.alloc_cell_id();
+        self.validation_cache().invalidate(id);
         let n_name = self.names.assign_name(id, &cell.name);
         cell.name = n_name;
         self.name_map.insert(cell.name.clone(), id);
@@ -869,6 +1107,30 @@ impl<S: Schema + ?Sized> LibraryBuilder<S> {
         id
     }
 
+    /// Returns the validation cache, discarding its contents if validation panicked.
+    pub(crate) fn validation_cache(&mut self) -> &mut ValidationCache {
+        if self.validation.is_poisoned() {
+            self.validation.clear_poison();
+            *self
+                .validation
+                .get_mut()
+                .unwrap_or_else(PoisonError::into_inner) = ValidationCache::default();
+        }
+        self.validation
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Locks the validation cache, discarding its contents if validation panicked.
+    pub(crate) fn lock_validation_cache(&self) -> MutexGuard<'_, ValidationCache> {
+        self.validation.lock().unwrap_or_else(|err| {
+            let mut cache = err.into_inner();
+            *cache = ValidationCache::default();
+            self.validation.clear_poison();
+            cache
+        })
+    }
+
     #[inline]
     pub(crate) fn alloc_cell_id(&mut self) -> CellId {
         self.cell_id += 1;
@@ -890,6 +1152,7 @@ impl<S: Schema + ?Sized> LibraryBuilder<S> {
     pub(crate) fn add_cell_with_id(&mut self, id: impl Into<CellId>, cell: Cell) {
         let id = id.into();
         assert!(!self.cells.contains_key(&id));
+        self.validation_cache().invalidate(id);
         self.cell_id = std::cmp::max(id.0, self.cell_id);
         self.name_map.insert(cell.name.clone(), id);
         self.names.reserve_name(id, cell.name.clone());
@@ -909,6 +1172,7 @@ impl<S: Schema + ?Sized> LibraryBuilder<S> {
     pub fn overwrite_cell_with_id(&mut self, id: impl Into<CellId>, cell: Cell) {
         let id = id.into();
         assert!(self.cells.contains_key(&id));
+        self.validation_cache().invalidate(id);
         self.cell_id = std::cmp::max(id.0, self.cell_id);
         self.name_map.insert(cell.name.clone(), id);
         self.cells.insert(id, cell);
@@ -919,6 +1183,7 @@ impl<S: Schema + ?Sized> LibraryBuilder<S> {
     /// Returns the ID of the newly added primitive.
     pub fn add_primitive(&mut self, primitive: S::Primitive) -> PrimitiveId {
         let id = self.alloc_primitive_id();
+        self.validation_cache().invalidate_all();
         self.primitives.insert(id, primitive);
         id
     }
@@ -948,6 +1213,7 @@ impl<S: Schema + ?Sized> LibraryBuilder<S> {
     ) {
         let id = id.into();
         assert!(!self.primitives.contains_key(&id));
+        self.validation_cache().invalidate_all();
         self.primitive_id = std::cmp::max(id.0, self.primitive_id);
         self.primitives.insert(id, primitive);
     }
@@ -969,6 +1235,7 @@ impl<S: Schema + ?Sized> LibraryBuilder<S> {
     ) {
         let id = id.into();
         assert!(self.primitives.contains_key(&id));
+        self.validation_cache().invalidate_all();
         self.primitive_id = std::cmp::max(id.0, self.primitive_id);
         self.primitives.insert(id, primitive);
     }
@@ -978,6 +1245,58 @@ impl<S: Schema + ?Sized> LibraryBuilder<S> {
         self.top = Some(cell);
     }
 
+    /// Designates the given cell as an additional root of the library.
+    ///
+    /// Roots are cells that are not necessarily instantiated by any other cell,
+    /// such as a design under test and the testbenches that exercise it.
+    /// Netlisters may treat each root differently (e.g. inlining testbenches).
+    pub fn add_root(&mut self, cell: CellId) {
+        self.roots.insert(cell);
+    }
+
+    /// Sets whether port direction contradictions are treated as errors.
+    ///
+    /// By default, ports whose connectivity contradicts their declared
+    /// [`Direction`] are reported as warnings during driver analysis.
+    /// In strict mode, they are reported as errors, causing
+    /// [`build`](LibraryBuilder::build) to fail.
+    pub fn set_strict_directions(&mut self, strict: bool) {
+        self.strict_directions = strict;
+    }
+
+    /// Sets the configuration of driver analysis.
+    ///
+    /// Allows driver checks to be relaxed or escalated, and individual issues to be waived.
+    pub fn set_driver_config(&mut self, config: DriverConfig) {
+        self.driver_config = config;
+    }
+
+    /// The configuration of driver analysis.
+    #[inline]
+    pub fn driver_config(&self) -> &DriverConfig {
+        &self.driver_config
+    }
+
+    /// Declares a global net with the given name.
+    ///
+    /// Cells can reference global nets using [`Cell::add_global`]
+    /// without routing them through ports. Global nets are declared
+    /// in exported netlists and are never renamed during netlisting.
+    pub fn add_global(&mut self, name: impl Into<ArcStr>) {
+        self.validation_cache().invalidate_all();
+        self.globals.insert(name.into());
+    }
+
+    /// Iterates over the global nets declared in the library.
+    pub fn globals(&self) -> impl Iterator<Item = &ArcStr> {
+        self.globals.iter()
+    }
+
+    /// Returns `true` if a global net with the given name has been declared.
+    pub fn is_global(&self, name: &str) -> bool {
+        self.globals.contains(name)
+    }
+
     /// The ID of the top-level cell, if there is one.
     #[inline]
     pub fn top_cell(&self) -> Option<CellId> {
@@ -989,6 +1308,24 @@ impl<S: Schema + ?Sized> LibraryBuilder<S> {
         self.top_cell().map(|c| c == cell).unwrap_or_default()
     }
 
+    /// Iterates over the root cells of the library.
+    ///
+    /// The top cell, if any, is yielded first, followed by the roots added using
+    /// [`add_root`](LibraryBuilder::add_root) in the order in which they were added.
+    pub fn roots(&self) -> impl Iterator<Item = CellId> + '_ {
+        self.top.into_iter().chain(
+            self.roots
+                .iter()
+                .copied()
+                .filter(move |&id| Some(id) != self.top),
+        )
+    }
+
+    /// Returns `true` if the given cell is the top cell or an additional root.
+    pub fn is_root(&self, cell: CellId) -> bool {
+        self.is_top(cell) || self.roots.contains(&cell)
+    }
+
     /// Gets the cell with the given ID.
     ///
     /// # Panics
@@ -1243,13 +1580,29 @@ impl<S: Schema + ?Sized> LibraryBuilder<S> {
         let SignalPath { instances, tail } = path.0;
         let annotated_path = self.annotate_instance_path(instances);
 
-        let bot = self.cell(annotated_path.bot().unwrap());
+        let bot_id = annotated_path.bot().unwrap();
+        let bot = self.cell(bot_id);
 
         let (name, index) = match &tail {
-            SignalPathTail::Id(id) => (&bot.signal(id.signal()).name, id.index()),
+            SignalPathTail::Id(id) => (
+                conv.and_then(|conv| conv.cells.get(&bot_id)?.signals.get(&id.signal()))
+                    .unwrap_or(&bot.signal(id.signal()).name),
+                id.index(),
+            ),
             SignalPathTail::Name(name) => (name.signal(), name.index()),
         };
 
+        // Global nets are shared by all cells, so they are addressed without an instance path.
+        let global = match &tail {
+            SignalPathTail::Id(id) => bot.signal(id.signal()).is_global(),
+            SignalPathTail::Name(name) => bot
+                .try_signal_named(name.signal())
+                .is_some_and(SignalInfo::is_global),
+        };
+        if global {
+            return NamedPath(vec![index_fmt(name, index)]);
+        }
+
         let mut name_path = self.convert_annotated_instance_path(conv, annotated_path);
         name_path.push(index_fmt(name, index));
 
@@ -1285,6 +1638,110 @@ impl<S: Schema + ?Sized> LibraryBuilder<S> {
         self.convert_slice_one_path_inner(Some(conv), path, index_fmt)
     }
 
+    /// Finds the instance with the given netlisted name in the given cell.
+    ///
+    /// Names are compared exactly if possible, falling back to a case-insensitive
+    /// comparison for simulators that do not preserve case.
+    fn find_netlisted_instance(
+        &self,
+        conv: &NetlistLibConversion,
+        cell: CellId,
+        name: &str,
+    ) -> Option<InstanceId> {
+        let instances = &conv.cells.get(&cell)?.instances;
+        instances
+            .iter()
+            .find(|(_, netlisted)| netlisted.as_str() == name)
+            .or_else(|| {
+                instances
+                    .iter()
+                    .find(|(_, netlisted)| netlisted.eq_ignore_ascii_case(name))
+            })
+            .map(|(id, _)| *id)
+    }
+
+    /// Returns the instance path and the ID of the bottom cell, if the bottom
+    /// instance is not a primitive.
+    fn find_netlisted_instance_path_inner(
+        &self,
+        conv: &NetlistLibConversion,
+        names: &[&str],
+    ) -> Option<(InstancePath, Option<CellId>)> {
+        let top = self.top_cell()?;
+        let mut path = InstancePath::new(top);
+        let mut cell = Some(top);
+        for name in names {
+            let parent = cell?;
+            let id = self.find_netlisted_instance(conv, parent, name)?;
+            path.push(id);
+            cell = match self.cell(parent).instance(id).child() {
+                ChildId::Cell(child) => Some(child),
+                ChildId::Primitive(_) => None,
+            };
+        }
+        Some((path, cell))
+    }
+
+    /// Finds the [`InstancePath`] corresponding to a path of netlisted instance names,
+    /// starting from the top cell.
+    ///
+    /// Uses the provided `conv` to match the names assigned to instances during netlisting.
+    /// This is the inverse of [`convert_instance_path_with_conv`](LibraryBuilder::convert_instance_path_with_conv).
+    ///
+    /// Returns [`None`] if there is no top cell or if no matching instance exists.
+    pub fn find_netlisted_instance_path(
+        &self,
+        conv: &NetlistLibConversion,
+        names: &[&str],
+    ) -> Option<InstancePath> {
+        self.find_netlisted_instance_path_inner(conv, names)
+            .map(|(path, _)| path)
+    }
+
+    /// Finds the [`SliceOnePath`] corresponding to a netlisted signal.
+    ///
+    /// `instances` are the netlisted names of the instances containing the signal,
+    /// starting from the top cell. `signal` and `index` identify the signal
+    /// within the bottom cell.
+    ///
+    /// Returns [`None`] if there is no top cell or if no matching signal exists.
+    pub fn find_netlisted_slice_one_path(
+        &self,
+        conv: &NetlistLibConversion,
+        instances: &[&str],
+        signal: &str,
+        index: Option<usize>,
+    ) -> Option<SliceOnePath> {
+        let (path, cell_id) = self.find_netlisted_instance_path_inner(conv, instances)?;
+        let cell_id = cell_id?;
+        let cell = self.cell(cell_id);
+        let renamed = conv.cells.get(&cell_id).map(|conv| &conv.signals);
+        let signals = cell
+            .signals()
+            .map(|(id, info)| {
+                let name = renamed
+                    .and_then(|renamed| renamed.get(&id))
+                    .unwrap_or(&info.name);
+                (name, info)
+            })
+            .collect::<Vec<_>>();
+        let (_, info) = signals
+            .iter()
+            .find(|(name, _)| name.as_str() == signal)
+            .or_else(|| {
+                signals
+                    .iter()
+                    .find(|(name, _)| name.eq_ignore_ascii_case(signal))
+            })?;
+        let slice = info.slice();
+        let tail = match (index, info.width) {
+            (None, None) => slice.slice_one()?,
+            (Some(index), Some(width)) if index < width => slice.index(index),
+            _ => return None,
+        };
+        Some(path.slice_one(tail))
+    }
+
     /// Returns a simplified path to the provided node, bubbling up through IOs.
     ///
     /// # Panics
@@ -1357,6 +1814,63 @@ impl<S: Schema + ?Sized> LibraryBuilder<S> {
         })
     }
 
+    /// Returns every path to the provided node.
+    ///
+    /// A node is shared by all of the cell ports it is connected to, so the returned paths
+    /// consist of the [simplified](LibraryBuilder::simplify_path) path to the node followed by
+    /// the paths to every port connected to it, at any depth below the simplified path.
+    /// Ports of primitives are not included.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the provided path does not exist within the SCIR library.
+    pub fn merged_paths(&self, path: SliceOnePath) -> Vec<SliceOnePath> {
+        let root = self.simplify_path(path);
+        let cell = self.cell(
+            self.annotate_instance_path(root.instances().clone())
+                .bot()
+                .expect("path does not exist within the SCIR library"),
+        );
+        let slice = match root.tail() {
+            SignalPathTail::Id(slice) => *slice,
+            SignalPathTail::Name(name) => {
+                SliceOne::new(cell.signal_named(name.signal()).id, name.index())
+            }
+        };
+        let mut paths = Vec::new();
+        self.push_merged_paths(cell, root.0.instances, slice, &mut paths);
+        paths
+    }
+
+    /// Pushes the path to `slice` in `cell` and the paths to all ports connected to it
+    /// below `cell` onto `paths`.
+    fn push_merged_paths(
+        &self,
+        cell: &Cell,
+        instances: InstancePath,
+        slice: SliceOne,
+        paths: &mut Vec<SliceOnePath>,
+    ) {
+        paths.push(instances.clone().slice_one(slice));
+        for (id, inst) in cell.instances() {
+            let ChildId::Cell(child_id) = inst.child() else {
+                continue;
+            };
+            let child = self.cell(child_id);
+            for (port, conn) in inst.connections() {
+                for i in 0..conn.width() {
+                    if conn.index(i) == slice {
+                        let info = child.signal_named(port);
+                        let mut instances = instances.clone();
+                        instances.push(id);
+                        let slice = SliceOne::new(info.id, info.width.map(|_| i));
+                        self.push_merged_paths(child, instances, slice, paths);
+                    }
+                }
+            }
+        }
+    }
+
     /// Validate and construct a SCIR [`Library`].
     ///
     /// If errors are encountered during validation,
@@ -1384,10 +1898,11 @@ impl<S: Schema + ?Sized> LibraryBuilder<S> {
     ///
     pub fn try_build(self) -> Result<(Library<S>, Issues), Issues> {
         let correctness = self.validate();
-        let drivers = self.validate_drivers();
+        let (drivers, waived) = self.validate_drivers_with_waivers();
         let issues = Issues {
             correctness,
             drivers,
+            waived,
         };
         if issues.has_error() {
             Err(issues)
@@ -1396,10 +1911,19 @@ impl<S: Schema + ?Sized> LibraryBuilder<S> {
         }
     }
 
+    #[allow(clippy::type_complexity)]
     fn convert_inner<C: Schema + ?Sized, E>(
         self,
-        convert_primitive: fn(<S as Schema>::Primitive) -> Result<<C as Schema>::Primitive, E>,
-        convert_instance: fn(&mut Instance, &<S as Schema>::Primitive) -> Result<(), E>,
+        convert_primitive: fn(
+            &mut ConversionContext<'_, C>,
+            PrimitiveId,
+            <S as Schema>::Primitive,
+        ) -> Result<<C as Schema>::Primitive, E>,
+        convert_instance: fn(
+            &mut ConversionContext<'_, C>,
+            &mut Instance,
+            &<S as Schema>::Primitive,
+        ) -> Result<(), E>,
     ) -> Result<LibraryBuilder<C>, E> {
         let LibraryBuilder {
             cell_id,
@@ -1408,47 +1932,83 @@ impl<S: Schema + ?Sized> LibraryBuilder<S> {
             name_map,
             primitives,
             top,
+            roots,
             names,
+            strict_directions,
+            driver_config,
+            globals,
+            validation: _,
         } = self;
 
-        for (_, cell) in cells.iter_mut() {
-            for (_, instance) in cell.instances.iter_mut() {
+        let mut support = SupportItems::new(cell_id, primitive_id);
+        for i in 0..cells.len() {
+            // Take the instances out of the cell so that conversions can look up other cells.
+            let mut instances = std::mem::take(&mut cells[i].instances);
+            let mut ctx = ConversionContext::new(&cells, &name_map, &mut support);
+            for (_, instance) in instances.iter_mut() {
                 if let ChildId::Primitive(p) = instance.child {
                     if let Some(primitive) = primitives.get(&p) {
-                        convert_instance(instance, primitive)?;
+                        convert_instance(&mut ctx, instance, primitive)?;
                     }
                 }
             }
+            cells[i].instances = instances;
         }
 
-        Ok(LibraryBuilder {
+        let mut ctx = ConversionContext::new(&cells, &name_map, &mut support);
+        let primitives = primitives
+            .into_iter()
+            .map(|(k, v)| Ok((k, convert_primitive(&mut ctx, k, v)?)))
+            .collect::<Result<_, _>>()?;
+
+        let SupportItems {
+            cell_id,
+            primitive_id,
+            cells: support_cells,
+            primitives: support_primitives,
+            ..
+        } = support;
+        let mut lib = LibraryBuilder {
             cell_id,
             primitive_id,
             cells,
             name_map,
             names,
-            primitives: primitives
-                .into_iter()
-                .map(|(k, v)| Ok((k, convert_primitive(v)?)))
-                .collect::<Result<_, _>>()?,
+            primitives,
             top,
-        })
+            roots,
+            strict_directions,
+            driver_config,
+            globals,
+            validation: Mutex::default(),
+        };
+        for (id, primitive) in support_primitives {
+            lib.add_primitive_with_id(id, primitive);
+        }
+        for (id, cell) in support_cells {
+            lib.add_cell_with_id(id, cell);
+        }
+        Ok(lib)
     }
 
     /// Converts a [`LibraryBuilder<S>`] to a [`LibraryBuilder<NoSchema>`], throwing an error if there
     /// are any primitives.
     pub fn drop_schema(self) -> Result<LibraryBuilder<NoSchema>, NoSchemaError> {
-        self.convert_inner(|_| Err(NoSchemaError), |_, _| Err(NoSchemaError))
+        self.convert_inner(|_, _, _| Err(NoSchemaError), |_, _, _| Err(NoSchemaError))
     }
 
     /// Converts a [`LibraryBuilder<S>`] into a [`LibraryBuilder<C>`].
     ///
+    /// Instances of primitives are converted first using [`FromSchema::convert_instance_in`],
+    /// followed by the primitives themselves using [`FromSchema::convert_primitive_in`].
+    /// Support cells and primitives added by the conversion are then added to the library.
+    ///
     /// Instances associated with non-existent primitives will remain unchanged.
     pub fn convert_schema<C>(self) -> Result<LibraryBuilder<C>, C::Error>
     where
         C: FromSchema<S> + ?Sized,
     {
-        self.convert_inner(C::convert_primitive, C::convert_instance)
+        self.convert_inner(C::convert_primitive_in, C::convert_instance_in)
     }
 }
 
@@ -1487,12 +2047,136 @@ impl Cell {
             signals: HashMap::new(),
             signal_name_map: HashMap::new(),
             instance_id: 0,
-            instances: IndexMap::new(),
+            instances: InstanceStore::default(),
             instance_name_map: HashMap::new(),
+            attributes: IndexMap::new(),
+            comments: Vec::new(),
+            preserve: false,
+            params: IndexMap::new(),
+            source_info: None,
         }
     }
 
+    /// Declares a parameter of this cell.
+    ///
+    /// Instances of this cell may assign the parameter using [`Instance::set_param`],
+    /// and instances within this cell may refer to it using [`Expr::Param`].
+    /// Declaring an existing parameter replaces its default value.
+    pub fn add_param(&mut self, name: impl Into<ArcStr>, param: Param) {
+        self.params.insert(name.into(), param);
+    }
+
+    /// Gets the parameter of this cell with the given name, if it is declared.
+    pub fn param(&self, name: &str) -> Option<&Param> {
+        self.params.get(name)
+    }
+
+    /// Iterates over the parameters of this cell, in the order in which they were declared.
+    pub fn params(&self) -> impl Iterator<Item = (&ArcStr, &Param)> {
+        self.params.iter()
+    }
+
+    /// Sets whether this cell must be kept intact by library passes.
+    ///
+    /// Preserved cells are never renamed by [`LibraryBuilder::rename_cells`], keep their names
+    /// when merged into another library where possible, and are never pruned by
+    /// [`LibraryBuilder::merge_cells`]. Netlisters should also keep their contents as is
+    /// (e.g. by not renaming instances). This is required when a cell must match a
+    /// hand-qualified LVS or PEX macro.
+    pub fn set_preserve(&mut self, preserve: bool) {
+        self.preserve = preserve;
+    }
+
+    /// Sets whether to store the instances of this cell compactly.
+    ///
+    /// Compact storage keeps instances in arrays sorted by ID rather than in a hash map,
+    /// reducing per-instance memory at the cost of logarithmic rather than constant time
+    /// lookups by ID. Useful for cells with millions of instances, such as memory arrays.
+    /// Existing instances are kept. Deserialized cells do not use compact storage.
+    ///
+    /// Disabled by default.
+    pub fn set_compact_instances(&mut self, compact: bool) {
+        self.instances.set_compact(compact);
+    }
+
+    /// Returns `true` if the instances of this cell are stored compactly.
+    ///
+    /// See [`Cell::set_compact_instances`].
+    pub fn has_compact_instances(&self) -> bool {
+        self.instances.is_compact()
+    }
+
+    /// Returns `true` if this cell must be kept intact by library passes.
+    ///
+    /// See [`Cell::set_preserve`].
+    pub fn is_preserved(&self) -> bool {
+        self.preserve
+    }
+
+    /// Sets the attribute `key` of this cell to `value`.
+    ///
+    /// Attributes are opaque to SCIR and are preserved across schema conversions.
+    /// Netlisters may use them to emit tool-specific annotations (e.g. LVS directives).
+    /// Setting an existing attribute replaces its value but keeps its original position.
+    pub fn set_attribute(&mut self, key: impl Into<ArcStr>, value: impl Into<ArcStr>) {
+        self.attributes.insert(key.into(), value.into());
+    }
+
+    /// Gets the value of the attribute `key` of this cell, if it is set.
+    pub fn attribute(&self, key: &str) -> Option<&ArcStr> {
+        self.attributes.get(key)
+    }
+
+    /// Removes the attribute `key` of this cell, returning its value if it was set.
+    pub fn remove_attribute(&mut self, key: &str) -> Option<ArcStr> {
+        self.attributes.shift_remove(key)
+    }
+
+    /// Iterates over the attributes of this cell, in the order in which they were set.
+    pub fn attributes(&self) -> impl Iterator<Item = (&ArcStr, &ArcStr)> {
+        self.attributes.iter()
+    }
+
+    /// Adds a comment describing this cell.
+    ///
+    /// Unlike attributes, comments carry no meaning for downstream tools.
+    /// Netlisters write them immediately before the cell's definition.
+    pub fn add_comment(&mut self, comment: impl Into<ArcStr>) {
+        self.comments.push(comment.into());
+    }
+
+    /// Iterates over the comments describing this cell, in the order in which they were added.
+    pub fn comments(&self) -> impl Iterator<Item = &ArcStr> {
+        self.comments.iter()
+    }
+
+    /// Records the generator code that created this cell.
+    ///
+    /// Validation issues and netlisting errors concerning this cell report it.
+    pub fn set_source_info(&mut self, source_info: SourceInfo) {
+        self.source_info = Some(source_info);
+    }
+
+    /// The generator code that created this cell, if known.
+    #[inline]
+    pub fn source_info(&self) -> Option<&SourceInfo> {
+        self.source_info.as_ref()
+    }
+
+    /// Records the generator code that created the given signal.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the signal does not exist.
+    pub fn set_signal_source_info(&mut self, signal: impl Into<SignalId>, source_info: SourceInfo) {
+        self.signals
+            .get_mut(&signal.into())
+            .expect("signal does not exist")
+            .source_info = Some(source_info);
+    }
+
     fn add_signal(&mut self, name: ArcStr, width: Option<usize>) -> SignalId {
+        let name = intern::intern(name);
         self.signal_id += 1;
         let id = SignalId(self.signal_id);
         self.signal_name_map.insert(name.clone(), id);
@@ -1503,11 +2187,23 @@ impl Cell {
                 port: None,
                 name,
                 width,
+                global: false,
+                source_info: None,
             },
         );
         id
     }
 
+    /// References the global net with the given name from this cell.
+    ///
+    /// The global net must be declared using [`LibraryBuilder::add_global`].
+    /// Global nets cannot be exposed as ports.
+    pub fn add_global(&mut self, name: impl Into<ArcStr>) -> SliceOne {
+        let id = self.add_signal(name.into(), None);
+        self.signals.get_mut(&id).unwrap().global = true;
+        SliceOne::new(id, None)
+    }
+
     /// Creates a new 1-bit signal in this cell.
     pub fn add_node(&mut self, name: impl Into<ArcStr>) -> SliceOne {
         let id = self.add_signal(name.into(), None);
@@ -1543,6 +2239,56 @@ impl Cell {
         }
     }
 
+    /// Sets the order of the ports of this cell.
+    ///
+    /// Ports are netlisted in the given order, both in cell definitions and in
+    /// the connections of instances of this cell. Ports that are not listed in
+    /// `order` are placed after the listed ports, keeping their relative order.
+    ///
+    /// By default, ports are ordered in the order in which they were exposed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` contains a name that is not a port of this cell.
+    pub fn set_port_order(&mut self, order: impl IntoIterator<Item = impl AsRef<str>>) {
+        let mut ports = IndexMap::with_capacity(self.ports.len());
+        for name in order {
+            let name = name.as_ref();
+            let (name, port) = self
+                .ports
+                .shift_remove_entry(name)
+                .unwrap_or_else(|| panic!("`{name}` is not a port of cell `{}`", self.name));
+            ports.insert(name, port);
+        }
+        ports.extend(self.ports.drain(..));
+        self.ports = ports;
+        self.reindex_ports();
+    }
+
+    /// Sorts the ports of this cell according to the given policy.
+    ///
+    /// See [`Cell::set_port_order`].
+    pub fn sort_ports(&mut self, order: PortOrder) {
+        match order {
+            PortOrder::Alphabetical => self.ports.sort_keys(),
+            PortOrder::BusGrouped => {
+                let is_bus = |port: &Port| self.signals[&port.signal].width.is_some();
+                self.ports.sort_by(|_, a, _, b| is_bus(a).cmp(&is_bus(b)));
+            }
+        }
+        self.reindex_ports();
+    }
+
+    /// Recomputes the starting index of each port after the ports are reordered.
+    fn reindex_ports(&mut self) {
+        self.port_idx = 0;
+        for port in self.ports.values() {
+            let info = self.signals.get_mut(&port.signal).unwrap();
+            info.port = Some(self.port_idx);
+            self.port_idx += info.width.unwrap_or(1);
+        }
+    }
+
     /// The name of the cell.
     #[inline]
     pub fn name(&self) -> &ArcStr {
@@ -1565,6 +2311,14 @@ impl Cell {
         self.ports.get(name).unwrap()
     }
 
+    /// Get a port of this cell by name.
+    ///
+    /// Returns [`None`] if the provided port does not exist.
+    #[inline]
+    pub fn try_port(&self, name: &str) -> Option<&Port> {
+        self.ports.get(name)
+    }
+
     /// Iterate over the signals of this cell.
     #[inline]
     pub fn signals(&self) -> impl Iterator<Item = (SignalId, &SignalInfo)> {
@@ -1641,6 +2395,19 @@ impl Cell {
         }
     }
 
+    /// Gets a mutable reference to the instance associated with the given path element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no such instance exists.
+    pub fn instance_from_path_element_mut(&mut self, elem: &InstancePathElement) -> &mut Instance {
+        let id = match elem {
+            InstancePathElement::Id(id) => *id,
+            InstancePathElement::Name(name) => *self.instance_name_map.get(name).unwrap(),
+        };
+        self.instances.get_mut(&id).unwrap()
+    }
+
     /// Add the given instance to the cell.
     #[inline]
     pub fn add_instance(&mut self, instance: Instance) -> InstanceId {
@@ -1665,13 +2432,72 @@ impl Instance {
             child: child.into(),
             name: name.into(),
             connections: HashMap::new(),
+            attributes: SparseMap::default(),
+            params: SparseMap::default(),
+            source_info: None,
         }
     }
 
+    /// Assigns `value` to the parameter `name` of the child cell.
+    ///
+    /// The child must be a cell that declares the parameter (see [`Cell::add_param`]).
+    /// Parameters that are not assigned take their default values.
+    pub fn set_param(&mut self, name: impl Into<ArcStr>, value: impl Into<Expr>) {
+        self.params.insert(name.into(), value.into());
+    }
+
+    /// Gets the value assigned to the parameter `name` of the child cell, if any.
+    pub fn param(&self, name: &str) -> Option<&Expr> {
+        self.params.get(name)
+    }
+
+    /// Iterates over the parameter assignments of this instance,
+    /// in the order in which they were first assigned.
+    pub fn params(&self) -> impl Iterator<Item = (&ArcStr, &Expr)> {
+        self.params.iter()
+    }
+
+    /// Sets the attribute `key` of this instance to `value`.
+    ///
+    /// Attributes are opaque to SCIR and are preserved across schema conversions.
+    /// Netlisters may use them to emit tool-specific annotations (e.g. LVS directives).
+    /// Setting an existing attribute replaces its value but keeps its original position.
+    pub fn set_attribute(&mut self, key: impl Into<ArcStr>, value: impl Into<ArcStr>) {
+        self.attributes.insert(key.into(), value.into());
+    }
+
+    /// Gets the value of the attribute `key` of this instance, if it is set.
+    pub fn attribute(&self, key: &str) -> Option<&ArcStr> {
+        self.attributes.get(key)
+    }
+
+    /// Removes the attribute `key` of this instance, returning its value if it was set.
+    pub fn remove_attribute(&mut self, key: &str) -> Option<ArcStr> {
+        self.attributes.shift_remove(key)
+    }
+
+    /// Iterates over the attributes of this instance, in the order in which they were set.
+    pub fn attributes(&self) -> impl Iterator<Item = (&ArcStr, &ArcStr)> {
+        self.attributes.iter()
+    }
+
+    /// Records the generator code that created this instance.
+    ///
+    /// Validation issues and netlisting errors concerning this instance report it.
+    pub fn set_source_info(&mut self, source_info: SourceInfo) {
+        self.source_info = Some(Box::new(source_info));
+    }
+
+    /// The generator code that created this instance, if known.
+    #[inline]
+    pub fn source_info(&self) -> Option<&SourceInfo> {
+        self.source_info.as_deref()
+    }
+
     /// Connect the given port of the child cell to the given node in the parent cell.
     #[inline]
     pub fn connect(&mut self, name: impl Into<ArcStr>, conn: impl Into<Concat>) {
-        self.connections.insert(name.into(), conn.into());
+        self.connections.insert(intern::intern(name), conn.into());
     }
 
     /// The ID of the child cell.
@@ -1682,6 +2508,15 @@ impl Instance {
         self.child
     }
 
+    /// Replaces the child of this instance.
+    ///
+    /// Connections are kept as is, so they must match the ports of the new child.
+    /// Useful for schema conversions that wrap primitives in support cells
+    /// (see [`FromSchema::convert_instance_in`]).
+    pub fn set_child(&mut self, child: impl Into<ChildId>) {
+        self.child = child.into();
+    }
+
     /// The name of this instance.
     ///
     /// This is not necessarily the name of the child cell.
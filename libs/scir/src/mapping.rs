@@ -0,0 +1,254 @@
+//! Table-driven conversions between schemas.
+//!
+//! Conversions between simulator and PDK schemas often only rename primitive kinds,
+//! ports, and parameters. The [`bidirectional_conversion!`](crate::bidirectional_conversion)
+//! macro derives [`FromSchema`](crate::schema::FromSchema) in both directions from a table
+//! of such renames.
+
+use arcstr::ArcStr;
+
+use crate::{Instance, ParamValue};
+
+/// The parts of a primitive that can be renamed by a conversion table.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PrimitiveParts {
+    /// The kind of the primitive, such as the name of a device model or subcircuit.
+    pub kind: ArcStr,
+    /// The ordered ports of the primitive.
+    pub ports: Vec<ArcStr>,
+    /// The parameters of the primitive.
+    pub params: Vec<(ArcStr, ParamValue)>,
+}
+
+/// A primitive that can be converted using a conversion table.
+pub trait TabularPrimitive: Sized {
+    /// The kind of the primitive.
+    ///
+    /// See [`PrimitiveParts::kind`].
+    fn kind(&self) -> &str;
+
+    /// Splits the primitive into its parts.
+    fn into_parts(self) -> PrimitiveParts;
+
+    /// Creates a primitive from its parts.
+    fn from_parts(parts: PrimitiveParts) -> Self;
+}
+
+/// A row of a conversion table, relating a primitive kind of schema `A` to
+/// a primitive kind of schema `B`.
+#[derive(Clone, Copy, Debug)]
+pub struct KindMapping {
+    /// The kind in schema `A`.
+    pub a: &'static str,
+    /// The kind in schema `B`.
+    pub b: &'static str,
+    /// Pairs of corresponding port names in schemas `A` and `B`.
+    ///
+    /// Ports that are not listed keep their names.
+    pub ports: &'static [(&'static str, &'static str)],
+    /// Pairs of corresponding parameter names in schemas `A` and `B`.
+    ///
+    /// Parameters that are not listed keep their names.
+    pub params: &'static [(&'static str, &'static str)],
+}
+
+/// The direction in which a conversion table is applied.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MappingDirection {
+    /// Converts from schema `A` to schema `B`.
+    AToB,
+    /// Converts from schema `B` to schema `A`.
+    BToA,
+}
+
+impl MappingDirection {
+    fn rename<'a>(self, pairs: &'a [(&'a str, &'a str)], name: &str) -> Option<&'a str> {
+        pairs.iter().find_map(|&(a, b)| match self {
+            Self::AToB => (a == name).then_some(b),
+            Self::BToA => (b == name).then_some(a),
+        })
+    }
+
+    fn find(self, table: &[KindMapping], kind: &str) -> Option<&KindMapping> {
+        table.iter().find(|row| match self {
+            Self::AToB => row.a == kind,
+            Self::BToA => row.b == kind,
+        })
+    }
+}
+
+fn rename(direction: MappingDirection, pairs: &[(&str, &str)], name: ArcStr) -> ArcStr {
+    direction
+        .rename(pairs, &name)
+        .map(ArcStr::from)
+        .unwrap_or(name)
+}
+
+/// An error converting a primitive whose kind is not listed in a conversion table.
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+#[error("primitive kind `{0}` is not listed in the conversion table")]
+pub struct UnmappedKindError(pub ArcStr);
+
+/// Converts `primitive` according to `table`.
+///
+/// Returns an error if the kind of the primitive is not listed in the table.
+pub fn convert_primitive<P: TabularPrimitive, Q: TabularPrimitive>(
+    table: &[KindMapping],
+    direction: MappingDirection,
+    primitive: P,
+) -> Result<Q, UnmappedKindError> {
+    let parts = primitive.into_parts();
+    let Some(row) = direction.find(table, &parts.kind) else {
+        return Err(UnmappedKindError(parts.kind));
+    };
+    let kind = match direction {
+        MappingDirection::AToB => row.b,
+        MappingDirection::BToA => row.a,
+    };
+    Ok(Q::from_parts(PrimitiveParts {
+        kind: ArcStr::from(kind),
+        ports: parts
+            .ports
+            .into_iter()
+            .map(|port| rename(direction, row.ports, port))
+            .collect(),
+        params: parts
+            .params
+            .into_iter()
+            .map(|(name, value)| (rename(direction, row.params, name), value))
+            .collect(),
+    }))
+}
+
+/// Renames the connections of an instance of `primitive` according to `table`.
+///
+/// Returns an error if the kind of the primitive is not listed in the table.
+pub fn convert_instance<P: TabularPrimitive>(
+    table: &[KindMapping],
+    direction: MappingDirection,
+    instance: &mut Instance,
+    primitive: &P,
+) -> Result<(), UnmappedKindError> {
+    let row = direction
+        .find(table, primitive.kind())
+        .ok_or_else(|| UnmappedKindError(primitive.kind().into()))?;
+    instance.map_connections(|port| rename(direction, row.ports, port));
+    Ok(())
+}
+
+/// Derives [`FromSchema`](crate::schema::FromSchema) in both directions between two schemas.
+///
+/// Both schemas must be given as identifiers, so import them first.
+///
+/// Without a table, the schemas are converted into one another by converting their
+/// primitives using [`Into`], leaving instances unchanged. This is useful for schemas
+/// that share a primitive type:
+///
+/// ```
+/// # use scir::schema::Schema;
+/// # use arcstr::ArcStr;
+/// pub struct Generic;
+/// pub struct Flavor;
+///
+/// impl Schema for Generic {
+///     type Primitive = ArcStr;
+/// }
+/// impl Schema for Flavor {
+///     type Primitive = ArcStr;
+/// }
+///
+/// scir::bidirectional_conversion!(Generic <=> Flavor);
+/// ```
+///
+/// With a table, both primitive types must implement [`TabularPrimitive`].
+/// Each row relates a primitive kind of the first schema to a kind of the second, along with
+/// the port and parameter names that differ between them:
+///
+/// ```ignore
+/// scir::bidirectional_conversion!(MyPdk <=> MySimulator {
+///     "nfet_01v8" <=> "nch" {
+///         ports: ["d" <=> "D", "g" <=> "G", "s" <=> "S", "b" <=> "B"],
+///         params: ["w" <=> "W", "l" <=> "L"],
+///     },
+///     "res_generic" <=> "resistor" { ports: [], params: ["r" <=> "R"] },
+/// });
+/// ```
+///
+/// Ports and parameters that are not listed keep their names. Converting a primitive whose
+/// kind is not listed fails with an [`UnmappedKindError`], so every kind supported by both
+/// schemas must have a row.
+#[macro_export]
+macro_rules! bidirectional_conversion {
+    ($a:ident <=> $b:ident) => {
+        $crate::bidirectional_conversion!(@into $a, $b);
+        $crate::bidirectional_conversion!(@into $b, $a);
+    };
+    ($a:ident <=> $b:ident {
+        $(
+            $ka:literal <=> $kb:literal {
+                ports: [$($pa:literal <=> $pb:literal),* $(,)?],
+                params: [$($qa:literal <=> $qb:literal),* $(,)?] $(,)?
+            }
+        ),* $(,)?
+    }) => {
+        const _: () = {
+            const TABLE: &[$crate::mapping::KindMapping] = &[$(
+                $crate::mapping::KindMapping {
+                    a: $ka,
+                    b: $kb,
+                    ports: &[$(($pa, $pb)),*],
+                    params: &[$(($qa, $qb)),*],
+                }
+            ),*];
+            $crate::bidirectional_conversion!(@table $a, $b, AToB, TABLE);
+            $crate::bidirectional_conversion!(@table $b, $a, BToA, TABLE);
+        };
+    };
+    (@into $from:ident, $to:ident) => {
+        impl $crate::schema::FromSchema<$from> for $to {
+            type Error = ::std::convert::Infallible;
+
+            fn convert_primitive(
+                primitive: <$from as $crate::schema::Schema>::Primitive,
+            ) -> ::std::result::Result<<Self as $crate::schema::Schema>::Primitive, Self::Error>
+            {
+                ::std::result::Result::Ok(::std::convert::Into::into(primitive))
+            }
+
+            fn convert_instance(
+                _instance: &mut $crate::Instance,
+                _primitive: &<$from as $crate::schema::Schema>::Primitive,
+            ) -> ::std::result::Result<(), Self::Error> {
+                ::std::result::Result::Ok(())
+            }
+        }
+    };
+    (@table $from:ident, $to:ident, $direction:ident, $table:ident) => {
+        impl $crate::schema::FromSchema<$from> for $to {
+            type Error = $crate::mapping::UnmappedKindError;
+
+            fn convert_primitive(
+                primitive: <$from as $crate::schema::Schema>::Primitive,
+            ) -> ::std::result::Result<<Self as $crate::schema::Schema>::Primitive, Self::Error>
+            {
+                $crate::mapping::convert_primitive(
+                    $table,
+                    $crate::mapping::MappingDirection::$direction,
+                    primitive,
+                )
+            }
+
+            fn convert_instance(
+                instance: &mut $crate::Instance,
+                primitive: &<$from as $crate::schema::Schema>::Primitive,
+            ) -> ::std::result::Result<(), Self::Error> {
+                $crate::mapping::convert_instance(
+                    $table,
+                    $crate::mapping::MappingDirection::$direction,
+                    instance,
+                    primitive,
+                )
+            }
+        }
+    };
+}
@@ -4,6 +4,11 @@ use uniquify::Names;
 
 use super::*;
 
+/// An error renaming cells such that more than one cell would have the same name.
+#[derive(Clone, Eq, PartialEq, Debug, thiserror::Error)]
+#[error("renaming cells would give more than one cell the name `{0}`")]
+pub struct DuplicateRenameError(pub ArcStr);
+
 /// Keeps track of cell and primitive IDs after a library is merged.
 pub struct MergedMapping {
     cells: HashMap<CellId, CellId>,
@@ -59,8 +64,14 @@ impl<'a, S: Schema + ?Sized> Merger<'a, S> {
             self.names.reserve_name(id, cell.name());
         }
         let mut cells: Vec<_> = if let Some(cells) = self.merge_cells.as_ref() {
+            // Preserved cells are never pruned.
+            let preserved = self
+                .src
+                .cells()
+                .filter(|(_, cell)| cell.is_preserved())
+                .map(|(id, _)| id);
             self.src
-                .cells_used_by(cells.iter().copied())
+                .cells_used_by(cells.iter().copied().chain(preserved))
                 .iter()
                 .map(|&id| (id, self.src.cell(id).clone()))
                 .collect()
@@ -68,7 +79,12 @@ impl<'a, S: Schema + ?Sized> Merger<'a, S> {
             self.src.cells.drain(..).collect()
         };
         let primitives: Vec<_> = self.src.primitives.drain(..).collect();
-        for (id, cell) in cells.iter_mut() {
+        // Preserved cells are named first so that they keep their names
+        // if they collide with other cells being merged.
+        for (id, cell) in cells.iter_mut().filter(|(_, cell)| cell.is_preserved()) {
+            self.assign_cell_identifiers(*id, cell);
+        }
+        for (id, cell) in cells.iter_mut().filter(|(_, cell)| !cell.is_preserved()) {
             self.assign_cell_identifiers(*id, cell);
         }
         for (id, _) in primitives.iter() {
@@ -80,6 +96,7 @@ impl<'a, S: Schema + ?Sized> Merger<'a, S> {
         for (id, primitive) in primitives {
             self.merge_primitive(id, primitive);
         }
+        self.dst.globals.extend(self.src.globals.drain(..));
 
         MergedMapping {
             cells: self.cell_mapping,
@@ -90,6 +107,13 @@ impl<'a, S: Schema + ?Sized> Merger<'a, S> {
     fn assign_cell_identifiers(&mut self, id: CellId, cell: &mut Cell) {
         let n_id = self.dst.alloc_cell_id();
         let n_name = self.names.assign_name(n_id, &cell.name);
+        if cell.preserve && n_name != cell.name {
+            tracing::warn!(
+                cell = %cell.name,
+                renamed = %n_name,
+                "preserved cell renamed to avoid a name collision during merge"
+            );
+        }
         self.cell_mapping.insert(id, n_id);
         cell.name = n_name;
     }
@@ -150,6 +174,9 @@ impl<S: Schema + ?Sized> LibraryBuilder<S> {
     }
 
     /// Merges the given cells from another SCIR library into the current library.
+    ///
+    /// Cells of `other` not used by the given cells are pruned, except for
+    /// [preserved](Cell::set_preserve) cells, which are always merged.
     pub fn merge_cells(
         &mut self,
         other: Self,
@@ -157,4 +184,52 @@ impl<S: Schema + ?Sized> LibraryBuilder<S> {
     ) -> MergedMapping {
         Merger::merge_cells(self, other, cells).merge()
     }
+
+    /// Renames every cell in the library using the provided function.
+    ///
+    /// Instances refer to their child cells by ID, so all references remain valid.
+    /// Names of cells referenced by primitives (e.g. raw instances) are not modified.
+    /// [Preserved](Cell::set_preserve) cells are not renamed.
+    ///
+    /// Returns an error and leaves the library unchanged if more than one cell
+    /// would have the same name after renaming.
+    pub fn rename_cells(
+        &mut self,
+        mut rename: impl FnMut(&ArcStr) -> ArcStr,
+    ) -> Result<(), DuplicateRenameError> {
+        let mut names = Names::with_capacity(self.cells.len());
+        let mut renamed = Vec::with_capacity(self.cells.len());
+        for (id, cell) in self.cells.iter() {
+            let name = if cell.preserve {
+                cell.name.clone()
+            } else {
+                rename(&cell.name)
+            };
+            if !names.reserve_name(*id, name.clone()) {
+                return Err(DuplicateRenameError(name));
+            }
+            renamed.push(name);
+        }
+
+        // Cached validation issues refer to cells by name.
+        self.validation_cache().invalidate_all();
+        self.name_map.clear();
+        for ((id, cell), name) in self.cells.iter_mut().zip(renamed) {
+            self.name_map.insert(name.clone(), *id);
+            cell.name = name;
+        }
+        self.names = names;
+        Ok(())
+    }
+
+    /// Prefixes the names of all cells in the library with `prefix`.
+    ///
+    /// Allows independently generated libraries to be merged or netlisted into
+    /// one deck without their cell names colliding.
+    ///
+    /// Returns an error if a prefixed name collides with the name of a
+    /// [preserved](Cell::set_preserve) cell.
+    pub fn prefix_cell_names(&mut self, prefix: &str) -> Result<(), DuplicateRenameError> {
+        self.rename_cells(|name| arcstr::format!("{}{}", prefix, name))
+    }
 }
@@ -0,0 +1,221 @@
+//! Pattern queries over the hierarchy of a SCIR library.
+//!
+//! Instances and signals are matched by their hierarchical names, which consist of the
+//! names of the instances leading to them from the top cell, separated by `.`. The bits of
+//! a bus are named by their index in square brackets:
+//!
+//! ```ignore
+//! let bitlines = lib.find_signals("xbank.xcol*.bl[0]");
+//! let columns = lib.find_instances(Pattern::regex(r"xbank\.xcol[0-7]")?);
+//! ```
+
+use std::fmt::Write;
+
+use regex::Regex;
+
+use crate::schema::Schema;
+use crate::{CellId, ChildId, InstancePath, LibraryBuilder, SliceOnePath};
+
+/// The separator between segments of a hierarchical name.
+pub const SEPARATOR: char = '.';
+
+/// A pattern matched against hierarchical names.
+#[derive(Clone, Debug)]
+pub struct Pattern {
+    regex: Regex,
+    /// Patterns for the leading segments of matching names, used to skip subtrees
+    /// of the hierarchy that cannot contain a match.
+    segments: Vec<Regex>,
+    /// Whether matching names may have more segments than `segments`.
+    unbounded: bool,
+}
+
+/// Translates a glob to an anchored regular expression.
+fn glob_regex(glob: &str) -> Regex {
+    let mut regex = String::from("^");
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                regex.push_str(".*");
+            }
+            '*' => write!(regex, "[^{}]*", regex::escape(&SEPARATOR.to_string())).unwrap(),
+            '?' => write!(regex, "[^{}]", regex::escape(&SEPARATOR.to_string())).unwrap(),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+    Regex::new(&regex).expect("escaped glob is a valid regex")
+}
+
+impl Pattern {
+    /// Creates a pattern from a glob.
+    ///
+    /// `*` matches any sequence of characters within a single path segment, `**` matches any
+    /// sequence of characters including separators, and `?` matches a single character other
+    /// than a separator. All other characters, including square brackets, match literally.
+    ///
+    /// Queries skip the parts of the hierarchy ruled out by the segments before the first `**`.
+    pub fn glob(glob: &str) -> Self {
+        let mut segments = Vec::new();
+        let mut unbounded = false;
+        for segment in glob.split(SEPARATOR) {
+            if segment.contains("**") {
+                unbounded = true;
+                break;
+            }
+            segments.push(glob_regex(segment));
+        }
+        Self {
+            regex: glob_regex(glob),
+            segments,
+            unbounded,
+        }
+    }
+
+    /// Creates a pattern from a regular expression.
+    ///
+    /// The regular expression must match an entire name.
+    /// Queries using regular expressions visit the entire hierarchy.
+    pub fn regex(regex: &str) -> Result<Self, regex::Error> {
+        Ok(Self {
+            regex: Regex::new(&format!("^(?:{regex})$"))?,
+            segments: Vec::new(),
+            unbounded: true,
+        })
+    }
+
+    /// Returns `true` if `name` matches this pattern.
+    pub fn is_match(&self, name: &str) -> bool {
+        self.regex.is_match(name)
+    }
+
+    /// Returns `false` if no name below the hierarchical name `prefix` can match this pattern.
+    ///
+    /// Every name is below the empty prefix.
+    pub(crate) fn may_match_below(&self, prefix: &str) -> bool {
+        if prefix.is_empty() {
+            return true;
+        }
+        let depth = prefix.split(SEPARATOR).count();
+        (self.unbounded || depth < self.segments.len())
+            && prefix
+                .split(SEPARATOR)
+                .zip(self.segments.iter())
+                .all(|(segment, pattern)| pattern.is_match(segment))
+    }
+}
+
+impl From<&str> for Pattern {
+    /// Creates a pattern from a glob. See [`Pattern::glob`].
+    fn from(value: &str) -> Self {
+        Self::glob(value)
+    }
+}
+
+impl<S: Schema + ?Sized> LibraryBuilder<S> {
+    /// Finds all instances whose hierarchical names match `pattern`, starting from the top cell.
+    ///
+    /// Instances of primitives are included. Returns an empty list if there is no top cell.
+    pub fn find_instances(&self, pattern: impl Into<Pattern>) -> Vec<InstancePath> {
+        let pattern = pattern.into();
+        let mut matches = Vec::new();
+        self.walk(&pattern, |path, name, _| {
+            if pattern.is_match(name) {
+                matches.push(path.clone());
+            }
+        });
+        matches
+    }
+
+    /// Finds all signal bits whose hierarchical names match `pattern`, starting from the top
+    /// cell.
+    ///
+    /// Every signal of every cell in the hierarchy is considered, so a net that is connected
+    /// to the ports of several instances may be matched more than once.
+    /// Use [`LibraryBuilder::simplify_path`] to find the canonical path of each match.
+    /// Returns an empty list if there is no top cell.
+    pub fn find_signals(&self, pattern: impl Into<Pattern>) -> Vec<SliceOnePath> {
+        let pattern = pattern.into();
+        let mut matches = Vec::new();
+        let Some(top) = self.top_cell() else {
+            return matches;
+        };
+        let mut visit = |path: &InstancePath, prefix: &str, cell: CellId| {
+            if !pattern.may_match_below(prefix) {
+                return;
+            }
+            for (_, info) in self.cell(cell).signals() {
+                let name = |bit: Option<usize>| {
+                    let mut name = if prefix.is_empty() {
+                        info.name.to_string()
+                    } else {
+                        format!("{prefix}{SEPARATOR}{}", info.name)
+                    };
+                    if let Some(bit) = bit {
+                        write!(name, "[{bit}]").unwrap();
+                    }
+                    name
+                };
+                match info.width {
+                    None => {
+                        if pattern.is_match(&name(None)) {
+                            let tail = info.slice().slice_one().unwrap();
+                            matches.push(path.clone().slice_one(tail));
+                        }
+                    }
+                    Some(width) => {
+                        for bit in 0..width {
+                            if pattern.is_match(&name(Some(bit))) {
+                                matches.push(path.clone().slice_one(info.slice().index(bit)));
+                            }
+                        }
+                    }
+                }
+            }
+        };
+        visit(&InstancePath::new(top), "", top);
+        self.walk(&pattern, |path, name, child| {
+            if let ChildId::Cell(cell) = child {
+                visit(path, name, cell);
+            }
+        });
+        matches
+    }
+
+    /// Calls `f` with the path, hierarchical name, and child of every instance in the
+    /// hierarchy below the top cell, in depth-first order.
+    ///
+    /// Does not descend into instances below which no name can match `pattern`.
+    fn walk(&self, pattern: &Pattern, mut f: impl FnMut(&InstancePath, &str, ChildId)) {
+        if let Some(top) = self.top_cell() {
+            self.walk_cell(pattern, &mut InstancePath::new(top), "", top, &mut f);
+        }
+    }
+
+    fn walk_cell(
+        &self,
+        pattern: &Pattern,
+        path: &mut InstancePath,
+        prefix: &str,
+        cell: CellId,
+        f: &mut impl FnMut(&InstancePath, &str, ChildId),
+    ) {
+        for (id, inst) in self.cell(cell).instances() {
+            let name = if prefix.is_empty() {
+                inst.name().to_string()
+            } else {
+                format!("{prefix}{SEPARATOR}{}", inst.name())
+            };
+            path.push(id);
+            f(path, &name, inst.child());
+            if let ChildId::Cell(child) = inst.child() {
+                if pattern.may_match_below(&name) {
+                    self.walk_cell(pattern, path, &name, child, f);
+                }
+            }
+            path.pop();
+        }
+    }
+}
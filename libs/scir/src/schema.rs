@@ -1,9 +1,11 @@
 //! Traits and definitions associated with schemas, or data formats
 //! used for storing SCIR libraries.
 
-use crate::Instance;
+use crate::{Cell, CellId, Instance, PrimitiveId};
 use arcstr::ArcStr;
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::convert::Infallible;
 
 /// A data format for storing SCIR libraries.
@@ -11,6 +13,24 @@ use std::convert::Infallible;
 pub trait Schema {
     /// A primitive used for storing arbitrary data that is opaque to SCIR.
     type Primitive: Primitive + Sized;
+
+    /// Returns a copy of `primitive` representing `m` copies of it in parallel.
+    ///
+    /// Returns [`None`] if the primitive does not support multipliers, which is the default.
+    #[allow(unused_variables)]
+    fn multiply_primitive(primitive: &Self::Primitive, m: u64) -> Option<Self::Primitive> {
+        None
+    }
+
+    /// Returns `true` if instances of `primitive` may assign pass-through parameters
+    /// (see [`Instance::set_param`](crate::Instance::set_param)).
+    ///
+    /// Returns `false` by default, in which case assigning a parameter to an instance of
+    /// `primitive` is a validation error.
+    #[allow(unused_variables)]
+    fn supports_instance_params(primitive: &Self::Primitive) -> bool {
+        false
+    }
 }
 
 /// A primitive of a SCIR schema.
@@ -34,6 +54,144 @@ pub trait FromSchema<S: Schema + ?Sized>: Schema {
         instance: &mut Instance,
         primitive: &<S as Schema>::Primitive,
     ) -> Result<(), Self::Error>;
+
+    /// Converts a primitive of the other schema to a primitive of this schema,
+    /// with access to the library being converted.
+    ///
+    /// Called by [`LibraryBuilder::convert_schema`](crate::LibraryBuilder::convert_schema)
+    /// after all instances have been converted.
+    /// Defaults to [`FromSchema::convert_primitive`].
+    #[allow(unused_variables)]
+    fn convert_primitive_in(
+        ctx: &mut ConversionContext<'_, Self>,
+        id: PrimitiveId,
+        primitive: <S as Schema>::Primitive,
+    ) -> Result<<Self as Schema>::Primitive, Self::Error> {
+        Self::convert_primitive(primitive)
+    }
+
+    /// Converts an instance from the other schema to a new instance based on its
+    /// associated primitive, with access to the library being converted.
+    ///
+    /// Unlike [`FromSchema::convert_instance`], this may look up the cells of the library and
+    /// add support cells, such as a wrapper subcircuit that the instance is redirected to
+    /// using [`Instance::set_child`].
+    ///
+    /// Called by [`LibraryBuilder::convert_schema`](crate::LibraryBuilder::convert_schema).
+    /// Defaults to [`FromSchema::convert_instance`].
+    #[allow(unused_variables)]
+    fn convert_instance_in(
+        ctx: &mut ConversionContext<'_, Self>,
+        instance: &mut Instance,
+        primitive: &<S as Schema>::Primitive,
+    ) -> Result<(), Self::Error> {
+        Self::convert_instance(instance, primitive)
+    }
+}
+
+/// Cells and primitives added by a schema conversion.
+pub(crate) struct SupportItems<C: Schema + ?Sized> {
+    /// The cell ID counter of the converted library.
+    pub(crate) cell_id: u64,
+    /// The primitive ID counter of the converted library.
+    pub(crate) primitive_id: u64,
+    pub(crate) cells: IndexMap<CellId, Cell>,
+    name_map: HashMap<ArcStr, CellId>,
+    pub(crate) primitives: IndexMap<PrimitiveId, C::Primitive>,
+}
+
+impl<C: Schema + ?Sized> SupportItems<C> {
+    pub(crate) fn new(cell_id: u64, primitive_id: u64) -> Self {
+        Self {
+            cell_id,
+            primitive_id,
+            cells: IndexMap::new(),
+            name_map: HashMap::new(),
+            primitives: IndexMap::new(),
+        }
+    }
+}
+
+/// An error adding a support cell whose name is already taken.
+#[derive(Clone, Eq, PartialEq, Debug, thiserror::Error)]
+#[error("a cell named `{0}` already exists in the library being converted")]
+pub struct DuplicateCellNameError(pub ArcStr);
+
+/// The library being converted to schema `C`, as seen by [`FromSchema`] conversions.
+///
+/// Provides read access to the cells of the library and allows conversions to add support
+/// cells and primitives to the converted library. Cells and primitives added through the
+/// context are already in schema `C`, so they are not converted.
+pub struct ConversionContext<'a, C: Schema + ?Sized> {
+    cells: &'a IndexMap<CellId, Cell>,
+    name_map: &'a HashMap<ArcStr, CellId>,
+    support: &'a mut SupportItems<C>,
+}
+
+impl<'a, C: Schema + ?Sized> ConversionContext<'a, C> {
+    pub(crate) fn new(
+        cells: &'a IndexMap<CellId, Cell>,
+        name_map: &'a HashMap<ArcStr, CellId>,
+        support: &'a mut SupportItems<C>,
+    ) -> Self {
+        Self {
+            cells,
+            name_map,
+            support,
+        }
+    }
+
+    /// Gets the cell with the given ID, including support cells.
+    ///
+    /// The instances of the cell whose instances are being converted are not visible.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no cell has the given ID.
+    pub fn cell(&self, id: CellId) -> &Cell {
+        self.try_cell(id).unwrap()
+    }
+
+    /// Gets the cell with the given ID, including support cells.
+    pub fn try_cell(&self, id: CellId) -> Option<&Cell> {
+        self.cells.get(&id).or_else(|| self.support.cells.get(&id))
+    }
+
+    /// Gets the ID of the cell with the given name, including support cells.
+    pub fn try_cell_id_named(&self, name: &str) -> Option<CellId> {
+        self.name_map
+            .get(name)
+            .or_else(|| self.support.name_map.get(name))
+            .copied()
+    }
+
+    /// Adds a support cell to the converted library, returning its ID.
+    ///
+    /// Primitive instances within the cell must refer to primitives of schema `C`,
+    /// such as those added using [`ConversionContext::add_primitive`]. Instances of
+    /// primitives of the library being converted refer to their converted counterparts.
+    ///
+    /// Returns an error if a cell with the same name already exists, either in the library
+    /// or among previously added support cells. Use [`ConversionContext::try_cell_id_named`]
+    /// to reuse a support cell across conversions.
+    pub fn add_cell(&mut self, cell: Cell) -> Result<CellId, DuplicateCellNameError> {
+        if self.try_cell_id_named(&cell.name).is_some() {
+            return Err(DuplicateCellNameError(cell.name));
+        }
+        self.support.cell_id += 1;
+        let id = CellId(self.support.cell_id);
+        self.support.name_map.insert(cell.name.clone(), id);
+        self.support.cells.insert(id, cell);
+        Ok(id)
+    }
+
+    /// Adds a primitive of schema `C` to the converted library, returning its ID.
+    pub fn add_primitive(&mut self, primitive: C::Primitive) -> PrimitiveId {
+        self.support.primitive_id += 1;
+        let id = PrimitiveId(self.support.primitive_id);
+        self.support.primitives.insert(id, primitive);
+        id
+    }
 }
 
 impl<S: Schema + ?Sized> FromSchema<S> for S {
@@ -0,0 +1,100 @@
+//! Locations in generator code responsible for SCIR objects.
+//!
+//! Generators such as Substrate may attach a [`SourceInfo`] to the cells, instances, and
+//! signals they create. Validation issues and netlisting errors report it, so that
+//! diagnostics point to the generator code rather than to the generated SCIR.
+
+use std::fmt::Display;
+use std::panic::Location;
+
+use arcstr::ArcStr;
+use serde::{Deserialize, Serialize};
+
+/// A location in a source file.
+#[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SourceLocation {
+    /// The path of the file.
+    pub file: ArcStr,
+    /// The line number, starting at 1.
+    pub line: u32,
+    /// The column number, starting at 1.
+    pub column: u32,
+}
+
+impl Display for SourceLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
+
+/// The generator code responsible for a SCIR object.
+#[derive(Clone, Debug, Default, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SourceInfo {
+    /// The location at which the object was created.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    location: Option<SourceLocation>,
+    /// The path of the block that generated the object.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    block: Option<ArcStr>,
+}
+
+impl SourceInfo {
+    /// Creates a [`SourceInfo`] pointing to the given line and column of `file`.
+    pub fn new(file: impl Into<ArcStr>, line: u32, column: u32) -> Self {
+        Self {
+            location: Some(SourceLocation {
+                file: file.into(),
+                line,
+                column,
+            }),
+            block: None,
+        }
+    }
+
+    /// Creates a [`SourceInfo`] pointing to the caller of this function.
+    ///
+    /// Like with [`Location::caller`], annotate functions with `#[track_caller]`
+    /// for them to be skipped when looking up the call stack.
+    #[track_caller]
+    pub fn from_caller() -> Self {
+        let loc = Location::caller();
+        Self::new(loc.file(), loc.line(), loc.column())
+    }
+
+    /// Creates a [`SourceInfo`] that only identifies the block that generated an object.
+    pub fn from_block(block: impl Into<ArcStr>) -> Self {
+        Self {
+            location: None,
+            block: Some(block.into()),
+        }
+    }
+
+    /// Sets the path of the block that generated the object.
+    pub fn with_block(mut self, block: impl Into<ArcStr>) -> Self {
+        self.block = Some(block.into());
+        self
+    }
+
+    /// The location at which the object was created, if known.
+    #[inline]
+    pub fn location(&self) -> Option<&SourceLocation> {
+        self.location.as_ref()
+    }
+
+    /// The path of the block that generated the object, if known.
+    #[inline]
+    pub fn block(&self) -> Option<&ArcStr> {
+        self.block.as_ref()
+    }
+}
+
+impl Display for SourceInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.location, &self.block) {
+            (Some(location), Some(block)) => write!(f, "{location} (in block `{block}`)"),
+            (Some(location), None) => write!(f, "{location}"),
+            (None, Some(block)) => write!(f, "block `{block}`"),
+            (None, None) => write!(f, "unknown location"),
+        }
+    }
+}
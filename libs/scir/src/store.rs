@@ -0,0 +1,206 @@
+//! Storage for the instances of large cells.
+//!
+//! Memory-like cells may contain millions of instances, so per-instance overhead can dominate
+//! the memory used by a library. These containers keep the serialized format of the maps
+//! they replace. The savings depend on the size of each instance; the `instances` benchmark
+//! compares the storage modes.
+
+use std::borrow::Borrow;
+use std::hash::Hash;
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{Instance, InstanceId};
+
+/// The instances of a cell, in order of increasing ID.
+///
+/// See [`Cell::set_compact_instances`](crate::Cell::set_compact_instances).
+#[derive(Debug, Clone)]
+pub(crate) enum InstanceStore {
+    /// Instances stored in a hash map.
+    Indexed(IndexMap<InstanceId, Instance>),
+    /// Instances stored in sorted arrays.
+    ///
+    /// Instance IDs are assigned in increasing order and instances are never removed,
+    /// so instances can be looked up by binary search instead of hashing. IDs and instances
+    /// are stored in separate arrays to keep the search cache-friendly.
+    Compact {
+        ids: Vec<InstanceId>,
+        instances: Vec<Instance>,
+    },
+}
+
+impl Default for InstanceStore {
+    fn default() -> Self {
+        Self::Indexed(IndexMap::new())
+    }
+}
+
+impl InstanceStore {
+    /// Returns `true` if instances are stored in sorted arrays.
+    pub(crate) fn is_compact(&self) -> bool {
+        matches!(self, Self::Compact { .. })
+    }
+
+    /// Switches between hash map and sorted array storage, keeping the stored instances.
+    pub(crate) fn set_compact(&mut self, compact: bool) {
+        if compact == self.is_compact() {
+            return;
+        }
+        *self = match std::mem::take(self) {
+            Self::Indexed(map) => {
+                let (ids, instances) = map.into_iter().unzip();
+                Self::Compact { ids, instances }
+            }
+            Self::Compact { ids, instances } => {
+                Self::Indexed(ids.into_iter().zip(instances).collect())
+            }
+        };
+    }
+
+    /// Returns `true` if there are no instances.
+    pub(crate) fn is_empty(&self) -> bool {
+        match self {
+            Self::Indexed(map) => map.is_empty(),
+            Self::Compact { ids, .. } => ids.is_empty(),
+        }
+    }
+
+    /// Reserves capacity for at least `additional` more instances.
+    pub(crate) fn reserve(&mut self, additional: usize) {
+        match self {
+            Self::Indexed(map) => map.reserve(additional),
+            Self::Compact { ids, instances } => {
+                ids.reserve(additional);
+                instances.reserve(additional);
+            }
+        }
+    }
+
+    /// Adds an instance with the given ID.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` is not greater than the IDs of all existing instances.
+    pub(crate) fn insert(&mut self, id: InstanceId, instance: Instance) {
+        match self {
+            Self::Indexed(map) => {
+                assert!(
+                    !map.last().is_some_and(|(last, _)| *last >= id),
+                    "instance IDs must be inserted in increasing order"
+                );
+                map.insert(id, instance);
+            }
+            Self::Compact { ids, instances } => {
+                assert!(
+                    !ids.last().is_some_and(|last| *last >= id),
+                    "instance IDs must be inserted in increasing order"
+                );
+                ids.push(id);
+                instances.push(instance);
+            }
+        }
+    }
+
+    pub(crate) fn get(&self, id: &InstanceId) -> Option<&Instance> {
+        match self {
+            Self::Indexed(map) => map.get(id),
+            Self::Compact { ids, instances } => Some(&instances[ids.binary_search(id).ok()?]),
+        }
+    }
+
+    pub(crate) fn get_mut(&mut self, id: &InstanceId) -> Option<&mut Instance> {
+        match self {
+            Self::Indexed(map) => map.get_mut(id),
+            Self::Compact { ids, instances } => Some(&mut instances[ids.binary_search(id).ok()?]),
+        }
+    }
+
+    /// Iterates over the instances in order of increasing ID.
+    pub(crate) fn iter(&self) -> Box<dyn Iterator<Item = (&InstanceId, &Instance)> + '_> {
+        match self {
+            Self::Indexed(map) => Box::new(map.iter()),
+            Self::Compact { ids, instances } => Box::new(ids.iter().zip(instances.iter())),
+        }
+    }
+
+    /// Mutably iterates over the instances in order of increasing ID.
+    pub(crate) fn iter_mut(
+        &mut self,
+    ) -> Box<dyn Iterator<Item = (&InstanceId, &mut Instance)> + '_> {
+        match self {
+            Self::Indexed(map) => Box::new(map.iter_mut()),
+            Self::Compact { ids, instances } => Box::new(ids.iter().zip(instances.iter_mut())),
+        }
+    }
+}
+
+impl Serialize for InstanceStore {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.iter())
+    }
+}
+
+/// Deserializes instances into hash map storage.
+impl<'de> Deserialize<'de> for InstanceStore {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut instances = IndexMap::<InstanceId, Instance>::deserialize(deserializer)?;
+        instances.sort_keys();
+        Ok(Self::Indexed(instances))
+    }
+}
+
+/// An insertion-ordered map that is usually empty.
+///
+/// Only allocates once an entry is inserted, and takes the space of a single pointer
+/// when empty.
+#[derive(Debug, Clone)]
+pub(crate) struct SparseMap<K, V>(Option<Box<IndexMap<K, V>>>);
+
+impl<K, V> Default for SparseMap<K, V> {
+    fn default() -> Self {
+        Self(None)
+    }
+}
+
+impl<K: Hash + Eq, V> SparseMap<K, V> {
+    pub(crate) fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.0
+            .get_or_insert_with(Default::default)
+            .insert(key, value)
+    }
+
+    pub(crate) fn get<Q: Hash + Eq + ?Sized>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+    {
+        self.0.as_ref()?.get(key)
+    }
+
+    pub(crate) fn shift_remove<Q: Hash + Eq + ?Sized>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+    {
+        self.0.as_mut()?.shift_remove(key)
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.0.iter().flat_map(|map| map.iter())
+    }
+}
+
+impl<K: Serialize, V: Serialize> Serialize for SparseMap<K, V> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.0.iter().flat_map(|map| map.iter()))
+    }
+}
+
+impl<'de, K: Deserialize<'de> + Hash + Eq, V: Deserialize<'de>> Deserialize<'de>
+    for SparseMap<K, V>
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let map = IndexMap::<K, V>::deserialize(deserializer)?;
+        Ok(Self((!map.is_empty()).then(|| Box::new(map))))
+    }
+}
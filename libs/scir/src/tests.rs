@@ -176,6 +176,7 @@ fn schema_conversion() {
     let mut inst_a = Instance::new("inst_a", prim_a);
     inst_a.connect("a1", vdd);
     inst_a.connect("a2", vss);
+    inst_a.set_attribute("LVS_IGNORE", "");
     let inst_a = cell.add_instance(inst_a);
 
     let mut inst_b = Instance::new("inst_b", prim_b);
@@ -185,6 +186,7 @@ fn schema_conversion() {
 
     cell.expose_port(vdd, Direction::InOut);
     cell.expose_port(vss, Direction::InOut);
+    cell.set_attribute("owner", "analog");
 
     let cell = lib.add_cell(cell);
 
@@ -208,6 +210,17 @@ fn schema_conversion() {
     assert_eq!(orig_lib.primitive(prim_a), "prim_a");
     assert_eq!(orig_lib.primitive(prim_b), "prim_b");
 
+    // Attributes are preserved across conversions.
+    assert_eq!(
+        orig_cell.attribute("owner").map(|v| v.as_str()),
+        Some("analog")
+    );
+    assert_eq!(
+        orig_cell.instance(inst_a).attributes().collect::<Vec<_>>(),
+        vec![(&arcstr::literal!("LVS_IGNORE"), &arcstr::literal!(""))]
+    );
+    assert_eq!(orig_cell.instance(b_inst).attributes().count(), 0);
+
     orig_lib.add_primitive("invalid_prim".into());
     assert!(orig_lib.convert_schema::<PartiallyTypedSchema>().is_err());
 }
@@ -277,6 +290,21 @@ fn nested_lib(n: usize) -> (Library<StringSchema>, Vec<SliceOnePath>) {
     (lib, vec![name_path, id_path, mixed_path])
 }
 
+#[test]
+fn library_serde_round_trip() {
+    let (lib, _) = nested_lib(4);
+    let json = serde_json::to_string(&lib).unwrap();
+    let lib2: Library<StringSchema> = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(lib2.cells().count(), 4);
+    assert_eq!(lib2.top_cell(), lib.top_cell());
+    for ((id, cell), (id2, cell2)) in lib.cells().zip(lib2.cells()) {
+        assert_eq!(id, id2);
+        assert_eq!(cell.name(), cell2.name());
+        assert_eq!(cell.instances().count(), cell2.instances().count());
+    }
+}
+
 #[test]
 fn path_simplification() {
     const N: usize = 5;
@@ -291,6 +319,27 @@ fn path_simplification() {
     }
 }
 
+#[test]
+fn merged_paths_include_connected_ports() {
+    const N: usize = 5;
+
+    let (lib, paths) = nested_lib(N);
+
+    for path in paths {
+        let merged = lib.merged_paths(path.clone());
+        // The node is shared by the VDD ports of `cell_{N-3}` through `cell_0`,
+        // but not by the port of the primitive instantiated by `cell_0`.
+        assert_eq!(
+            merged
+                .iter()
+                .map(|path| path.instances().len())
+                .collect::<Vec<_>>(),
+            (1..N).collect::<Vec<_>>()
+        );
+        assert_eq!(merged[0], lib.simplify_path(path));
+    }
+}
+
 #[test]
 fn name_path_conversion() {
     const N: usize = 5;
@@ -338,3 +387,1068 @@ fn merge_scir_libraries() {
     assert!(new_name.starts_with("vdivider"));
     assert_eq!(lib1.cell(vdivider_id).name(), "vdivider");
 }
+
+#[test]
+fn preserved_cells_are_kept_intact() {
+    let make_lib = || {
+        let mut lib = LibraryBuilder::<StringSchema>::new();
+        let mut macro_cell = Cell::new("qualified_esd");
+        macro_cell.set_preserve(true);
+        lib.add_cell(macro_cell);
+        let inv = lib.add_cell(Cell::new("inv"));
+        let mut top = Cell::new("top");
+        top.add_instance(Instance::new("inv0", inv));
+        let top = lib.add_cell(top);
+        lib.prefix_cell_names("p_").unwrap();
+        (lib, top)
+    };
+
+    let (lib, top) = make_lib();
+    let mut names = lib
+        .cells()
+        .map(|(_, cell)| cell.name().as_str())
+        .collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(names, vec!["p_inv", "p_top", "qualified_esd"]);
+
+    // Unused preserved cells are not pruned.
+    let mut dst = LibraryBuilder::<StringSchema>::new();
+    dst.add_cell(Cell::new("qualified_esd_copy"));
+    dst.merge_cells(lib, [top]);
+    let esd = dst.cell_named("qualified_esd");
+    assert!(esd.is_preserved());
+    assert_eq!(dst.cells().count(), 4);
+
+    let issues = dst.validate();
+    assert_eq!(issues.num_errors(), 0);
+
+    // A preserved cell colliding with a cell already in the destination library is renamed.
+    let (src, _) = make_lib();
+    let mut dst = LibraryBuilder::<StringSchema>::new();
+    dst.add_cell(Cell::new("qualified_esd"));
+    dst.merge(src);
+    let esd = dst.cell_named("qualified_esd_1");
+    assert!(esd.is_preserved());
+
+    // Renamed cells being merged do not take the name of a preserved cell.
+    let mut src = LibraryBuilder::<StringSchema>::new();
+    let top = src.add_cell(Cell::new("top"));
+    let mut macro_cell = Cell::new("top_1");
+    macro_cell.set_preserve(true);
+    let esd = src.add_cell(macro_cell);
+    let mut dst = LibraryBuilder::<StringSchema>::new();
+    dst.add_cell(Cell::new("top"));
+    let mapping = dst.merge(src);
+    assert_eq!(dst.cell(mapping.new_cell_id(esd)).name(), "top_1");
+    assert_eq!(dst.cell(mapping.new_cell_id(top)).name(), "top_2");
+}
+
+#[test]
+fn cell_params_are_validated() {
+    let mut lib = LibraryBuilder::<StringSchema>::new();
+    let mut unit = Cell::new("unit");
+    unit.add_param("nf", Param::new(Decimal::new(1, 0)));
+    let unit = lib.add_cell(unit);
+
+    let mut top = Cell::new("top");
+    top.add_param("scale", Param::new(Decimal::new(2, 0)));
+    let mut x0 = Instance::new("x0", unit);
+    x0.set_param("nf", Decimal::new(4, 0));
+    top.add_instance(x0);
+    let mut x1 = Instance::new("x1", unit);
+    x1.set_param("nf", Expr::Param(arcstr::literal!("scale")));
+    top.add_instance(x1);
+    let top = lib.add_cell(top);
+
+    let issues = lib.validate();
+    assert_eq!(issues.num_errors(), 0);
+    assert_eq!(
+        lib.cell(top).instance_named("x1").param("nf"),
+        Some(&Expr::Param(arcstr::literal!("scale")))
+    );
+
+    let mut bad = Cell::new("bad");
+    let mut x0 = Instance::new("x0", unit);
+    x0.set_param("nw", Decimal::new(4, 0));
+    bad.add_instance(x0);
+    let mut x1 = Instance::new("x1", unit);
+    x1.set_param("nf", Expr::Param(arcstr::literal!("scale")));
+    bad.add_instance(x1);
+    lib.add_cell(bad);
+
+    let issues = lib.validate();
+    assert_eq!(issues.num_errors(), 2);
+    assert!(issues
+        .iter()
+        .any(|issue| matches!(issue.cause(), validation::Cause::UndeclaredParam { .. })));
+    assert!(issues.iter().any(|issue| matches!(
+        issue.cause(),
+        validation::Cause::UnknownParamReference { .. }
+    )));
+}
+
+#[test]
+fn prefix_cell_names_avoids_merge_collisions() {
+    let make_lib = |prefix: &str| {
+        let mut lib = LibraryBuilder::<StringSchema>::new();
+        let inv = lib.add_cell(Cell::new("inv"));
+        let mut top = Cell::new("top");
+        top.add_instance(Instance::new("inv0", inv));
+        lib.add_cell(top);
+        lib.prefix_cell_names(prefix).unwrap();
+        lib
+    };
+
+    let mut lib = make_lib("a_");
+    let mapping = lib.merge(make_lib("b_"));
+
+    let issues = lib.validate();
+    assert_eq!(issues.num_warnings(), 0);
+    assert_eq!(issues.num_errors(), 0);
+
+    let mut names = lib
+        .cells()
+        .map(|(_, cell)| cell.name().as_str())
+        .collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(names, vec!["a_inv", "a_top", "b_inv", "b_top"]);
+
+    let b_top = lib.cell_id_named("b_top");
+    let b_inv = lib.cell_id_named("b_inv");
+    let (_, inst) = lib.cell(b_top).instances().next().unwrap();
+    assert_eq!(inst.child(), ChildId::Cell(b_inv));
+    assert_eq!(mapping.new_cell_id(CellId(1)), b_inv);
+}
+
+#[test]
+fn rename_cells_rejects_collisions() {
+    let mut lib = LibraryBuilder::<StringSchema>::new();
+    let mut macro_cell = Cell::new("p_inv");
+    macro_cell.set_preserve(true);
+    lib.add_cell(macro_cell);
+    let inv = lib.add_cell(Cell::new("inv"));
+
+    assert_eq!(
+        lib.prefix_cell_names("p_"),
+        Err(merge::DuplicateRenameError("p_inv".into()))
+    );
+    assert_eq!(lib.cell(inv).name().as_str(), "inv");
+    assert_eq!(lib.cell_id_named("inv"), inv);
+}
+
+#[test]
+fn global_nets() {
+    let mut lib = LibraryBuilder::<StringSchema>::new();
+    lib.add_global("vdd!");
+    let res = lib.add_primitive("res".into());
+
+    let mut leaf = Cell::new("leaf");
+    let vdd = leaf.add_global("vdd!");
+    let out = leaf.add_node("out");
+    leaf.expose_port(out, Direction::InOut);
+    let mut r = Instance::new("r", res);
+    r.connect("p", vdd);
+    r.connect("n", out);
+    leaf.add_instance(r);
+    let leaf = lib.add_cell(leaf);
+
+    let mut top = Cell::new("top");
+    let out = top.add_node("out");
+    top.expose_port(out, Direction::InOut);
+    let mut inst = Instance::new("leaf", leaf);
+    inst.connect("out", out);
+    let inst = top.add_instance(inst);
+    let top = lib.add_cell(top);
+
+    let issues = lib.validate();
+    assert_eq!(issues.num_errors(), 0);
+    assert_eq!(issues.num_warnings(), 0);
+
+    let mut path = InstancePath::new(top);
+    path.push(inst);
+    let path = path.slice_one(vdd);
+    let name_path = lib.convert_slice_one_path(path, |name, _| name.clone());
+    assert_eq!(name_path.join("."), "vdd!");
+
+    let mut bad = Cell::new("bad");
+    let vss = bad.add_global("vss!");
+    bad.expose_port(vss, Direction::InOut);
+    lib.add_cell(bad);
+
+    let issues = lib.validate();
+    assert_eq!(issues.num_errors(), 2);
+    assert!(issues.iter().any(|issue| matches!(
+        issue.cause(),
+        validation::Cause::UndeclaredGlobal { name, .. } if name == "vss!"
+    )));
+    assert!(issues.iter().any(|issue| matches!(
+        issue.cause(),
+        validation::Cause::GlobalPort { name, .. } if name == "vss!"
+    )));
+}
+
+/// Creates a library containing a buffer cell with an inout port `out`
+/// and a top cell that connects the buffer output to an input port.
+fn direction_contradiction_lib() -> (LibraryBuilder, CellId, CellId) {
+    let mut lib = <LibraryBuilder>::new();
+
+    let mut inv = Cell::new("inv");
+    let din = inv.add_node("din");
+    let dout = inv.add_node("dout");
+    inv.expose_port(din, Direction::Input);
+    inv.expose_port(dout, Direction::Output);
+    let inv = lib.add_cell(inv);
+
+    let mut buf = Cell::new("buf");
+    let din = buf.add_node("din");
+    let x = buf.add_node("x");
+    let dout = buf.add_node("dout");
+    let mut inv1 = Instance::new("inv1", inv);
+    inv1.connect("din", din);
+    inv1.connect("dout", x);
+    buf.add_instance(inv1);
+    let mut inv2 = Instance::new("inv2", inv);
+    inv2.connect("din", x);
+    inv2.connect("dout", dout);
+    buf.add_instance(inv2);
+    buf.expose_port(din, Direction::InOut);
+    buf.expose_port(dout, Direction::InOut);
+    let buf = lib.add_cell(buf);
+
+    let mut top = Cell::new("top");
+    let a = top.add_node("a");
+    let y = top.add_node("y");
+    let mut buf1 = Instance::new("buf1", buf);
+    buf1.connect("din", y);
+    buf1.connect("dout", a);
+    top.add_instance(buf1);
+    top.expose_port(a, Direction::Input);
+    top.expose_port(y, Direction::Output);
+    let top = lib.add_cell(top);
+
+    (lib, buf, top)
+}
+
+#[test]
+fn infer_port_directions() {
+    let (lib, buf, top) = direction_contradiction_lib();
+    let dirs = lib.infer_directions();
+
+    assert_eq!(dirs[&buf]["din"], Direction::Input);
+    assert_eq!(dirs[&buf]["dout"], Direction::Output);
+    assert_eq!(dirs[&top]["a"], Direction::Input);
+    assert_eq!(dirs[&top]["y"], Direction::Output);
+}
+
+#[test]
+fn driver_analysis_skips_unknown_ports() {
+    let (mut lib, buf, _) = direction_contradiction_lib();
+    let mut top = Cell::new("bad_top");
+    let a = top.add_node("a");
+    let mut buf1 = Instance::new("buf1", buf);
+    buf1.connect("din", a);
+    buf1.connect("missing", a);
+    top.add_instance(buf1);
+    top.expose_port(a, Direction::InOut);
+    let top = lib.add_cell(top);
+
+    assert_eq!(lib.infer_directions()[&top]["a"], Direction::Input);
+    lib.validate_drivers();
+}
+
+#[test]
+fn port_direction_contradictions() {
+    let (lib, _, _) = direction_contradiction_lib();
+    let issues = lib.validate_drivers();
+    let causes = issues.iter().map(|issue| issue.cause()).collect::<Vec<_>>();
+    assert!(causes.contains(&&drivers::Cause::DrivenInput));
+    assert!(causes.contains(&&drivers::Cause::UndrivenOutput));
+    // `inv` has no contents, so its output port is not expected to be driven.
+    assert!(issues
+        .iter()
+        .all(|issue| !(issue.cause() == &drivers::Cause::UndrivenOutput
+            && issue.net().cell_name().as_str() == "inv")));
+    assert!(!issues.has_error());
+    assert!(lib.build().is_ok());
+
+    let (mut lib, _, _) = direction_contradiction_lib();
+    lib.set_strict_directions(true);
+    let issues = lib.build().err().unwrap();
+    // The empty `inv` cell has no contents, so its output is not checked.
+    assert_eq!(issues.drivers.num_errors(), 2);
+}
+
+#[test]
+fn port_width_mismatch_diagnostics() {
+    let mut lib = <LibraryBuilder>::new();
+
+    let mut child = Cell::new("child");
+    let data = child.add_bus("data", 8);
+    child.expose_port(data, Direction::Input);
+    let child = lib.add_cell(child);
+
+    let mut parent = Cell::new("parent");
+    let a = parent.add_bus("a", 4);
+    let b = parent.add_node("b");
+    parent.add_bus("data", 8);
+    let mut inst = Instance::new("inst", child);
+    inst.connect("data", Concat::new(vec![a.into(), b.into()]));
+    parent.add_instance(inst);
+    lib.add_cell(parent);
+
+    let issues = lib.validate();
+    assert_eq!(issues.num_errors(), 1);
+    let issue = issues
+        .iter()
+        .find(|issue| matches!(issue.cause(), validation::Cause::PortWidthMismatch { .. }))
+        .unwrap();
+    let validation::Cause::PortWidthMismatch {
+        expected_width,
+        actual_width,
+        parts,
+        suggestion,
+        ..
+    } = issue.cause()
+    else {
+        unreachable!()
+    };
+    assert_eq!(*expected_width, 8);
+    assert_eq!(*actual_width, 5);
+    assert_eq!(
+        parts.iter().map(|part| part.width).collect::<Vec<_>>(),
+        vec![4, 1]
+    );
+    assert_eq!(suggestion.as_deref(), Some("data"));
+    assert!(issue.to_string().contains("did you mean `data`?"));
+}
+
+#[test]
+fn export_library_graph() {
+    let mut lib = LibraryBuilder::<StringSchema>::new();
+    let res = lib.add_primitive("res".into());
+
+    let mut vdivider = Cell::new("vdivider");
+    let vdd = vdivider.add_node("vdd");
+    let out = vdivider.add_node("out");
+    let vss = vdivider.add_node("vss");
+    let mut r1 = Instance::new("r1", res);
+    r1.connect("1", vdd);
+    r1.connect("2", out);
+    vdivider.add_instance(r1);
+    let mut r2 = Instance::new("r2", res);
+    r2.connect("1", out);
+    r2.connect("2", vss);
+    vdivider.add_instance(r2);
+    vdivider.expose_port(vdd, Direction::InOut);
+    vdivider.expose_port(vss, Direction::InOut);
+    vdivider.expose_port(out, Direction::Output);
+    let vdivider = lib.add_cell(vdivider);
+
+    let mut top = Cell::new("top");
+    let bus = top.add_bus("bus", 2);
+    let mut xdiv = Instance::new("xdiv", vdivider);
+    xdiv.connect("vdd", bus.index(0));
+    xdiv.connect("out", bus.index(1));
+    xdiv.connect("vss", bus.index(0));
+    top.add_instance(xdiv);
+    let top = lib.add_cell(top);
+    lib.set_top(top);
+
+    let graph = lib.to_graph();
+    assert_eq!(graph.top.as_deref(), Some("top"));
+    assert_eq!(graph.cells.len(), 2);
+
+    let vdivider = &graph.cells[0];
+    assert_eq!(vdivider.nets.len(), 3);
+    assert_eq!(vdivider.nets[1].direction, Some(Direction::Output));
+    let r1 = &vdivider.instances[0];
+    assert_eq!(r1.connections.len(), 2);
+    assert_eq!(r1.connections[0].port, "1");
+    assert_eq!(r1.connections[0].net, "vdd");
+
+    let xdiv = &graph.cells[1].instances[0];
+    assert_eq!(xdiv.child, graph::GraphChild::Cell("vdivider".into()));
+    assert_eq!(xdiv.connections[0].port, "out");
+    assert_eq!(xdiv.connections[0].range, Some((1, 2)));
+
+    let dot = graph.to_dot();
+    assert!(dot.starts_with("digraph scir {"));
+    assert!(dot.contains("label=\"top (top)\";"));
+    assert!(dot.contains("\"c0/inst/r1\" -> \"c0/net/vdd\" [label=\"1\", dir=none];"));
+    assert!(dot.contains("\"c1/inst/xdiv\" -> \"c0\" [style=dashed, lhead=cluster_0];"));
+
+    let json = graph.to_json().unwrap();
+    let parsed: graph::Graph = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed, graph);
+}
+
+#[test]
+fn find_instances_and_signals() {
+    let mut lib = LibraryBuilder::<StringSchema>::new();
+    let res = lib.add_primitive("res".into());
+
+    let mut col = Cell::new("col");
+    let bl = col.add_bus("bl", 2);
+    let vdd = col.add_node("vdd");
+    let mut rpu = Instance::new("rpu", res);
+    rpu.connect("1", vdd);
+    rpu.connect("2", bl.index(0));
+    col.add_instance(rpu);
+    col.expose_port(bl, Direction::InOut);
+    col.expose_port(vdd, Direction::InOut);
+    let col = lib.add_cell(col);
+
+    let mut top = Cell::new("top");
+    let vdd = top.add_node("vdd");
+    for i in 0..2 {
+        let bl = top.add_bus(format!("bl{i}"), 2);
+        let mut xcol = Instance::new(format!("xcol{i}"), col);
+        xcol.connect("bl", bl);
+        xcol.connect("vdd", vdd);
+        top.add_instance(xcol);
+    }
+    let top = lib.add_cell(top);
+    lib.set_top(top);
+
+    assert_eq!(lib.find_instances("xcol*").len(), 2);
+    assert_eq!(lib.find_instances("xcol?.rpu").len(), 2);
+    assert_eq!(lib.find_instances("*").len(), 2);
+    assert_eq!(lib.find_instances("**").len(), 4);
+
+    let bl = lib.find_signals("xcol1.bl[1]");
+    assert_eq!(bl.len(), 1);
+    assert_eq!(bl[0].instances().len(), 1);
+    assert_eq!(lib.find_signals("vdd").len(), 1);
+    assert_eq!(lib.find_signals("**vdd").len(), 3);
+    assert!(lib.find_signals("xcol2.bl[0]").is_empty());
+
+    let pattern = query::Pattern::regex(r"xcol\d\.bl\[0\]").unwrap();
+    assert_eq!(lib.find_signals(pattern).len(), 2);
+    assert!(query::Pattern::regex("(").is_err());
+}
+
+#[test]
+fn glob_patterns_prune_the_hierarchy() {
+    let pattern = query::Pattern::glob("xcol0.x*.rpu");
+    assert!(pattern.may_match_below(""));
+    assert!(pattern.may_match_below("xcol0"));
+    assert!(pattern.may_match_below("xcol0.xa"));
+    assert!(!pattern.may_match_below("xcol1"));
+    assert!(!pattern.may_match_below("xcol0.ya"));
+    assert!(!pattern.may_match_below("xcol0.xa.rpu"));
+
+    let pattern = query::Pattern::glob("xcol0.**.rpu");
+    assert!(pattern.may_match_below("xcol0.xa.xb.xc"));
+    assert!(!pattern.may_match_below("xcol1.xa"));
+    assert!(query::Pattern::regex("xcol0.*")
+        .unwrap()
+        .may_match_below("xcol1"));
+}
+
+#[test]
+fn connected_terminals() {
+    let mut lib = LibraryBuilder::<StringSchema>::new();
+    let res = lib.add_primitive("res".into());
+
+    let mut col = Cell::new("col");
+    let col_bl = col.add_bus("bl", 2);
+    let vdd = col.add_node("vdd");
+    let mut rpu = Instance::new("rpu", res);
+    rpu.connect("1", vdd);
+    rpu.connect("2", col_bl.index(0));
+    col.add_instance(rpu);
+    col.expose_port(col_bl, Direction::InOut);
+    col.expose_port(vdd, Direction::InOut);
+    let col = lib.add_cell(col);
+
+    let mut top = Cell::new("top");
+    let vdd = top.add_node("vdd");
+    let mut xcols = Vec::new();
+    for i in 0..2 {
+        let bl = top.add_bus(format!("bl{i}"), 2);
+        let mut xcol = Instance::new(format!("xcol{i}"), col);
+        xcol.connect("bl", bl);
+        xcol.connect("vdd", vdd);
+        xcols.push(top.add_instance(xcol));
+    }
+    let top = lib.add_cell(top);
+    lib.set_top(top);
+
+    // Start below the top cell so that the net must first be followed upward.
+    let mut xcol0 = InstancePath::new(top);
+    xcol0.push(xcols[0]);
+    let terminals = lib.connected_terminals(&SliceOnePath::new(xcol0, col_bl.index(0)));
+    assert_eq!(terminals.len(), 2);
+    assert_eq!(terminals[0].instance.len(), 1);
+    assert_eq!(terminals[0].child, ChildId::Cell(col));
+    assert_eq!(terminals[0].port.as_str(), "bl");
+    assert_eq!(terminals[0].index, 0);
+    assert_eq!(terminals[1].instance.len(), 2);
+    assert_eq!(terminals[1].child, ChildId::Primitive(res));
+    assert_eq!(terminals[1].port.as_str(), "2");
+
+    let terminals =
+        lib.connected_terminals(&InstancePath::new(top).slice_one(vdd.slice_one().unwrap()));
+    assert_eq!(terminals.len(), 4);
+    assert_eq!(
+        terminals
+            .iter()
+            .filter(|terminal| terminal.child == ChildId::Primitive(res))
+            .count(),
+        2
+    );
+
+    let terminals = lib.connected_terminals(
+        &InstancePath::new(top).slice_one(lib.cell(top).signal_named("bl1").slice().index(1)),
+    );
+    assert_eq!(terminals.len(), 1);
+    assert_eq!(terminals[0].index, 1);
+}
+
+#[test]
+fn instance_array() {
+    let mut lib = LibraryBuilder::<StringSchema>::new();
+    let mut bitcell = Cell::new("bitcell");
+    let wl = bitcell.add_node("wl");
+    let bl = bitcell.add_node("bl");
+    bitcell.expose_port(wl, Direction::Input);
+    bitcell.expose_port(bl, Direction::InOut);
+    let bitcell = lib.add_cell(bitcell);
+
+    let mut array = Cell::new("array");
+    let wl = array.add_bus("wl", 3);
+    let bl = array.add_bus("bl", 5);
+    let cells = array.add_array(
+        bitcell,
+        3,
+        5,
+        |row, col| format!("xcell_{row}_{col}"),
+        |row, col, inst| {
+            inst.connect("wl", wl.index(row));
+            inst.connect("bl", bl.index(col));
+        },
+    );
+    array.expose_port(wl, Direction::Input);
+    array.expose_port(bl, Direction::InOut);
+
+    assert_eq!((cells.rows(), cells.cols()), (3, 5));
+    assert_eq!(array.instances().count(), 15);
+    let (row, col, id) = cells.iter().nth(7).unwrap();
+    assert_eq!((row, col), (1, 2));
+    assert_eq!(id, cells.get(1, 2));
+    let inst = array.instance_named("xcell_1_2");
+    assert_eq!(inst.connection("wl").index(0), wl.index(1));
+    assert_eq!(inst.connection("bl").index(0), bl.index(2));
+
+    let array = lib.add_cell(array);
+    lib.set_top(array);
+    assert!(!lib.validate().has_error());
+}
+
+#[test]
+fn cell_serialization_round_trip() {
+    let mut lib = LibraryBuilder::<StringSchema>::new();
+    let res = lib.add_primitive("res".into());
+    for compact in [false, true] {
+        let mut cell = Cell::new("cell");
+        cell.set_compact_instances(compact);
+        let a = cell.add_node("a");
+        let mut ids = Vec::new();
+        for i in 0..4 {
+            let mut inst = Instance::new(format!("r{i}"), res);
+            inst.connect("1", a);
+            if i % 2 == 0 {
+                inst.set_attribute("lvs", "ignore");
+            }
+            ids.push(cell.add_instance(inst));
+        }
+        assert_eq!(cell.has_compact_instances(), compact);
+
+        let json = serde_json::to_string(&cell).unwrap();
+        let mut parsed: Cell = serde_json::from_str(&json).unwrap();
+        assert!(!parsed.has_compact_instances());
+        // Switching storage modes keeps the existing instances.
+        parsed.set_compact_instances(!compact);
+        assert_eq!(parsed.instances().count(), 4);
+        for (i, id) in ids.iter().enumerate() {
+            let inst = parsed.instance(*id);
+            assert_eq!(inst.name().as_str(), format!("r{i}"));
+            assert_eq!(
+                inst.attribute("lvs").map(|value| value.as_str()),
+                (i % 2 == 0).then_some("ignore")
+            );
+        }
+        assert_eq!(parsed.instance_named("r3").connection("1").width(), 1);
+        assert_eq!(serde_json::to_string(&parsed).unwrap(), json);
+    }
+}
+
+#[test]
+fn library_interns_shared_names() {
+    let mut lib = LibraryBuilder::<StringSchema>::new();
+    let res = lib.add_primitive("res".into());
+
+    let mut cells = Vec::new();
+    for i in 0..2 {
+        let mut cell = Cell::new(format!("cell{i}"));
+        let vdd = cell.add_node("vdd");
+        let vss = cell.add_node("vss");
+        let mut inst = Instance::new("r0", res);
+        inst.connect("1", vdd);
+        inst.connect("2", vss);
+        cell.add_instance(inst);
+        cell.expose_port(vdd, Direction::InOut);
+        cells.push(lib.add_cell(cell));
+    }
+
+    let (cell0, cell1) = (lib.cell(cells[0]), lib.cell(cells[1]));
+    assert!(ArcStr::ptr_eq(
+        &cell0.signal_named("vdd").name,
+        &cell1.signal_named("vdd").name
+    ));
+    let port = |cell: &Cell| {
+        cell.instance_named("r0")
+            .connections()
+            .keys()
+            .find(|port| port.as_str() == "1")
+            .unwrap()
+            .clone()
+    };
+    assert!(ArcStr::ptr_eq(&port(cell0), &port(cell1)));
+    assert!(ArcStr::ptr_eq(
+        &intern::intern("vdd"),
+        &cell0.signal_named("vdd").name
+    ));
+    // Instance names are usually unique, so they are not interned.
+    assert!(!ArcStr::ptr_eq(
+        cell0.instance_named("r0").name(),
+        cell1.instance_named("r0").name()
+    ));
+
+    // Deserialized names are interned as well.
+    let parsed: Cell = serde_json::from_str(&serde_json::to_string(cell0).unwrap()).unwrap();
+    assert!(ArcStr::ptr_eq(
+        &parsed.signal_named("vdd").name,
+        &cell1.signal_named("vdd").name
+    ));
+    assert!(ArcStr::ptr_eq(&port(&parsed), &port(cell1)));
+
+    let mut conv = NetlistLibConversion::new();
+    for &id in &cells {
+        let mut cell_conv = NetlistCellConversion::new();
+        let (inst, _) = lib.cell(id).instances().next().unwrap();
+        cell_conv
+            .instances
+            .insert(inst, arcstr::format!("X{}", "r0"));
+        conv.add_cell(id, cell_conv);
+    }
+    let name = |id| conv.cells[&id].instances.values().next().unwrap().clone();
+    assert!(ArcStr::ptr_eq(&name(cells[0]), &name(cells[1])));
+    assert_eq!(conv.names.len(), 1);
+}
+
+#[test]
+fn validation_is_incremental() {
+    let mut lib = LibraryBuilder::<StringSchema>::new();
+    let mut child = Cell::new("child");
+    let a = child.add_node("a");
+    child.expose_port(a, Direction::InOut);
+    let child = lib.add_cell(child);
+
+    let mut parent = Cell::new("parent");
+    let a = parent.add_node("a");
+    let mut inst = Instance::new("xchild", child);
+    inst.connect("a", a);
+    parent.add_instance(inst);
+    lib.add_cell(parent);
+    assert!(lib.validate().is_empty());
+
+    // Replacing the child invalidates the cached results of its parent.
+    let mut new_child = Cell::new("child");
+    let a = new_child.add_node("a");
+    let b = new_child.add_node("b");
+    new_child.expose_port(a, Direction::InOut);
+    new_child.expose_port(b, Direction::InOut);
+    lib.overwrite_cell_with_id(child, new_child);
+    let issues = lib.validate();
+    assert_eq!(issues.num_errors(), 1);
+    assert!(matches!(
+        issues.iter().next().unwrap().cause(),
+        validation::Cause::UnconnectedPort { port, .. } if port.as_str() == "b"
+    ));
+    assert_eq!(lib.validate().num_errors(), 1);
+}
+
+#[test]
+fn validation_tracks_primitive_changes() {
+    /// A schema whose primitives named `param` accept instance parameters.
+    struct ParamSchema;
+
+    impl Schema for ParamSchema {
+        type Primitive = ArcStr;
+
+        fn supports_instance_params(primitive: &ArcStr) -> bool {
+            primitive == "param"
+        }
+    }
+
+    let mut lib = LibraryBuilder::<ParamSchema>::new();
+    let prim = lib.add_primitive(arcstr::literal!("param"));
+    let mut top = Cell::new("top");
+    let mut inst = Instance::new("x0", prim);
+    inst.set_param("w", Decimal::new(1, 0));
+    top.add_instance(inst);
+    lib.add_cell(top);
+    assert!(lib.validate().is_empty());
+
+    // Replacing a primitive keeps the number of primitives the same,
+    // but still invalidates cached results.
+    lib.overwrite_primitive_with_id(prim, arcstr::literal!("fixed"));
+    assert_eq!(lib.validate().num_errors(), 1);
+}
+
+#[test]
+fn validation_of_many_cells() {
+    let mut lib = LibraryBuilder::<StringSchema>::new();
+    let mut leaf = Cell::new("leaf");
+    let a = leaf.add_node("a");
+    leaf.expose_port(a, Direction::InOut);
+    let leaf = lib.add_cell(leaf);
+
+    for i in 0..256 {
+        let mut cell = Cell::new(format!("cell{i}"));
+        let a = cell.add_node("a");
+        let mut inst = Instance::new("xleaf", leaf);
+        if i != 100 {
+            inst.connect("a", a);
+        }
+        cell.add_instance(inst);
+        lib.add_cell(cell);
+    }
+
+    let issues = lib.validate();
+    assert_eq!(issues.num_errors(), 1);
+    assert!(matches!(
+        issues.iter().next().unwrap().cause(),
+        validation::Cause::UnconnectedPort { parent_cell_name, .. } if parent_cell_name.as_str() == "cell100"
+    ));
+}
+
+#[test]
+fn driver_config_and_waivers() {
+    use diagnostics::Severity;
+    use drivers::{Cause, DriverConfig, Waiver};
+
+    // `top/a` is an input driven from within `top`, `top/y` is an output that is not driven,
+    // and the ports of `inv` are not connected within it.
+    let (mut lib, _, _) = direction_contradiction_lib();
+    lib.set_strict_directions(true);
+    lib.set_driver_config(
+        DriverConfig::new()
+            .ignore_cell("inv")
+            .waive(Waiver::new(Cause::DrivenInput, "top", "driven by a testbench").signal("a"))
+            .severity(Cause::UndrivenOutput, Severity::Info),
+    );
+    let (_, issues) = lib.try_build().unwrap();
+    assert!(!issues.has_error());
+    assert_eq!(issues.waived.len(), 1);
+    let waived = &issues.waived[0];
+    assert_eq!(waived.issue().cause(), &Cause::DrivenInput);
+    assert_eq!(waived.issue().net().signal_name().as_str(), "a");
+    assert_eq!(waived.waiver().reason().as_str(), "driven by a testbench");
+    assert!(issues
+        .drivers
+        .iter()
+        .all(|issue| issue.net().cell_name().as_str() != "inv"));
+    assert!(issues
+        .drivers
+        .iter()
+        .any(|issue| issue.cause() == &Cause::UndrivenOutput));
+    assert!(issues
+        .drivers
+        .iter()
+        .all(|issue| issue.cause() != &Cause::DrivenInput));
+}
+
+#[test]
+fn multiple_drivers_can_be_allowed() {
+    use diagnostics::Severity;
+    use drivers::{Cause, DriverConfig};
+
+    let mut lib = LibraryBuilder::<StringSchema>::new();
+    let mut drv = Cell::new("drv");
+    let out = drv.add_node("out");
+    drv.expose_port(out, Direction::Output);
+    let drv = lib.add_cell(drv);
+
+    let mut top = Cell::new("top");
+    let bus = top.add_node("bus");
+    for i in 0..2 {
+        let mut inst = Instance::new(format!("xdrv{i}"), drv);
+        inst.connect("out", bus);
+        top.add_instance(inst);
+    }
+    lib.add_cell(top);
+
+    let multiple_drivers = |lib: &LibraryBuilder<StringSchema>| {
+        lib.validate_drivers()
+            .iter()
+            .filter(|issue| issue.cause() == &Cause::MultipleDrivers)
+            .count()
+    };
+    assert_eq!(multiple_drivers(&lib), 1);
+    lib.set_driver_config(
+        DriverConfig::new()
+            .allow_multiple_drivers("top", "bus")
+            .severity(Cause::MultipleDrivers, Severity::Error),
+    );
+    assert_eq!(multiple_drivers(&lib), 0);
+    lib.set_driver_config(DriverConfig::new().severity(Cause::MultipleDrivers, Severity::Error));
+    assert!(lib.validate_drivers().has_error());
+}
+
+#[test]
+fn validation_issues_report_source_info() {
+    let mut lib = LibraryBuilder::<StringSchema>::new();
+    let mut child = Cell::new("child");
+    let a = child.add_node("a");
+    child.expose_port(a, Direction::InOut);
+    let child = lib.add_cell(child);
+
+    let mut parent = Cell::new("parent");
+    parent.set_source_info(SourceInfo::from_block("blocks::Parent"));
+    let source_info = SourceInfo::new("src/parent.rs", 12, 9).with_block("blocks::Parent");
+    let mut inst = Instance::new("xchild", child);
+    inst.set_source_info(source_info.clone());
+    parent.add_instance(inst);
+    let parent = lib.add_cell(parent);
+    assert_eq!(
+        lib.cell(parent).instance_named("xchild").source_info(),
+        Some(&source_info)
+    );
+
+    let issues = lib.validate();
+    assert_eq!(issues.num_errors(), 1);
+    let issue = issues.iter().next().unwrap();
+    assert!(matches!(
+        issue.cause(),
+        validation::Cause::UnconnectedPort { port, .. } if port.as_str() == "a"
+    ));
+    assert_eq!(issue.source_info(), Some(&source_info));
+    assert!(issue
+        .to_string()
+        .ends_with("(generated by src/parent.rs:12:9 (in block `blocks::Parent`))"));
+}
+
+#[test]
+fn schema_conversion_with_support_cells() {
+    use crate::schema::{ConversionContext, DuplicateCellNameError};
+
+    /// A schema in which resistors must be instantiated through a wrapper subcircuit.
+    pub struct WrappedSchema;
+
+    impl Schema for WrappedSchema {
+        type Primitive = ArcStr;
+    }
+
+    impl FromSchema<StringSchema> for WrappedSchema {
+        type Error = ();
+
+        fn convert_primitive(
+            primitive: <StringSchema as Schema>::Primitive,
+        ) -> Result<<Self as Schema>::Primitive, Self::Error> {
+            Ok(primitive)
+        }
+
+        fn convert_instance(
+            _instance: &mut Instance,
+            _primitive: &<StringSchema as Schema>::Primitive,
+        ) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn convert_instance_in(
+            ctx: &mut ConversionContext<'_, Self>,
+            instance: &mut Instance,
+            primitive: &<StringSchema as Schema>::Primitive,
+        ) -> Result<(), Self::Error> {
+            if primitive.as_str() != "res" {
+                return Ok(());
+            }
+            let wrapper = match ctx.try_cell_id_named("res_wrapper") {
+                Some(id) => id,
+                None => {
+                    let mut cell = Cell::new("res_wrapper");
+                    let p = cell.add_node("p");
+                    let n = cell.add_node("n");
+                    cell.expose_port(p, Direction::InOut);
+                    cell.expose_port(n, Direction::InOut);
+                    let mut inner = Instance::new("xres", instance.child());
+                    inner.connect("p", p);
+                    inner.connect("n", n);
+                    cell.add_instance(inner);
+                    ctx.add_cell(cell).unwrap()
+                }
+            };
+            assert_eq!(ctx.cell(wrapper).name().as_str(), "res_wrapper");
+            assert_eq!(
+                ctx.add_cell(Cell::new("res_wrapper")),
+                Err(DuplicateCellNameError("res_wrapper".into()))
+            );
+            assert_eq!(
+                ctx.add_cell(Cell::new("top")),
+                Err(DuplicateCellNameError("top".into()))
+            );
+            instance.set_child(wrapper);
+            Ok(())
+        }
+    }
+
+    let mut lib = LibraryBuilder::<StringSchema>::new();
+    let res = lib.add_primitive("res".into());
+    let cap = lib.add_primitive("cap".into());
+
+    let mut cell = Cell::new("top");
+    let a = cell.add_node("a");
+    let b = cell.add_node("b");
+    cell.expose_port(a, Direction::InOut);
+    cell.expose_port(b, Direction::InOut);
+    let mut insts = Vec::new();
+    for (name, child) in [("xres1", res), ("xres2", res), ("xcap", cap)] {
+        let mut inst = Instance::new(name, child);
+        inst.connect("p", a);
+        inst.connect("n", b);
+        insts.push(cell.add_instance(inst));
+    }
+    let top = lib.add_cell(cell);
+
+    let wlib = lib.convert_schema::<WrappedSchema>().unwrap();
+    let wrapper = wlib.cell_id_named("res_wrapper");
+    assert_eq!(wlib.cells().count(), 2);
+    let top = wlib.cell(top);
+    assert_eq!(top.instance(insts[0]).child(), ChildId::Cell(wrapper));
+    assert_eq!(top.instance(insts[1]).child(), ChildId::Cell(wrapper));
+    assert_eq!(top.instance(insts[2]).child(), ChildId::Primitive(cap));
+    assert_eq!(
+        wlib.cell(wrapper).instance_named("xres").child(),
+        ChildId::Primitive(res)
+    );
+    assert_eq!(wlib.primitive(res).as_str(), "res");
+    assert!(wlib.validate().is_empty());
+}
+
+#[test]
+fn bidirectional_conversion_table() {
+    use crate::mapping::{PrimitiveParts, TabularPrimitive, UnmappedKindError};
+
+    #[derive(Clone, Debug, Eq, PartialEq)]
+    pub struct Device(PrimitiveParts);
+
+    impl TabularPrimitive for Device {
+        fn kind(&self) -> &str {
+            &self.0.kind
+        }
+
+        fn into_parts(self) -> PrimitiveParts {
+            self.0
+        }
+
+        fn from_parts(parts: PrimitiveParts) -> Self {
+            Self(parts)
+        }
+    }
+
+    pub struct Pdk;
+    pub struct Simulator;
+
+    impl Schema for Pdk {
+        type Primitive = Device;
+    }
+
+    impl Schema for Simulator {
+        type Primitive = Device;
+    }
+
+    crate::bidirectional_conversion!(Pdk <=> Simulator {
+        "nfet_01v8" <=> "nch" {
+            ports: ["d" <=> "D", "g" <=> "G", "s" <=> "S", "b" <=> "B"],
+            params: ["w" <=> "W", "l" <=> "L"],
+        },
+        "res" <=> "res" { ports: [], params: [] },
+    });
+
+    let nfet = Device(PrimitiveParts {
+        kind: "nfet_01v8".into(),
+        ports: ["d", "g", "s", "b"].into_iter().map(ArcStr::from).collect(),
+        params: vec![
+            (
+                "w".into(),
+                ParamValue::Numeric(rust_decimal::Decimal::from(1)),
+            ),
+            (
+                "nf".into(),
+                ParamValue::Numeric(rust_decimal::Decimal::from(2)),
+            ),
+        ],
+    });
+    let res = Device(PrimitiveParts {
+        kind: "res".into(),
+        ports: vec!["p".into(), "n".into()],
+        params: Vec::new(),
+    });
+
+    let mut lib = LibraryBuilder::<Pdk>::new();
+    let nfet_id = lib.add_primitive(nfet.clone());
+    let res_id = lib.add_primitive(res.clone());
+    let mut cell = Cell::new("top");
+    let x = cell.add_node("x");
+    let mut mn = Instance::new("mn", nfet_id);
+    for port in ["d", "g", "s", "b"] {
+        mn.connect(port, x);
+    }
+    let mn = cell.add_instance(mn);
+    let mut rr = Instance::new("rr", res_id);
+    rr.connect("p", x);
+    rr.connect("n", x);
+    let rr = cell.add_instance(rr);
+    let top = lib.add_cell(cell);
+
+    let sim = lib.convert_schema::<Simulator>().unwrap();
+    let converted = &sim.primitive(nfet_id).0;
+    assert_eq!(converted.kind.as_str(), "nch");
+    assert_eq!(
+        converted
+            .ports
+            .iter()
+            .map(|p| p.as_str())
+            .collect::<Vec<_>>(),
+        vec!["D", "G", "S", "B"]
+    );
+    assert_eq!(converted.params[0].0.as_str(), "W");
+    assert_eq!(converted.params[1].0.as_str(), "nf");
+    assert_eq!(sim.primitive(res_id), &res);
+    let mut ports = sim
+        .cell(top)
+        .instance(mn)
+        .connections()
+        .keys()
+        .map(|port| port.as_str())
+        .collect::<Vec<_>>();
+    ports.sort();
+    assert_eq!(ports, vec!["B", "D", "G", "S"]);
+    assert!(sim.cell(top).instance(rr).connections().contains_key("p"));
+
+    let mut pdk = sim.convert_schema::<Pdk>().unwrap();
+    assert_eq!(pdk.primitive(nfet_id), &nfet);
+    assert!(pdk.cell(top).instance(mn).connections().contains_key("d"));
+
+    // Kinds that are not listed in the table cannot be converted.
+    pdk.add_primitive(Device(PrimitiveParts {
+        kind: "cap".into(),
+        ..Default::default()
+    }));
+    assert_eq!(
+        pdk.convert_schema::<Simulator>().err(),
+        Some(UnmappedKindError("cap".into()))
+    );
+}
@@ -15,6 +15,9 @@ use super::*;
 pub struct ValidatorIssue {
     cause: Cause,
     severity: Severity,
+    /// The generator code responsible for the issue, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source_info: Option<SourceInfo>,
 }
 
 /// The cause of a SCIR error or warning.
@@ -62,6 +65,24 @@ pub enum Cause {
         /// The name of the offending cell.
         cell_name: ArcStr,
     },
+    /// A cell references a global net that is not declared in the library.
+    UndeclaredGlobal {
+        /// The name of the global net.
+        name: ArcStr,
+        /// The ID of the offending cell.
+        cell_id: CellId,
+        /// The name of the offending cell.
+        cell_name: ArcStr,
+    },
+    /// A global net is exposed as a port.
+    GlobalPort {
+        /// The name of the global net.
+        name: ArcStr,
+        /// The ID of the offending cell.
+        cell_id: CellId,
+        /// The name of the offending cell.
+        cell_name: ArcStr,
+    },
     /// A signal identifier is used but not declared.
     MissingSignal {
         /// The ID of the signal.
@@ -159,9 +180,58 @@ pub enum Cause {
         child_cell_id: CellId,
         /// The name of the child cell.
         child_cell_name: ArcStr,
+        /// The parts of the connection.
+        parts: Vec<ConnectionPart>,
+        /// A signal in the parent cell that may have been intended instead, if any.
+        suggestion: Option<ArcStr>,
+    },
+    /// An instance assigns a parameter that its child does not declare.
+    UndeclaredParam {
+        /// The name of the parameter.
+        param: ArcStr,
+        /// The ID of the child.
+        child_id: ChildId,
+        /// The ID of the cell containing the offending instance.
+        parent_cell_id: CellId,
+        /// The name of the cell containing the offending instance.
+        parent_cell_name: ArcStr,
+        /// The name of the offending instance.
+        instance_name: ArcStr,
+    },
+    /// An instance parameter refers to a parameter that the parent cell does not declare.
+    UnknownParamReference {
+        /// The name of the referenced parameter.
+        param: ArcStr,
+        /// The ID of the cell containing the offending instance.
+        cell_id: CellId,
+        /// The name of the cell containing the offending instance.
+        cell_name: ArcStr,
+        /// The name of the offending instance.
+        instance_name: ArcStr,
     },
 }
 
+/// A part of a [`Concat`] connected to an instance port.
+#[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ConnectionPart {
+    /// The name of the signal.
+    pub signal_name: ArcStr,
+    /// The range of bus indices, if the signal is a bus.
+    pub range: Option<SliceRange>,
+    /// The width of this part.
+    pub width: usize,
+}
+
+impl Display for ConnectionPart {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "`{}", self.signal_name)?;
+        if let Some(range) = self.range {
+            write!(f, "[{}..{}]", range.start, range.end)?;
+        }
+        write!(f, "` (width {})", self.width)
+    }
+}
+
 impl Diagnostic for ValidatorIssue {
     fn severity(&self) -> Severity {
         self.severity
@@ -171,7 +241,11 @@ impl Diagnostic for ValidatorIssue {
 impl ValidatorIssue {
     /// Creates a new validator issue from the given cause and severity.
     pub(crate) fn new(cause: Cause, severity: Severity) -> Self {
-        Self { cause, severity }
+        Self {
+            cause,
+            severity,
+            source_info: None,
+        }
     }
 
     /// Gets the underlying cause of this issue.
@@ -180,11 +254,32 @@ impl ValidatorIssue {
         &self.cause
     }
 
+    /// The generator code responsible for this issue, if known.
+    ///
+    /// See [`SourceInfo`].
+    #[inline]
+    pub fn source_info(&self) -> Option<&SourceInfo> {
+        self.source_info.as_ref()
+    }
+
     /// Creates a new validator issue and logs it immediately.
     ///
     /// The log level will be selected according to the given severity.
     pub(crate) fn new_and_log(cause: Cause, severity: Severity) -> Self {
-        let result = Self::new(cause, severity);
+        Self::new_and_log_at(cause, severity, None)
+    }
+
+    /// Creates a new validator issue caused by the object created by the given
+    /// generator code and logs it immediately.
+    pub(crate) fn new_and_log_at(
+        cause: Cause,
+        severity: Severity,
+        source_info: Option<&SourceInfo>,
+    ) -> Self {
+        let result = Self {
+            source_info: source_info.cloned(),
+            ..Self::new(cause, severity)
+        };
         match severity {
             Severity::Info => tracing::event!(Level::INFO, issue = ?result.cause, "{}", result),
             Severity::Warning => tracing::event!(Level::WARN, issue = ?result.cause, "{}", result),
@@ -196,7 +291,11 @@ impl ValidatorIssue {
 
 impl Display for ValidatorIssue {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.cause)
+        write!(f, "{}", self.cause)?;
+        if let Some(source_info) = &self.source_info {
+            write!(f, " (generated by {source_info})")?;
+        }
+        Ok(())
     }
 }
 
@@ -228,6 +327,22 @@ impl Display for Cause {
                     cell_name
                 ),
 
+            Self::UndeclaredGlobal { name, cell_name, .. } =>
+                write!(
+                    f,
+                    "undeclared global net: cell `{}` references global net `{}`, but the library does not declare it",
+                    cell_name,
+                    name
+                ),
+
+            Self::GlobalPort { name, cell_name, .. } =>
+                write!(
+                    f,
+                    "global port: global net `{}` is exposed as a port of cell `{}`",
+                    name,
+                    cell_name
+                ),
+
             Self::MissingSignal { id, cell_name, .. } =>
                 write!(
                     f,
@@ -290,7 +405,7 @@ impl Display for Cause {
                     cell_name
                 ),
 
-            Self::PortWidthMismatch { expected_width, actual_width, instance_name, port, parent_cell_name, child_cell_name, .. } =>
+            Self::PortWidthMismatch { expected_width, actual_width, instance_name, port, parent_cell_name, child_cell_name, parts, suggestion, .. } => {
                 write!(
                     f,
                     "mismatched port width: instance `{}` in cell `{}` specifies a connection to port `{}` of cell `{}` of width {}, but the expected width is {}",
@@ -300,89 +415,149 @@ impl Display for Cause {
                     child_cell_name,
                     actual_width,
                     expected_width
+                )?;
+                if !parts.is_empty() {
+                    write!(f, "; connected parts: ")?;
+                    for (i, part) in parts.iter().enumerate() {
+                        if i > 0 {
+                            write!(f, ", ")?;
+                        }
+                        write!(f, "{part}")?;
+                    }
+                }
+                if let Some(suggestion) = suggestion {
+                    write!(f, "; did you mean `{suggestion}`?")?;
+                }
+                Ok(())
+            }
+
+            Self::UndeclaredParam { param, child_id, parent_cell_name, instance_name, .. } =>
+                write!(
+                    f,
+                    "undeclared parameter: instance `{}` in cell `{}` assigns parameter `{}`, but its child ({}) does not declare it",
+                    instance_name,
+                    parent_cell_name,
+                    param,
+                    child_id
+                ),
+
+            Self::UnknownParamReference { param, cell_name, instance_name, .. } =>
+                write!(
+                    f,
+                    "unknown parameter reference: instance `{}` in cell `{}` refers to parameter `{}`, but cell `{}` does not declare it",
+                    instance_name,
+                    cell_name,
+                    param,
+                    cell_name
                 ),
 
         }
     }
 }
 
-impl<S: Schema + ?Sized> LibraryBuilder<S> {
-    /// Check whether this library is valid.
-    pub fn validate(&self) -> IssueSet<ValidatorIssue> {
-        let _guard = span!(Level::INFO, "validating SCIR Library").entered();
-        let mut issues = IssueSet::new();
-        self.validate1(&mut issues);
+/// Per-cell validation results cached between calls to [`LibraryBuilder::validate`].
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ValidationCache {
+    /// Cells that were replaced since the last validation.
+    dirty: HashSet<CellId>,
+    /// Whether all cached results are invalid, for example because primitives or
+    /// global nets changed since the last validation.
+    stale: bool,
+    /// The issues found in each cell by pass 1.
+    pass1: HashMap<CellId, Vec<ValidatorIssue>>,
+    /// The issues found in each cell by pass 2.
+    pass2: HashMap<CellId, Vec<ValidatorIssue>>,
+}
 
-        if issues.has_error() {
-            return issues;
-        }
+impl ValidationCache {
+    /// Marks the cell with the given ID as changed.
+    pub(crate) fn invalidate(&mut self, id: CellId) {
+        self.dirty.insert(id);
+    }
 
-        self.validate2(&mut issues);
-        issues
+    /// Marks all cached results as invalid.
+    pub(crate) fn invalidate_all(&mut self) {
+        self.stale = true;
     }
+}
 
-    fn validate1(&self, issues: &mut IssueSet<ValidatorIssue>) {
-        let _guard = span!(
-            Level::INFO,
-            "validation pass 1 (checking signal and port identifier validity)"
-        )
-        .entered();
+/// The parts of a library needed to validate its cells, shared between validation threads.
+struct CellValidator<'a> {
+    cells: &'a IndexMap<CellId, Cell>,
+    globals: &'a IndexSet<ArcStr>,
+    primitives: HashSet<PrimitiveId>,
+    /// The primitives whose instances may assign parameters.
+    param_primitives: HashSet<PrimitiveId>,
+}
 
-        let mut cell_names = HashMap::new();
-        for (id, cell) in self.cells.iter() {
-            self.validate_cell1(*id, issues);
-            if let Some(id1) = cell_names.insert(cell.name.clone(), id) {
-                let issue = ValidatorIssue::new_and_log(
-                    Cause::DuplicateCellNames {
-                        id1: *id1,
-                        id2: *id,
-                        name: cell.name.clone(),
-                    },
-                    Severity::Error,
-                );
-                issues.add(issue);
-            }
-        }
-    }
+/// The minimum number of cells to validate before spreading work across threads.
+const MIN_PARALLEL_CELLS: usize = 64;
+
+impl CellValidator<'_> {
+    /// Runs `validate` on each of the given cells, in parallel if there are enough of them.
+    fn validate_cells(
+        &self,
+        ids: &[CellId],
+        validate: impl Fn(&Self, CellId, &mut IssueSet<ValidatorIssue>) + Sync,
+    ) -> Vec<(CellId, Vec<ValidatorIssue>)> {
+        let validate_chunk = |ids: &[CellId]| {
+            ids.iter()
+                .map(|&id| {
+                    let mut issues = IssueSet::new();
+                    validate(self, id, &mut issues);
+                    (id, issues.into_iter().collect())
+                })
+                .collect::<Vec<_>>()
+        };
 
-    fn validate2(&self, issues: &mut IssueSet<ValidatorIssue>) {
-        let _guard = span!(
-            Level::INFO,
-            "validation pass 2 (checking connection validity)"
-        )
-        .entered();
-        for id in self.cells.keys().copied() {
-            self.validate_cell2(id, issues);
+        let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+        if threads == 1 || ids.len() < MIN_PARALLEL_CELLS {
+            return validate_chunk(ids);
         }
+        let chunk_size = ids.len().div_ceil(threads);
+        // Workers do not inherit the caller's span, so enter it explicitly.
+        let span = tracing::Span::current();
+        std::thread::scope(|scope| {
+            let handles = ids
+                .chunks(chunk_size)
+                .map(|chunk| scope.spawn(|| span.in_scope(|| validate_chunk(chunk))))
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().unwrap())
+                .collect()
+        })
     }
-
     fn validate_cell1(&self, id: CellId, issues: &mut IssueSet<ValidatorIssue>) {
         let cell = self.cells.get(&id).unwrap();
         let _guard =
             span!(Level::INFO, "validating SCIR cell (pass 1)", cell.id = %id, cell.name = %cell.name)
                 .entered();
 
-        let invalid_signal = |signal_id: SignalId| {
-            ValidatorIssue::new_and_log(
+        let invalid_signal = |signal_id: SignalId, source_info: Option<&SourceInfo>| {
+            ValidatorIssue::new_and_log_at(
                 Cause::MissingSignal {
                     id: signal_id,
                     cell_id: id,
                     cell_name: cell.name.clone(),
                 },
                 Severity::Error,
+                source_info.or(cell.source_info()),
             )
         };
 
         let mut inst_names = HashSet::new();
         for (_id, instance) in cell.instances.iter() {
+            let source_info = instance.source_info().or(cell.source_info());
             if inst_names.contains(&instance.name) {
-                issues.add(ValidatorIssue::new_and_log(
+                issues.add(ValidatorIssue::new_and_log_at(
                     Cause::DuplicateInstanceNames {
                         inst_name: instance.name.clone(),
                         cell_id: id,
                         cell_name: cell.name.clone(),
                     },
                     Severity::Error,
+                    source_info,
                 ));
             }
             inst_names.insert(instance.name.clone());
@@ -391,7 +566,7 @@ impl<S: Schema + ?Sized> LibraryBuilder<S> {
                     let signal = match cell.signals.get(&part.signal()) {
                         Some(signal) => signal,
                         None => {
-                            issues.add(invalid_signal(part.signal()));
+                            issues.add(invalid_signal(part.signal(), source_info));
                             continue;
                         }
                     };
@@ -400,7 +575,7 @@ impl<S: Schema + ?Sized> LibraryBuilder<S> {
                     match (signal.width, part.range()) {
                         (Some(width), Some(range)) => {
                             if range.end > width {
-                                issues.add(ValidatorIssue::new_and_log(
+                                issues.add(ValidatorIssue::new_and_log_at(
                                     Cause::IndexOutOfBounds {
                                         idx: range.end,
                                         width,
@@ -408,27 +583,30 @@ impl<S: Schema + ?Sized> LibraryBuilder<S> {
                                         cell_name: cell.name.clone(),
                                     },
                                     Severity::Error,
+                                    source_info,
                                 ));
                             }
                         }
                         (Some(_), None) => {
-                            issues.add(ValidatorIssue::new_and_log(
+                            issues.add(ValidatorIssue::new_and_log_at(
                                 Cause::MissingIndex {
                                     signal_name: signal.name.clone(),
                                     cell_id: id,
                                     cell_name: cell.name.clone(),
                                 },
                                 Severity::Error,
+                                source_info,
                             ));
                         }
                         (None, Some(_)) => {
-                            issues.add(ValidatorIssue::new_and_log(
+                            issues.add(ValidatorIssue::new_and_log_at(
                                 Cause::IndexedWire {
                                     signal_name: signal.name.clone(),
                                     cell_id: id,
                                     cell_name: cell.name.clone(),
                                 },
                                 Severity::Error,
+                                source_info,
                             ));
                         }
                         (None, None) => {}
@@ -440,28 +618,57 @@ impl<S: Schema + ?Sized> LibraryBuilder<S> {
         let mut port_signals = HashSet::with_capacity(cell.ports.len());
         for port in cell.ports() {
             if !cell.signals.contains_key(&port.signal) {
-                issues.add(invalid_signal(port.signal));
+                issues.add(invalid_signal(port.signal, None));
                 continue;
             }
 
             if !port_signals.insert(port.signal) {
-                let issue = ValidatorIssue::new_and_log(
+                let signal = cell.signals.get(&port.signal).unwrap();
+                let issue = ValidatorIssue::new_and_log_at(
                     Cause::ShortedPorts {
                         signal: port.signal,
-                        name: cell.signals.get(&port.signal).unwrap().name.clone(),
+                        name: signal.name.clone(),
                         cell_id: id,
                         cell_name: cell.name.clone(),
                     },
                     Severity::Error,
+                    signal.source_info.as_ref().or(cell.source_info()),
                 );
                 issues.add(issue);
             }
         }
 
+        for (_, signal) in cell.signals().filter(|(_, signal)| signal.is_global()) {
+            let source_info = signal.source_info.as_ref().or(cell.source_info());
+            if !self.globals.contains(&signal.name) {
+                issues.add(ValidatorIssue::new_and_log_at(
+                    Cause::UndeclaredGlobal {
+                        name: signal.name.clone(),
+                        cell_id: id,
+                        cell_name: cell.name.clone(),
+                    },
+                    Severity::Error,
+                    source_info,
+                ));
+            }
+            if signal.is_port() {
+                issues.add(ValidatorIssue::new_and_log_at(
+                    Cause::GlobalPort {
+                        name: signal.name.clone(),
+                        cell_id: id,
+                        cell_name: cell.name.clone(),
+                    },
+                    Severity::Error,
+                    source_info,
+                ));
+            }
+        }
+
         let mut signal_names = HashMap::new();
         for (signal_id, signal) in cell.signals() {
+            let source_info = signal.source_info.as_ref().or(cell.source_info());
             if let Some(other) = signal_names.insert(&signal.name, signal_id) {
-                let issue = ValidatorIssue::new_and_log(
+                let issue = ValidatorIssue::new_and_log_at(
                     Cause::DuplicateSignalNames {
                         id1: signal_id,
                         id2: other,
@@ -470,6 +677,7 @@ impl<S: Schema + ?Sized> LibraryBuilder<S> {
                         cell_name: cell.name().clone(),
                     },
                     Severity::Error,
+                    source_info,
                 );
                 issues.add(issue);
             }
@@ -483,12 +691,53 @@ impl<S: Schema + ?Sized> LibraryBuilder<S> {
                 .entered();
 
         for (_id, instance) in cell.instances.iter() {
+            let source_info = instance.source_info().or(cell.source_info());
+            for (name, value) in instance.params.iter() {
+                let declared = match instance.child {
+                    ChildId::Cell(c) => self
+                        .cells
+                        .get(&c)
+                        .map(|child| child.params.contains_key(name))
+                        .unwrap_or(true),
+                    ChildId::Primitive(p) => self.param_primitives.contains(&p),
+                };
+                if !declared {
+                    let issue = ValidatorIssue::new_and_log_at(
+                        Cause::UndeclaredParam {
+                            param: name.clone(),
+                            child_id: instance.child,
+                            parent_cell_id: id,
+                            parent_cell_name: cell.name.clone(),
+                            instance_name: instance.name.clone(),
+                        },
+                        Severity::Error,
+                        source_info,
+                    );
+                    issues.add(issue);
+                }
+                if let Expr::Param(param) = value {
+                    if !cell.params.contains_key(param) {
+                        let issue = ValidatorIssue::new_and_log_at(
+                            Cause::UnknownParamReference {
+                                param: param.clone(),
+                                cell_id: id,
+                                cell_name: cell.name.clone(),
+                                instance_name: instance.name.clone(),
+                            },
+                            Severity::Error,
+                            source_info,
+                        );
+                        issues.add(issue);
+                    }
+                }
+            }
+
             match instance.child {
                 ChildId::Cell(c) => {
                     let child = match self.cells.get(&c) {
                         Some(child) => child,
                         None => {
-                            let issue = ValidatorIssue::new_and_log(
+                            let issue = ValidatorIssue::new_and_log_at(
                                 Cause::MissingChild {
                                     child_id: c.into(),
                                     parent_cell_id: id,
@@ -496,6 +745,7 @@ impl<S: Schema + ?Sized> LibraryBuilder<S> {
                                     instance_name: instance.name.clone(),
                                 },
                                 Severity::Error,
+                                source_info,
                             );
                             issues.add(issue);
                             continue;
@@ -511,7 +761,7 @@ impl<S: Schema + ?Sized> LibraryBuilder<S> {
                             Some(conn) => {
                                 let expected_width = child.signals[&port.signal].width.unwrap_or(1);
                                 if conn.width() != expected_width {
-                                    let issue = ValidatorIssue::new_and_log(
+                                    let issue = ValidatorIssue::new_and_log_at(
                                         Cause::PortWidthMismatch {
                                             expected_width,
                                             actual_width: conn.width(),
@@ -521,14 +771,31 @@ impl<S: Schema + ?Sized> LibraryBuilder<S> {
                                             child_cell_name: child.name.clone(),
                                             parent_cell_name: cell.name.clone(),
                                             parent_cell_id: id,
+                                            parts: conn
+                                                .parts()
+                                                .map(|part| ConnectionPart {
+                                                    signal_name: cell.signals[&part.signal()]
+                                                        .name
+                                                        .clone(),
+                                                    range: part.range(),
+                                                    width: part.width(),
+                                                })
+                                                .collect(),
+                                            suggestion: suggest_signal(
+                                                cell,
+                                                name,
+                                                expected_width,
+                                                conn.width(),
+                                            ),
                                         },
                                         Severity::Error,
+                                        source_info,
                                     );
                                     issues.add(issue);
                                 }
                             }
                             None => {
-                                let issue = ValidatorIssue::new_and_log(
+                                let issue = ValidatorIssue::new_and_log_at(
                                     Cause::UnconnectedPort {
                                         child_cell_id: instance.child.unwrap_cell(),
                                         child_cell_name: child.name.clone(),
@@ -538,6 +805,7 @@ impl<S: Schema + ?Sized> LibraryBuilder<S> {
                                         instance_name: instance.name.clone(),
                                     },
                                     Severity::Error,
+                                    source_info,
                                 );
                                 issues.add(issue);
                             }
@@ -547,7 +815,7 @@ impl<S: Schema + ?Sized> LibraryBuilder<S> {
                     // Check for extra ports
                     for conn in instance.connections.keys() {
                         if !child_ports.contains(conn) {
-                            let issue = ValidatorIssue::new_and_log(
+                            let issue = ValidatorIssue::new_and_log_at(
                                 Cause::ExtraPort {
                                     child_cell_id: instance.child.unwrap_cell(),
                                     child_cell_name: child.name.clone(),
@@ -557,14 +825,15 @@ impl<S: Schema + ?Sized> LibraryBuilder<S> {
                                     instance_name: instance.name.clone(),
                                 },
                                 Severity::Error,
+                                source_info,
                             );
                             issues.add(issue);
                         }
                     }
                 }
                 ChildId::Primitive(p) => {
-                    if self.try_primitive(p).is_none() {
-                        let issue = ValidatorIssue::new_and_log(
+                    if !self.primitives.contains(&p) {
+                        let issue = ValidatorIssue::new_and_log_at(
                             Cause::MissingChild {
                                 child_id: p.into(),
                                 parent_cell_id: id,
@@ -572,6 +841,7 @@ impl<S: Schema + ?Sized> LibraryBuilder<S> {
                                 instance_name: instance.name.clone(),
                             },
                             Severity::Error,
+                            source_info,
                         );
                         issues.add(issue);
                     }
@@ -580,3 +850,158 @@ impl<S: Schema + ?Sized> LibraryBuilder<S> {
         }
     }
 }
+
+impl<S: Schema + ?Sized> LibraryBuilder<S> {
+    /// Check whether this library is valid.
+    ///
+    /// Cells are validated in parallel. Results are cached for each cell, so calling this
+    /// method again only revalidates cells added or replaced since the last call, along with
+    /// the cells that instantiate them. Issues found in cached cells are not logged again.
+    pub fn validate(&self) -> IssueSet<ValidatorIssue> {
+        let _guard = span!(Level::INFO, "validating SCIR Library").entered();
+        let mut cache = self.lock_validation_cache();
+        self.refresh_validation_cache(&mut cache);
+
+        let validator = CellValidator {
+            cells: &self.cells,
+            globals: &self.globals,
+            primitives: self.primitives.keys().copied().collect(),
+            param_primitives: self
+                .primitives
+                .iter()
+                .filter(|(_, primitive)| S::supports_instance_params(primitive))
+                .map(|(id, _)| *id)
+                .collect(),
+        };
+        let mut issues = IssueSet::new();
+        self.validate1(&validator, &mut cache, &mut issues);
+
+        if issues.has_error() {
+            return issues;
+        }
+
+        self.validate2(&validator, &mut cache, &mut issues);
+        issues
+    }
+
+    /// Removes cached results that may have been affected by changes to the library.
+    fn refresh_validation_cache(&self, cache: &mut ValidationCache) {
+        if std::mem::take(&mut cache.stale) {
+            cache.pass1.clear();
+            cache.pass2.clear();
+        }
+
+        let mut changed = std::mem::take(&mut cache.dirty);
+        changed.extend(self.cells.keys().filter(|id| !cache.pass1.contains_key(id)));
+        if changed.is_empty() {
+            return;
+        }
+        for id in changed.iter() {
+            cache.pass1.remove(id);
+            cache.pass2.remove(id);
+        }
+        // Pass 2 checks instances against the ports of their children.
+        if !cache.pass2.is_empty() {
+            for (id, cell) in self.cells.iter() {
+                if cell.instances.iter().any(|(_, inst)| {
+                    inst.child
+                        .into_cell()
+                        .is_some_and(|child| changed.contains(&child))
+                }) {
+                    cache.pass2.remove(id);
+                }
+            }
+        }
+    }
+
+    fn validate1(
+        &self,
+        validator: &CellValidator<'_>,
+        cache: &mut ValidationCache,
+        issues: &mut IssueSet<ValidatorIssue>,
+    ) {
+        let _guard = span!(
+            Level::INFO,
+            "validation pass 1 (checking signal and port identifier validity)"
+        )
+        .entered();
+
+        let ids = self
+            .cells
+            .keys()
+            .filter(|id| !cache.pass1.contains_key(id))
+            .copied()
+            .collect::<Vec<_>>();
+        cache
+            .pass1
+            .extend(validator.validate_cells(&ids, CellValidator::validate_cell1));
+
+        let mut cell_names = HashMap::new();
+        for (id, cell) in self.cells.iter() {
+            for issue in cache.pass1[id].iter() {
+                issues.add(issue.clone());
+            }
+            if let Some(id1) = cell_names.insert(cell.name.clone(), id) {
+                let issue = ValidatorIssue::new_and_log_at(
+                    Cause::DuplicateCellNames {
+                        id1: *id1,
+                        id2: *id,
+                        name: cell.name.clone(),
+                    },
+                    Severity::Error,
+                    cell.source_info(),
+                );
+                issues.add(issue);
+            }
+        }
+    }
+
+    fn validate2(
+        &self,
+        validator: &CellValidator<'_>,
+        cache: &mut ValidationCache,
+        issues: &mut IssueSet<ValidatorIssue>,
+    ) {
+        let _guard = span!(
+            Level::INFO,
+            "validation pass 2 (checking connection validity)"
+        )
+        .entered();
+
+        let ids = self
+            .cells
+            .keys()
+            .filter(|id| !cache.pass2.contains_key(id))
+            .copied()
+            .collect::<Vec<_>>();
+        cache
+            .pass2
+            .extend(validator.validate_cells(&ids, CellValidator::validate_cell2));
+
+        for id in self.cells.keys() {
+            for issue in cache.pass2[id].iter() {
+                issues.add(issue.clone());
+            }
+        }
+    }
+}
+
+/// Suggests a signal in `cell` to connect to `port` in place of a connection of width
+/// `actual_width`.
+///
+/// Returns the signal whose width is nearest to `expected_width`, preferring a signal
+/// with the same name as the port. Only signals that are a closer match than the existing
+/// connection are suggested.
+fn suggest_signal(
+    cell: &Cell,
+    port: &str,
+    expected_width: usize,
+    actual_width: usize,
+) -> Option<ArcStr> {
+    let current = actual_width.abs_diff(expected_width);
+    cell.signals()
+        .map(|(_, info)| (info, info.width.unwrap_or(1).abs_diff(expected_width)))
+        .filter(|(_, dist)| *dist < current)
+        .min_by_key(|(info, dist)| (*dist, info.name.as_str() != port, info.name.clone()))
+        .map(|(info, _)| info.name.clone())
+}
@@ -0,0 +1,105 @@
+//! Measures the memory used while building large libraries and exporting them as SPICE netlists.
+//!
+//! Run with `cargo bench -p spice --bench netlist`. Numbers depend on the allocator and
+//! platform, so they are printed rather than checked.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use rust_decimal_macros::dec;
+use scir::netlist::ConvertibleNetlister;
+use scir::{Cell, Direction, Instance, LibraryBuilder};
+use spice::{ComponentValue, Primitive, Spice};
+
+/// An allocator that tracks the number of bytes currently allocated and its peak.
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let allocated = ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+        PEAK.fetch_max(allocated, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+const MIB: f64 = (1 << 20) as f64;
+
+/// Creates a library of `n` leaf cells with similarly named contents,
+/// all instantiated by a top cell.
+fn library(n: usize) -> LibraryBuilder<Spice> {
+    let mut lib = LibraryBuilder::<Spice>::new();
+    let res = lib.add_primitive(Primitive::Res2 {
+        value: ComponentValue::Fixed(dec!(100)),
+        params: Default::default(),
+        m: 1,
+    });
+
+    let mut top = Cell::new("top");
+    let vdd = top.add_node("vdd");
+    let vss = top.add_node("vss");
+    for i in 0..n {
+        let mut leaf = Cell::new(format!("leaf_{i}"));
+        let nodes = ["vdd", "a", "b", "vss"].map(|name| leaf.add_node(name));
+        for (j, pair) in nodes.windows(2).enumerate() {
+            let mut inst = Instance::new(format!("xinst{j}"), res);
+            inst.connect("1", pair[0]);
+            inst.connect("2", pair[1]);
+            leaf.add_instance(inst);
+        }
+        leaf.expose_port(nodes[0], Direction::InOut);
+        leaf.expose_port(nodes[3], Direction::InOut);
+        let leaf = lib.add_cell(leaf);
+
+        let mut inst = Instance::new(format!("xleaf{i}"), leaf);
+        inst.connect("vdd", vdd);
+        inst.connect("vss", vss);
+        top.add_instance(inst);
+    }
+    let top = lib.add_cell(top);
+    lib.set_top(top);
+    lib
+}
+
+fn main() {
+    for n in [1_000, 10_000, 100_000] {
+        let before = ALLOCATED.load(Ordering::Relaxed);
+        PEAK.store(before, Ordering::Relaxed);
+        let start = Instant::now();
+        let lib = library(n).build().unwrap();
+        let build = start.elapsed();
+        println!(
+            "{n:>7} cells: build  {build:>10.2?}, peak {:>7.1} MiB, library    {:>7.1} MiB",
+            (PEAK.load(Ordering::Relaxed) - before) as f64 / MIB,
+            (ALLOCATED.load(Ordering::Relaxed) - before) as f64 / MIB,
+        );
+
+        let before = ALLOCATED.load(Ordering::Relaxed);
+        PEAK.store(before, Ordering::Relaxed);
+        let start = Instant::now();
+        let conv = Spice
+            .write_scir_netlist(&lib, &mut std::io::sink(), Default::default())
+            .unwrap();
+        let export = start.elapsed();
+        let peak = PEAK.load(Ordering::Relaxed) - before;
+        let retained = ALLOCATED.load(Ordering::Relaxed) - before;
+
+        println!(
+            "{n:>7} cells: export {export:>10.2?}, peak {:>7.1} MiB, conversion {:>7.1} MiB ({} interned names)",
+            peak as f64 / MIB,
+            retained as f64 / MIB,
+            conv.names.len(),
+        );
+    }
+}
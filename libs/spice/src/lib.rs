@@ -113,6 +113,14 @@ impl StringPathSchema for Spice {
 
 impl Schema for Spice {
     type Primitive = Primitive;
+
+    fn multiply_primitive(primitive: &Primitive, m: u64) -> Option<Primitive> {
+        primitive.multiplied(m)
+    }
+
+    fn supports_instance_params(primitive: &Primitive) -> bool {
+        primitive.accepts_params()
+    }
 }
 
 impl FromSchema<NoSchema> for Spice {
@@ -159,11 +167,19 @@ pub enum Primitive {
         value: ComponentValue,
         /// Parameters associated with the resistor.
         params: HashMap<UniCase<ArcStr>, ParamValue>,
+        /// The multiplier, or number of identical devices in parallel.
+        ///
+        /// Only netlisted if not 1.
+        m: u64,
     },
     /// A capacitor primitive with ports "1" and "2" and value `value`.
     Cap2 {
         /// The capacitor value.
         value: Decimal,
+        /// The multiplier, or number of identical devices in parallel.
+        ///
+        /// Only netlisted if not 1.
+        m: u64,
     },
     /// A diode primitive with ports "1" and "2".
     Diode2 {
@@ -171,6 +187,10 @@ pub enum Primitive {
         model: ArcStr,
         /// Parameters associated with the diode.
         params: HashMap<UniCase<ArcStr>, ParamValue>,
+        /// The multiplier, or number of identical devices in parallel.
+        ///
+        /// Only netlisted if not 1.
+        m: u64,
     },
     /// A BJT primitive with ports "NC", "NB", and "NE".
     ///
@@ -182,6 +202,10 @@ pub enum Primitive {
         params: HashMap<UniCase<ArcStr>, ParamValue>,
         /// Whether the primitive has a substrate port.
         has_substrate_port: bool,
+        /// The multiplier, or number of identical devices in parallel.
+        ///
+        /// Only netlisted if not 1.
+        m: u64,
     },
     /// A MOS primitive with ports "D", "G", "S", and "B".
     Mos {
@@ -189,6 +213,10 @@ pub enum Primitive {
         model: ArcStr,
         /// Parameters associated with the MOS primitive.
         params: HashMap<UniCase<ArcStr>, ParamValue>,
+        /// The multiplier, or number of identical devices in parallel.
+        ///
+        /// Only netlisted if not 1.
+        m: u64,
     },
     /// A raw instance with an associated cell.
     RawInstance {
@@ -318,6 +346,77 @@ impl Primitive {
                 .collect(),
         }
     }
+
+    /// Returns the name of the externally defined model or subcircuit instantiated by this
+    /// primitive, if any.
+    ///
+    /// Primitives that define their own subcircuit, such as
+    /// [`Primitive::RawInstanceWithCell`], do not reference an external model.
+    pub fn model(&self) -> Option<&ArcStr> {
+        match self {
+            Primitive::Res2 {
+                value: ComponentValue::Model(model),
+                ..
+            }
+            | Primitive::Diode2 { model, .. }
+            | Primitive::Bjt { model, .. }
+            | Primitive::Mos { model, .. }
+            | Primitive::RawInstance { cell: model, .. } => Some(model),
+            Primitive::Res2 { .. }
+            | Primitive::Cap2 { .. }
+            | Primitive::RawInstanceWithCell { .. }
+            | Primitive::BlackboxInstance { .. }
+            | Primitive::RawInstanceWithInclude { .. } => None,
+        }
+    }
+
+    /// Returns a copy of this primitive representing `m` copies in parallel.
+    ///
+    /// Devices have their multiplier scaled by `m`. Raw instances have their `m` parameter
+    /// scaled by `m`, or set to `m` if not already present. Returns [`None`] for primitives that
+    /// cannot be multiplied.
+    pub fn multiplied(&self, m: u64) -> Option<Self> {
+        let mut primitive = self.clone();
+        match &mut primitive {
+            Primitive::Res2 { m: prev, .. }
+            | Primitive::Cap2 { m: prev, .. }
+            | Primitive::Diode2 { m: prev, .. }
+            | Primitive::Bjt { m: prev, .. }
+            | Primitive::Mos { m: prev, .. } => *prev *= m,
+            Primitive::RawInstance { params, .. }
+            | Primitive::RawInstanceWithCell { params, .. } => {
+                let value = match params.get(&UniCase::new(arcstr::literal!("m"))) {
+                    None => Decimal::from(m),
+                    Some(ParamValue::Numeric(prev)) => *prev * Decimal::from(m),
+                    Some(ParamValue::String(_)) => return None,
+                };
+                params.insert(
+                    UniCase::new(arcstr::literal!("m")),
+                    ParamValue::Numeric(value),
+                );
+            }
+            Primitive::BlackboxInstance { .. } | Primitive::RawInstanceWithInclude { .. } => {
+                return None
+            }
+        }
+        Some(primitive)
+    }
+
+    /// Returns `true` if instances of this primitive may be netlisted with
+    /// additional `NAME=VALUE` parameters.
+    pub fn accepts_params(&self) -> bool {
+        match self {
+            Primitive::Diode2 { .. }
+            | Primitive::Bjt { .. }
+            | Primitive::Mos { .. }
+            | Primitive::RawInstance { .. }
+            | Primitive::RawInstanceWithCell { .. }
+            | Primitive::RawInstanceWithInclude { .. } => true,
+            Primitive::Res2 { .. }
+            | Primitive::Cap2 { .. }
+            | Primitive::BlackboxInstance { .. } => false,
+        }
+    }
 }
 
 /// An ideal 2-terminal resistor.
@@ -355,6 +454,7 @@ impl Schematic for Resistor {
         let mut prim = substrate::schematic::PrimitiveBinding::new(Primitive::Res2 {
             value: ComponentValue::Fixed(self.value()),
             params: Default::default(),
+            m: 1,
         });
         prim.connect("1", io.p);
         prim.connect("2", io.n);
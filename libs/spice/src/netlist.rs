@@ -2,16 +2,20 @@
 
 use arcstr::ArcStr;
 use itertools::Itertools;
+use rust_decimal::Decimal;
 use scir::netlist::ConvertibleNetlister;
 use std::collections::{HashMap, HashSet};
 
 use std::io::{Result, Write};
 use std::path::PathBuf;
+use tracing::{span, Level};
+use uniquify::Names;
 
 use crate::{BlackboxElement, Primitive, Spice};
 use scir::schema::Schema;
 use scir::{
-    Cell, ChildId, Library, NetlistCellConversion, NetlistLibConversion, SignalInfo, Slice,
+    Cell, CellId, ChildId, Expr, Instance, InstanceId, Library, NetlistCellConversion,
+    NetlistLibConversion, Param, SignalId, SignalInfo, Slice, SourceInfo,
 };
 
 /// A netlist include statement.
@@ -58,6 +62,16 @@ pub trait HasSpiceLikeNetlist: Schema {
     ///
     /// A newline will be added afterward.
     fn write_include<W: Write>(&self, out: &mut W, include: &Include) -> Result<()>;
+    /// Writes a declaration of the global nets of the library.
+    ///
+    /// Called once per netlist, after includes, even if the library has no global nets.
+    /// Should include a newline after if needed.
+    fn write_globals<W: Write>(&self, out: &mut W, globals: &[&ArcStr]) -> Result<()> {
+        if !globals.is_empty() {
+            writeln!(out, ".GLOBAL {}", globals.iter().join(" "))?;
+        }
+        Ok(())
+    }
     /// Writes a begin subcircuit statement.
     ///
     /// A newline will be added afterward.
@@ -67,6 +81,22 @@ pub trait HasSpiceLikeNetlist: Schema {
         name: &ArcStr,
         ports: &[&SignalInfo],
     ) -> Result<()>;
+    /// Writes the parameters declared by a subcircuit.
+    ///
+    /// Called immediately after [`HasSpiceLikeNetlist::write_start_subckt`], only if the
+    /// cell declares at least one parameter. Defaults to appending `PARAMS: NAME=DEFAULT ...`
+    /// to the subcircuit statement.
+    fn write_subckt_params<W: Write>(
+        &self,
+        out: &mut W,
+        params: &[(&ArcStr, &Param)],
+    ) -> Result<()> {
+        write!(out, " PARAMS:")?;
+        for (name, param) in params {
+            write!(out, " {}={}", name, param.default)?;
+        }
+        Ok(())
+    }
     /// Writes an end subcircuit statement.
     ///
     /// A newline will be added afterward.
@@ -81,6 +111,25 @@ pub trait HasSpiceLikeNetlist: Schema {
         connections: Vec<ArcStr>,
         child: &ArcStr,
     ) -> Result<ArcStr>;
+    /// Writes the parameter values assigned by a SCIR instance.
+    ///
+    /// Called immediately after [`HasSpiceLikeNetlist::write_instance`] or
+    /// [`HasSpiceLikeNetlist::write_primitive_inst`], only if the
+    /// instance assigns at least one parameter. Defaults to appending `NAME=VALUE` pairs,
+    /// with references to parameters of the parent cell written as `{PARAM}`.
+    fn write_instance_params<W: Write>(
+        &self,
+        out: &mut W,
+        params: &[(&ArcStr, &Expr)],
+    ) -> Result<()> {
+        for (name, value) in params {
+            match value {
+                Expr::Value(value) => write!(out, " {}={}", name, value)?,
+                Expr::Param(param) => write!(out, " {}={{{}}}", name, param)?,
+            }
+        }
+        Ok(())
+    }
     /// Writes a primitive instantiation.
     ///
     /// A newline will be added afterward.
@@ -91,6 +140,30 @@ pub trait HasSpiceLikeNetlist: Schema {
         connections: HashMap<ArcStr, Vec<ArcStr>>,
         primitive: &<Self as Schema>::Primitive,
     ) -> Result<ArcStr>;
+    /// Writes an annotation for an attribute of a cell or instance.
+    ///
+    /// Called once per attribute, at the start of the body of a cell or immediately
+    /// before an instance. Defaults to a SPICE comment directive of the form `*.KEY VALUE`,
+    /// or `*.KEY` if the value is empty.
+    ///
+    /// A newline will be added afterward.
+    fn write_attribute<W: Write>(&self, out: &mut W, key: &ArcStr, value: &ArcStr) -> Result<()> {
+        if value.is_empty() {
+            write!(out, "*.{key}")
+        } else {
+            write!(out, "*.{key} {value}")
+        }
+    }
+    /// Writes a comment.
+    ///
+    /// Called once per line of each comment of a cell, immediately before the cell's definition,
+    /// so `comment` never contains a line break.
+    /// Defaults to a SPICE comment of the form `* COMMENT`.
+    ///
+    /// A newline will be added afterward.
+    fn write_comment<W: Write>(&self, out: &mut W, comment: &str) -> Result<()> {
+        write!(out, "* {comment}")
+    }
     /// Writes a slice.
     ///
     /// Should not include a newline at the end.
@@ -107,6 +180,50 @@ pub trait HasSpiceLikeNetlist: Schema {
         }
         Ok(())
     }
+    /// Returns a name that is legal in this netlist format, derived from `name`.
+    ///
+    /// Should return `name` unchanged if it is already legal. Only applied if
+    /// [`NetlistOptions::legalize_names`] is set, in which case the names of instances and
+    /// internal signals are legalized before they are netlisted and names that collide after
+    /// legalization are made unique by the netlister. Ports are never renamed.
+    /// Defaults to leaving names unchanged.
+    fn legalize_name(&self, name: &ArcStr) -> ArcStr {
+        name.clone()
+    }
+    /// Whether names in this netlist format are case-insensitive.
+    ///
+    /// If `true`, names that differ only in case are considered colliding.
+    fn case_insensitive_names(&self) -> bool {
+        false
+    }
+    /// Writes a DC voltage source named `name` from `pos` to `neg`.
+    ///
+    /// Used for the supplies implied by [`NodeMapping`]s.
+    /// A newline will be added afterward.
+    fn write_supply<W: Write>(
+        &self,
+        out: &mut W,
+        name: &ArcStr,
+        pos: &ArcStr,
+        neg: &ArcStr,
+        voltage: Decimal,
+    ) -> Result<()> {
+        write!(out, "V{name} {pos} {neg} {voltage}")
+    }
+    /// Writes a 0V voltage source named `name` that measures the current flowing
+    /// from `pos` to `neg`.
+    ///
+    /// Called once per [`Ammeter`], immediately after the measured instance.
+    /// Defaults to a SPICE voltage source named `V{name}`.
+    fn write_ammeter<W: Write>(
+        &self,
+        out: &mut W,
+        name: &ArcStr,
+        pos: &ArcStr,
+        neg: &ArcStr,
+    ) -> Result<()> {
+        write!(out, "V{name} {pos} {neg} 0")
+    }
     /// Writes a postlude to the end of the output stream.
     #[allow(unused_variables)]
     fn write_postlude<W: Write>(&self, out: &mut W, lib: &Library<Self>) -> Result<()> {
@@ -114,6 +231,25 @@ pub trait HasSpiceLikeNetlist: Schema {
     }
 }
 
+/// Writes the multiplier of a device, if it is not 1.
+fn write_multiplier<W: Write>(out: &mut W, m: u64) -> Result<()> {
+    if m != 1 {
+        write!(out, " m={m}")?;
+    }
+    Ok(())
+}
+
+/// Annotates an error encountered while netlisting an object with the generator code
+/// that created the object, if known.
+fn with_source_info(err: std::io::Error, source_info: Option<&SourceInfo>) -> std::io::Error {
+    match source_info {
+        Some(source_info) => {
+            std::io::Error::new(err.kind(), format!("{err} (generated by {source_info})"))
+        }
+        None => err,
+    }
+}
+
 /// An enumeration describing whether the ground node of a testbench should be renamed.
 #[derive(Clone, Debug)]
 pub enum RenameGround {
@@ -132,21 +268,427 @@ pub enum NetlistKind {
     Cells,
     /// A testbench netlist that should have its top cell inlined and its ground renamed to
     /// the simulator ground node.
+    ///
+    /// Additional ports of the top cell can be mapped to simulator nodes using
+    /// [`NetlistOptions::map_node`].
     Testbench(RenameGround),
 }
 
+/// A mapping from a port of a testbench top cell to a simulator node.
+///
+/// Allows testbenches to expose ports other than ground (e.g. supplies).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NodeMapping {
+    /// The name of the testbench port.
+    pub port: ArcStr,
+    /// The simulator node the port should be netlisted as.
+    pub node: ArcStr,
+    /// The voltage of an implied DC source from `node` to ground, if any.
+    pub supply: Option<Decimal>,
+}
+
+impl NodeMapping {
+    /// Creates a new [`NodeMapping`] from `port` to `node`.
+    pub fn new(port: impl Into<ArcStr>, node: impl Into<ArcStr>) -> Self {
+        Self {
+            port: port.into(),
+            node: node.into(),
+            supply: None,
+        }
+    }
+
+    /// Creates a new [`NodeMapping`] from `port` to `node`, with an implied
+    /// DC source of the given voltage from `node` to ground.
+    pub fn supply(port: impl Into<ArcStr>, node: impl Into<ArcStr>, voltage: Decimal) -> Self {
+        Self {
+            supply: Some(voltage),
+            ..Self::new(port, node)
+        }
+    }
+}
+
+/// A 0V voltage source in series with one bit of an instance terminal.
+///
+/// The source is inserted while netlisting, so currents can be measured at any
+/// depth of the hierarchy without modifying the SCIR library.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Ammeter {
+    /// The cell containing the measured instance.
+    pub cell: CellId,
+    /// The SCIR name of the measured instance.
+    pub instance: ArcStr,
+    /// The name of the measured terminal.
+    pub port: ArcStr,
+    /// The measured bit of the terminal.
+    pub index: usize,
+    /// The name of the voltage source.
+    ///
+    /// Also used as the name of the node between the source and the terminal,
+    /// so it must not collide with a signal of `cell`.
+    pub name: ArcStr,
+}
+
+/// The scheme used to name netlisted instances.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub enum InstanceNaming {
+    /// Instances are netlisted using their SCIR names.
+    #[default]
+    Scir,
+    /// Instances are named after their child cell and a hash of their connections
+    /// (e.g. `inverter_1a2b3c4d`).
+    ///
+    /// Unlike automatically generated SCIR names, these names do not change when other
+    /// instances are added to, removed from, or reordered within the parent. Connections to
+    /// internal nodes named after an instance are hashed relative to that instance's child
+    /// cell, so they do not depend on the instance's SCIR name either. Instances of the same
+    /// child with identical connections are distinguished by a numeric suffix in creation
+    /// order, as are instances whose names would otherwise collide.
+    ///
+    /// Internal nodes that were named after an instance are renamed accordingly.
+    /// All renames are recorded in the returned [`NetlistLibConversion`].
+    ///
+    /// Instances within [preserved](scir::Cell::set_preserve) cells keep their SCIR names.
+    Stable,
+}
+
 /// Configuration for SPICE netlists.
 #[derive(Clone, Debug, Default)]
 pub struct NetlistOptions<'a> {
     kind: NetlistKind,
     includes: &'a [Include],
+    naming: InstanceNaming,
+    legalize: bool,
+    nodes: Vec<NodeMapping>,
+    global_supplies: Vec<(ArcStr, Decimal)>,
+    roots: HashMap<CellId, NetlistKind>,
+    ammeters: Vec<Ammeter>,
 }
 
 impl<'a> NetlistOptions<'a> {
     /// Creates a new [`NetlistOptions`].
     pub fn new(kind: NetlistKind, includes: &'a [Include]) -> Self {
-        Self { kind, includes }
+        Self {
+            kind,
+            includes,
+            naming: InstanceNaming::default(),
+            legalize: false,
+            nodes: Vec::new(),
+            global_supplies: Vec::new(),
+            roots: HashMap::new(),
+            ammeters: Vec::new(),
+        }
+    }
+
+    /// Maps a port of the testbench top cell to a simulator node.
+    ///
+    /// Only applies to [testbench](NetlistKind::Testbench) netlists.
+    /// Every port of the testbench other than ground must be mapped,
+    /// and every mapped port must be a single-bit port of the testbench.
+    pub fn map_node(mut self, mapping: NodeMapping) -> Self {
+        self.nodes.push(mapping);
+        self
+    }
+
+    /// Ties the global net `net` to a DC source of the given voltage relative to ground.
+    ///
+    /// Only applies to [testbench](NetlistKind::Testbench) netlists, allowing supplies
+    /// connected to global rails to be driven without instantiating sources in the testbench.
+    /// Ground rails can be tied to ground using a voltage of 0. The net must be declared
+    /// using [`scir::LibraryBuilder::add_global`].
+    pub fn tie_global(mut self, net: impl Into<ArcStr>, voltage: Decimal) -> Self {
+        self.global_supplies.push((net.into(), voltage));
+        self
+    }
+
+    /// Sets the kind of netlist to export for the given root cell.
+    ///
+    /// By default, the top cell is exported according to the [`NetlistKind`] passed to
+    /// [`NetlistOptions::new`] and all other cells are exported as subcircuits. This allows
+    /// libraries with multiple roots (see [`scir::LibraryBuilder::add_root`]) to netlist each
+    /// root differently, e.g. a design under test as a subcircuit alongside an inlined testbench.
+    ///
+    /// The cell must be a root of the netlisted library, and at most one cell
+    /// can be exported as a [testbench](NetlistKind::Testbench).
+    /// Node mappings added using [`NetlistOptions::map_node`] apply to every testbench root.
+    pub fn root_kind(mut self, cell: CellId, kind: NetlistKind) -> Self {
+        self.roots.insert(cell, kind);
+        self
+    }
+
+    /// Inserts an [`Ammeter`] in series with an instance terminal.
+    ///
+    /// Netlisting fails if the instance, terminal, or bit does not exist,
+    /// or if the name of the ammeter collides with a signal of its cell.
+    pub fn insert_ammeter(mut self, ammeter: Ammeter) -> Self {
+        self.ammeters.push(ammeter);
+        self
+    }
+
+    /// Sets the scheme used to name netlisted instances.
+    pub fn with_naming(mut self, naming: InstanceNaming) -> Self {
+        self.naming = naming;
+        self
+    }
+
+    /// Legalizes the names of netlisted instances and internal signals
+    /// using [`HasSpiceLikeNetlist::legalize_name`].
+    ///
+    /// Renames are recorded in the returned [`NetlistLibConversion`]. Ports are never
+    /// renamed, since that would change the pin names of the netlisted subcircuits;
+    /// netlisting fails if a port name is illegal or collides with another port or global net.
+    /// Names within [preserved](scir::Cell::set_preserve) cells are never legalized.
+    pub fn legalize_names(mut self) -> Self {
+        self.legalize = true;
+        self
+    }
+}
+
+/// The netlisted names of the instances and signals in a cell.
+#[derive(Clone, Debug, Default)]
+struct CellNames {
+    /// The names of all instances in the cell, before any schema-specific prefixes are added.
+    instances: HashMap<InstanceId, ArcStr>,
+    /// The names of signals whose netlisted name differs from their SCIR name.
+    signals: HashMap<SignalId, ArcStr>,
+}
+
+impl CellNames {
+    fn new<S: HasSpiceLikeNetlist>(
+        schema: &S,
+        lib: &Library<S>,
+        cell: &Cell,
+        naming: InstanceNaming,
+        legalize: bool,
+    ) -> Result<Self> {
+        // Preserved cells must match their SCIR contents exactly,
+        // so their names are neither regenerated nor legalized.
+        let (naming, legalize) = if cell.is_preserved() {
+            (InstanceNaming::Scir, false)
+        } else {
+            (naming, legalize)
+        };
+        let mut names = match naming {
+            InstanceNaming::Scir => Self {
+                instances: cell
+                    .instances()
+                    .map(|(id, inst)| (id, inst.name().clone()))
+                    .collect(),
+                signals: HashMap::new(),
+            },
+            InstanceNaming::Stable => Self::stable(lib, cell),
+        };
+        if legalize {
+            names.legalize(schema, cell)?;
+        }
+        Ok(names)
+    }
+
+    /// Names instances according to [`InstanceNaming::Stable`].
+    fn stable<S: Schema>(lib: &Library<S>, cell: &Cell) -> Self {
+        let ports = cell
+            .ports()
+            .map(|port| port.signal())
+            .collect::<HashSet<_>>();
+        let signals = cell
+            .signals()
+            .filter(|(id, info)| !ports.contains(id) && !info.is_global())
+            .sorted_by_key(|(id, _)| *id)
+            .collect::<Vec<_>>();
+
+        // Maps each internal signal named after an instance to that instance
+        // and the remainder of the signal's name.
+        let mut owners = HashMap::new();
+        for (id, inst) in cell.instances() {
+            let prefix = format!("{}_", inst.name());
+            for (signal, info) in signals.iter() {
+                if let Some(rest) = info.name.strip_prefix(&prefix) {
+                    owners.entry(*signal).or_insert((id, rest));
+                }
+            }
+        }
+
+        let base = |inst: &Instance| match inst.child() {
+            ChildId::Cell(child_id) => lib.cell(child_id).name().clone(),
+            ChildId::Primitive(_) => arcstr::literal!("prim"),
+        };
+        let mut candidates = cell
+            .instances()
+            .map(|(id, inst)| {
+                let mut key = match inst.child() {
+                    ChildId::Cell(_) => format!("cell {}", base(inst)),
+                    ChildId::Primitive(_) => "primitive".to_string(),
+                };
+                for (port, conn) in inst.connections().iter().sorted_by_key(|(port, _)| *port) {
+                    key.push_str(&format!(" {port}="));
+                    for part in conn.parts() {
+                        match owners.get(&part.signal()) {
+                            Some((owner, rest)) if *owner == id => {
+                                key.push_str(&format!("self.{rest}"))
+                            }
+                            Some((owner, rest)) => {
+                                key.push_str(&format!("{}.{rest}", base(cell.instance(*owner))))
+                            }
+                            None => key.push_str(&cell.signal(part.signal()).name),
+                        }
+                        if let Some(range) = part.range() {
+                            key.push_str(&format!("[{}:{}]", range.start(), range.end()));
+                        }
+                        key.push(',');
+                    }
+                }
+                // Truncated to 32 bits to keep names short; collisions are resolved below.
+                let hash = fnv1a(key.as_bytes()) as u32;
+                (id, arcstr::format!("{}_{hash:08x}", base(inst)))
+            })
+            .collect::<Vec<_>>();
+        candidates.sort_by(|(a_id, a), (b_id, b)| a.cmp(b).then(a_id.cmp(b_id)));
+
+        let mut names = Self::default();
+        let mut instance_names = Names::new();
+        for (id, candidate) in candidates {
+            let name = instance_names.assign_name(id, &candidate);
+            names.instances.insert(id, name);
+        }
+
+        let mut taken = cell
+            .signals()
+            .map(|(_, info)| info.name.clone())
+            .collect::<HashSet<_>>();
+        for (signal, _) in signals.iter() {
+            if let Some((owner, rest)) = owners.get(signal) {
+                let renamed = arcstr::format!("{}_{}", names.instances[owner], rest);
+                if taken.insert(renamed.clone()) {
+                    names.signals.insert(*signal, renamed);
+                }
+            }
+        }
+        names
+    }
+
+    /// Legalizes all names using [`HasSpiceLikeNetlist::legalize_name`],
+    /// resolving any collisions that result.
+    ///
+    /// Returns an error if a port would need to be renamed.
+    fn legalize<S: HasSpiceLikeNetlist>(&mut self, schema: &S, cell: &Cell) -> Result<()> {
+        let case_insensitive = schema.case_insensitive_names();
+
+        let instances = cell
+            .instances()
+            .map(|(id, _)| (id, self.instances[&id].clone()))
+            .collect();
+        self.instances = legalize_names(
+            instances,
+            |_, name| schema.legalize_name(name),
+            case_insensitive,
+        )
+        .into_iter()
+        .collect();
+
+        // Global nets and ports must keep their names, so they are legalized first
+        // to claim their names before any internal signal.
+        let globals = cell
+            .signals()
+            .filter(|(_, info)| info.is_global())
+            .map(|(id, _)| id)
+            .sorted()
+            .collect::<Vec<_>>();
+        let ports = cell.ports().map(|port| port.signal()).collect::<Vec<_>>();
+        let signals = globals
+            .iter()
+            .chain(ports.iter())
+            .copied()
+            .chain(
+                cell.signals()
+                    .map(|(id, _)| id)
+                    .filter(|id| !globals.contains(id) && !ports.contains(id))
+                    .sorted(),
+            )
+            .map(|id| {
+                let name = self
+                    .signals
+                    .get(&id)
+                    .unwrap_or(&cell.signal(id).name)
+                    .clone();
+                (id, name)
+            })
+            .collect();
+        let legalize = |id: &SignalId, name: &ArcStr| {
+            if cell.signal(*id).is_global() {
+                name.clone()
+            } else {
+                schema.legalize_name(name)
+            }
+        };
+        self.signals = legalize_names(signals, legalize, case_insensitive)
+            .into_iter()
+            .filter(|(id, name)| name != &cell.signal(*id).name)
+            .collect();
+
+        if let Some(port) = ports.iter().find(|port| self.signals.contains_key(port)) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "port `{}` of cell `{}` is not a legal name or collides with another port or global net",
+                    cell.signal(*port).name,
+                    cell.name()
+                ),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Hashes `bytes` using 64-bit FNV-1a.
+///
+/// Unlike the hashers in the standard library, the result is the same across Rust releases,
+/// so it can be used to derive names that must not change between netlists.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Legalizes the given names, renaming names that collide after legalization.
+///
+/// Names that are already legal and unique keep their original names.
+fn legalize_names<K>(
+    names: Vec<(K, ArcStr)>,
+    legalize: impl Fn(&K, &ArcStr) -> ArcStr,
+    case_insensitive: bool,
+) -> Vec<(K, ArcStr)> {
+    let key = |name: &str| {
+        if case_insensitive {
+            name.to_lowercase()
+        } else {
+            name.to_string()
+        }
+    };
+
+    let mut taken = HashSet::new();
+    let mut legal = vec![None; names.len()];
+    for (i, (k, name)) in names.iter().enumerate() {
+        if &legalize(k, name) == name && taken.insert(key(name)) {
+            legal[i] = Some(name.clone());
+        }
     }
+
+    names
+        .into_iter()
+        .zip(legal)
+        .map(|((k, name), legal)| {
+            let name = legal.unwrap_or_else(|| {
+                let base = legalize(&k, &name);
+                let mut candidate = base.clone();
+                let mut i = 1;
+                while !taken.insert(key(&candidate)) {
+                    candidate = legalize(&k, &arcstr::format!("{}_{}", base, i));
+                    i += 1;
+                }
+                candidate
+            });
+            (k, name)
+        })
+        .collect()
 }
 
 /// An instance of a netlister.
@@ -183,88 +725,184 @@ impl<S: HasSpiceLikeNetlist, W: Write> NetlisterInstance<'_, S, W> {
     }
 
     fn export_library(&mut self) -> Result<NetlistLibConversion> {
+        let _guard = span!(Level::INFO, "netlisting SCIR library").entered();
         self.schema.write_prelude(self.out, self.lib)?;
         for include in self.opts.includes {
             self.schema.write_include(self.out, include)?;
             writeln!(self.out)?;
         }
+        let globals = self.lib.globals().collect::<Vec<_>>();
+        self.schema.write_globals(self.out, &globals)?;
         writeln!(self.out)?;
 
         let mut conv = NetlistLibConversion::new();
 
+        let kinds = self.root_kinds()?;
+        let ammeters = self.ammeters()?;
         for (id, cell) in self.lib.cells() {
-            conv.cells
-                .insert(id, self.export_cell(cell, self.lib.is_top(id))?);
+            let _guard =
+                span!(Level::DEBUG, "netlisting SCIR cell", cell.id = %id, cell.name = %cell.name())
+                    .entered();
+            conv.add_cell(
+                id,
+                self.export_cell(cell, kinds.get(&id), ammeters.get(&id))?,
+            );
         }
 
         self.schema.write_postlude(self.out, self.lib)?;
         Ok(conv)
     }
 
-    fn export_cell(&mut self, cell: &Cell, is_top: bool) -> Result<NetlistCellConversion> {
-        let is_testbench_top = is_top && self.opts.kind.is_testbench();
+    /// Returns the kind of netlist to export for each root cell of the library.
+    fn root_kinds(&self) -> Result<HashMap<CellId, NetlistKind>> {
+        let invalid = |msg: String| std::io::Error::new(std::io::ErrorKind::InvalidInput, msg);
+
+        let mut kinds = HashMap::new();
+        if let Some(top) = self.lib.top_cell() {
+            kinds.insert(top, self.opts.kind.clone());
+        }
+        for (&id, kind) in self.opts.roots.iter() {
+            if !self.lib.is_root(id) {
+                return Err(invalid(format!(
+                    "cell `{}` is not a root of the netlisted library",
+                    self.lib.cell(id).name()
+                )));
+            }
+            kinds.insert(id, kind.clone());
+        }
+
+        let testbenches = self
+            .lib
+            .roots()
+            .filter(|id| kinds.get(id).is_some_and(NetlistKind::is_testbench))
+            .map(|id| self.lib.cell(id).name())
+            .collect::<Vec<_>>();
+        if testbenches.len() > 1 {
+            return Err(invalid(format!(
+                "at most one root can be netlisted as a testbench, found {}",
+                testbenches
+                    .iter()
+                    .map(|name| format!("`{name}`"))
+                    .join(", ")
+            )));
+        }
+
+        Ok(kinds)
+    }
+
+    fn export_cell(
+        &mut self,
+        cell: &Cell,
+        kind: Option<&NetlistKind>,
+        ammeters: Option<&HashMap<ArcStr, Vec<Ammeter>>>,
+    ) -> Result<NetlistCellConversion> {
+        let testbench_kind = kind.filter(|kind| kind.is_testbench());
+        let is_testbench_top = testbench_kind.is_some();
 
         let indent = if is_testbench_top { "" } else { "  " };
 
-        let ground = match (is_testbench_top, &self.opts.kind) {
-            (true, NetlistKind::Testbench(RenameGround::Yes(replace_with))) => {
-                let msg = "testbench should have one port: ground";
-                let mut ports = cell.ports();
-                let ground = ports.next().expect(msg);
-                assert!(ports.next().is_none(), "{}", msg);
-                let ground = &cell.signal(ground.signal()).name;
-                Some((ground.clone(), replace_with.clone()))
-            }
-            _ => None,
+        let node_map = if let Some(kind) = testbench_kind {
+            self.testbench_nodes(cell, kind)
+                .map_err(|e| with_source_info(e, cell.source_info()))?
+        } else {
+            HashMap::new()
         };
 
+        let mut names = CellNames::new(
+            self.schema,
+            self.lib,
+            cell,
+            self.opts.naming,
+            self.opts.legalize,
+        )?;
+
+        // Comments are written line by line so that multi-line comments cannot
+        // break out of the comment syntax.
+        for line in cell.comments().flat_map(|comment| comment.lines()) {
+            self.schema.write_comment(self.out, line)?;
+            writeln!(self.out)?;
+        }
+
         if !is_testbench_top {
-            let ports: Vec<&SignalInfo> = cell
+            let ports: Vec<SignalInfo> = cell
                 .ports()
-                .map(|port| cell.signal(port.signal()))
+                .map(|port| {
+                    let info = cell.signal(port.signal());
+                    match names.signals.get(&port.signal()) {
+                        Some(name) => SignalInfo {
+                            name: name.clone(),
+                            ..info.clone()
+                        },
+                        None => info.clone(),
+                    }
+                })
                 .collect();
-            self.schema
-                .write_start_subckt(self.out, cell.name(), &ports)?;
+            self.schema.write_start_subckt(
+                self.out,
+                cell.name(),
+                &ports.iter().collect::<Vec<_>>(),
+            )?;
+            let params = cell.params().collect::<Vec<_>>();
+            if !params.is_empty() {
+                self.schema.write_subckt_params(self.out, &params)?;
+            }
             writeln!(self.out, "\n")?;
         }
 
+        for (key, value) in cell.attributes() {
+            write!(self.out, "{}", indent)?;
+            self.schema.write_attribute(self.out, key, value)?;
+            writeln!(self.out)?;
+        }
+
         let mut conv = NetlistCellConversion::new();
         for (id, inst) in cell.instances() {
+            let inst_name = &names.instances[&id];
+            for (key, value) in inst.attributes() {
+                write!(self.out, "{}", indent)?;
+                self.schema.write_attribute(self.out, key, value)?;
+                writeln!(self.out)?;
+            }
             write!(self.out, "{}", indent)?;
-            let mut connections: HashMap<_, _> = inst
-                .connections()
-                .iter()
-                .map(|(k, v)| {
-                    Ok((
-                        k.clone(),
-                        v.parts()
-                            .map(|part| self.make_slice(cell, *part, &ground))
-                            .collect::<Result<Vec<_>>>()?,
-                    ))
-                })
-                .collect::<Result<_>>()?;
-            let name = match inst.child() {
-                ChildId::Cell(child_id) => {
-                    let child = self.lib.cell(child_id);
-                    let ports = child
-                        .ports()
-                        .flat_map(|port| {
-                            let port_name = &child.signal(port.signal()).name;
-                            connections.remove(port_name).unwrap()
-                        })
-                        .collect::<Vec<_>>();
+            let ammeters = ammeters
+                .and_then(|ammeters| ammeters.get(inst.name()))
+                .map_or(&[][..], Vec::as_slice);
+            let name = self
+                .export_instance(cell, inst, inst_name, &node_map, &names.signals, ammeters)
+                .map_err(|e| with_source_info(e, inst.source_info().or(cell.source_info())))?;
+            conv.instances.insert(id, name);
+            writeln!(self.out)?;
+        }
+
+        if let Some(kind) = testbench_kind {
+            let ground = match kind {
+                NetlistKind::Testbench(RenameGround::Yes(ground)) => ground.clone(),
+                _ => arcstr::literal!("0"),
+            };
+            for mapping in self.opts.nodes.iter() {
+                if let Some(voltage) = mapping.supply {
+                    let name = arcstr::format!("supply_{}", mapping.port);
                     self.schema
-                        .write_instance(self.out, inst.name(), ports, child.name())?
+                        .write_supply(self.out, &name, &mapping.node, &ground, voltage)?;
+                    writeln!(self.out)?;
                 }
-                ChildId::Primitive(child_id) => {
-                    let child = self.lib.primitive(child_id);
-                    self.schema
-                        .write_primitive_inst(self.out, inst.name(), connections, child)?
+                let id = cell.signal_named(&mapping.port).id;
+                names.signals.insert(id, mapping.node.clone());
+            }
+            for (net, voltage) in self.opts.global_supplies.iter() {
+                if !self.lib.is_global(net) {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidInput,
+                        format!("tied net `{net}` is not a declared global net"),
+                    ));
                 }
-            };
-            conv.instances.insert(id, name);
-            writeln!(self.out)?;
+                let name = arcstr::format!("global_{}", net.trim_end_matches('!'));
+                self.schema
+                    .write_supply(self.out, &name, net, &ground, *voltage)?;
+                writeln!(self.out)?;
+            }
         }
+        conv.signals = names.signals;
 
         if !is_testbench_top {
             writeln!(self.out)?;
@@ -274,28 +912,222 @@ impl<S: HasSpiceLikeNetlist, W: Write> NetlisterInstance<'_, S, W> {
         Ok(conv)
     }
 
+    /// Returns the ammeters to insert in each cell, keyed by the SCIR name of the measured instance.
+    fn ammeters(&self) -> Result<HashMap<CellId, HashMap<ArcStr, Vec<Ammeter>>>> {
+        let invalid = |msg: String| std::io::Error::new(std::io::ErrorKind::InvalidInput, msg);
+
+        let mut ammeters: HashMap<CellId, HashMap<ArcStr, Vec<Ammeter>>> = HashMap::new();
+        for ammeter in self.opts.ammeters.iter() {
+            let cell = self.lib.try_cell(ammeter.cell).ok_or_else(|| {
+                invalid(format!(
+                    "cannot insert ammeter `{}`: cell does not exist in the netlisted library",
+                    ammeter.name
+                ))
+            })?;
+            if cell.try_instance_named(&ammeter.instance).is_none() {
+                return Err(invalid(format!(
+                    "cannot insert ammeter `{}`: cell `{}` has no instance `{}`",
+                    ammeter.name,
+                    cell.name(),
+                    ammeter.instance
+                )));
+            }
+            if cell.try_signal_named(&ammeter.name).is_some() {
+                return Err(invalid(format!(
+                    "cannot insert ammeter `{}`: cell `{}` already has a signal with the same name",
+                    ammeter.name,
+                    cell.name()
+                )));
+            }
+            ammeters
+                .entry(ammeter.cell)
+                .or_default()
+                .entry(ammeter.instance.clone())
+                .or_default()
+                .push(ammeter.clone());
+        }
+        Ok(ammeters)
+    }
+
+    /// Writes an instance, without its attributes, returning its netlisted name.
+    ///
+    /// The given ammeters are written after the instance, in series with the terminals they measure.
+    fn export_instance(
+        &mut self,
+        cell: &Cell,
+        inst: &Instance,
+        inst_name: &ArcStr,
+        node_map: &HashMap<SignalId, ArcStr>,
+        renamed: &HashMap<SignalId, ArcStr>,
+        ammeters: &[Ammeter],
+    ) -> Result<ArcStr> {
+        let mut connections: HashMap<_, _> = inst
+            .connections()
+            .iter()
+            .map(|(k, v)| {
+                Ok((
+                    k.clone(),
+                    v.parts()
+                        .map(|part| self.make_slice(cell, *part, node_map, renamed))
+                        .collect::<Result<Vec<_>>>()?,
+                ))
+            })
+            .collect::<Result<_>>()?;
+        let mut measured = Vec::with_capacity(ammeters.len());
+        for ammeter in ammeters {
+            let node = connections
+                .get_mut(&ammeter.port)
+                .and_then(|bits| bits.get_mut(ammeter.index))
+                .ok_or_else(|| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::InvalidInput,
+                        format!(
+                            "cannot insert ammeter `{}`: instance `{}` has no terminal `{}[{}]`",
+                            ammeter.name,
+                            inst.name(),
+                            ammeter.port,
+                            ammeter.index
+                        ),
+                    )
+                })?;
+            measured.push((&ammeter.name, std::mem::replace(node, ammeter.name.clone())));
+        }
+        let name = match inst.child() {
+            ChildId::Cell(child_id) => {
+                let child = self.lib.cell(child_id);
+                let ports = child
+                    .ports()
+                    .flat_map(|port| {
+                        let port_name = &child.signal(port.signal()).name;
+                        connections.remove(port_name).unwrap()
+                    })
+                    .collect::<Vec<_>>();
+                let name = self
+                    .schema
+                    .write_instance(self.out, inst_name, ports, child.name())?;
+                let params = inst.params().collect::<Vec<_>>();
+                if !params.is_empty() {
+                    self.schema.write_instance_params(self.out, &params)?;
+                }
+                name
+            }
+            ChildId::Primitive(child_id) => {
+                let child = self.lib.primitive(child_id);
+                let name =
+                    self.schema
+                        .write_primitive_inst(self.out, inst_name, connections, child)?;
+                let params = inst.params().collect::<Vec<_>>();
+                if !params.is_empty() {
+                    self.schema.write_instance_params(self.out, &params)?;
+                }
+                name
+            }
+        };
+        for (ammeter, net) in measured {
+            writeln!(self.out)?;
+            self.schema
+                .write_ammeter(self.out, ammeter, &net, ammeter)?;
+        }
+        Ok(name)
+    }
+
+    /// Returns the simulator nodes that the ports of the testbench top cell are mapped to.
+    fn testbench_nodes(
+        &self,
+        cell: &Cell,
+        kind: &NetlistKind,
+    ) -> Result<HashMap<SignalId, ArcStr>> {
+        let invalid = |msg: String| std::io::Error::new(std::io::ErrorKind::InvalidInput, msg);
+
+        let mut nodes = HashMap::new();
+        let mut ports = cell.ports();
+        if let NetlistKind::Testbench(RenameGround::Yes(replace_with)) = kind {
+            let ground = ports
+                .next()
+                .ok_or_else(|| invalid("testbench should have a ground port".to_string()))?;
+            if cell.signal(ground.signal()).width.is_some() {
+                return Err(invalid(format!(
+                    "ground port of testbench `{}` must be a single-bit port",
+                    cell.name()
+                )));
+            }
+            nodes.insert(ground.signal(), replace_with.clone());
+        }
+
+        for mapping in self.opts.nodes.iter() {
+            let info = cell
+                .try_signal_named(&mapping.port)
+                .filter(|info| info.is_port() && !info.is_global())
+                .ok_or_else(|| {
+                    invalid(format!(
+                        "mapped net `{}` is not a port of testbench `{}`",
+                        mapping.port,
+                        cell.name()
+                    ))
+                })?;
+            if info.width.is_some() {
+                return Err(invalid(format!(
+                    "mapped port `{}` of testbench `{}` must be a single-bit port",
+                    mapping.port,
+                    cell.name()
+                )));
+            }
+            if nodes.insert(info.id, mapping.node.clone()).is_some() {
+                return Err(invalid(format!(
+                    "port `{}` of testbench `{}` is mapped more than once",
+                    mapping.port,
+                    cell.name()
+                )));
+            }
+        }
+
+        if matches!(kind, NetlistKind::Testbench(RenameGround::Yes(_))) {
+            if let Some(port) = ports.find(|port| !nodes.contains_key(&port.signal())) {
+                return Err(invalid(format!(
+                    "testbench `{}` has unmapped port `{}`; testbench ports other than ground must be mapped to simulator nodes",
+                    cell.name(),
+                    cell.signal(port.signal()).name
+                )));
+            }
+        }
+
+        Ok(nodes)
+    }
+
     fn make_slice(
         &mut self,
         cell: &Cell,
         slice: Slice,
-        rename_ground: &Option<(ArcStr, ArcStr)>,
+        node_map: &HashMap<SignalId, ArcStr>,
+        renamed: &HashMap<SignalId, ArcStr>,
     ) -> Result<ArcStr> {
         let sig_info = cell.signal(slice.signal());
-        if let Some((signal, replace_with)) = rename_ground {
-            if signal == &sig_info.name && slice.range().is_none() {
-                // Ground renaming cannot apply to buses.
-                // TODO assert that the ground port has width 1.
-                return Ok(replace_with.clone());
+        if let Some(node) = node_map.get(&slice.signal()) {
+            // Only single-bit ports are mapped (see `testbench_nodes`), and global nets
+            // are never mapped to simulator nodes.
+            if slice.range().is_none() && !sig_info.is_global() {
+                return Ok(node.clone());
             }
         }
         let mut buf = Vec::new();
-        self.schema.write_slice(&mut buf, slice, sig_info)?;
+        if let Some(name) = renamed.get(&slice.signal()) {
+            let sig_info = SignalInfo {
+                name: name.clone(),
+                ..sig_info.clone()
+            };
+            self.schema.write_slice(&mut buf, slice, &sig_info)?;
+        } else {
+            self.schema.write_slice(&mut buf, slice, sig_info)?;
+        }
         Ok(ArcStr::from(std::str::from_utf8(&buf).expect(
             "slice should only have UTF8-compatible characters",
         )))
     }
 }
 
+/// Characters that cannot appear in SPICE names.
+const SPICE_RESERVED_CHARS: &[char] = &['=', '(', ')', ',', '\'', '"', ';', '{', '}'];
+
 impl HasSpiceLikeNetlist for Spice {
     fn write_prelude<W: Write>(&self, out: &mut W, lib: &Library<Self>) -> std::io::Result<()> {
         writeln!(out, "* Substrate SPICE library")?;
@@ -335,6 +1167,25 @@ impl HasSpiceLikeNetlist for Spice {
         Ok(())
     }
 
+    fn legalize_name(&self, name: &ArcStr) -> ArcStr {
+        // Node `0` is global ground.
+        if name == "0" {
+            return arcstr::literal!("x0");
+        }
+        let legal = |c: char| !c.is_whitespace() && !SPICE_RESERVED_CHARS.contains(&c);
+        if name.chars().all(legal) {
+            return name.clone();
+        }
+        name.chars()
+            .map(|c| if legal(c) { c } else { '_' })
+            .collect::<String>()
+            .into()
+    }
+
+    fn case_insensitive_names(&self) -> bool {
+        true
+    }
+
     fn write_include<W: Write>(&self, out: &mut W, include: &Include) -> std::io::Result<()> {
         if let Some(section) = &include.section {
             write!(out, ".LIB {:?} {}", include.path, section)?;
@@ -394,7 +1245,7 @@ impl HasSpiceLikeNetlist for Spice {
         primitive: &<Self as Schema>::Primitive,
     ) -> std::io::Result<ArcStr> {
         let name = match &primitive {
-            Primitive::Res2 { value, params } => {
+            Primitive::Res2 { value, params, m } => {
                 let name = arcstr::format!("R{}", name);
                 write!(out, "{}", name)?;
                 for port in ["1", "2"] {
@@ -409,9 +1260,10 @@ impl HasSpiceLikeNetlist for Spice {
                 for (key, value) in params.iter().sorted_by_key(|(key, _)| *key) {
                     write!(out, " {key}={value}")?;
                 }
+                write_multiplier(out, *m)?;
                 name
             }
-            Primitive::Cap2 { value } => {
+            Primitive::Cap2 { value, m } => {
                 let name = arcstr::format!("C{}", name);
                 write!(out, "{}", name)?;
                 for port in ["1", "2"] {
@@ -420,11 +1272,13 @@ impl HasSpiceLikeNetlist for Spice {
                     }
                 }
                 write!(out, " {value}")?;
+                write_multiplier(out, *m)?;
                 name
             }
             Primitive::Diode2 {
                 model: mname,
                 params,
+                m,
             } => {
                 let name = arcstr::format!("D{}", name);
                 write!(out, "{}", name)?;
@@ -440,12 +1294,14 @@ impl HasSpiceLikeNetlist for Spice {
                 for (key, value) in params.iter().sorted_by_key(|(key, _)| *key) {
                     write!(out, " {key}={value}")?;
                 }
+                write_multiplier(out, *m)?;
                 name
             }
             Primitive::Bjt {
                 model: mname,
                 params,
                 has_substrate_port,
+                m,
             } => {
                 let name = arcstr::format!("Q{}", name);
                 write!(out, "{}", name)?;
@@ -462,11 +1318,13 @@ impl HasSpiceLikeNetlist for Spice {
                 for (key, value) in params.iter().sorted_by_key(|(key, _)| *key) {
                     write!(out, " {key}={value}")?;
                 }
+                write_multiplier(out, *m)?;
                 name
             }
             Primitive::Mos {
                 model: mname,
                 params,
+                m,
             } => {
                 let name = arcstr::format!("M{}", name);
                 write!(out, "{}", name)?;
@@ -479,6 +1337,7 @@ impl HasSpiceLikeNetlist for Spice {
                 for (key, value) in params.iter().sorted_by_key(|(key, _)| *key) {
                     write!(out, " {key}={value}")?;
                 }
+                write_multiplier(out, *m)?;
                 name
             }
             Primitive::RawInstance { cell, ports, .. }
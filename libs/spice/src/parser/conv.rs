@@ -148,7 +148,7 @@ impl<'a> ScirConverter<'a> {
             match component {
                 Component::Mos(mos) => {
                     let model = ArcStr::from(mos.model.as_str());
-                    let params = mos
+                    let mut params = mos
                         .params
                         .iter()
                         .map(|(k, v)| {
@@ -162,7 +162,11 @@ impl<'a> ScirConverter<'a> {
                         })
                         .collect::<ConvResult<HashMap<_, _>>>()?;
                     // TODO: Deduplicate primitives, though does not affect functionality
-                    let id = self.lib.add_primitive(Primitive::Mos { model, params });
+                    let id = self.lib.add_primitive(Primitive::Mos {
+                        m: take_multiplier(&mut params),
+                        model,
+                        params,
+                    });
                     let mut sinst = scir::Instance::new(&mos.name[1..], id);
                     sinst.connect("D", node(&mos.d, &mut cell));
                     sinst.connect("G", node(&mos.g, &mut cell));
@@ -172,7 +176,7 @@ impl<'a> ScirConverter<'a> {
                 }
                 Component::Diode(diode) => {
                     let model = ArcStr::from(diode.model.as_str());
-                    let params = diode
+                    let mut params = diode
                         .params
                         .iter()
                         .map(|(k, v)| {
@@ -186,7 +190,11 @@ impl<'a> ScirConverter<'a> {
                         })
                         .collect::<ConvResult<HashMap<_, _>>>()?;
                     // TODO: Deduplicate primitives, though does not affect functionality
-                    let id = self.lib.add_primitive(Primitive::Diode2 { model, params });
+                    let id = self.lib.add_primitive(Primitive::Diode2 {
+                        m: take_multiplier(&mut params),
+                        model,
+                        params,
+                    });
                     let mut sinst = scir::Instance::new(&diode.name[1..], id);
                     sinst.connect("1", node(&diode.pos, &mut cell));
                     sinst.connect("2", node(&diode.neg, &mut cell));
@@ -194,7 +202,7 @@ impl<'a> ScirConverter<'a> {
                 }
                 Component::Bjt(bjt) => {
                     let model = ArcStr::from(bjt.model.as_str());
-                    let params = bjt
+                    let mut params = bjt
                         .params
                         .iter()
                         .map(|(k, v)| {
@@ -207,11 +215,13 @@ impl<'a> ScirConverter<'a> {
                             ))
                         })
                         .collect::<ConvResult<HashMap<_, _>>>()?;
+                    let m = take_multiplier(&mut params);
                     // TODO: Deduplicate primitives, though does not affect functionality
                     let id = self.lib.add_primitive(Primitive::Bjt {
                         model,
                         params,
                         has_substrate_port: bjt.substrate.is_some(),
+                        m,
                     });
                     let mut sinst = scir::Instance::new(&bjt.name[1..], id);
                     sinst.connect("NC", node(&bjt.collector, &mut cell));
@@ -231,7 +241,7 @@ impl<'a> ScirConverter<'a> {
                             ComponentValue::Model(ArcStr::from(model.as_str()))
                         }
                     };
-                    let params = res
+                    let mut params = res
                         .params
                         .iter()
                         .map(|(k, v)| {
@@ -244,15 +254,34 @@ impl<'a> ScirConverter<'a> {
                             ))
                         })
                         .collect::<ConvResult<HashMap<_, _>>>()?;
-                    let id = self.lib.add_primitive(Primitive::Res2 { value, params });
+                    let id = self.lib.add_primitive(Primitive::Res2 {
+                        m: take_multiplier(&mut params),
+                        value,
+                        params,
+                    });
                     let mut sinst = scir::Instance::new(&res.name[1..], id);
                     sinst.connect("1", node(&res.pos, &mut cell));
                     sinst.connect("2", node(&res.neg, &mut cell));
                     cell.add_instance(sinst);
                 }
                 Component::Cap(cap) => {
+                    let mut params = cap
+                        .params
+                        .iter()
+                        .map(|(k, v)| {
+                            Ok((
+                                UniCase::new(ArcStr::from(k.as_str())),
+                                match substr_as_numeric_lit(v) {
+                                    Ok(v) => ParamValue::Numeric(v),
+                                    Err(_) => ParamValue::String(v.to_string().into()),
+                                },
+                            ))
+                        })
+                        .collect::<ConvResult<HashMap<_, _>>>()?;
+                    // Ideal capacitors only support a multiplier; other parameters are ignored.
                     let id = self.lib.add_primitive(Primitive::Cap2 {
                         value: substr_as_numeric_lit(&cap.value)?,
+                        m: take_multiplier(&mut params),
                     });
                     let mut sinst = scir::Instance::new(&cap.name[1..], id);
                     sinst.connect("1", node(&cap.pos, &mut cell));
@@ -392,6 +421,23 @@ fn substr_as_numeric_lit(s: &Substr) -> ConvResult<Decimal> {
     str_as_numeric_lit(s).map_err(|_| ConvError::InvalidLiteral(s.clone()))
 }
 
+/// Removes a positive integer `m` parameter from `params`, returning its value.
+///
+/// Returns 1 if there is no such parameter. Other `m` parameters are left untouched.
+fn take_multiplier(params: &mut HashMap<UniCase<ArcStr>, ParamValue>) -> u64 {
+    let key = UniCase::new(arcstr::literal!("m"));
+    let m = match params.get(&key) {
+        Some(ParamValue::Numeric(m)) if m.fract().is_zero() && m.is_sign_positive() => {
+            u64::try_from(*m).ok().filter(|&m| m > 0)
+        }
+        _ => None,
+    };
+    if let Some(m) = m {
+        params.remove(&key);
+    }
+    m.unwrap_or(1)
+}
+
 pub(crate) fn map_subckts(ast: &Ast) -> HashMap<SubcktName, &Subckt> {
     let mut subckts = HashMap::new();
     for elem in ast.elems.iter() {
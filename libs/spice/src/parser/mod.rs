@@ -353,12 +353,22 @@ impl Parser {
                             params,
                         }))
                     }
-                    'C' => Line::Component(Component::Cap(Cap {
-                        name: self.buffer[0].try_ident()?.clone(),
-                        pos: self.buffer[1].try_ident()?.clone(),
-                        neg: self.buffer[2].try_ident()?.clone(),
-                        value: self.buffer[3].try_ident()?.clone(),
-                    })),
+                    'C' => {
+                        let mut params = Params::default();
+                        for i in (4..self.buffer.len()).step_by(3) {
+                            let k = self.buffer[i].try_ident()?.clone();
+                            assert!(matches!(self.buffer[i + 1], Token::Equals));
+                            let v = self.buffer[i + 2].try_ident()?.clone();
+                            params.insert(k, v);
+                        }
+                        Line::Component(Component::Cap(Cap {
+                            name: self.buffer[0].try_ident()?.clone(),
+                            pos: self.buffer[1].try_ident()?.clone(),
+                            neg: self.buffer[2].try_ident()?.clone(),
+                            value: self.buffer[3].try_ident()?.clone(),
+                            params,
+                        }))
+                    }
                     'X' => {
                         // An X instance line looks like this:
                         //
@@ -591,8 +601,10 @@ pub struct Cap {
     pub pos: Node,
     /// The node connected to the negative terminal.
     pub neg: Node,
-    /// The value of the resistor.
+    /// The value of the capacitor.
     pub value: Substr,
+    /// Parameters and their values.
+    pub params: Params,
 }
 
 /// A subcircuit instance.
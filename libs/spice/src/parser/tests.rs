@@ -4,6 +4,7 @@ use crate::netlist::NetlistOptions;
 use crate::Primitive;
 use scir::netlist::ConvertibleNetlister;
 use std::path::PathBuf;
+use unicase::UniCase;
 
 pub const TEST_DATA_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/data");
 
@@ -147,14 +148,48 @@ fn convert_mos_to_scir() {
     let (_, inst) = cell.instances().next().unwrap();
     let prim = lib.primitive(inst.child().unwrap_primitive());
     match prim {
-        Primitive::Mos { model, params } => {
+        Primitive::Mos { model, params, m } => {
             assert_eq!(model, "my_mos_model");
             assert_eq!(params.len(), 0);
+            assert_eq!(*m, 1);
         }
         _ => panic!("incorrect primitive kind"),
     }
 }
 
+#[test]
+fn convert_multipliers_to_scir() {
+    let parsed = Parser::parse(
+        Dialect::Spice,
+        r#"
+.subckt multipliers c b e p n
+Q0 c b e my_bjt_model m=2 area=3
+C1 p n 1e-15 m=4
+.ends
+"#,
+    )
+    .unwrap();
+    let converter = ScirConverter::new(&parsed.ast);
+    let lib = converter.convert().unwrap();
+    let cell = lib.cell_named("multipliers");
+    assert_eq!(cell.instances().count(), 2);
+
+    for (_, inst) in cell.instances() {
+        match lib.primitive(inst.child().unwrap_primitive()) {
+            Primitive::Bjt {
+                model, params, m, ..
+            } => {
+                assert_eq!(model, "my_bjt_model");
+                assert_eq!(params.len(), 1);
+                assert!(params.contains_key(&UniCase::new(arcstr::literal!("area"))));
+                assert_eq!(*m, 2);
+            }
+            Primitive::Cap2 { m, .. } => assert_eq!(*m, 4),
+            _ => panic!("incorrect primitive kind"),
+        }
+    }
+}
+
 #[test]
 fn convert_dff_to_scir() {
     let parsed = Parser::parse_file(Dialect::Spice, test_data("dff.spice")).unwrap();
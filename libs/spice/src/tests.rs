@@ -1,5 +1,6 @@
 use crate::netlist::{
-    HasSpiceLikeNetlist, Include, NetlistKind, NetlistOptions, NetlisterInstance, RenameGround,
+    Ammeter, HasSpiceLikeNetlist, Include, InstanceNaming, NetlistKind, NetlistOptions,
+    NetlisterInstance, NodeMapping, RenameGround,
 };
 
 use crate::{BlackboxContents, BlackboxElement, ComponentValue, Primitive, Spice};
@@ -9,9 +10,10 @@ use rust_decimal_macros::dec;
 use scir::netlist::ConvertibleNetlister;
 use scir::schema::Schema;
 use scir::{
-    Cell, Concat, Direction, IndexOwned, Instance, Library, LibraryBuilder, SignalInfo, Slice,
+    Cell, Concat, Direction, Expr, IndexOwned, Instance, Library, LibraryBuilder, Param,
+    SignalInfo, Slice,
 };
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::io::Write;
 
 #[test]
@@ -265,12 +267,797 @@ fn spice_like_netlist() {
     }
 }
 
+#[test]
+fn stable_instance_naming() {
+    // Returns the netlist and the netlisted name of the node between the two inverters.
+    let netlist = |with_spare: bool| {
+        let mut lib = LibraryBuilder::<Spice>::new();
+        let mut inv = Cell::new("inv");
+        let a = inv.add_node("a");
+        let y = inv.add_node("y");
+        inv.expose_port(a, Direction::Input);
+        inv.expose_port(y, Direction::Output);
+        let inv_id = lib.add_cell(inv);
+
+        let mut top = Cell::new("top");
+        let din = top.add_node("din");
+        let dout = top.add_node("dout");
+        top.expose_port(din, Direction::Input);
+        top.expose_port(dout, Direction::Output);
+
+        // A spare inverter of the same cell shifts the SCIR names of the other inverters.
+        let mut n = 0;
+        if with_spare {
+            let spare = top.add_node("spare");
+            let mut inst = Instance::new("xinst0", inv_id);
+            inst.connect("a", din);
+            inst.connect("y", spare);
+            top.add_instance(inst);
+            n += 1;
+        }
+        let mid = top.add_node(format!("xinst{n}_y"));
+        let mut inv0 = Instance::new(format!("xinst{n}"), inv_id);
+        inv0.connect("a", din);
+        inv0.connect("y", mid);
+        top.add_instance(inv0);
+        let mut inv1 = Instance::new(format!("xinst{}", n + 1), inv_id);
+        inv1.connect("a", mid);
+        inv1.connect("y", dout);
+        top.add_instance(inv1);
+        lib.add_cell(top);
+        let lib = lib.build().unwrap();
+
+        let mut buf = Vec::new();
+        let conv = NetlisterInstance::new(
+            &Spice,
+            &lib,
+            &mut buf,
+            NetlistOptions::default().with_naming(InstanceNaming::Stable),
+        )
+        .export()
+        .unwrap();
+
+        let top_id = lib.cell_id_named("top");
+        let top_conv = &conv.cells[&top_id];
+        let cell = lib.cell(top_id);
+        let signals = top_conv
+            .signals
+            .iter()
+            .map(|(id, name)| (cell.signal(*id).name.clone(), name.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(signals.len(), 1);
+        let (old, new) = signals.into_iter().next().unwrap();
+        assert_eq!(old, format!("xinst{n}_y"));
+
+        (String::from_utf8(buf).unwrap(), new)
+    };
+
+    let (netlist, mid) = netlist(false);
+    let (spare_netlist, spare_mid) = netlist(true);
+    println!("{}\n{}", netlist, spare_netlist);
+
+    assert_eq!(mid, spare_mid);
+    assert!(mid.starts_with("inv_") && mid.ends_with("_y"));
+    let inverters = |netlist: &str| {
+        netlist
+            .lines()
+            .filter(|line| line.starts_with("Xinv_") && !line.contains("spare"))
+            .map(str::to_string)
+            .collect::<Vec<_>>()
+    };
+    let lines = inverters(&netlist);
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines, inverters(&spare_netlist));
+    assert!(lines
+        .iter()
+        .any(|line| line.ends_with(&format!(" din {mid} inv"))));
+    assert!(lines
+        .iter()
+        .any(|line| line.ends_with(&format!(" {mid} dout inv"))));
+}
+
+#[test]
+fn stable_instance_names_are_unique() {
+    let mut lib = LibraryBuilder::<Spice>::new();
+    let res = lib.add_primitive(Primitive::Res2 {
+        value: ComponentValue::Fixed(dec!(100)),
+        params: Default::default(),
+        m: 1,
+    });
+    let mut prim = Cell::new("prim");
+    let a = prim.add_node("a");
+    let b = prim.add_node("b");
+    prim.expose_port(a, Direction::InOut);
+    prim.expose_port(b, Direction::InOut);
+    let prim = lib.add_cell(prim);
+
+    let mut top = Cell::new("top");
+    let a = top.add_node("a");
+    let b = top.add_node("b");
+    top.expose_port(a, Direction::InOut);
+    top.expose_port(b, Direction::InOut);
+    // Two identical resistors in parallel, alongside a cell that shares their base name.
+    for name in ["r0", "r1"] {
+        let mut r = Instance::new(name, res);
+        r.connect("1", a);
+        r.connect("2", b);
+        top.add_instance(r);
+    }
+    let mut x = Instance::new("x0", prim);
+    x.connect("a", a);
+    x.connect("b", b);
+    top.add_instance(x);
+    lib.add_cell(top);
+    let lib = lib.build().unwrap();
+
+    let mut buf = Vec::new();
+    let conv = NetlisterInstance::new(
+        &Spice,
+        &lib,
+        &mut buf,
+        NetlistOptions::default().with_naming(InstanceNaming::Stable),
+    )
+    .export()
+    .unwrap();
+    println!("{}", String::from_utf8(buf).unwrap());
+
+    let top_conv = &conv.cells[&lib.cell_id_named("top")];
+    let names = top_conv
+        .instances
+        .values()
+        .map(|name| name.to_string())
+        .collect::<HashSet<_>>();
+    assert_eq!(names.len(), 3);
+}
+
+#[test]
+fn spice_names_are_legalized() {
+    let build = |port_collision: bool, preserve: bool| {
+        let mut lib = LibraryBuilder::<Spice>::new();
+        let res = lib.add_primitive(Primitive::Res2 {
+            value: ComponentValue::Fixed(dec!(100)),
+            params: Default::default(),
+            m: 1,
+        });
+        let mut cell = Cell::new("legalize");
+        cell.set_preserve(preserve);
+        let a = cell.add_node("a");
+        let a_upper = cell.add_node("A");
+        let b = cell.add_node("b");
+        let zero = cell.add_node("0");
+        let spaced = cell.add_node("x y");
+        cell.expose_port(a, Direction::InOut);
+        cell.expose_port(b, Direction::InOut);
+        if port_collision {
+            cell.expose_port(a_upper, Direction::InOut);
+        }
+        for (name, p, n) in [
+            ("r0", a, zero),
+            ("R0", zero, spaced),
+            ("r1", spaced, a_upper),
+            ("r2", a_upper, b),
+        ] {
+            let mut inst = Instance::new(name, res);
+            inst.connect("1", p);
+            inst.connect("2", n);
+            cell.add_instance(inst);
+        }
+        let id = lib.add_cell(cell);
+        (id, lib.build().unwrap())
+    };
+
+    // Names are left untouched unless legalization is requested.
+    let (_, lib) = build(false, false);
+    let mut buf = Vec::new();
+    NetlisterInstance::new(&Spice, &lib, &mut buf, Default::default())
+        .export()
+        .unwrap();
+    let netlist = String::from_utf8(buf).unwrap();
+    assert!(netlist.contains("Rr1 x y A 100"));
+
+    let (id, lib) = build(false, false);
+    let mut buf = Vec::new();
+    let conv = NetlisterInstance::new(
+        &Spice,
+        &lib,
+        &mut buf,
+        NetlistOptions::default().legalize_names(),
+    )
+    .export()
+    .unwrap();
+    let netlist = String::from_utf8(buf).unwrap();
+    println!("{}", netlist);
+
+    assert!(netlist.contains(".SUBCKT legalize a b\n"));
+    assert!(netlist.contains("Rr0 a x0 100"));
+    assert!(netlist.contains("RR0_1 x0 x_y 100"));
+    assert!(netlist.contains("Rr1 x_y A_1 100"));
+    assert!(netlist.contains("Rr2 A_1 b 100"));
+
+    let cell = lib.cell(id);
+    let mut signals = conv.cells[&id]
+        .signals
+        .iter()
+        .map(|(id, name)| (cell.signal(*id).name.as_str(), name.as_str()))
+        .collect::<Vec<_>>();
+    signals.sort();
+    assert_eq!(signals, vec![("0", "x0"), ("A", "A_1"), ("x y", "x_y")]);
+
+    // Names within preserved cells are never legalized.
+    let (id, lib) = build(false, true);
+    let mut buf = Vec::new();
+    let conv = NetlisterInstance::new(
+        &Spice,
+        &lib,
+        &mut buf,
+        NetlistOptions::default().legalize_names(),
+    )
+    .export()
+    .unwrap();
+    let netlist = String::from_utf8(buf).unwrap();
+    assert!(netlist.contains("RR0 0 x y 100"));
+    assert!(netlist.contains("Rr1 x y A 100"));
+    assert!(conv.cells[&id].signals.is_empty());
+
+    // Ports are never renamed.
+    let (_, lib) = build(true, false);
+    let mut buf = Vec::new();
+    assert!(NetlisterInstance::new(
+        &Spice,
+        &lib,
+        &mut buf,
+        NetlistOptions::default().legalize_names(),
+    )
+    .export()
+    .is_err());
+}
+
+#[test]
+fn netlist_respects_port_order() {
+    let mut lib = LibraryBuilder::<Spice>::new();
+    let mut dut = Cell::new("dut");
+    let a = dut.add_node("a");
+    let b = dut.add_node("b");
+    let c = dut.add_bus("c", 2);
+    dut.expose_port(a, Direction::Input);
+    dut.expose_port(b, Direction::Input);
+    dut.expose_port(c, Direction::Output);
+    dut.set_port_order(["c", "a"]);
+    assert_eq!(dut.signal(a.signal()).port, Some(2));
+    assert_eq!(dut.signal(b.signal()).port, Some(3));
+    assert_eq!(dut.signal(c.signal()).port, Some(0));
+    let dut = lib.add_cell(dut);
+
+    let mut top = Cell::new("top");
+    let x = top.add_node("x");
+    let y = top.add_node("y");
+    let z = top.add_bus("z", 2);
+    let mut inst = Instance::new("dut", dut);
+    inst.connect("a", x);
+    inst.connect("b", y);
+    inst.connect("c", z);
+    top.add_instance(inst);
+    top.expose_port(z, Direction::Output);
+    top.expose_port(x, Direction::Input);
+    top.expose_port(y, Direction::Input);
+    top.sort_ports(scir::PortOrder::BusGrouped);
+    lib.add_cell(top);
+    let lib = lib.build().unwrap();
+
+    let mut buf = Vec::new();
+    NetlisterInstance::new(&Spice, &lib, &mut buf, Default::default())
+        .export()
+        .unwrap();
+    let netlist = String::from_utf8(buf).unwrap();
+    println!("{}", netlist);
+
+    assert!(netlist.contains(".SUBCKT dut c[0] c[1] a b"));
+    assert!(netlist.contains(".SUBCKT top x y z[0] z[1]"));
+    assert!(netlist.contains("Xdut z[0] z[1] x y dut"));
+}
+
+#[test]
+fn netlist_global_nets() {
+    let mut lib = LibraryBuilder::<Spice>::new();
+    lib.add_global("vdd!");
+    let res = lib.add_primitive(Primitive::Res2 {
+        value: ComponentValue::Fixed(dec!(100)),
+        params: Default::default(),
+        m: 1,
+    });
+    let mut tb = Cell::new("tb");
+    let vss = tb.add_node("vss");
+    let vdd = tb.add_global("vdd!");
+    tb.expose_port(vss, Direction::InOut);
+    let mut r = Instance::new("r", res);
+    r.connect("1", vdd);
+    r.connect("2", vss);
+    tb.add_instance(r);
+    let tb = lib.add_cell(tb);
+    lib.set_top(tb);
+    let lib = lib.build().unwrap();
+
+    let mut buf = Vec::new();
+    let conv = NetlisterInstance::new(
+        &Spice,
+        &lib,
+        &mut buf,
+        NetlistOptions::new(NetlistKind::Testbench(RenameGround::Yes("0".into())), &[])
+            .with_naming(InstanceNaming::Stable),
+    )
+    .export()
+    .unwrap();
+    let netlist = String::from_utf8(buf).unwrap();
+    println!("{}", netlist);
+
+    assert!(netlist.contains(".GLOBAL vdd!"));
+    assert!(netlist
+        .lines()
+        .any(|line| line.starts_with("Rprim_") && line.ends_with(" vdd! 0 100")));
+    assert!(conv.cells[&tb].signals.is_empty());
+
+    let export = |opts: NetlistOptions| {
+        let mut buf = Vec::new();
+        NetlisterInstance::new(&Spice, &lib, &mut buf, opts).export()?;
+        Ok::<_, std::io::Error>(String::from_utf8(buf).unwrap())
+    };
+    let kind = || NetlistKind::Testbench(RenameGround::Yes("0".into()));
+
+    let netlist = export(NetlistOptions::new(kind(), &[]).tie_global("vdd!", dec!(1.8))).unwrap();
+    println!("{}", netlist);
+    assert!(netlist.contains("Vglobal_vdd vdd! 0 1.8"));
+
+    let err = export(NetlistOptions::new(kind(), &[]).tie_global("vss!", dec!(0))).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+    // Global nets cannot be mapped to simulator nodes.
+    let err = export(NetlistOptions::new(kind(), &[]).map_node(NodeMapping::new("vdd!", "vdd")))
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn netlist_testbench_bus_ground_is_rejected() {
+    let mut lib = LibraryBuilder::<Spice>::new();
+    let mut tb = Cell::new("tb");
+    let vss = tb.add_bus("vss", 2);
+    tb.expose_port(vss, Direction::InOut);
+    let tb = lib.add_cell(tb);
+    lib.set_top(tb);
+    let lib = lib.build().unwrap();
+
+    let mut buf = Vec::new();
+    let err = NetlisterInstance::new(
+        &Spice,
+        &lib,
+        &mut buf,
+        NetlistOptions::new(NetlistKind::Testbench(RenameGround::Yes("0".into())), &[]),
+    )
+    .export()
+    .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn netlist_testbench_node_mappings() {
+    let mut lib = LibraryBuilder::<Spice>::new();
+    let res = lib.add_primitive(Primitive::Res2 {
+        value: ComponentValue::Fixed(dec!(100)),
+        params: Default::default(),
+        m: 1,
+    });
+    let mut tb = Cell::new("tb");
+    let vss = tb.add_node("vss");
+    let vdd = tb.add_node("vdd");
+    let vin = tb.add_node("vin");
+    let internal = tb.add_node("internal");
+    tb.expose_port(vss, Direction::InOut);
+    tb.expose_port(vdd, Direction::InOut);
+    tb.expose_port(vin, Direction::InOut);
+    for (name, p, n) in [("r1", vdd, vin), ("r2", vin, vss), ("r3", internal, vss)] {
+        let mut r = Instance::new(name, res);
+        r.connect("1", p);
+        r.connect("2", n);
+        tb.add_instance(r);
+    }
+    let tb = lib.add_cell(tb);
+    lib.set_top(tb);
+    let lib = lib.build().unwrap();
+
+    let export = |opts: NetlistOptions| {
+        let mut buf = Vec::new();
+        let conv = NetlisterInstance::new(&Spice, &lib, &mut buf, opts).export()?;
+        Ok::<_, std::io::Error>((String::from_utf8(buf).unwrap(), conv))
+    };
+    let kind = || NetlistKind::Testbench(RenameGround::Yes("0".into()));
+
+    let (netlist, conv) = export(
+        NetlistOptions::new(kind(), &[])
+            .map_node(NodeMapping::supply("vdd", "vdd_sim", dec!(1.8)))
+            .map_node(NodeMapping::new("vin", "in")),
+    )
+    .unwrap();
+    println!("{}", netlist);
+    assert!(netlist.contains("Rr1 vdd_sim in 100"));
+    assert!(netlist.contains("Rr2 in 0 100"));
+    assert!(netlist.contains("Vsupply_vdd vdd_sim 0 1.8"));
+    let cell = lib.cell(tb);
+    assert_eq!(
+        conv.cells[&tb].signals[&cell.signal_named("vin").id].as_str(),
+        "in"
+    );
+
+    // Every port other than ground must be mapped.
+    assert!(
+        export(NetlistOptions::new(kind(), &[]).map_node(NodeMapping::new("vin", "in"))).is_err()
+    );
+    // Mapped nets must be ports.
+    assert!(export(
+        NetlistOptions::new(kind(), &[])
+            .map_node(NodeMapping::new("vdd", "vdd"))
+            .map_node(NodeMapping::new("vin", "in"))
+            .map_node(NodeMapping::new("internal", "x"))
+    )
+    .is_err());
+}
+
+#[test]
+fn netlist_ammeters() {
+    let mut lib = LibraryBuilder::<Spice>::new();
+    let res = lib.add_primitive(Primitive::Res2 {
+        value: ComponentValue::Fixed(dec!(100)),
+        params: Default::default(),
+        m: 1,
+    });
+    let mut div = Cell::new("div");
+    let p = div.add_node("p");
+    let n = div.add_node("n");
+    div.expose_port(p, Direction::InOut);
+    div.expose_port(n, Direction::InOut);
+    let mut r = Instance::new("r", res);
+    r.connect("1", p);
+    r.connect("2", n);
+    div.add_instance(r);
+    let div = lib.add_cell(div);
+
+    let mut tb = Cell::new("tb");
+    let vss = tb.add_node("vss");
+    let vdd = tb.add_node("vdd");
+    tb.expose_port(vss, Direction::InOut);
+    let mut dut = Instance::new("dut", div);
+    dut.connect("p", vdd);
+    dut.connect("n", vss);
+    tb.add_instance(dut);
+    let tb = lib.add_cell(tb);
+    lib.set_top(tb);
+    let lib = lib.build().unwrap();
+
+    let ammeter = |instance: &str, port: &str, index: usize, name: &str| Ammeter {
+        cell: div,
+        instance: instance.into(),
+        port: port.into(),
+        index,
+        name: name.into(),
+    };
+    let export = |ammeter: Ammeter| {
+        let mut buf = Vec::new();
+        NetlisterInstance::new(
+            &Spice,
+            &lib,
+            &mut buf,
+            NetlistOptions::new(NetlistKind::Testbench(RenameGround::Yes("0".into())), &[])
+                .insert_ammeter(ammeter),
+        )
+        .export()?;
+        Ok::<_, std::io::Error>(String::from_utf8(buf).unwrap())
+    };
+
+    let netlist = export(ammeter("r", "1", 0, "am")).unwrap();
+    println!("{}", netlist);
+    assert!(netlist.contains("Rr am n 100"));
+    assert!(netlist.contains("Vam p am 0"));
+    // The library is not modified.
+    assert_eq!(lib.cell(div).instances().count(), 1);
+
+    // The measured instance, terminal, and bit must exist.
+    assert!(export(ammeter("r2", "1", 0, "am")).is_err());
+    assert!(export(ammeter("r", "3", 0, "am")).is_err());
+    assert!(export(ammeter("r", "1", 1, "am")).is_err());
+    // The ammeter node must not collide with an existing signal.
+    assert!(export(ammeter("r", "1", 0, "n")).is_err());
+}
+
+#[test]
+fn netlist_multiple_roots() {
+    let mut lib = LibraryBuilder::<Spice>::new();
+    let res = lib.add_primitive(Primitive::Res2 {
+        value: ComponentValue::Fixed(dec!(100)),
+        params: Default::default(),
+        m: 1,
+    });
+    let mut dut = Cell::new("dut");
+    let p = dut.add_node("p");
+    let n = dut.add_node("n");
+    dut.expose_port(p, Direction::InOut);
+    dut.expose_port(n, Direction::InOut);
+    let mut r = Instance::new("r", res);
+    r.connect("1", p);
+    r.connect("2", n);
+    dut.add_instance(r);
+    let dut = lib.add_cell(dut);
+
+    let mut tbs = Vec::new();
+    for name in ["tb1", "tb2"] {
+        let mut tb = Cell::new(name);
+        let vss = tb.add_node("vss");
+        let a = tb.add_node("a");
+        tb.expose_port(vss, Direction::InOut);
+        let mut inst = Instance::new("dut0", dut);
+        inst.connect("p", a);
+        inst.connect("n", vss);
+        tb.add_instance(inst);
+        tbs.push(lib.add_cell(tb));
+    }
+    let (tb1, tb2) = (tbs[0], tbs[1]);
+    lib.add_root(dut);
+    lib.add_root(tb1);
+    lib.add_root(tb2);
+    let lib = lib.build().unwrap();
+    assert_eq!(lib.roots().collect::<Vec<_>>(), vec![dut, tb1, tb2]);
+
+    let export = |opts: NetlistOptions| {
+        let mut buf = Vec::new();
+        NetlisterInstance::new(&Spice, &lib, &mut buf, opts).export()?;
+        Ok::<_, std::io::Error>(String::from_utf8(buf).unwrap())
+    };
+    let kind = || NetlistKind::Testbench(RenameGround::Yes("0".into()));
+
+    let netlist =
+        export(NetlistOptions::new(NetlistKind::Cells, &[]).root_kind(tb1, kind())).unwrap();
+    println!("{}", netlist);
+    assert!(netlist.contains(".SUBCKT dut p n"));
+    assert!(netlist.contains(".SUBCKT tb2 vss"));
+    assert!(!netlist.contains(".SUBCKT tb1"));
+    assert!(netlist.contains("\nXdut0 a 0 dut\n"));
+
+    // At most one root can be inlined as a testbench.
+    assert!(export(
+        NetlistOptions::new(NetlistKind::Cells, &[])
+            .root_kind(tb1, kind())
+            .root_kind(tb2, kind())
+    )
+    .is_err());
+}
+
+#[test]
+fn netlist_attributes() {
+    let mut lib = LibraryBuilder::<Spice>::new();
+    let res = lib.add_primitive(Primitive::Res2 {
+        value: ComponentValue::Fixed(dec!(100)),
+        params: Default::default(),
+        m: 1,
+    });
+    let mut cell = Cell::new("dummy_res");
+    let p = cell.add_node("p");
+    let n = cell.add_node("n");
+    cell.expose_port(p, Direction::InOut);
+    cell.expose_port(n, Direction::InOut);
+    cell.set_attribute("CONNECT", "p n");
+    let mut r = Instance::new("r", res);
+    r.connect("1", p);
+    r.connect("2", n);
+    r.set_attribute("LVS_IGNORE", "");
+    cell.add_instance(r);
+    lib.add_cell(cell);
+    let lib = lib.build().unwrap();
+
+    let mut buf = Vec::new();
+    NetlisterInstance::new(&Spice, &lib, &mut buf, Default::default())
+        .export()
+        .unwrap();
+    let netlist = String::from_utf8(buf).unwrap();
+    println!("{}", netlist);
+
+    assert!(netlist
+        .contains(".SUBCKT dummy_res p n\n\n  *.CONNECT p n\n  *.LVS_IGNORE\n  Rr p n 100\n"));
+}
+
+#[test]
+fn netlist_cell_comments() {
+    let mut lib = LibraryBuilder::<Spice>::new();
+    let res = lib.add_primitive(Primitive::Res2 {
+        value: ComponentValue::Fixed(dec!(100)),
+        params: Default::default(),
+        m: 1,
+    });
+    let mut cell = Cell::new("dummy_res");
+    let p = cell.add_node("p");
+    let n = cell.add_node("n");
+    cell.expose_port(p, Direction::InOut);
+    cell.expose_port(n, Direction::InOut);
+    cell.add_comment("substrate_generator: dummy_res");
+    cell.add_comment("substrate_version: 1.0.0");
+    cell.add_comment("substrate_parameters: Res {\n    value: 100,\n}");
+    let mut r = Instance::new("r", res);
+    r.connect("1", p);
+    r.connect("2", n);
+    cell.add_instance(r);
+    lib.add_cell(cell);
+    let lib = lib.build().unwrap();
+
+    let mut buf = Vec::new();
+    NetlisterInstance::new(&Spice, &lib, &mut buf, Default::default())
+        .export()
+        .unwrap();
+    let netlist = String::from_utf8(buf).unwrap();
+    println!("{}", netlist);
+
+    assert!(netlist.contains(
+        "* substrate_generator: dummy_res\n* substrate_version: 1.0.0\n\
+         * substrate_parameters: Res {\n*     value: 100,\n* }\n.SUBCKT dummy_res p n\n"
+    ));
+}
+
+#[test]
+fn netlist_cell_params() {
+    let mut lib = LibraryBuilder::<Spice>::new();
+    let res = lib.add_primitive(Primitive::Res2 {
+        value: ComponentValue::Fixed(dec!(100)),
+        params: Default::default(),
+        m: 1,
+    });
+    let mut unit = Cell::new("unit");
+    let p = unit.add_node("p");
+    let n = unit.add_node("n");
+    unit.expose_port(p, Direction::InOut);
+    unit.expose_port(n, Direction::InOut);
+    unit.add_param("nf", Param::new(dec!(1)));
+    let mut r = Instance::new("r", res);
+    r.connect("1", p);
+    r.connect("2", n);
+    unit.add_instance(r);
+    let unit = lib.add_cell(unit);
+
+    let mut top = Cell::new("top");
+    let p = top.add_node("p");
+    let n = top.add_node("n");
+    top.expose_port(p, Direction::InOut);
+    top.expose_port(n, Direction::InOut);
+    top.add_param("scale", Param::new(dec!(2)));
+    let mut x0 = Instance::new("x0", unit);
+    x0.connect("p", p);
+    x0.connect("n", n);
+    x0.set_param("nf", dec!(4));
+    top.add_instance(x0);
+    let mut x1 = Instance::new("x1", unit);
+    x1.connect("p", p);
+    x1.connect("n", n);
+    x1.set_param("nf", Expr::Param(arcstr::literal!("scale")));
+    top.add_instance(x1);
+    lib.add_cell(top);
+    let lib = lib.build().unwrap();
+
+    let mut buf = Vec::new();
+    NetlisterInstance::new(&Spice, &lib, &mut buf, Default::default())
+        .export()
+        .unwrap();
+    let netlist = String::from_utf8(buf).unwrap();
+    println!("{}", netlist);
+
+    assert!(netlist.contains(".SUBCKT unit p n PARAMS: nf=1\n"));
+    assert!(netlist.contains(".SUBCKT top p n PARAMS: scale=2\n"));
+    assert!(netlist.contains("  Xx0 p n unit nf=4\n"));
+    assert!(netlist.contains("  Xx1 p n unit nf={scale}\n"));
+}
+
+#[test]
+fn netlist_multipliers() {
+    let mut lib = LibraryBuilder::<Spice>::new();
+    let res = Primitive::Res2 {
+        value: ComponentValue::Fixed(dec!(100)),
+        params: Default::default(),
+        m: 1,
+    };
+    let single = lib.add_primitive(res.clone());
+    let multiplied = lib.add_primitive(res.multiplied(2).unwrap().multiplied(3).unwrap());
+    let raw = lib.add_primitive(
+        Primitive::RawInstance {
+            ports: vec!["p".into(), "n".into()],
+            cell: "unit".into(),
+            params: Default::default(),
+        }
+        .multiplied(4)
+        .unwrap(),
+    );
+    let mut cell = Cell::new("parallel");
+    let p = cell.add_node("p");
+    let n = cell.add_node("n");
+    cell.expose_port(p, Direction::InOut);
+    cell.expose_port(n, Direction::InOut);
+    for (name, child) in [("r0", single), ("r1", multiplied)] {
+        let mut r = Instance::new(name, child);
+        r.connect("1", p);
+        r.connect("2", n);
+        cell.add_instance(r);
+    }
+    let mut x = Instance::new("x0", raw);
+    x.connect("p", p);
+    x.connect("n", n);
+    cell.add_instance(x);
+    lib.add_cell(cell);
+    let lib = lib.build().unwrap();
+
+    let mut buf = Vec::new();
+    NetlisterInstance::new(&Spice, &lib, &mut buf, Default::default())
+        .export()
+        .unwrap();
+    let netlist = String::from_utf8(buf).unwrap();
+    println!("{}", netlist);
+
+    assert!(netlist.contains("  Rr0 p n 100\n"));
+    assert!(netlist.contains("  Rr1 p n 100 m=6\n"));
+    assert!(netlist.contains("  Xx0 p n unit m=4\n"));
+}
+
+#[test]
+fn netlist_primitive_params() {
+    let mut lib = LibraryBuilder::<Spice>::new();
+    let nmos = lib.add_primitive(Primitive::RawInstance {
+        cell: arcstr::literal!("nmos"),
+        ports: vec!["d".into(), "g".into(), "s".into(), "b".into()],
+        params: Default::default(),
+    });
+    let mut unit = Cell::new("unit");
+    let d = unit.add_node("d");
+    let g = unit.add_node("g");
+    let s = unit.add_node("s");
+    for port in [d, g, s] {
+        unit.expose_port(port, Direction::InOut);
+    }
+    unit.add_param("wp", Param::new(dec!(1)));
+    let mut m = Instance::new("m", nmos);
+    m.connect("d", d);
+    m.connect("g", g);
+    m.connect("s", s);
+    m.connect("b", s);
+    m.set_param("w", Expr::Param(arcstr::literal!("wp")));
+    unit.add_instance(m);
+    lib.add_cell(unit);
+    let lib = lib.build().unwrap();
+
+    let mut buf = Vec::new();
+    NetlisterInstance::new(&Spice, &lib, &mut buf, Default::default())
+        .export()
+        .unwrap();
+    let netlist = String::from_utf8(buf).unwrap();
+    println!("{}", netlist);
+
+    assert!(netlist.contains("  Xm d g s s nmos w={wp}\n"));
+
+    let mut lib = LibraryBuilder::<Spice>::new();
+    let res = lib.add_primitive(Primitive::Res2 {
+        value: ComponentValue::Fixed(dec!(100)),
+        params: Default::default(),
+        m: 1,
+    });
+    let mut cell = Cell::new("cell");
+    let p = cell.add_node("p");
+    let mut r = Instance::new("r", res);
+    r.connect("1", p);
+    r.connect("2", p);
+    r.set_param("w", dec!(1));
+    cell.add_instance(r);
+    lib.add_cell(cell);
+    assert!(lib.validate().has_error());
+}
+
 /// Creates a 1:3 resistive voltage divider.
 pub(crate) fn vdivider() -> Library<Spice> {
     let mut lib = LibraryBuilder::new();
     let res = lib.add_primitive(crate::Primitive::Res2 {
         value: ComponentValue::Fixed(dec!(100)),
         params: Default::default(),
+        m: 1,
     });
 
     let mut vdivider = Cell::new("vdivider");
@@ -0,0 +1,35 @@
+//! Python bindings for Substrate generators.
+//!
+//! Generator libraries build a Python extension module by registering their blocks with a
+//! [`Cli`](substrate_cli::Cli), exactly as they would for the command-line interface, and
+//! calling `init_module` from a `pyo3::pymodule`. The bindings are only compiled with the
+//! `python` feature, so that building a workspace that depends on this crate does not
+//! require a Python installation:
+//!
+//! ```ignore
+//! use pyo3::prelude::*;
+//!
+//! #[pymodule]
+//! fn my_generators(m: &Bound<'_, PyModule>) -> PyResult<()> {
+//!     substrate_py::init_module(m, my_generators::setup)
+//! }
+//! ```
+//!
+//! The module can then be used from Python:
+//!
+//! ```python
+//! import my_generators
+//!
+//! ctx = my_generators.Context()
+//! gds = ctx.gds("inverter", {"strength": 2}).gds
+//! vout = ctx.simulate("inverter_tb", {"strength": 2}).data["vout"]
+//! ```
+#![warn(missing_docs)]
+
+#[cfg(feature = "python")]
+mod python;
+
+#[cfg(feature = "python")]
+pub use python::{init_module, PyContext, PyGenerated};
+#[cfg(feature = "python")]
+pub use {numpy, pyo3};
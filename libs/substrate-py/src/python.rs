@@ -0,0 +1,263 @@
+//! PyO3 bindings, compiled with the `python` feature.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, OnceLock};
+
+use numpy::{IntoPyArray, PyArray1};
+use pyo3::exceptions::{PyKeyError, PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDict};
+use substrate::block::registry::{Generated, Params};
+use substrate::context::Context;
+use substrate_cli::{Cli, Flow};
+
+static CLI: OnceLock<Arc<Cli>> = OnceLock::new();
+
+/// Adds the Substrate classes to the Python module `m`.
+///
+/// `setup` registers the generators available from Python. Generators are shared by all
+/// contexts in the process, so only one module may be initialized per process. Returns an
+/// error if another module (e.g. a second extension module loaded into the same interpreter)
+/// has already been initialized.
+pub fn init_module(m: &Bound<'_, PyModule>, setup: impl FnOnce(&mut Cli)) -> PyResult<()> {
+    let mut cli = Cli::new();
+    setup(&mut cli);
+    CLI.set(Arc::new(cli)).map_err(|_| {
+        PyRuntimeError::new_err(format!(
+            "cannot initialize Substrate module `{}`: another Substrate module was already \
+             initialized in this process",
+            m.name().map(|name| name.to_string()).unwrap_or_default()
+        ))
+    })?;
+    m.add_class::<PyContext>()?;
+    m.add_class::<PyGenerated>()?;
+    Ok(())
+}
+
+/// A Substrate context in which registered generators are run.
+///
+/// Generated cells are cached within a context, so repeated calls to `generate`
+/// with the same parameters are cheap.
+#[pyclass(name = "Context", frozen)]
+pub struct PyContext {
+    cli: Arc<Cli>,
+    ctx: Context,
+}
+
+#[pymethods]
+impl PyContext {
+    #[new]
+    fn new() -> PyResult<Self> {
+        let cli = CLI
+            .get()
+            .cloned()
+            .ok_or_else(|| PyRuntimeError::new_err("Substrate module was not initialized"))?;
+        let ctx = cli.create_context();
+        Ok(Self { cli, ctx })
+    }
+
+    /// Returns the registered `(flow, generator)` pairs.
+    ///
+    /// If `flow` is given, only generators supporting `flow` are returned.
+    #[pyo3(signature = (flow = None))]
+    fn generators(&self, flow: Option<&str>) -> PyResult<Vec<(&'static str, String)>> {
+        let flows = match flow {
+            Some(flow) => vec![parse_flow(flow)?],
+            None => Flow::ALL.to_vec(),
+        };
+        Ok(flows
+            .into_iter()
+            .filter_map(|flow| Some((flow, self.cli.registry(flow)?)))
+            .flat_map(|(flow, registry)| {
+                registry
+                    .names()
+                    .map(move |name| (flow.name(), name.to_string()))
+            })
+            .collect())
+    }
+
+    /// Runs `flow` on the generator named `generator` with the given parameters.
+    ///
+    /// Parameter values are converted to strings before being passed to the generator.
+    /// Outputs are written to `out_dir`, which defaults to `build/<generator>/<flow>`.
+    #[pyo3(signature = (flow, generator, params = None, out_dir = None))]
+    fn generate(
+        &self,
+        py: Python<'_>,
+        flow: &str,
+        generator: &str,
+        params: Option<&Bound<'_, PyDict>>,
+        out_dir: Option<PathBuf>,
+    ) -> PyResult<PyGenerated> {
+        self.run(py, parse_flow(flow)?, generator, params, out_dir)
+    }
+
+    /// Writes a netlist of the generator named `generator`.
+    ///
+    /// Equivalent to `generate("netlist", generator, params, out_dir)`.
+    #[pyo3(signature = (generator, params = None, out_dir = None))]
+    fn netlist(
+        &self,
+        py: Python<'_>,
+        generator: &str,
+        params: Option<&Bound<'_, PyDict>>,
+        out_dir: Option<PathBuf>,
+    ) -> PyResult<PyGenerated> {
+        self.run(py, Flow::Netlist, generator, params, out_dir)
+    }
+
+    /// Writes the GDS of the generator named `generator`.
+    ///
+    /// Equivalent to `generate("gds", generator, params, out_dir)`.
+    #[pyo3(signature = (generator, params = None, out_dir = None))]
+    fn gds(
+        &self,
+        py: Python<'_>,
+        generator: &str,
+        params: Option<&Bound<'_, PyDict>>,
+        out_dir: Option<PathBuf>,
+    ) -> PyResult<PyGenerated> {
+        self.run(py, Flow::Gds, generator, params, out_dir)
+    }
+
+    /// Simulates the testbench named `generator`.
+    ///
+    /// Simulation outputs are available as NumPy arrays from the returned `Generated.data`.
+    /// Equivalent to `generate("simulate", generator, params, out_dir)`.
+    #[pyo3(signature = (generator, params = None, out_dir = None))]
+    fn simulate(
+        &self,
+        py: Python<'_>,
+        generator: &str,
+        params: Option<&Bound<'_, PyDict>>,
+        out_dir: Option<PathBuf>,
+    ) -> PyResult<PyGenerated> {
+        self.run(py, Flow::Simulate, generator, params, out_dir)
+    }
+
+    /// Runs design rule checking on the generator named `generator`.
+    ///
+    /// Equivalent to `generate("drc", generator, params, out_dir)`.
+    #[pyo3(signature = (generator, params = None, out_dir = None))]
+    fn drc(
+        &self,
+        py: Python<'_>,
+        generator: &str,
+        params: Option<&Bound<'_, PyDict>>,
+        out_dir: Option<PathBuf>,
+    ) -> PyResult<PyGenerated> {
+        self.run(py, Flow::Drc, generator, params, out_dir)
+    }
+
+    /// Runs layout versus schematic checking on the generator named `generator`.
+    ///
+    /// Equivalent to `generate("lvs", generator, params, out_dir)`.
+    #[pyo3(signature = (generator, params = None, out_dir = None))]
+    fn lvs(
+        &self,
+        py: Python<'_>,
+        generator: &str,
+        params: Option<&Bound<'_, PyDict>>,
+        out_dir: Option<PathBuf>,
+    ) -> PyResult<PyGenerated> {
+        self.run(py, Flow::Lvs, generator, params, out_dir)
+    }
+}
+
+impl PyContext {
+    fn run(
+        &self,
+        py: Python<'_>,
+        flow: Flow,
+        generator: &str,
+        params: Option<&Bound<'_, PyDict>>,
+        out_dir: Option<PathBuf>,
+    ) -> PyResult<PyGenerated> {
+        let registry = self
+            .cli
+            .registry(flow)
+            .filter(|registry| registry.contains(generator))
+            .ok_or_else(|| {
+                PyKeyError::new_err(format!(
+                    "no generator named `{generator}` supports `{flow}`"
+                ))
+            })?;
+
+        let mut values = Params::new();
+        if let Some(params) = params {
+            for (name, value) in params.iter() {
+                values.set(name.str()?.to_cow()?.as_ref(), param_value(&value)?);
+            }
+        }
+        let out_dir = out_dir.unwrap_or_else(|| flow.default_out_dir(generator));
+
+        let generated = py
+            .allow_threads(|| registry.generate(&self.ctx, generator, &values, &out_dir))
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Ok(PyGenerated(generated))
+    }
+}
+
+/// Returns the flow run by the subcommand named `name`.
+fn parse_flow(name: &str) -> PyResult<Flow> {
+    Flow::from_name(name).ok_or_else(|| PyValueError::new_err(format!("unknown flow `{name}`")))
+}
+
+/// Converts a Python parameter value to the string representation expected by Rust parsers.
+fn param_value(value: &Bound<'_, PyAny>) -> PyResult<String> {
+    if value.is_instance_of::<PyBool>() {
+        // Python formats booleans as `True` and `False`, which Rust does not parse.
+        return Ok(value.extract::<bool>()?.to_string());
+    }
+    Ok(value.str()?.to_cow()?.into_owned())
+}
+
+/// The outputs of a generator run from Python.
+#[pyclass(name = "Generated", frozen)]
+pub struct PyGenerated(Generated);
+
+#[pymethods]
+impl PyGenerated {
+    /// The path to the generated GDS file, if any.
+    #[getter]
+    fn gds(&self) -> Option<PathBuf> {
+        self.0.gds.clone()
+    }
+
+    /// The path to the generated netlist, if any.
+    #[getter]
+    fn netlist(&self) -> Option<PathBuf> {
+        self.0.netlist.clone()
+    }
+
+    /// Paths to other generated files, keyed by name.
+    #[getter]
+    fn outputs(&self) -> HashMap<String, PathBuf> {
+        self.0
+            .outputs
+            .iter()
+            .map(|(name, path)| (name.to_string(), path.clone()))
+            .collect()
+    }
+
+    /// Numeric data produced by the generator as NumPy arrays, keyed by name.
+    #[getter]
+    fn data<'py>(&self, py: Python<'py>) -> HashMap<String, Bound<'py, PyArray1<f64>>> {
+        self.0
+            .data
+            .iter()
+            .map(|(name, values)| (name.to_string(), values.clone().into_pyarray(py)))
+            .collect()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Generated(gds={:?}, netlist={:?}, outputs={:?}, data={:?})",
+            self.0.gds,
+            self.0.netlist,
+            self.0.outputs,
+            self.0.data.keys().collect::<Vec<_>>(),
+        )
+    }
+}
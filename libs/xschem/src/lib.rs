@@ -0,0 +1,420 @@
+//! Export SCIR libraries to [Xschem](https://xschem.sourceforge.io/) schematics.
+//!
+//! Each SCIR cell is exported as an Xschem symbol (`.sym`) and schematic (`.sch`), so that
+//! generated designs can be browsed, descended into, and probed in Xschem.
+//! Primitives are exported as symbols only, since their contents are opaque to SCIR.
+//!
+//! Instances are placed on a grid and connected using net labels,
+//! so the exported schematics preserve connectivity but not any particular layout.
+#![warn(missing_docs)]
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt::Write as _;
+use std::io::Write;
+use std::path::Path;
+
+use arcstr::ArcStr;
+use scir::schema::Schema;
+use scir::{Cell, ChildId, Concat, Direction, LibraryBuilder, PrimitiveId};
+
+#[cfg(test)]
+mod tests;
+
+/// The header written at the start of every Xschem file.
+const HEADER: &str = "v {xschem version=3.4.5 file_version=1.2}";
+
+/// The vertical spacing between symbol pins.
+const PIN_PITCH: i64 = 20;
+/// Half the width of a symbol's body.
+const BODY_HALF_WIDTH: i64 = 60;
+/// The length of the stub connecting each pin to a symbol's body.
+const PIN_LENGTH: i64 = 20;
+/// The horizontal spacing between instances in exported schematics.
+const INSTANCE_PITCH: i64 = 400;
+/// The number of instances placed in each row of an exported schematic.
+const INSTANCES_PER_ROW: usize = 4;
+
+/// The side of a symbol on which a pin is placed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    Left,
+    Right,
+}
+
+/// A pin of an exported symbol.
+#[derive(Debug, Clone)]
+struct SymbolPin {
+    name: ArcStr,
+    direction: Direction,
+    side: Side,
+    /// The location of the pin's connection point, relative to the symbol origin.
+    x: i64,
+    y: i64,
+}
+
+/// The pins and body of an exported symbol.
+#[derive(Debug, Clone)]
+struct Symbol {
+    pins: Vec<SymbolPin>,
+    /// The y-coordinate of the bottom of the symbol's body.
+    bottom: i64,
+}
+
+impl Symbol {
+    fn new(ports: impl IntoIterator<Item = (ArcStr, Direction)>) -> Self {
+        let mut counts = [0i64; 2];
+        let pins = ports
+            .into_iter()
+            .map(|(name, direction)| {
+                let side = match direction {
+                    Direction::Output => Side::Right,
+                    Direction::Input | Direction::InOut => Side::Left,
+                };
+                let count = &mut counts[side as usize];
+                let y = *count * PIN_PITCH;
+                *count += 1;
+                let x = match side {
+                    Side::Left => -(BODY_HALF_WIDTH + PIN_LENGTH),
+                    Side::Right => BODY_HALF_WIDTH + PIN_LENGTH,
+                };
+                SymbolPin {
+                    name,
+                    direction,
+                    side,
+                    x,
+                    y,
+                }
+            })
+            .collect();
+        let rows = counts.into_iter().max().unwrap_or_default().max(1);
+        Self {
+            pins,
+            bottom: rows * PIN_PITCH,
+        }
+    }
+
+    fn height(&self) -> i64 {
+        self.bottom + PIN_PITCH
+    }
+
+    fn write<W: Write>(&self, out: &mut W) -> std::io::Result<()> {
+        writeln!(out, "{HEADER}")?;
+        writeln!(out, "G {{}}")?;
+        writeln!(out, "K {{type=subcircuit")?;
+        writeln!(out, "format=\"@name @pinlist @symname\"")?;
+        writeln!(out, "template=\"name=x1\"")?;
+        writeln!(out, "}}")?;
+        writeln!(out, "V {{}}")?;
+        writeln!(out, "S {{}}")?;
+        writeln!(out, "E {{}}")?;
+
+        let (left, right, top, bottom) =
+            (-BODY_HALF_WIDTH, BODY_HALF_WIDTH, -PIN_PITCH, self.bottom);
+        writeln!(out, "L 4 {left} {top} {right} {top} {{}}")?;
+        writeln!(out, "L 4 {left} {bottom} {right} {bottom} {{}}")?;
+        writeln!(out, "L 4 {left} {top} {left} {bottom} {{}}")?;
+        writeln!(out, "L 4 {right} {top} {right} {bottom} {{}}")?;
+
+        for pin in self.pins.iter() {
+            let (x, y) = (pin.x, pin.y);
+            let (body_x, text_x, flip) = match pin.side {
+                Side::Left => (left, left + 5, 0),
+                Side::Right => (right, right - 5, 1),
+            };
+            let dir = match pin.direction {
+                Direction::Input => "in",
+                Direction::Output => "out",
+                Direction::InOut => "inout",
+            };
+            writeln!(out, "L 4 {x} {y} {body_x} {y} {{}}")?;
+            writeln!(
+                out,
+                "B 5 {} {} {} {} {{name={} dir={dir}}}",
+                x as f64 - 2.5,
+                y as f64 - 2.5,
+                x as f64 + 2.5,
+                y as f64 + 2.5,
+                escape(&pin.name),
+            )?;
+            writeln!(
+                out,
+                "T {{{}}} {text_x} {} 0 {flip} 0.2 0.2 {{}}",
+                escape(&pin.name),
+                y - 6
+            )?;
+        }
+
+        writeln!(out, "T {{@symname}} {left} {} 0 0 0.3 0.3 {{}}", top - 30)?;
+        writeln!(out, "T {{@name}} {right} {} 0 1 0.2 0.2 {{}}", top - 25)?;
+        Ok(())
+    }
+}
+
+/// Escapes a string for use within an Xschem property or text field.
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        if matches!(c, '{' | '}' | '\\') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Converts a name into a string suitable for use as a file name.
+fn file_stem(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Returns a file stem for `name` that is not already in `used`, and marks it as used.
+///
+/// Distinct names can map to the same [`file_stem`], so later names are disambiguated with a
+/// numeric suffix.
+fn unique_file_stem(name: &str, used: &mut HashSet<String>) -> String {
+    let stem = file_stem(name);
+    let stem = if used.contains(&stem) {
+        (1..)
+            .map(|i| format!("{stem}_{i}"))
+            .find(|stem| !used.contains(stem))
+            .unwrap()
+    } else {
+        stem
+    };
+    used.insert(stem.clone());
+    stem
+}
+
+/// Returns the Xschem bus notation for a signal of the given width (e.g. `data[7:0]`).
+///
+/// Zero-width buses have no bits, so they are named without a range.
+fn bus_name(name: &str, width: Option<usize>) -> String {
+    match width {
+        Some(width) if width > 0 => format!("{name}[{}:0]", width - 1),
+        _ => name.to_string(),
+    }
+}
+
+/// Returns the Xschem label corresponding to a concatenation of signals in `cell`.
+fn concat_label(cell: &Cell, concat: &Concat) -> String {
+    let mut label = String::new();
+    for (i, part) in concat.parts().enumerate() {
+        if i > 0 {
+            label.push(',');
+        }
+        let name = &cell.signal(part.signal()).name;
+        match part.range() {
+            Some(range) if range.width() == 1 => {
+                write!(label, "{name}[{}]", range.start()).unwrap();
+            }
+            Some(range) => {
+                write!(label, "{name}[{}:{}]", range.end() - 1, range.start()).unwrap();
+            }
+            None => label.push_str(name),
+        }
+    }
+    label
+}
+
+/// An exporter of SCIR libraries to Xschem schematics and symbols.
+pub struct XschemExporter<'a, S: Schema + ?Sized> {
+    lib: &'a LibraryBuilder<S>,
+    /// The file stem and symbol of each cell.
+    cell_symbols: HashMap<scir::CellId, (String, Symbol)>,
+    /// The file stem and symbol of each primitive.
+    primitive_symbols: HashMap<PrimitiveId, (String, Symbol)>,
+}
+
+impl<'a, S: Schema + ?Sized> XschemExporter<'a, S> {
+    /// Creates a new exporter for the given library.
+    ///
+    /// Accepts both built [`scir::Library`]s and unvalidated [`LibraryBuilder`]s.
+    pub fn new(lib: &'a LibraryBuilder<S>) -> Self {
+        let mut stems = HashSet::new();
+        let cell_symbols = lib
+            .cells()
+            .map(|(id, cell)| {
+                let symbol = Symbol::new(cell.ports().map(|port| {
+                    let signal = cell.signal(port.signal());
+                    (
+                        bus_name(&signal.name, signal.width).into(),
+                        port.direction(),
+                    )
+                }));
+                (id, (unique_file_stem(cell.name(), &mut stems), symbol))
+            })
+            .collect();
+
+        // Primitives do not declare ports, so their symbols are derived
+        // from the ports connected in each of their instances.
+        let mut primitive_ports: HashMap<PrimitiveId, BTreeSet<ArcStr>> = HashMap::new();
+        for (_, cell) in lib.cells() {
+            for (_, inst) in cell.instances() {
+                if let ChildId::Primitive(id) = inst.child() {
+                    primitive_ports
+                        .entry(id)
+                        .or_default()
+                        .extend(inst.connections().keys().cloned());
+                }
+            }
+        }
+        let primitive_symbols = lib
+            .primitives()
+            .map(|(id, _)| {
+                let ports = primitive_ports.remove(&id).unwrap_or_default();
+                let symbol = Symbol::new(ports.into_iter().map(|port| (port, Direction::InOut)));
+                (id, (unique_file_stem(&id.to_string(), &mut stems), symbol))
+            })
+            .collect();
+
+        Self {
+            lib,
+            cell_symbols,
+            primitive_symbols,
+        }
+    }
+
+    fn child_symbol(&self, child: ChildId) -> &(String, Symbol) {
+        match child {
+            ChildId::Cell(id) => &self.cell_symbols[&id],
+            ChildId::Primitive(id) => &self.primitive_symbols[&id],
+        }
+    }
+
+    /// Writes the symbol of the given cell.
+    pub fn write_symbol<W: Write>(&self, cell: scir::CellId, out: &mut W) -> std::io::Result<()> {
+        self.cell_symbols[&cell].1.write(out)
+    }
+
+    /// Writes the schematic of the given cell.
+    ///
+    /// The cell's ports are drawn as Xschem pins, and its instances are placed on a grid
+    /// with a net label at each instance pin.
+    pub fn write_schematic<W: Write>(
+        &self,
+        cell: scir::CellId,
+        out: &mut W,
+    ) -> std::io::Result<()> {
+        let cell = self.lib.cell(cell);
+        writeln!(out, "{HEADER}")?;
+        writeln!(out, "G {{}}")?;
+        writeln!(out, "K {{}}")?;
+        writeln!(out, "V {{}}")?;
+        writeln!(out, "S {{}}")?;
+        writeln!(out, "E {{}}")?;
+
+        for (i, port) in cell.ports().enumerate() {
+            let signal = cell.signal(port.signal());
+            let symbol = match port.direction() {
+                Direction::Input => "ipin.sym",
+                Direction::Output => "opin.sym",
+                Direction::InOut => "iopin.sym",
+            };
+            writeln!(
+                out,
+                "C {{{symbol}}} {} {} 0 0 {{name=p{i} lab={}}}",
+                -INSTANCE_PITCH,
+                i as i64 * PIN_PITCH * 2,
+                escape(&bus_name(&signal.name, signal.width)),
+            )?;
+        }
+
+        let mut labels = 0;
+        let mut y = 0;
+        let instances = cell.instances().map(|(_, inst)| inst).collect::<Vec<_>>();
+        for row in instances.chunks(INSTANCES_PER_ROW) {
+            let row_height = row
+                .iter()
+                .map(|inst| self.child_symbol(inst.child()).1.height())
+                .max()
+                .unwrap_or_default();
+            y += PIN_PITCH * 2;
+            for (col, inst) in row.iter().enumerate() {
+                let x = col as i64 * INSTANCE_PITCH;
+                let (stem, symbol) = self.child_symbol(inst.child());
+                writeln!(
+                    out,
+                    "C {{{}.sym}} {x} {y} 0 0 {{name={}}}",
+                    escape(stem),
+                    escape(inst.name())
+                )?;
+                for pin in symbol.pins.iter() {
+                    let port = pin
+                        .name
+                        .split_once('[')
+                        .map_or(pin.name.as_str(), |(name, _)| name);
+                    let Some(concat) = inst.connections().get(port) else {
+                        continue;
+                    };
+                    let flip = match pin.side {
+                        Side::Left => 0,
+                        Side::Right => 1,
+                    };
+                    writeln!(
+                        out,
+                        "C {{lab_pin.sym}} {} {} 0 {flip} {{name=l{labels} sig_type=std_logic lab={}}}",
+                        x + pin.x,
+                        y + pin.y,
+                        escape(&concat_label(cell, concat)),
+                    )?;
+                    labels += 1;
+                }
+            }
+            y += row_height;
+        }
+        Ok(())
+    }
+
+    /// Writes the symbol of the given primitive.
+    pub fn write_primitive_symbol<W: Write>(
+        &self,
+        primitive: PrimitiveId,
+        out: &mut W,
+    ) -> std::io::Result<()> {
+        self.primitive_symbols[&primitive].1.write(out)
+    }
+
+    /// Exports all cells and primitives in the library to the given directory.
+    ///
+    /// Also writes an `xschemrc` that adds the directory and Xschem's device library
+    /// to the symbol search path, so the exported files can be opened with
+    /// `xschem --rcfile <dir>/xschemrc <dir>/<cell>.sch`.
+    pub fn export(&self, dir: impl AsRef<Path>) -> std::io::Result<()> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+        for (id, (stem, _)) in self.cell_symbols.iter() {
+            let mut f = std::fs::File::create(dir.join(format!("{stem}.sym")))?;
+            self.write_symbol(*id, &mut f)?;
+            let mut f = std::fs::File::create(dir.join(format!("{stem}.sch")))?;
+            self.write_schematic(*id, &mut f)?;
+        }
+        for (id, (stem, _)) in self.primitive_symbols.iter() {
+            let mut f = std::fs::File::create(dir.join(format!("{stem}.sym")))?;
+            self.write_primitive_symbol(*id, &mut f)?;
+        }
+        std::fs::write(
+            dir.join("xschemrc"),
+            "append XSCHEM_LIBRARY_PATH :[file dirname [info script]]\n\
+             append XSCHEM_LIBRARY_PATH :${XSCHEM_SHAREDIR}/xschem_library/devices\n",
+        )?;
+        Ok(())
+    }
+}
+
+/// Exports all cells and primitives in `lib` to Xschem files in `dir`.
+///
+/// See [`XschemExporter::export`] for details.
+pub fn export_xschem<S: Schema + ?Sized>(
+    lib: &LibraryBuilder<S>,
+    dir: impl AsRef<Path>,
+) -> std::io::Result<()> {
+    XschemExporter::new(lib).export(dir)
+}
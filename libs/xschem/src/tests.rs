@@ -0,0 +1,108 @@
+use scir::schema::StringSchema;
+use scir::{Cell, Direction, IndexOwned, Instance, LibraryBuilder};
+
+use crate::{bus_name, XschemExporter};
+
+#[test]
+fn export_vdivider() {
+    let mut lib = LibraryBuilder::<StringSchema>::new();
+    let res = lib.add_primitive("res".into());
+
+    let mut vdivider = Cell::new("vdivider");
+    let vdd = vdivider.add_node("vdd");
+    let out = vdivider.add_node("out");
+    let vss = vdivider.add_node("vss");
+    let mut r1 = Instance::new("r1", res);
+    r1.connect("p", vdd);
+    r1.connect("n", out);
+    vdivider.add_instance(r1);
+    let mut r2 = Instance::new("r2", res);
+    r2.connect("p", out);
+    r2.connect("n", vss);
+    vdivider.add_instance(r2);
+    vdivider.expose_port(vdd, Direction::InOut);
+    vdivider.expose_port(vss, Direction::InOut);
+    vdivider.expose_port(out, Direction::Output);
+    let vdivider = lib.add_cell(vdivider);
+
+    let mut top = Cell::new("top");
+    let bus = top.add_bus("bus", 3);
+    let mut xdiv = Instance::new("xdiv", vdivider);
+    xdiv.connect("vdd", bus.index(2));
+    xdiv.connect("out", bus.index(1));
+    xdiv.connect("vss", bus.index(0));
+    top.add_instance(xdiv);
+    top.expose_port(bus, Direction::InOut);
+    let top = lib.add_cell(top);
+    lib.set_top(top);
+
+    let exporter = XschemExporter::new(&lib);
+
+    let mut sym = Vec::new();
+    exporter.write_symbol(vdivider, &mut sym).unwrap();
+    let sym = String::from_utf8(sym).unwrap();
+    assert!(sym.starts_with("v {xschem version="));
+    assert!(sym.contains("B 5 -82.5 -2.5 -77.5 2.5 {name=vdd dir=inout}"));
+    assert!(sym.contains("B 5 -82.5 17.5 -77.5 22.5 {name=vss dir=inout}"));
+    assert!(sym.contains("B 5 77.5 -2.5 82.5 2.5 {name=out dir=out}"));
+
+    let mut sch = Vec::new();
+    exporter.write_schematic(top, &mut sch).unwrap();
+    let sch = String::from_utf8(sch).unwrap();
+    assert!(sch.contains("C {iopin.sym} -400 0 0 0 {name=p0 lab=bus[2:0]}"));
+    assert!(sch.contains("C {vdivider.sym} 0 40 0 0 {name=xdiv}"));
+    assert!(sch.contains("C {lab_pin.sym} -80 40 0 0 {name=l0 sig_type=std_logic lab=bus[2]}"));
+    assert!(sch.contains("C {lab_pin.sym} 80 40 0 1 {name=l2 sig_type=std_logic lab=bus[1]}"));
+
+    let mut sch = Vec::new();
+    exporter.write_schematic(vdivider, &mut sch).unwrap();
+    let sch = String::from_utf8(sch).unwrap();
+    assert!(sch.contains("C {primitive1.sym} 0 40 0 0 {name=r1}"));
+    assert!(sch.contains("C {primitive1.sym} 400 40 0 0 {name=r2}"));
+}
+
+#[test]
+fn colliding_file_names_are_disambiguated() {
+    let mut lib = LibraryBuilder::<StringSchema>::new();
+    let res = lib.add_primitive("res".into());
+    let spaced = lib.add_cell(Cell::new("a b"));
+    let underscored = lib.add_cell(Cell::new("a_b"));
+    let mut top = Cell::new("primitive1");
+    let vdd = top.add_node("vdd");
+    let mut r1 = Instance::new("r1", res);
+    r1.connect("p", vdd);
+    top.add_instance(r1);
+    top.add_instance(Instance::new("x0", spaced));
+    top.add_instance(Instance::new("x1", underscored));
+    let top = lib.add_cell(top);
+    lib.set_top(top);
+
+    let exporter = XschemExporter::new(&lib);
+    let mut sch = Vec::new();
+    exporter.write_schematic(top, &mut sch).unwrap();
+    let sch = String::from_utf8(sch).unwrap();
+    assert!(sch.contains("C {primitive1_1.sym} 0 40 0 0 {name=r1}"));
+    assert!(sch.contains("C {a_b.sym} 400 40 0 0 {name=x0}"));
+    assert!(sch.contains("C {a_b_1.sym} 800 40 0 0 {name=x1}"));
+
+    let dir = tempfile::tempdir().unwrap();
+    exporter.export(dir.path()).unwrap();
+    for file in [
+        "a_b.sym",
+        "a_b.sch",
+        "a_b_1.sym",
+        "a_b_1.sch",
+        "primitive1.sym",
+        "primitive1.sch",
+        "primitive1_1.sym",
+    ] {
+        assert!(dir.path().join(file).exists(), "{file} was not exported");
+    }
+}
+
+#[test]
+fn zero_width_buses_have_no_range() {
+    assert_eq!(bus_name("data", Some(8)), "data[7:0]");
+    assert_eq!(bus_name("data", Some(0)), "data");
+    assert_eq!(bus_name("data", None), "data");
+}
@@ -0,0 +1,131 @@
+//! Capacitor devices.
+
+use std::collections::HashMap;
+
+use arcstr::ArcStr;
+use layir::Shape;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use substrate::block::Block;
+use substrate::geometry::rect::Rect;
+use substrate::layout::rules::centered_cuts;
+use substrate::layout::{CellBuilder, Layout};
+use substrate::schematic::{PrimitiveBinding, Schematic};
+use substrate::types::codegen::PortGeometryBundle;
+use substrate::types::layout::PortGeometry;
+use substrate::types::{TwoTerminalIo, TwoTerminalIoView};
+
+use crate::layers::Sky130Layer;
+use crate::rules::rules;
+use crate::{Primitive, Sky130};
+
+/// A metal-insulator-metal (MIM) capacitor between met3 and met4.
+///
+/// The positive terminal is the top plate (on met4),
+/// and the negative terminal is the bottom plate (on met3).
+///
+/// Produces an instance of `sky130_fd_pr__cap_mim_m3_1`.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MimCap {
+    /// The width of the top plate, in nm.
+    w: i64,
+    /// The length of the top plate, in nm.
+    l: i64,
+}
+
+impl MimCap {
+    /// Creates a new [`MimCap`] with the given top plate dimensions, in nm.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either dimension is less than 1000 nm.
+    pub fn new(w: i64, l: i64) -> Self {
+        assert!(
+            w >= 1_000 && l >= 1_000,
+            "MIM capacitor dimensions must be at least 1um"
+        );
+        Self { w, l }
+    }
+}
+
+impl Block for MimCap {
+    type Io = TwoTerminalIo;
+
+    fn name(&self) -> ArcStr {
+        arcstr::format!("cap_mim_m3_w{}_l{}", self.w, self.l)
+    }
+
+    fn io(&self) -> Self::Io {
+        Default::default()
+    }
+}
+
+impl Schematic for MimCap {
+    type Schema = Sky130;
+    type NestedData = ();
+    fn schematic(
+        &self,
+        io: &substrate::types::schematic::IoNodeBundle<Self>,
+        cell: &mut substrate::schematic::CellBuilder<<Self as Schematic>::Schema>,
+    ) -> substrate::error::Result<Self::NestedData> {
+        let mut prim = PrimitiveBinding::new(Primitive::RawInstance {
+            cell: arcstr::literal!("sky130_fd_pr__cap_mim_m3_1"),
+            ports: vec!["c0".into(), "c1".into()],
+            params: HashMap::from_iter([
+                (arcstr::literal!("w"), Decimal::new(self.w, 3).into()),
+                (arcstr::literal!("l"), Decimal::new(self.l, 3).into()),
+            ]),
+        });
+        prim.connect("c0", io.p);
+        prim.connect("c1", io.n);
+        cell.set_primitive(prim);
+        Ok(())
+    }
+}
+
+impl Layout for MimCap {
+    type Schema = Sky130;
+    type Bundle = TwoTerminalIoView<PortGeometryBundle<Sky130>>;
+    type Data = ();
+    fn layout(
+        &self,
+        cell: &mut CellBuilder<Self::Schema>,
+    ) -> substrate::error::Result<(Self::Bundle, Self::Data)> {
+        let capm = Rect::from_sides(0, 0, self.w, self.l);
+        cell.draw(Shape::new(Sky130Layer::Capm, capm))?;
+
+        let top = Shape::new(Sky130Layer::Met4, capm);
+        cell.draw(top.clone())?;
+
+        let rules = rules();
+        let enclosure = rules
+            .min_enclosure(&Sky130Layer::Capm, &Sky130Layer::Met3)
+            .unwrap();
+        let bot = Shape::new(Sky130Layer::Met3, capm.expand_all(enclosure));
+        cell.draw(bot.clone())?;
+
+        // Via3 cuts land on capm rather than met3, so use the capm enclosure.
+        let via = rules.via(&Sky130Layer::Via3).unwrap();
+        let enclosure = rules
+            .min_enclosure(&Sky130Layer::Via3, &Sky130Layer::Capm)
+            .unwrap();
+        let cuts = |span| {
+            centered_cuts(span, via.size, via.spacing, enclosure)
+                .expect("MIM capacitors are large enough for at least one via")
+        };
+        let ys = cuts(capm.vspan());
+        for x in cuts(capm.hspan()) {
+            for &y in ys.iter() {
+                cell.draw(Shape::new(Sky130Layer::Via3, Rect::from_spans(x, y)))?;
+            }
+        }
+
+        Ok((
+            TwoTerminalIoView {
+                p: PortGeometry::new(top),
+                n: PortGeometry::new(bot),
+            },
+            (),
+        ))
+    }
+}
@@ -1,12 +1,10 @@
 //! An enumeration of supported corners.
 
-use std::path::PathBuf;
-
 use crate::{Sky130, SpectreModelSelect};
-use arcstr::ArcStr;
 use ngspice::Ngspice;
 use serde::{Deserialize, Serialize};
 use spectre::Spectre;
+use substrate::simulation::models::{ModelFile, ModelLibrary, ProvidesModels, CORNER_PLACEHOLDER};
 use substrate::simulation::options::SimOption;
 use substrate::simulation::{SimulationContext, Simulator};
 
@@ -40,7 +38,7 @@ impl Sky130Corner {
 }
 
 impl Sky130 {
-    fn spectre_model_file_includes(&self, corner: Sky130Corner) -> Vec<(PathBuf, Vec<ArcStr>)> {
+    fn spectre_model_files(&self) -> Vec<ModelFile> {
         match self.spectre_model_select {
             SpectreModelSelect::All => [
                 SpectreModelSelect::SrcNda,
@@ -48,61 +46,67 @@ impl Sky130 {
                 SpectreModelSelect::Open,
             ]
             .into_iter()
-            .filter_map(|select| self.spectre_model_file_includes_helper(select, corner))
+            .filter_map(|select| self.spectre_model_file(select))
             .collect(),
-            select => self
-                .spectre_model_file_includes_helper(select, corner)
-                .into_iter()
-                .collect(),
+            select => self.spectre_model_file(select).into_iter().collect(),
         }
     }
-    fn spectre_model_file_includes_helper(
-        &self,
-        select: SpectreModelSelect,
-        corner: Sky130Corner,
-    ) -> Option<(PathBuf, Vec<ArcStr>)> {
+
+    fn spectre_model_file(&self, select: SpectreModelSelect) -> Option<ModelFile> {
         Some(match select {
             SpectreModelSelect::All => unreachable!(),
-            SpectreModelSelect::SrcNda => (
+            SpectreModelSelect::SrcNda => ModelFile::new(
                 self.src_nda_root_dir
                     .as_ref()?
                     .join("MODELS/SPECTRE/s8phirs_10r/Models/design_wrapper.lib.scs"),
-                vec![
-                    arcstr::format!("{}_fet", corner.name()),
-                    arcstr::format!("{}_cell", corner.name()),
-                    arcstr::format!("{}_parRC", corner.name()),
-                    arcstr::format!("{}_rc", corner.name()),
-                ],
-            ),
-            SpectreModelSelect::Cds => (
-                self.cds_root_dir.as_ref()?.join("models/sky130.lib.spice"),
-                vec![corner.name()],
-            ),
-            SpectreModelSelect::Open => (
+            )
+            .section("{corner}_fet")
+            .section("{corner}_cell")
+            .section("{corner}_parRC")
+            .section("{corner}_rc"),
+            SpectreModelSelect::Cds => {
+                ModelFile::new(self.cds_root_dir.as_ref()?.join("models/sky130.lib.spice"))
+                    .section(CORNER_PLACEHOLDER)
+            }
+            SpectreModelSelect::Open => ModelFile::new(
                 self.open_root_dir
                     .as_ref()?
                     .join("libraries/sky130_fd_pr/latest/models/sky130.lib.spice"),
-                vec![corner.name()],
-            ),
+            )
+            .section(CORNER_PLACEHOLDER),
         })
     }
 }
 
+impl ProvidesModels<Spectre> for Sky130 {
+    fn models(&self) -> ModelLibrary {
+        self.spectre_model_files()
+            .into_iter()
+            .fold(ModelLibrary::new(), ModelLibrary::file)
+    }
+}
+
+impl ProvidesModels<Ngspice> for Sky130 {
+    fn models(&self) -> ModelLibrary {
+        ModelLibrary::new().file(
+            ModelFile::new(
+                self.open_root_dir
+                    .as_ref()
+                    .expect("Open root directory must be specified")
+                    .join("libraries/sky130_fd_pr/latest/models/sky130.lib.spice"),
+            )
+            .section(CORNER_PLACEHOLDER),
+        )
+    }
+}
+
 impl SimOption<Spectre> for Sky130Corner {
     fn set_option(
         self,
         opts: &mut <Spectre as Simulator>::Options,
         ctx: &SimulationContext<Spectre>,
     ) {
-        let pdk = ctx
-            .ctx
-            .get_installation::<Sky130>()
-            .expect("Sky130 PDK must be installed");
-        for (path, sections) in pdk.spectre_model_file_includes(self) {
-            for section in sections {
-                opts.include_section(&path, section);
-            }
-        }
+        ctx.include_models::<Sky130>(&self.name(), opts);
     }
 }
 
@@ -112,16 +116,6 @@ impl SimOption<Ngspice> for Sky130Corner {
         opts: &mut <Ngspice as Simulator>::Options,
         ctx: &SimulationContext<Ngspice>,
     ) {
-        let pdk = ctx
-            .ctx
-            .get_installation::<Sky130>()
-            .expect("Sky130 PDK must be installed");
-        opts.include_section(
-            pdk.open_root_dir
-                .as_ref()
-                .expect("Open root directory must be specified")
-                .join("libraries/sky130_fd_pr/latest/models/sky130.lib.spice"),
-            self.name(),
-        )
+        ctx.include_models::<Sky130>(&self.name(), opts);
     }
 }
@@ -3,7 +3,10 @@
 
 use std::collections::HashMap;
 
-use gdsconv::{conv::FromGds, GdsLayer};
+use gdsconv::{
+    conv::{FromGds, ToGds},
+    GdsLayer,
+};
 use lazy_static::lazy_static;
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
@@ -33,6 +36,8 @@ pub enum Sky130Layer {
     Met2,
     Via2,
     Met3,
+    /// MIM capacitor top plate.
+    Capm,
     Via3,
     Met4,
     Via4,
@@ -87,6 +92,7 @@ lazy_static! {
         (Sky130Layer::Met2, GdsLayer(69, 20)),
         (Sky130Layer::Via2, GdsLayer(69, 44)),
         (Sky130Layer::Met3, GdsLayer(70, 20)),
+        (Sky130Layer::Capm, GdsLayer(89, 44)),
         (Sky130Layer::Via3, GdsLayer(70, 44)),
         (Sky130Layer::Met4, GdsLayer(71, 20)),
         (Sky130Layer::Via4, GdsLayer(71, 44)),
@@ -177,6 +183,20 @@ impl Sky130Layer {
     }
 }
 
+impl ToGds for Sky130Layer {
+    fn to_gds(&self) -> GdsLayer {
+        self.gds_layer()
+    }
+
+    fn to_gds_pin(&self) -> Option<GdsLayer> {
+        self.gds_pin_layer()
+    }
+
+    fn to_gds_label(&self) -> Option<GdsLayer> {
+        self.gds_label_layer()
+    }
+}
+
 impl FromGds for Sky130Layer {
     fn from_gds(layer: GdsLayer) -> Option<Self> {
         GDS_LAYER_TO_SKY130.get(&layer).copied()
@@ -3,9 +3,10 @@
 use arcstr::ArcStr;
 use gds::GdsUnits;
 use gdsconv::GdsLayer;
-use geometry::prelude::Transformation;
+use geometry::ring::Ring;
 use geometry::{bbox::Bbox, dir::Dir, rect::Rect, span::Span};
-use layir::{Cell, Element, Instance, LibraryBuilder, Shape, Text};
+use geometry_macros::{TransformMut, TransformRef, TranslateMut, TranslateRef};
+use layir::Shape;
 use serde::{Deserialize, Serialize};
 use substrate::types::codegen::PortGeometryBundle;
 use substrate::{
@@ -23,52 +24,11 @@ use crate::{layers::Sky130Layer, Sky130};
 pub const GDS_UNITS: GdsUnits = GdsUnits::new(1., 1e-9);
 
 /// Convert a sky130 layout library to a GDS layout library.
+///
+/// Port shapes are placed on pin layers and labeled on both the pin and label layers.
 // TODO: cell IDs are not preserved
 pub fn to_gds(lib: &layir::Library<Sky130Layer>) -> (layir::Library<GdsLayer>, GdsUnits) {
-    let mut olib = LibraryBuilder::<GdsLayer>::new();
-    let cells = lib.topological_order();
-    for cell in cells {
-        let cell = lib.cell(cell);
-        let mut ocell = Cell::new(cell.name());
-        for elt in cell.elements() {
-            ocell.add_element(elt.map_layer(Sky130Layer::gds_layer));
-        }
-        for (_, inst) in cell.instances() {
-            let name = lib.cell(inst.child()).name();
-            let child_id = olib.cell_id_named(name);
-            ocell.add_instance(Instance::with_transformation(
-                child_id,
-                inst.name(),
-                inst.transformation(),
-            ));
-        }
-        for (name, oport) in cell.ports() {
-            let mut port = oport.map_layer(|layer| Sky130Layer::gds_pin_layer(layer).unwrap());
-            oport
-                .elements()
-                .filter_map(|e| match e {
-                    Element::Text(_) => None,
-                    Element::Shape(s) => Some(s),
-                })
-                .for_each(|s| {
-                    let center = s.bbox_rect().center();
-                    // places 2 labels: one on the pin layer, and one on the label layer
-                    for layer in [
-                        Sky130Layer::gds_pin_layer(s.layer()).unwrap(),
-                        Sky130Layer::gds_label_layer(s.layer()).unwrap(),
-                    ] {
-                        port.add_element(Element::Text(Text::with_transformation(
-                            layer,
-                            name.clone(),
-                            Transformation::translate(center.x, center.y),
-                        )));
-                    }
-                });
-            ocell.add_port(name, port);
-        }
-        olib.add_cell(ocell);
-    }
-    (olib.build().unwrap(), GDS_UNITS)
+    (gdsconv::conv::to_gds(lib).unwrap(), GDS_UNITS)
 }
 
 struct TapTileData {
@@ -256,3 +216,241 @@ impl Layout for PtapTile {
         ))
     }
 }
+
+struct TapRingData {
+    li: Ring,
+    tap: Ring,
+}
+
+/// A ring of taps enclosing a rectangular region.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Serialize, Deserialize)]
+struct TapRing {
+    /// Width of the enclosed region, in number of li1 tracks.
+    xtracks: i64,
+    /// Height of the enclosed region, in number of m1 tracks.
+    ytracks: i64,
+}
+
+impl TapRing {
+    fn new(xtracks: i64, ytracks: i64) -> Self {
+        assert!(xtracks > 0 && ytracks > 0);
+        Self { xtracks, ytracks }
+    }
+
+    fn name(&self) -> ArcStr {
+        arcstr::format!("tap_ring_x{}_y{}", self.xtracks, self.ytracks)
+    }
+
+    /// The region enclosed by the ring, in the ring's coordinate system.
+    fn inner(&self) -> Rect {
+        let m0tracks = UniformTracks::new(170, 260);
+        let m1tracks = UniformTracks::new(400, 140);
+
+        let hspan = m0tracks.track(0).union(m0tracks.track(self.xtracks - 1));
+        let vspan = Span::new(
+            m1tracks.track(0).center(),
+            m1tracks.track(self.ytracks - 1).center(),
+        )
+        .expand_all(85);
+        Rect::from_spans(hspan, vspan)
+    }
+
+    fn layout(&self, cell: &mut CellBuilder<Sky130>) -> substrate::error::Result<TapRingData> {
+        let m0tracks = UniformTracks::new(170, 260);
+        let m1tracks = UniformTracks::new(400, 140);
+
+        let row = |y: i64| Span::from_center_span(m1tracks.track(y).center(), 170);
+        let (left, right) = (m0tracks.track(-1), m0tracks.track(self.xtracks));
+        let (bot, top) = (row(-1), row(self.ytracks));
+
+        for x in -1..=self.xtracks {
+            for y in [-1, self.ytracks] {
+                let cut = Rect::from_spans(m0tracks.track(x), row(y));
+                cell.draw(Shape::new(Sky130Layer::Licon1, cut))?;
+            }
+        }
+        for y in 0..self.ytracks {
+            for x in [-1, self.xtracks] {
+                let cut = Rect::from_spans(m0tracks.track(x), row(y));
+                cell.draw(Shape::new(Sky130Layer::Licon1, cut))?;
+            }
+        }
+
+        let cuts = Rect::from_spans(left.union(right), bot.union(top));
+        let li = Ring::builder()
+            .outer(cuts.expand_dir(Dir::Horiz, 80))
+            .widths(left.length() + 160)
+            .heights(bot.length())
+            .build();
+        for rect in li.rects() {
+            cell.draw(Shape::new(Sky130Layer::Li1, rect))?;
+        }
+
+        let tap = Ring::builder()
+            .outer(cuts.expand_dir(Dir::Vert, 65).expand_dir(Dir::Horiz, 120))
+            .widths(left.length() + 240)
+            .heights(bot.length() + 130)
+            .build();
+        for rect in tap.rects() {
+            cell.draw(Shape::new(Sky130Layer::Tap, rect))?;
+        }
+
+        Ok(TapRingData { li, tap })
+    }
+}
+
+/// Data exported by guard ring tiles.
+#[derive(Debug, Clone, Copy, TransformRef, TranslateRef, TransformMut, TranslateMut)]
+pub struct GuardRingData {
+    /// The region enclosed by the guard ring.
+    ///
+    /// Devices placed within this region will not overlap the ring.
+    pub inner: Rect,
+}
+
+/// Draws the implant ring of a guard ring, returning the outer bounding box of the implant.
+fn draw_implant_ring(
+    cell: &mut CellBuilder<Sky130>,
+    layer: Sky130Layer,
+    tap: &Ring,
+) -> substrate::error::Result<Rect> {
+    let implant = Ring::builder()
+        .outer(tap.outer().expand_all(130))
+        .widths(tap.left().width() + 260)
+        .heights(tap.bot().height() + 260)
+        .build();
+    for rect in implant.rects() {
+        cell.draw(Shape::new(layer, rect))?;
+    }
+    Ok(implant.outer())
+}
+
+/// A guard ring of N+ taps in an N-well.
+///
+/// The N-well covers the enclosed region,
+/// so this ring can be used to surround and bias PMOS devices.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub struct NtapRing {
+    ring: TapRing,
+}
+
+impl NtapRing {
+    /// Create a new ntap guard ring enclosing a region
+    /// `xtracks` li1 tracks wide and `ytracks` m1 tracks tall.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either dimension is not positive.
+    pub fn new(xtracks: i64, ytracks: i64) -> Self {
+        Self {
+            ring: TapRing::new(xtracks, ytracks),
+        }
+    }
+}
+
+impl Block for NtapRing {
+    type Io = NtapIo;
+
+    fn name(&self) -> ArcStr {
+        arcstr::format!("n{}", self.ring.name())
+    }
+
+    fn io(&self) -> Self::Io {
+        Default::default()
+    }
+}
+
+impl Layout for NtapRing {
+    type Schema = Sky130;
+    type Bundle = NtapIoView<PortGeometryBundle<Sky130>>;
+    type Data = GuardRingData;
+    fn layout(
+        &self,
+        cell: &mut substrate::layout::CellBuilder<Self::Schema>,
+    ) -> substrate::error::Result<(Self::Bundle, Self::Data)> {
+        let data = self.ring.layout(cell)?;
+        let implant = draw_implant_ring(cell, Sky130Layer::Nsdm, &data.tap)?;
+
+        let nwell = data.tap.outer().expand_all(180).union(implant);
+        cell.draw(Shape::new(Sky130Layer::Nwell, nwell))?;
+
+        let bbox = cell.bbox_rect();
+        cell.draw(Shape::new(Sky130Layer::Outline, bbox))?;
+
+        let mut vpb = PortGeometry::new(Shape::new(Sky130Layer::Li1, data.li.bot()));
+        vpb.unnamed_shapes.extend(
+            [data.li.right(), data.li.top(), data.li.left()]
+                .map(|rect| Shape::new(Sky130Layer::Li1, rect)),
+        );
+
+        Ok((
+            NtapIoView { vpb },
+            GuardRingData {
+                inner: self.ring.inner(),
+            },
+        ))
+    }
+}
+
+/// A guard ring of P+ taps for biasing a P-well or P-substrate.
+///
+/// This ring can be used to surround and bias NMOS devices.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub struct PtapRing {
+    ring: TapRing,
+}
+
+impl PtapRing {
+    /// Create a new ptap guard ring enclosing a region
+    /// `xtracks` li1 tracks wide and `ytracks` m1 tracks tall.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either dimension is not positive.
+    pub fn new(xtracks: i64, ytracks: i64) -> Self {
+        Self {
+            ring: TapRing::new(xtracks, ytracks),
+        }
+    }
+}
+
+impl Block for PtapRing {
+    type Io = PtapIo;
+
+    fn name(&self) -> ArcStr {
+        arcstr::format!("p{}", self.ring.name())
+    }
+
+    fn io(&self) -> Self::Io {
+        Default::default()
+    }
+}
+
+impl Layout for PtapRing {
+    type Schema = Sky130;
+    type Bundle = PtapIoView<PortGeometryBundle<Sky130>>;
+    type Data = GuardRingData;
+    fn layout(
+        &self,
+        cell: &mut substrate::layout::CellBuilder<Self::Schema>,
+    ) -> substrate::error::Result<(Self::Bundle, Self::Data)> {
+        let data = self.ring.layout(cell)?;
+        draw_implant_ring(cell, Sky130Layer::Psdm, &data.tap)?;
+
+        let bbox = cell.bbox_rect();
+        cell.draw(Shape::new(Sky130Layer::Outline, bbox))?;
+
+        let mut vnb = PortGeometry::new(Shape::new(Sky130Layer::Li1, data.li.bot()));
+        vnb.unnamed_shapes.extend(
+            [data.li.right(), data.li.top(), data.li.left()]
+                .map(|rect| Shape::new(Sky130Layer::Li1, rect)),
+        );
+
+        Ok((
+            PtapIoView { vnb },
+            GuardRingData {
+                inner: self.ring.inner(),
+            },
+        ))
+    }
+}
@@ -20,15 +20,23 @@ use thiserror::Error;
 use unicase::UniCase;
 
 use crate::mos::{MosKind, MosParams};
-use scir::schema::{FromSchema, Schema};
-use scir::{Instance, ParamValue};
+use crate::res::{PolyResKind, PolyResWidth};
+use scir::mapping::PrimitiveParts;
+use scir::schema::{ConversionContext, FromSchema};
+use scir::{Instance, ParamValue, PrimitiveId};
 use spice::Spice;
-use substrate::context::Installation;
+use substrate::context::{ContextBuilder, Installation};
+use substrate::schematic::primitives::{
+    DescribePrimitive, ParamSpec, PrimitiveLibrary, PrimitiveSpec,
+};
 
+pub mod cap;
 pub mod corner;
 pub mod layers;
 pub mod layout;
 pub mod mos;
+pub mod res;
+pub mod rules;
 pub mod stdcells;
 #[cfg(test)]
 mod tests;
@@ -74,6 +82,7 @@ impl scir::schema::Schema for Sky130 {
 fn convert_spice_mos(
     kind: &str,
     params: &HashMap<UniCase<ArcStr>, ParamValue>,
+    multiplier: u64,
 ) -> Result<Primitive, ConvError> {
     let schema = MosKind::schema(kind).ok_or(ConvError::UnsupportedPrimitive)?;
     let kind = MosKind::try_from_str(kind).ok_or(ConvError::UnsupportedPrimitive)?;
@@ -97,6 +106,7 @@ fn convert_spice_mos(
             .unwrap_or(dec!(1)),
     )
     .map_err(|_| ConvError::InvalidParameter)?;
+    let multiplier = i64::try_from(multiplier).map_err(|_| ConvError::InvalidParameter)?;
     Ok(Primitive::Mos {
         kind,
         params: MosParams {
@@ -125,7 +135,8 @@ fn convert_spice_mos(
             )
             .map_err(|_| ConvError::InvalidParameter)?
                 * m
-                * mult,
+                * mult
+                * multiplier,
         },
     })
 }
@@ -143,7 +154,7 @@ impl FromSchema<Spice> for Sky130 {
                 params,
             } => {
                 if MosKind::try_from_str(&cell).is_some() {
-                    convert_spice_mos(&cell, &params)
+                    convert_spice_mos(&cell, &params, 1)
                 } else {
                     Ok(Primitive::RawInstance {
                         cell,
@@ -156,7 +167,7 @@ impl FromSchema<Spice> for Sky130 {
                     })
                 }
             }
-            spice::Primitive::Mos { model, params } => convert_spice_mos(&model, &params),
+            spice::Primitive::Mos { model, params, m } => convert_spice_mos(&model, &params, m),
             _ => Err(ConvError::UnsupportedPrimitive),
         }
     }
@@ -184,6 +195,45 @@ impl FromSchema<Spice> for Sky130 {
         }
         Ok(())
     }
+
+    /// Converts a SPICE primitive, keeping raw instances of cells defined in the library as is.
+    ///
+    /// Netlists that define their own subcircuit with the name of a Sky 130 device
+    /// (e.g. a behavioral model) instantiate that subcircuit rather than the PDK device.
+    fn convert_primitive_in(
+        ctx: &mut ConversionContext<'_, Self>,
+        _id: PrimitiveId,
+        primitive: <Spice as scir::schema::Schema>::Primitive,
+    ) -> Result<<Self as scir::schema::Schema>::Primitive, Self::Error> {
+        match primitive {
+            spice::Primitive::RawInstance {
+                cell,
+                ports,
+                params,
+            } if ctx.try_cell_id_named(&cell).is_some() => Ok(Primitive::RawInstance {
+                cell,
+                ports,
+                params: params
+                    .into_iter()
+                    .map(|(k, v)| (k.into_inner(), v))
+                    .collect(),
+            }),
+            primitive => Self::convert_primitive(primitive),
+        }
+    }
+
+    fn convert_instance_in(
+        ctx: &mut ConversionContext<'_, Self>,
+        instance: &mut Instance,
+        primitive: &<Spice as scir::schema::Schema>::Primitive,
+    ) -> Result<(), Self::Error> {
+        match primitive {
+            spice::Primitive::RawInstance { cell, .. } if ctx.try_cell_id_named(cell).is_some() => {
+                Ok(())
+            }
+            primitive => Self::convert_instance(instance, primitive),
+        }
+    }
 }
 
 /// A schema for the open PDK.
@@ -194,39 +244,7 @@ impl scir::schema::Schema for Sky130OpenSchema {
     type Primitive = Primitive;
 }
 
-impl FromSchema<Sky130> for Sky130OpenSchema {
-    type Error = Infallible;
-
-    fn convert_primitive(
-        primitive: <Sky130 as Schema>::Primitive,
-    ) -> Result<<Self as Schema>::Primitive, Self::Error> {
-        Ok(primitive)
-    }
-
-    fn convert_instance(
-        _instance: &mut Instance,
-        _primitive: &<Sky130 as Schema>::Primitive,
-    ) -> Result<(), Self::Error> {
-        Ok(())
-    }
-}
-
-impl FromSchema<Sky130OpenSchema> for Sky130 {
-    type Error = Infallible;
-
-    fn convert_primitive(
-        primitive: <Sky130 as Schema>::Primitive,
-    ) -> Result<<Self as Schema>::Primitive, Self::Error> {
-        Ok(primitive)
-    }
-
-    fn convert_instance(
-        _instance: &mut Instance,
-        _primitive: &<Sky130 as Schema>::Primitive,
-    ) -> Result<(), Self::Error> {
-        Ok(())
-    }
-}
+scir::bidirectional_conversion!(Sky130 <=> Sky130OpenSchema);
 
 impl FromSchema<Sky130OpenSchema> for Spice {
     type Error = Infallible;
@@ -335,39 +353,7 @@ impl scir::schema::Schema for Sky130SrcNdaSchema {
     type Primitive = Primitive;
 }
 
-impl FromSchema<Sky130> for Sky130SrcNdaSchema {
-    type Error = Infallible;
-
-    fn convert_primitive(
-        primitive: <Sky130 as Schema>::Primitive,
-    ) -> Result<<Self as Schema>::Primitive, Self::Error> {
-        Ok(primitive)
-    }
-
-    fn convert_instance(
-        _instance: &mut Instance,
-        _primitive: &<Sky130 as Schema>::Primitive,
-    ) -> Result<(), Self::Error> {
-        Ok(())
-    }
-}
-
-impl FromSchema<Sky130SrcNdaSchema> for Sky130 {
-    type Error = Infallible;
-
-    fn convert_primitive(
-        primitive: <Sky130 as Schema>::Primitive,
-    ) -> Result<<Self as Schema>::Primitive, Self::Error> {
-        Ok(primitive)
-    }
-
-    fn convert_instance(
-        _instance: &mut Instance,
-        _primitive: &<Sky130 as Schema>::Primitive,
-    ) -> Result<(), Self::Error> {
-        Ok(())
-    }
-}
+scir::bidirectional_conversion!(Sky130 <=> Sky130SrcNdaSchema);
 
 impl FromSchema<Sky130SrcNdaSchema> for Spice {
     type Error = Infallible;
@@ -404,6 +390,7 @@ impl FromSchema<Sky130SrcNdaSchema> for Spice {
                         Decimal::from(params.nf).into(),
                     ),
                 ]),
+                m: 1,
             },
         })
     }
@@ -457,39 +444,7 @@ impl scir::schema::Schema for Sky130CdsSchema {
     type Primitive = Primitive;
 }
 
-impl FromSchema<Sky130> for Sky130CdsSchema {
-    type Error = Infallible;
-
-    fn convert_primitive(
-        primitive: <Sky130 as Schema>::Primitive,
-    ) -> Result<<Self as Schema>::Primitive, Self::Error> {
-        Ok(primitive)
-    }
-
-    fn convert_instance(
-        _instance: &mut Instance,
-        _primitive: &<Sky130 as Schema>::Primitive,
-    ) -> Result<(), Self::Error> {
-        Ok(())
-    }
-}
-
-impl FromSchema<Sky130CdsSchema> for Sky130 {
-    type Error = Infallible;
-
-    fn convert_primitive(
-        primitive: <Sky130 as Schema>::Primitive,
-    ) -> Result<<Self as Schema>::Primitive, Self::Error> {
-        Ok(primitive)
-    }
-
-    fn convert_instance(
-        _instance: &mut Instance,
-        _primitive: &<Sky130 as Schema>::Primitive,
-    ) -> Result<(), Self::Error> {
-        Ok(())
-    }
-}
+scir::bidirectional_conversion!(Sky130 <=> Sky130CdsSchema);
 
 impl FromSchema<Sky130CdsSchema> for Spice {
     type Error = Infallible;
@@ -526,6 +481,7 @@ impl FromSchema<Sky130CdsSchema> for Spice {
                     ),
                     (UniCase::new(arcstr::literal!("mult")), dec!(1).into()),
                 ]),
+                m: 1,
             },
         })
     }
@@ -664,9 +620,87 @@ impl Sky130 {
             .build()
             .unwrap()
     }
+
+    /// Returns the library of devices that may be instantiated as
+    /// [`Primitive::RawInstance`]s.
+    ///
+    /// MOSFETs are listed under their names in each of the open-source, SRC NDA, and CDS PDKs.
+    /// Dimensions are in microns, except for devices of the CDS PDK, whose dimensions are
+    /// in meters. Other devices are only available in the open-source PDK.
+    ///
+    /// The library is installed along with the PDK, so that raw instances of these
+    /// devices and [`Primitive::Mos`] devices are checked when they are generated.
+    pub fn primitive_library() -> PrimitiveLibrary {
+        let mut library = PrimitiveLibrary::new::<Sky130>();
+        let positive = || ParamSpec::required().min(dec!(0.001));
+
+        for &kind in MosKind::ALL {
+            let (w, l) = kind.min_dims();
+            for (name, scale) in [
+                (kind.open_subckt(), dec!(1)),
+                (kind.src_nda_subckt(), dec!(1)),
+                (kind.cds_subckt(), dec!(1e-6)),
+            ] {
+                library.add(
+                    PrimitiveSpec::new(name, ["D", "G", "S", "B"])
+                        .param("w", ParamSpec::required().min(w * scale))
+                        .param("l", ParamSpec::required().min(l * scale))
+                        .param("nf", ParamSpec::with_default(dec!(1)).min(dec!(1)))
+                        .param("mult", ParamSpec::with_default(dec!(1)).min(dec!(1))),
+                );
+            }
+        }
+        for kind in PolyResKind::ALL {
+            for width in PolyResWidth::ALL {
+                library.add(
+                    PrimitiveSpec::new(res::model_name(kind, width), ["r0", "r1", "b"])
+                        .param("l", positive()),
+                );
+            }
+        }
+        library.add(
+            PrimitiveSpec::new("sky130_fd_pr__cap_mim_m3_1", ["c0", "c1"])
+                .param("w", ParamSpec::required().min(dec!(1)))
+                .param("l", ParamSpec::required().min(dec!(1))),
+        );
+
+        library
+    }
 }
 
-impl Installation for Sky130 {}
+impl Installation for Sky130 {
+    fn post_install(&self, ctx: &mut ContextBuilder) {
+        ctx.install(Sky130::primitive_library());
+    }
+}
+
+impl DescribePrimitive for Sky130 {
+    fn describe_primitive(primitive: &Primitive) -> Option<PrimitiveParts> {
+        match primitive {
+            Primitive::RawInstance {
+                cell,
+                ports,
+                params,
+            } => Some(PrimitiveParts {
+                kind: cell.clone(),
+                ports: ports.clone(),
+                params: params
+                    .iter()
+                    .map(|(name, value)| (name.clone(), value.clone()))
+                    .collect(),
+            }),
+            Primitive::Mos { kind, params } => Some(PrimitiveParts {
+                kind: kind.open_subckt(),
+                ports: ["D", "G", "S", "B"].into_iter().map(ArcStr::from).collect(),
+                params: vec![
+                    (arcstr::literal!("w"), Decimal::new(params.w, 3).into()),
+                    (arcstr::literal!("l"), Decimal::new(params.l, 3).into()),
+                    (arcstr::literal!("nf"), Decimal::from(params.nf).into()),
+                ],
+            }),
+        }
+    }
+}
 
 impl substrate::layout::schema::Schema for Sky130 {
     type Layer = Sky130Layer;
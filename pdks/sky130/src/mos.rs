@@ -7,6 +7,8 @@ use crate::{Sky130, Sky130Schema};
 use arcstr::ArcStr;
 use geometry_macros::{TransformMut, TransformRef, TranslateMut, TranslateRef};
 use layir::Shape;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
 use substrate::block::Block;
 use substrate::geometry::bbox::Bbox;
@@ -73,6 +75,9 @@ macro_rules! define_mosfets {
         }
 
         impl MosKind {
+            /// All MOSFET varieties.
+            pub(crate) const ALL: &'static [MosKind] = &[$(MosKind::$typ),*];
+
             pub(crate) fn open_subckt(&self) -> arcstr::ArcStr {
                 match self {
                     $(
@@ -273,6 +278,26 @@ define_mosfets!(
     }
 );
 
+impl MosKind {
+    /// The minimum width and length of the device, in microns.
+    ///
+    /// Taken from the SkyWater device documentation. The special SRAM devices
+    /// are only characterized at a single size.
+    pub(crate) fn min_dims(&self) -> (Decimal, Decimal) {
+        match self {
+            MosKind::Nfet01v8 => (dec!(0.36), dec!(0.15)),
+            MosKind::Nfet01v8Lvt => (dec!(0.42), dec!(0.15)),
+            MosKind::Nfet03v3Nvt => (dec!(0.42), dec!(0.5)),
+            MosKind::Nfet05v0Nvt => (dec!(0.42), dec!(0.9)),
+            MosKind::Nfet20v0 | MosKind::Pfet20v0 => (dec!(30), dec!(0.5)),
+            MosKind::SpecialNfetLatch => (dec!(0.21), dec!(0.15)),
+            MosKind::SpecialNfetPass | MosKind::SpecialPfetPass => (dec!(0.14), dec!(0.15)),
+            MosKind::Pfet01v8 | MosKind::Pfet01v8Hvt => (dec!(0.42), dec!(0.15)),
+            MosKind::Pfet01v8Lvt => (dec!(0.42), dec!(0.35)),
+        }
+    }
+}
+
 /// Determines the connection direction of a transistor gate.
 #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum GateDir {
@@ -0,0 +1,235 @@
+//! Resistor devices.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use arcstr::ArcStr;
+use layir::Shape;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use substrate::block::Block;
+use substrate::geometry::bbox::Bbox;
+use substrate::geometry::dir::Dir;
+use substrate::geometry::rect::Rect;
+use substrate::geometry::span::Span;
+use substrate::layout::rules::centered_cuts;
+use substrate::layout::{CellBuilder, Layout};
+use substrate::schematic::{PrimitiveBinding, Schematic};
+use substrate::types::codegen::PortGeometryBundle;
+use substrate::types::layout::PortGeometry;
+use substrate::types::{InOut, Io, Signal};
+
+use crate::layers::Sky130Layer;
+use crate::{Primitive, Sky130};
+
+/// The sheet resistance variety of a precision poly resistor.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PolyResKind {
+    /// A high sheet resistance poly resistor, implanted with the `rpm` layer.
+    High,
+    /// An extra-high sheet resistance poly resistor, implanted with the `urpm` layer.
+    XHigh,
+}
+
+impl PolyResKind {
+    /// All poly resistor kinds.
+    pub(crate) const ALL: [PolyResKind; 2] = [Self::High, Self::XHigh];
+
+    fn implant(&self) -> Sky130Layer {
+        match self {
+            Self::High => Sky130Layer::Rpm,
+            Self::XHigh => Sky130Layer::Urpm,
+        }
+    }
+}
+
+impl Display for PolyResKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::High => write!(f, "high"),
+            Self::XHigh => write!(f, "xhigh"),
+        }
+    }
+}
+
+/// The set of supported precision poly resistor widths.
+#[derive(
+    Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize,
+)]
+pub enum PolyResWidth {
+    /// 350nm.
+    #[default]
+    W350,
+    /// 690nm.
+    W690,
+    /// 1410nm.
+    W1410,
+    /// 2850nm.
+    W2850,
+    /// 5730nm.
+    W5730,
+}
+
+impl PolyResWidth {
+    /// All supported poly resistor widths.
+    pub(crate) const ALL: [PolyResWidth; 5] = [
+        Self::W350,
+        Self::W690,
+        Self::W1410,
+        Self::W2850,
+        Self::W5730,
+    ];
+
+    /// The width in nanometers.
+    pub fn nm(&self) -> i64 {
+        match *self {
+            Self::W350 => 350,
+            Self::W690 => 690,
+            Self::W1410 => 1410,
+            Self::W2850 => 2850,
+            Self::W5730 => 5730,
+        }
+    }
+
+    fn suffix(&self) -> &'static str {
+        match *self {
+            Self::W350 => "0p35",
+            Self::W690 => "0p69",
+            Self::W1410 => "1p41",
+            Self::W2850 => "2p85",
+            Self::W5730 => "5p73",
+        }
+    }
+}
+
+/// The name of the model of a precision poly resistor.
+pub(crate) fn model_name(kind: PolyResKind, width: PolyResWidth) -> ArcStr {
+    arcstr::format!("sky130_fd_pr__res_{}_po_{}", kind, width.suffix())
+}
+
+/// The IO of a [`PolyRes`].
+#[derive(Debug, Default, Clone, Io)]
+pub struct PolyResIo {
+    /// The positive terminal.
+    pub p: InOut<Signal>,
+    /// The negative terminal.
+    pub n: InOut<Signal>,
+    /// The body connection.
+    pub b: InOut<Signal>,
+}
+
+/// A precision poly resistor.
+///
+/// Produces an instance of `sky130_fd_pr__res_{kind}_po_{width}`
+/// (e.g. `sky130_fd_pr__res_xhigh_po_0p35`).
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PolyRes {
+    kind: PolyResKind,
+    width: PolyResWidth,
+    /// The length of the resistor body, in nm.
+    l: i64,
+}
+
+/// The length of the contact cuts at each end of a precision poly resistor.
+const HEAD_CUT_LEN: i64 = 2_000;
+
+impl PolyRes {
+    /// Creates a new [`PolyRes`] with the given body length, in nm.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length is not positive.
+    pub fn new(kind: PolyResKind, width: PolyResWidth, l: i64) -> Self {
+        assert!(l > 0, "resistor length must be positive");
+        Self { kind, width, l }
+    }
+
+    fn model(&self) -> ArcStr {
+        model_name(self.kind, self.width)
+    }
+}
+
+impl Block for PolyRes {
+    type Io = PolyResIo;
+
+    fn name(&self) -> ArcStr {
+        arcstr::format!("res_{}_po_w{}_l{}", self.kind, self.width.nm(), self.l)
+    }
+
+    fn io(&self) -> Self::Io {
+        Default::default()
+    }
+}
+
+impl Schematic for PolyRes {
+    type Schema = Sky130;
+    type NestedData = ();
+    fn schematic(
+        &self,
+        io: &substrate::types::schematic::IoNodeBundle<Self>,
+        cell: &mut substrate::schematic::CellBuilder<<Self as Schematic>::Schema>,
+    ) -> substrate::error::Result<Self::NestedData> {
+        let mut prim = PrimitiveBinding::new(Primitive::RawInstance {
+            cell: self.model(),
+            ports: vec!["r0".into(), "r1".into(), "b".into()],
+            params: HashMap::from_iter([(arcstr::literal!("l"), Decimal::new(self.l, 3).into())]),
+        });
+        prim.connect("r0", io.p);
+        prim.connect("r1", io.n);
+        prim.connect("b", io.b);
+        cell.set_primitive(prim);
+        Ok(())
+    }
+}
+
+impl Layout for PolyRes {
+    type Schema = Sky130;
+    type Bundle = PolyResIoView<PortGeometryBundle<Sky130>>;
+    type Data = ();
+    fn layout(
+        &self,
+        cell: &mut CellBuilder<Self::Schema>,
+    ) -> substrate::error::Result<(Self::Bundle, Self::Data)> {
+        let w = self.width.nm();
+        let head = HEAD_CUT_LEN + 2 * 80;
+        let hspan = Span::new(0, w);
+        let poly = Rect::from_spans(hspan, Span::new(-head, self.l + head));
+        cell.draw(Shape::new(Sky130Layer::Poly, poly))?;
+
+        let mut terminals = Vec::new();
+        for vspan in [
+            Span::with_start_and_length(self.l + 80, HEAD_CUT_LEN),
+            Span::with_start_and_length(-head + 80, HEAD_CUT_LEN),
+        ] {
+            let cuts = centered_cuts(hspan, 190, 170, 80)
+                .expect("all poly resistor widths fit at least one contact");
+            for &x in cuts.iter() {
+                cell.draw(Shape::new(Sky130Layer::Licon1, Rect::from_spans(x, vspan)))?;
+            }
+            let li = Rect::from_spans(cuts.first().unwrap().union(*cuts.last().unwrap()), vspan)
+                .expand_dir(Dir::Vert, 80);
+            let li = Shape::new(Sky130Layer::Li1, li);
+            cell.draw(li.clone())?;
+            terminals.push(li);
+        }
+
+        cell.draw(Shape::new(Sky130Layer::Psdm, poly.expand_all(110)))?;
+        cell.draw(Shape::new(self.kind.implant(), poly.expand_all(200)))?;
+
+        let bbox = cell.bbox_rect();
+        let pwell = Shape::new(Sky130Layer::Pwell, bbox);
+        cell.draw(pwell.clone())?;
+        cell.draw(Shape::new(Sky130Layer::Outline, bbox))?;
+
+        let n = terminals.pop().unwrap();
+        let p = terminals.pop().unwrap();
+        Ok((
+            PolyResIoView {
+                p: PortGeometry::new(p),
+                n: PortGeometry::new(n),
+                b: PortGeometry::new(pwell),
+            },
+            (),
+        ))
+    }
+}
@@ -0,0 +1,99 @@
+//! Design rules.
+
+use lazy_static::lazy_static;
+use substrate::layout::rules::{DesignRules, RuleDeck, ViaRule};
+
+use crate::layers::Sky130Layer;
+use crate::Sky130;
+
+lazy_static! {
+    static ref SKY130_RULES: RuleDeck<Sky130Layer> = {
+        use Sky130Layer::*;
+        let mut rules = RuleDeck::new();
+        for (layer, width, spacing) in [
+            (Nwell, 840, 1_270),
+            (Diff, 150, 270),
+            (Tap, 150, 270),
+            (Poly, 150, 210),
+            (Nsdm, 380, 380),
+            (Psdm, 380, 380),
+            (Licon1, 170, 170),
+            (Li1, 170, 170),
+            (Mcon, 170, 190),
+            (Met1, 140, 140),
+            (Via, 150, 170),
+            (Met2, 140, 140),
+            (Via2, 200, 200),
+            (Met3, 300, 300),
+            (Capm, 1_000, 840),
+            (Via3, 200, 200),
+            (Met4, 300, 300),
+            (Via4, 800, 800),
+            (Met5, 1_600, 1_600),
+        ] {
+            rules
+                .set_min_width(layer, width)
+                .set_min_spacing(layer, spacing);
+        }
+        for (inner, outer, enclosure) in [
+            (Diff, Nsdm, 125),
+            (Diff, Psdm, 125),
+            (Tap, Nsdm, 125),
+            (Tap, Psdm, 125),
+            (Diff, Nwell, 180),
+            (Tap, Nwell, 180),
+            (Licon1, Diff, 40),
+            (Licon1, Tap, 120),
+            (Licon1, Poly, 50),
+            (Licon1, Li1, 0),
+            (Mcon, Li1, 0),
+            (Mcon, Met1, 30),
+            (Via, Met1, 55),
+            (Via, Met2, 55),
+            (Via2, Met2, 40),
+            (Via2, Met3, 65),
+            (Capm, Met3, 140),
+            (Via3, Met3, 60),
+            (Via3, Met4, 65),
+            (Via3, Capm, 200),
+            (Via4, Met4, 190),
+            (Via4, Met5, 310),
+        ] {
+            rules.set_min_enclosure(inner, outer, enclosure);
+        }
+        for (cut, bot, top) in [
+            (Licon1, Poly, Li1),
+            (Mcon, Li1, Met1),
+            (Via, Met1, Met2),
+            (Via2, Met2, Met3),
+            (Via3, Met3, Met4),
+            (Via4, Met4, Met5),
+        ] {
+            let size = rules.min_width(&cut).unwrap();
+            let spacing = rules.min_spacing(&cut).unwrap();
+            rules.set_via(
+                cut,
+                ViaRule {
+                    bot,
+                    top,
+                    size,
+                    spacing,
+                },
+            );
+        }
+        rules
+    };
+}
+
+impl DesignRules for Sky130 {
+    fn rules(&self) -> &RuleDeck<Sky130Layer> {
+        &SKY130_RULES
+    }
+}
+
+/// The design rules of the Sky 130 PDK.
+///
+/// Equivalent to calling [`DesignRules::rules`] on an installed [`Sky130`] PDK.
+pub fn rules() -> &'static RuleDeck<Sky130Layer> {
+    &SKY130_RULES
+}
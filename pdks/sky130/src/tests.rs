@@ -1,7 +1,9 @@
+use crate::cap::MimCap;
 use crate::corner::Sky130Corner;
 use crate::layers::Sky130Layer;
-use crate::layout::{to_gds, GDS_UNITS};
+use crate::layout::{to_gds, NtapRing, PtapRing, GDS_UNITS};
 use crate::mos::{MosKind, MosLength, NmosTile, PmosTile};
+use crate::res::{PolyRes, PolyResKind, PolyResWidth};
 use crate::stdcells::{And2, And2Io};
 use crate::{convert_spice_mos, Primitive, Sky130, Sky130OpenSchema, Sky130SrcNdaSchema};
 use approx::assert_abs_diff_eq;
@@ -13,6 +15,7 @@ use layir::Element;
 use ngspice::Ngspice;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
+use scir::mapping::PrimitiveParts;
 use scir::ParamValue;
 use spectre::Spectre;
 use std::any::Any;
@@ -26,6 +29,7 @@ use substrate::schematic::{CellBuilder, ConvertSchema, Schematic};
 use substrate::simulation::waveform::TimeWaveform;
 use substrate::types::schematic::Terminal;
 use substrate::types::{TestbenchIo, TwoTerminalIo};
+use substrate::units::{Time, Voltage};
 use unicase::UniCase;
 
 const BUILD_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/build");
@@ -110,7 +114,7 @@ impl SupportsAnd2Tb for Ngspice {
         ConvertSchema::new(ConvertSchema::new(params))
     }
     fn dc_vsource(v: Decimal) -> Self::DcVsource {
-        ngspice::blocks::Vsource::dc(v)
+        ngspice::blocks::Vsource::dc(Voltage::new(v))
     }
 }
 
@@ -122,7 +126,7 @@ impl SupportsAnd2Tb for Spectre {
         ConvertSchema::new(ConvertSchema::new(params))
     }
     fn dc_vsource(v: Decimal) -> Self::DcVsource {
-        spectre::blocks::Vsource::dc(v)
+        spectre::blocks::Vsource::dc(Voltage::new(v))
     }
 }
 
@@ -179,8 +183,8 @@ fn sky130_and2_ngspice() {
             .simulate(
                 opts,
                 ngspice::tran::Tran {
-                    step: dec!(1e-9),
-                    stop: dec!(2e-9),
+                    step: Time::new(dec!(1e-9)),
+                    stop: Time::new(dec!(2e-9)),
                     ..Default::default()
                 },
             )
@@ -224,7 +228,7 @@ fn sky130_and2_monte_carlo_spectre() {
                     seed: None,
                     firstrun: None,
                     analysis: spectre::analysis::tran::Tran {
-                        stop: dec!(2e-9),
+                        stop: Time::new(dec!(2e-9)),
                         errpreset: Some(spectre::ErrPreset::Conservative),
                         ..Default::default()
                     },
@@ -313,6 +317,44 @@ fn pfet_01v8_layout() {
     .unwrap();
 }
 
+#[test]
+fn tap_ring_layout() {
+    let test_name = "tap_ring_layout";
+    let ctx = sky130_src_nda_ctx();
+
+    ctx.write_layout(
+        NtapRing::new(6, 4),
+        to_gds,
+        get_path(test_name, "ntap_ring.gds"),
+    )
+    .unwrap();
+    ctx.write_layout(
+        PtapRing::new(6, 4),
+        to_gds,
+        get_path(test_name, "ptap_ring.gds"),
+    )
+    .unwrap();
+}
+
+#[test]
+fn passives_layout() {
+    let test_name = "passives_layout";
+    let ctx = sky130_src_nda_ctx();
+
+    ctx.write_layout(
+        MimCap::new(4_000, 6_000),
+        to_gds,
+        get_path(test_name, "mim_cap.gds"),
+    )
+    .unwrap();
+    ctx.write_layout(
+        PolyRes::new(PolyResKind::XHigh, PolyResWidth::W690, 5_000),
+        to_gds,
+        get_path(test_name, "poly_res.gds"),
+    )
+    .unwrap();
+}
+
 #[test]
 fn test_convert_spice_mos() {
     let params = HashMap::from_iter([
@@ -338,7 +380,7 @@ fn test_convert_spice_mos() {
         ),
     ]);
     let kind = "nshort";
-    let prim = convert_spice_mos(kind, &params).expect("failed to convert mos");
+    let prim = convert_spice_mos(kind, &params, 1).expect("failed to convert mos");
     match prim {
         Primitive::Mos { kind, params } => {
             assert_eq!(kind, MosKind::Nfet01v8);
@@ -349,3 +391,201 @@ fn test_convert_spice_mos() {
         _ => panic!("bad primitive"),
     }
 }
+
+#[test]
+fn import_spice_mos_with_multiplier() {
+    let parsed = spice::parser::Parser::parse(
+        spice::parser::Dialect::Spice,
+        r#"* multiplier import
+.subckt mos_mult d g s b
+M0 d g s b sky130_fd_pr__nfet_01v8 w=1 l=0.15 nf=2 m=2
+.ends
+"#,
+    )
+    .expect("failed to parse netlist");
+    let lib = parsed
+        .to_scir()
+        .expect("failed to convert netlist to SCIR")
+        .convert_schema::<Sky130>()
+        .expect("failed to convert MOSFET with m=2")
+        .build()
+        .expect("failed to build library");
+    let (_, prim) = lib.primitives().next().expect("expected one primitive");
+    match prim {
+        Primitive::Mos { kind, params } => {
+            assert_eq!(*kind, MosKind::Nfet01v8);
+            assert_eq!(params.nf, 4);
+            assert_eq!(params.w, 1_000);
+            assert_eq!(params.l, 150);
+        }
+        _ => panic!("bad primitive"),
+    }
+}
+
+#[test]
+fn spice_raw_instances_of_library_cells_are_not_converted_to_devices() {
+    let mut lib = scir::LibraryBuilder::<spice::Spice>::new();
+    let device = spice::Primitive::RawInstance {
+        cell: arcstr::literal!("sky130_fd_pr__nfet_01v8"),
+        ports: ["d", "g", "s", "b"]
+            .into_iter()
+            .map(arcstr::ArcStr::from)
+            .collect(),
+        params: HashMap::from_iter([
+            (
+                UniCase::new(arcstr::literal!("w")),
+                ParamValue::Numeric(dec!(1)),
+            ),
+            (
+                UniCase::new(arcstr::literal!("l")),
+                ParamValue::Numeric(dec!(0.15)),
+            ),
+        ]),
+    };
+    let device = lib.add_primitive(device);
+
+    let mut cell = scir::Cell::new("top");
+    let mut inst = scir::Instance::new("x0", device);
+    for port in ["d", "g", "s", "b"] {
+        let node = cell.add_node(port);
+        cell.expose_port(node, scir::Direction::InOut);
+        inst.connect(port, node);
+    }
+    cell.add_instance(inst);
+    lib.add_cell(cell);
+
+    let converted = lib.clone().convert_schema::<Sky130>().unwrap();
+    assert!(matches!(
+        converted.primitive(device),
+        Primitive::Mos {
+            kind: MosKind::Nfet01v8,
+            ..
+        }
+    ));
+
+    // A cell defined in the library with the name of the device takes precedence.
+    let mut model = scir::Cell::new("sky130_fd_pr__nfet_01v8");
+    for port in ["d", "g", "s", "b"] {
+        let node = model.add_node(port);
+        model.expose_port(node, scir::Direction::InOut);
+    }
+    lib.add_cell(model);
+    let converted = lib.convert_schema::<Sky130>().unwrap();
+    match converted.primitive(device) {
+        Primitive::RawInstance { cell, ports, .. } => {
+            assert_eq!(cell.as_str(), "sky130_fd_pr__nfet_01v8");
+            assert_eq!(
+                ports.iter().map(|port| port.as_str()).collect::<Vec<_>>(),
+                ["d", "g", "s", "b"]
+            );
+        }
+        _ => panic!("bad primitive"),
+    }
+    let top = converted.cell_id_named("top");
+    assert!(converted
+        .cell(top)
+        .instance_named("x0")
+        .connections()
+        .contains_key("d"));
+}
+
+#[test]
+fn primitive_library_covers_all_schemas() {
+    let library = Sky130::primitive_library();
+    for &kind in MosKind::ALL {
+        let (w, l) = kind.min_dims();
+        for (name, scale) in [
+            (kind.open_subckt(), dec!(1)),
+            (kind.src_nda_subckt(), dec!(1)),
+            (kind.cds_subckt(), dec!(1e-6)),
+        ] {
+            let spec = library
+                .get(&name)
+                .unwrap_or_else(|| panic!("missing MOSFET `{name}`"));
+            assert_eq!(spec.param_spec("w").unwrap().range().min, Some(w * scale));
+            assert_eq!(spec.param_spec("l").unwrap().range().min, Some(l * scale));
+        }
+    }
+    assert_eq!(
+        library
+            .get("nshort")
+            .unwrap()
+            .param_spec("l")
+            .unwrap()
+            .range()
+            .min,
+        Some(dec!(0.15))
+    );
+    assert_eq!(
+        library
+            .get("pfet_01v8_lvt")
+            .unwrap()
+            .param_spec("l")
+            .unwrap()
+            .range()
+            .min,
+        Some(dec!(0.35e-6))
+    );
+}
+
+#[test]
+fn primitive_library_validates_sky130_devices() {
+    use substrate::error::Error;
+    use substrate::schematic::primitives::PrimitiveError;
+
+    let ctx = Context::builder()
+        .install(Sky130::primitive_library())
+        .build();
+
+    for width in [PolyResWidth::W350, PolyResWidth::W5730] {
+        ctx.generate_schematic(PolyRes::new(PolyResKind::XHigh, width, 2_000))
+            .try_cell()
+            .expect("failed to generate poly resistor");
+    }
+    ctx.generate_schematic(MimCap::new(2_000, 4_000))
+        .try_cell()
+        .expect("failed to generate MIM capacitor");
+    ctx.generate_schematic(crate::mos::Nfet01v8::new((360, 150)))
+        .try_cell()
+        .expect("failed to generate minimum size NMOS");
+
+    for err in [
+        ctx.generate_schematic(crate::mos::Nfet01v8::new((200, 150)))
+            .try_cell()
+            .unwrap_err(),
+        ctx.generate_schematic(crate::mos::Pfet01v8Lvt::new((1_000, 150)))
+            .try_cell()
+            .unwrap_err(),
+    ] {
+        assert!(matches!(
+            err,
+            Error::InvalidPrimitive {
+                source: PrimitiveError::OutOfRange { .. },
+                ..
+            }
+        ));
+    }
+
+    let library = ctx
+        .get_installation::<substrate::schematic::primitives::PrimitiveLibrary>()
+        .unwrap();
+    let mim_cap = |w: Decimal| PrimitiveParts {
+        kind: arcstr::literal!("sky130_fd_pr__cap_mim_m3_1"),
+        ports: vec!["c0".into(), "c1".into()],
+        params: vec![("w".into(), w.into()), ("l".into(), dec!(2).into())],
+    };
+    assert!(library.validate(&mim_cap(dec!(2))).is_ok());
+    assert!(matches!(
+        library.validate(&mim_cap(dec!(0.5))),
+        Err(PrimitiveError::OutOfRange { .. })
+    ));
+    let poly_res = PrimitiveParts {
+        kind: arcstr::literal!("sky130_fd_pr__res_high_po_0p35"),
+        ports: vec!["r0".into(), "r1".into(), "b".into()],
+        params: Vec::new(),
+    };
+    assert!(matches!(
+        library.validate(&poly_res),
+        Err(PrimitiveError::MissingParam { .. })
+    ));
+}
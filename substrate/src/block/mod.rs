@@ -9,6 +9,7 @@ pub use codegen::Block;
 use crate::arcstr::ArcStr;
 use crate::types::Io;
 
+pub mod registry;
 #[cfg(test)]
 mod tests;
 
@@ -32,6 +33,22 @@ pub trait Block: Hash + Eq + Send + Sync + Any {
 
     /// Returns a fully-specified instance of this cell's `Io`.
     fn io(&self) -> Self::Io;
+
+    /// The version of this block's generator.
+    ///
+    /// Blocks that declare a version record their [`Provenance`] in exported netlists
+    /// and GDS files. Defaults to [`None`].
+    fn version(&self) -> Option<ArcStr> {
+        None
+    }
+
+    /// A description of this block's parameters, recorded in its [`Provenance`].
+    ///
+    /// Defaults to [`None`]. Blocks that derive [`Block`] with a version describe their
+    /// parameters using their [`Debug`] representation.
+    fn parameters(&self) -> Option<ArcStr> {
+        None
+    }
 }
 
 impl<T: Block> Block for Arc<T> {
@@ -44,4 +61,53 @@ impl<T: Block> Block for Arc<T> {
     fn io(&self) -> Self::Io {
         T::io(self)
     }
+
+    fn version(&self) -> Option<ArcStr> {
+        T::version(self)
+    }
+
+    fn parameters(&self) -> Option<ArcStr> {
+        T::parameters(self)
+    }
+}
+
+/// A record of the generator that produced a cell.
+///
+/// Exported netlists include the provenance of each cell as comments, and exported
+/// GDS files include it as properties, so that fabricated artifacts can be traced back
+/// to the code revision that generated them.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct Provenance {
+    /// The name of the generator's type.
+    pub generator: ArcStr,
+    /// The version of the generator.
+    pub version: ArcStr,
+    /// A description of the generator's parameters, if provided.
+    pub parameters: Option<ArcStr>,
+}
+
+impl Provenance {
+    /// Returns the provenance of `block`, or [`None`] if it does not declare a version.
+    pub fn of<T: Block>(block: &T) -> Option<Self> {
+        Some(Self {
+            generator: std::any::type_name::<T>().into(),
+            version: block.version()?,
+            parameters: block.parameters(),
+        })
+    }
+
+    /// The key-value pairs recorded in exported artifacts.
+    pub fn entries(&self) -> Vec<(ArcStr, ArcStr)> {
+        let mut entries = vec![
+            (
+                arcstr::literal!("substrate_generator"),
+                self.generator.clone(),
+            ),
+            (arcstr::literal!("substrate_version"), self.version.clone()),
+        ];
+        if let Some(parameters) = &self.parameters {
+            entries.push((arcstr::literal!("substrate_parameters"), parameters.clone()));
+        }
+        entries
+    }
 }
@@ -0,0 +1,349 @@
+//! A registry of blocks that can be generated by name with runtime parameters.
+//!
+//! External tools (e.g. a layout editor or a command-line wrapper) typically refer to
+//! cells by name and describe their parameters as strings. Blocks implementing
+//! [`FromParams`] can be registered in a [`Registry`] along with a function that writes
+//! the block's generated views (e.g. GDS or netlists) to disk:
+//!
+//! ```ignore
+//! let mut registry = Registry::new();
+//! registry.register("inverter", |ctx: &Context, block: Inverter, out_dir: &Path| {
+//!     let gds = out_dir.join("inverter.gds");
+//!     ctx.write_layout(block, to_gds, &gds)?;
+//!     Ok(Generated::new().with_gds(gds))
+//! });
+//!
+//! let params: Params = "strength=2".parse()?;
+//! let generated = registry.generate(&ctx, "inverter", &params, "build/inverter")?;
+//! ```
+
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use arcstr::ArcStr;
+use indexmap::IndexMap;
+
+use super::Block;
+use crate::context::Context;
+use crate::error::Result;
+
+/// An error constructing or generating a registered block.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum RegistryError {
+    /// No generator is registered with the given name.
+    #[error("no generator named `{0}` is registered")]
+    UnknownGenerator(ArcStr),
+    /// A required parameter was not provided.
+    #[error("missing parameter `{0}`")]
+    MissingParam(ArcStr),
+    /// A parameter value could not be parsed.
+    #[error("invalid value `{value}` for parameter `{name}`: {reason}")]
+    InvalidParam {
+        /// The name of the parameter.
+        name: ArcStr,
+        /// The provided value.
+        value: ArcStr,
+        /// The reason the value could not be parsed.
+        reason: ArcStr,
+    },
+    /// A parameter string was not of the form `name=value`.
+    #[error("malformed parameter `{0}`; expected `name=value`")]
+    MalformedParam(ArcStr),
+    /// A parameter was provided that the block does not read.
+    #[error("unknown parameter `{0}`")]
+    UnknownParam(ArcStr),
+}
+
+/// A map of parameter names to unparsed values.
+///
+/// Keeps track of which parameters have been read, so that parameters not used
+/// by a block can be reported (see [`Params::unused`]).
+#[derive(Debug, Default)]
+pub struct Params {
+    values: IndexMap<ArcStr, ArcStr>,
+    read: Mutex<HashSet<ArcStr>>,
+}
+
+impl Clone for Params {
+    fn clone(&self) -> Self {
+        Self {
+            values: self.values.clone(),
+            read: Mutex::new(self.read_names().clone()),
+        }
+    }
+}
+
+impl PartialEq for Params {
+    fn eq(&self, other: &Self) -> bool {
+        self.values == other.values
+    }
+}
+
+impl Eq for Params {}
+
+impl Params {
+    /// Creates an empty parameter map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the value of parameter `name`, replacing any existing value.
+    pub fn set(&mut self, name: impl Into<ArcStr>, value: impl Into<ArcStr>) -> &mut Self {
+        self.values.insert(name.into(), value.into());
+        self
+    }
+
+    fn read_names(&self) -> MutexGuard<'_, HashSet<ArcStr>> {
+        // The set is always left in a consistent state, so a poisoned lock can be reused.
+        self.read.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns the unparsed value of parameter `name`, if present.
+    ///
+    /// Marks the parameter as read.
+    pub fn raw(&self, name: &str) -> Option<&ArcStr> {
+        let (name, value) = self.values.get_key_value(name)?;
+        self.read_names().insert(name.clone());
+        Some(value)
+    }
+
+    /// Returns the names of parameters that have not been read, in insertion order.
+    pub fn unused(&self) -> Vec<ArcStr> {
+        let read = self.read_names();
+        self.values
+            .keys()
+            .filter(|name| !read.contains(*name))
+            .cloned()
+            .collect()
+    }
+
+    /// Iterates over parameter names and unparsed values in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = (&ArcStr, &ArcStr)> {
+        self.values.iter()
+    }
+
+    /// Parses the value of parameter `name`, returning [`None`] if it is not present.
+    pub fn get_opt<T>(&self, name: &str) -> Result<Option<T>, RegistryError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.raw(name)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|e: T::Err| RegistryError::InvalidParam {
+                        name: name.into(),
+                        value: value.clone(),
+                        reason: arcstr::format!("{}", e),
+                    })
+            })
+            .transpose()
+    }
+
+    /// Parses the value of required parameter `name`.
+    pub fn get<T>(&self, name: &str) -> Result<T, RegistryError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.get_opt(name)?
+            .ok_or_else(|| RegistryError::MissingParam(name.into()))
+    }
+
+    /// Parses the value of parameter `name`, returning `default` if it is not present.
+    pub fn get_or<T>(&self, name: &str, default: T) -> Result<T, RegistryError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        Ok(self.get_opt(name)?.unwrap_or(default))
+    }
+}
+
+impl<K: Into<ArcStr>, V: Into<ArcStr>> FromIterator<(K, V)> for Params {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        Self {
+            values: iter
+                .into_iter()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect(),
+            read: Default::default(),
+        }
+    }
+}
+
+impl FromStr for Params {
+    type Err = RegistryError;
+
+    /// Parses a comma-separated list of `name=value` pairs.
+    ///
+    /// A comma-separated item without an `=` continues the value of the preceding
+    /// parameter, so values may contain commas (e.g. `widths=1,2,3,l=150`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut pairs: Vec<(&str, String)> = Vec::new();
+        for item in s.split(',').filter(|item| !item.trim().is_empty()) {
+            match (item.split_once('='), pairs.last_mut()) {
+                (Some((name, value)), _) => pairs.push((name.trim(), value.to_string())),
+                (None, Some((_, value))) => {
+                    value.push(',');
+                    value.push_str(item);
+                }
+                (None, None) => return Err(RegistryError::MalformedParam(item.trim().into())),
+            }
+        }
+        Ok(pairs
+            .into_iter()
+            .map(|(name, value)| (name, value.trim().to_string()))
+            .collect())
+    }
+}
+
+/// A block that can be constructed from runtime parameters.
+pub trait FromParams: Sized {
+    /// Constructs the block from the given parameters.
+    fn from_params(params: &Params) -> Result<Self, RegistryError>;
+}
+
+/// Paths to the views written by a registered generator.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Generated {
+    /// The path to the generated GDS file, if any.
+    pub gds: Option<PathBuf>,
+    /// The path to the generated netlist, if any.
+    pub netlist: Option<PathBuf>,
+    /// Paths to any other generated files (e.g. simulation outputs or verification reports),
+    /// keyed by a short description.
+    pub outputs: IndexMap<ArcStr, PathBuf>,
+    /// Numeric data produced by the generator (e.g. simulation waveforms), keyed by name.
+    pub data: IndexMap<ArcStr, Vec<f64>>,
+}
+
+impl Generated {
+    /// Creates an empty set of generated views.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the path to the generated GDS file.
+    pub fn with_gds(mut self, gds: impl Into<PathBuf>) -> Self {
+        self.gds = Some(gds.into());
+        self
+    }
+
+    /// Sets the path to the generated netlist.
+    pub fn with_netlist(mut self, netlist: impl Into<PathBuf>) -> Self {
+        self.netlist = Some(netlist.into());
+        self
+    }
+
+    /// Adds the path to another generated file.
+    pub fn with_output(mut self, name: impl Into<ArcStr>, path: impl Into<PathBuf>) -> Self {
+        self.outputs.insert(name.into(), path.into());
+        self
+    }
+
+    /// Adds an array of numeric data.
+    pub fn with_data(mut self, name: impl Into<ArcStr>, data: impl Into<Vec<f64>>) -> Self {
+        self.data.insert(name.into(), data.into());
+        self
+    }
+
+    /// Iterates over the paths to all generated files, keyed by a short description.
+    pub fn paths(&self) -> impl Iterator<Item = (&str, &Path)> {
+        self.gds
+            .iter()
+            .map(|gds| ("gds", gds.as_path()))
+            .chain(
+                self.netlist
+                    .iter()
+                    .map(|netlist| ("netlist", netlist.as_path())),
+            )
+            .chain(
+                self.outputs
+                    .iter()
+                    .map(|(name, path)| (name.as_str(), path.as_path())),
+            )
+    }
+}
+
+type GenerateFn = Box<dyn Fn(&Context, &Params, &Path) -> Result<Generated> + Send + Sync>;
+
+/// A registry of generators that construct blocks from runtime parameters.
+#[derive(Default)]
+pub struct Registry {
+    generators: IndexMap<ArcStr, GenerateFn>,
+}
+
+impl std::fmt::Debug for Registry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Registry")
+            .field("generators", &self.generators.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl Registry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers block `B` under `name`.
+    ///
+    /// `generate` is called with the block constructed from the provided parameters and an
+    /// output directory, and should write the desired views of the block to that directory.
+    /// Generation fails with [`RegistryError::UnknownParam`] if a provided parameter is not
+    /// read by [`FromParams::from_params`].
+    /// Replaces any generator previously registered under the same name.
+    pub fn register<B: Block + FromParams>(
+        &mut self,
+        name: impl Into<ArcStr>,
+        generate: impl Fn(&Context, B, &Path) -> Result<Generated> + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.generators.insert(
+            name.into(),
+            Box::new(move |ctx, params, out_dir| {
+                let block = B::from_params(params)?;
+                if let Some(name) = params.unused().into_iter().next() {
+                    return Err(RegistryError::UnknownParam(name).into());
+                }
+                generate(ctx, block, out_dir)
+            }),
+        );
+        self
+    }
+
+    /// Iterates over the names of registered generators in registration order.
+    pub fn names(&self) -> impl Iterator<Item = &ArcStr> {
+        self.generators.keys()
+    }
+
+    /// Returns `true` if a generator is registered under `name`.
+    pub fn contains(&self, name: &str) -> bool {
+        self.generators.contains_key(name)
+    }
+
+    /// Generates the block registered under `name` with the given parameters,
+    /// writing its views to `out_dir`.
+    ///
+    /// Creates `out_dir` if it does not exist.
+    pub fn generate(
+        &self,
+        ctx: &Context,
+        name: &str,
+        params: &Params,
+        out_dir: impl AsRef<Path>,
+    ) -> Result<Generated> {
+        let generate = self
+            .generators
+            .get(name)
+            .ok_or_else(|| RegistryError::UnknownGenerator(name.into()))?;
+        let out_dir = out_dir.as_ref();
+        std::fs::create_dir_all(out_dir)?;
+        generate(ctx, params, out_dir)
+    }
+}
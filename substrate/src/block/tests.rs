@@ -1,7 +1,15 @@
+use std::path::Path;
+
+use crate::context::Context;
+use crate::error::Error;
+use crate::tests::get_path;
 use crate::types::TestbenchIo;
 use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
-use substrate::block::Block;
+use substrate::block::{Block, Provenance};
+
+use super::registry::{FromParams, Generated, Params, Registry, RegistryError};
 
 #[derive(Debug, Copy, Clone, Hash, Eq, PartialEq, Serialize, Deserialize, Block)]
 #[substrate(io = "TestbenchIo")]
@@ -10,3 +18,107 @@ pub struct DelayCellTb<T> {
     pub tr: Decimal,
     pub tf: Decimal,
 }
+
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq, Block)]
+#[substrate(io = "TestbenchIo")]
+pub struct ParamTb {
+    pub n: usize,
+    pub tr: Decimal,
+}
+
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq, Block)]
+#[substrate(io = "TestbenchIo", version = "1.2.0")]
+pub struct VersionedTb {
+    pub n: usize,
+}
+
+impl FromParams for ParamTb {
+    fn from_params(params: &Params) -> Result<Self, RegistryError> {
+        Ok(Self {
+            n: params.get("n")?,
+            tr: params.get_or("tr", dec!(1))?,
+        })
+    }
+}
+
+#[test]
+fn registry_generates_blocks_from_params() {
+    let mut registry = Registry::new();
+    registry.register(
+        "param_tb",
+        |_ctx: &Context, block: ParamTb, out_dir: &Path| {
+            Ok(Generated::new().with_netlist(out_dir.join(format!("param_tb_{}.sp", block.n))))
+        },
+    );
+    assert_eq!(registry.names().collect::<Vec<_>>(), vec!["param_tb"]);
+
+    let ctx = Context::new();
+    let out_dir = get_path("registry_generates_blocks_from_params", "out");
+    let params: Params = "n = 4, tr=0.5".parse().unwrap();
+    assert_eq!(
+        ParamTb::from_params(&params).unwrap(),
+        ParamTb {
+            n: 4,
+            tr: dec!(0.5)
+        }
+    );
+    let generated = registry
+        .generate(&ctx, "param_tb", &params, &out_dir)
+        .unwrap();
+    assert_eq!(generated.netlist, Some(out_dir.join("param_tb_4.sp")));
+    assert_eq!(generated.gds, None);
+
+    assert!(matches!(
+        registry.generate(&ctx, "param_tb", &Params::new(), &out_dir),
+        Err(Error::Registry(RegistryError::MissingParam(name))) if name == "n"
+    ));
+    assert!(matches!(
+        registry.generate(&ctx, "param_tb", &"n=four".parse().unwrap(), &out_dir),
+        Err(Error::Registry(RegistryError::InvalidParam { .. }))
+    ));
+    assert!(matches!(
+        registry.generate(&ctx, "missing", &params, &out_dir),
+        Err(Error::Registry(RegistryError::UnknownGenerator(_)))
+    ));
+    assert!(matches!(
+        "n".parse::<Params>(),
+        Err(RegistryError::MalformedParam(_))
+    ));
+    assert!(matches!(
+        registry.generate(&ctx, "param_tb", &"n=4,m=2".parse().unwrap(), &out_dir),
+        Err(Error::Registry(RegistryError::UnknownParam(name))) if name == "m"
+    ));
+}
+
+#[test]
+fn params_values_can_contain_commas() {
+    let params: Params = "widths = 1, 2,3, l=150,".parse().unwrap();
+    assert_eq!(params.raw("widths").map(|v| v.as_str()), Some("1, 2,3"));
+    assert_eq!(params.raw("l").map(|v| v.as_str()), Some("150"));
+    assert!(params.unused().is_empty());
+}
+
+#[test]
+fn provenance_is_recorded_for_versioned_blocks() {
+    assert_eq!(Provenance::of(&ParamTb { n: 1, tr: dec!(1) }), None);
+
+    let provenance = Provenance::of(&VersionedTb { n: 4 }).unwrap();
+    assert_eq!(provenance.version, "1.2.0");
+    assert!(provenance.generator.ends_with("VersionedTb"));
+    assert_eq!(
+        provenance.parameters.as_deref(),
+        Some("VersionedTb { n: 4 }")
+    );
+    assert_eq!(
+        provenance
+            .entries()
+            .iter()
+            .map(|(key, _)| key.as_str())
+            .collect::<Vec<_>>(),
+        [
+            "substrate_generator",
+            "substrate_version",
+            "substrate_parameters"
+        ]
+    );
+}
@@ -8,6 +8,7 @@ use std::{
 use cache::{mem::TypeCache, multi::MultiCache, CacheHandle, Cacheable, CacheableWithState};
 use serde::{de::DeserializeOwned, Serialize};
 
+pub mod stats;
 #[cfg(test)]
 mod tests;
 
@@ -0,0 +1,177 @@
+//! Generator memoization statistics.
+//!
+//! Substrate memoizes schematic and layout generators on the value of the block being generated.
+//! The statistics recorded here show which blocks were served from the in-memory cache and which
+//! were regenerated, making it easier to see why changing one parameter causes many cells to be
+//! regenerated.
+
+use std::any::type_name;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use arcstr::ArcStr;
+use indexmap::IndexMap;
+
+use crate::block::Block;
+
+/// The view of a block being generated.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum View {
+    /// A schematic view.
+    Schematic,
+    /// A layout view.
+    Layout,
+}
+
+/// The components of the key used to memoize a generator.
+///
+/// Two requests for the same view with equal block types and block hashes
+/// are served from the same cache entry.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct GenerationKey {
+    /// The view being generated.
+    pub view: View,
+    /// The Rust type of the block.
+    pub block_type: &'static str,
+    /// The name of the block, as returned by [`Block::name`].
+    pub block_name: ArcStr,
+    /// A hash of the block's value.
+    ///
+    /// Blocks with the same name but different hashes differ in at least
+    /// one parameter that is not reflected in the block's name.
+    pub block_hash: u64,
+}
+
+impl GenerationKey {
+    pub(crate) fn new<B: Block>(view: View, block: &B) -> Self {
+        let mut hasher = DefaultHasher::new();
+        block.hash(&mut hasher);
+        Self {
+            view,
+            block_type: type_name::<B>(),
+            block_name: block.name(),
+            block_hash: hasher.finish(),
+        }
+    }
+}
+
+/// Memoization statistics for a single cache entry.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EntryStats {
+    /// The number of times the block was requested.
+    pub requests: usize,
+    /// The number of times the block's generator was run.
+    ///
+    /// Generators are run at most once per context, so this is either 0 or 1.
+    pub generations: usize,
+    /// The time spent running the block's generator.
+    ///
+    /// [`None`] if the generator has not yet completed.
+    /// Does not include the time spent waiting on instantiated cells.
+    pub generation_time: Option<Duration>,
+}
+
+impl EntryStats {
+    /// The number of requests served from the cache without running the generator.
+    pub fn hits(&self) -> usize {
+        self.requests.saturating_sub(self.generations)
+    }
+}
+
+/// Memoization statistics for the generators run in a [`Context`](crate::context::Context).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GenerationStats {
+    entries: IndexMap<GenerationKey, EntryStats>,
+}
+
+impl GenerationStats {
+    /// Iterates over the statistics of each cache entry, in the order in which they were first requested.
+    pub fn entries(&self) -> impl Iterator<Item = (&GenerationKey, &EntryStats)> {
+        self.entries.iter()
+    }
+
+    /// Returns the statistics for the given block and view, if the block was requested.
+    pub fn get<B: Block>(&self, view: View, block: &B) -> Option<&EntryStats> {
+        self.entries.get(&GenerationKey::new(view, block))
+    }
+
+    /// The total number of requests served from the cache.
+    pub fn hits(&self) -> usize {
+        self.entries.values().map(EntryStats::hits).sum()
+    }
+
+    /// The total number of generators run.
+    pub fn generations(&self) -> usize {
+        self.entries.values().map(|entry| entry.generations).sum()
+    }
+
+    /// The total time spent running generators.
+    ///
+    /// Since generators run in parallel, this may exceed the elapsed wall-clock time.
+    pub fn generation_time(&self) -> Duration {
+        self.entries
+            .values()
+            .filter_map(|entry| entry.generation_time)
+            .sum()
+    }
+
+    /// Returns the keys of all blocks with the given name that were generated more than once
+    /// with different parameters.
+    ///
+    /// Useful for identifying parameters that cause unexpected regeneration.
+    pub fn variants(&self, view: View, block_name: &str) -> Vec<&GenerationKey> {
+        self.entries
+            .iter()
+            .filter(|(key, entry)| {
+                key.view == view && key.block_name == block_name && entry.generations > 0
+            })
+            .map(|(key, _)| key)
+            .collect()
+    }
+}
+
+/// A thread-safe recorder of [`GenerationStats`].
+#[derive(Debug, Clone, Default)]
+pub(crate) struct StatsRecorder(Arc<Mutex<GenerationStats>>);
+
+impl StatsRecorder {
+    /// Records a request for the given key.
+    pub(crate) fn request(&self, key: &GenerationKey) {
+        let mut stats = self.0.lock().unwrap();
+        stats.entries.entry(key.clone()).or_default().requests += 1;
+    }
+
+    /// Runs `generate`, recording it as a generation of `key`.
+    pub(crate) fn generate<T>(&self, key: &GenerationKey, generate: impl FnOnce() -> T) -> T {
+        self.0
+            .lock()
+            .unwrap()
+            .entries
+            .entry(key.clone())
+            .or_default()
+            .generations += 1;
+        let start = Instant::now();
+        let out = generate();
+        let elapsed = start.elapsed();
+        self.0
+            .lock()
+            .unwrap()
+            .entries
+            .entry(key.clone())
+            .or_default()
+            .generation_time = Some(elapsed);
+        out
+    }
+
+    /// Returns a snapshot of the recorded statistics.
+    pub(crate) fn snapshot(&self) -> GenerationStats {
+        self.0.lock().unwrap().clone()
+    }
+
+    /// Clears the recorded statistics.
+    pub(crate) fn reset(&self) {
+        *self.0.lock().unwrap() = GenerationStats::default();
+    }
+}
@@ -7,6 +7,7 @@ use serde::{Deserialize, Serialize};
 use substrate::schematic::{CellBuilder, HasNestedView, InstancePath};
 use substrate::{block::Block, schematic::Schematic};
 
+use crate::cache::stats::View;
 use crate::context::Context;
 
 lazy_static! {
@@ -86,3 +87,57 @@ fn caching_works() {
     }
     assert_eq!(*RUNS.lock().unwrap(), 1);
 }
+
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub struct StatsBlock(u64);
+
+impl Block for StatsBlock {
+    type Io = ();
+
+    fn name(&self) -> arcstr::ArcStr {
+        arcstr::format!("statsblock")
+    }
+
+    fn io(&self) -> Self::Io {
+        Default::default()
+    }
+}
+
+impl Schematic for StatsBlock {
+    type Schema = StringSchema;
+    type NestedData = ();
+
+    fn schematic(
+        &self,
+        _io: &crate::types::schematic::IoNodeBundle<Self>,
+        _cell: &mut CellBuilder<<Self as Schematic>::Schema>,
+    ) -> crate::error::Result<Self::NestedData> {
+        Ok(())
+    }
+}
+
+#[test]
+fn generation_stats_record_hits_and_variants() {
+    let ctx = Context::new();
+    for i in [0, 1, 0, 0] {
+        ctx.generate_schematic(StatsBlock(i)).cell();
+    }
+
+    let stats = ctx.generation_stats();
+    let entry = stats.get(View::Schematic, &StatsBlock(0)).unwrap();
+    assert_eq!(entry.requests, 3);
+    assert_eq!(entry.generations, 1);
+    assert_eq!(entry.hits(), 2);
+    assert!(entry.generation_time.is_some());
+    assert_eq!(stats.generations(), 2);
+    assert_eq!(stats.hits(), 2);
+
+    // Both blocks share the name `statsblock`, but differ in their hashes.
+    let variants = stats.variants(View::Schematic, "statsblock");
+    assert_eq!(variants.len(), 2);
+    assert_ne!(variants[0].block_hash, variants[1].block_hash);
+    assert!(variants[0].block_type.ends_with("StatsBlock"));
+
+    ctx.reset_generation_stats();
+    assert_eq!(ctx.generation_stats().entries().count(), 0);
+}
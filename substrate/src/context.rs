@@ -1,37 +1,49 @@
 //! The global context.
 
 use std::any::{Any, TypeId};
+use std::collections::hash_map::Entry;
 use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 use std::marker::PhantomData;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock};
 
+use arcstr::ArcStr;
 use config::Config;
-use gds::GdsUnits;
-use gdsconv::export::GdsExportOpts;
+use gds::{GdsDateTimes, GdsUnits};
+use gdsconv::export::{GdsExportOpts, GdsStreamWriter, DEFAULT_PROPERTY_LAYER};
 use gdsconv::GdsLayer;
 use indexmap::IndexMap;
 use substrate::schematic::{CellBuilder, ConvCacheKey, RawCellContentsBuilder};
 use tracing::{span, Level};
 
-use crate::block::Block;
+use crate::block::{Block, Provenance};
+use crate::cache::stats::{GenerationKey, GenerationStats, StatsRecorder, View};
 use crate::cache::Cache;
 use crate::diagnostics::SourceInfo;
 use crate::error::Result;
 use crate::execute::{Executor, LocalExecutor};
-use crate::layout::conv::export_multi_top_layir_lib;
-use crate::layout::element::{Element, NamedPorts, RawCell};
+use crate::layout::conv::{export_layir_cells, export_multi_top_layir_lib, LayirExportError};
+use crate::layout::element::{CellId as LayoutCellId, Element, NamedPorts, RawCell};
 use crate::layout::error::LayoutError;
+use crate::layout::scheduler::Scheduler;
 use crate::layout::{Cell as LayoutCell, CellHandle as LayoutCellHandle};
 use crate::layout::{CellBuilder as LayoutCellBuilder, CellLayer};
 use crate::layout::{Layout, LayoutContext};
 use crate::schematic::conv::{export_multi_top_scir_lib, ConvError, RawLib};
+use crate::schematic::overrides::Overrides;
 use crate::schematic::schema::{FromSchema, Schema};
 use crate::schematic::{
     Cell as SchematicCell, CellCacheKey, CellHandle as SchematicCellHandle, CellId, CellMetadata,
-    RawCellInnerBuilder, SchemaCellCacheValue, SchemaCellHandle, Schematic, SchematicContext,
+    HasNestedView, RawCellInnerBuilder, SchemaCellCacheValue, SchemaCellHandle, Schematic,
+    SchematicContext,
 };
-use crate::simulation::{SimController, SimulationContext, Simulator, Testbench};
+use crate::simulation::batch::{run_batch, BatchSim};
+use crate::simulation::data::Save;
+use crate::simulation::{
+    SavedData, SimController, SimulationContext, Simulator, SupportedBy, Testbench,
+};
+use crate::snapshot::{Snapshot, SnapshotMetadata};
 use crate::types::layout::PortGeometryBuilder;
 use crate::types::schematic::{IoNodeBundle, NodeContext, NodePriority, Port};
 use crate::types::{FlatLen, Flatten, Flipped, HasBundleKind, HasNameTree, NameBuf};
@@ -46,6 +58,10 @@ use crate::types::{FlatLen, Flatten, Flipped, HasBundleKind, HasNameTree, NameBu
 pub struct Context {
     pub(crate) inner: Arc<RwLock<ContextInner>>,
     installations: Arc<HashMap<TypeId, Arc<dyn Any + Send + Sync>>>,
+    scheduler: Scheduler,
+    simulation_parallelism: usize,
+    pub(crate) reproducible: bool,
+    gds_property_layer: Option<GdsLayer>,
     /// The executor to which commands should be submitted.
     pub executor: Arc<dyn Executor>,
     /// A cache for storing the results of expensive computations.
@@ -60,6 +76,10 @@ impl Default for Context {
         Self {
             inner: Default::default(),
             installations: Default::default(),
+            scheduler: Default::default(),
+            simulation_parallelism: default_parallelism(),
+            reproducible: false,
+            gds_property_layer: Some(DEFAULT_PROPERTY_LAYER),
             executor: Arc::new(LocalExecutor),
             cache: Cache::new(
                 cfg.cache
@@ -83,7 +103,8 @@ pub trait Installation: Any + Send + Sync {
     /// required by the installation.
     ///
     /// PDKs, for example, should use this hook to install their layer
-    /// set and standard cell libraries.
+    /// set, standard cell libraries, and
+    /// [`PrimitiveLibrary`](crate::schematic::primitives::PrimitiveLibrary).
     #[allow(unused_variables)]
     fn post_install(&self, ctx: &mut ContextBuilder) {}
 }
@@ -96,6 +117,10 @@ pub struct ContextBuilder {
     installations: HashMap<TypeId, Arc<dyn Any + Send + Sync>>,
     executor: Arc<dyn Executor>,
     cache: Option<Cache>,
+    layout_parallelism: Option<usize>,
+    simulation_parallelism: Option<usize>,
+    reproducible: bool,
+    gds_property_layer: Option<GdsLayer>,
 }
 
 impl Default for ContextBuilder {
@@ -104,6 +129,10 @@ impl Default for ContextBuilder {
             installations: Default::default(),
             executor: Arc::new(LocalExecutor),
             cache: None,
+            layout_parallelism: None,
+            simulation_parallelism: None,
+            reproducible: false,
+            gds_property_layer: Some(DEFAULT_PROPERTY_LAYER),
         }
     }
 }
@@ -143,6 +172,60 @@ impl ContextBuilder {
         self
     }
 
+    /// Sets the maximum number of layout cells generated in parallel by background threads.
+    ///
+    /// Defaults to the available parallelism of the host machine.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `parallelism` is zero.
+    pub fn layout_parallelism(&mut self, parallelism: usize) -> &mut Self {
+        assert!(parallelism > 0, "layout parallelism must be positive");
+        self.layout_parallelism = Some(parallelism);
+        self
+    }
+
+    /// Sets the maximum number of simulations run in parallel by [`Context::simulate_batch`].
+    ///
+    /// Defaults to the available parallelism of the host machine.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `parallelism` is zero.
+    pub fn simulation_parallelism(&mut self, parallelism: usize) -> &mut Self {
+        assert!(parallelism > 0, "simulation parallelism must be positive");
+        self.simulation_parallelism = Some(parallelism);
+        self
+    }
+
+    /// Enables or disables reproducible builds.
+    ///
+    /// When enabled, schematic and layout cell IDs are derived from stable hashes of
+    /// the blocks being generated rather than from the order in which generation is requested,
+    /// and GDS files are written with fixed timestamps. Repeated builds of the same blocks
+    /// then produce byte-identical netlists and GDS files, regardless of thread scheduling.
+    /// Cells that are not generated from a block, such as imported or uniquified layouts,
+    /// are assigned IDs derived from their names.
+    ///
+    /// Disabled by default.
+    pub fn reproducible(&mut self, reproducible: bool) -> &mut Self {
+        self.reproducible = reproducible;
+        self
+    }
+
+    /// Sets the GDS layer on which cell properties are written.
+    ///
+    /// Layout cells of versioned blocks carry provenance properties identifying the
+    /// generator, version, and parameters that produced them. These properties are
+    /// attached to a text element at the origin of each exported GDS struct on this layer.
+    /// Passing [`None`] omits cell properties from GDS files.
+    ///
+    /// Defaults to [`DEFAULT_PROPERTY_LAYER`].
+    pub fn gds_property_layer(&mut self, layer: impl Into<Option<GdsLayer>>) -> &mut Self {
+        self.gds_property_layer = layer.into();
+        self
+    }
+
     /// Builds the context based on the configuration in this builder.
     pub fn build(&mut self) -> Context {
         let cfg = Config::default().expect("requires valid Substrate configuration");
@@ -150,6 +233,15 @@ impl ContextBuilder {
         Context {
             inner: Arc::new(RwLock::new(ContextInner::new())),
             installations: Arc::new(self.installations.clone()),
+            scheduler: self
+                .layout_parallelism
+                .map(Scheduler::new)
+                .unwrap_or_default(),
+            simulation_parallelism: self
+                .simulation_parallelism
+                .unwrap_or_else(default_parallelism),
+            reproducible: self.reproducible,
+            gds_property_layer: self.gds_property_layer,
             executor: self.executor.clone(),
             cache: self.cache.clone().unwrap_or_else(|| {
                 Cache::new(
@@ -170,8 +262,9 @@ impl ContextBuilder {
 #[derive(Debug, Default)]
 pub(crate) struct ContextInner {
     pub(crate) schematic: SchematicContext,
-    layout: LayoutContext,
+    pub(crate) layout: LayoutContext,
     private_installations: HashMap<TypeId, Arc<dyn Any + Send + Sync>>,
+    stats: StatsRecorder,
 }
 
 impl ContextInner {
@@ -186,12 +279,22 @@ impl Context {
         Default::default()
     }
 
-    /// Allocates a new [`CellId`].
-    fn alloc_cell_id(&self) -> CellId {
+    /// Allocates a new [`CellId`] for a cell named `name`.
+    ///
+    /// In [reproducible](ContextBuilder::reproducible) contexts, the ID is derived from `name`.
+    fn alloc_cell_id(&self, name: &str) -> CellId {
         let mut inner = self.inner.write().unwrap();
-        let SchematicContext { next_id, .. } = &mut inner.schematic;
-        next_id.increment();
-        *next_id
+        let SchematicContext {
+            next_id,
+            stable_ids,
+            ..
+        } = &mut inner.schematic;
+        if self.reproducible {
+            CellId::from_stable_hash(stable_ids.fresh(name))
+        } else {
+            next_id.increment();
+            *next_id
+        }
     }
 
     /// Steps to create schematic:
@@ -206,51 +309,76 @@ impl Context {
     pub(crate) fn generate_schematic_inner<B: Schematic>(
         &self,
         block: Arc<B>,
+        overrides: Overrides,
     ) -> SchemaCellHandle<B::Schema, B> {
         let key = CellCacheKey {
             block: block.clone(),
+            overrides,
             phantom: PhantomData::<B::Schema>,
         };
         let block_clone = block.clone();
         let mut inner = self.inner.write().unwrap();
         let context = self.clone();
+        let reproducible = self.reproducible;
+        let stats = inner.stats.clone();
+        let stats_key = GenerationKey::new(View::Schematic, block.as_ref());
+        stats.request(&stats_key);
         let SchematicContext {
             next_id,
+            stable_ids,
             cell_cache,
-            ..
         } = &mut inner.schematic;
+        let span = span!(
+            Level::DEBUG,
+            "generating schematic",
+            block = %block.name(),
+        )
+        .or_current();
         let (metadata, handle) = cell_cache.generate_partial_blocking(
             key,
             |key| {
-                next_id.increment();
-                let (cell_builder, io_data) =
-                    prepare_cell_builder(Some(*next_id), context, key.block.as_ref());
+                let id = if reproducible {
+                    CellId::from_stable_hash(stable_ids.id(&key.block.name(), key))
+                } else {
+                    next_id.increment();
+                    *next_id
+                };
+                let (mut cell_builder, io_data) =
+                    prepare_cell_builder(Some(id), context, key.block.as_ref());
+                cell_builder.overrides = key.overrides.clone();
                 let io_data = Arc::new(io_data);
                 (
                     CellMetadata::<B> {
-                        id: *next_id,
+                        id,
                         io_data: io_data.clone(),
                     },
-                    (*next_id, cell_builder, io_data),
+                    (id, cell_builder, io_data),
                 )
             },
             move |_key, (id, mut cell_builder, io_data)| {
-                let res = B::schematic(block_clone.as_ref(), io_data.as_ref(), &mut cell_builder);
+                let _guard = span.enter();
+                let res = stats.generate(&stats_key, || {
+                    B::schematic(block_clone.as_ref(), io_data.as_ref(), &mut cell_builder)
+                });
                 let fatal = cell_builder.fatal_error;
-                let raw = Arc::new(cell_builder.finish());
+                let raw = cell_builder.finish().map(Arc::new);
                 (!fatal)
                     .then_some(())
                     .ok_or(crate::error::Error::CellBuildFatal)
-                    .and(res.map(|data| SchemaCellCacheValue {
-                        raw: raw.clone(),
-                        cell: Arc::new(SchematicCell::new(
-                            id,
-                            io_data,
-                            block_clone,
-                            raw,
-                            Arc::new(data),
-                        )),
-                    }))
+                    .and(res)
+                    .and_then(|data| {
+                        let raw = raw?;
+                        Ok(SchemaCellCacheValue {
+                            raw: raw.clone(),
+                            cell: Arc::new(SchematicCell::new(
+                                id,
+                                io_data,
+                                block_clone,
+                                raw,
+                                Arc::new(data),
+                            )),
+                        })
+                    })
             },
         );
 
@@ -270,25 +398,25 @@ impl Context {
         }
     }
 
-    fn generate_cross_schematic_inner<B: Schematic, S2: FromSchema<B::Schema> + ?Sized>(
+    pub(crate) fn generate_cross_schematic_inner<
+        B: Schematic,
+        S2: FromSchema<B::Schema> + ?Sized,
+    >(
         &self,
         block: Arc<B>,
+        overrides: Overrides,
     ) -> SchemaCellHandle<S2, B> {
-        let handle = self.generate_schematic_inner(block);
+        let handle = self.generate_schematic_inner(block, overrides.clone());
         let mut inner = self.inner.write().unwrap();
         SchemaCellHandle {
             handle: inner.schematic.cell_cache.generate(
                 ConvCacheKey::<B, S2, B::Schema> {
                     block: handle.cell.block.clone(),
+                    overrides,
                     phantom: PhantomData,
                 },
                 move |_| {
-                    let SchemaCellCacheValue { raw, cell } = handle
-                        .handle
-                        .try_get()
-                        .unwrap()
-                        .as_ref()
-                        .map_err(|e| e.clone())?;
+                    let SchemaCellCacheValue { raw, cell } = handle.try_value()?;
                     Ok(SchemaCellCacheValue {
                         raw: Arc::new((**raw).clone().convert_schema::<S2>()?),
                         cell: cell.clone(),
@@ -306,7 +434,7 @@ impl Context {
         &self,
         block: B,
     ) -> SchemaCellHandle<S2, B> {
-        self.generate_cross_schematic_inner(Arc::new(block))
+        self.generate_cross_schematic_inner(Arc::new(block), Overrides::new())
     }
 
     /// Generates a schematic for `block` in the background.
@@ -314,7 +442,7 @@ impl Context {
     /// Returns a handle to the cell being generated.
     pub fn generate_schematic<T: Schematic>(&self, block: T) -> SchemaCellHandle<T::Schema, T> {
         let block = Arc::new(block);
-        self.generate_schematic_inner(block)
+        self.generate_schematic_inner(block, Overrides::new())
     }
 
     /// Export the given block and all sub-blocks as a SCIR library.
@@ -322,13 +450,28 @@ impl Context {
     /// Returns a SCIR library and metadata for converting between SCIR and Substrate formats.
     pub fn export_scir<T: Schematic>(&self, block: T) -> Result<RawLib<T::Schema>, ConvError> {
         let cell = self.generate_schematic(block);
-        // TODO: Handle errors.
-        let SchemaCellCacheValue { raw, .. } = cell.handle.unwrap_inner();
+        let SchemaCellCacheValue { raw, .. } = cell
+            .try_value()
+            .map_err(|e| ConvError::Generation(Arc::new(e)))?;
         raw.to_scir_lib()
     }
 
+    /// Exports a hierarchy and connectivity graph of the given block and all sub-blocks.
+    ///
+    /// The graph can be rendered with Graphviz or serialized to JSON.
+    /// See [`scir::graph::Graph`] for details.
+    pub fn export_schematic_graph<T: Schematic>(
+        &self,
+        block: T,
+    ) -> Result<scir::graph::Graph, ConvError> {
+        Ok(self.export_scir(block)?.scir.to_graph())
+    }
+
     /// Export the given cells and all their subcells as a SCIR library.
     ///
+    /// Each of the given cells is designated as a root of the library
+    /// (see [`scir::LibraryBuilder::add_root`]).
+    ///
     /// Returns a SCIR library and metadata for converting between SCIR and Substrate formats.
     pub fn export_scir_all<S: Schema + ?Sized>(
         &self,
@@ -351,14 +494,14 @@ impl Context {
             .get_installation::<S>()
             .expect("Simulator must be installed");
         let block = Arc::new(block);
-        let cell = self.generate_schematic_inner(block.clone());
-        // TODO: Handle errors.
-        let SchemaCellCacheValue { raw, cell } = cell.handle.unwrap_inner();
+        let cell = self.generate_schematic_inner(block.clone(), Overrides::new());
+        let SchemaCellCacheValue { raw, cell } = cell.try_value()?;
         let lib = raw.to_scir_lib()?;
         let ctx = SimulationContext {
             lib: Arc::new(lib),
             work_dir: work_dir.into(),
             ctx: self.clone(),
+            progress: None,
         };
         Ok(SimController {
             tb: cell.clone(),
@@ -367,6 +510,36 @@ impl Context {
         })
     }
 
+    /// Simulates a batch of independent testbenches, returning their outputs in order.
+    ///
+    /// All testbenches are generated before any simulation starts. Simulations are then
+    /// run in parallel, up to the limit set by [`ContextBuilder::simulation_parallelism`].
+    /// Returns an error if any testbench fails to generate; otherwise, returns the result of
+    /// each simulation. See [`batch`](crate::simulation::batch) for details.
+    pub fn simulate_batch<S, T, A>(
+        &self,
+        sims: impl IntoIterator<Item = BatchSim<T, S::Options, A>>,
+    ) -> Result<Vec<Result<SavedData<T, S, A>, S::Error>>>
+    where
+        S: Simulator<Options: Send, Error: Send>,
+        T: Testbench<S> + Schematic<NestedData: HasNestedView<NestedView: Save<S, A>>>,
+        A: SupportedBy<S> + Send,
+        SavedData<T, S, A>: Send,
+    {
+        let sims = sims
+            .into_iter()
+            .map(|sim| {
+                Ok((
+                    self.get_sim_controller(sim.tb, sim.work_dir)?,
+                    sim.options,
+                    sim.analysis,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let _guard = span!(Level::INFO, "simulating batch", simulations = sims.len()).entered();
+        Ok(run_batch(sims, self.simulation_parallelism))
+    }
+
     /// Installs the given [`PrivateInstallation`].
     ///
     /// Only one installation of any given type can exist. Overwrites
@@ -405,6 +578,21 @@ impl Context {
             .unwrap()
     }
 
+    /// Returns a snapshot of the generator memoization statistics recorded by this context.
+    ///
+    /// Reports which blocks were served from the cache and which were regenerated,
+    /// along with the time spent in each generator and the components of each cache key.
+    pub fn generation_stats(&self) -> GenerationStats {
+        self.inner.read().unwrap().stats.snapshot()
+    }
+
+    /// Clears the generator memoization statistics recorded by this context.
+    ///
+    /// Does not clear the generator cache itself.
+    pub fn reset_generation_stats(&self) {
+        self.inner.read().unwrap().stats.reset();
+    }
+
     /// Gets a private installation from the context installation map.
     pub fn get_private_installation<I: PrivateInstallation>(&self) -> Option<Arc<I>> {
         retrieve_installation(&self.inner.read().unwrap().private_installations)
@@ -417,12 +605,21 @@ impl Context {
 
     /// Generates a layout for `block` in the background.
     ///
+    /// Generation is scheduled on a thread pool shared by all layouts in this context.
     /// Returns a handle to the cell being generated.
     pub fn generate_layout<T: Layout>(&self, block: T) -> LayoutCellHandle<T> {
         let context_clone = self.clone();
+        let scheduler = self.scheduler.clone();
         let mut inner_mut = self.inner.write().unwrap();
-        let id = inner_mut.layout.get_id();
+        let id = if self.reproducible {
+            LayoutCellId::from_stable_hash(inner_mut.layout.stable_ids.id(&block.name(), &block))
+        } else {
+            inner_mut.layout.get_id()
+        };
         let block = Arc::new(block);
+        let stats = inner_mut.stats.clone();
+        let stats_key = GenerationKey::new(View::Layout, block.as_ref());
+        stats.request(&stats_key);
 
         let span = span!(
             Level::INFO,
@@ -433,36 +630,63 @@ impl Context {
 
         LayoutCellHandle {
             block: block.clone(),
-            cell: inner_mut.layout.cell_cache.generate(block, move |block| {
-                let block_io = block.io();
-                let mut cell_builder = LayoutCellBuilder::new(context_clone);
-                let _guard = span.enter();
-                let (io, data) = block.layout(&mut cell_builder)?;
-                if block_io.kind() != io.kind() || block_io.kind().len() != io.len() {
-                    tracing::event!(
+            cell: inner_mut
+                .layout
+                .cell_cache
+                .generate_blocking(block, move |block| {
+                    let block = block.clone();
+                    scheduler.spawn(move || {
+                        let block_io = block.io();
+                        let mut cell_builder = LayoutCellBuilder::new(context_clone);
+                        let _guard = span.enter();
+                        let (io, data) =
+                            stats.generate(&stats_key, || block.layout(&mut cell_builder))?;
+                        if block_io.kind() != io.kind() || block_io.kind().len() != io.len() {
+                            tracing::event!(
                         Level::ERROR,
                         "layout IO and block IO have different bundle kinds or flattened lengths"
                     );
-                    return Err(LayoutError::IoDefinition.into());
-                }
-                let ports = IndexMap::from_iter(
-                    block
-                        .io()
-                        .kind()
-                        .flat_names(None)
-                        .into_iter()
-                        .zip(io.flatten_vec()),
-                );
-                Ok(LayoutCell::new(
-                    block.clone(),
-                    data,
-                    io,
-                    Arc::new(cell_builder.finish(id, block.name()).with_ports(ports)),
-                ))
-            }),
+                            return Err(LayoutError::IoDefinition.into());
+                        }
+                        let ports = IndexMap::from_iter(
+                            block
+                                .io()
+                                .kind()
+                                .flat_names(None)
+                                .into_iter()
+                                .zip(io.flatten_vec()),
+                        );
+                        Ok(LayoutCell::new(
+                            block.clone(),
+                            data,
+                            io,
+                            Arc::new(
+                                cell_builder
+                                    .finish(id, block.name())
+                                    .with_ports(ports)
+                                    .with_provenance(Provenance::of(&block)),
+                            ),
+                        ))
+                    })
+                })
+                .clone(),
         }
     }
 
+    /// Exports the schematic and layout of a block to a [`Snapshot`] that can be saved to disk
+    /// and loaded in another process.
+    pub fn export_snapshot<T: Schematic + Layout + Clone>(
+        &self,
+        block: T,
+    ) -> Result<Snapshot<<T as Schematic>::Schema, CellLayer<T>>> {
+        let metadata = SnapshotMetadata::new(block.name());
+        let scir = self.export_scir(block.clone())?;
+        let layir = self.export_layir(block)?;
+        Ok(Snapshot::new(metadata)
+            .with_schematic(scir.scir)
+            .with_layout(layir.layir))
+    }
+
     /// Exports the layout of a block to a LayIR library.
     pub fn export_layir<T: Layout>(
         &self,
@@ -496,7 +720,7 @@ impl Context {
         let mut cells: HashMap<layir::CellId, Arc<RawCell<S::Layer>>> = HashMap::new();
         for id in lib.topological_order() {
             let cell = lib.cell(id);
-            let sid = inner.layout.get_id();
+            let sid = inner.layout.alloc_id(self.reproducible, cell.name());
             let mut raw = RawCell::new(sid, cell.name());
             raw.elements
                 .extend(cell.elements().map(|elt| Element::from(elt.clone())));
@@ -525,48 +749,164 @@ impl Context {
     }
 
     /// Writes a layout cell to GDS.
+    ///
+    /// Cells are converted and written one at a time, after the cells they instantiate,
+    /// so the LayIR and GDS data of the whole layout are never held in memory at once.
+    /// `to_gds` is called once per cell, with a LayIR library containing that cell and an
+    /// empty cell for each cell it instantiates. It must preserve cell names.
     pub fn write_layout<B: Layout>(
         &self,
         block: B,
-        to_gds: impl FnOnce(&layir::Library<CellLayer<B>>) -> (layir::Library<GdsLayer>, GdsUnits),
+        to_gds: impl Fn(&layir::Library<CellLayer<B>>) -> (layir::Library<GdsLayer>, GdsUnits),
         path: impl AsRef<Path>,
     ) -> Result<()> {
         let name = block.name();
-        let layir = self.export_layir(block)?;
-        let (layir, units) = to_gds(&layir.layir);
-        let gds = gdsconv::export::export_gds(
-            layir,
-            GdsExportOpts {
-                name,
-                units: Some(units),
-            },
-        );
-        gds.save(path)?;
-        Ok(())
+        let _guard = span!(Level::INFO, "writing layout", block = %name).entered();
+        let handle = self.generate_layout(block);
+        let cell = handle.try_cell()?;
+        self.stream_gds(name, &[cell.raw().as_ref()], to_gds, path)
     }
 
     /// Writes a set of layout cells to GDS.
+    ///
+    /// See [`Context::write_layout`] for how cells are converted and written.
     pub fn write_layout_all<'a, L: Clone + 'a>(
         &self,
         cells: impl IntoIterator<Item = &'a RawCell<L>>,
-        to_gds: impl FnOnce(&layir::Library<L>) -> (layir::Library<GdsLayer>, GdsUnits),
+        to_gds: impl Fn(&layir::Library<L>) -> (layir::Library<GdsLayer>, GdsUnits),
         path: impl AsRef<Path>,
     ) -> Result<()> {
         let name = arcstr::literal!("TOP");
-        let layir = self.export_layir_all(cells)?;
-        let (layir, units) = to_gds(&layir.layir);
-        let gds = gdsconv::export::export_gds(
-            layir,
-            GdsExportOpts {
-                name,
-                units: Some(units),
-            },
-        );
-        gds.save(path)?;
+        let _guard = span!(Level::INFO, "writing layout", block = %name).entered();
+        let cells = cells.into_iter().collect::<Vec<_>>();
+        self.stream_gds(name, &cells, to_gds, path)
+    }
+
+    /// Writes `cells` and all of their subcells to a GDS library named `name`, one cell at a time.
+    fn stream_gds<L: Clone>(
+        &self,
+        name: ArcStr,
+        cells: &[&RawCell<L>],
+        to_gds: impl Fn(&layir::Library<L>) -> (layir::Library<GdsLayer>, GdsUnits),
+        path: impl AsRef<Path>,
+    ) -> Result<()> {
+        let opts = |units| GdsExportOpts {
+            name: name.clone(),
+            units: Some(units),
+            dates: self.reproducible.then(GdsDateTimes::epoch),
+            property_layer: self.gds_property_layer,
+        };
+        // The GDS header records the units returned by `to_gds`,
+        // so it is written once the first cell has been converted.
+        let mut writer = None;
+        export_layir_cells(cells, |lib, id| -> Result<()> {
+            let (gds, units) = to_gds(lib);
+            let writer = match &mut writer {
+                Some(writer) => writer,
+                None => writer.insert(GdsStreamWriter::create(path.as_ref(), opts(units))?),
+            };
+            writer.write_cell(&gds, gds.cell_id_named(lib.cell(id).name()))?;
+            Ok(())
+        })?;
+        let writer = match writer {
+            Some(writer) => writer,
+            None => {
+                let empty = layir::LibraryBuilder::new()
+                    .build()
+                    .map_err(|_| LayirExportError)?;
+                let (_, units) = to_gds(&empty);
+                GdsStreamWriter::create(path.as_ref(), opts(units))?
+            }
+        };
+        writer.finish()?;
         Ok(())
     }
 }
 
+/// The available parallelism of the host machine.
+fn default_parallelism() -> usize {
+    std::thread::available_parallelism().map_or(1, std::num::NonZeroUsize::get)
+}
+
+/// A 64-bit FNV-1a hasher.
+///
+/// Unlike [`DefaultHasher`](std::collections::hash_map::DefaultHasher), whose algorithm is
+/// unspecified and may change between Rust releases, FNV-1a is fully specified. Hashes are
+/// therefore stable across runs and toolchains, provided that the [`Hash`] implementations
+/// of the hashed values do not change.
+struct StableHasher(u64);
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+}
+
+impl Hasher for StableHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(0x100000001b3);
+        }
+    }
+}
+
+/// Hashes `value` with a [`StableHasher`] seeded by `seed`.
+fn stable_hash<T: Hash + ?Sized>(seed: u64, value: &T) -> u64 {
+    let mut hasher = StableHasher::default();
+    hasher.write_u64(seed);
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Cell IDs derived from stable hashes, for use in [reproducible](ContextBuilder::reproducible)
+/// contexts.
+///
+/// IDs are derived from a hash of the key for which they are allocated along with the name of
+/// the cell, so that keys of different types rarely collide. If the ID derived from a key is
+/// already taken by a different key, subsequent IDs are probed in order.
+#[derive(Debug, Default)]
+pub(crate) struct StableIds {
+    /// Maps each allocated ID to the type and a second, independent hash of the key for which it
+    /// was allocated, or to [`None`] if it was allocated by [`StableIds::fresh`].
+    assigned: HashMap<u64, Option<(TypeId, u64)>>,
+}
+
+impl StableIds {
+    /// Returns the ID of the cell named `name` generated for `key`,
+    /// allocating a new ID if none has been allocated for `key`.
+    pub(crate) fn id<K: Hash + Any>(&mut self, name: &str, key: &K) -> u64 {
+        let check = Some((TypeId::of::<K>(), stable_hash(1, &(name, key))));
+        let mut id = stable_hash(0, &(name, key));
+        loop {
+            match self.assigned.entry(id) {
+                Entry::Vacant(entry) => {
+                    entry.insert(check);
+                    return id;
+                }
+                Entry::Occupied(entry) if *entry.get() == check => return id,
+                Entry::Occupied(_) => id = id.wrapping_add(1),
+            }
+        }
+    }
+
+    /// Allocates a new ID for a cell named `name`.
+    ///
+    /// Cells with the same name are assigned distinct IDs in the order in which they are
+    /// allocated.
+    pub(crate) fn fresh(&mut self, name: &str) -> u64 {
+        let mut id = stable_hash(0, name);
+        while self.assigned.contains_key(&id) {
+            id = id.wrapping_add(1);
+        }
+        self.assigned.insert(id, None);
+        id
+    }
+}
+
 fn retrieve_installation<I: Any + Send + Sync>(
     map: &HashMap<TypeId, Arc<dyn Any + Send + Sync>>,
 ) -> Option<Arc<I>> {
@@ -584,7 +924,7 @@ pub fn prepare_cell_builder<T: Schematic>(
     context: Context,
     block: &T,
 ) -> (CellBuilder<T::Schema>, IoNodeBundle<T>) {
-    let id = id.unwrap_or_else(|| context.alloc_cell_id());
+    let id = id.unwrap_or_else(|| context.alloc_cell_id(&block.name()));
     let mut node_ctx = NodeContext::new();
     // outward-facing IO (to other enclosing blocks)
     let io_outward = block.io();
@@ -619,7 +959,15 @@ pub fn prepare_cell_builder<T: Schematic>(
             node_names,
             fatal_error: false,
             ports,
+            rails: HashMap::new(),
+            global_rails: HashMap::new(),
+            supplies: Vec::new(),
+            overrides: Overrides::new(),
             flatten: false,
+            preserve: false,
+            provenance: Provenance::of(block),
+            block: std::any::type_name::<T>().into(),
+            params: IndexMap::new(),
             contents: RawCellContentsBuilder::Cell(RawCellInnerBuilder::default()),
         },
         io_data,
@@ -1,6 +1,6 @@
 // FIXME: unify crate with diagnostics crate?
 
-use std::{borrow::Cow, panic::Location};
+use std::{borrow::Cow, fmt::Display, panic::Location};
 
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub struct SourceInfo {
@@ -28,3 +28,15 @@ impl SourceInfo {
         }
     }
 }
+
+impl From<&SourceInfo> for scir::SourceInfo {
+    fn from(value: &SourceInfo) -> Self {
+        scir::SourceInfo::new(value.file.as_ref(), value.line, value.column)
+    }
+}
+
+impl Display for SourceInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
@@ -3,11 +3,16 @@
 use std::process::Command;
 use std::sync::Arc;
 
+use arcstr::ArcStr;
 use gds::GdsError;
 
+use crate::block::registry::RegistryError;
 use crate::layout::conv::LayirExportError;
 use crate::layout::error::{GdsImportError, LayoutError};
 use crate::schematic::conv::ConvError;
+use crate::schematic::primitives::PrimitiveError;
+use crate::snapshot::SnapshotError;
+use crate::types::SupplyKind;
 
 /// A result type returning Substrate errors.
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -27,6 +32,36 @@ pub enum Error {
     /// An error indicating that one or more fatal errors occured while building a cell.
     #[error("fatal errors occured while building cell")]
     CellBuildFatal,
+    /// An error generating a cell instantiated within another cell.
+    ///
+    /// When instantiations are nested, the [source](std::error::Error::source) of this error
+    /// is the error of the failing child, forming a trace from the parent cell down to
+    /// the generator that failed.
+    #[error("error generating instance `{instance}` of `{cell}` in cell `{parent}` (instantiated at {location})")]
+    Instantiation {
+        /// The name of the cell containing the instance.
+        parent: ArcStr,
+        /// The name of the instance.
+        instance: ArcStr,
+        /// The name of the instantiated cell.
+        cell: ArcStr,
+        /// The source location at which the cell was instantiated.
+        location: ArcStr,
+        /// The error encountered while generating the instantiated cell.
+        #[source]
+        source: Arc<Error>,
+    },
+    /// Supplies declared in a cell are not connected to any other node.
+    #[error(
+        "cell `{cell}` has unconnected supplies: {}",
+        .supplies.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ")
+    )]
+    UnconnectedSupplies {
+        /// The name of the cell containing the supplies.
+        cell: ArcStr,
+        /// The unconnected supplies.
+        supplies: Vec<UnconnectedSupply>,
+    },
     /// An error thrown by caching functions.
     #[error(transparent)]
     CacheError(#[from] Arc<cache::error::Error>),
@@ -54,9 +89,56 @@ pub enum Error {
     /// An error indicating that the schema does not support an instantiated primitive.
     #[error("schema does not support primitive")]
     UnsupportedPrimitive,
+    /// A primitive does not match its specification in the installed
+    /// [`PrimitiveLibrary`](crate::schematic::primitives::PrimitiveLibrary).
+    #[error("invalid primitive in cell `{cell}`: {source}")]
+    InvalidPrimitive {
+        /// The name of the cell containing the primitive.
+        cell: ArcStr,
+        /// The reason the primitive is invalid.
+        #[source]
+        source: PrimitiveError,
+    },
+    /// A pass-through parameter was assigned to an instance of a cell that does not declare it.
+    #[error("cell `{cell}` does not declare pass-through parameter `{param}`")]
+    UndeclaredParam {
+        /// The name of the instantiated cell.
+        cell: ArcStr,
+        /// The name of the undeclared parameter.
+        param: ArcStr,
+    },
+    /// A cell was generated with an [override](crate::schematic::overrides::Overrides) that
+    /// does not refer to any named instance in the cell.
+    #[error("override of `{path}` in cell `{cell}` does not match any named instance")]
+    UnmatchedOverride {
+        /// The name of the cell.
+        cell: ArcStr,
+        /// The unmatched path, relative to the cell.
+        path: ArcStr,
+    },
     /// Indicates an error exporting a layout cell to LayIR.
     #[error("error exporting to LayIR")]
     LayirExport(#[from] LayirExportError),
+    /// An error constructing or generating a block from a [`Registry`](crate::block::registry::Registry).
+    #[error("registry error: {0}")]
+    Registry(#[from] RegistryError),
+    /// An error saving or loading a design snapshot.
+    #[error("snapshot error: {0}")]
+    Snapshot(#[from] SnapshotError),
+}
+
+/// A supply that is not connected to any other node.
+///
+/// See [`Error::UnconnectedSupplies`].
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("{kind} supply `{node}` (declared at {location})")]
+pub struct UnconnectedSupply {
+    /// The name of the unconnected supply node.
+    pub node: ArcStr,
+    /// The kind of the supply.
+    pub kind: SupplyKind,
+    /// The source location at which the supply was declared.
+    pub location: ArcStr,
 }
 
 impl From<std::io::Error> for Error {
@@ -2,16 +2,22 @@
 
 use std::collections::HashMap;
 
+use arcstr::ArcStr;
+use geometry::bbox::Bbox;
+use geometry::transform::Transformation;
 use layir::Cell;
 use layir::Direction;
 use layir::LibraryBuilder;
 use layir::Port;
+use layir::Text;
 
 use super::element::Element;
 use super::element::RawCell;
 
 use super::element::CellId as SubCellId;
 use layir::CellId as LayCellId;
+use tracing::{span, Level};
+use uniquify::Names;
 
 /// Metadata associated with a conversion from a Substrate schematic to a LayIR library.
 ///
@@ -86,13 +92,38 @@ impl<L: Clone> RawCell<L> {
             return Ok(*conv);
         }
 
-        let mut cell = Cell::new(self.name.clone());
+        for elt in self.elements() {
+            if let Element::Instance(inst) = elt {
+                inst.raw_cell().to_layir_cell(lib_ctx)?;
+            }
+        }
+        let cell =
+            self.to_layir_cell_only(self.name.clone(), |child| lib_ctx.conv.cells[&child.id]);
+        let id = lib_ctx.lib.add_cell(cell);
+        lib_ctx.conv.cells.insert(self.id, id);
+        Ok(id)
+    }
+
+    /// Converts this [`RawCell`] to a LayIR cell named `name`, without converting its subcells.
+    ///
+    /// `child` returns the LayIR ID of each instantiated cell.
+    fn to_layir_cell_only(
+        &self,
+        name: ArcStr,
+        mut child: impl FnMut(&RawCell<L>) -> LayCellId,
+    ) -> Cell<L> {
+        let _guard = span!(Level::DEBUG, "exporting layout cell", cell.name = %self.name).entered();
+        let mut cell = Cell::new(name);
+        if let Some(provenance) = &self.provenance {
+            for (key, value) in provenance.entries() {
+                cell.set_property(key, value);
+            }
+        }
         for elt in self.elements() {
             match elt {
                 Element::Instance(inst) => {
-                    let child = inst.raw_cell().to_layir_cell(lib_ctx)?;
                     let inst = layir::Instance::with_transformation(
-                        child,
+                        child(inst.raw_cell()),
                         inst.raw_cell().name.clone(),
                         inst.trans,
                     );
@@ -107,20 +138,32 @@ impl<L: Clone> RawCell<L> {
             }
         }
         for (name, port) in self.ports() {
+            let name = arcstr::format!("{}", name);
             // TODO: use correct port directions
             let mut lport = Port::new(Direction::InOut);
-            lport.add_element(port.primary.clone());
-            for shape in port.unnamed_shapes.iter() {
+            for shape in port.shapes() {
                 lport.add_element(shape.clone());
             }
-            for (_, shape) in port.named_shapes.iter() {
-                lport.add_element(shape.clone());
+            for label in port.labels() {
+                lport.add_element(label.clone());
+            }
+            // Without explicit labels, label each port shape so that extraction tools
+            // can recognize the pin.
+            if port.labels.is_empty() {
+                for shape in port.shapes() {
+                    if let Some(rect) = shape.bbox() {
+                        let center = rect.center();
+                        lport.add_element(Text::with_transformation(
+                            shape.layer().clone(),
+                            name.clone(),
+                            Transformation::translate(center.x, center.y),
+                        ));
+                    }
+                }
             }
-            cell.add_port(arcstr::format!("{}", name), lport);
+            cell.add_port(name, lport);
         }
-        let id = lib_ctx.lib.add_cell(cell);
-        lib_ctx.conv.cells.insert(self.id, id);
-        Ok(id)
+        cell
     }
 }
 
@@ -143,3 +186,61 @@ pub(crate) fn export_multi_top_layir_lib<L: Clone>(
         conv: lib_ctx.conv,
     })
 }
+
+/// Exports a collection of cells and all their subcells to LayIR one cell at a time.
+///
+/// Produces the same cells as [`export_multi_top_layir_lib`], but never builds the full library.
+/// Instead, each cell is passed to `export` after all of the cells it instantiates, as the
+/// last cell of a LayIR library that otherwise only contains empty cells with the names of
+/// the cells it instantiates. Each library is dropped once `export` returns.
+pub(crate) fn export_layir_cells<L: Clone, E>(
+    cells: &[&RawCell<L>],
+    mut export: impl FnMut(&layir::Library<L>, LayCellId) -> Result<(), E>,
+) -> Result<(), E>
+where
+    E: From<LayirExportError>,
+{
+    let mut names = Names::new();
+    for &cell in cells {
+        cell.export_layir_cells(&mut names, &mut export)?;
+    }
+    Ok(())
+}
+
+impl<L: Clone> RawCell<L> {
+    /// Exports this cell and all of its subcells that are not yet named in `names`.
+    ///
+    /// See [`export_layir_cells`].
+    fn export_layir_cells<E>(
+        &self,
+        names: &mut Names<SubCellId>,
+        export: &mut impl FnMut(&layir::Library<L>, LayCellId) -> Result<(), E>,
+    ) -> Result<(), E>
+    where
+        E: From<LayirExportError>,
+    {
+        if names.name(&self.id).is_some() {
+            return Ok(());
+        }
+        for elt in self.elements() {
+            if let Element::Instance(inst) = elt {
+                inst.raw_cell().export_layir_cells(names, export)?;
+            }
+        }
+
+        let mut lib = LibraryBuilder::new();
+        let mut children = HashMap::new();
+        for elt in self.elements() {
+            if let Element::Instance(inst) = elt {
+                let child = inst.raw_cell().id;
+                children
+                    .entry(child)
+                    .or_insert_with(|| lib.add_cell(Cell::new(names.name(&child).unwrap())));
+            }
+        }
+        let name = names.assign_name(self.id, &self.name);
+        let cell = self.to_layir_cell_only(name, |child| children[&child.id]);
+        let id = lib.add_cell(cell);
+        export(&lib.build().map_err(|_| LayirExportError)?, id)
+    }
+}
@@ -17,6 +17,7 @@ use indexmap::IndexMap;
 use layir::{LayerBbox, Shape, Text};
 use serde::{Deserialize, Serialize};
 
+use crate::block::Provenance;
 use crate::types::layout::PortGeometry;
 use crate::{
     error::{Error, Result},
@@ -35,6 +36,10 @@ impl CellId {
     pub(crate) fn increment(&mut self) {
         *self = CellId(self.0 + 1)
     }
+
+    pub(crate) fn from_stable_hash(hash: u64) -> Self {
+        CellId(hash)
+    }
 }
 
 /// A mapping from names to ports.
@@ -48,6 +53,8 @@ pub struct RawCell<L> {
     pub(crate) elements: Vec<Element<L>>,
     ports: NamedPorts<L>,
     port_names: HashMap<String, NameBuf>,
+    /// The provenance of the block that generated this cell, if it is versioned.
+    pub(crate) provenance: Option<Provenance>,
 }
 
 impl<L> RawCell<L> {
@@ -58,6 +65,7 @@ impl<L> RawCell<L> {
             elements: Vec::new(),
             ports: IndexMap::new(),
             port_names: HashMap::new(),
+            provenance: None,
         }
     }
 
@@ -70,18 +78,22 @@ impl<L> RawCell<L> {
         }
     }
 
+    pub(crate) fn with_provenance(self, provenance: Option<Provenance>) -> Self {
+        Self { provenance, ..self }
+    }
+
     #[doc(hidden)]
     pub fn port_map(&self) -> &NamedPorts<L> {
         &self.ports
     }
 
-    #[allow(dead_code)]
-    pub(crate) fn add_element(&mut self, elem: impl Into<Element<L>>) {
+    /// Adds an element to this cell.
+    pub fn add_element(&mut self, elem: impl Into<Element<L>>) {
         self.elements.push(elem.into());
     }
 
-    #[allow(dead_code)]
-    pub(crate) fn add_elements(&mut self, elems: impl IntoIterator<Item = impl Into<Element<L>>>) {
+    /// Adds elements to this cell.
+    pub fn add_elements(&mut self, elems: impl IntoIterator<Item = impl Into<Element<L>>>) {
         self.elements.extend(elems.into_iter().map(|x| x.into()));
     }
 
@@ -95,6 +107,11 @@ impl<L> RawCell<L> {
         self.elements.iter()
     }
 
+    /// Returns an iterator over mutable references to the elements of this cell.
+    pub fn elements_mut(&mut self) -> impl Iterator<Item = &mut Element<L>> {
+        self.elements.iter_mut()
+    }
+
     /// Returns an iterator over the ports of this cell, as `(name, geometry)` pairs.
     pub fn ports(&self) -> impl Iterator<Item = (&NameBuf, &PortGeometry<L>)> {
         self.ports.iter()
@@ -191,6 +208,17 @@ impl<L: Clone> RawInstance<L> {
     pub fn cell(&self) -> RawCell<L> {
         self.cell.transform_ref(self.trans)
     }
+
+    /// Returns a mutable reference to the child cell.
+    ///
+    /// If the child cell is shared with other instances, it is cloned first. The clone keeps
+    /// the ID of the original cell, so exporting both would only export one of them.
+    /// Uniquify the hierarchy using [`uniquify`](super::hierarchy::uniquify) before modifying
+    /// instances differently.
+    #[inline]
+    pub fn raw_cell_mut(&mut self) -> &mut RawCell<L> {
+        Arc::make_mut(&mut self.cell)
+    }
 }
 
 impl<L> Bbox for RawInstance<L> {
@@ -18,6 +18,15 @@ pub enum LayoutError {
     /// A port had no geometry.
     #[error("a port had no geometry")]
     EmptyPort,
+    /// A cell had no geometry.
+    #[error("a cell had no geometry")]
+    EmptyCell,
+    /// No wire width was available for a layer.
+    #[error("no wire width specified for layer {0}")]
+    MissingWireWidth(ArcStr),
+    /// No via rule connects a pair of layers.
+    #[error("no via rule connects layers {0} and {1}")]
+    MissingViaRule(ArcStr, ArcStr),
 }
 
 impl From<GdsExportError> for LayoutError {
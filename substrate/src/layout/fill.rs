@@ -0,0 +1,407 @@
+//! Density fill generation.
+//!
+//! Foundries require the density of shapes on many layers to lie within a given range over
+//! every window of a fixed size. [`generate_fill`] computes the density of each window of a
+//! finished layout cell and inserts fill shapes where the density is too low.
+//!
+//! Fill shapes are drawn on a dedicated fill layer for each filled layer (typically a separate
+//! GDS datatype), so that they can be distinguished from signal routing by downstream tools.
+//! The returned shapes can be added to a copy of the cell using [`RawCell::add_elements`].
+
+use std::collections::{HashMap, HashSet};
+
+use geometry::bbox::Bbox;
+use geometry::rect::Rect;
+use geometry::region::Region;
+use layir::Shape;
+
+use super::element::RawCell;
+
+/// Fill parameters for a single layer.
+///
+/// All dimensions are in layout database units.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FillLayer<L> {
+    /// The layer whose density is checked.
+    pub layer: L,
+    /// The layer on which fill shapes are drawn.
+    ///
+    /// Shapes on this layer count towards the density of [`FillLayer::layer`].
+    pub fill_layer: L,
+    /// The minimum density of each window, as a fraction between 0 and 1.
+    pub min_density: f64,
+    /// The width and height of each (square) fill shape.
+    pub size: i64,
+    /// The minimum spacing between fill shapes.
+    pub spacing: i64,
+    /// The minimum spacing between fill shapes and existing shapes on [`FillLayer::layer`].
+    pub keepout: i64,
+}
+
+/// Configuration for [`generate_fill`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct FillConfig<L> {
+    window: i64,
+    step: i64,
+    layers: Vec<FillLayer<L>>,
+    keepouts: Vec<Rect>,
+}
+
+impl<L> FillConfig<L> {
+    /// Creates a configuration that checks square windows of width `window`,
+    /// placed every `step` units in each direction.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `window` or `step` is not positive.
+    pub fn new(window: i64, step: i64) -> Self {
+        assert!(
+            window > 0 && step > 0,
+            "density window and step must be positive"
+        );
+        Self {
+            window,
+            step,
+            layers: Vec::new(),
+            keepouts: Vec::new(),
+        }
+    }
+
+    /// Adds a layer to fill.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the fill size is not positive or if the spacing or keepout is negative.
+    pub fn add_layer(&mut self, layer: FillLayer<L>) -> &mut Self {
+        assert!(layer.size > 0, "fill size must be positive");
+        assert!(
+            layer.spacing >= 0 && layer.keepout >= 0,
+            "fill spacing and keepout must be non-negative"
+        );
+        self.layers.push(layer);
+        self
+    }
+
+    /// Adds a region in which no fill is inserted on any layer.
+    pub fn add_keepout(&mut self, rect: Rect) -> &mut Self {
+        self.keepouts.push(rect);
+        self
+    }
+}
+
+/// Returns the fill shapes needed for every window of `cell` to meet
+/// the minimum density of each layer in `config`.
+///
+/// Windows cover the bounding box of `cell`; windows at the upper and right edges are
+/// clipped to the bounding box. Instances are flattened, and polygons are approximated by
+/// their bounding boxes. Fill is placed on a grid aligned to the lower left corner of the
+/// bounding box, and windows whose density cannot be raised to the minimum are filled
+/// as much as possible.
+pub fn generate_fill<L: Clone + PartialEq>(
+    cell: &RawCell<L>,
+    config: &FillConfig<L>,
+) -> Vec<Shape<L>> {
+    let Some(bbox) = cell.bbox() else {
+        return Vec::new();
+    };
+    let rects = flat_rects(cell);
+    let windows = windows(bbox, config.window, config.step);
+
+    let mut fill = Vec::new();
+    for layer in config.layers.iter() {
+        let on_layer = |l: &L| {
+            Region::from_rects(
+                rects
+                    .iter()
+                    .filter(|(other, _)| other == l)
+                    .map(|(_, rect)| *rect),
+            )
+        };
+        let (signal, existing) = (on_layer(&layer.layer), on_layer(&layer.fill_layer));
+        let blocked = signal
+            .grow(layer.keepout)
+            .union(&existing.grow(layer.spacing))
+            .union(&Region::from_rects(config.keepouts.iter().copied()));
+        let blocked = RectIndex::new(config.window, blocked.into_rects());
+        let mut filled = RectIndex::new(config.window, signal.union(&existing).into_rects());
+
+        let grid = FillGrid::new(bbox, layer.size, layer.spacing);
+        let mut used = HashSet::new();
+        for window in windows.iter() {
+            let target = (layer.min_density * window.area() as f64).ceil() as i64;
+            let mut area = filled.covered_area(*window);
+            for site in grid.sites_within(*window) {
+                if area >= target {
+                    break;
+                }
+                let rect = grid.rect(site);
+                if used.contains(&site) || blocked.overlaps(rect) {
+                    continue;
+                }
+                used.insert(site);
+                area += rect.area();
+                filled.insert(rect);
+                fill.push(Shape::new(layer.fill_layer.clone(), rect));
+            }
+        }
+    }
+    fill
+}
+
+/// Returns the fraction of `window` covered by shapes on any of the given layers in `cell`.
+///
+/// Instances are flattened, and polygons are approximated by their bounding boxes.
+pub fn density<L: Clone + PartialEq>(cell: &RawCell<L>, layers: &[L], window: Rect) -> f64 {
+    let region = Region::from_rects(
+        flat_rects(cell)
+            .into_iter()
+            .filter(|(layer, _)| layers.contains(layer))
+            .map(|(_, rect)| rect),
+    );
+    region.intersection(&Region::from(window)).area() as f64 / window.area() as f64
+}
+
+/// Returns the layer and bounding box of every shape in the flattened `cell`.
+fn flat_rects<L: Clone>(cell: &RawCell<L>) -> Vec<(L, Rect)> {
+    let flat = cell.flatten();
+    flat.elements()
+        .filter_map(|elem| elem.as_ref().shape())
+        .chain(flat.ports().flat_map(|(_, port)| port.shapes()))
+        .filter_map(|shape| Some((shape.layer().clone(), shape.bbox()?)))
+        .collect()
+}
+
+/// Returns the density windows covering `bbox`.
+fn windows(bbox: Rect, window: i64, step: i64) -> Vec<Rect> {
+    let starts = |lo: i64, hi: i64| {
+        let n = (std::cmp::max(hi - lo - window, 0) + step - 1) / step + 1;
+        (0..n).map(move |i| lo + i * step)
+    };
+    starts(bbox.left(), bbox.right())
+        .flat_map(|x| {
+            starts(bbox.bot(), bbox.top()).filter_map(move |y| {
+                Rect::from_sides(x, y, x + window, y + window).intersection(bbox)
+            })
+        })
+        .collect()
+}
+
+/// The grid of candidate fill shapes within a bounding box.
+struct FillGrid {
+    origin: (i64, i64),
+    size: i64,
+    pitch: i64,
+    nx: i64,
+    ny: i64,
+}
+
+impl FillGrid {
+    fn new(bbox: Rect, size: i64, spacing: i64) -> Self {
+        let pitch = size + spacing;
+        Self {
+            origin: (bbox.left(), bbox.bot()),
+            size,
+            pitch,
+            nx: (bbox.width() + spacing) / pitch,
+            ny: (bbox.height() + spacing) / pitch,
+        }
+    }
+
+    /// Returns the fill shape at grid site `(i, j)`.
+    fn rect(&self, (i, j): (i64, i64)) -> Rect {
+        let (x, y) = (
+            self.origin.0 + i * self.pitch,
+            self.origin.1 + j * self.pitch,
+        );
+        Rect::from_sides(x, y, x + self.size, y + self.size)
+    }
+
+    /// Returns the grid sites whose fill shapes lie entirely within `window`,
+    /// in column-major order.
+    fn sites_within(&self, window: Rect) -> impl Iterator<Item = (i64, i64)> {
+        let range = |lo: i64, hi: i64, origin: i64, n: i64| {
+            let first = (lo - origin + self.pitch - 1).div_euclid(self.pitch).max(0);
+            let last = (hi - self.size - origin).div_euclid(self.pitch).min(n - 1);
+            first..last + 1
+        };
+        let xs = range(window.left(), window.right(), self.origin.0, self.nx);
+        let ys = range(window.bot(), window.top(), self.origin.1, self.ny);
+        xs.flat_map(move |i| ys.clone().map(move |j| (i, j)))
+    }
+}
+
+/// A spatial index of rectangles, binned on a uniform grid.
+///
+/// Queries only visit the rectangles in bins overlapping the query rectangle,
+/// so each window of a large cell does not scan every shape in the cell.
+struct RectIndex {
+    bin: i64,
+    rects: Vec<Rect>,
+    bins: HashMap<(i64, i64), Vec<usize>>,
+}
+
+impl RectIndex {
+    /// Creates an index of `rects` with square bins of width `bin`.
+    fn new(bin: i64, rects: impl IntoIterator<Item = Rect>) -> Self {
+        let mut index = Self {
+            bin,
+            rects: Vec::new(),
+            bins: HashMap::new(),
+        };
+        for rect in rects {
+            index.insert(rect);
+        }
+        index
+    }
+
+    /// Returns the bins overlapping `rect`.
+    fn bins_of(&self, rect: Rect) -> impl Iterator<Item = (i64, i64)> {
+        let (x0, x1) = (
+            rect.left().div_euclid(self.bin),
+            rect.right().div_euclid(self.bin),
+        );
+        let (y0, y1) = (
+            rect.bot().div_euclid(self.bin),
+            rect.top().div_euclid(self.bin),
+        );
+        (x0..=x1).flat_map(move |x| (y0..=y1).map(move |y| (x, y)))
+    }
+
+    fn insert(&mut self, rect: Rect) {
+        let id = self.rects.len();
+        self.rects.push(rect);
+        for bin in self.bins_of(rect) {
+            self.bins.entry(bin).or_default().push(id);
+        }
+    }
+
+    /// Returns the indexed rectangles in bins overlapping `rect`, without duplicates.
+    fn query(&self, rect: Rect) -> impl Iterator<Item = Rect> + '_ {
+        let mut ids = self
+            .bins_of(rect)
+            .filter_map(|bin| self.bins.get(&bin))
+            .flatten()
+            .copied()
+            .collect::<Vec<_>>();
+        ids.sort_unstable();
+        ids.dedup();
+        ids.into_iter().map(|id| self.rects[id])
+    }
+
+    /// Returns `true` if the interior of `rect` overlaps any indexed rectangle.
+    fn overlaps(&self, rect: Rect) -> bool {
+        self.query(rect).any(|other| overlaps(rect, other))
+    }
+
+    /// Returns the area of the union of the indexed rectangles within `clip`.
+    fn covered_area(&self, clip: Rect) -> i64 {
+        Region::from_rects(
+            self.query(clip)
+                .filter_map(|rect| rect.intersection(clip))
+                .filter(|rect| rect.area() > 0),
+        )
+        .area()
+    }
+}
+
+/// Returns `true` if the interiors of `a` and `b` overlap.
+fn overlaps(a: Rect, b: Rect) -> bool {
+    a.left() < b.right() && b.left() < a.right() && a.bot() < b.top() && b.bot() < a.top()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::element::CellId;
+
+    #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+    enum Layer {
+        Met1,
+        Met1Fill,
+        Boundary,
+    }
+
+    #[test]
+    fn rect_index_counts_overlaps_once() {
+        let index = RectIndex::new(
+            40,
+            [
+                Rect::from_sides(0, 0, 100, 100),
+                Rect::from_sides(50, 50, 150, 150),
+                Rect::from_sides(0, 0, 100, 100),
+                Rect::from_sides(-300, -300, -200, -200),
+            ],
+        );
+        assert_eq!(index.covered_area(Rect::from_sides(0, 0, 200, 200)), 17_500);
+        assert_eq!(index.covered_area(Rect::from_sides(0, 0, 50, 50)), 2_500);
+        assert!(index.overlaps(Rect::from_sides(-250, -250, -240, -240)));
+        assert!(!index.overlaps(Rect::from_sides(150, 0, 200, 50)));
+        assert_eq!(index.query(Rect::from_sides(120, 120, 130, 130)).count(), 1);
+    }
+
+    #[test]
+    fn fill_grid_sites_lie_within_window() {
+        let grid = FillGrid::new(Rect::from_sides(0, 0, 1000, 1000), 50, 50);
+        let window = Rect::from_sides(120, 0, 500, 300);
+        let sites = grid.sites_within(window).collect::<Vec<_>>();
+        assert_eq!(sites.len(), 3 * 3);
+        for site in sites {
+            let rect = grid.rect(site);
+            assert_eq!(rect.intersection(window), Some(rect));
+        }
+    }
+
+    #[test]
+    fn fill_meets_min_density() {
+        let mut cell = RawCell::new(CellId::default(), "top");
+        cell.add_element(Shape::new(
+            Layer::Boundary,
+            Rect::from_sides(0, 0, 1000, 1000),
+        ));
+        let signal = Rect::from_sides(0, 0, 100, 100);
+        cell.add_element(Shape::new(Layer::Met1, signal));
+        let keepout = Rect::from_sides(500, 500, 1000, 1000);
+
+        let mut config = FillConfig::new(500, 500);
+        config
+            .add_layer(FillLayer {
+                layer: Layer::Met1,
+                fill_layer: Layer::Met1Fill,
+                min_density: 0.2,
+                size: 50,
+                spacing: 50,
+                keepout: 20,
+            })
+            .add_keepout(keepout);
+        let fill = generate_fill(&cell, &config);
+
+        for shape in fill.iter() {
+            assert_eq!(*shape.layer(), Layer::Met1Fill);
+            let rect = shape.bbox().unwrap();
+            assert!(!overlaps(rect, signal.expand_all(20)));
+            assert!(!overlaps(rect, keepout));
+        }
+        for (i, a) in fill.iter().enumerate() {
+            for b in fill.iter().skip(i + 1) {
+                assert!(!overlaps(
+                    a.bbox().unwrap().expand_all(50),
+                    b.bbox().unwrap()
+                ));
+            }
+        }
+
+        cell.add_elements(fill);
+        let layers = [Layer::Met1, Layer::Met1Fill];
+        for window in [
+            Rect::from_sides(0, 0, 500, 500),
+            Rect::from_sides(500, 0, 1000, 500),
+            Rect::from_sides(0, 500, 500, 1000),
+        ] {
+            assert!(density(&cell, &layers, window) >= 0.2);
+        }
+        assert_eq!(
+            density(&cell, &layers, Rect::from_sides(500, 500, 1000, 1000)),
+            0.
+        );
+    }
+}
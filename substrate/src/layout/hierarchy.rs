@@ -0,0 +1,101 @@
+//! Transformations of layout cell hierarchies.
+//!
+//! Layout post-processing steps (e.g. fill insertion) may need to modify instances of the
+//! same cell differently, or to operate on a single flat list of shapes. The utilities in this
+//! module flatten and uniquify hierarchies of [`RawCell`]s without regenerating them.
+
+use std::sync::Arc;
+
+use arcstr::ArcStr;
+use geometry::transform::{TransformRef, Transformation};
+use uniquify::Names;
+
+use super::element::{CellId, Element, RawCell, RawInstance};
+use crate::context::Context;
+
+impl<L: Clone> RawCell<L> {
+    /// Returns a copy of this cell with all instances recursively replaced by their contents.
+    ///
+    /// The contents of each instance are transformed into the coordinate system of this cell.
+    /// The port shapes of instantiated cells are kept as regular shapes, while the ports of this
+    /// cell are preserved.
+    ///
+    /// The flattened cell has the same ID and name as this cell, so it should replace this
+    /// cell rather than be exported alongside it. Use [`uniquify`] to obtain a copy with a
+    /// new ID.
+    pub fn flatten(&self) -> Self {
+        let mut elements = Vec::new();
+        flatten_elements(&self.elements, Transformation::identity(), &mut elements);
+        let mut cell = RawCell::new(self.id, self.name.clone()).with_ports(self.port_map().clone());
+        cell.add_elements(elements);
+        cell
+    }
+}
+
+/// Appends the contents of `elements`, transformed by `trans`, to `out` with all instances
+/// recursively flattened.
+fn flatten_elements<L: Clone>(
+    elements: &[Element<L>],
+    trans: Transformation,
+    out: &mut Vec<Element<L>>,
+) {
+    for elem in elements {
+        match elem {
+            Element::Instance(inst) => {
+                let trans = Transformation::cascade(trans, inst.trans);
+                let child = inst.raw_cell();
+                flatten_elements(&child.elements, trans, out);
+                out.extend(
+                    child
+                        .ports()
+                        .flat_map(|(_, port)| port.shapes())
+                        .map(|shape| Element::Shape(shape.transform_ref(trans))),
+                );
+            }
+            elem => out.push(elem.transform_ref(trans)),
+        }
+    }
+}
+
+/// Returns a copy of `cell` in which every instance refers to a distinct copy of its child cell.
+///
+/// Each copy is assigned a new ID from `ctx` and a name derived from the name of the original
+/// cell that is unique within the returned hierarchy. The top cell keeps its name but is also
+/// assigned a new ID, so the result can be exported alongside the original cell.
+///
+/// After uniquification, each instance's cell can be modified without affecting other instances
+/// (see [`RawInstance::raw_cell_mut`]).
+pub fn uniquify<L: Clone>(ctx: &Context, cell: &RawCell<L>) -> RawCell<L> {
+    let mut inner = ctx.inner.write().unwrap();
+    let mut names = Names::new();
+    let id = inner.layout.alloc_id(ctx.reproducible, &cell.name);
+    names.reserve_name(id, cell.name.clone());
+    uniquify_cell(
+        cell,
+        id,
+        cell.name.clone(),
+        &mut |name: &str| inner.layout.alloc_id(ctx.reproducible, name),
+        &mut names,
+    )
+}
+
+fn uniquify_cell<L: Clone>(
+    cell: &RawCell<L>,
+    id: CellId,
+    name: ArcStr,
+    alloc_id: &mut impl FnMut(&str) -> CellId,
+    names: &mut Names<CellId>,
+) -> RawCell<L> {
+    let mut copy = RawCell::new(id, name).with_ports(cell.port_map().clone());
+    copy.add_elements(cell.elements.iter().map(|elem| match elem {
+        Element::Instance(inst) => {
+            let child = inst.raw_cell();
+            let id = alloc_id(&child.name);
+            let name = names.assign_name(id, &child.name);
+            let child = uniquify_cell(child, id, name, alloc_id, names);
+            Element::Instance(RawInstance::new(Arc::new(child), inst.trans))
+        }
+        elem => elem.clone(),
+    }));
+    copy
+}
@@ -0,0 +1,267 @@
+//! Placement helpers for matched devices.
+//!
+//! Analog circuits often require a set of devices to be matched,
+//! such as the two halves of a differential pair or the branches of a current mirror.
+//! Matching is improved by splitting each device into unit cells and arranging
+//! the unit cells in an interdigitated or common-centroid pattern.
+
+use geometry::prelude::{Bbox, Point};
+use geometry::transform::TranslateMut;
+use geometry::union::BoundingUnion;
+
+use crate::error::Result;
+
+use super::error::LayoutError;
+use super::{CellBuilder, Draw, DrawReceiver, Instance, Layout};
+
+/// A placement pattern for a set of matched devices.
+///
+/// Stores a grid of device indices. Rows are ordered from top to bottom
+/// and columns are ordered from left to right.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct MatchingPattern {
+    grid: Vec<Vec<usize>>,
+}
+
+impl MatchingPattern {
+    /// Creates a new [`MatchingPattern`] from a grid of device indices.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the grid is empty or if the rows do not all have the same length.
+    pub fn new(grid: Vec<Vec<usize>>) -> Self {
+        assert!(!grid.is_empty() && !grid[0].is_empty());
+        assert!(grid.iter().all(|row| row.len() == grid[0].len()));
+        Self { grid }
+    }
+
+    /// Interdigitates the unit cells of a set of devices (e.g. `ABAB`).
+    ///
+    /// Device `i` is split into `units[i]` unit cells.
+    /// The unit cells are assigned to positions in row-major order,
+    /// cycling through the devices that have unit cells remaining.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rows` is zero or if the total number of unit cells
+    /// is not a nonzero multiple of `rows`.
+    pub fn interdigitated(units: &[usize], rows: usize) -> Self {
+        let order = round_robin(units.to_vec());
+        Self::from_order(order, rows)
+    }
+
+    /// Arranges the unit cells of a set of devices in a common-centroid pattern (e.g. `ABBA`).
+    ///
+    /// Device `i` is split into `units[i]` unit cells.
+    /// Each unit cell at row `r` and column `c` is paired with a unit cell of the same
+    /// device at row `rows - 1 - r` and column `cols - 1 - c`, so the centroid of every
+    /// device lies at the center of the array.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rows` is zero, if the total number of unit cells
+    /// is not a nonzero multiple of `rows`, or if a common-centroid arrangement
+    /// is not possible. An arrangement is possible if every device has an even number of
+    /// unit cells, except for at most one device when the total number of unit cells is odd.
+    pub fn common_centroid(units: &[usize], rows: usize) -> Self {
+        let total = units.iter().sum::<usize>();
+        let odd = units
+            .iter()
+            .enumerate()
+            .filter(|(_, n)| *n % 2 == 1)
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+        assert!(
+            odd.len() == total % 2,
+            "common-centroid placement requires an even number of unit cells per device"
+        );
+
+        let half = round_robin(units.iter().map(|n| n / 2).collect());
+        let mut order = half.clone();
+        order.extend(odd);
+        order.extend(half.into_iter().rev());
+        Self::from_order(order, rows)
+    }
+
+    fn from_order(order: Vec<usize>, rows: usize) -> Self {
+        assert!(rows > 0, "number of rows must be positive");
+        assert!(
+            !order.is_empty() && order.len() % rows == 0,
+            "number of unit cells must be a nonzero multiple of the number of rows"
+        );
+        let cols = order.len() / rows;
+        Self::new(order.chunks(cols).map(|row| row.to_vec()).collect())
+    }
+
+    /// The number of rows in the pattern.
+    pub fn rows(&self) -> usize {
+        self.grid.len()
+    }
+
+    /// The number of columns in the pattern.
+    pub fn cols(&self) -> usize {
+        self.grid[0].len()
+    }
+
+    /// The index of the device placed at the given row and column.
+    pub fn get(&self, row: usize, col: usize) -> Option<usize> {
+        self.grid.get(row).and_then(|row| row.get(col)).copied()
+    }
+
+    /// Iterates over the positions in the pattern as `(row, col, device)` tuples.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize, usize)> + '_ {
+        self.grid.iter().enumerate().flat_map(|(i, row)| {
+            row.iter()
+                .enumerate()
+                .map(move |(j, device)| (i, j, *device))
+        })
+    }
+
+    /// The centroid of the given device, in units of rows and columns.
+    ///
+    /// Returns `None` if the device does not appear in the pattern.
+    pub fn centroid(&self, device: usize) -> Option<(f64, f64)> {
+        let (mut rsum, mut csum, mut n) = (0, 0, 0);
+        for (i, j, _) in self.iter().filter(|(_, _, d)| *d == device) {
+            rsum += i;
+            csum += j;
+            n += 1;
+        }
+        (n > 0).then(|| (rsum as f64 / n as f64, csum as f64 / n as f64))
+    }
+}
+
+/// Assigns positions to devices by cycling through the devices that have unit cells remaining.
+fn round_robin(mut remaining: Vec<usize>) -> Vec<usize> {
+    let mut order = Vec::with_capacity(remaining.iter().sum());
+    while remaining.iter().any(|n| *n > 0) {
+        for (device, n) in remaining.iter_mut().enumerate() {
+            if *n > 0 {
+                order.push(device);
+                *n -= 1;
+            }
+        }
+    }
+    order
+}
+
+/// A unit cell of a matched device, placed as part of a [`MatchedArray`].
+pub struct MatchedInstance<T: Layout> {
+    /// The index of the device this unit cell belongs to.
+    ///
+    /// Unit cells with the same device index should be connected to the same nets.
+    pub device: usize,
+    /// The row of the unit cell in the [`MatchingPattern`].
+    pub row: usize,
+    /// The column of the unit cell in the [`MatchingPattern`].
+    pub col: usize,
+    /// The placed instance.
+    pub inst: Instance<T>,
+}
+
+/// A set of unit cells placed according to a [`MatchingPattern`].
+pub struct MatchedArray<T: Layout> {
+    instances: Vec<MatchedInstance<T>>,
+}
+
+impl<T: Layout> MatchedArray<T> {
+    /// Places one instance of `unit` at each position of `pattern`.
+    ///
+    /// Unit cells are abutted using the bounding box of `unit`, with the top left
+    /// unit cell placed at the origin.
+    pub fn new(
+        cell: &mut CellBuilder<T::Schema>,
+        unit: T,
+        pattern: &MatchingPattern,
+    ) -> Result<Self> {
+        let inst = cell.generate_blocking(unit)?;
+        let bbox = inst.bbox().ok_or(LayoutError::EmptyCell)?;
+        let instances = pattern
+            .iter()
+            .map(|(row, col, device)| {
+                let mut inst = inst.clone();
+                inst.translate_mut(Point::new(
+                    col as i64 * bbox.width() - bbox.left(),
+                    -(row as i64 + 1) * bbox.height() - bbox.bot(),
+                ));
+                MatchedInstance {
+                    device,
+                    row,
+                    col,
+                    inst,
+                }
+            })
+            .collect();
+        Ok(Self { instances })
+    }
+
+    /// Iterates over all placed unit cells.
+    pub fn instances(&self) -> impl Iterator<Item = &MatchedInstance<T>> {
+        self.instances.iter()
+    }
+
+    /// Iterates over the placed unit cells belonging to the given device.
+    pub fn device(&self, device: usize) -> impl Iterator<Item = &Instance<T>> {
+        self.instances
+            .iter()
+            .filter(move |inst| inst.device == device)
+            .map(|inst| &inst.inst)
+    }
+}
+
+impl<T: Layout> Draw<T::Schema> for MatchedArray<T> {
+    fn draw(self, recv: &mut DrawReceiver<T::Schema>) -> Result<()> {
+        for inst in self.instances {
+            recv.draw(inst.inst)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: Layout> Bbox for MatchedArray<T> {
+    fn bbox(&self) -> Option<geometry::rect::Rect> {
+        self.instances
+            .iter()
+            .fold(None, |acc, inst| acc.bounding_union(&inst.inst.bbox()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interdigitated_pattern() {
+        let pattern = MatchingPattern::interdigitated(&[2, 2], 1);
+        assert_eq!(pattern.grid, vec![vec![0, 1, 0, 1]]);
+
+        let pattern = MatchingPattern::interdigitated(&[4, 2], 2);
+        assert_eq!(pattern.grid, vec![vec![0, 1, 0], vec![1, 0, 0]]);
+    }
+
+    #[test]
+    fn common_centroid_pattern() {
+        let pattern = MatchingPattern::common_centroid(&[2, 2], 1);
+        assert_eq!(pattern.grid, vec![vec![0, 1, 1, 0]]);
+
+        let pattern = MatchingPattern::common_centroid(&[4, 4], 2);
+        assert_eq!(pattern.grid, vec![vec![0, 1, 0, 1], vec![1, 0, 1, 0]]);
+
+        let pattern = MatchingPattern::common_centroid(&[2, 1, 2], 1);
+        assert_eq!(pattern.grid, vec![vec![0, 2, 1, 2, 0]]);
+
+        let center = (
+            (pattern.rows() - 1) as f64 / 2.,
+            (pattern.cols() - 1) as f64 / 2.,
+        );
+        for device in 0..3 {
+            assert_eq!(pattern.centroid(device), Some(center));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn common_centroid_requires_even_units() {
+        MatchingPattern::common_centroid(&[3, 3], 1);
+    }
+}
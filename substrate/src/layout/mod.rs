@@ -2,10 +2,10 @@
 
 use std::fmt::Debug;
 use std::ops::{Deref, DerefMut};
-use std::{marker::PhantomData, sync::Arc, thread};
+use std::{marker::PhantomData, sync::Arc};
 
 use arcstr::ArcStr;
-use cache::{error::TryInnerError, mem::TypeCache, CacheHandle};
+use cache::mem::TypeCache;
 use geometry::prelude::Rect;
 use geometry::transform::{TransformRef, TranslateRef};
 use geometry::{
@@ -14,25 +14,32 @@ use geometry::{
     union::BoundingUnion,
 };
 use layir::LayerBbox;
-use once_cell::sync::OnceCell;
+use once_cell::sync::Lazy;
 use schema::Schema;
 
-use crate::context::Context;
+use crate::context::{Context, StableIds};
 use crate::error::Error;
 use crate::error::Result;
 use crate::types::layout::LayoutBundle;
 use crate::types::{HasBundleKind, IoKind};
 
 use self::element::{CellId, Element, RawCell, RawInstance};
+use self::scheduler::Task;
 
 pub mod conv;
 pub mod element;
 pub mod error;
+pub mod fill;
+pub mod hierarchy;
+pub mod matching;
+pub mod rules;
+pub(crate) mod scheduler;
 pub mod schema;
 #[cfg(test)]
 mod tests;
 pub mod tiling;
 pub mod tracks;
+pub mod wire;
 
 use crate::block::Block;
 
@@ -75,6 +82,8 @@ impl<T: Layout> Layout for Arc<T> {
 #[derive(Debug, Default)]
 pub struct LayoutContext {
     next_id: CellId,
+    /// IDs allocated in reproducible contexts.
+    pub(crate) stable_ids: StableIds,
     pub(crate) cell_cache: TypeCache,
 }
 
@@ -88,6 +97,18 @@ impl LayoutContext {
         self.next_id.increment();
         self.next_id
     }
+
+    /// Allocates a new ID for a cell named `name`.
+    ///
+    /// If `reproducible` is true, the ID is derived from `name` rather than from the number of
+    /// previously allocated IDs.
+    pub(crate) fn alloc_id(&mut self, reproducible: bool, name: &str) -> CellId {
+        if reproducible {
+            CellId::from_stable_hash(self.stable_ids.fresh(name))
+        } else {
+            self.get_id()
+        }
+    }
 }
 
 /// A generic layout cell.
@@ -158,7 +179,7 @@ where
 /// A handle to a schematic cell that is being generated.
 pub struct CellHandle<T: Layout> {
     pub(crate) block: Arc<T>,
-    pub(crate) cell: CacheHandle<Result<Cell<T>>>,
+    pub(crate) cell: Arc<Task<Result<Cell<T>>>>,
 }
 
 impl<T: Layout> Clone for CellHandle<T> {
@@ -175,10 +196,11 @@ impl<T: Layout> CellHandle<T> {
     ///
     /// Blocks until cell generation completes and returns an error if one was thrown during generation.
     pub fn try_cell(&self) -> Result<&Cell<T>> {
-        self.cell.try_inner().map_err(|e| match e {
-            TryInnerError::CacheError(e) => Error::CacheError(e.clone()),
-            TryInnerError::GeneratorError(e) => e.clone(),
-        })
+        match self.cell.get() {
+            Some(Ok(cell)) => Ok(cell),
+            Some(Err(e)) => Err(e.clone()),
+            None => Err(Error::CacheError(Arc::new(cache::error::Error::Panic))),
+        }
     }
 
     /// Returns the underlying [`Cell`].
@@ -518,9 +540,9 @@ impl<S: Schema> CellBuilder<S> {
 
     /// Draw layout object `obj`.
     ///
-    /// For instances, a new thread is spawned to add the instance once the underlying cell has
-    /// been generated. If generation fails, the spawned thread may panic after this function has
-    /// been called.
+    /// For instances, the instance is added once the underlying cell has been generated.
+    /// If generation fails, finishing or querying the bounding box of the enclosing cell
+    /// may panic after this function has been called.
     ///
     /// For error recovery, instance generation results should be checked using [`Instance::try_cell`]
     /// before calling `draw`.
@@ -550,7 +572,9 @@ impl<S: Schema> LayerBbox<S::Layer> for CellBuilder<S> {
     }
 }
 
-type RawInstanceHandle<S> = Arc<OnceCell<Option<RawInstance<<S as Schema>::Layer>>>>;
+type RawInstanceFn<L> = Box<dyn FnOnce() -> Option<RawInstance<L>> + Send>;
+type RawInstanceHandle<S> =
+    Arc<Lazy<Option<RawInstance<<S as Schema>::Layer>>, RawInstanceFn<<S as Schema>::Layer>>>;
 
 /// A receiver for drawing layout objects.
 ///
@@ -608,7 +632,7 @@ impl<S: Schema> DrawReceiver<S> {
     fn get_instances(&self) -> Vec<&RawInstance<S::Layer>> {
         self.instances
             .iter()
-            .map(|instance| instance.wait().as_ref().unwrap())
+            .map(|instance| Lazy::force(instance).as_ref().unwrap())
             .collect()
     }
 
@@ -616,7 +640,7 @@ impl<S: Schema> DrawReceiver<S> {
         for instance in self
             .instances
             .into_iter()
-            .map(|instance| instance.wait().clone().unwrap())
+            .map(|instance| Lazy::force(&instance).clone().unwrap())
         {
             elements.push(instance.transform(self.trans).into());
         }
@@ -645,23 +669,21 @@ impl<S: Schema> DrawReceiver<S> {
 
 impl<S: Schema> DrawReceiver<S> {
     pub(crate) fn draw_instance<I: Layout<Schema = S>>(&mut self, inst: Instance<I>) {
-        let instance = Arc::new(OnceCell::new());
-        self.instances.push(instance.clone());
-
         let cell = inst.cell.clone();
-        thread::spawn(move || {
-            instance.set(cell.try_cell().ok().map(|cell| RawInstance {
+        let trans = inst.trans;
+        self.instances.push(Arc::new(Lazy::new(Box::new(move || {
+            cell.try_cell().ok().map(|cell| RawInstance {
                 cell: cell.raw.clone(),
-                trans: inst.trans,
-            }))
-        });
+                trans,
+            })
+        }))));
     }
 
     /// Draw layout object `obj`.
     ///
-    /// For instances, a new thread is spawned to add the instance once the underlying cell has
-    /// been generated. If generation fails, the spawned thread may panic after this function has
-    /// been called.
+    /// For instances, the instance is added once the underlying cell has been generated.
+    /// If generation fails, finishing or querying the bounding box of the enclosing cell
+    /// may panic after this function has been called.
     ///
     /// For error recovery, instance generation results should be checked using [`Instance::try_cell`]
     /// before calling `draw`.
@@ -799,9 +821,9 @@ impl<S: Schema> Container<S> {
 impl<S: Schema> Container<S> {
     /// Draw layout object `obj`.
     ///
-    /// For instances, a new thread is spawned to add the instance once the underlying cell has
-    /// been generated. If generation fails, the spawned thread may panic after this function has
-    /// been called.
+    /// For instances, the instance is added once the underlying cell has been generated.
+    /// If generation fails, finishing or querying the bounding box of the enclosing cell
+    /// may panic after this function has been called.
     ///
     /// For error recovery, instance generation results should be checked using [`Instance::try_cell`]
     /// before calling `draw`.
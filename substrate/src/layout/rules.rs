@@ -0,0 +1,323 @@
+//! Design rules.
+//!
+//! Layout schemas can expose basic design rules through the [`DesignRules`] trait,
+//! allowing generators to compute legal geometry instead of hard-coding rule values.
+
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+use geometry::prelude::Rect;
+use geometry::span::Span;
+
+use super::schema::Schema;
+
+/// A layout schema that exposes a set of design rules.
+///
+/// Since PDKs are typically installed in the [`Context`](crate::context::Context),
+/// generators can query rules by retrieving the installation:
+///
+/// ```ignore
+/// let pdk = cell.ctx.get_installation::<MyPdk>().unwrap();
+/// let width = pdk.rules().min_width(&MyLayer::Met1);
+/// ```
+pub trait DesignRules: Schema {
+    /// The design rules of this schema.
+    fn rules(&self) -> &RuleDeck<Self::Layer>;
+}
+
+/// The geometry of a via between two layers.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct ViaRule<L> {
+    /// The layer below the via.
+    pub bot: L,
+    /// The layer above the via.
+    pub top: L,
+    /// The width and height of each via cut.
+    pub size: i64,
+    /// The minimum spacing between adjacent via cuts.
+    pub spacing: i64,
+}
+
+/// A collection of basic design rules.
+///
+/// All dimensions are in layout database units.
+#[derive(Debug, Clone)]
+pub struct RuleDeck<L> {
+    min_width: HashMap<L, i64>,
+    min_spacing: HashMap<L, i64>,
+    min_area: HashMap<L, i64>,
+    min_enclosure: HashMap<(L, L), i64>,
+    vias: HashMap<L, ViaRule<L>>,
+}
+
+impl<L> Default for RuleDeck<L> {
+    fn default() -> Self {
+        Self {
+            min_width: HashMap::new(),
+            min_spacing: HashMap::new(),
+            min_area: HashMap::new(),
+            min_enclosure: HashMap::new(),
+            vias: HashMap::new(),
+        }
+    }
+}
+
+impl<L: Clone + Eq + Hash> RuleDeck<L> {
+    /// Creates a new, empty [`RuleDeck`].
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the minimum width of shapes on `layer`.
+    pub fn set_min_width(&mut self, layer: L, width: i64) -> &mut Self {
+        self.min_width.insert(layer, width);
+        self
+    }
+
+    /// Sets the minimum spacing between shapes on `layer`.
+    pub fn set_min_spacing(&mut self, layer: L, spacing: i64) -> &mut Self {
+        self.min_spacing.insert(layer, spacing);
+        self
+    }
+
+    /// Sets the minimum area of shapes on `layer`.
+    pub fn set_min_area(&mut self, layer: L, area: i64) -> &mut Self {
+        self.min_area.insert(layer, area);
+        self
+    }
+
+    /// Sets the minimum enclosure of shapes on `inner` by shapes on `outer`.
+    pub fn set_min_enclosure(&mut self, inner: L, outer: L, enclosure: i64) -> &mut Self {
+        self.min_enclosure.insert((inner, outer), enclosure);
+        self
+    }
+
+    /// Sets the via rule for the via cut layer `cut`.
+    pub fn set_via(&mut self, cut: L, rule: ViaRule<L>) -> &mut Self {
+        self.vias.insert(cut, rule);
+        self
+    }
+
+    /// The minimum width of shapes on `layer`.
+    pub fn min_width(&self, layer: &L) -> Option<i64> {
+        self.min_width.get(layer).copied()
+    }
+
+    /// The minimum spacing between shapes on `layer`.
+    pub fn min_spacing(&self, layer: &L) -> Option<i64> {
+        self.min_spacing.get(layer).copied()
+    }
+
+    /// The minimum area of shapes on `layer`.
+    pub fn min_area(&self, layer: &L) -> Option<i64> {
+        self.min_area.get(layer).copied()
+    }
+
+    /// The minimum enclosure of shapes on `inner` by shapes on `outer`.
+    pub fn min_enclosure(&self, inner: &L, outer: &L) -> Option<i64> {
+        self.min_enclosure
+            .get(&(inner.clone(), outer.clone()))
+            .copied()
+    }
+
+    /// The via rule for the via cut layer `cut`.
+    pub fn via(&self, cut: &L) -> Option<&ViaRule<L>> {
+        self.vias.get(cut)
+    }
+
+    /// Returns the via cut layer and via rule connecting layers `a` and `b`, in either order.
+    pub fn via_between(&self, a: &L, b: &L) -> Option<(&L, &ViaRule<L>)> {
+        self.vias
+            .iter()
+            .find(|(_, via)| (via.bot == *a && via.top == *b) || (via.bot == *b && via.top == *a))
+    }
+
+    /// Returns the via cut layers and via rules connecting layer `from` to layer `to`,
+    /// ordered from `from` to `to`.
+    ///
+    /// If no single via connects the two layers, vias are stacked through intermediate
+    /// layers using as few vias as possible. Returns an empty stack if `from` and `to`
+    /// are the same layer, and `None` if no stack of vias connects them.
+    pub fn via_stack<'a>(&'a self, from: &'a L, to: &'a L) -> Option<Vec<(&'a L, &'a ViaRule<L>)>> {
+        // Maps each layer reached to the layer it was reached from and the via cut used.
+        let mut prev: HashMap<&L, (&L, &L)> = HashMap::new();
+        let mut queue = VecDeque::from([from]);
+        while let Some(layer) = queue.pop_front() {
+            if layer == to {
+                break;
+            }
+            for (cut, via) in self.vias.iter() {
+                let next = if via.bot == *layer {
+                    &via.top
+                } else if via.top == *layer {
+                    &via.bot
+                } else {
+                    continue;
+                };
+                if next != from && !prev.contains_key(next) {
+                    prev.insert(next, (layer, cut));
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        let mut stack = Vec::new();
+        let mut layer = to;
+        while layer != from {
+            let (prev_layer, cut) = prev.get(layer)?;
+            stack.push((*cut, &self.vias[*cut]));
+            layer = prev_layer;
+        }
+        stack.reverse();
+        Some(stack)
+    }
+
+    /// Returns the largest array of via cuts on layer `cut` that fits within `rect`,
+    /// such that the cuts are enclosed by `rect` on both the top and bottom layers.
+    ///
+    /// The array is centered within `rect`.
+    /// Returns `None` if there is no via rule for `cut` or if no cuts fit within `rect`.
+    pub fn via_array(&self, cut: &L, rect: Rect) -> Option<Vec<Rect>> {
+        let via = self.via(cut)?;
+        let enclosure = std::cmp::max(
+            self.min_enclosure(cut, &via.bot).unwrap_or_default(),
+            self.min_enclosure(cut, &via.top).unwrap_or_default(),
+        );
+        let xs = centered_cuts(rect.hspan(), via.size, via.spacing, enclosure)?;
+        let ys = centered_cuts(rect.vspan(), via.size, via.spacing, enclosure)?;
+        Some(
+            xs.iter()
+                .flat_map(|&x| ys.iter().map(move |&y| Rect::from_spans(x, y)))
+                .collect(),
+        )
+    }
+}
+
+/// Returns the spans of the largest row of cuts centered within `span`.
+///
+/// Places as many cuts of length `size` separated by `spacing` as will fit
+/// while keeping an `enclosure` on either end.
+/// Returns `None` if no cuts fit.
+pub fn centered_cuts(span: Span, size: i64, spacing: i64, enclosure: i64) -> Option<Vec<Span>> {
+    let avail = span.length() - 2 * enclosure;
+    let n = (avail + spacing) / (size + spacing);
+    if n <= 0 {
+        return None;
+    }
+    let start = span.center() - (n * size + (n - 1) * spacing) / 2;
+    Some(
+        (0..n)
+            .map(|i| Span::with_start_and_length(start + i * (size + spacing), size))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+    enum Layer {
+        Met1,
+        Via1,
+        Met2,
+        Via2,
+        Met3,
+    }
+
+    fn rules() -> RuleDeck<Layer> {
+        let mut rules = RuleDeck::new();
+        rules
+            .set_min_width(Layer::Met1, 140)
+            .set_min_spacing(Layer::Met1, 140)
+            .set_min_area(Layer::Met2, 83_000)
+            .set_min_enclosure(Layer::Via1, Layer::Met1, 55)
+            .set_min_enclosure(Layer::Via1, Layer::Met2, 85)
+            .set_via(
+                Layer::Via1,
+                ViaRule {
+                    bot: Layer::Met1,
+                    top: Layer::Met2,
+                    size: 150,
+                    spacing: 170,
+                },
+            )
+            .set_via(
+                Layer::Via2,
+                ViaRule {
+                    bot: Layer::Met2,
+                    top: Layer::Met3,
+                    size: 200,
+                    spacing: 200,
+                },
+            );
+        rules
+    }
+
+    #[test]
+    fn rule_deck_queries() {
+        let rules = rules();
+        assert_eq!(rules.min_width(&Layer::Met1), Some(140));
+        assert_eq!(rules.min_spacing(&Layer::Met1), Some(140));
+        assert_eq!(rules.min_width(&Layer::Met2), None);
+        assert_eq!(rules.min_enclosure(&Layer::Via1, &Layer::Met2), Some(85));
+        assert_eq!(rules.min_enclosure(&Layer::Met2, &Layer::Via1), None);
+        assert_eq!(
+            rules
+                .via_between(&Layer::Met2, &Layer::Met1)
+                .map(|(cut, _)| *cut),
+            Some(Layer::Via1)
+        );
+        assert!(rules.via_between(&Layer::Met1, &Layer::Met1).is_none());
+        assert_eq!(rules.min_area(&Layer::Met2), Some(83_000));
+        assert_eq!(rules.min_area(&Layer::Met1), None);
+    }
+
+    #[test]
+    fn rule_deck_via_stack() {
+        let rules = rules();
+        let cuts = |from, to| {
+            rules
+                .via_stack(&from, &to)
+                .map(|stack| stack.into_iter().map(|(cut, _)| *cut).collect::<Vec<_>>())
+        };
+        assert_eq!(cuts(Layer::Met1, Layer::Met2), Some(vec![Layer::Via1]));
+        assert_eq!(
+            cuts(Layer::Met1, Layer::Met3),
+            Some(vec![Layer::Via1, Layer::Via2])
+        );
+        assert_eq!(
+            cuts(Layer::Met3, Layer::Met1),
+            Some(vec![Layer::Via2, Layer::Via1])
+        );
+        assert_eq!(cuts(Layer::Met2, Layer::Met2), Some(vec![]));
+        assert_eq!(cuts(Layer::Met1, Layer::Via1), None);
+    }
+
+    #[test]
+    fn rule_deck_via_array() {
+        let rules = rules();
+
+        let cuts = rules
+            .via_array(&Layer::Via1, Rect::from_sides(0, 0, 320, 320))
+            .unwrap();
+        assert_eq!(cuts, vec![Rect::from_sides(85, 85, 235, 235)]);
+
+        let cuts = rules
+            .via_array(&Layer::Via1, Rect::from_sides(0, 0, 640, 320))
+            .unwrap();
+        assert_eq!(
+            cuts,
+            vec![
+                Rect::from_sides(85, 85, 235, 235),
+                Rect::from_sides(405, 85, 555, 235)
+            ]
+        );
+
+        assert_eq!(
+            rules.via_array(&Layer::Via1, Rect::from_sides(0, 0, 200, 320)),
+            None
+        );
+    }
+}
@@ -0,0 +1,177 @@
+//! A bounded thread pool for layout generation.
+//!
+//! Layout generators frequently block on the cells they instantiate (e.g. to query their
+//! bounding boxes). With a fixed number of worker threads, naively blocking on a queued cell
+//! could deadlock if every worker is waiting on a cell that has not yet started. To avoid this,
+//! a thread that waits on a task that has not started runs the task itself. Since a block can
+//! never depend on itself, the tasks being waited on always make progress.
+
+use std::collections::VecDeque;
+use std::num::NonZeroUsize;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use once_cell::sync::OnceCell;
+
+/// A work item that can be run by a worker thread.
+trait Runnable: Send + Sync {
+    /// Runs the task if it has not already been started by another thread.
+    fn run(&self);
+}
+
+/// A task that produces a value of type `V`.
+pub(crate) struct Task<V> {
+    job: Mutex<Option<Box<dyn FnOnce() -> V + Send>>>,
+    /// The output of the task, or [`None`] if the task panicked.
+    value: OnceCell<Option<V>>,
+}
+
+impl<V: Send + Sync> Runnable for Task<V> {
+    fn run(&self) {
+        let job = self.job.lock().unwrap().take();
+        if let Some(job) = job {
+            let value = catch_unwind(AssertUnwindSafe(job)).ok();
+            let _ = self.value.set(value);
+        }
+    }
+}
+
+impl<V: Send + Sync> Task<V> {
+    /// Blocks until the task completes, running it on the current thread if it has not started.
+    ///
+    /// Returns [`None`] if the task panicked.
+    pub(crate) fn get(&self) -> Option<&V> {
+        self.run();
+        self.value.wait().as_ref()
+    }
+}
+
+#[derive(Default)]
+struct State {
+    queue: VecDeque<Arc<dyn Runnable>>,
+    workers: usize,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    limit: usize,
+}
+
+/// A pool of worker threads that runs tasks in the order they are submitted.
+///
+/// Worker threads are spawned on demand, up to a configurable limit,
+/// and exit once the queue is empty.
+#[derive(Clone)]
+pub(crate) struct Scheduler {
+    shared: Arc<Shared>,
+}
+
+impl Default for Scheduler {
+    fn default() -> Self {
+        Self::new(thread::available_parallelism().map_or(1, NonZeroUsize::get))
+    }
+}
+
+impl std::fmt::Debug for Scheduler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Scheduler")
+            .field("limit", &self.shared.limit)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Scheduler {
+    /// Creates a scheduler that runs at most `limit` tasks in parallel on worker threads.
+    ///
+    /// Threads waiting on tasks may run additional tasks in parallel.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `limit` is zero.
+    pub(crate) fn new(limit: usize) -> Self {
+        assert!(limit > 0, "scheduler parallelism limit must be positive");
+        Self {
+            shared: Arc::new(Shared {
+                state: Mutex::new(State::default()),
+                limit,
+            }),
+        }
+    }
+
+    /// Submits `job` to be run in the background, returning a handle to its result.
+    pub(crate) fn spawn<V: Send + Sync + 'static>(
+        &self,
+        job: impl FnOnce() -> V + Send + 'static,
+    ) -> Arc<Task<V>> {
+        let task = Arc::new(Task {
+            job: Mutex::new(Some(Box::new(job))),
+            value: OnceCell::new(),
+        });
+        let mut state = self.shared.state.lock().unwrap();
+        state.queue.push_back(task.clone());
+        if state.workers < self.shared.limit {
+            state.workers += 1;
+            let shared = self.shared.clone();
+            thread::spawn(move || worker(shared));
+        }
+        task
+    }
+}
+
+fn worker(shared: Arc<Shared>) {
+    loop {
+        let task = {
+            let mut state = shared.state.lock().unwrap();
+            match state.queue.pop_front() {
+                Some(task) => task,
+                None => {
+                    state.workers -= 1;
+                    return;
+                }
+            }
+        };
+        task.run();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    #[test]
+    fn nested_tasks_do_not_deadlock() {
+        let scheduler = Scheduler::new(1);
+        let s = scheduler.clone();
+        let outer = scheduler.spawn(move || {
+            let inner = (0..8)
+                .map(|i| {
+                    let s2 = s.clone();
+                    s.spawn(move || *s2.spawn(move || i).get().unwrap() * 2)
+                })
+                .collect::<Vec<_>>();
+            inner.iter().map(|t| *t.get().unwrap()).sum::<usize>()
+        });
+        assert_eq!(outer.get(), Some(&56));
+    }
+
+    #[test]
+    fn task_runs_once() {
+        let scheduler = Scheduler::new(2);
+        let count = Arc::new(AtomicUsize::new(0));
+        let c = count.clone();
+        let task = scheduler.spawn(move || c.fetch_add(1, Ordering::SeqCst));
+        task.get();
+        task.get();
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn panicking_task_returns_none() {
+        let scheduler = Scheduler::new(1);
+        let task = scheduler.spawn(|| -> usize { panic!("task failed") });
+        assert_eq!(task.get(), None);
+    }
+}
@@ -1,14 +1,15 @@
-use gds::GdsUnits;
+use gds::{GdsDateTimes, GdsUnits};
+use gdsconv::export::{GdsExportOpts, DEFAULT_PROPERTY_LAYER};
 use gdsconv::GdsLayer;
 use geometry::{
     align::{AlignBbox, AlignMode},
     bbox::Bbox,
     rect::Rect,
     side::Sides,
-    transform::{TransformMut, TransformRef, TranslateMut, TranslateRef},
+    transform::{TransformMut, TransformRef, Transformation, TranslateMut, TranslateRef},
     union::BoundingUnion,
 };
-use layir::{Cell, LibraryBuilder, Shape};
+use layir::{Cell, Element, LibraryBuilder, Shape, Text};
 
 use crate::{
     block::Block,
@@ -22,6 +23,8 @@ use crate::{
 };
 
 use super::{
+    conv::{export_layir_cells, LayirExportError},
+    hierarchy::uniquify,
     schema::Schema,
     tiling::{ArrayTiler, GridTile, GridTiler, Tile, TileAlignMode},
     CellBundle, Instance, Layout,
@@ -279,6 +282,38 @@ impl Layout for BufferNxM {
     }
 }
 
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Block, Hash)]
+#[substrate(io = "BufferIo")]
+pub struct LabeledInverter;
+
+impl Layout for LabeledInverter {
+    type Schema = ExampleSchema;
+    type Bundle = View<BufferIo, PortGeometryBundle<ExampleSchema>>;
+    type Data = ();
+    fn layout(
+        &self,
+        _cell: &mut super::CellBuilder<Self::Schema>,
+    ) -> crate::error::Result<(Self::Bundle, Self::Data)> {
+        let port = |rect| PortGeometry::new(Shape::new(ExampleLayer::B, rect));
+        let mut din = port(Rect::from_sides(0, 75, 25, 125));
+        din.add_label(Text::with_transformation(
+            ExampleLayer::C,
+            "din",
+            Transformation::translate(10, 80),
+        ));
+
+        Ok((
+            BufferIoView {
+                din,
+                dout: port(Rect::from_sides(75, 75, 100, 125)),
+                vdd: port(Rect::from_sides(25, 175, 75, 200)),
+                vss: port(Rect::from_sides(25, 0, 75, 25)),
+            },
+            (),
+        ))
+    }
+}
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Block, Hash)]
 #[substrate(io = "()")]
 pub struct GridTilerExample;
@@ -399,6 +434,80 @@ fn cell_builder_supports_bbox() {
     assert_eq!(cell.bbox(), Some(Rect::from_sides(-10, -1110, 2200, 210)));
 }
 
+#[test]
+fn layout_generation_with_single_thread_does_not_deadlock() {
+    let block = BufferNxM::new(5, 10, 6);
+
+    let ctx = Context::builder().layout_parallelism(1).build();
+    let handle = ctx.generate_layout(block);
+    let cell = handle.cell();
+
+    assert_eq!(cell.bbox(), Some(Rect::from_sides(-10, -1110, 2200, 210)));
+}
+
+#[test]
+fn reproducible_contexts_assign_stable_cell_ids() {
+    let ctx_a = Context::builder().reproducible(true).build();
+    let ctx_b = Context::builder().reproducible(true).build();
+
+    let small = BufferN::new(5, 2);
+    let large = BufferN::new(5, 10);
+    let a = ctx_a.generate_layout(small);
+    ctx_b.generate_layout(large);
+    let b = ctx_b.generate_layout(small);
+
+    assert_eq!(a.cell().raw().id(), b.cell().raw().id());
+    assert_ne!(
+        a.cell().raw().id(),
+        ctx_a.generate_layout(large).cell().raw().id()
+    );
+}
+
+#[test]
+fn stable_ids_resolve_collisions() {
+    use crate::context::StableIds;
+
+    let mut ids = StableIds::default();
+    let a = ids.id("buffer", &1u64);
+    assert_eq!(ids.id("buffer", &1u64), a);
+    assert_ne!(ids.id("buffer", &2u64), a);
+
+    // A key of a different type whose hash equals that of `1u64` is assigned a different ID.
+    let b = ids.id("buffer", &1i64);
+    assert_ne!(b, a);
+    assert_eq!(ids.id("buffer", &1i64), b);
+
+    // Fresh IDs are never reused, even for the same name.
+    let c = ids.fresh("buffer");
+    assert_ne!(ids.fresh("buffer"), c);
+}
+
+#[test]
+fn port_labels_are_exported() {
+    let ctx = Context::new();
+    let lib = ctx.export_layir(LabeledInverter).unwrap();
+    let (_, cell) = lib.layir.cells().next().unwrap();
+    let labels = |name| {
+        cell.port(name)
+            .elements()
+            .filter_map(|elt| match elt {
+                Element::Text(t) => Some((*t.layer(), t.transformation())),
+                Element::Shape(_) => None,
+            })
+            .collect::<Vec<_>>()
+    };
+
+    // Explicit labels replace the default label at the center of each port shape.
+    assert_eq!(
+        labels("din"),
+        vec![(ExampleLayer::C, Transformation::translate(10, 80))]
+    );
+    assert_eq!(
+        labels("dout"),
+        vec![(ExampleLayer::B, Transformation::translate(87, 100))]
+    );
+}
+
 #[test]
 fn export_multi_top_layout() {
     let test_name = "export_multi_top_layout";
@@ -423,6 +532,74 @@ fn export_multi_top_layout() {
     .expect("failed to write layout");
 }
 
+#[test]
+fn layout_cells_are_exported_one_at_a_time() {
+    let ctx = Context::new();
+    let block = BufferN::new(5, 3);
+    let full = ctx.export_layir(block).unwrap().layir;
+    let handle = ctx.generate_layout(block);
+    let raw = handle.cell().raw();
+
+    let mut exported = Vec::new();
+    export_layir_cells(&[raw.as_ref()], |lib, id| -> Result<(), LayirExportError> {
+        // Only the exported cell has contents;
+        // the cells it instantiates are empty placeholders that were exported earlier.
+        let cell = lib.cell(id);
+        for (other, placeholder) in lib.cells() {
+            if other != id {
+                assert!(exported.contains(placeholder.name()));
+                assert_eq!(placeholder.elements().count(), 0);
+                assert_eq!(placeholder.instances().count(), 0);
+            }
+        }
+        let expected = full.cell_named(cell.name());
+        assert_eq!(
+            cell.elements().collect::<Vec<_>>(),
+            expected.elements().collect::<Vec<_>>()
+        );
+        assert_eq!(cell.instances().count(), expected.instances().count());
+        exported.push(cell.name().clone());
+        Ok(())
+    })
+    .unwrap();
+
+    let names = full
+        .topological_order()
+        .into_iter()
+        .map(|id| full.cell(id).name().clone())
+        .collect::<Vec<_>>();
+    assert_eq!(exported, names);
+}
+
+#[test]
+fn streamed_gds_matches_full_export() {
+    let test_name = "streamed_gds_matches_full_export";
+    let ctx = Context::builder().reproducible(true).build();
+    let block = BufferNxM::new(5, 10, 6);
+
+    let streamed = get_path(test_name, "streamed.gds");
+    ctx.write_layout(block, to_gds, &streamed).unwrap();
+
+    let full = get_path(test_name, "full.gds");
+    let (lib, units) = to_gds(&ctx.export_layir(block).unwrap().layir);
+    gdsconv::export::save_gds(
+        &lib,
+        GdsExportOpts {
+            name: block.name(),
+            units: Some(units),
+            dates: Some(GdsDateTimes::epoch()),
+            property_layer: Some(DEFAULT_PROPERTY_LAYER),
+        },
+        &full,
+    )
+    .unwrap();
+
+    assert_eq!(
+        std::fs::read(streamed).unwrap(),
+        std::fs::read(full).unwrap()
+    );
+}
+
 #[test]
 fn grid_tiler_works_with_various_spans() {
     let test_name = "grid_tiler_works_with_various_spans";
@@ -431,3 +608,35 @@ fn grid_tiler_works_with_various_spans() {
     ctx.write_layout(GridTilerExample, to_gds, get_path(test_name, "layout.gds"))
         .expect("failed to write layout");
 }
+
+#[test]
+fn layout_hierarchies_can_be_flattened_and_uniquified() {
+    let ctx = Context::new();
+    let handle = ctx.generate_layout(Buffer::new(5));
+    let raw = handle.cell().raw().clone();
+
+    let flat = raw.flatten();
+    assert_eq!(flat.bbox(), raw.bbox());
+    assert!(flat
+        .elements()
+        .all(|elem| elem.as_ref().instance().is_none()));
+    // One routing shape, plus one shape and four port shapes per inverter.
+    assert_eq!(flat.elements().count(), 11);
+    assert_eq!(flat.ports().count(), raw.ports().count());
+
+    let uniq = uniquify(&ctx, &raw);
+    assert_ne!(uniq.id(), raw.id());
+    let children = uniq
+        .elements()
+        .filter_map(|elem| elem.as_ref().instance())
+        .map(|inst| inst.raw_cell())
+        .collect::<Vec<_>>();
+    assert_eq!(children.len(), 2);
+    assert_ne!(children[0].id(), children[1].id());
+    assert_eq!(uniq.bbox(), raw.bbox());
+
+    let lib = ctx.export_layir_all([raw.as_ref()]).unwrap();
+    assert_eq!(lib.layir.cells().count(), 2);
+    let lib = ctx.export_layir_all([&uniq]).unwrap();
+    assert_eq!(lib.layir.cells().count(), 3);
+}
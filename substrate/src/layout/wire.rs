@@ -0,0 +1,313 @@
+//! Hand-guided wire routing.
+//!
+//! A [`Wire`] is a sequence of horizontal and vertical segments, possibly spanning
+//! several layers. Wire widths default to the minimum widths in a [`RuleDeck`], and vias
+//! are inserted wherever the wire changes layers, stacked through any intermediate layers.
+//!
+//! ```ignore
+//! let mut wire = Wire::new(MyLayer::Met1, Point::new(0, 0));
+//! wire.horiz_to(1_000).via(MyLayer::Met2).vert_to(2_000);
+//! for shape in wire.shapes(pdk.rules())? {
+//!     cell.draw(shape)?;
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+use arcstr::ArcStr;
+use geometry::prelude::{Point, Rect};
+use geometry::span::Span;
+use layir::Shape;
+use tracing::{span, Level};
+
+use super::error::LayoutError;
+use super::rules::RuleDeck;
+use crate::error::Result;
+
+/// A wire consisting of horizontal and vertical segments on one or more layers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Wire<L> {
+    /// The points visited on each layer, in order.
+    ///
+    /// The last point of each run is the first point of the next run.
+    runs: Vec<(L, Vec<Point>)>,
+    widths: HashMap<L, i64>,
+}
+
+impl<L: Clone + Eq + Hash + Debug> Wire<L> {
+    /// Creates a wire starting at `start` on `layer`.
+    pub fn new(layer: L, start: Point) -> Self {
+        Self {
+            runs: vec![(layer, vec![start])],
+            widths: HashMap::new(),
+        }
+    }
+
+    /// The current end point of the wire.
+    pub fn end(&self) -> Point {
+        *self.runs.last().unwrap().1.last().unwrap()
+    }
+
+    /// The layer on which the wire currently ends.
+    pub fn layer(&self) -> &L {
+        &self.runs.last().unwrap().0
+    }
+
+    /// Extends the wire to `p` on the current layer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the segment from the current end point to `p` is neither horizontal nor vertical.
+    pub fn to(&mut self, p: Point) -> &mut Self {
+        let end = self.end();
+        assert!(
+            end.x == p.x || end.y == p.y,
+            "wire segments must be horizontal or vertical"
+        );
+        if p != end {
+            self.runs.last_mut().unwrap().1.push(p);
+        }
+        self
+    }
+
+    /// Extends the wire horizontally to x-coordinate `x` on the current layer.
+    pub fn horiz_to(&mut self, x: i64) -> &mut Self {
+        let end = self.end();
+        self.to(Point::new(x, end.y))
+    }
+
+    /// Extends the wire vertically to y-coordinate `y` on the current layer.
+    pub fn vert_to(&mut self, y: i64) -> &mut Self {
+        let end = self.end();
+        self.to(Point::new(end.x, y))
+    }
+
+    /// Continues the wire on `layer`, placing a via at the current end point.
+    ///
+    /// Does nothing if the wire is already on `layer`.
+    pub fn via(&mut self, layer: L) -> &mut Self {
+        if layer != *self.layer() {
+            let end = self.end();
+            self.runs.push((layer, vec![end]));
+        }
+        self
+    }
+
+    /// Sets the width of the wire on `layer`, overriding the minimum width in the rule deck.
+    pub fn set_width(&mut self, layer: L, width: i64) -> &mut Self {
+        self.widths.insert(layer, width);
+        self
+    }
+
+    /// Returns the shapes making up this wire.
+    ///
+    /// Each segment is extended by half its width at both ends, so that consecutive
+    /// segments overlap at corners. At each layer change, a stack of single via cuts
+    /// connecting the two layers is placed at the wire's end point. Each metal layer in
+    /// the stack gets a landing pad that satisfies the enclosure rules of every via
+    /// landing on it, grown if necessary to the layer's minimum area.
+    ///
+    /// Returns an error if a layer has no width, or if no stack of vias connects
+    /// two consecutive layers.
+    pub fn shapes(&self, rules: &RuleDeck<L>) -> Result<Vec<Shape<L>>> {
+        let _guard = span!(Level::DEBUG, "routing wire", layers = self.runs.len()).entered();
+        let mut shapes = Vec::new();
+        for (layer, points) in self.runs.iter() {
+            if points.len() < 2 {
+                continue;
+            }
+            let width = self
+                .widths
+                .get(layer)
+                .copied()
+                .or_else(|| rules.min_width(layer))
+                .ok_or_else(|| LayoutError::MissingWireWidth(layer_name(layer)))?;
+            for segment in points.windows(2) {
+                let (p0, p1) = (segment[0], segment[1]);
+                let hspan = Span::new(p0.x.min(p1.x), p0.x.max(p1.x));
+                let vspan = Span::new(p0.y.min(p1.y), p0.y.max(p1.y));
+                let rect = Rect::from_spans(widen(hspan, width), widen(vspan, width));
+                shapes.push(Shape::new(layer.clone(), rect));
+            }
+        }
+
+        for runs in self.runs.windows(2) {
+            let (from, to) = (&runs[0].0, &runs[1].0);
+            let p = runs[1].1[0];
+            let stack = rules
+                .via_stack(from, to)
+                .ok_or_else(|| LayoutError::MissingViaRule(layer_name(from), layer_name(to)))?;
+            let mut pads: Vec<(&L, Rect)> = Vec::new();
+            let mut cuts = Vec::new();
+            for (cut, via) in stack {
+                let span = |c: i64| Span::with_start_and_length(c - via.size / 2, via.size);
+                let rect = Rect::from_spans(span(p.x), span(p.y));
+                for layer in [&via.bot, &via.top] {
+                    let enclosure = rules.min_enclosure(cut, layer).unwrap_or_default();
+                    let pad = rect.expand_all(enclosure);
+                    match pads.iter_mut().find(|(l, _)| *l == layer) {
+                        Some((_, existing)) => *existing = existing.union(pad),
+                        None => pads.push((layer, pad)),
+                    }
+                }
+                cuts.push(Shape::new(cut.clone(), rect));
+            }
+            for (layer, pad) in pads {
+                let pad = with_min_area(pad, rules.min_area(layer).unwrap_or_default());
+                shapes.push(Shape::new(layer.clone(), pad));
+            }
+            shapes.extend(cuts);
+        }
+        Ok(shapes)
+    }
+}
+
+/// Grows `rect` about its center until its area is at least `area`.
+///
+/// Only the shorter side is lengthened.
+fn with_min_area(rect: Rect, area: i64) -> Rect {
+    let (width, height) = (rect.width(), rect.height());
+    if width == 0 || height == 0 || rect.area() >= area {
+        return rect;
+    }
+    let grow = |span: Span, long: i64| {
+        let delta = (area + long - 1) / long - span.length();
+        Span::new(span.start() - delta / 2, span.stop() + delta - delta / 2)
+    };
+    if width >= height {
+        Rect::from_spans(rect.hspan(), grow(rect.vspan(), width))
+    } else {
+        Rect::from_spans(grow(rect.hspan(), height), rect.vspan())
+    }
+}
+
+/// Expands a span of wire centerline coordinates to a wire of the given width.
+fn widen(span: Span, width: i64) -> Span {
+    Span::new(span.start() - width / 2, span.stop() - width / 2 + width)
+}
+
+fn layer_name<L: Debug>(layer: &L) -> ArcStr {
+    arcstr::format!("{:?}", layer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+    use crate::layout::rules::ViaRule;
+
+    #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+    enum Layer {
+        Met1,
+        Via1,
+        Met2,
+        Via2,
+        Met3,
+    }
+
+    fn rules() -> RuleDeck<Layer> {
+        let mut rules = RuleDeck::new();
+        rules
+            .set_min_width(Layer::Met1, 140)
+            .set_min_width(Layer::Met2, 200)
+            .set_min_enclosure(Layer::Via1, Layer::Met1, 55)
+            .set_min_enclosure(Layer::Via1, Layer::Met2, 85)
+            .set_via(
+                Layer::Via1,
+                ViaRule {
+                    bot: Layer::Met1,
+                    top: Layer::Met2,
+                    size: 150,
+                    spacing: 170,
+                },
+            );
+        rules
+    }
+
+    #[test]
+    fn wire_shapes() {
+        let mut wire = Wire::new(Layer::Met1, Point::new(0, 0));
+        wire.horiz_to(1_000).via(Layer::Met2).vert_to(2_000);
+        assert_eq!(wire.end(), Point::new(1_000, 2_000));
+        assert_eq!(*wire.layer(), Layer::Met2);
+
+        let shapes = wire.shapes(&rules()).unwrap();
+        assert_eq!(
+            shapes,
+            vec![
+                Shape::new(Layer::Met1, Rect::from_sides(-70, -70, 1_070, 70)),
+                Shape::new(Layer::Met2, Rect::from_sides(900, -100, 1_100, 2_100)),
+                Shape::new(Layer::Met1, Rect::from_sides(870, -130, 1_130, 130)),
+                Shape::new(Layer::Met2, Rect::from_sides(840, -160, 1_160, 160)),
+                Shape::new(Layer::Via1, Rect::from_sides(925, -75, 1_075, 75)),
+            ]
+        );
+
+        wire.set_width(Layer::Met2, 300);
+        let shapes = wire.shapes(&rules()).unwrap();
+        assert_eq!(
+            shapes[1],
+            Shape::new(Layer::Met2, Rect::from_sides(850, -150, 1_150, 2_150))
+        );
+    }
+
+    #[test]
+    fn wire_stacks_vias() {
+        let mut rules = rules();
+        rules
+            .set_min_width(Layer::Met3, 300)
+            .set_min_area(Layer::Met2, 120_000)
+            .set_min_enclosure(Layer::Via2, Layer::Met2, 65)
+            .set_min_enclosure(Layer::Via2, Layer::Met3, 65)
+            .set_via(
+                Layer::Via2,
+                ViaRule {
+                    bot: Layer::Met2,
+                    top: Layer::Met3,
+                    size: 150,
+                    spacing: 170,
+                },
+            );
+
+        let mut wire = Wire::new(Layer::Met1, Point::new(0, 0));
+        wire.horiz_to(1_000).via(Layer::Met3).vert_to(2_000);
+        let shapes = wire.shapes(&rules).unwrap();
+        assert_eq!(
+            shapes,
+            vec![
+                Shape::new(Layer::Met1, Rect::from_sides(-70, -70, 1_070, 70)),
+                Shape::new(Layer::Met3, Rect::from_sides(850, -150, 1_150, 2_150)),
+                Shape::new(Layer::Met1, Rect::from_sides(870, -130, 1_130, 130)),
+                // The Met2 pad encloses both vias and is lengthened to the minimum area.
+                Shape::new(Layer::Met2, Rect::from_sides(840, -187, 1_160, 188)),
+                Shape::new(Layer::Met3, Rect::from_sides(860, -140, 1_140, 140)),
+                Shape::new(Layer::Via1, Rect::from_sides(925, -75, 1_075, 75)),
+                Shape::new(Layer::Via2, Rect::from_sides(925, -75, 1_075, 75)),
+            ]
+        );
+    }
+
+    #[test]
+    fn wire_errors() {
+        let mut wire = Wire::new(Layer::Met2, Point::new(0, 0));
+        wire.via(Layer::Met3).horiz_to(100);
+        assert!(matches!(
+            wire.shapes(&rules()),
+            Err(Error::Layout(LayoutError::MissingWireWidth(_)))
+        ));
+
+        wire.set_width(Layer::Met3, 100);
+        assert!(matches!(
+            wire.shapes(&rules()),
+            Err(Error::Layout(LayoutError::MissingViaRule(_, _)))
+        ));
+    }
+
+    #[test]
+    #[should_panic]
+    fn wire_panics_on_diagonal_segment() {
+        Wire::new(Layer::Met1, Point::new(0, 0)).to(Point::new(100, 100));
+    }
+}
@@ -12,12 +12,16 @@ mod diagnostics;
 pub mod error;
 pub mod execute;
 pub mod layout;
+#[cfg(feature = "logging")]
+pub mod logging;
 pub mod lut;
 pub mod schematic;
 pub mod simulation;
+pub mod snapshot;
 #[cfg(test)]
 pub(crate) mod tests;
 pub mod types;
+pub mod units;
 
 // Re-exported for procedural macros.
 #[doc(hidden)]
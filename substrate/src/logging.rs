@@ -0,0 +1,92 @@
+//! Logging utilities.
+//!
+//! Substrate instruments cell generation, netlisting, and simulation with
+//! [`tracing`] spans that record the names of the cells and analyses involved.
+//! Since generators run on many threads at once, the helpers in this module write
+//! each event along with its full chain of enclosing spans, which makes it possible
+//! to tell which cell or analysis a given message came from.
+//!
+//! Requires the `logging` feature.
+
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use tracing::Subscriber;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::EnvFilter;
+
+/// The name of the log file created by [`init_work_dir_logging`].
+pub const LOG_FILE_NAME: &str = "substrate.log";
+
+/// An error encountered while setting up logging.
+#[derive(Debug, thiserror::Error)]
+pub enum LoggingError {
+    /// The log file could not be created.
+    #[error("error creating log file `{}`", .path.display())]
+    Io {
+        /// The path to the log file.
+        path: PathBuf,
+        /// The underlying error.
+        #[source]
+        source: std::io::Error,
+    },
+    /// A global default subscriber has already been set.
+    #[error("a global tracing subscriber has already been set")]
+    AlreadyInitialized(#[from] tracing::subscriber::SetGlobalDefaultError),
+}
+
+/// Creates a subscriber that writes hierarchical logs to [`LOG_FILE_NAME`] in `work_dir`.
+///
+/// Each line contains the thread name and the enclosing spans of the event,
+/// and span durations are logged when spans close. The log level can be
+/// configured using the `RUST_LOG` environment variable and defaults to `info`.
+pub fn work_dir_subscriber(
+    work_dir: impl AsRef<Path>,
+) -> Result<impl Subscriber + Send + Sync + 'static, LoggingError> {
+    let work_dir = work_dir.as_ref();
+    let path = work_dir.join(LOG_FILE_NAME);
+    let file = std::fs::create_dir_all(work_dir)
+        .and_then(|_| File::create(&path))
+        .map_err(|source| LoggingError::Io { path, source })?;
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+
+    Ok(tracing_subscriber::fmt()
+        .with_writer(Arc::new(file))
+        .with_ansi(false)
+        .with_thread_names(true)
+        .with_span_events(FmtSpan::CLOSE)
+        .with_env_filter(filter)
+        .finish())
+}
+
+/// Sets up a global subscriber that writes hierarchical logs to [`LOG_FILE_NAME`] in `work_dir`.
+///
+/// A global subscriber is required to capture events from generator threads.
+/// Returns an error if a global subscriber has already been set.
+/// See [`work_dir_subscriber`] for details on the log format.
+pub fn init_work_dir_logging(work_dir: impl AsRef<Path>) -> Result<(), LoggingError> {
+    tracing::subscriber::set_global_default(work_dir_subscriber(work_dir)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing::{span, Level};
+
+    use super::*;
+    use crate::tests::get_path;
+
+    #[test]
+    fn work_dir_logs_include_spans() {
+        let work_dir = get_path("work_dir_logs_include_spans", "");
+        let subscriber = work_dir_subscriber(&work_dir).unwrap();
+        tracing::subscriber::with_default(subscriber, || {
+            let _guard = span!(Level::INFO, "generating schematic", block = "inverter").entered();
+            tracing::info!("created instance");
+        });
+        let log = std::fs::read_to_string(work_dir.join(LOG_FILE_NAME)).unwrap();
+        assert!(log.contains("generating schematic{block=\"inverter\"}: "));
+        assert!(log.contains("created instance"));
+    }
+}
@@ -2,6 +2,7 @@
 
 use std::collections::{HashMap, HashSet};
 use std::fmt::Formatter;
+use std::sync::Arc;
 
 use arcstr::ArcStr;
 use scir::{Cell, ChildId, Concat, IndexOwned, Instance, LibraryBuilder};
@@ -260,6 +261,68 @@ impl<S: Schema> RawLib<S> {
         }
     }
 
+    /// Converts a SCIR [`scir::SliceOnePath`] relative to the top cell
+    /// back to a Substrate [`NodePath`].
+    ///
+    /// Returns [`None`] if the path does not correspond to a Substrate node,
+    /// such as if it refers to a signal by name or passes through a primitive.
+    pub fn convert_scir_slice_one_path(&self, path: &scir::SliceOnePath) -> Option<NodePath> {
+        let top = self.conv.top?;
+        let mut cell = self.conv.cells.get(&top)?.as_ref().into_cell()?;
+
+        let mut instances = Vec::new();
+        for elem in path.instances().iter() {
+            let scir::InstancePathElement::Id(id) = elem else {
+                return None;
+            };
+            cell = self.find_scir_instance(cell, *id, &mut instances)?;
+        }
+
+        let scir::SignalPathTail::Id(tail) = path.tail() else {
+            return None;
+        };
+        let node = cell
+            .signals
+            .iter()
+            .find(|(_, slice)| *slice == tail)
+            .map(|(node, _)| *node)?;
+
+        Some(NodePath {
+            top,
+            instances,
+            node,
+        })
+    }
+
+    /// Finds the Substrate instance corresponding to the SCIR instance `id`,
+    /// pushing the Substrate instance IDs along the way to `instances`.
+    ///
+    /// Returns the conversion of the child cell of the instance.
+    fn find_scir_instance<'a>(
+        &'a self,
+        conv: &'a ScirCellConversion,
+        id: scir::InstanceId,
+        instances: &mut Vec<InstanceId>,
+    ) -> Option<&'a ScirCellConversion> {
+        for (inst_id, inst) in conv.instances.iter() {
+            match inst.instance.as_ref() {
+                ConvertedScirInstanceContentRef::Cell(scir_id) if *scir_id == id => {
+                    instances.push(*inst_id);
+                    return self.conv.cells.get(&inst.child)?.as_ref().into_cell();
+                }
+                ConvertedScirInstanceContentRef::InlineCell(child) => {
+                    instances.push(*inst_id);
+                    if let Some(found) = self.find_scir_instance(child, id, instances) {
+                        return Some(found);
+                    }
+                    instances.pop();
+                }
+                _ => (),
+            }
+        }
+        None
+    }
+
     /// Must ensure that `instances` is returned to its original value by the end of the
     /// function call.
     fn find_connected_terminals_in_scir_instance(
@@ -544,8 +607,19 @@ impl<S: Schema + ?Sized> RawCell<S> {
                 let mut conv =
                     self.export_instances(lib_ctx, &mut cell_ctx, FlatExport::No, None)?;
                 let ScirCellExportContext {
-                    cell: scir_cell, ..
+                    cell: mut scir_cell,
+                    ..
                 } = cell_ctx;
+                scir_cell.set_preserve(self.preserve);
+                scir_cell.set_source_info(scir::SourceInfo::from_block(self.block.clone()));
+                if let Some(provenance) = &self.provenance {
+                    for (key, value) in provenance.entries() {
+                        scir_cell.add_comment(arcstr::format!("{key}: {value}"));
+                    }
+                }
+                for (name, param) in self.params.iter() {
+                    scir_cell.add_param(name.clone(), param.clone());
+                }
 
                 let id = lib_ctx.lib.merge_cell(scir_cell);
                 conv.cell_id = Some(id);
@@ -660,7 +734,18 @@ impl<S: Schema + ?Sized> RawCell<S> {
 
         for (&src, &root) in self.roots.iter() {
             let s = if !roots_added.contains(&root) {
-                let s = cell_ctx.cell.add_node(self.node_name(root));
+                let s = if let Some(global) = self.globals.get(&root) {
+                    lib_ctx.lib.add_global(global.clone());
+                    cell_ctx.cell.add_global(global.clone())
+                } else {
+                    cell_ctx.cell.add_node(self.node_name(root))
+                };
+                if let Some(source_info) = self.node_sources.get(&root) {
+                    cell_ctx.cell.set_signal_source_info(
+                        s,
+                        scir::SourceInfo::from(source_info).with_block(self.block.clone()),
+                    );
+                }
                 roots_added.insert(root);
                 nodes.insert(root, s);
                 s
@@ -684,9 +769,22 @@ impl<S: Schema + ?Sized> RawCell<S> {
                 for instance in contents.instances.iter() {
                     let child_id: ChildId = match &instance.child.contents {
                         RawCellContents::Primitive(_) | RawCellContents::ConvertedPrimitive(_) => {
-                            instance.child.to_scir_cell(lib_ctx)?
+                            let child_id = instance.child.to_scir_cell(lib_ctx)?;
+                            if instance.multiplier == 1 {
+                                child_id
+                            } else {
+                                let primitive = <S as scir::schema::Schema>::multiply_primitive(
+                                    lib_ctx.lib.primitive(child_id.unwrap_primitive()),
+                                    instance.multiplier,
+                                )
+                                .ok_or(ConvError::UnsupportedMultiplier)?;
+                                lib_ctx.lib.add_primitive(primitive).into()
+                            }
                         }
                         _ => {
+                            if instance.multiplier != 1 {
+                                return Err(ConvError::UnsupportedMultiplier);
+                            }
                             if instance.child.flatten {
                                 let ports = instance.connections.iter().map(|c| nodes[c]).collect();
                                 let inst_conv = instance.child.export_instances(
@@ -718,6 +816,13 @@ impl<S: Schema + ?Sized> RawCell<S> {
                     );
                     let mut sinst =
                         Instance::new(arcstr::format!("{}{}", prefix, instance.name), child_id);
+                    sinst.set_source_info(
+                        scir::SourceInfo::from(&instance.source_info)
+                            .with_block(self.block.clone()),
+                    );
+                    for (name, value) in instance.params.iter() {
+                        sinst.set_param(name.clone(), value.clone());
+                    }
                     cell_ctx.inst_idx += 1;
 
                     assert_eq!(instance.child.ports.len(), instance.connections.len());
@@ -785,12 +890,20 @@ pub enum ConvError {
     /// An unsupported primitive was encountered during conversion.
     #[error("unsupported primitive")]
     UnsupportedPrimitive,
+    /// An instance with a multiplier other than 1 is not a primitive instance
+    /// that supports multipliers.
+    #[error("unsupported instance multiplier")]
+    UnsupportedMultiplier,
+    /// The cell being converted, or one of its instances, failed to generate.
+    #[error("error generating cell")]
+    Generation(#[source] Arc<crate::error::Error>),
 }
 
 /// Export a collection of cells and all their subcells as a SCIR library.
 ///
 /// Returns the SCIR library and metadata for converting between SCIR and Substrate formats.
 /// The resulting SCIR library will **not** have a top cell set.
+/// Instead, each of the given cells is designated as a root of the library.
 /// If you want a SCIR library with a known top cell, consider using [`RawCell::to_scir_lib`] instead.
 pub(crate) fn export_multi_top_scir_lib<S: Schema + ?Sized>(
     cells: &[&RawCell<S>],
@@ -798,7 +911,9 @@ pub(crate) fn export_multi_top_scir_lib<S: Schema + ?Sized>(
     let mut lib_ctx = ScirLibExportContext::new();
 
     for &cell in cells {
-        cell.to_scir_cell(&mut lib_ctx)?;
+        if let ChildId::Cell(scir_id) = cell.to_scir_cell(&mut lib_ctx)? {
+            lib_ctx.lib.add_root(scir_id);
+        }
     }
 
     Ok(RawLib {
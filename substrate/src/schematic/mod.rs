@@ -2,7 +2,9 @@
 
 pub mod conv;
 pub mod netlist;
+pub mod overrides;
 pub mod pex;
+pub mod primitives;
 pub mod schema;
 #[cfg(test)]
 mod tests;
@@ -21,19 +23,23 @@ use std::ops::Deref;
 use std::sync::Arc;
 
 use arcstr::ArcStr;
+use indexmap::IndexMap;
 use once_cell::sync::OnceCell;
+use scir::{Expr, Param, ParamValue};
 
-use crate::block::Block;
-use crate::context::Context;
+use crate::block::{Block, Provenance};
+use crate::context::{Context, StableIds};
 use crate::diagnostics::SourceInfo;
-use crate::error::{Error, Result};
+use crate::error::{Error, Result, UnconnectedSupply};
 use crate::schematic::conv::ConvError;
+use crate::schematic::overrides::Overrides;
+use crate::schematic::primitives::PrimitiveLibrary;
 use crate::schematic::schema::{FromSchema, Schema};
 use crate::types::schematic::{
-    IoNodeBundle, IoTerminalBundle, Node, NodeBundle, NodeContext, NodePriority, NodeUf, Port,
-    SchematicBundleKind,
+    HasSupplies, IoNodeBundle, IoTerminalBundle, NestedTerminal, Node, NodeBundle, NodeContext,
+    NodePriority, NodeUf, Port, SchematicBundleKind, Terminal,
 };
-use crate::types::{Flatten, HasBundleKind, HasNameTree, IoKind, NameBuf};
+use crate::types::{Flatten, HasBundleKind, HasNameTree, IoKind, NameBuf, Signal, SupplyKind};
 
 /// A block that has a schematic.
 pub trait Schematic: Block<Io: HasBundleKind<BundleKind: SchematicBundleKind>> {
@@ -53,6 +59,21 @@ pub trait Schematic: Block<Io: HasBundleKind<BundleKind: SchematicBundleKind>> {
     ) -> Result<Self::NestedData>;
 }
 
+/// A block whose parameters can be passed through to its instances rather than
+/// being elaborated by its generator.
+///
+/// The schematic of [`PassThroughParams::base`] should declare each parameter using
+/// [`CellBuilder::declare_param`]. Instantiate blocks with pass-through parameters using
+/// [`CellBuilder::instantiate_parameterized`].
+pub trait PassThroughParams: Schematic + Sized {
+    /// Returns the block whose generated cell is shared by all blocks
+    /// that only differ in pass-through parameter values.
+    fn base(&self) -> Self;
+
+    /// Returns the values of the pass-through parameters of this block.
+    fn param_values(&self) -> Vec<(ArcStr, ParamValue)>;
+}
+
 impl<T: Schematic> Schematic for Arc<T> {
     type Schema = T::Schema;
     type NestedData = T::NestedData;
@@ -158,6 +179,13 @@ pub struct CellBuilder<S: Schema + ?Sized> {
     pub(crate) id: CellId,
     pub(crate) cell_name: ArcStr,
     pub(crate) flatten: bool,
+    pub(crate) preserve: bool,
+    /// The provenance of the block being generated.
+    pub(crate) provenance: Option<Provenance>,
+    /// The type name of the block being generated.
+    pub(crate) block: ArcStr,
+    /// Parameters declared using [`CellBuilder::declare_param`].
+    pub(crate) params: IndexMap<ArcStr, Param>,
     pub(crate) node_ctx: NodeContext,
     pub(crate) node_names: HashMap<Node, NameBuf>,
     /// Whether a fatal error occured while building the cell.
@@ -168,35 +196,345 @@ pub struct CellBuilder<S: Schema + ?Sized> {
     /// are the wrong directions to use when looking at connections to this
     /// cell's IO from *within* the cell.
     pub(crate) ports: Vec<Port>,
+    /// The supply rails of this cell, set by [`CellBuilder::set_rails`].
+    pub(crate) rails: HashMap<SupplyKind, Node>,
+    /// Nodes referring to global rails, created by [`CellBuilder::global_rail`].
+    pub(crate) global_rails: HashMap<SupplyKind, Node>,
+    /// Supplies that must be connected when the cell is built.
+    pub(crate) supplies: Vec<DeclaredSupply>,
+    /// Overrides of blocks instantiated below this cell, relative to this cell.
+    pub(crate) overrides: Overrides,
     pub(crate) contents: RawCellContentsBuilder<S>,
 }
 
+/// A supply node declared using [`CellBuilder::declare_supplies`].
+pub(crate) struct DeclaredSupply {
+    kind: SupplyKind,
+    node: Node,
+    source_info: SourceInfo,
+}
+
 impl<S: Schema + ?Sized> CellBuilder<S> {
-    pub(crate) fn finish(self) -> RawCell<S> {
+    /// Builds the raw cell, blocking until all instantiated cells have been generated.
+    ///
+    /// Returns an error if any instantiated cell failed to generate.
+    pub(crate) fn finish(self) -> Result<RawCell<S>> {
         let mut roots = HashMap::with_capacity(self.node_names.len());
-        let mut uf = self.node_ctx.into_uf();
+        let (mut uf, sources) = self.node_ctx.into_parts();
         for &node in self.node_names.keys() {
-            let root = uf.probe_value(node).unwrap().source;
-            roots.insert(node, root);
+            roots.insert(node, uf.probe_value(node).unwrap().source);
+        }
+        // Nodes are indexed in creation order, so each net is attributed to the earliest-created
+        // node merged into it that has a location, regardless of which node became the root.
+        let mut node_sources = HashMap::new();
+        for (idx, source_info) in sources.into_iter().enumerate() {
+            if let Some(source_info) = source_info {
+                let node = <Node as ena::unify::UnifyKey>::from_index(u32::try_from(idx).unwrap());
+                let root = uf.probe_value(node).unwrap().source;
+                node_sources.entry(root).or_insert(source_info);
+            }
+        }
+
+        if let Some(path) = self.overrides.paths().next() {
+            return Err(Error::UnmatchedOverride {
+                cell: self.cell_name,
+                path: path.into(),
+            });
         }
 
-        RawCell {
+        if let RawCellContentsBuilder::Primitive(binding) = &self.contents {
+            if let Some(library) = self.ctx.get_installation::<PrimitiveLibrary>() {
+                library
+                    .validate_primitive::<S>(&binding.primitive)
+                    .map_err(|source| Error::InvalidPrimitive {
+                        cell: self.cell_name.clone(),
+                        source,
+                    })?;
+            }
+        }
+
+        let contents = self.contents.build(&self.cell_name)?;
+
+        // A supply is unconnected if no other node belongs to its net.
+        let mut net_sizes = HashMap::<Node, usize>::new();
+        for root in roots.values() {
+            *net_sizes.entry(*root).or_default() += 1;
+        }
+        let unconnected = self
+            .supplies
+            .iter()
+            .filter(|supply| {
+                roots
+                    .get(&supply.node)
+                    .and_then(|root| net_sizes.get(root))
+                    .copied()
+                    .unwrap_or_default()
+                    < 2
+            })
+            .map(|supply| UnconnectedSupply {
+                node: self
+                    .node_names
+                    .get(&supply.node)
+                    .map(|name| arcstr::format!("{name}"))
+                    .unwrap_or_default(),
+                kind: supply.kind,
+                location: arcstr::format!("{}", supply.source_info),
+            })
+            .collect::<Vec<_>>();
+        if !unconnected.is_empty() {
+            return Err(Error::UnconnectedSupplies {
+                cell: self.cell_name,
+                supplies: unconnected,
+            });
+        }
+
+        let globals = self
+            .global_rails
+            .iter()
+            .map(|(kind, node)| (roots[node], ArcStr::from(kind.global_net())))
+            .collect();
+
+        Ok(RawCell {
             id: self.id,
             name: self.cell_name,
             node_names: self.node_names,
             ports: self.ports,
-            flatten: self.flatten,
+            flatten: self.flatten && !self.preserve && self.params.is_empty(),
+            preserve: self.preserve,
+            provenance: self.provenance,
+            block: self.block,
+            params: self.params,
             uf,
             roots,
-            contents: self.contents.build(),
+            node_sources,
+            globals,
+            contents,
+        })
+    }
+
+    /// Sets the supply rails of this cell.
+    ///
+    /// `rails` is typically the power IO of this cell. Supplies passed to
+    /// [`CellBuilder::connect_supplies`] or instantiated using [`CellBuilder::instantiate_supplied`]
+    /// are connected to the rail of the same [`SupplyKind`] instead of the corresponding
+    /// [global rail](CellBuilder::global_rail). The rails are also declared as
+    /// supplies (see [`CellBuilder::declare_supplies`]).
+    #[track_caller]
+    pub fn set_rails(&mut self, rails: &impl HasSupplies) {
+        let source_info = SourceInfo::from_caller();
+        for (kind, node) in rails.supplies() {
+            self.rails.insert(kind, node);
+            self.declare_supply(kind, node, source_info.clone());
+        }
+    }
+
+    /// Declares the nodes in `supplies` as supplies that must be connected.
+    ///
+    /// # Errors
+    ///
+    /// If a declared supply is not connected to any other node when the generator
+    /// completes, the cell fails to generate with an [`Error::UnconnectedSupply`].
+    #[track_caller]
+    pub fn declare_supplies(&mut self, supplies: &impl HasSupplies) {
+        let source_info = SourceInfo::from_caller();
+        for (kind, node) in supplies.supplies() {
+            self.declare_supply(kind, node, source_info.clone());
+        }
+    }
+
+    fn declare_supply(&mut self, kind: SupplyKind, node: Node, source_info: SourceInfo) {
+        self.supplies.push(DeclaredSupply {
+            kind,
+            node,
+            source_info,
+        });
+    }
+
+    /// Returns a node referring to the global rail of the given kind.
+    ///
+    /// The node is exported as the library-level global net named by
+    /// [`SupplyKind::global_net`], so it must not be connected to a port of this cell.
+    /// Testbenches can drive global rails using sources connected to this node, or tie them
+    /// to sources when netlisting.
+    #[track_caller]
+    pub fn global_rail(&mut self, kind: SupplyKind) -> Node {
+        if let Some(&node) = self.global_rails.get(&kind) {
+            return node;
+        }
+        let node = self.signal(kind.global_net(), Signal);
+        self.global_rails.insert(kind, node);
+        node
+    }
+
+    /// Connects the nodes in `supplies` to the rails of this cell.
+    ///
+    /// Supplies of a kind for which no rail has been set using [`CellBuilder::set_rails`]
+    /// are connected to the [global rail](CellBuilder::global_rail) of that kind.
+    /// All supplies are declared (see [`CellBuilder::declare_supplies`]).
+    #[track_caller]
+    pub fn connect_supplies(&mut self, supplies: &impl HasSupplies) {
+        let source_info = SourceInfo::from_caller();
+        for (kind, node) in supplies.supplies() {
+            let rail = match self.rails.get(&kind) {
+                Some(&rail) => rail,
+                None => self.global_rail(kind),
+            };
+            self.connect(node, rail);
+            self.declare_supply(kind, node, source_info.clone());
         }
     }
 
     /// Marks this cell to be flattened.
+    ///
+    /// Has no effect if the cell is [preserved](CellBuilder::preserve)
+    /// or [declares parameters](CellBuilder::declare_param).
     pub fn flatten(&mut self) {
         self.flatten = true;
     }
 
+    /// Marks this cell as preserved.
+    ///
+    /// Preserved cells are never flattened, are exported with their exact name where
+    /// possible, and are kept intact by SCIR library passes (see [`scir::Cell::set_preserve`]).
+    /// Use this for cells that must match a hand-qualified LVS or PEX macro.
+    pub fn preserve(&mut self) {
+        self.preserve = true;
+    }
+
+    /// Declares a pass-through parameter of this cell with the given default value.
+    ///
+    /// Declared parameters are exported as SCIR cell parameters (see [`scir::Cell::add_param`])
+    /// rather than being elaborated by the generator, so a single exported cell can be shared by
+    /// instances that only differ in parameter values. Instances within this cell may refer to
+    /// the parameter using [`Expr::Param`].
+    ///
+    /// Cells that declare parameters are never flattened.
+    pub fn declare_param(&mut self, name: impl Into<ArcStr>, default: impl Into<ParamValue>) {
+        self.params.insert(name.into(), Param::new(default));
+    }
+
+    /// Assigns `value` to the pass-through parameter `name` of the given instance.
+    ///
+    /// The instantiated cell must declare the parameter using [`CellBuilder::declare_param`];
+    /// otherwise, the exported SCIR library will fail validation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `inst` was not instantiated in this cell.
+    pub fn set_instance_param<B: Schematic>(
+        &mut self,
+        inst: &Instance<B>,
+        name: impl Into<ArcStr>,
+        value: impl Into<Expr>,
+    ) {
+        assert_eq!(
+            inst.parent,
+            InstancePath::new(self.id),
+            "instance was not instantiated in this cell"
+        );
+        let raw = self
+            .contents
+            .as_mut()
+            .unwrap_cell()
+            .instances
+            .iter_mut()
+            .find(|raw| raw.id == inst.id)
+            .expect("instance was not instantiated in this cell");
+        raw.params.insert(name.into(), value.into());
+    }
+
+    /// Sets the multiplier of the given instance, making it represent `m` identical
+    /// copies of its child connected in parallel.
+    ///
+    /// Only instances of primitives whose schema supports multipliers
+    /// (see [`scir::schema::Schema::multiply_primitive`]) may have a multiplier other than 1;
+    /// exporting any other multiplied instance fails with a [`ConvError::UnsupportedMultiplier`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `m` is 0 or if `inst` was not instantiated in this cell.
+    pub fn set_instance_multiplier<B: Schematic>(&mut self, inst: &Instance<B>, m: u64) {
+        assert!(m > 0, "instance multiplier must be positive");
+        assert_eq!(
+            inst.parent,
+            InstancePath::new(self.id),
+            "instance was not instantiated in this cell"
+        );
+        let raw = self
+            .contents
+            .as_mut()
+            .unwrap_cell()
+            .instances
+            .iter_mut()
+            .find(|raw| raw.id == inst.id)
+            .expect("instance was not instantiated in this cell");
+        raw.multiplier = m;
+    }
+
+    /// Instantiates a block with pass-through parameters.
+    ///
+    /// Instantiates [`PassThroughParams::base`] and assigns the values given by
+    /// [`PassThroughParams::param_values`] to the resulting instance. Blocks with the same base
+    /// share a single generated cell.
+    ///
+    /// Blocks until the base cell is generated.
+    ///
+    /// See [`CellBuilder::instantiate`] for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the base cell fails to generate or if it does not declare
+    /// one of the parameters returned by [`PassThroughParams::param_values`].
+    #[track_caller]
+    pub fn instantiate_parameterized<B: PassThroughParams<Schema = S>>(
+        &mut self,
+        block: B,
+    ) -> Result<Instance<B>> {
+        self.instantiate_parameterized_inner(block, None, SourceInfo::from_caller())
+    }
+
+    /// Instantiates a block with pass-through parameters and assigns a name to the instance.
+    ///
+    /// See [`CellBuilder::instantiate_parameterized`] and [`CellBuilder::instantiate_named`]
+    /// for details.
+    ///
+    /// # Errors
+    ///
+    /// See [`CellBuilder::instantiate_parameterized`].
+    #[track_caller]
+    pub fn instantiate_parameterized_named<B: PassThroughParams<Schema = S>>(
+        &mut self,
+        block: B,
+        name: impl Into<ArcStr>,
+    ) -> Result<Instance<B>> {
+        self.instantiate_parameterized_inner(block, Some(name.into()), SourceInfo::from_caller())
+    }
+
+    fn instantiate_parameterized_inner<B: PassThroughParams<Schema = S>>(
+        &mut self,
+        block: B,
+        name: Option<ArcStr>,
+        source_info: SourceInfo,
+    ) -> Result<Instance<B>> {
+        let (block, overrides) = self.resolve_overrides(block, name.as_deref(), Overrides::new());
+        let values = block.param_values();
+        let cell = self
+            .ctx()
+            .generate_schematic_inner(Arc::new(block.base()), overrides);
+        let inst = self.post_instantiate(cell, source_info, name);
+        let raw = inst.try_cell()?.raw.clone();
+        for (name, value) in values {
+            if !raw.params.contains_key(&name) {
+                return Err(Error::UndeclaredParam {
+                    cell: raw.name.clone(),
+                    param: name,
+                });
+            }
+            self.set_instance_param(&inst, name, Expr::Value(value));
+        }
+        Ok(inst)
+    }
+
     /// Create a new signal with the given name and hardware type.
     #[track_caller]
     pub fn signal<K: HasBundleKind<BundleKind: SchematicBundleKind>>(
@@ -306,11 +644,11 @@ impl<S: Schema + ?Sized> CellBuilder<S> {
     /// Does not block on generation. If immediate error recovery is desired,
     /// check errors before calling this function using [`CellHandle::try_cell`].
     ///
-    /// # Panics
+    /// # Errors
     ///
-    /// If the instantiated cell fails to generate, this function will eventually cause a panic after
-    /// the parent cell's generator completes. To avoid this, return errors using [`Instance::try_data`]
-    /// before your generator returns.
+    /// If the instantiated cell fails to generate, the parent cell fails with an
+    /// [`Error::Instantiation`] after its generator completes. To handle the error within
+    /// the generator instead, check [`Instance::try_data`] before your generator returns.
     #[track_caller]
     pub fn add<B: Schematic>(&mut self, cell: SchemaCellHandle<S, B>) -> Instance<B>
     where
@@ -328,20 +666,47 @@ impl<S: Schema + ?Sized> CellBuilder<S> {
     /// Spawns a thread that generates the underlying cell. If immediate error
     /// recovery is desired, use the generate and add workflow mentioned above.
     ///
-    /// # Panics
+    /// # Errors
     ///
-    /// If the instantiated cell fails to generate, this function will eventually cause a panic after
-    /// the parent cell's generator completes. To avoid this, return errors using [`Instance::try_data`]
-    /// before your generator returns.
-    ///
-    /// If an error is not returned from the enclosing generator, but this function returns
-    /// an error, the enclosing generator will panic since the instantiation irrecoverably failed.
+    /// If the instantiated cell fails to generate, the parent cell fails with an
+    /// [`Error::Instantiation`] after its generator completes. To handle the error within
+    /// the generator instead, check [`Instance::try_data`] before your generator returns.
     #[track_caller]
     pub fn instantiate<B: Schematic<Schema = S>>(&mut self, block: B) -> Instance<B> {
-        let cell = self.ctx().generate_schematic(block);
+        self.instantiate_with_overrides(block, Overrides::new())
+    }
+
+    /// Instantiates a block, overriding blocks instantiated below it.
+    ///
+    /// Paths in `overrides` are relative to the instantiated block. The instance is not named,
+    /// so overrides inherited from the ancestors of this cell cannot refer to it.
+    ///
+    /// See [`CellBuilder::instantiate`] and [`Overrides`] for details.
+    #[track_caller]
+    pub fn instantiate_with_overrides<B: Schematic<Schema = S>>(
+        &mut self,
+        block: B,
+        overrides: Overrides,
+    ) -> Instance<B> {
+        let (block, overrides) = self.resolve_overrides(block, None, overrides);
+        let cell = self.ctx().generate_schematic_inner(block, overrides);
         self.post_instantiate(cell, SourceInfo::from_caller(), None)
     }
 
+    /// Instantiates a schematic view of the given block and connects its supplies
+    /// to the rails of this cell.
+    ///
+    /// See [`CellBuilder::instantiate`] and [`CellBuilder::connect_supplies`] for details.
+    #[track_caller]
+    pub fn instantiate_supplied<B: Schematic<Schema = S>>(&mut self, block: B) -> Instance<B>
+    where
+        IoTerminalBundle<B>: HasSupplies,
+    {
+        let inst = self.instantiate(block);
+        self.connect_supplies(inst.io());
+        inst
+    }
+
     /// Instantiates a block and assigns a name to the instance.
     ///
     /// See [`CellBuilder::instantiate`] for details.
@@ -353,8 +718,26 @@ impl<S: Schema + ?Sized> CellBuilder<S> {
         block: B,
         name: impl Into<ArcStr>,
     ) -> Instance<B> {
-        let cell = self.ctx().generate_schematic(block);
-        self.post_instantiate(cell, SourceInfo::from_caller(), Some(name.into()))
+        self.instantiate_named_with_overrides(block, name, Overrides::new())
+    }
+
+    /// Instantiates a block with the given name, overriding blocks instantiated below it.
+    ///
+    /// Overrides inherited from the ancestors of this cell take precedence over `overrides`.
+    ///
+    /// See [`CellBuilder::instantiate_named`] and [`CellBuilder::instantiate_with_overrides`]
+    /// for details.
+    #[track_caller]
+    pub fn instantiate_named_with_overrides<B: Schematic<Schema = S>>(
+        &mut self,
+        block: B,
+        name: impl Into<ArcStr>,
+        overrides: Overrides,
+    ) -> Instance<B> {
+        let name = name.into();
+        let (block, overrides) = self.resolve_overrides(block, Some(name.as_str()), overrides);
+        let cell = self.ctx().generate_schematic_inner(block, overrides);
+        self.post_instantiate(cell, SourceInfo::from_caller(), Some(name))
     }
 
     /// Instantiates a schematic view of the given block, blocking on generator for underlying
@@ -362,10 +745,10 @@ impl<S: Schema + ?Sized> CellBuilder<S> {
     ///
     /// See [`SubCellBuilder::instantiate`] for details.
     ///
-    /// # Panics
+    /// # Errors
     ///
     /// If an error is not returned from the enclosing generator, but this function returns
-    /// an error, the enclosing generator will panic since the instantiation irrecoverably failed.
+    /// an error, the enclosing cell will fail to generate since the instantiation irrecoverably failed.
     #[track_caller]
     pub fn instantiate_blocking<B: Schematic<Schema = S>>(
         &mut self,
@@ -403,6 +786,41 @@ impl<S: Schema + ?Sized> CellBuilder<S> {
         inst
     }
 
+    /// Applies the overrides of this cell to an instance of `block` named `name`.
+    ///
+    /// Unnamed instances cannot be overridden, so `block` and `overrides` are returned
+    /// unchanged if `name` is [`None`].
+    ///
+    /// Returns the block to instantiate and the overrides to apply below the instance.
+    /// Flags a fatal error if the instance is overridden by a block of a different type.
+    #[track_caller]
+    fn resolve_overrides<B: Block>(
+        &mut self,
+        block: B,
+        name: Option<&str>,
+        mut overrides: Overrides,
+    ) -> (Arc<B>, Overrides) {
+        let Some(name) = name else {
+            return (Arc::new(block), overrides);
+        };
+        let (replacement, child) = self.overrides.take(name);
+        overrides.extend(child);
+        let block = match replacement.map(|block| block.downcast::<B>()) {
+            Some(Ok(block)) => block,
+            Some(Err(_)) => {
+                tracing::error!(
+                    sinfo = ?SourceInfo::from_caller(),
+                    instance = name,
+                    "instance overridden by a block of a different type",
+                );
+                self.fatal_error = true;
+                Arc::new(block)
+            }
+            None => Arc::new(block),
+        };
+        (block, overrides)
+    }
+
     /// Creates nodes for the newly-instantiated block's IOs and adds the raw instance.
     fn post_instantiate<B: Schematic>(
         &mut self,
@@ -422,7 +840,7 @@ impl<S: Schema + ?Sized> CellBuilder<S> {
 
         let (nodes, io_data) =
             self.node_ctx
-                .instantiate_directed(&io, NodePriority::Auto, source_info);
+                .instantiate_directed(&io, NodePriority::Auto, source_info.clone());
 
         let names = <<B as Block>::Io as HasBundleKind>::kind(&io)
             .flat_names(Some(inst_name.clone().into()));
@@ -444,18 +862,15 @@ impl<S: Schema + ?Sized> CellBuilder<S> {
         cell_contents.instances.push(RawInstanceBuilder {
             id: inst.id,
             name: inst_name.clone(),
-            // name: arcstr::literal!("unnamed"),
+            cell_name: inst.cell.block.name(),
+            source_info,
             connections: nodes,
+            params: IndexMap::new(),
+            multiplier: 1,
             child: cell.handle.map(|handle| match handle {
-                Ok(Ok(SchemaCellCacheValue { raw, .. })) => Ok(raw.clone()),
-                Ok(Err(e)) => {
-                    tracing::error!("{:?}", e);
-                    panic!("cell generator failed")
-                }
-                Err(e) => {
-                    tracing::error!("{:?}", e);
-                    panic!("cache failed")
-                }
+                Ok(Ok(SchemaCellCacheValue { raw, .. })) => Ok(Ok(raw.clone())),
+                Ok(Err(e)) => Ok(Err(e.clone())),
+                Err(e) => Err(e),
             }),
         });
 
@@ -535,11 +950,11 @@ impl<S1: FromSchema<S2>, S2: Schema + ?Sized> SubCellBuilder<'_, S1, S2> {
     /// Does not block on generation. If immediate error recovery is desired,
     /// check errors before calling this function using [`CellHandle::try_cell`].
     ///
-    /// # Panics
+    /// # Errors
     ///
-    /// If the instantiated cell fails to generate, this function will eventually cause a panic after
-    /// the parent cell's generator completes. To avoid this, return errors using [`Instance::try_data`]
-    /// before your generator returns.
+    /// If the instantiated cell fails to generate, the parent cell fails with an
+    /// [`Error::Instantiation`] after its generator completes. To handle the error within
+    /// the generator instead, check [`Instance::try_data`] before your generator returns.
     #[track_caller]
     pub fn add<B: Schematic<Schema = S2>>(&mut self, cell: SchemaCellHandle<S1, B>) -> Instance<B> {
         self.0.add(cell)
@@ -554,17 +969,15 @@ impl<S1: FromSchema<S2>, S2: Schema + ?Sized> SubCellBuilder<'_, S1, S2> {
     /// Spawns a thread that generates the underlying cell. If immediate error
     /// recovery is desired, use the generate and add workflow mentioned above.
     ///
-    /// # Panics
-    ///
-    /// If the instantiated cell fails to generate, this function will eventually cause a panic after
-    /// the parent cell's generator completes. To avoid this, return errors using [`Instance::try_data`]
-    /// before your generator returns.
+    /// # Errors
     ///
-    /// If an error is not returned from the enclosing generator, but this function returns
-    /// an error, the enclosing generator will panic since the instantiation irrecoverably failed.
+    /// If the instantiated cell fails to generate, the parent cell fails with an
+    /// [`Error::Instantiation`] after its generator completes. To handle the error within
+    /// the generator instead, check [`Instance::try_data`] before your generator returns.
     #[track_caller]
     pub fn instantiate<B: Schematic<Schema = S2>>(&mut self, block: B) -> Instance<B> {
-        let cell = self.ctx().generate_cross_schematic(block);
+        let (block, overrides) = self.0.resolve_overrides(block, None, Overrides::new());
+        let cell = self.ctx().generate_cross_schematic_inner(block, overrides);
         self.post_instantiate(cell, SourceInfo::from_caller(), None)
     }
 
@@ -579,8 +992,12 @@ impl<S1: FromSchema<S2>, S2: Schema + ?Sized> SubCellBuilder<'_, S1, S2> {
         block: B,
         name: impl Into<ArcStr>,
     ) -> Instance<B> {
-        let cell = self.ctx().generate_cross_schematic(block);
-        self.post_instantiate(cell, SourceInfo::from_caller(), Some(name.into()))
+        let name = name.into();
+        let (block, overrides) =
+            self.0
+                .resolve_overrides(block, Some(name.as_str()), Overrides::new());
+        let cell = self.ctx().generate_cross_schematic_inner(block, overrides);
+        self.post_instantiate(cell, SourceInfo::from_caller(), Some(name))
     }
 
     /// Instantiates a schematic view of the given block, blocking on generator for underlying
@@ -588,10 +1005,10 @@ impl<S1: FromSchema<S2>, S2: Schema + ?Sized> SubCellBuilder<'_, S1, S2> {
     ///
     /// See [`SubCellBuilder::instantiate`] for details.
     ///
-    /// # Panics
+    /// # Errors
     ///
     /// If an error is not returned from the enclosing generator, but this function returns
-    /// an error, the enclosing generator will panic since the instantiation irrecoverably failed.
+    /// an error, the enclosing cell will fail to generate since the instantiation irrecoverably failed.
     #[track_caller]
     pub fn instantiate_blocking<B: Schematic<Schema = S2>>(
         &mut self,
@@ -798,6 +1215,17 @@ impl<S: Schema, B: Schematic> SchemaCellHandle<S, B> {
         self.cell.cell()
     }
 
+    /// Returns the cached value of this cell, blocking until generation completes.
+    ///
+    /// Returns an error if the cell or any of its instances failed to generate.
+    pub(crate) fn try_value(&self) -> Result<&SchemaCellCacheValue<S, B>> {
+        self.handle
+            .try_get()
+            .map_err(|e| Error::CacheError(e.clone()))?
+            .as_ref()
+            .map_err(|e| e.clone())
+    }
+
     /// Returns the raw cell.
     #[doc(hidden)]
     pub fn raw(&self) -> Arc<RawCell<S>> {
@@ -963,6 +1391,23 @@ impl<T: Schematic> NestedInstance<T> {
         self.0.io().nested_view(&self.0.parent)
     }
 
+    /// The terminals of this instance, along with the names of their ports.
+    ///
+    /// Buses are expanded, so each terminal is a single bit of a port.
+    pub fn terminals(&self) -> Vec<(NameBuf, NestedTerminal)> {
+        let io = self.0.io();
+        let names = io.kind().flat_names(None);
+        let terminals = Flatten::<Terminal>::flatten_vec(io);
+        names
+            .into_iter()
+            .zip(
+                terminals
+                    .iter()
+                    .map(|terminal| terminal.nested_view(&self.0.parent)),
+            )
+            .collect()
+    }
+
     /// Tries to access the underlying cell data.
     ///
     /// Returns an error if one was thrown during generation.
@@ -994,6 +1439,8 @@ impl<T: Schematic> NestedInstance<T> {
 #[derive(Debug)]
 pub struct SchematicContext {
     pub(crate) next_id: CellId,
+    /// IDs allocated in reproducible contexts.
+    pub(crate) stable_ids: StableIds,
     /// Cache from [`CellCacheKey`] and [`ConvCacheKey`]
     /// to `Result<(Arc<RawCell>, Arc<Cell>)>`.
     pub(crate) cell_cache: TypeCache,
@@ -1003,6 +1450,7 @@ impl Default for SchematicContext {
     fn default() -> Self {
         Self {
             next_id: CellId(0),
+            stable_ids: Default::default(),
             cell_cache: Default::default(),
         }
     }
@@ -1032,6 +1480,8 @@ impl<B: Schematic> Clone for CellMetadata<B> {
 
 pub(crate) struct CellCacheKey<B, S: ?Sized> {
     pub(crate) block: Arc<B>,
+    /// Overrides of blocks instantiated below the cell.
+    pub(crate) overrides: Overrides,
     pub(crate) phantom: PhantomData<S>,
 }
 
@@ -1039,6 +1489,7 @@ impl<B, S: ?Sized> Clone for CellCacheKey<B, S> {
     fn clone(&self) -> Self {
         Self {
             block: self.block.clone(),
+            overrides: self.overrides.clone(),
             phantom: PhantomData,
         }
     }
@@ -1046,7 +1497,7 @@ impl<B, S: ?Sized> Clone for CellCacheKey<B, S> {
 
 impl<B: PartialEq, S: ?Sized> PartialEq for CellCacheKey<B, S> {
     fn eq(&self, other: &Self) -> bool {
-        self.block.eq(&other.block)
+        self.block.eq(&other.block) && self.overrides.eq(&other.overrides)
     }
 }
 
@@ -1054,7 +1505,8 @@ impl<B: Eq, S: ?Sized> Eq for CellCacheKey<B, S> {}
 
 impl<B: Hash, S: ?Sized> Hash for CellCacheKey<B, S> {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.block.hash(state)
+        self.block.hash(state);
+        self.overrides.hash(state);
     }
 }
 
@@ -1064,7 +1516,8 @@ pub(crate) type ConvCacheKey<B, S1, S2> = CellCacheKey<B, (PhantomData<S1>, S2)>
 /// A path to an instance from a top level cell.
 ///
 /// Inexpensive to clone as it only clones an ID and a reference counted pointer.
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(into = "SerializedInstancePath", from = "SerializedInstancePath")]
 pub struct InstancePath {
     /// The ID of the top level cell that this path is relative to.
     pub(crate) top: CellId,
@@ -1077,6 +1530,34 @@ pub struct InstancePath {
     pub(crate) path: PathTree<InstanceId>,
 }
 
+/// The serialized form of an [`InstancePath`].
+#[derive(Serialize, Deserialize)]
+struct SerializedInstancePath {
+    top: CellId,
+    bot: Option<CellId>,
+    path: Vec<InstanceId>,
+}
+
+impl From<InstancePath> for SerializedInstancePath {
+    fn from(value: InstancePath) -> Self {
+        Self {
+            top: value.top,
+            bot: value.bot,
+            path: value.path.iter().copied().collect(),
+        }
+    }
+}
+
+impl From<SerializedInstancePath> for InstancePath {
+    fn from(value: SerializedInstancePath) -> Self {
+        Self {
+            top: value.top,
+            bot: value.bot,
+            path: value.path.into_iter().collect(),
+        }
+    }
+}
+
 impl InstancePath {
     pub(crate) fn new(top: CellId) -> Self {
         Self {
@@ -1144,8 +1625,16 @@ impl InstancePath {
 pub(crate) struct RawInstanceBuilder<S: Schema + ?Sized> {
     id: InstanceId,
     name: ArcStr,
+    /// The name of the instantiated block.
+    cell_name: ArcStr,
+    /// The location at which the block was instantiated.
+    source_info: SourceInfo,
     connections: Vec<Node>,
-    child: CacheHandle<Arc<RawCell<S>>>,
+    /// Values assigned to the pass-through parameters of the child.
+    params: IndexMap<ArcStr, Expr>,
+    /// The number of copies of the child in parallel.
+    multiplier: u64,
+    child: CacheHandle<Result<Arc<RawCell<S>>>>,
 }
 
 impl<S: Schema<Primitive = impl std::fmt::Debug> + ?Sized> std::fmt::Debug
@@ -1156,19 +1645,40 @@ impl<S: Schema<Primitive = impl std::fmt::Debug> + ?Sized> std::fmt::Debug
         let _ = builder.field("id", &self.id);
         let _ = builder.field("name", &self.name);
         let _ = builder.field("connections", &self.connections);
+        let _ = builder.field("params", &self.params);
+        let _ = builder.field("multiplier", &self.multiplier);
         let _ = builder.field("child", &self.child);
         builder.finish()
     }
 }
 
 impl<S: Schema + ?Sized> RawInstanceBuilder<S> {
-    fn build(self) -> RawInstance<S> {
-        RawInstance {
+    /// Builds the instance, blocking until the instantiated cell has been generated.
+    ///
+    /// Generation errors are wrapped in an [`Error::Instantiation`] that records
+    /// the instance's location within `parent`.
+    fn build(self, parent: &ArcStr) -> Result<RawInstance<S>> {
+        let child = self
+            .child
+            .try_get()
+            .map_err(|e| Error::CacheError(e.clone()))
+            .and_then(|child| child.clone())
+            .map_err(|e| Error::Instantiation {
+                parent: parent.clone(),
+                instance: self.name.clone(),
+                cell: self.cell_name.clone(),
+                location: arcstr::format!("{}", self.source_info),
+                source: Arc::new(e),
+            })?;
+        Ok(RawInstance {
             id: self.id,
             name: self.name,
+            source_info: self.source_info,
             connections: self.connections,
-            child: self.child.get().clone(),
-        }
+            params: self.params,
+            multiplier: self.multiplier,
+            child,
+        })
     }
 }
 
@@ -1177,7 +1687,11 @@ impl<S: Schema + ?Sized> RawInstanceBuilder<S> {
 pub(crate) struct RawInstance<S: Schema + ?Sized> {
     id: InstanceId,
     name: ArcStr,
+    /// The location at which the instance was created.
+    source_info: SourceInfo,
     connections: Vec<Node>,
+    params: IndexMap<ArcStr, Expr>,
+    multiplier: u64,
     child: Arc<RawCell<S>>,
 }
 
@@ -1186,7 +1700,10 @@ impl<S: Schema<Primitive = impl std::fmt::Debug> + ?Sized> std::fmt::Debug for R
         let mut builder = f.debug_struct("RawInstance");
         let _ = builder.field("id", &self.id);
         let _ = builder.field("name", &self.name);
+        let _ = builder.field("source_info", &self.source_info);
         let _ = builder.field("connections", &self.connections);
+        let _ = builder.field("params", &self.params);
+        let _ = builder.field("multiplier", &self.multiplier);
         let _ = builder.field("child", &self.child);
         builder.finish()
     }
@@ -1197,7 +1714,10 @@ impl<S: Schema + ?Sized> Clone for RawInstance<S> {
         Self {
             id: self.id,
             name: self.name.clone(),
+            source_info: self.source_info.clone(),
             connections: self.connections.clone(),
+            params: self.params.clone(),
+            multiplier: self.multiplier,
             child: self.child.clone(),
         }
     }
@@ -1208,7 +1728,10 @@ impl<S: Schema + ?Sized> RawInstance<S> {
         Ok(RawInstance {
             id: self.id,
             name: self.name,
+            source_info: self.source_info,
             connections: self.connections,
+            params: self.params,
+            multiplier: self.multiplier,
             child: Arc::new((*self.child).clone().convert_schema()?),
         })
     }
@@ -1228,6 +1751,18 @@ pub struct RawCell<S: Schema + ?Sized> {
     roots: HashMap<Node, Node>,
     /// Whether this cell should be flattened when being exported.
     flatten: bool,
+    /// Whether this cell should be kept intact when being exported.
+    preserve: bool,
+    /// The provenance of the block that generated this cell.
+    provenance: Option<Provenance>,
+    /// The type name of the block that generated this cell.
+    block: ArcStr,
+    /// The location at which each root node was created, if known.
+    node_sources: HashMap<Node, SourceInfo>,
+    /// The global net referred to by each root node, if any.
+    globals: HashMap<Node, ArcStr>,
+    /// The pass-through parameters declared by this cell.
+    params: IndexMap<ArcStr, Param>,
     contents: RawCellContents<S>,
 }
 
@@ -1241,8 +1776,12 @@ impl<S: Schema<Primitive = impl std::fmt::Debug> + ?Sized> std::fmt::Debug for R
         let _ = builder.field("uf", &self.uf);
         let _ = builder.field("node_names", &self.node_names);
         let _ = builder.field("roots", &self.roots);
+        let _ = builder.field("globals", &self.globals);
         let _ = builder.field("contents", &self.contents);
         let _ = builder.field("flatten", &self.flatten);
+        let _ = builder.field("preserve", &self.preserve);
+        let _ = builder.field("provenance", &self.provenance);
+        let _ = builder.field("params", &self.params);
         builder.finish()
     }
 }
@@ -1258,6 +1797,12 @@ impl<S: Schema + ?Sized> Clone for RawCell<S> {
             roots: self.roots.clone(),
             contents: self.contents.clone(),
             flatten: self.flatten,
+            preserve: self.preserve,
+            provenance: self.provenance.clone(),
+            block: self.block.clone(),
+            node_sources: self.node_sources.clone(),
+            globals: self.globals.clone(),
+            params: self.params.clone(),
         }
     }
 }
@@ -1272,6 +1817,12 @@ impl<S: Schema + ?Sized> RawCell<S> {
             node_names: self.node_names,
             roots: self.roots,
             flatten: self.flatten,
+            preserve: self.preserve,
+            provenance: self.provenance,
+            block: self.block,
+            node_sources: self.node_sources,
+            globals: self.globals,
+            params: self.params,
             contents: self.contents.convert_schema()?,
         })
     }
@@ -1282,13 +1833,13 @@ pub(crate) type RawCellContentsBuilder<S> =
     RawCellKind<RawCellInnerBuilder<S>, ScirBinding<S>, PrimitiveBinding<S>, ConvertedPrimitive<S>>;
 
 impl<S: Schema + ?Sized> RawCellContentsBuilder<S> {
-    fn build(self) -> RawCellContents<S> {
-        match self {
-            RawCellContentsBuilder::Cell(b) => RawCellContents::Cell(b.build()),
+    fn build(self, cell_name: &ArcStr) -> Result<RawCellContents<S>> {
+        Ok(match self {
+            RawCellContentsBuilder::Cell(b) => RawCellContents::Cell(b.build(cell_name)?),
             RawCellContentsBuilder::Scir(s) => RawCellContents::Scir(s),
             RawCellContentsBuilder::Primitive(s) => RawCellContents::Primitive(s),
             RawCellContentsBuilder::ConvertedPrimitive(s) => RawCellContents::ConvertedPrimitive(s),
-        }
+        })
     }
 }
 
@@ -1481,14 +2032,14 @@ impl<S: Schema + ?Sized> Default for RawCellInnerBuilder<S> {
 }
 
 impl<S: Schema + ?Sized> RawCellInnerBuilder<S> {
-    fn build(self) -> RawCellInner<S> {
-        RawCellInner {
+    fn build(self, cell_name: &ArcStr) -> Result<RawCellInner<S>> {
+        Ok(RawCellInner {
             instances: self
                 .instances
                 .into_iter()
-                .map(|builder| builder.build())
-                .collect(),
-        }
+                .map(|builder| builder.build(cell_name))
+                .collect::<Result<_>>()?,
+        })
     }
 }
 
@@ -1613,6 +2164,10 @@ impl CellId {
         let next = self.0.checked_add(1).expect("integer overflow");
         *self = CellId(next)
     }
+
+    pub(crate) fn from_stable_hash(hash: u64) -> Self {
+        CellId(hash)
+    }
 }
 
 /// A cell-wide unique identifier for an instance.
@@ -0,0 +1,151 @@
+//! Hierarchical overrides of instantiated blocks.
+
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::fmt::Formatter;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+use arcstr::ArcStr;
+
+use crate::block::Block;
+
+/// A set of overrides of blocks instantiated below a cell.
+///
+/// Each override replaces the block instantiated at a path of instance names with
+/// another block of the same type, allowing the parameters of a descendant to be changed
+/// without defining a new block type for each of its ancestors. Paths are relative to
+/// the cell being instantiated, so the first segment names an instance within that cell.
+///
+/// Overrides are applied when the instance they refer to is created using one of the
+/// `instantiate_named` methods of [`CellBuilder`](super::CellBuilder). Only explicitly
+/// named instances can be overridden, so that paths do not depend on the order in which
+/// instances are created. A cell fails to generate with an
+/// [`Error::UnmatchedOverride`](crate::error::Error::UnmatchedOverride) if any of its
+/// overrides does not refer to a named instance within it.
+///
+/// If the same path is overridden both by a parent and by a descendant,
+/// the parent's override takes precedence.
+///
+/// Overrides are part of the key under which generated cells are cached, so a block
+/// instantiated with overrides is generated separately from the same block without them.
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct Overrides {
+    entries: BTreeMap<Vec<ArcStr>, Override>,
+}
+
+impl std::fmt::Debug for Overrides {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.paths()).finish()
+    }
+}
+
+impl Overrides {
+    /// Creates an empty set of overrides.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces the block instantiated at `path` with `block`.
+    ///
+    /// Replaces any existing override of the same path.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `path` is empty.
+    pub fn set<B: Block>(
+        &mut self,
+        path: impl IntoIterator<Item = impl Into<ArcStr>>,
+        block: B,
+    ) -> &mut Self {
+        let path = path.into_iter().map(Into::into).collect::<Vec<_>>();
+        assert!(!path.is_empty(), "override path must not be empty");
+        self.entries.insert(path, Override(Arc::new(block)));
+        self
+    }
+
+    /// Returns the number of overridden paths.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if no paths are overridden.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Removes and returns the overrides that refer to the instance named `name`.
+    ///
+    /// Returns the block overriding the instance itself, if any, and the overrides that apply
+    /// below the instance, relative to that instance.
+    pub(crate) fn take(&mut self, name: &str) -> (Option<Arc<dyn Any + Send + Sync>>, Self) {
+        let mut block = None;
+        let mut child = Self::new();
+        self.entries.retain(|path, entry| {
+            if path[0] != name {
+                return true;
+            }
+            if path.len() == 1 {
+                block = Some(entry.0.clone().into_any());
+            } else {
+                child.entries.insert(path[1..].to_vec(), entry.clone());
+            }
+            false
+        });
+        (block, child)
+    }
+
+    /// Returns the overridden paths, with elements joined by `.`.
+    pub(crate) fn paths(&self) -> impl Iterator<Item = String> + '_ {
+        self.entries.keys().map(|path| path.join("."))
+    }
+
+    /// Adds all overrides in `outer`, replacing existing overrides of the same paths.
+    pub(crate) fn extend(&mut self, outer: Overrides) {
+        self.entries.extend(outer.entries);
+    }
+}
+
+/// A type-erased block used as an override.
+#[derive(Clone)]
+struct Override(Arc<dyn DynBlock>);
+
+impl PartialEq for Override {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.dyn_eq(other.0.as_any())
+    }
+}
+
+impl Eq for Override {}
+
+impl Hash for Override {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.as_any().type_id().hash(state);
+        self.0.dyn_hash(state);
+    }
+}
+
+trait DynBlock: Send + Sync {
+    fn as_any(&self) -> &dyn Any;
+    fn into_any(self: Arc<Self>) -> Arc<dyn Any + Send + Sync>;
+    fn dyn_eq(&self, other: &dyn Any) -> bool;
+    fn dyn_hash(&self, state: &mut dyn Hasher);
+}
+
+impl<B: Block> DynBlock for B {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn into_any(self: Arc<Self>) -> Arc<dyn Any + Send + Sync> {
+        self
+    }
+
+    fn dyn_eq(&self, other: &dyn Any) -> bool {
+        other.downcast_ref::<B>().is_some_and(|other| self == other)
+    }
+
+    fn dyn_hash(&self, mut state: &mut dyn Hasher) {
+        self.hash(&mut state);
+    }
+}
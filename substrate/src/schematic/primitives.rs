@@ -0,0 +1,359 @@
+//! Libraries of the primitive devices available in a PDK.
+//!
+//! A PDK describes the devices it provides, along with their ports and parameters,
+//! using a [`PrimitiveLibrary`]. Once the library is installed in a
+//! [`Context`](crate::context::Context), the primitives of generated cells are checked
+//! against it, so that invalid parameters are reported when a cell is generated
+//! rather than by the simulator.
+
+use std::any::{Any, TypeId};
+use std::fmt::Display;
+
+use arcstr::ArcStr;
+use indexmap::IndexMap;
+use rust_decimal::Decimal;
+use scir::mapping::PrimitiveParts;
+use scir::ParamValue;
+
+use crate::context::Installation;
+use crate::schematic::schema::Schema;
+
+/// A schema whose primitives can be checked against a [`PrimitiveLibrary`].
+pub trait DescribePrimitive: Schema {
+    /// Describes the kind, ports, and parameters of `primitive`.
+    ///
+    /// Returns [`None`] if `primitive` should not be checked, such as
+    /// for primitives whose parameters are already checked by their type.
+    fn describe_primitive(primitive: &<Self as Schema>::Primitive) -> Option<PrimitiveParts>;
+}
+
+/// The allowed values of a primitive parameter.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ParamSpec {
+    default: Option<ParamValue>,
+    min: Option<Decimal>,
+    max: Option<Decimal>,
+}
+
+impl ParamSpec {
+    /// Creates a specification for a parameter that must be provided.
+    pub fn required() -> Self {
+        Self::default()
+    }
+
+    /// Creates a specification for an optional parameter with the given default value.
+    pub fn with_default(default: impl Into<ParamValue>) -> Self {
+        Self {
+            default: Some(default.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the minimum allowed value of the parameter, inclusive.
+    pub fn min(mut self, min: Decimal) -> Self {
+        self.min = Some(min);
+        self
+    }
+
+    /// Sets the maximum allowed value of the parameter, inclusive.
+    pub fn max(mut self, max: Decimal) -> Self {
+        self.max = Some(max);
+        self
+    }
+
+    /// The value used by the simulator if the parameter is not provided.
+    ///
+    /// Parameters without a default value are required.
+    #[inline]
+    pub fn default_value(&self) -> Option<&ParamValue> {
+        self.default.as_ref()
+    }
+
+    /// Returns `true` if the parameter must be provided.
+    #[inline]
+    pub fn is_required(&self) -> bool {
+        self.default.is_none()
+    }
+
+    /// The allowed range of the parameter's value.
+    ///
+    /// A parameter with a minimum or maximum must have a numeric value.
+    #[inline]
+    pub fn range(&self) -> ParamRange {
+        ParamRange {
+            min: self.min,
+            max: self.max,
+        }
+    }
+}
+
+/// An inclusive range of numeric parameter values.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ParamRange {
+    /// The minimum value, if any.
+    pub min: Option<Decimal>,
+    /// The maximum value, if any.
+    pub max: Option<Decimal>,
+}
+
+impl ParamRange {
+    /// Returns `true` if the range does not constrain values.
+    pub fn is_unbounded(&self) -> bool {
+        self.min.is_none() && self.max.is_none()
+    }
+
+    /// Returns `true` if `value` lies within the range.
+    pub fn contains(&self, value: Decimal) -> bool {
+        self.min.is_none_or(|min| value >= min) && self.max.is_none_or(|max| value <= max)
+    }
+}
+
+impl Display for ParamRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.min, self.max) {
+            (Some(min), Some(max)) => write!(f, "[{min}, {max}]"),
+            (Some(min), None) => write!(f, "[{min}, inf)"),
+            (None, Some(max)) => write!(f, "(-inf, {max}]"),
+            (None, None) => write!(f, "(-inf, inf)"),
+        }
+    }
+}
+
+/// A primitive device provided by a PDK.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PrimitiveSpec {
+    name: ArcStr,
+    ports: Vec<ArcStr>,
+    params: IndexMap<ArcStr, ParamSpec>,
+}
+
+impl PrimitiveSpec {
+    /// Creates a new [`PrimitiveSpec`] with the given name and ordered ports.
+    pub fn new(
+        name: impl Into<ArcStr>,
+        ports: impl IntoIterator<Item = impl Into<ArcStr>>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            ports: ports.into_iter().map(|port| port.into()).collect(),
+            params: IndexMap::new(),
+        }
+    }
+
+    /// Adds a parameter to the primitive.
+    pub fn param(mut self, name: impl Into<ArcStr>, spec: ParamSpec) -> Self {
+        self.params.insert(name.into(), spec);
+        self
+    }
+
+    /// The name of the primitive, such as the name of its device model or subcircuit.
+    #[inline]
+    pub fn name(&self) -> &ArcStr {
+        &self.name
+    }
+
+    /// The ordered ports of the primitive.
+    #[inline]
+    pub fn ports(&self) -> &[ArcStr] {
+        &self.ports
+    }
+
+    /// The parameters accepted by the primitive.
+    #[inline]
+    pub fn params(&self) -> impl Iterator<Item = (&ArcStr, &ParamSpec)> {
+        self.params.iter()
+    }
+
+    /// Gets the specification of the parameter with the given name.
+    #[inline]
+    pub fn param_spec(&self, name: &str) -> Option<&ParamSpec> {
+        self.params.get(name)
+    }
+
+    /// Checks the ports and parameters of `primitive` against this specification.
+    pub fn validate(&self, primitive: &PrimitiveParts) -> Result<(), PrimitiveError> {
+        if primitive.ports != self.ports {
+            return Err(PrimitiveError::PortMismatch {
+                primitive: self.name.clone(),
+                expected: self.ports.clone(),
+                found: primitive.ports.clone(),
+            });
+        }
+
+        for (param, value) in primitive.params.iter() {
+            let spec = self
+                .params
+                .get(param)
+                .ok_or_else(|| PrimitiveError::UnknownParam {
+                    primitive: self.name.clone(),
+                    param: param.clone(),
+                })?;
+            let range = spec.range();
+            if range.is_unbounded() {
+                continue;
+            }
+            let value = value
+                .get_numeric()
+                .ok_or_else(|| PrimitiveError::NotNumeric {
+                    primitive: self.name.clone(),
+                    param: param.clone(),
+                })?;
+            if !range.contains(*value) {
+                return Err(PrimitiveError::OutOfRange {
+                    primitive: self.name.clone(),
+                    param: param.clone(),
+                    value: *value,
+                    range,
+                });
+            }
+        }
+
+        if let Some((param, _)) = self.params.iter().find(|(param, spec)| {
+            spec.is_required() && !primitive.params.iter().any(|(name, _)| name == *param)
+        }) {
+            return Err(PrimitiveError::MissingParam {
+                primitive: self.name.clone(),
+                param: param.clone(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// An error indicating that a primitive does not match its [`PrimitiveSpec`].
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum PrimitiveError {
+    /// The primitive has different ports than its specification.
+    #[error("primitive `{primitive}` has ports {found:?}, expected {expected:?}")]
+    PortMismatch {
+        /// The name of the primitive.
+        primitive: ArcStr,
+        /// The ports in the specification of the primitive.
+        expected: Vec<ArcStr>,
+        /// The ports of the primitive.
+        found: Vec<ArcStr>,
+    },
+    /// A parameter is not accepted by the primitive.
+    #[error("primitive `{primitive}` has no parameter `{param}`")]
+    UnknownParam {
+        /// The name of the primitive.
+        primitive: ArcStr,
+        /// The name of the parameter.
+        param: ArcStr,
+    },
+    /// A required parameter was not provided.
+    #[error("primitive `{primitive}` is missing required parameter `{param}`")]
+    MissingParam {
+        /// The name of the primitive.
+        primitive: ArcStr,
+        /// The name of the parameter.
+        param: ArcStr,
+    },
+    /// A parameter with a range has a non-numeric value.
+    #[error("parameter `{param}` of primitive `{primitive}` must be numeric")]
+    NotNumeric {
+        /// The name of the primitive.
+        primitive: ArcStr,
+        /// The name of the parameter.
+        param: ArcStr,
+    },
+    /// A parameter value lies outside of the allowed range.
+    #[error(
+        "parameter `{param}` of primitive `{primitive}` is {value}, expected a value in {range}"
+    )]
+    OutOfRange {
+        /// The name of the primitive.
+        primitive: ArcStr,
+        /// The name of the parameter.
+        param: ArcStr,
+        /// The value of the parameter.
+        value: Decimal,
+        /// The allowed range of the parameter.
+        range: ParamRange,
+    },
+}
+
+/// A registry of the primitive devices available in a PDK.
+///
+/// A PDK typically installs its library in its
+/// [`post_install`](Installation::post_install) hook.
+/// When installed in a context, the primitives of every generated cell of schema `S`
+/// are checked against the library. Primitives whose names are not in the library
+/// are not checked.
+#[derive(Clone)]
+pub struct PrimitiveLibrary {
+    schema: TypeId,
+    describe: fn(&dyn Any) -> Option<PrimitiveParts>,
+    primitives: IndexMap<ArcStr, PrimitiveSpec>,
+}
+
+impl std::fmt::Debug for PrimitiveLibrary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PrimitiveLibrary")
+            .field("primitives", &self.primitives)
+            .finish_non_exhaustive()
+    }
+}
+
+fn describe_any<S: DescribePrimitive + ?Sized>(primitive: &dyn Any) -> Option<PrimitiveParts> {
+    primitive
+        .downcast_ref::<<S as Schema>::Primitive>()
+        .and_then(S::describe_primitive)
+}
+
+impl PrimitiveLibrary {
+    /// Creates an empty library of primitives of schema `S`.
+    pub fn new<S: DescribePrimitive + ?Sized>() -> Self {
+        Self {
+            schema: TypeId::of::<S>(),
+            describe: describe_any::<S>,
+            primitives: IndexMap::new(),
+        }
+    }
+
+    /// Adds a primitive to the library, replacing any primitive with the same name.
+    pub fn add(&mut self, spec: PrimitiveSpec) {
+        self.primitives.insert(spec.name.clone(), spec);
+    }
+
+    /// Gets the primitive with the given name.
+    #[inline]
+    pub fn get(&self, name: &str) -> Option<&PrimitiveSpec> {
+        self.primitives.get(name)
+    }
+
+    /// Iterates over the primitives in the library.
+    #[inline]
+    pub fn primitives(&self) -> impl Iterator<Item = &PrimitiveSpec> {
+        self.primitives.values()
+    }
+
+    /// Checks `primitive` against the library.
+    ///
+    /// Succeeds if the library does not contain a primitive named `primitive.kind`.
+    pub fn validate(&self, primitive: &PrimitiveParts) -> Result<(), PrimitiveError> {
+        match self.get(&primitive.kind) {
+            Some(spec) => spec.validate(primitive),
+            None => Ok(()),
+        }
+    }
+
+    /// Checks a primitive of schema `S` against the library.
+    ///
+    /// Succeeds if the library describes primitives of a different schema.
+    pub fn validate_primitive<S: Schema + ?Sized>(
+        &self,
+        primitive: &<S as Schema>::Primitive,
+    ) -> Result<(), PrimitiveError> {
+        if TypeId::of::<S>() != self.schema {
+            return Ok(());
+        }
+        match (self.describe)(primitive) {
+            Some(parts) => self.validate(&parts),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Installation for PrimitiveLibrary {}
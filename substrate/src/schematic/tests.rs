@@ -7,13 +7,21 @@ use arcstr::ArcStr;
 use codegen::Io;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
+use scir::mapping::PrimitiveParts;
+use scir::{Expr, ParamValue};
 
 use super::{Instance, NestedInstance};
 use crate::context::Context;
-use crate::schematic::CellBuilder;
+use crate::schematic::overrides::Overrides;
+use crate::schematic::primitives::{
+    DescribePrimitive, ParamSpec, PrimitiveError, PrimitiveLibrary, PrimitiveSpec,
+};
+use crate::schematic::{CellBuilder, PassThroughParams};
 use crate::tests::{Buffer, BufferN, BufferNxM, Inverter, InverterMos};
-use crate::types::schematic::{DataView, IoNodeBundle, NestedTerminal, NodeBundle, Terminal};
-use crate::types::{Array, Flipped, HasBundleKind, Input, PowerIo};
+use crate::types::schematic::{DataView, IoNodeBundle, NestedTerminal, Node, NodeBundle, Terminal};
+use crate::types::{
+    concat, Array, ArrayBundle, Flatten, Flipped, HasBundleKind, Input, PowerIo, SupplyKind,
+};
 use crate::{
     block::Block,
     schematic::{conv::RawLib, NestedData, PrimitiveBinding, Schematic},
@@ -51,6 +59,13 @@ pub enum Primitive {
 
 impl scir::schema::Schema for Schema {
     type Primitive = Primitive;
+
+    fn multiply_primitive(primitive: &Primitive, m: u64) -> Option<Primitive> {
+        match primitive {
+            Primitive::Resistor(value) => Some(Primitive::Resistor(*value / Decimal::from(m))),
+            Primitive::Pmos | Primitive::Nmos => None,
+        }
+    }
 }
 
 #[derive(Io, Clone, Default, Debug)]
@@ -719,6 +734,39 @@ fn nested_node_naming() {
     );
 }
 
+#[derive(Block, Copy, Clone, Debug, Hash, Eq, PartialEq)]
+#[substrate(io = "()")]
+pub struct BufferNxMWrapper;
+
+impl Schematic for BufferNxMWrapper {
+    type Schema = Schema;
+    type NestedData = Instance<BufferNxM>;
+
+    fn schematic(
+        &self,
+        _io: &IoNodeBundle<Self>,
+        cell: &mut CellBuilder<<Self as Schematic>::Schema>,
+    ) -> crate::error::Result<Self::NestedData> {
+        Ok(cell.instantiate(BufferNxM::new(1, 2, 2)))
+    }
+}
+
+#[test]
+fn instance_terminals_expand_buses() {
+    let ctx = Context::new();
+    let handle = ctx.generate_schematic(BufferNxMWrapper);
+    let buffer = handle.cell().data();
+
+    let terminals = buffer.terminals();
+    let names = terminals
+        .iter()
+        .map(|(name, _)| name.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["vdd", "vss", "din_0", "din_1", "dout_0", "dout_1"]);
+    assert_eq!(terminals[3].1.path(), buffer.io().din[1].path());
+    assert_eq!(terminals[4].1.path(), buffer.io().dout[0].path());
+}
+
 #[derive(Block, Copy, Clone, Debug, Hash, Eq, PartialEq)]
 #[substrate(io = "()")]
 pub struct Block1;
@@ -764,3 +812,611 @@ fn error_propagation_works() {
     let handle = ctx.generate_schematic(Block2);
     assert!(handle.try_cell().is_err());
 }
+
+#[derive(Block, Copy, Clone, Debug, Hash, Eq, PartialEq)]
+#[substrate(io = "()")]
+pub struct Block3;
+
+impl Schematic for Block3 {
+    type Schema = Schema;
+    type NestedData = ();
+
+    fn schematic(
+        &self,
+        _io: &IoNodeBundle<Self>,
+        cell: &mut CellBuilder<<Self as Schematic>::Schema>,
+    ) -> crate::error::Result<Self::NestedData> {
+        // Errors from the instantiated cell are intentionally not checked here.
+        cell.instantiate_named(Block1, "xblock1");
+        Ok(())
+    }
+}
+
+#[test]
+fn unchecked_instantiation_errors_propagate() {
+    let ctx = Context::new();
+    let handle = ctx.generate_schematic(Block3);
+    let err = handle.try_cell().unwrap_err();
+    let crate::error::Error::Instantiation {
+        parent,
+        instance,
+        cell,
+        source,
+        ..
+    } = &err
+    else {
+        panic!("expected an instantiation error, got {err:?}");
+    };
+    assert_eq!(parent, &Block3.name());
+    assert_eq!(instance, "xblock1");
+    assert_eq!(cell, &Block1.name());
+    assert!(matches!(**source, crate::error::Error::Anyhow(_)));
+
+    assert!(matches!(
+        ctx.export_scir(Block3),
+        Err(super::conv::ConvError::Generation(_))
+    ));
+}
+
+#[derive(Block, Copy, Clone, Debug, Hash, Eq, PartialEq)]
+#[substrate(io = "ResistorIo")]
+pub struct ParallelResistors {
+    multiply_cell: bool,
+}
+
+impl Schematic for ParallelResistors {
+    type Schema = Schema;
+    type NestedData = ();
+
+    fn schematic(
+        &self,
+        io: &IoNodeBundle<Self>,
+        cell: &mut CellBuilder<<Self as Schematic>::Schema>,
+    ) -> crate::error::Result<Self::NestedData> {
+        let r1 = cell.instantiate(Resistor(dec!(100)));
+        cell.connect(io, r1.io());
+        cell.set_instance_multiplier(&r1, 4);
+        let r2 = cell.instantiate(Resistor(dec!(100)));
+        cell.connect(io, r2.io());
+        if self.multiply_cell {
+            let vdiv = cell.instantiate(Vdivider::new(dec!(100), dec!(100)));
+            cell.set_instance_multiplier(&vdiv, 2);
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn instance_multipliers_are_exported() {
+    let ctx = Context::new();
+    let block = ParallelResistors {
+        multiply_cell: false,
+    };
+    let RawLib { scir, conv: _ } = ctx.export_scir(block).unwrap();
+    let cell = scir.cell_named(&block.name());
+    let mut values = cell
+        .instances()
+        .map(
+            |(_, inst)| match scir.primitive(inst.child().unwrap_primitive()) {
+                Primitive::Resistor(value) => *value,
+                _ => panic!("expected a resistor"),
+            },
+        )
+        .collect::<Vec<_>>();
+    values.sort();
+    assert_eq!(values, vec![dec!(25), dec!(100)]);
+
+    assert!(matches!(
+        ctx.export_scir(ParallelResistors {
+            multiply_cell: true
+        }),
+        Err(super::conv::ConvError::UnsupportedMultiplier)
+    ));
+}
+
+#[derive(Block, Copy, Clone, Debug, Hash, Eq, PartialEq)]
+#[substrate(io = "PowerIo")]
+pub struct SupplyLeaf;
+
+impl Schematic for SupplyLeaf {
+    type Schema = Schema;
+    type NestedData = ();
+
+    fn schematic(
+        &self,
+        _io: &IoNodeBundle<Self>,
+        _cell: &mut CellBuilder<<Self as Schematic>::Schema>,
+    ) -> crate::error::Result<Self::NestedData> {
+        Ok(())
+    }
+}
+
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum SupplyConnection {
+    /// Connect supplies to the rails of the parent.
+    Rails,
+    /// Connect supplies to the global rails.
+    Global,
+    /// Leave supplies unconnected.
+    None,
+}
+
+#[derive(Block, Copy, Clone, Debug, Hash, Eq, PartialEq)]
+#[substrate(io = "PowerIo")]
+pub struct SupplyParent {
+    connect: SupplyConnection,
+}
+
+impl Schematic for SupplyParent {
+    type Schema = Schema;
+    type NestedData = ();
+
+    fn schematic(
+        &self,
+        io: &IoNodeBundle<Self>,
+        cell: &mut CellBuilder<<Self as Schematic>::Schema>,
+    ) -> crate::error::Result<Self::NestedData> {
+        match self.connect {
+            SupplyConnection::Rails => {
+                cell.set_rails(io);
+                cell.instantiate_supplied(SupplyLeaf);
+            }
+            SupplyConnection::Global => {
+                cell.instantiate_supplied(SupplyLeaf);
+            }
+            SupplyConnection::None => {
+                let leaf = cell.instantiate(SupplyLeaf);
+                cell.declare_supplies(leaf.io());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn supplies_connect_to_rails() {
+    let ctx = Context::new();
+    let lib = ctx
+        .export_scir(SupplyParent {
+            connect: SupplyConnection::Rails,
+        })
+        .expect("failed to export SCIR");
+    let cell = lib.scir.cell(lib.scir.top_cell().unwrap());
+    let (_, inst) = cell.instances().next().unwrap();
+    for port in ["vdd", "vss"] {
+        let conn = inst.connection(port).parts().next().unwrap();
+        let signal = cell.signal(conn.signal());
+        assert_eq!(signal.name, port);
+        assert!(!signal.is_global());
+    }
+    assert_eq!(lib.scir.globals().count(), 0);
+
+    let err = ctx
+        .generate_schematic(SupplyParent {
+            connect: SupplyConnection::None,
+        })
+        .try_cell()
+        .unwrap_err();
+    let crate::error::Error::UnconnectedSupplies { supplies, .. } = err else {
+        panic!("expected an unconnected supply error, got {err:?}");
+    };
+    let kinds = supplies
+        .iter()
+        .map(|supply| supply.kind)
+        .collect::<Vec<_>>();
+    assert_eq!(kinds, [SupplyKind::Power, SupplyKind::Ground]);
+}
+
+#[test]
+fn supplies_connect_to_global_rails() {
+    let ctx = Context::new();
+    let lib = ctx
+        .export_scir(SupplyParent {
+            connect: SupplyConnection::Global,
+        })
+        .expect("failed to export SCIR");
+    assert!(lib.scir.is_global("vdd!"));
+    assert!(lib.scir.is_global("vss!"));
+    let cell = lib.scir.cell(lib.scir.top_cell().unwrap());
+    let (_, inst) = cell.instances().next().unwrap();
+    for (port, kind) in [("vdd", SupplyKind::Power), ("vss", SupplyKind::Ground)] {
+        let conn = inst.connection(port).parts().next().unwrap();
+        let signal = cell.signal(conn.signal());
+        assert_eq!(signal.name, kind.global_net());
+        assert!(signal.is_global());
+    }
+}
+
+#[derive(Block, Copy, Clone, Debug, Hash, Eq, PartialEq)]
+#[substrate(io = "()")]
+pub struct BusSlicing;
+
+impl Schematic for BusSlicing {
+    type Schema = Schema;
+    type NestedData = ();
+
+    fn schematic(
+        &self,
+        _io: &IoNodeBundle<Self>,
+        cell: &mut CellBuilder<<Self as Schematic>::Schema>,
+    ) -> crate::error::Result<Self::NestedData> {
+        let bus = cell.signal("bus", Array::new(8, Signal));
+        let a = cell.signal("a", Array::new(2, Signal));
+        let b = cell.signal("b", Array::new(2, Signal));
+
+        let nodes = |bundle: &ArrayBundle<Node>| Flatten::<Node>::flatten_vec(bundle);
+
+        let upper = bus.slice(4..);
+        assert_eq!(upper.kind(), Array::new(4, Signal));
+        assert_eq!(nodes(&upper), nodes(&bus)[4..]);
+
+        let ab = concat([&a, &b]);
+        assert_eq!(ab.kind(), Array::new(4, Signal));
+        assert_eq!(nodes(&ab), [nodes(&a), nodes(&b)].concat());
+        assert_eq!(nodes(&ab), nodes(&a.concat(&b)));
+
+        cell.connect(bus.slice(3..7), ab);
+        cell.connect(bus.slice(..1), bus.slice(7..));
+        Ok(())
+    }
+}
+
+#[test]
+fn array_bundles_can_be_sliced_and_concatenated() {
+    let ctx = Context::new();
+    ctx.generate_schematic(BusSlicing)
+        .try_cell()
+        .expect("failed to generate cell");
+    ctx.export_scir(BusSlicing).expect("failed to export SCIR");
+}
+
+/// A voltage divider whose resistors are named, so that they can be overridden.
+#[derive(Block, Copy, Clone, Debug, Hash, Eq, PartialEq)]
+#[substrate(io = "VdividerIo")]
+pub struct NamedVdivider {
+    r1: Decimal,
+    r2: Decimal,
+}
+
+impl Schematic for NamedVdivider {
+    type Schema = Schema;
+    type NestedData = VdividerData;
+
+    fn schematic(
+        &self,
+        io: &IoNodeBundle<Self>,
+        cell: &mut CellBuilder<<Self as Schematic>::Schema>,
+    ) -> crate::error::Result<Self::NestedData> {
+        let r1 = cell.instantiate_named(Resistor(self.r1), "r1");
+        let r2 = cell.instantiate(Resistor(self.r2));
+
+        cell.connect(io.pwr.vdd, r1.io().p);
+        cell.connect(io.out, r1.io().n);
+        cell.connect(io.out, r2.io().p);
+        cell.connect(io.pwr.vss, r2.io().n);
+        Ok(VdividerData { r1, r2 })
+    }
+}
+
+#[derive(Block, Copy, Clone, Debug, Hash, Eq, PartialEq)]
+#[substrate(io = "VdividerIo")]
+pub struct VdividerWrapper;
+
+impl Schematic for VdividerWrapper {
+    type Schema = Schema;
+    type NestedData = Instance<NamedVdivider>;
+
+    fn schematic(
+        &self,
+        io: &IoNodeBundle<Self>,
+        cell: &mut CellBuilder<<Self as Schematic>::Schema>,
+    ) -> crate::error::Result<Self::NestedData> {
+        let div = cell.instantiate_named(
+            NamedVdivider {
+                r1: dec!(300),
+                r2: dec!(100),
+            },
+            "div",
+        );
+        cell.connect(io, div.io());
+        Ok(div)
+    }
+}
+
+/// The instance overridden by [`OverriddenVdividers`].
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum OverrideTarget {
+    /// A named resistor within the divider.
+    Resistor,
+    /// The divider itself, overridden by a block of a different type.
+    MismatchedType,
+    /// An unnamed resistor within the divider.
+    Unnamed,
+}
+
+#[derive(Block, Copy, Clone, Debug, Hash, Eq, PartialEq)]
+#[substrate(io = "VdividerIo")]
+pub struct OverriddenVdividers {
+    target: OverrideTarget,
+}
+
+#[derive(NestedData)]
+pub struct OverriddenVdividersData {
+    plain: Instance<VdividerWrapper>,
+    overridden: Instance<VdividerWrapper>,
+}
+
+impl Schematic for OverriddenVdividers {
+    type Schema = Schema;
+    type NestedData = OverriddenVdividersData;
+
+    fn schematic(
+        &self,
+        io: &IoNodeBundle<Self>,
+        cell: &mut CellBuilder<<Self as Schematic>::Schema>,
+    ) -> crate::error::Result<Self::NestedData> {
+        let plain = cell.instantiate_named(VdividerWrapper, "plain");
+        let mut overrides = Overrides::new();
+        match self.target {
+            OverrideTarget::Resistor => overrides.set(["div", "r1"], Resistor(dec!(500))),
+            OverrideTarget::MismatchedType => overrides.set(["div"], Resistor(dec!(500))),
+            OverrideTarget::Unnamed => overrides.set(["div", "xinst1"], Resistor(dec!(500))),
+        };
+        let overridden =
+            cell.instantiate_named_with_overrides(VdividerWrapper, "overridden", overrides);
+        cell.connect(io, plain.io());
+        cell.connect(io, overridden.io());
+        Ok(OverriddenVdividersData { plain, overridden })
+    }
+}
+
+#[test]
+fn hierarchical_overrides_replace_descendants() {
+    let ctx = Context::new();
+    let block = OverriddenVdividers {
+        target: OverrideTarget::Resistor,
+    };
+    let handle = ctx.generate_schematic(block);
+    let cell = handle.cell();
+
+    assert_eq!(*cell.plain.r1.block(), Resistor(dec!(300)));
+    assert_eq!(*cell.overridden.r1.block(), Resistor(dec!(500)));
+    assert_eq!(*cell.overridden.r2.block(), Resistor(dec!(100)));
+
+    // The overridden wrapper must not share a cached cell with the plain wrapper.
+    let RawLib { scir, conv: _ } = ctx.export_scir(block).unwrap();
+    assert_eq!(scir.validate().num_errors(), 0);
+    let mut values = scir
+        .cells()
+        .flat_map(|(_, cell)| cell.instances().map(|(_, inst)| inst.child()))
+        .filter_map(|child| child.into_primitive())
+        .map(|id| match scir.primitive(id) {
+            Primitive::Resistor(value) => *value,
+            _ => panic!("expected a resistor"),
+        })
+        .collect::<Vec<_>>();
+    values.sort();
+    assert_eq!(values, vec![dec!(100), dec!(100), dec!(300), dec!(500)]);
+
+    // Overrides must refer to named instances of the correct type.
+    for target in [OverrideTarget::MismatchedType, OverrideTarget::Unnamed] {
+        assert!(ctx
+            .generate_schematic(OverriddenVdividers { target })
+            .try_cell()
+            .is_err());
+    }
+}
+
+impl DescribePrimitive for Schema {
+    fn describe_primitive(primitive: &Primitive) -> Option<PrimitiveParts> {
+        match primitive {
+            Primitive::Resistor(value) => Some(PrimitiveParts {
+                kind: arcstr::literal!("resistor"),
+                ports: vec![arcstr::literal!("p"), arcstr::literal!("n")],
+                params: vec![(arcstr::literal!("r"), (*value).into())],
+            }),
+            Primitive::Pmos | Primitive::Nmos => None,
+        }
+    }
+}
+
+#[test]
+fn primitive_library_validates_primitive_params() {
+    let mut library = PrimitiveLibrary::new::<Schema>();
+    library.add(
+        PrimitiveSpec::new("resistor", ["p", "n"])
+            .param("r", ParamSpec::required().min(dec!(1)).max(dec!(1000))),
+    );
+    let ctx = Context::builder().install(library).build();
+
+    let library = ctx.get_installation::<PrimitiveLibrary>().unwrap();
+    let spec = library.get("resistor").unwrap();
+    assert_eq!(
+        spec.ports()
+            .iter()
+            .map(|port| port.as_str())
+            .collect::<Vec<_>>(),
+        vec!["p", "n"]
+    );
+    assert!(spec.param_spec("r").unwrap().is_required());
+
+    let RawLib { scir, conv: _ } = ctx
+        .export_scir(Vdivider::new(dec!(300), dec!(100)))
+        .unwrap();
+    assert_eq!(scir.validate().num_errors(), 0);
+
+    let err = ctx
+        .generate_schematic(Resistor(dec!(2000)))
+        .try_cell()
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        crate::error::Error::InvalidPrimitive {
+            source: PrimitiveError::OutOfRange { .. },
+            ..
+        }
+    ));
+    assert!(ctx.export_scir(Vdivider::new(dec!(300), dec!(0))).is_err());
+}
+
+#[derive(Block, Copy, Clone, Debug, Hash, Eq, PartialEq)]
+#[substrate(io = "ResistorIo")]
+pub struct TunableResistor {
+    nf: Option<i64>,
+    declare: bool,
+}
+
+impl PassThroughParams for TunableResistor {
+    fn base(&self) -> Self {
+        Self { nf: None, ..*self }
+    }
+
+    fn param_values(&self) -> Vec<(ArcStr, ParamValue)> {
+        self.nf
+            .map(|nf| (arcstr::literal!("nf"), Decimal::from(nf).into()))
+            .into_iter()
+            .collect()
+    }
+}
+
+impl Schematic for TunableResistor {
+    type Schema = Schema;
+    type NestedData = ();
+
+    fn schematic(
+        &self,
+        io: &IoNodeBundle<Self>,
+        cell: &mut CellBuilder<<Self as Schematic>::Schema>,
+    ) -> crate::error::Result<Self::NestedData> {
+        if self.declare {
+            cell.declare_param("nf", dec!(1));
+        }
+        let r = cell.instantiate(Resistor(dec!(100)));
+        cell.connect(io.p, r.io().p);
+        cell.connect(io.n, r.io().n);
+        Ok(())
+    }
+}
+
+#[derive(Block, Copy, Clone, Debug, Hash, Eq, PartialEq)]
+#[substrate(io = "ResistorIo")]
+pub struct TunableResistors {
+    declare: bool,
+}
+
+impl Schematic for TunableResistors {
+    type Schema = Schema;
+    type NestedData = ();
+
+    fn schematic(
+        &self,
+        io: &IoNodeBundle<Self>,
+        cell: &mut CellBuilder<<Self as Schematic>::Schema>,
+    ) -> crate::error::Result<Self::NestedData> {
+        for nf in [2, 4] {
+            let r = cell.instantiate_parameterized(TunableResistor {
+                nf: Some(nf),
+                declare: self.declare,
+            })?;
+            cell.connect(io.p, r.io().p);
+            cell.connect(io.n, r.io().n);
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn parameterized_instances_share_cells() {
+    let ctx = Context::new();
+    let block = TunableResistors { declare: true };
+    let RawLib { scir, conv: _ } = ctx.export_scir(block).unwrap();
+    assert_eq!(scir.validate().num_errors(), 0);
+
+    let top = scir.cell_named(&block.name());
+    let mut values = top
+        .instances()
+        .map(|(_, inst)| inst.param("nf").cloned())
+        .collect::<Vec<_>>();
+    values.sort_by_key(|value| value.as_ref().map(|value| value.to_string()));
+    assert_eq!(
+        values,
+        vec![
+            Some(Expr::Value(dec!(2).into())),
+            Some(Expr::Value(dec!(4).into()))
+        ]
+    );
+    let children = top
+        .instances()
+        .map(|(_, inst)| inst.child())
+        .collect::<HashSet<_>>();
+    assert_eq!(children.len(), 1);
+
+    assert!(matches!(
+        ctx.generate_schematic(TunableResistors { declare: false })
+            .try_cell(),
+        Err(crate::error::Error::UndeclaredParam { .. })
+    ));
+}
+
+#[derive(Block, Copy, Clone, Debug, Hash, Eq, PartialEq)]
+#[substrate(io = "ResistorIo")]
+pub struct MergedSignals {
+    flip: bool,
+}
+
+impl Schematic for MergedSignals {
+    type Schema = Schema;
+    type NestedData = ();
+
+    fn schematic(
+        &self,
+        io: &IoNodeBundle<Self>,
+        cell: &mut CellBuilder<<Self as Schematic>::Schema>,
+    ) -> crate::error::Result<Self::NestedData> {
+        let a = cell.signal("a", Signal);
+        let b = cell.signal("b", Signal);
+        if self.flip {
+            cell.connect(b, a);
+        } else {
+            cell.connect(a, b);
+        }
+        let c = cell.signal("c", Signal);
+        cell.connect(io.p, c);
+        Ok(())
+    }
+}
+
+#[test]
+fn merged_signals_keep_earliest_source_info() {
+    let ctx = Context::new();
+    let lines = [false, true].map(|flip| {
+        let block = MergedSignals { flip };
+        let RawLib { scir, conv: _ } = ctx.export_scir(block).unwrap();
+        let cell = scir.cell_named(&block.name());
+        let line_of = |name: &str| {
+            let (_, signal) = cell
+                .signals()
+                .find(|(_, signal)| signal.name == name)
+                .unwrap();
+            signal
+                .source_info
+                .as_ref()
+                .and_then(|info| info.location())
+                .map(|location| location.line)
+        };
+        let internal = cell
+            .signals()
+            .find(|(_, signal)| signal.name == "a" || signal.name == "b")
+            .map(|(_, signal)| signal.name.clone())
+            .unwrap();
+        (line_of(&internal), line_of("p"), line_of("n"))
+    });
+
+    let (internal, p, n) = lines[0];
+    assert_eq!(lines[1], lines[0]);
+    // The merged net is attributed to `a` in either order, and port `p` to `c`,
+    // which is created seven lines after `a`.
+    assert!(internal.is_some());
+    assert_eq!(p, internal.map(|line| line + 7));
+    assert_eq!(n, None);
+}
@@ -0,0 +1,438 @@
+//! Simulator-agnostic analysis descriptions.
+//!
+//! The analyses in this module describe what to simulate without committing to a
+//! particular simulator. Simulator plugins implement [`SupportedBy`](super::SupportedBy)
+//! for the analyses they can run by converting them to their native analyses, so a
+//! testbench written against these analyses can be run on a different simulator by
+//! changing only the simulator type parameter.
+//!
+//! Nodes and instances referenced by analyses are identified by [`NodeRef`]s and
+//! [`InstanceRef`]s, which may be Substrate paths that each simulator resolves to its own
+//! netlisted names.
+//!
+//! Signals in the outputs are keyed by the simulator's raw (netlisted) names, which
+//! may differ between simulators. Saved signals can instead be retrieved using the
+//! simulator-agnostic [`SaveKey`]s returned when saving them.
+
+use std::collections::HashMap;
+use std::ops::AddAssign;
+use std::sync::Arc;
+
+use arcstr::ArcStr;
+use num::complex::Complex64;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use super::data::{SaveKey, SignalLookupError};
+use super::samples::Samples;
+use super::waveform::{TimePoint, TimeWaveform, WaveformRef};
+use super::Analysis;
+use crate::schematic::InstancePath;
+use crate::types::schematic::{NestedNode, NodePath};
+use crate::units::{Current, Frequency, Quantity, Time, Voltage};
+
+/// A node referenced by an analysis.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum NodeRef {
+    /// The raw (netlisted) name of the node.
+    Raw(ArcStr),
+    /// The path to a node of the testbench.
+    Path(NodePath),
+}
+
+impl<T: Into<ArcStr>> From<T> for NodeRef {
+    fn from(value: T) -> Self {
+        Self::Raw(value.into())
+    }
+}
+
+impl From<NodePath> for NodeRef {
+    fn from(value: NodePath) -> Self {
+        Self::Path(value)
+    }
+}
+
+impl From<&NestedNode> for NodeRef {
+    fn from(value: &NestedNode) -> Self {
+        Self::Path(value.path())
+    }
+}
+
+/// An instance referenced by an analysis.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum InstanceRef {
+    /// The raw (netlisted) name of the instance.
+    Raw(ArcStr),
+    /// The path to an instance in the testbench.
+    Path(InstancePath),
+}
+
+impl<T: Into<ArcStr>> From<T> for InstanceRef {
+    fn from(value: T) -> Self {
+        Self::Raw(value.into())
+    }
+}
+
+impl From<InstancePath> for InstanceRef {
+    fn from(value: InstancePath) -> Self {
+        Self::Path(value)
+    }
+}
+
+impl From<&InstancePath> for InstanceRef {
+    fn from(value: &InstancePath) -> Self {
+        Self::Path(value.clone())
+    }
+}
+
+/// The spacing of points in a frequency sweep.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Sweep {
+    /// Linear sweep with the given number of points.
+    Linear(usize),
+    /// Logarithmic sweep with the given number of points **per decade**.
+    Decade(usize),
+}
+
+/// A transient analysis.
+#[derive(Clone, Default, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Tran {
+    /// Stop time.
+    pub stop: Time,
+    /// Suggested time step.
+    ///
+    /// Simulators that require a step use `stop / 1000` if this is [`None`].
+    pub step: Option<Time>,
+    /// Start time.
+    ///
+    /// Defaults to 0.
+    pub start: Option<Time>,
+}
+
+impl Tran {
+    /// Creates a transient analysis that stops at the given time.
+    pub fn new(stop: Time) -> Self {
+        Self {
+            stop,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the suggested time step, defaulting to `stop / 1000`.
+    pub fn step_or_default(&self) -> Time {
+        self.step
+            .unwrap_or_else(|| Time::new(self.stop.value() / Decimal::from(1000)))
+    }
+}
+
+/// An AC small-signal analysis.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Ac {
+    /// Start frequency.
+    pub start: Frequency,
+    /// Stop frequency.
+    pub stop: Frequency,
+    /// The sweep kind and number of points.
+    pub sweep: Sweep,
+}
+
+/// A DC operating point analysis.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Dc;
+
+/// A small-signal noise analysis.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Noise {
+    /// The node at which output noise is measured.
+    pub output: NodeRef,
+    /// The independent source to which noise is referred.
+    ///
+    /// Some simulators require this to be a top-level voltage or current source.
+    pub input_source: InstanceRef,
+    /// Start frequency.
+    pub start: Frequency,
+    /// Stop frequency.
+    pub stop: Frequency,
+    /// The sweep kind and number of points.
+    pub sweep: Sweep,
+}
+
+/// Returns the sum of the raw values referred to by `key`.
+///
+/// # Panics
+///
+/// Panics if any of the referenced signals were not saved.
+fn saved<T: Copy + AddAssign>(
+    raw_values: &HashMap<ArcStr, Arc<Vec<T>>>,
+    saved_values: &HashMap<u64, ArcStr>,
+    key: &[u64],
+) -> Arc<Vec<T>> {
+    let mut values = key
+        .iter()
+        .map(|id| raw_values.get(saved_values.get(id).unwrap()).unwrap());
+    let first = values
+        .next()
+        .expect("save key must refer to at least one signal");
+    values.fold(first.clone(), |mut acc, x| {
+        for (acc, x) in Arc::make_mut(&mut acc).iter_mut().zip(x.iter()) {
+            *acc += *x;
+        }
+        acc
+    })
+}
+
+/// The output of a [`Tran`] analysis.
+#[derive(Debug, Clone, Default)]
+pub struct TranOutput {
+    /// The time points of the transient simulation.
+    pub time: Arc<Vec<f64>>,
+    /// A map from signal name to values.
+    pub raw_values: HashMap<ArcStr, Arc<Vec<f64>>>,
+    /// A map from a save ID to a raw value identifier.
+    pub saved_values: HashMap<u64, ArcStr>,
+}
+
+/// A saved transient waveform.
+#[derive(Debug, Clone)]
+pub struct TranWaveform {
+    /// Time samples.
+    pub t: Arc<Vec<f64>>,
+    /// Values corresponding to time samples in `t`.
+    pub x: Arc<Vec<f64>>,
+}
+
+impl TranWaveform {
+    /// Converts a [`TranWaveform`] to a [`WaveformRef`].
+    pub fn as_ref(&self) -> WaveformRef<'_, f64> {
+        WaveformRef::new(&self.t, &self.x)
+    }
+}
+
+impl TimeWaveform for TranWaveform {
+    type Data = f64;
+    fn get(&self, idx: usize) -> Option<TimePoint<f64>> {
+        self.as_ref().get(idx)
+    }
+
+    fn len(&self) -> usize {
+        self.t.len()
+    }
+}
+
+/// The voltage and current of a terminal saved in a [`Tran`] analysis.
+#[derive(Debug, Clone)]
+pub struct TranTerminal {
+    /// The voltage at the terminal.
+    pub v: TranWaveform,
+    /// The current flowing into the terminal.
+    pub i: TranWaveform,
+}
+
+impl TranOutput {
+    /// Returns the saved waveform referred to by `key`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the signals referred to by `key` were not saved.
+    pub fn saved<U>(&self, key: &SaveKey<U>) -> TranWaveform {
+        TranWaveform {
+            t: self.time.clone(),
+            x: saved(&self.raw_values, &self.saved_values, key.ids()),
+        }
+    }
+
+    /// Returns the samples of the signal with the given raw (netlisted) name.
+    ///
+    /// If no such signal was saved, the returned error lists similarly named saved signals.
+    pub fn raw_samples(&self, name: &str) -> Result<Samples, SignalLookupError> {
+        let x = self
+            .raw_values
+            .get(name)
+            .ok_or_else(|| SignalLookupError::not_found(name, self.raw_values.keys()))?;
+        Ok(Samples::Real(x.clone()))
+    }
+}
+
+/// The output of an [`Ac`] analysis.
+#[derive(Debug, Clone, Default)]
+pub struct AcOutput {
+    /// The frequency points of the AC simulation.
+    pub freq: Arc<Vec<f64>>,
+    /// A map from signal name to values.
+    pub raw_values: HashMap<ArcStr, Arc<Vec<Complex64>>>,
+    /// A map from a save ID to a raw value identifier.
+    pub saved_values: HashMap<u64, ArcStr>,
+}
+
+/// The voltage and current of a terminal saved in an [`Ac`] analysis.
+#[derive(Debug, Clone)]
+pub struct AcTerminal {
+    /// The voltage at the terminal.
+    pub v: Arc<Vec<Complex64>>,
+    /// The current flowing into the terminal.
+    pub i: Arc<Vec<Complex64>>,
+}
+
+impl AcOutput {
+    /// Returns the saved values referred to by `key`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the signals referred to by `key` were not saved.
+    pub fn saved<U>(&self, key: &SaveKey<U>) -> Arc<Vec<Complex64>> {
+        saved(&self.raw_values, &self.saved_values, key.ids())
+    }
+
+    /// Returns the samples of the signal with the given raw (netlisted) name.
+    ///
+    /// If no such signal was saved, the returned error lists similarly named saved signals.
+    pub fn raw_samples(&self, name: &str) -> Result<Samples, SignalLookupError> {
+        let x = self
+            .raw_values
+            .get(name)
+            .ok_or_else(|| SignalLookupError::not_found(name, self.raw_values.keys()))?;
+        Ok(Samples::Complex(x.clone()))
+    }
+}
+
+/// The output of a [`Dc`] analysis.
+#[derive(Debug, Clone, Default)]
+pub struct DcOutput {
+    /// A map from signal name to value.
+    pub raw_values: HashMap<ArcStr, f64>,
+    /// A map from a save ID to a raw value identifier.
+    pub saved_values: HashMap<u64, ArcStr>,
+}
+
+/// The voltage and current of a terminal saved in a [`Dc`] analysis.
+#[derive(Debug, Clone, Copy)]
+pub struct DcTerminal {
+    /// The voltage at the terminal.
+    pub v: f64,
+    /// The current flowing into the terminal.
+    pub i: f64,
+}
+
+impl DcTerminal {
+    /// Returns the voltage at the terminal.
+    ///
+    /// Returns [`None`] if the simulated voltage is not finite.
+    pub fn voltage(&self) -> Option<Voltage> {
+        Voltage::from_f64(self.v)
+    }
+
+    /// Returns the current flowing into the terminal.
+    ///
+    /// Returns [`None`] if the simulated current is not finite.
+    pub fn current(&self) -> Option<Current> {
+        Current::from_f64(self.i)
+    }
+}
+
+impl DcOutput {
+    /// Returns the saved value referred to by `key`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the signals referred to by `key` were not saved.
+    pub fn saved<U>(&self, key: &SaveKey<U>) -> f64 {
+        key.ids()
+            .iter()
+            .map(|id| self.raw_values[&self.saved_values[id]])
+            .sum()
+    }
+
+    /// Returns the saved value referred to by `key` as a quantity in the unit of `key`.
+    ///
+    /// Returns [`None`] if the saved value is not finite.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the signals referred to by `key` were not saved.
+    pub fn quantity<U>(&self, key: &SaveKey<U>) -> Option<Quantity<U>> {
+        Quantity::from_f64(self.saved(key))
+    }
+
+    /// Returns the value of the signal with the given raw (netlisted) name.
+    ///
+    /// If no such signal was saved, the returned error lists similarly named saved signals.
+    pub fn raw_value(&self, name: &str) -> Result<f64, SignalLookupError> {
+        self.raw_values
+            .get(name)
+            .copied()
+            .ok_or_else(|| SignalLookupError::not_found(name, self.raw_values.keys()))
+    }
+}
+
+/// The output of a [`Noise`] analysis.
+#[derive(Debug, Clone, Default)]
+pub struct NoiseOutput {
+    /// The frequency points of the noise simulation.
+    pub freq: Arc<Vec<f64>>,
+    /// The output noise spectral density, in units per square root hertz.
+    pub output_noise: Arc<Vec<f64>>,
+    /// The input-referred noise spectral density, in units per square root hertz.
+    pub input_noise: Arc<Vec<f64>>,
+}
+
+impl Analysis for Tran {
+    type Output = TranOutput;
+}
+
+impl Analysis for Ac {
+    type Output = AcOutput;
+}
+
+impl Analysis for Dc {
+    type Output = DcOutput;
+}
+
+impl Analysis for Noise {
+    type Output = NoiseOutput;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::data::{CurrentSaveKey, VoltageSaveKey};
+
+    #[test]
+    fn save_keys_resolve_in_common_outputs() {
+        let output = TranOutput {
+            time: Arc::new(vec![0., 1.]),
+            raw_values: HashMap::from_iter([
+                (arcstr::literal!("out"), Arc::new(vec![0., 1.])),
+                (arcstr::literal!("i1"), Arc::new(vec![1., 2.])),
+                (arcstr::literal!("i2"), Arc::new(vec![3., 4.])),
+            ]),
+            saved_values: HashMap::from_iter([
+                (0, arcstr::literal!("out")),
+                (1, arcstr::literal!("i1")),
+                (2, arcstr::literal!("i2")),
+            ]),
+        };
+        assert_eq!(*output.saved(&VoltageSaveKey::new(0)).x, vec![0., 1.]);
+        assert_eq!(
+            *output.saved(&CurrentSaveKey::from_ids([1, 2])).x,
+            vec![4., 6.]
+        );
+        // Summing must not modify the underlying saved values.
+        assert_eq!(*output.raw_values["i1"], vec![1., 2.]);
+
+        let output = DcOutput {
+            raw_values: HashMap::from_iter([
+                (arcstr::literal!("i1"), 1.),
+                (arcstr::literal!("i2"), 3.),
+            ]),
+            saved_values: HashMap::from_iter([
+                (1, arcstr::literal!("i1")),
+                (2, arcstr::literal!("i2")),
+            ]),
+        };
+        assert_eq!(output.saved(&CurrentSaveKey::from_ids([1, 2])), 4.);
+        assert_eq!(
+            output.quantity(&CurrentSaveKey::from_ids([1, 2])),
+            Some(Current::new(Decimal::from(4)))
+        );
+    }
+}
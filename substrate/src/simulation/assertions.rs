@@ -0,0 +1,467 @@
+//! Assertions on transient waveforms.
+//!
+//! Each check is available in two forms: a `check_*` function that returns a
+//! [`WaveformAssertionError`] describing the failure, and an `assert_*` function that
+//! panics with that description. Failures report the time window in which the check
+//! failed along with the offending values, so that simulation-based regression tests
+//! can be written concisely:
+//!
+//! ```ignore
+//! let vout = output.tran.saved(&vout_key);
+//! assert_settles_within(&vout, 0.9, 0.01, 5e-9);
+//! assert_no_glitch(&vout, 0.45, 100e-12);
+//! ```
+
+use super::waveform::{EdgeDir, TimeWaveform};
+
+/// A failed waveform assertion.
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum WaveformAssertionError {
+    /// The waveform has no points in the checked window.
+    #[error("waveform has no points in window [{start:e}, {stop:e}]")]
+    NoData {
+        /// The start of the checked window.
+        start: f64,
+        /// The end of the checked window.
+        stop: f64,
+    },
+    /// The waveform did not settle within the given tolerance of its target.
+    #[error(
+        "waveform did not settle to {target} ± {tolerance} by t = {deadline:e}: \
+         x({t:e}) = {x} in window [{deadline:e}, {end:e}]"
+    )]
+    NotSettled {
+        /// The target value.
+        target: f64,
+        /// The allowed deviation from the target.
+        tolerance: f64,
+        /// The time by which the waveform should have settled.
+        deadline: f64,
+        /// The end of the waveform.
+        end: f64,
+        /// The time of the first point outside the tolerance band.
+        t: f64,
+        /// The value of the first point outside the tolerance band.
+        x: f64,
+    },
+    /// The waveform contains a pulse narrower than the minimum pulse width.
+    #[error(
+        "glitch of width {width:e} in window [{start:e}, {stop:e}] \
+         (minimum pulse width {min_width:e}); peak value {x}"
+    )]
+    Glitch {
+        /// The time at which the glitch crossed the threshold.
+        start: f64,
+        /// The time at which the glitch crossed back over the threshold.
+        stop: f64,
+        /// The width of the glitch.
+        width: f64,
+        /// The minimum allowed pulse width.
+        min_width: f64,
+        /// The value furthest from the threshold during the glitch.
+        x: f64,
+    },
+    /// The waveform is not monotonic.
+    #[error(
+        "waveform is not monotonically {} in window [{t0:e}, {t1:e}]: x went from {x0} to {x1}",
+        direction(.dir)
+    )]
+    NotMonotonic {
+        /// The expected direction.
+        dir: EdgeDir,
+        /// The time of the point before the violation.
+        t0: f64,
+        /// The time of the point after the violation.
+        t1: f64,
+        /// The value of the point before the violation.
+        x0: f64,
+        /// The value of the point after the violation.
+        x1: f64,
+    },
+    /// The eye opening is smaller than required.
+    #[error(
+        "eye height {height} is less than {min_height} in window [{start:e}, {stop:e}] \
+         (worst high level {high}, worst low level {low})"
+    )]
+    EyeClosed {
+        /// The measured eye height.
+        height: f64,
+        /// The minimum allowed eye height.
+        min_height: f64,
+        /// The start of the unit interval containing the worst-case point.
+        start: f64,
+        /// The end of the unit interval containing the worst-case point.
+        stop: f64,
+        /// The lowest value above the decision threshold within the eye window.
+        high: f64,
+        /// The highest value below the decision threshold within the eye window.
+        low: f64,
+    },
+    /// The unit interval of an [`EyeSpec`] is not positive.
+    #[error("unit interval {ui:e} of eye starting at t = {start:e} must be positive")]
+    InvalidUnitInterval {
+        /// The unit interval.
+        ui: f64,
+        /// The time of the start of the first unit interval.
+        start: f64,
+    },
+}
+
+impl WaveformAssertionError {
+    /// The time window in which the assertion failed.
+    pub fn window(&self) -> (f64, f64) {
+        match *self {
+            Self::NoData { start, stop } => (start, stop),
+            Self::NotSettled { deadline, end, .. } => (deadline, end),
+            Self::Glitch { start, stop, .. } => (start, stop),
+            Self::NotMonotonic { t0, t1, .. } => (t0, t1),
+            Self::EyeClosed { start, stop, .. } => (start, stop),
+            Self::InvalidUnitInterval { start, .. } => (start, start),
+        }
+    }
+}
+
+fn direction(dir: &EdgeDir) -> &'static str {
+    match dir {
+        EdgeDir::Rising => "rising",
+        EdgeDir::Falling => "falling",
+    }
+}
+
+/// The eye diagram specification used by [`check_eye_open`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EyeSpec {
+    /// The unit interval (bit period).
+    pub ui: f64,
+    /// The time of the start of the first unit interval to check.
+    ///
+    /// Typically chosen to skip any initial settling.
+    pub start: f64,
+    /// The decision threshold separating high and low levels.
+    pub threshold: f64,
+    /// The width of the eye window, centered in each unit interval.
+    pub width: f64,
+    /// The minimum allowed eye height within the eye window.
+    pub min_height: f64,
+}
+
+/// Checks that `wav` stays within `tolerance` of `target` from time `deadline` onward.
+pub fn check_settles_within<W: TimeWaveform<Data = f64>>(
+    wav: &W,
+    target: f64,
+    tolerance: f64,
+    deadline: f64,
+) -> Result<(), WaveformAssertionError> {
+    let Some(end) = wav.last_t() else {
+        return Err(WaveformAssertionError::NoData {
+            start: deadline,
+            stop: deadline,
+        });
+    };
+    if end < deadline {
+        return Err(WaveformAssertionError::NoData {
+            start: deadline,
+            stop: end,
+        });
+    }
+    if let Some(p) = wav
+        .values()
+        .filter(|p| p.t() >= deadline)
+        .find(|p| (p.x() - target).abs() > tolerance)
+    {
+        return Err(WaveformAssertionError::NotSettled {
+            target,
+            tolerance,
+            deadline,
+            end,
+            t: p.t(),
+            x: p.x(),
+        });
+    }
+    Ok(())
+}
+
+/// Checks that `wav` does not contain pulses narrower than `min_width` across `threshold`.
+///
+/// A pulse is the interval between two consecutive crossings of `threshold`.
+pub fn check_no_glitch<W: TimeWaveform<Data = f64>>(
+    wav: &W,
+    threshold: f64,
+    min_width: f64,
+) -> Result<(), WaveformAssertionError> {
+    let edges = wav.edges(threshold).collect::<Vec<_>>();
+    for pair in edges.windows(2) {
+        let (e0, e1) = (&pair[0], &pair[1]);
+        let width = e1.t() - e0.t();
+        if width < min_width {
+            let pulse = (e0.idx_after()..=e1.idx_before()).filter_map(|i| wav.get(i));
+            let x = if e0.dir().is_rising() {
+                pulse.map(|p| p.x()).fold(threshold, f64::max)
+            } else {
+                pulse.map(|p| p.x()).fold(threshold, f64::min)
+            };
+            return Err(WaveformAssertionError::Glitch {
+                start: e0.t(),
+                stop: e1.t(),
+                width,
+                min_width,
+                x,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Checks that `wav` is monotonic in direction `dir`.
+///
+/// Steps against `dir` of at most `tolerance` are ignored, allowing for simulator noise.
+pub fn check_monotonic<W: TimeWaveform<Data = f64>>(
+    wav: &W,
+    dir: EdgeDir,
+    tolerance: f64,
+) -> Result<(), WaveformAssertionError> {
+    for i in 1..wav.len() {
+        let p0 = wav.get(i - 1).unwrap();
+        let p1 = wav.get(i).unwrap();
+        let step = match dir {
+            EdgeDir::Rising => p0.x() - p1.x(),
+            EdgeDir::Falling => p1.x() - p0.x(),
+        };
+        if step > tolerance {
+            return Err(WaveformAssertionError::NotMonotonic {
+                dir,
+                t0: p0.t(),
+                t1: p1.t(),
+                x0: p0.x(),
+                x1: p1.x(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Checks that the eye diagram of `wav` is open according to `spec`.
+///
+/// The waveform is folded into unit intervals beginning at `spec.start`. Within the eye
+/// window of each unit interval, all points above the threshold must be at least
+/// `spec.min_height` above all points below the threshold.
+///
+/// Returns [`WaveformAssertionError::InvalidUnitInterval`] if `spec.ui` is not positive.
+pub fn check_eye_open<W: TimeWaveform<Data = f64>>(
+    wav: &W,
+    spec: EyeSpec,
+) -> Result<(), WaveformAssertionError> {
+    if spec.ui.is_nan() || spec.ui <= 0. {
+        return Err(WaveformAssertionError::InvalidUnitInterval {
+            ui: spec.ui,
+            start: spec.start,
+        });
+    }
+    let lo = (spec.ui - spec.width) / 2.;
+    let hi = (spec.ui + spec.width) / 2.;
+
+    // The worst-case high and low levels, along with the times at which they occur.
+    let mut high: Option<(f64, f64)> = None;
+    let mut low: Option<(f64, f64)> = None;
+    for p in wav.values().filter(|p| p.t() >= spec.start) {
+        let phase = (p.t() - spec.start) % spec.ui;
+        if phase < lo || phase > hi {
+            continue;
+        }
+        if p.x() >= spec.threshold {
+            if high.is_none_or(|(_, x)| p.x() < x) {
+                high = Some((p.t(), p.x()));
+            }
+        } else if low.is_none_or(|(_, x)| p.x() > x) {
+            low = Some((p.t(), p.x()));
+        }
+    }
+
+    let (t, high, low) = match (high, low) {
+        (None, None) => {
+            return Err(WaveformAssertionError::NoData {
+                start: spec.start,
+                stop: wav.last_t().unwrap_or(spec.start),
+            })
+        }
+        (Some((t, high)), None) => (t, high, spec.threshold),
+        (None, Some((t, low))) => (t, spec.threshold, low),
+        (Some((t_high, high)), Some((t_low, low))) => {
+            // Localize the failure to whichever level is closer to the threshold.
+            let t = if high - spec.threshold < spec.threshold - low {
+                t_high
+            } else {
+                t_low
+            };
+            (t, high, low)
+        }
+    };
+    let height = high - low;
+    if height < spec.min_height {
+        let start = spec.start + ((t - spec.start) / spec.ui).floor() * spec.ui;
+        return Err(WaveformAssertionError::EyeClosed {
+            height,
+            min_height: spec.min_height,
+            start,
+            stop: start + spec.ui,
+            high,
+            low,
+        });
+    }
+    Ok(())
+}
+
+/// Asserts that `wav` stays within `tolerance` of `target` from time `deadline` onward.
+///
+/// See [`check_settles_within`].
+#[track_caller]
+pub fn assert_settles_within<W: TimeWaveform<Data = f64>>(
+    wav: &W,
+    target: f64,
+    tolerance: f64,
+    deadline: f64,
+) {
+    if let Err(e) = check_settles_within(wav, target, tolerance, deadline) {
+        panic!("{e}");
+    }
+}
+
+/// Asserts that `wav` does not contain pulses narrower than `min_width` across `threshold`.
+///
+/// See [`check_no_glitch`].
+#[track_caller]
+pub fn assert_no_glitch<W: TimeWaveform<Data = f64>>(wav: &W, threshold: f64, min_width: f64) {
+    if let Err(e) = check_no_glitch(wav, threshold, min_width) {
+        panic!("{e}");
+    }
+}
+
+/// Asserts that `wav` is monotonic in direction `dir`.
+///
+/// See [`check_monotonic`].
+#[track_caller]
+pub fn assert_monotonic<W: TimeWaveform<Data = f64>>(wav: &W, dir: EdgeDir, tolerance: f64) {
+    if let Err(e) = check_monotonic(wav, dir, tolerance) {
+        panic!("{e}");
+    }
+}
+
+/// Asserts that the eye diagram of `wav` is open according to `spec`.
+///
+/// See [`check_eye_open`].
+#[track_caller]
+pub fn assert_eye_open<W: TimeWaveform<Data = f64>>(wav: &W, spec: EyeSpec) {
+    if let Err(e) = check_eye_open(wav, spec) {
+        panic!("{e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use super::*;
+    use crate::simulation::waveform::Waveform;
+
+    #[test]
+    fn settling_failures_report_first_violation() {
+        let wav = Waveform::from_iter([(0., 0.), (1., 0.8), (2., 1.05), (3., 1.01), (4., 1.)]);
+        assert_settles_within(&wav, 1., 0.02, 3.);
+        assert_eq!(
+            check_settles_within(&wav, 1., 0.02, 1.5),
+            Err(WaveformAssertionError::NotSettled {
+                target: 1.,
+                tolerance: 0.02,
+                deadline: 1.5,
+                end: 4.,
+                t: 2.,
+                x: 1.05,
+            })
+        );
+        assert!(matches!(
+            check_settles_within(&wav, 1., 0.02, 5.),
+            Err(WaveformAssertionError::NoData { .. })
+        ));
+        assert_eq!(
+            check_settles_within(&Waveform::<f64>::new(), 1., 0.02, 0.),
+            Err(WaveformAssertionError::NoData {
+                start: 0.,
+                stop: 0.
+            })
+        );
+    }
+
+    #[test]
+    fn glitches_are_localized() {
+        let wav = Waveform::from_iter([
+            (0., 0.),
+            (1., 0.),
+            (1.1, 1.),
+            (1.2, 0.),
+            (5., 0.),
+            (6., 1.),
+            (10., 1.),
+        ]);
+        let err = check_no_glitch(&wav, 0.5, 1.).unwrap_err();
+        let (start, stop) = err.window();
+        assert!(start > 1. && stop < 1.2);
+        assert!(matches!(err, WaveformAssertionError::Glitch { x, .. } if x == 1.));
+        assert_no_glitch(&wav, 0.5, 0.05);
+    }
+
+    #[test]
+    fn monotonic_violations_are_localized() {
+        let wav = Waveform::from_iter([(0., 0.), (1., 0.5), (2., 0.4), (3., 1.)]);
+        assert_monotonic(&wav, EdgeDir::Rising, 0.2);
+        assert_eq!(
+            check_monotonic(&wav, EdgeDir::Rising, 0.01),
+            Err(WaveformAssertionError::NotMonotonic {
+                dir: EdgeDir::Rising,
+                t0: 1.,
+                t1: 2.,
+                x0: 0.5,
+                x1: 0.4,
+            })
+        );
+        assert!(check_monotonic(&wav, EdgeDir::Falling, 0.).is_err());
+    }
+
+    #[test]
+    fn eye_opening_is_checked() {
+        // A 1 ns UI bit stream with 0.1 ns transitions.
+        let bits = [true, false, true, true, false, false, true, false];
+        let mut wav = Waveform::with_initial_value(0.);
+        for (i, bit) in bits.into_iter().enumerate() {
+            let x = if bit { 1. } else { 0. };
+            let t = i as f64;
+            wav.push(t + 0.1, x);
+            wav.push(t + 0.5, if i == 5 { 0.3 } else { x });
+            wav.push(t + 1., x);
+        }
+        let spec = EyeSpec {
+            ui: 1.,
+            start: 0.,
+            threshold: 0.5,
+            width: 0.4,
+            min_height: 0.8,
+        };
+        let err = check_eye_open(&wav, spec).unwrap_err();
+        assert_eq!(err.window(), (5., 6.));
+        let WaveformAssertionError::EyeClosed { height, low, .. } = err else {
+            panic!("expected eye to be closed");
+        };
+        assert_relative_eq!(height, 0.7);
+        assert_relative_eq!(low, 0.3);
+        assert_eye_open(
+            &wav,
+            EyeSpec {
+                min_height: 0.6,
+                ..spec
+            },
+        );
+        assert!(matches!(
+            check_eye_open(&wav, EyeSpec { ui: 0., ..spec }),
+            Err(WaveformAssertionError::InvalidUnitInterval { .. })
+        ));
+    }
+}
@@ -0,0 +1,86 @@
+//! Batches of independent simulations.
+//!
+//! [`Context::simulate_batch`](crate::context::Context::simulate_batch) runs many
+//! testbenches in parallel, each in its own working directory:
+//!
+//! ```ignore
+//! let sims = corners.iter().map(|corner| {
+//!     BatchSim::new(Tb::new(*corner), work_dir.join(corner.name()), opts.clone(), tran.clone())
+//! });
+//! for output in ctx.simulate_batch(sims)? {
+//!     let output = output?;
+//!     // ...
+//! }
+//! ```
+//!
+//! Every simulation is submitted to the context's executor and shares the context's cache,
+//! so repeated batches only rerun simulations whose netlists have changed.
+
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use super::{SavedData, SimController, Simulator, SupportedBy, Testbench};
+use crate::schematic::{HasNestedView, Schematic};
+use crate::simulation::data::Save;
+
+/// A simulation to run as part of a batch.
+pub struct BatchSim<T, O, A> {
+    /// The testbench to simulate.
+    pub tb: T,
+    /// The working directory of the simulation.
+    pub work_dir: PathBuf,
+    /// The simulation options.
+    pub options: O,
+    /// The analysis to run.
+    pub analysis: A,
+}
+
+impl<T, O, A> BatchSim<T, O, A> {
+    /// Creates a simulation of `analysis` on `tb` in `work_dir`.
+    pub fn new(tb: T, work_dir: impl Into<PathBuf>, options: O, analysis: A) -> Self {
+        Self {
+            tb,
+            work_dir: work_dir.into(),
+            options,
+            analysis,
+        }
+    }
+}
+
+/// Simulates each of the given controllers on at most `parallelism` threads.
+///
+/// Returns the results in the order of `sims`.
+pub(crate) fn run_batch<S, T, A>(
+    sims: Vec<(SimController<S, T>, S::Options, A)>,
+    parallelism: usize,
+) -> Vec<Result<SavedData<T, S, A>, S::Error>>
+where
+    S: Simulator<Options: Send, Error: Send>,
+    T: Testbench<S> + Schematic<NestedData: HasNestedView<NestedView: Save<S, A>>>,
+    A: SupportedBy<S> + Send,
+    SavedData<T, S, A>: Send,
+{
+    let len = sims.len();
+    let queue = Mutex::new(sims.into_iter().enumerate().collect::<VecDeque<_>>());
+    let results = Mutex::new((0..len).map(|_| None).collect::<Vec<_>>());
+
+    std::thread::scope(|scope| {
+        for _ in 0..parallelism.min(len) {
+            scope.spawn(|| loop {
+                let Some((i, (sim, options, analysis))) = queue.lock().unwrap().pop_front() else {
+                    return;
+                };
+                let output = sim.simulate(options, analysis);
+                results.lock().unwrap()[i] = Some(output);
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|output| output.expect("batch simulation did not complete"))
+        .collect()
+}
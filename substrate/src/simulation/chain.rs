@@ -0,0 +1,125 @@
+//! Chained analyses whose parameters depend on the results of earlier analyses.
+//!
+//! Analyses passed to [`Simulator::simulate`] are run together in a single simulator
+//! invocation, so their parameters must be known up front. A [`Then`] instead runs its
+//! first analysis, then computes the next analysis (and any changes to the simulation
+//! options) from the first analysis's output:
+//!
+//! ```ignore
+//! let chain = Then::new(DcOp, |op: &OpOutput, _: &mut Options| Ac { .. });
+//! let chain = Then::new(chain, |(op, _ac): &(OpOutput, AcOutput), opts: &mut Options| {
+//!     opts.include(select_corner(op));
+//!     Tran { .. }
+//! });
+//! let ((op, ac), tran) = sim.simulate_chain(Options::default(), chain)?;
+//! ```
+//!
+//! Each stage of a chain is run in a separate simulator invocation, in its own
+//! subdirectory (`stage0`, `stage1`, ...) of the simulation working directory.
+
+use super::{SimulationContext, Simulator, SupportedBy};
+
+/// Runs the stages of a chained analysis.
+pub struct ChainContext<'a, S: Simulator> {
+    simulator: &'a S,
+    ctx: &'a SimulationContext<S>,
+    stage: usize,
+}
+
+impl<'a, S: Simulator> ChainContext<'a, S> {
+    /// Creates a context for running a chain with the given simulator and simulation context.
+    pub fn new(simulator: &'a S, ctx: &'a SimulationContext<S>) -> Self {
+        Self {
+            simulator,
+            ctx,
+            stage: 0,
+        }
+    }
+
+    /// The number of stages that have been run.
+    pub fn stages(&self) -> usize {
+        self.stage
+    }
+
+    /// Simulates `analysis` with the given options in a new stage.
+    pub fn simulate<A: SupportedBy<S>>(
+        &mut self,
+        options: S::Options,
+        analysis: A,
+    ) -> Result<A::Output, S::Error> {
+        let ctx = SimulationContext {
+            work_dir: self.ctx.work_dir.join(format!("stage{}", self.stage)),
+            lib: self.ctx.lib.clone(),
+            ctx: self.ctx.ctx.clone(),
+            progress: self.ctx.progress.clone(),
+        };
+        self.stage += 1;
+        self.simulator.simulate(&ctx, options, analysis)
+    }
+}
+
+/// An analysis that may be split across several simulator invocations.
+///
+/// Implemented for all analyses supported by a simulator, which are run in a single
+/// invocation, and for [`Then`].
+pub trait ChainedAnalysis<S: Simulator> {
+    /// The output produced by this analysis.
+    type Output;
+
+    /// Simulates the analysis using `chain`.
+    fn simulate_chain(
+        self,
+        chain: &mut ChainContext<'_, S>,
+        options: S::Options,
+    ) -> Result<Self::Output, S::Error>;
+}
+
+impl<S: Simulator, A: SupportedBy<S>> ChainedAnalysis<S> for A {
+    type Output = A::Output;
+
+    fn simulate_chain(
+        self,
+        chain: &mut ChainContext<'_, S>,
+        options: S::Options,
+    ) -> Result<Self::Output, S::Error> {
+        chain.simulate(options, self)
+    }
+}
+
+/// Runs analysis `A`, then the analysis produced by calling `F` on the output of `A`.
+///
+/// `F` receives a copy of the options used for `A`, which it may modify before they
+/// are used for the next analysis (e.g. to set initial conditions).
+/// The output is a tuple of the outputs of both analyses.
+pub struct Then<A, F> {
+    first: A,
+    then: F,
+}
+
+impl<A, F> Then<A, F> {
+    /// Creates a chain that runs `first`, then the analysis returned by `then`.
+    pub fn new(first: A, then: F) -> Self {
+        Self { first, then }
+    }
+}
+
+impl<S, A, B, F> ChainedAnalysis<S> for Then<A, F>
+where
+    S: Simulator<Options: Clone>,
+    A: ChainedAnalysis<S>,
+    B: ChainedAnalysis<S>,
+    F: FnOnce(&A::Output, &mut S::Options) -> B,
+{
+    type Output = (A::Output, B::Output);
+
+    fn simulate_chain(
+        self,
+        chain: &mut ChainContext<'_, S>,
+        mut options: S::Options,
+    ) -> Result<Self::Output, S::Error> {
+        let first = self.first.simulate_chain(chain, options.clone())?;
+        let next = (self.then)(&first, &mut options);
+        let second = next.simulate_chain(chain, options)?;
+        Ok((first, second))
+    }
+}
@@ -0,0 +1,676 @@
+//! Sweep-and-fit characterization utilities.
+//!
+//! A [`LookupTable`] stores simulated quantities on a rectilinear grid of sweep points and
+//! supports multilinear interpolation between them. Tables are usually populated with
+//! [`LookupTable::sweep`], which calls a user-supplied function (typically one that runs a
+//! DC operating point simulation) at every grid point.
+//!
+//! [`GmIdTable`] builds on lookup tables to support gm/ID-based transistor sizing.
+//!
+//! Lookup tables can be saved and loaded using a compact binary format
+//! (see [`LookupTable::write`]), or with any [`serde`] format.
+
+use std::io::{Read, Write};
+
+use arcstr::ArcStr;
+use serde::{Deserialize, Serialize};
+
+/// The magic bytes at the start of a serialized [`LookupTable`].
+const MAGIC: &[u8; 4] = b"SLUT";
+/// The version of the lookup table binary format.
+const FORMAT_VERSION: u32 = 1;
+
+/// An error encountered while constructing or querying a [`LookupTable`].
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum LookupTableError {
+    /// An axis has no points.
+    #[error("axis `{0}` has no points")]
+    EmptyAxis(ArcStr),
+    /// The points of an axis are not strictly increasing.
+    #[error("points of axis `{0}` are not strictly increasing")]
+    UnsortedAxis(ArcStr),
+    /// The number of data values does not match the size of the grid.
+    #[error("expected {expected} data values, found {found}")]
+    DataLength {
+        /// The expected number of values.
+        expected: usize,
+        /// The number of values provided.
+        found: usize,
+    },
+    /// The number of coordinates does not match the number of axes.
+    #[error("expected {expected} coordinates, found {found}")]
+    Dimension {
+        /// The number of axes.
+        expected: usize,
+        /// The number of coordinates provided.
+        found: usize,
+    },
+    /// The table does not contain the requested quantity.
+    #[error("lookup table has no quantity named `{0}`")]
+    UnknownQuantity(ArcStr),
+    /// The table does not have an axis with the requested name.
+    #[error("lookup table has no axis named `{0}`")]
+    UnknownAxis(ArcStr),
+}
+
+/// A sweep axis of a [`LookupTable`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Axis {
+    /// The name of the swept variable.
+    pub name: ArcStr,
+    /// The sweep points, in strictly increasing order.
+    pub points: Vec<f64>,
+}
+
+impl Axis {
+    /// Creates a new axis with the given points.
+    pub fn new(name: impl Into<ArcStr>, points: impl Into<Vec<f64>>) -> Self {
+        Self {
+            name: name.into(),
+            points: points.into(),
+        }
+    }
+
+    /// Creates an axis with `n` evenly spaced points from `start` to `stop`, inclusive.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is less than 2.
+    pub fn linear(name: impl Into<ArcStr>, start: f64, stop: f64, n: usize) -> Self {
+        assert!(n >= 2, "a linear axis must have at least 2 points");
+        let step = (stop - start) / (n - 1) as f64;
+        Self::new(
+            name,
+            (0..n).map(|i| start + i as f64 * step).collect::<Vec<_>>(),
+        )
+    }
+
+    /// Returns the index `i` and weight `t` such that `x` is approximately
+    /// `(1 - t) * points[i] + t * points[i + 1]`.
+    ///
+    /// Coordinates outside the axis are clamped to its endpoints.
+    fn locate(&self, x: f64) -> (usize, f64) {
+        let n = self.points.len();
+        if n == 1 || x <= self.points[0] {
+            return (0, 0.);
+        }
+        if x >= self.points[n - 1] {
+            return (n - 2, 1.);
+        }
+        let i = self.points.partition_point(|&p| p <= x) - 1;
+        let (lo, hi) = (self.points[i], self.points[i + 1]);
+        (i, (x - lo) / (hi - lo))
+    }
+}
+
+/// Simulated quantities sampled on a rectilinear grid.
+///
+/// Values are stored in row-major order: the last axis varies fastest,
+/// and all quantities for a grid point are stored contiguously.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LookupTable {
+    axes: Vec<Axis>,
+    quantities: Vec<ArcStr>,
+    data: Vec<f64>,
+}
+
+impl LookupTable {
+    /// Creates a lookup table from existing data.
+    ///
+    /// `data` must contain one value per quantity for each grid point, in the order
+    /// described in the documentation of [`LookupTable`].
+    pub fn new(
+        axes: Vec<Axis>,
+        quantities: Vec<ArcStr>,
+        data: Vec<f64>,
+    ) -> Result<Self, LookupTableError> {
+        for axis in axes.iter() {
+            if axis.points.is_empty() {
+                return Err(LookupTableError::EmptyAxis(axis.name.clone()));
+            }
+            if axis.points.windows(2).any(|w| w[0] >= w[1]) {
+                return Err(LookupTableError::UnsortedAxis(axis.name.clone()));
+            }
+        }
+        let expected =
+            axes.iter().map(|axis| axis.points.len()).product::<usize>() * quantities.len();
+        if data.len() != expected {
+            return Err(LookupTableError::DataLength {
+                expected,
+                found: data.len(),
+            });
+        }
+        Ok(Self {
+            axes,
+            quantities,
+            data,
+        })
+    }
+
+    /// Populates a lookup table by calling `f` at every point of the grid.
+    ///
+    /// `f` receives the coordinates of a grid point (one per axis, in axis order) and must
+    /// return one value per quantity. Grid points are visited in row-major order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `f` returns the wrong number of values, or if the axes are invalid.
+    pub fn sweep<E>(
+        axes: Vec<Axis>,
+        quantities: Vec<ArcStr>,
+        mut f: impl FnMut(&[f64]) -> Result<Vec<f64>, E>,
+    ) -> Result<Self, E> {
+        let n = axes.iter().map(|axis| axis.points.len()).product::<usize>();
+        let mut data = Vec::with_capacity(n * quantities.len());
+        let mut coords = vec![0.; axes.len()];
+        for idx in 0..n {
+            let mut rem = idx;
+            for (coord, axis) in coords.iter_mut().zip(axes.iter()).rev() {
+                *coord = axis.points[rem % axis.points.len()];
+                rem /= axis.points.len();
+            }
+            let values = f(&coords)?;
+            assert_eq!(
+                values.len(),
+                quantities.len(),
+                "sweep function returned the wrong number of quantities"
+            );
+            data.extend(values);
+        }
+        Ok(Self::new(axes, quantities, data).expect("invalid lookup table axes"))
+    }
+
+    /// The sweep axes of this table.
+    pub fn axes(&self) -> &[Axis] {
+        &self.axes
+    }
+
+    /// Returns the axis with the given name.
+    pub fn axis(&self, name: &str) -> Option<&Axis> {
+        self.axes.iter().find(|axis| axis.name == name)
+    }
+
+    /// The names of the quantities stored in this table.
+    pub fn quantities(&self) -> &[ArcStr] {
+        &self.quantities
+    }
+
+    fn quantity_index(&self, quantity: &str) -> Result<usize, LookupTableError> {
+        self.quantities
+            .iter()
+            .position(|q| q == quantity)
+            .ok_or_else(|| LookupTableError::UnknownQuantity(quantity.into()))
+    }
+
+    fn check_dimension(&self, found: usize) -> Result<(), LookupTableError> {
+        if found != self.axes.len() {
+            return Err(LookupTableError::Dimension {
+                expected: self.axes.len(),
+                found,
+            });
+        }
+        Ok(())
+    }
+
+    fn flat_index(&self, indices: &[usize]) -> usize {
+        indices
+            .iter()
+            .zip(self.axes.iter())
+            .fold(0, |acc, (&i, axis)| acc * axis.points.len() + i)
+    }
+
+    /// Returns the value of `quantity` at the grid point with the given axis indices.
+    ///
+    /// Returns [`None`] if the quantity does not exist or the indices are out of bounds.
+    pub fn get(&self, quantity: &str, indices: &[usize]) -> Option<f64> {
+        let q = self.quantity_index(quantity).ok()?;
+        if indices.len() != self.axes.len()
+            || indices
+                .iter()
+                .zip(self.axes.iter())
+                .any(|(&i, axis)| i >= axis.points.len())
+        {
+            return None;
+        }
+        Some(self.data[self.flat_index(indices) * self.quantities.len() + q])
+    }
+
+    /// Interpolates `quantity` at the given coordinates.
+    ///
+    /// Uses multilinear interpolation between the surrounding grid points.
+    /// Coordinates outside the range of an axis are clamped to the nearest endpoint,
+    /// so the table is never extrapolated.
+    pub fn interpolate(&self, quantity: &str, coords: &[f64]) -> Result<f64, LookupTableError> {
+        let q = self.quantity_index(quantity)?;
+        self.check_dimension(coords.len())?;
+        let located = self
+            .axes
+            .iter()
+            .zip(coords)
+            .map(|(axis, &x)| axis.locate(x))
+            .collect::<Vec<_>>();
+
+        let mut value = 0.;
+        let mut indices = vec![0; located.len()];
+        for corner in 0..(1usize << located.len()) {
+            let mut weight = 1.;
+            for (dim, &(i, t)) in located.iter().enumerate() {
+                if corner & (1 << dim) == 0 {
+                    indices[dim] = i;
+                    weight *= 1. - t;
+                } else {
+                    indices[dim] = i + 1;
+                    weight *= t;
+                }
+            }
+            if weight == 0. {
+                continue;
+            }
+            value += weight * self.data[self.flat_index(&indices) * self.quantities.len() + q];
+        }
+        Ok(value)
+    }
+
+    /// Writes this table in a compact little-endian binary format.
+    ///
+    /// The format consists of the magic bytes `SLUT`, a format version, the axes
+    /// (name and points), the quantity names, and the raw data values.
+    pub fn write<W: Write>(&self, mut out: W) -> std::io::Result<()> {
+        out.write_all(MAGIC)?;
+        out.write_all(&FORMAT_VERSION.to_le_bytes())?;
+        write_len(&mut out, self.axes.len())?;
+        for axis in self.axes.iter() {
+            write_str(&mut out, &axis.name)?;
+            write_f64s(&mut out, &axis.points)?;
+        }
+        write_len(&mut out, self.quantities.len())?;
+        for quantity in self.quantities.iter() {
+            write_str(&mut out, quantity)?;
+        }
+        write_f64s(&mut out, &self.data)
+    }
+
+    /// Reads a table written by [`LookupTable::write`].
+    pub fn read<R: Read>(mut input: R) -> std::io::Result<Self> {
+        let mut magic = [0u8; 4];
+        input.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("not a lookup table file".to_string()));
+        }
+        let version = read_u32(&mut input)?;
+        if version != FORMAT_VERSION {
+            return Err(invalid_data(format!(
+                "unsupported lookup table format version {version}"
+            )));
+        }
+        // Lengths read from the input are untrusted, so they are not used to preallocate.
+        let num_axes = read_len(&mut input)?;
+        let mut axes = Vec::new();
+        for _ in 0..num_axes {
+            let name = read_str(&mut input)?;
+            let points = read_f64s(&mut input)?;
+            axes.push(Axis::new(name, points));
+        }
+        let num_quantities = read_len(&mut input)?;
+        let quantities = (0..num_quantities)
+            .map(|_| read_str(&mut input).map(ArcStr::from))
+            .collect::<std::io::Result<Vec<_>>>()?;
+        let data = read_f64s(&mut input)?;
+        Self::new(axes, quantities, data).map_err(|e| invalid_data(e.to_string()))
+    }
+}
+
+fn invalid_data(msg: String) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, msg)
+}
+
+fn write_len<W: Write>(out: &mut W, len: usize) -> std::io::Result<()> {
+    out.write_all(&(len as u64).to_le_bytes())
+}
+
+fn write_str<W: Write>(out: &mut W, s: &str) -> std::io::Result<()> {
+    write_len(out, s.len())?;
+    out.write_all(s.as_bytes())
+}
+
+fn write_f64s<W: Write>(out: &mut W, values: &[f64]) -> std::io::Result<()> {
+    write_len(out, values.len())?;
+    for value in values {
+        out.write_all(&value.to_le_bytes())?;
+    }
+    Ok(())
+}
+
+fn read_u32<R: Read>(input: &mut R) -> std::io::Result<u32> {
+    let mut buf = [0u8; 4];
+    input.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn read_len<R: Read>(input: &mut R) -> std::io::Result<usize> {
+    let mut buf = [0u8; 8];
+    input.read_exact(&mut buf)?;
+    usize::try_from(u64::from_le_bytes(buf)).map_err(|e| invalid_data(e.to_string()))
+}
+
+fn read_str<R: Read>(input: &mut R) -> std::io::Result<String> {
+    let len = read_len(input)?;
+    let mut buf = Vec::new();
+    input.by_ref().take(len as u64).read_to_end(&mut buf)?;
+    if buf.len() != len {
+        return Err(std::io::ErrorKind::UnexpectedEof.into());
+    }
+    String::from_utf8(buf).map_err(|e| invalid_data(e.to_string()))
+}
+
+fn read_f64s<R: Read>(input: &mut R) -> std::io::Result<Vec<f64>> {
+    let len = read_len(input)?;
+    let mut values = Vec::new();
+    let mut buf = [0u8; 8];
+    for _ in 0..len {
+        input.read_exact(&mut buf)?;
+        values.push(f64::from_le_bytes(buf));
+    }
+    Ok(values)
+}
+
+/// The bias point of a transistor being characterized by [`GmIdTable::characterize`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GmIdBias {
+    /// The channel width.
+    pub w: f64,
+    /// The channel length.
+    pub l: f64,
+    /// The gate-source voltage magnitude.
+    pub vgs: f64,
+    /// The drain-source voltage magnitude.
+    pub vds: f64,
+}
+
+/// The small-signal operating point of a characterized transistor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GmIdOp {
+    /// The drain current magnitude.
+    pub id: f64,
+    /// The transconductance.
+    pub gm: f64,
+    /// The output conductance.
+    pub gds: f64,
+}
+
+/// A gm/ID lookup table for a single transistor flavor.
+///
+/// The table is indexed by channel width, channel length, gate-source voltage, and
+/// drain-source voltage. Currents and conductances are stored per unit width, so that
+/// narrow-width effects show up as a dependence of the densities on width.
+/// For PMOS devices, all voltages and currents should be given as magnitudes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GmIdTable {
+    lut: LookupTable,
+}
+
+impl GmIdTable {
+    /// The name of the channel width axis.
+    pub const W: &'static str = "w";
+    /// The name of the channel length axis.
+    pub const L: &'static str = "l";
+    /// The name of the gate-source voltage axis.
+    pub const VGS: &'static str = "vgs";
+    /// The name of the drain-source voltage axis.
+    pub const VDS: &'static str = "vds";
+    /// The name of the drain current density quantity.
+    pub const ID_W: &'static str = "id_w";
+    /// The name of the transconductance density quantity.
+    pub const GM_W: &'static str = "gm_w";
+    /// The name of the output conductance density quantity.
+    pub const GDS_W: &'static str = "gds_w";
+
+    /// Characterizes a transistor by calling `simulate` at every combination
+    /// of the given widths, lengths, and bias voltages.
+    ///
+    /// `simulate` typically runs a DC operating point analysis of a testbench
+    /// containing the transistor and returns its operating point.
+    pub fn characterize<E>(
+        w: Vec<f64>,
+        l: Vec<f64>,
+        vgs: Vec<f64>,
+        vds: Vec<f64>,
+        mut simulate: impl FnMut(GmIdBias) -> Result<GmIdOp, E>,
+    ) -> Result<Self, E> {
+        let lut = LookupTable::sweep(
+            vec![
+                Axis::new(Self::W, w),
+                Axis::new(Self::L, l),
+                Axis::new(Self::VGS, vgs),
+                Axis::new(Self::VDS, vds),
+            ],
+            vec![
+                ArcStr::from(Self::ID_W),
+                ArcStr::from(Self::GM_W),
+                ArcStr::from(Self::GDS_W),
+            ],
+            |coords| {
+                let bias = GmIdBias {
+                    w: coords[0],
+                    l: coords[1],
+                    vgs: coords[2],
+                    vds: coords[3],
+                };
+                let op = simulate(bias)?;
+                Ok(vec![op.id / bias.w, op.gm / bias.w, op.gds / bias.w])
+            },
+        )?;
+        Ok(Self { lut })
+    }
+
+    /// Creates a gm/ID table from an existing lookup table.
+    ///
+    /// The lookup table must have the axes [`GmIdTable::W`], [`GmIdTable::L`],
+    /// [`GmIdTable::VGS`], and [`GmIdTable::VDS`] (in that order) and the quantities
+    /// [`GmIdTable::ID_W`], [`GmIdTable::GM_W`], and [`GmIdTable::GDS_W`].
+    pub fn from_lut(lut: LookupTable) -> Result<Self, LookupTableError> {
+        for (i, name) in [Self::W, Self::L, Self::VGS, Self::VDS]
+            .into_iter()
+            .enumerate()
+        {
+            if lut.axes.get(i).map(|axis| &axis.name) != Some(&ArcStr::from(name)) {
+                return Err(LookupTableError::UnknownAxis(name.into()));
+            }
+        }
+        lut.check_dimension(4)?;
+        for quantity in [Self::ID_W, Self::GM_W, Self::GDS_W] {
+            lut.quantity_index(quantity)?;
+        }
+        Ok(Self { lut })
+    }
+
+    /// The underlying lookup table.
+    pub fn lut(&self) -> &LookupTable {
+        &self.lut
+    }
+
+    fn value(&self, quantity: &str, bias: GmIdBias) -> f64 {
+        self.lut
+            .interpolate(quantity, &[bias.w, bias.l, bias.vgs, bias.vds])
+            .expect("gm/ID table should have valid axes and quantities")
+    }
+
+    /// The gm/ID ratio at the given bias point.
+    pub fn gm_id(&self, bias: GmIdBias) -> f64 {
+        self.value(Self::GM_W, bias) / self.value(Self::ID_W, bias)
+    }
+
+    /// Finds the gate-source voltage at which the transistor has the given gm/ID ratio.
+    ///
+    /// Searches the characterized gate-source voltages for a pair of adjacent points
+    /// whose gm/ID ratios bracket `gm_id`, then interpolates linearly between them.
+    /// Returns [`None`] if `gm_id` is not achievable within the characterized range.
+    pub fn vgs_for_gm_id(&self, w: f64, l: f64, vds: f64, gm_id: f64) -> Option<f64> {
+        let vgs = &self.lut.axes[2].points;
+        let ratios = vgs
+            .iter()
+            .map(|&vgs| self.gm_id(GmIdBias { w, l, vgs, vds }))
+            .collect::<Vec<_>>();
+        (0..vgs.len()).find_map(|i| {
+            if ratios[i] == gm_id {
+                return Some(vgs[i]);
+            }
+            let (r0, r1) = (ratios[i], *ratios.get(i + 1)?);
+            if (r0 - gm_id) * (r1 - gm_id) < 0. {
+                let t = (gm_id - r0) / (r1 - r0);
+                Some(vgs[i] + t * (vgs[i + 1] - vgs[i]))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// The drain current per unit width at the given gm/ID ratio.
+    pub fn current_density(&self, w: f64, l: f64, vds: f64, gm_id: f64) -> Option<f64> {
+        let vgs = self.vgs_for_gm_id(w, l, vds, gm_id)?;
+        Some(self.value(Self::ID_W, GmIdBias { w, l, vgs, vds }))
+    }
+
+    /// The intrinsic gain (gm/gds) at the given gm/ID ratio.
+    pub fn intrinsic_gain(&self, w: f64, l: f64, vds: f64, gm_id: f64) -> Option<f64> {
+        let vgs = self.vgs_for_gm_id(w, l, vds, gm_id)?;
+        let bias = GmIdBias { w, l, vgs, vds };
+        Some(self.value(Self::GM_W, bias) / self.value(Self::GDS_W, bias))
+    }
+
+    /// The width required to conduct drain current `id` at the given gm/ID ratio.
+    ///
+    /// Since the current density depends on width, the width is found by fixed-point
+    /// iteration starting from the smallest characterized width. Returns [`None`] if
+    /// no widths were characterized.
+    pub fn width_for_current(&self, id: f64, l: f64, vds: f64, gm_id: f64) -> Option<f64> {
+        let mut w = *self.lut.axes.first()?.points.first()?;
+        for _ in 0..WIDTH_ITERATIONS {
+            let next = id / self.current_density(w, l, vds, gm_id)?;
+            if (next - w).abs() <= WIDTH_TOLERANCE * w {
+                return Some(next);
+            }
+            w = next;
+        }
+        Some(w)
+    }
+}
+
+/// The maximum number of iterations used by [`GmIdTable::width_for_current`].
+const WIDTH_ITERATIONS: usize = 32;
+/// The relative tolerance at which [`GmIdTable::width_for_current`] stops iterating.
+const WIDTH_TOLERANCE: f64 = 1e-9;
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    /// A square-law transistor model with channel length modulation.
+    fn square_law(bias: GmIdBias) -> Result<GmIdOp, std::convert::Infallible> {
+        let (k, vth, lambda) = (50. * bias.w / bias.l, 0.4, 0.1);
+        let vov = bias.vgs - vth;
+        Ok(GmIdOp {
+            id: 0.5 * k * vov * vov * (1. + lambda * bias.vds),
+            gm: k * vov * (1. + lambda * bias.vds),
+            gds: 0.5 * k * vov * vov * lambda,
+        })
+    }
+
+    #[test]
+    fn lookup_table_interpolates_multilinearly() {
+        let lut = LookupTable::sweep(
+            vec![Axis::linear("x", 0., 2., 3), Axis::new("y", [0., 10.])],
+            vec![ArcStr::from("sum"), ArcStr::from("prod")],
+            |c| Ok::<_, ()>(vec![c[0] + c[1], c[0] * c[1]]),
+        )
+        .unwrap();
+        assert_eq!(lut.get("prod", &[2, 1]), Some(20.));
+        assert_relative_eq!(lut.interpolate("sum", &[0.5, 2.5]).unwrap(), 3.);
+        assert_relative_eq!(lut.interpolate("prod", &[1.5, 5.]).unwrap(), 7.5);
+        // Coordinates are clamped to the grid.
+        assert_relative_eq!(lut.interpolate("sum", &[5., -1.]).unwrap(), 2.);
+        assert_eq!(
+            lut.interpolate("sum", &[1.]),
+            Err(LookupTableError::Dimension {
+                expected: 2,
+                found: 1
+            })
+        );
+
+        let mut buf = Vec::new();
+        lut.write(&mut buf).unwrap();
+        assert_eq!(LookupTable::read(buf.as_slice()).unwrap(), lut);
+    }
+
+    #[test]
+    fn lookup_table_read_rejects_truncated_input() {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(MAGIC);
+        buf.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        // Claims far more axes than the input contains.
+        buf.extend_from_slice(&u64::MAX.to_le_bytes());
+        assert_eq!(
+            LookupTable::read(buf.as_slice()).unwrap_err().kind(),
+            std::io::ErrorKind::UnexpectedEof
+        );
+    }
+
+    #[test]
+    fn gm_id_table_sizes_square_law_device() {
+        let table = GmIdTable::characterize(
+            vec![1e-6, 2e-6, 4e-6],
+            vec![150e-9, 300e-9],
+            Axis::linear("vgs", 0.45, 1.2, 76).points,
+            vec![0.5, 1.0],
+            square_law,
+        )
+        .unwrap();
+
+        // For a square-law device, gm/ID = 2 / Vov.
+        let vgs = table.vgs_for_gm_id(2e-6, 150e-9, 0.5, 10.).unwrap();
+        assert_relative_eq!(vgs, 0.6, epsilon = 1e-3);
+        assert!(table.vgs_for_gm_id(2e-6, 150e-9, 0.5, 100.).is_none());
+
+        let density = table.current_density(3e-6, 150e-9, 0.5, 10.).unwrap();
+        let bias = GmIdBias {
+            w: 2e-6,
+            l: 150e-9,
+            vgs: 0.6,
+            vds: 0.5,
+        };
+        let expected = square_law(bias).unwrap().id / 2e-6;
+        assert_relative_eq!(density, expected, max_relative = 1e-2);
+        assert_relative_eq!(
+            table
+                .width_for_current(2. * expected * 1e-6, 150e-9, 0.5, 10.)
+                .unwrap(),
+            2e-6,
+            max_relative = 1e-2
+        );
+        assert!(GmIdTable::from_lut(table.lut().clone()).is_ok());
+    }
+
+    #[test]
+    fn gm_id_table_without_widths_cannot_size_devices() {
+        // Tables deserialized with serde are not validated, so their axes may be empty.
+        let table = GmIdTable {
+            lut: LookupTable {
+                axes: vec![
+                    Axis::new(GmIdTable::W, Vec::new()),
+                    Axis::new(GmIdTable::L, [150e-9]),
+                    Axis::new(GmIdTable::VGS, [0.6]),
+                    Axis::new(GmIdTable::VDS, [0.5]),
+                ],
+                quantities: vec![
+                    ArcStr::from(GmIdTable::ID_W),
+                    ArcStr::from(GmIdTable::GM_W),
+                    ArcStr::from(GmIdTable::GDS_W),
+                ],
+                data: Vec::new(),
+            },
+        };
+        assert_eq!(table.width_for_current(1e-6, 150e-9, 0.5, 10.), None);
+    }
+}
@@ -0,0 +1,376 @@
+//! PVT corner sweeps and worst-case corner search.
+//!
+//! A [`PvtSpace`] describes the process corners, supply voltages, and temperatures over
+//! which a design must be characterized. [`PvtSpace::exhaustive`] enumerates every
+//! combination, while [`CornerSearch`] estimates the sensitivity of each measurement to
+//! each PVT dimension from a few one-at-a-time runs and only simulates the corners that
+//! are predicted to be worst, which typically requires far fewer simulations.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use arcstr::ArcStr;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use super::Pvt;
+
+/// The set of PVT conditions over which a design is characterized.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PvtSpace<C> {
+    /// The process corners.
+    pub corners: Vec<C>,
+    /// The supply voltages.
+    pub voltages: Vec<Decimal>,
+    /// The temperatures, in degrees Celsius.
+    pub temps: Vec<Decimal>,
+}
+
+impl<C: Clone> PvtSpace<C> {
+    /// Creates a new PVT space.
+    pub fn new(corners: Vec<C>, voltages: Vec<Decimal>, temps: Vec<Decimal>) -> Self {
+        Self {
+            corners,
+            voltages,
+            temps,
+        }
+    }
+
+    /// The number of PVT combinations in this space.
+    pub fn len(&self) -> usize {
+        self.corners.len() * self.voltages.len() * self.temps.len()
+    }
+
+    /// Returns `true` if this space contains no PVT combinations.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Enumerates every PVT combination in this space.
+    ///
+    /// Temperatures vary fastest, followed by voltages, then process corners.
+    pub fn exhaustive(&self) -> Vec<Pvt<C>> {
+        self.indexed().map(|(_, pvt)| pvt).collect()
+    }
+
+    fn get(&self, [c, v, t]: [usize; 3]) -> Pvt<C> {
+        Pvt::new(self.corners[c].clone(), self.voltages[v], self.temps[t])
+    }
+
+    fn indexed(&self) -> impl Iterator<Item = ([usize; 3], Pvt<C>)> + '_ {
+        (0..self.corners.len()).flat_map(move |c| {
+            (0..self.voltages.len()).flat_map(move |v| {
+                (0..self.temps.len()).map(move |t| ([c, v, t], self.get([c, v, t])))
+            })
+        })
+    }
+}
+
+/// The direction in which a measurement is worst.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Worst {
+    /// Larger values are worse (e.g. delay or power).
+    Max,
+    /// Smaller values are worse (e.g. gain or phase margin).
+    Min,
+}
+
+impl Worst {
+    /// Returns `true` if `a` is worse than `b`.
+    pub fn is_worse(&self, a: f64, b: f64) -> bool {
+        match self {
+            Worst::Max => a > b,
+            Worst::Min => a < b,
+        }
+    }
+}
+
+/// A measurement tracked by a [`CornerSearch`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CornerMeasurement {
+    /// The name of the measurement.
+    pub name: ArcStr,
+    /// The direction in which the measurement is worst.
+    pub worst: Worst,
+}
+
+impl CornerMeasurement {
+    /// Creates a new measurement.
+    pub fn new(name: impl Into<ArcStr>, worst: Worst) -> Self {
+        Self {
+            name: name.into(),
+            worst,
+        }
+    }
+}
+
+/// A search for the worst-case PVT corner of each of a set of measurements.
+///
+/// The search proceeds in two phases:
+/// 1. **Sensitivity estimation.** The nominal point is simulated, followed by each value of
+///    each PVT dimension with the other dimensions held at their nominal values.
+/// 2. **Verification.** For each measurement, every PVT combination is ranked by its
+///    predicted value, assuming the effects of the dimensions are additive. The most
+///    pessimistic [`candidates`](CornerSearch::candidates) combinations are simulated.
+///
+/// Since interactions between dimensions are not modeled, the search may miss the true
+/// worst case of strongly non-additive measurements; increase the number of candidates
+/// or use [`PvtSpace::exhaustive`] for such measurements.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CornerSearch<C> {
+    space: PvtSpace<C>,
+    nominal: [usize; 3],
+    candidates: usize,
+}
+
+/// The worst case found for a single measurement.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorstCase<C> {
+    /// The name of the measurement.
+    pub measurement: ArcStr,
+    /// The worst simulated PVT corner.
+    pub pvt: Pvt<C>,
+    /// The value of the measurement at the worst corner.
+    pub value: f64,
+    /// The value predicted by the sensitivity model at the worst corner.
+    pub predicted: f64,
+}
+
+/// The result of a [`CornerSearch`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CornerSearchResult<C> {
+    /// Every simulated PVT corner and its measurements, in simulation order.
+    pub simulated: Vec<(Pvt<C>, Vec<f64>)>,
+    /// The worst case of each measurement, in the order the measurements were given.
+    pub worst: Vec<WorstCase<C>>,
+}
+
+impl<C> CornerSearchResult<C> {
+    /// Returns the worst case of the measurement with the given name.
+    pub fn get(&self, measurement: &str) -> Option<&WorstCase<C>> {
+        self.worst.iter().find(|w| w.measurement == measurement)
+    }
+}
+
+impl<C: Clone + Hash + Eq> CornerSearch<C> {
+    /// Creates a corner search over `space`, with sensitivities estimated around `nominal`.
+    ///
+    /// By default, a single candidate is verified per measurement.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `nominal` is not contained in `space`.
+    pub fn new(space: PvtSpace<C>, nominal: &Pvt<C>) -> Self {
+        let position = |found: Option<usize>, dim: &str| {
+            found.unwrap_or_else(|| panic!("nominal {dim} is not in the PVT space"))
+        };
+        let nominal = [
+            position(
+                space.corners.iter().position(|c| c == &nominal.corner),
+                "corner",
+            ),
+            position(
+                space.voltages.iter().position(|v| v == &nominal.voltage),
+                "voltage",
+            ),
+            position(
+                space.temps.iter().position(|t| t == &nominal.temp),
+                "temperature",
+            ),
+        ];
+        Self {
+            space,
+            nominal,
+            candidates: 1,
+        }
+    }
+
+    /// Sets the number of predicted-worst corners verified by simulation for each measurement.
+    pub fn candidates(mut self, candidates: usize) -> Self {
+        self.candidates = candidates.max(1);
+        self
+    }
+
+    /// Runs the search.
+    ///
+    /// `simulate` must return one value for each measurement, in the same order
+    /// as `measurements`. Each PVT corner is simulated at most once.
+    pub fn run<E>(
+        &self,
+        measurements: &[CornerMeasurement],
+        mut simulate: impl FnMut(&Pvt<C>) -> Result<Vec<f64>, E>,
+    ) -> Result<CornerSearchResult<C>, E> {
+        let mut runs = Runs {
+            simulated: Vec::new(),
+            lookup: HashMap::new(),
+        };
+        let mut eval = |runs: &mut Runs<C>, idx: [usize; 3]| -> Result<usize, E> {
+            if let Some(&i) = runs.lookup.get(&idx) {
+                return Ok(i);
+            }
+            let pvt = self.space.get(idx);
+            let values = simulate(&pvt)?;
+            assert_eq!(
+                values.len(),
+                measurements.len(),
+                "expected one value per measurement"
+            );
+            runs.simulated.push((pvt, values));
+            runs.lookup.insert(idx, runs.simulated.len() - 1);
+            Ok(runs.simulated.len() - 1)
+        };
+
+        // Phase 1: one-at-a-time sensitivity runs.
+        let dims = [
+            self.space.corners.len(),
+            self.space.voltages.len(),
+            self.space.temps.len(),
+        ];
+        let nominal = eval(&mut runs, self.nominal)?;
+        let mut sensitivity = Vec::new();
+        for (dim, &len) in dims.iter().enumerate() {
+            let mut dim_runs = Vec::with_capacity(len);
+            for value in 0..len {
+                let mut idx = self.nominal;
+                idx[dim] = value;
+                dim_runs.push(eval(&mut runs, idx)?);
+            }
+            sensitivity.push(dim_runs);
+        }
+
+        // Phase 2: rank combinations by predicted value and verify the most pessimistic.
+        let mut worst = Vec::with_capacity(measurements.len());
+        for (m, measurement) in measurements.iter().enumerate() {
+            let mut ranked = self
+                .space
+                .indexed()
+                .map(|(idx, _)| (idx, runs.predict(nominal, &sensitivity, idx, m)))
+                .collect::<Vec<_>>();
+            ranked.sort_by(|a, b| match measurement.worst {
+                Worst::Max => b.1.total_cmp(&a.1),
+                Worst::Min => a.1.total_cmp(&b.1),
+            });
+            for &(idx, _) in ranked.iter().take(self.candidates) {
+                eval(&mut runs, idx)?;
+            }
+
+            // Every simulated corner, including sensitivity runs, is considered.
+            let value = |i: usize| runs.simulated[i].1[m];
+            let i = (0..runs.simulated.len())
+                .reduce(|acc, j| {
+                    if measurement.worst.is_worse(value(j), value(acc)) {
+                        j
+                    } else {
+                        acc
+                    }
+                })
+                .expect("at least one corner is simulated");
+            let idx = *runs
+                .lookup
+                .iter()
+                .find(|(_, j)| **j == i)
+                .map(|(idx, _)| idx)
+                .expect("simulated corner should be indexed");
+            worst.push(WorstCase {
+                measurement: measurement.name.clone(),
+                pvt: runs.simulated[i].0.clone(),
+                value: value(i),
+                predicted: runs.predict(nominal, &sensitivity, idx, m),
+            });
+        }
+
+        Ok(CornerSearchResult {
+            simulated: runs.simulated,
+            worst,
+        })
+    }
+}
+
+/// The PVT corners simulated during a [`CornerSearch`].
+struct Runs<C> {
+    simulated: Vec<(Pvt<C>, Vec<f64>)>,
+    lookup: HashMap<[usize; 3], usize>,
+}
+
+impl<C> Runs<C> {
+    /// Predicts measurement `m` at `idx` using an additive model of one-at-a-time effects.
+    fn predict(
+        &self,
+        nominal: usize,
+        sensitivity: &[Vec<usize>],
+        idx: [usize; 3],
+        m: usize,
+    ) -> f64 {
+        let base = self.simulated[nominal].1[m];
+        base + (0..3)
+            .map(|dim| self.simulated[sensitivity[dim][idx[dim]]].1[m] - base)
+            .sum::<f64>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+    enum Corner {
+        Tt,
+        Ss,
+        Ff,
+    }
+
+    fn space() -> PvtSpace<Corner> {
+        PvtSpace::new(
+            vec![Corner::Tt, Corner::Ss, Corner::Ff],
+            vec![dec!(1.62), dec!(1.8), dec!(1.98)],
+            vec![dec!(-40), dec!(25), dec!(125)],
+        )
+    }
+
+    /// A delay that increases in the slow corner, at low voltage, and at high temperature,
+    /// and a gain that is worst in the fast corner.
+    fn simulate(pvt: &Pvt<Corner>) -> Result<Vec<f64>, ()> {
+        let process = match pvt.corner {
+            Corner::Tt => 0.,
+            Corner::Ss => 1.,
+            Corner::Ff => -1.,
+        };
+        let v: f64 = pvt.voltage.try_into().unwrap();
+        let t: f64 = pvt.temp.try_into().unwrap();
+        let delay = 10. + 2. * process + 5. * (1.8 - v) + 0.01 * t + 0.05 * process * (1.8 - v);
+        let gain = 40. - 3. * process.min(0.).abs() + 0.1 * v - 0.02 * t;
+        Ok(vec![delay, gain])
+    }
+
+    #[test]
+    fn corner_search_finds_worst_corners_with_fewer_simulations() {
+        let space = space();
+        let nominal = Pvt::new(Corner::Tt, dec!(1.8), dec!(25));
+        let measurements = [
+            CornerMeasurement::new("delay", Worst::Max),
+            CornerMeasurement::new("gain", Worst::Min),
+        ];
+        let result = CornerSearch::new(space.clone(), &nominal)
+            .run(&measurements, simulate)
+            .unwrap();
+
+        assert!(result.simulated.len() < space.len());
+        let delay = result.get("delay").unwrap();
+        assert_eq!(delay.pvt, Pvt::new(Corner::Ss, dec!(1.62), dec!(125)));
+        let gain = result.get("gain").unwrap();
+        assert_eq!(gain.pvt, Pvt::new(Corner::Ff, dec!(1.62), dec!(125)));
+
+        // The worst cases agree with an exhaustive sweep.
+        let exhaustive = space
+            .exhaustive()
+            .iter()
+            .map(|pvt| simulate(pvt).unwrap())
+            .collect::<Vec<_>>();
+        let max_delay = exhaustive.iter().map(|m| m[0]).fold(f64::MIN, f64::max);
+        let min_gain = exhaustive.iter().map(|m| m[1]).fold(f64::MAX, f64::min);
+        assert_eq!(delay.value, max_delay);
+        assert_eq!(gain.value, min_gain);
+    }
+}
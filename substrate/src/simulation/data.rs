@@ -1,15 +1,225 @@
 //! Interfaces for interacting with simulation data.
 
+use std::collections::HashMap;
+use std::marker::PhantomData;
 use std::ops::Deref;
 
+use arcstr::ArcStr;
 use codegen::impl_save_tuples;
+use derive_where::derive_where;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     schematic::{HasNestedView, NestedInstance, NestedView, Schematic},
     simulation::{Analysis, SimulationContext, Simulator},
-    types::schematic::{IoTerminalBundle, NestedNode},
+    types::schematic::{IoTerminalBundle, NestedNode, NestedTerminal},
+    units::{Amps, Volts},
 };
 
+/// The maximum number of near-miss signal names reported by [`SignalLookupError::NotFound`].
+const MAX_NEAR_MISSES: usize = 5;
+
+/// An error looking up a simulation waveform by hierarchical path.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SignalLookupError {
+    /// The path could not be resolved to a simulator signal name.
+    #[error("path `{0}` could not be resolved to a simulator signal")]
+    Unresolved(String),
+    /// The simulator signal name corresponding to the path was not present in the output.
+    #[error("signal `{name}` was not saved{}", fmt_near_misses(.near_misses))]
+    NotFound {
+        /// The simulator signal name that was looked up.
+        name: ArcStr,
+        /// Saved signal names that are similar to `name`, most similar first.
+        near_misses: Vec<ArcStr>,
+    },
+}
+
+fn fmt_near_misses(near_misses: &[ArcStr]) -> String {
+    if near_misses.is_empty() {
+        String::new()
+    } else {
+        let names = near_misses
+            .iter()
+            .map(|name| format!("`{name}`"))
+            .collect::<Vec<_>>();
+        format!("; similar saved signals: {}", names.join(", "))
+    }
+}
+
+impl SignalLookupError {
+    /// Creates a [`SignalLookupError::NotFound`] error for `name`,
+    /// listing the entries of `saved` that are most similar to `name`.
+    pub fn not_found<'a>(
+        name: impl Into<ArcStr>,
+        saved: impl IntoIterator<Item = &'a ArcStr>,
+    ) -> Self {
+        let name = name.into();
+        let near_misses = near_misses(&name, saved);
+        Self::NotFound { name, near_misses }
+    }
+}
+
+/// Returns the entries of `candidates` that are similar to `name`, most similar first.
+///
+/// Signal names are compared case-insensitively. A candidate is considered similar if
+/// it is within a small edit distance of `name` or if one name contains the other.
+pub fn near_misses<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a ArcStr>,
+) -> Vec<ArcStr> {
+    let name = name.to_lowercase();
+    let threshold = std::cmp::max(2, name.chars().count() / 3);
+    let mut matches = candidates
+        .into_iter()
+        .filter_map(|candidate| {
+            let lower = candidate.to_lowercase();
+            let distance = edit_distance(&name, &lower);
+            (distance <= threshold || lower.contains(&name) || name.contains(&lower))
+                .then(|| (distance, candidate.clone()))
+        })
+        .collect::<Vec<_>>();
+    matches.sort();
+    matches
+        .into_iter()
+        .take(MAX_NEAR_MISSES)
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
+
+/// A map from alternative names of simulator signals to the names under which they were saved.
+///
+/// Nodes that are merged during elaboration or netlisting, such as a subcircuit port and the
+/// net connected to it, appear in simulation output under a single name. Simulator plugins
+/// record the other names of such nodes here so that lookups by any of them succeed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NodeAliases {
+    aliases: HashMap<ArcStr, ArcStr>,
+}
+
+impl NodeAliases {
+    /// Creates an empty alias map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records `alias` as another name for the saved signal `name`.
+    ///
+    /// Does nothing if `alias` and `name` are the same.
+    pub fn insert(&mut self, alias: impl Into<ArcStr>, name: impl Into<ArcStr>) {
+        let (alias, name) = (alias.into(), name.into());
+        if alias != name {
+            self.aliases.insert(alias, name);
+        }
+    }
+
+    /// Returns the saved signal name that `alias` refers to, if `alias` is a known alias.
+    pub fn get(&self, alias: &str) -> Option<&ArcStr> {
+        self.aliases.get(alias)
+    }
+
+    /// Returns the saved signal name that `name` refers to.
+    ///
+    /// Returns `name` itself if it is not a known alias.
+    pub fn resolve<'a>(&'a self, name: &'a str) -> &'a str {
+        self.get(name).map(ArcStr::as_str).unwrap_or(name)
+    }
+
+    /// Returns all known aliases of the saved signal `name`.
+    pub fn aliases_of<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a ArcStr> + 'a {
+        self.aliases
+            .iter()
+            .filter(move |(_, target)| target.as_str() == name)
+            .map(|(alias, _)| alias)
+    }
+
+    /// Iterates over `(alias, saved signal name)` pairs.
+    pub fn iter(&self) -> impl Iterator<Item = (&ArcStr, &ArcStr)> {
+        self.aliases.iter()
+    }
+
+    /// The number of aliases.
+    pub fn len(&self) -> usize {
+        self.aliases.len()
+    }
+
+    /// Returns `true` if there are no aliases.
+    pub fn is_empty(&self) -> bool {
+        self.aliases.is_empty()
+    }
+}
+
+/// The Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut prev = (0..=b.len()).collect::<Vec<_>>();
+    let mut curr = vec![0; b.len() + 1];
+    for (i, ca) in a.chars().enumerate() {
+        curr[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(ca != *cb);
+            curr[j + 1] = substitution.min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    prev[b.len()]
+}
+
+/// A simulator-agnostic identifier for a saved quantity measured in unit `U`.
+///
+/// Simulator plugins assign an ID to each simulator signal they are asked to save.
+/// A key may refer to several simulator signals, in which case the saved quantity is
+/// their sum (e.g. the current flowing into a terminal connected to several devices).
+///
+/// Since all plugins use the same key types, generic testbench code can save and
+/// retrieve signals without branching on the simulator.
+#[derive(Serialize, Deserialize)]
+#[derive_where(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SaveKey<U> {
+    ids: Vec<u64>,
+    #[serde(skip)]
+    unit: PhantomData<fn() -> U>,
+}
+
+/// A key identifying a saved voltage.
+pub type VoltageSaveKey = SaveKey<Volts>;
+/// A key identifying a saved current.
+pub type CurrentSaveKey = SaveKey<Amps>;
+
+impl<U> SaveKey<U> {
+    /// Creates a key referring to the simulator signal with the given ID.
+    pub fn new(id: u64) -> Self {
+        Self::from_ids([id])
+    }
+
+    /// Creates a key referring to the sum of the simulator signals with the given IDs.
+    pub fn from_ids(ids: impl IntoIterator<Item = u64>) -> Self {
+        Self {
+            ids: ids.into_iter().collect(),
+            unit: PhantomData,
+        }
+    }
+
+    /// Returns the IDs of the simulator signals referred to by this key.
+    pub fn ids(&self) -> &[u64] {
+        &self.ids
+    }
+
+    /// Returns the ID of the single simulator signal referred to by this key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this key does not refer to exactly one simulator signal.
+    pub fn id(&self) -> u64 {
+        assert_eq!(
+            self.ids.len(),
+            1,
+            "save key does not refer to exactly one simulator signal"
+        );
+        self.ids[0]
+    }
+}
+
 /// Saves the raw output of a simulation.
 #[derive(Debug, Clone, Copy)]
 pub struct SaveOutput;
@@ -155,3 +365,167 @@ where
         }
     }
 }
+
+/// All terminals of a nested instance, saved together.
+///
+/// Buses are expanded, so each terminal is a single bit of a port. Terminals are named by
+/// their port and bit index (e.g. `data_3`).
+///
+/// Saving a [`NestedPorts`] is equivalent to saving each of its terminals individually:
+///
+/// ```ignore
+/// let ports = NestedPorts::new(&tb.dut);
+/// let key = <NestedPorts as Save<Spectre, Tran>>::save(&ports, ctx, opts);
+/// ```
+#[derive(Clone, Debug)]
+pub struct NestedPorts {
+    ports: Vec<(ArcStr, NestedTerminal)>,
+}
+
+impl NestedPorts {
+    /// Collects the terminals of `instance`.
+    pub fn new<T: Schematic>(instance: &NestedInstance<T>) -> Self {
+        Self {
+            ports: instance
+                .terminals()
+                .into_iter()
+                .map(|(name, terminal)| (ArcStr::from(name.to_string()), terminal))
+                .collect(),
+        }
+    }
+
+    /// Iterates over the terminals and their names.
+    pub fn iter(&self) -> impl Iterator<Item = (&ArcStr, &NestedTerminal)> {
+        self.ports.iter().map(|(name, terminal)| (name, terminal))
+    }
+
+    /// The number of terminals.
+    pub fn len(&self) -> usize {
+        self.ports.len()
+    }
+
+    /// Returns `true` if the instance has no terminals.
+    pub fn is_empty(&self) -> bool {
+        self.ports.is_empty()
+    }
+}
+
+/// The saved outputs of the terminals in a [`NestedPorts`].
+#[derive(Clone, Debug)]
+pub struct NestedPortsOutput<T> {
+    ports: Vec<(ArcStr, T)>,
+}
+
+impl<T> NestedPortsOutput<T> {
+    /// Returns the output of the terminal with the given name.
+    pub fn get(&self, name: &str) -> Option<&T> {
+        self.ports
+            .iter()
+            .find(|(port, _)| port == name)
+            .map(|(_, output)| output)
+    }
+
+    /// Iterates over the outputs of each terminal, in the order of [`NestedPorts::iter`].
+    pub fn iter(&self) -> impl Iterator<Item = (&ArcStr, &T)> {
+        self.ports.iter().map(|(name, output)| (name, output))
+    }
+
+    /// The number of terminals.
+    pub fn len(&self) -> usize {
+        self.ports.len()
+    }
+
+    /// Returns `true` if there are no terminals.
+    pub fn is_empty(&self) -> bool {
+        self.ports.is_empty()
+    }
+}
+
+impl<S, A> Save<S, A> for NestedPorts
+where
+    S: Simulator,
+    A: Analysis,
+    NestedTerminal: Save<S, A>,
+{
+    type SaveKey = Vec<(ArcStr, <NestedTerminal as Save<S, A>>::SaveKey)>;
+    type Saved = NestedPortsOutput<<NestedTerminal as Save<S, A>>::Saved>;
+
+    fn save(
+        &self,
+        ctx: &SimulationContext<S>,
+        opts: &mut <S as Simulator>::Options,
+    ) -> <Self as Save<S, A>>::SaveKey {
+        self.ports
+            .iter()
+            .map(|(name, terminal)| {
+                (
+                    name.clone(),
+                    <NestedTerminal as Save<S, A>>::save(terminal, ctx, opts),
+                )
+            })
+            .collect()
+    }
+
+    fn from_saved(
+        output: &<A as Analysis>::Output,
+        key: &<Self as Save<S, A>>::SaveKey,
+    ) -> <Self as Save<S, A>>::Saved {
+        NestedPortsOutput {
+            ports: key
+                .iter()
+                .map(|(name, key)| {
+                    (
+                        name.clone(),
+                        <NestedTerminal as Save<S, A>>::from_saved(output, key),
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signal_lookup_lists_near_misses() {
+        let saved = [
+            ArcStr::from("xdut.out"),
+            ArcStr::from("xdut.outb"),
+            ArcStr::from("xdut.vdd"),
+            ArcStr::from("clk"),
+        ];
+        let err = SignalLookupError::not_found("xdut.oute", &saved);
+        assert_eq!(
+            err,
+            SignalLookupError::NotFound {
+                name: "xdut.oute".into(),
+                near_misses: vec!["xdut.out".into(), "xdut.outb".into()],
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "signal `xdut.oute` was not saved; similar saved signals: `xdut.out`, `xdut.outb`"
+        );
+
+        let err = SignalLookupError::not_found("vss", &saved);
+        assert_eq!(err.to_string(), "signal `vss` was not saved");
+    }
+
+    #[test]
+    fn node_aliases_resolve_to_saved_names() {
+        let mut aliases = NodeAliases::new();
+        aliases.insert("xdut.in", "vin");
+        aliases.insert("xdut.xinv.a", "vin");
+        aliases.insert("vout", "vout");
+
+        assert_eq!(aliases.len(), 2);
+        assert_eq!(aliases.resolve("xdut.in"), "vin");
+        assert_eq!(aliases.resolve("vout"), "vout");
+        assert_eq!(aliases.get("vout"), None);
+        let mut names = aliases.aliases_of("vin").collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(names, ["xdut.in", "xdut.xinv.a"]);
+    }
+}
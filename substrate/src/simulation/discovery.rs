@@ -0,0 +1,378 @@
+//! Simulator discovery and version probing.
+//!
+//! Simulator plugins use these utilities to locate their executables and determine
+//! which version is installed, so that missing tools produce clear errors and
+//! version-dependent features can be enabled selectively.
+
+use std::ffi::OsString;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
+
+use config::Config;
+
+/// A tool version, consisting of one or more numeric components (e.g. `21.1.0`).
+///
+/// Versions are ordered lexicographically by component.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Version(Vec<u32>);
+
+impl Version {
+    /// Creates a new [`Version`] from the given components.
+    pub fn new(components: impl Into<Vec<u32>>) -> Self {
+        Self(components.into())
+    }
+
+    /// Returns the components of this version.
+    pub fn components(&self) -> &[u32] {
+        &self.0
+    }
+
+    /// Returns the major version.
+    pub fn major(&self) -> u32 {
+        self.0.first().copied().unwrap_or_default()
+    }
+
+    /// Returns the minor version.
+    pub fn minor(&self) -> u32 {
+        self.0.get(1).copied().unwrap_or_default()
+    }
+
+    /// Parses a version from the start of `s` (e.g. `21.1.0.389.isr8` parses to `21.1.0.389`).
+    ///
+    /// Returns [`None`] if `s` does not start with a digit.
+    pub fn parse_prefix(s: &str) -> Option<Self> {
+        let mut components = Vec::new();
+        for part in s.split('.') {
+            let digits = part
+                .find(|c: char| !c.is_ascii_digit())
+                .map_or(part, |idx| &part[..idx]);
+            let Ok(component) = digits.parse() else {
+                break;
+            };
+            components.push(component);
+            if digits.len() != part.len() {
+                break;
+            }
+        }
+        (!components.is_empty()).then_some(Self(components))
+    }
+
+    /// Parses the version immediately following the first occurrence of `marker` in `output`.
+    ///
+    /// Useful for extracting the version from the output of a tool's version command.
+    pub fn find_after(output: &str, marker: &str) -> Option<Self> {
+        let idx = output.find(marker)?;
+        Self::parse_prefix(&output[idx + marker.len()..])
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, component) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ".")?;
+            }
+            write!(f, "{component}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Information about an installed tool.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolInfo {
+    /// The path to the tool's executable.
+    pub path: PathBuf,
+    /// The version of the tool, if it could be determined.
+    pub version: Option<Version>,
+    /// The raw output of the tool's version command.
+    pub version_output: String,
+}
+
+impl ToolInfo {
+    /// Returns `true` if the tool's version is known and is at least `version`.
+    pub fn version_at_least(&self, version: &Version) -> bool {
+        self.version.as_ref().is_some_and(|v| v >= version)
+    }
+
+    /// Returns an error if the tool's version is not known to be at least `required`.
+    ///
+    /// Used by plugins to gate features that are only available in newer versions of a tool.
+    pub fn require_version(
+        &self,
+        name: &str,
+        feature: &str,
+        required: &Version,
+    ) -> Result<(), DiscoveryError> {
+        if self.version_at_least(required) {
+            Ok(())
+        } else {
+            Err(DiscoveryError::UnsupportedVersion {
+                name: name.to_string(),
+                feature: feature.to_string(),
+                required: required.clone(),
+                found: self.version.clone(),
+            })
+        }
+    }
+}
+
+/// An error encountered while discovering a tool.
+#[derive(Debug, thiserror::Error)]
+pub enum DiscoveryError {
+    /// The tool's executable could not be found.
+    #[error(
+        "could not find `{name}`; add it to your PATH, set the `{env_var}` environment variable, \
+         or set `tools.paths.{name}` in your Substrate configuration"
+    )]
+    NotFound {
+        /// The name of the tool.
+        name: String,
+        /// The environment variable that can be used to specify the tool's path.
+        env_var: String,
+    },
+    /// A configured path to the tool's executable could not be accessed.
+    #[error("cannot access `{}`", .path.display())]
+    InvalidPath {
+        /// The configured path.
+        path: PathBuf,
+        /// The underlying error.
+        #[source]
+        source: std::io::Error,
+    },
+    /// The tool's executable exists but cannot be executed.
+    #[error("`{}` is not executable", .path.display())]
+    NotExecutable {
+        /// The path to the tool's executable.
+        path: PathBuf,
+    },
+    /// The tool's version command could not be run.
+    #[error("error running `{}` to determine its version", .path.display())]
+    Probe {
+        /// The path to the tool's executable.
+        path: PathBuf,
+        /// The underlying error.
+        #[source]
+        source: std::io::Error,
+    },
+    /// The tool's version command exited unsuccessfully.
+    #[error("`{}` exited with {status} while determining its version:\n{output}", .path.display())]
+    ProbeFailed {
+        /// The path to the tool's executable.
+        path: PathBuf,
+        /// The exit status of the version command.
+        status: ExitStatus,
+        /// The combined standard output and standard error of the version command.
+        output: String,
+    },
+    /// The installed version of the tool does not support a requested feature.
+    #[error(
+        "{feature} requires {name} {required} or newer, but the installed version is {}",
+        .found.as_ref().map_or_else(|| "unknown".to_string(), Version::to_string)
+    )]
+    UnsupportedVersion {
+        /// The name of the tool.
+        name: String,
+        /// The feature that requires a newer version.
+        feature: String,
+        /// The minimum version supporting the feature.
+        required: Version,
+        /// The installed version, if it could be determined.
+        found: Option<Version>,
+    },
+}
+
+fn is_executable(path: &Path) -> bool {
+    #[cfg(any(unix, target_os = "redox"))]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        path.metadata()
+            .is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+    }
+    #[cfg(not(any(unix, target_os = "redox")))]
+    {
+        path.is_file()
+    }
+}
+
+/// Checks that an explicitly configured path refers to an executable file.
+fn check_configured(path: PathBuf) -> Result<PathBuf, DiscoveryError> {
+    if let Err(source) = path.metadata() {
+        return Err(DiscoveryError::InvalidPath { path, source });
+    }
+    if !is_executable(&path) {
+        return Err(DiscoveryError::NotExecutable { path });
+    }
+    Ok(path)
+}
+
+/// Locates the executable of the tool with the given name.
+///
+/// The following locations are searched, in order:
+/// 1. The path specified by the environment variable `env_var`.
+/// 2. The path specified by `tools.paths.<name>` in the Substrate configuration.
+/// 3. The directories in the `PATH` environment variable.
+///
+/// Paths specified by the environment or the configuration must exist and be executable.
+/// If `PATH` only contains files with the given name that are not executable, returns
+/// [`DiscoveryError::NotExecutable`]; if it contains no such file, returns
+/// [`DiscoveryError::NotFound`].
+pub fn find_executable(name: &str, env_var: &str) -> Result<PathBuf, DiscoveryError> {
+    if let Some(path) = std::env::var_os(env_var) {
+        return check_configured(PathBuf::from(path));
+    }
+    if let Some(path) = Config::default()
+        .ok()
+        .and_then(|cfg| cfg.tools.paths.get(name).cloned())
+    {
+        return check_configured(path);
+    }
+    let paths = std::env::var_os("PATH").unwrap_or_else(OsString::new);
+    let mut not_executable = None;
+    for path in std::env::split_paths(&paths).map(|dir| dir.join(name)) {
+        if is_executable(&path) {
+            return Ok(path);
+        }
+        if not_executable.is_none() && path.is_file() {
+            not_executable = Some(path);
+        }
+    }
+    Err(match not_executable {
+        Some(path) => DiscoveryError::NotExecutable { path },
+        None => DiscoveryError::NotFound {
+            name: name.to_string(),
+            env_var: env_var.to_string(),
+        },
+    })
+}
+
+/// Locates the executable of the tool with the given name, if it can be found.
+///
+/// Like [`find_executable`], but returns [`None`] instead of [`DiscoveryError::NotFound`].
+/// Run scripts invoke the tool by name in that case, since they may make the tool available
+/// (e.g. by sourcing a setup script) before invoking it. Any other discovery error is returned.
+pub fn find_optional_executable(
+    name: &str,
+    env_var: &str,
+) -> Result<Option<PathBuf>, DiscoveryError> {
+    match find_executable(name, env_var) {
+        Ok(path) => Ok(Some(path)),
+        Err(DiscoveryError::NotFound { .. }) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Runs the executable at `path` with `version_args` to determine its version.
+///
+/// The version is parsed from the text following `version_marker` in the
+/// combined standard output and standard error of the version command.
+pub fn probe_executable(
+    path: PathBuf,
+    version_args: &[&str],
+    version_marker: &str,
+) -> Result<ToolInfo, DiscoveryError> {
+    let output = match Command::new(&path)
+        .args(version_args)
+        .stdin(Stdio::null())
+        .output()
+    {
+        Ok(output) => output,
+        Err(source) if source.kind() == std::io::ErrorKind::PermissionDenied => {
+            return Err(DiscoveryError::NotExecutable { path });
+        }
+        Err(source) => return Err(DiscoveryError::Probe { path, source }),
+    };
+    let mut version_output = String::from_utf8_lossy(&output.stdout).into_owned();
+    version_output.push_str(&String::from_utf8_lossy(&output.stderr));
+    if !output.status.success() {
+        return Err(DiscoveryError::ProbeFailed {
+            path,
+            status: output.status,
+            output: version_output,
+        });
+    }
+    Ok(ToolInfo {
+        version: Version::find_after(&version_output, version_marker),
+        path,
+        version_output,
+    })
+}
+
+/// Locates the given tool and runs it with `version_args` to determine its version.
+///
+/// See [`find_executable`] and [`probe_executable`] for details.
+pub fn probe_tool(
+    name: &str,
+    env_var: &str,
+    version_args: &[&str],
+    version_marker: &str,
+) -> Result<ToolInfo, DiscoveryError> {
+    probe_executable(
+        find_executable(name, env_var)?,
+        version_args,
+        version_marker,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn versions_are_parsed() {
+        assert_eq!(
+            Version::find_after(
+                "@(#)$CDS: spectre version 21.1.0.389.isr8 64bit -- 2 Jun 2022 $",
+                "version "
+            ),
+            Some(Version::new([21, 1, 0, 389]))
+        );
+        assert_eq!(
+            Version::find_after(
+                "** ngspice-41 : Circuit level simulation program",
+                "ngspice-"
+            ),
+            Some(Version::new([41]))
+        );
+        assert_eq!(Version::parse_prefix("v1.2"), None);
+        assert!(Version::new([21, 1]) > Version::new([20, 10, 3]));
+        assert_eq!(Version::new([21, 1, 0]).to_string(), "21.1.0");
+    }
+
+    #[test]
+    fn versions_are_gated() {
+        let info = ToolInfo {
+            path: PathBuf::from("spectre"),
+            version: Some(Version::new([21, 1])),
+            version_output: String::new(),
+        };
+        assert!(info
+            .require_version("spectre", "PSF-XL output", &Version::new([15, 1]))
+            .is_ok());
+        assert!(matches!(
+            info.require_version("spectre", "PSF-XL output", &Version::new([22])),
+            Err(DiscoveryError::UnsupportedVersion { .. })
+        ));
+        let unknown = ToolInfo {
+            version: None,
+            ..info
+        };
+        assert!(unknown
+            .require_version("spectre", "PSF-XL output", &Version::new([15, 1]))
+            .is_err());
+    }
+
+    #[test]
+    fn configured_paths_are_checked() {
+        let dir = std::env::temp_dir();
+        assert!(matches!(
+            check_configured(dir.join("substrate_missing_simulator")),
+            Err(DiscoveryError::InvalidPath { .. })
+        ));
+        assert!(matches!(
+            check_configured(dir),
+            Err(DiscoveryError::NotExecutable { .. })
+        ));
+    }
+}
@@ -0,0 +1,353 @@
+//! Export of simulation results to common tabular and waveform formats.
+//!
+//! Simulator plugins use these utilities to implement exporters on their output types,
+//! allowing results to be loaded into external tools (e.g. pandas) without ad-hoc scripts.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+
+use arcstr::ArcStr;
+
+/// A named column of simulation data.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Column<'a> {
+    /// The name of the column.
+    pub name: &'a str,
+    /// The values in the column.
+    pub values: &'a [f64],
+}
+
+impl<'a> Column<'a> {
+    /// Creates a new [`Column`].
+    pub fn new(name: &'a str, values: &'a [f64]) -> Self {
+        Self { name, values }
+    }
+}
+
+/// Selects the named signals from `values`.
+///
+/// If `signals` is empty, selects all signals, sorted by name.
+/// Returns an error if any of the named signals do not exist.
+pub fn select_signals<'a, V>(
+    values: &'a HashMap<ArcStr, V>,
+    signals: &[&str],
+) -> std::io::Result<Vec<(&'a ArcStr, &'a V)>> {
+    if signals.is_empty() {
+        let mut selected = values.iter().collect::<Vec<_>>();
+        selected.sort_by(|a, b| a.0.cmp(b.0));
+        return Ok(selected);
+    }
+    signals
+        .iter()
+        .map(|signal| {
+            values.get_key_value(*signal).ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!("signal `{signal}` was not saved"),
+                )
+            })
+        })
+        .collect()
+}
+
+fn check_lengths(columns: &[Column<'_>]) -> std::io::Result<usize> {
+    let len = columns.first().map(|c| c.values.len()).unwrap_or_default();
+    if let Some(column) = columns.iter().find(|c| c.values.len() != len) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!(
+                "column `{}` has {} values, expected {len}",
+                column.name,
+                column.values.len()
+            ),
+        ));
+    }
+    Ok(len)
+}
+
+/// Quotes a CSV field if it contains characters with special meaning.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Writes the given columns to `out` in CSV format.
+///
+/// The first row contains the column names. All columns must have the same length.
+pub fn write_csv<W: Write>(mut out: W, columns: &[Column<'_>]) -> std::io::Result<()> {
+    let len = check_lengths(columns)?;
+    writeln!(
+        out,
+        "{}",
+        columns
+            .iter()
+            .map(|c| csv_field(c.name))
+            .collect::<Vec<_>>()
+            .join(",")
+    )?;
+    for i in 0..len {
+        for (j, column) in columns.iter().enumerate() {
+            if j > 0 {
+                write!(out, ",")?;
+            }
+            write!(out, "{:e}", column.values[i])?;
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+/// Writes the given columns to `out` as an Apache Parquet file.
+///
+/// Each column is stored as a non-nullable `f64` column. All columns must have the same length.
+#[cfg(feature = "parquet")]
+pub fn write_parquet<W: Write + Send>(
+    out: W,
+    columns: &[Column<'_>],
+) -> Result<(), parquet::errors::ParquetError> {
+    use std::sync::Arc;
+
+    use arrow::array::{ArrayRef, Float64Array};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+
+    check_lengths(columns)?;
+    let schema = Arc::new(Schema::new(
+        columns
+            .iter()
+            .map(|c| Field::new(c.name, DataType::Float64, false))
+            .collect::<Vec<_>>(),
+    ));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        columns
+            .iter()
+            .map(|c| Arc::new(Float64Array::from(c.values.to_vec())) as ArrayRef)
+            .collect(),
+    )?;
+    let mut writer = ArrowWriter::try_new(out, schema, None)?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(())
+}
+
+/// Thresholds used to convert analog waveforms into logic levels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Thresholds {
+    /// Values at or below this threshold are logic low.
+    pub low: f64,
+    /// Values at or above this threshold are logic high.
+    pub high: f64,
+}
+
+impl Thresholds {
+    /// Creates a new set of [`Thresholds`].
+    pub fn new(low: f64, high: f64) -> Self {
+        assert!(low <= high, "low threshold must not exceed high threshold");
+        Self { low, high }
+    }
+
+    /// Creates thresholds at 30% and 70% of the given supply voltage.
+    pub fn from_vdd(vdd: f64) -> Self {
+        Self::new(0.3 * vdd, 0.7 * vdd)
+    }
+
+    /// Converts a value to a VCD logic level.
+    ///
+    /// Values between the low and high thresholds are unknown (`x`).
+    pub fn logic_level(&self, value: f64) -> char {
+        if value >= self.high {
+            '1'
+        } else if value <= self.low {
+            '0'
+        } else {
+            'x'
+        }
+    }
+}
+
+/// The VCD time unit, in seconds.
+const VCD_TIMESCALE: f64 = 1e-15;
+
+/// A VCD scope, containing variables and nested scopes.
+#[derive(Default)]
+struct VcdScope<'a> {
+    vars: Vec<(&'a str, String)>,
+    scopes: BTreeMap<&'a str, VcdScope<'a>>,
+}
+
+impl VcdScope<'_> {
+    fn write<W: Write>(&self, out: &mut W, name: &str) -> std::io::Result<()> {
+        writeln!(out, "$scope module {name} $end")?;
+        for (var, id) in self.vars.iter() {
+            writeln!(out, "$var wire 1 {id} {var} $end")?;
+        }
+        for (name, scope) in self.scopes.iter() {
+            scope.write(out, name)?;
+        }
+        writeln!(out, "$upscope $end")?;
+        Ok(())
+    }
+}
+
+/// A signal to write in Value Change Dump (VCD) format.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VcdSignal<'a> {
+    /// The hierarchical path to the signal.
+    ///
+    /// All but the last element name the VCD scopes containing the signal, from outermost
+    /// to innermost. The last element is the name of the signal.
+    pub path: &'a [ArcStr],
+    /// The values of the signal.
+    pub values: &'a [f64],
+}
+
+impl<'a> VcdSignal<'a> {
+    /// Creates a new [`VcdSignal`].
+    pub fn new(path: &'a [ArcStr], values: &'a [f64]) -> Self {
+        Self { path, values }
+    }
+}
+
+/// Returns the VCD identifier code for the signal with the given index.
+fn vcd_id(mut idx: usize) -> String {
+    let mut id = String::new();
+    loop {
+        id.push((b'!' + (idx % 94) as u8) as char);
+        idx /= 94;
+        if idx == 0 {
+            break;
+        }
+    }
+    id
+}
+
+/// Writes the given signals to `out` in Value Change Dump (VCD) format.
+///
+/// Each signal is converted to a logic level using `thresholds`, and is placed in the scopes
+/// given by its [path](VcdSignal::path), all of which are nested within a top-level scope
+/// named `top`. Times are given in seconds and written with a timescale of 1 fs.
+///
+/// All signals must have a nonempty path and the same length as `time`.
+pub fn write_vcd<W: Write>(
+    mut out: W,
+    time: &[f64],
+    signals: &[VcdSignal<'_>],
+    thresholds: Thresholds,
+) -> std::io::Result<()> {
+    let mut top = VcdScope::default();
+    let mut columns = vec![Column::new("time", time)];
+    let ids = (0..signals.len()).map(vcd_id).collect::<Vec<_>>();
+    for (signal, id) in signals.iter().zip(ids.iter()) {
+        let (var, scopes) = signal.path.split_last().ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "VCD signal has an empty path",
+            )
+        })?;
+        let scope = scopes.iter().fold(&mut top, |scope, elem| {
+            scope.scopes.entry(elem.as_str()).or_default()
+        });
+        scope.vars.push((var.as_str(), id.clone()));
+        columns.push(Column::new(var, signal.values));
+    }
+    check_lengths(&columns)?;
+
+    writeln!(out, "$timescale 1fs $end")?;
+    top.write(&mut out, "top")?;
+    writeln!(out, "$enddefinitions $end")?;
+
+    let mut levels = vec![None; signals.len()];
+    let mut last_time = None;
+    for (i, t) in time.iter().enumerate() {
+        let t = (t / VCD_TIMESCALE).round() as u64;
+        for (j, signal) in signals.iter().enumerate() {
+            let level = thresholds.logic_level(signal.values[i]);
+            if levels[j] == Some(level) {
+                continue;
+            }
+            if last_time != Some(t) {
+                writeln!(out, "#{t}")?;
+                last_time = Some(t);
+            }
+            writeln!(out, "{level}{}", ids[j])?;
+            levels[j] = Some(level);
+        }
+    }
+    Ok(())
+}
+
+/// Writes the given signals to `out` in Fast Signal Database (FSDB) format.
+///
+/// FSDB is a proprietary format that cannot be written without the vendor's libraries, so
+/// this is not yet supported and always returns an [`Unsupported`](std::io::ErrorKind::Unsupported)
+/// error. Use [`write_vcd`] and convert the result with the waveform viewer's tools instead.
+pub fn write_fsdb<W: Write>(
+    _out: W,
+    _time: &[f64],
+    _signals: &[VcdSignal<'_>],
+    _thresholds: Thresholds,
+) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "FSDB export is not supported; write VCD and convert it instead",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vcd_scopes_follow_signal_paths() {
+        let clk = [arcstr::literal!("clk")];
+        // Names that contain `.` must not be split into scopes.
+        let out = [arcstr::literal!("xdut"), arcstr::literal!("a.b")];
+        let signals = [
+            VcdSignal::new(&clk, &[1.8, 1.8, 0., 0.]),
+            VcdSignal::new(&out, &[0., 0.9, 1.8, 1.8]),
+        ];
+
+        let mut buf = Vec::new();
+        write_vcd(
+            &mut buf,
+            &[0., 1e-12, 2e-12, 3e-12],
+            &signals,
+            Thresholds::from_vdd(1.8),
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "$timescale 1fs $end\n\
+             $scope module top $end\n\
+             $var wire 1 ! clk $end\n\
+             $scope module xdut $end\n\
+             $var wire 1 \" a.b $end\n\
+             $upscope $end\n\
+             $upscope $end\n\
+             $enddefinitions $end\n\
+             #0\n1!\n0\"\n\
+             #1000\nx\"\n\
+             #2000\n0!\n1\"\n"
+        );
+
+        assert!(write_vcd(
+            Vec::new(),
+            &[0.],
+            &[VcdSignal::new(&[], &[0.])],
+            Thresholds::from_vdd(1.8)
+        )
+        .is_err());
+        assert!(write_vcd(Vec::new(), &[0.], &signals, Thresholds::from_vdd(1.8)).is_err());
+        assert_eq!(
+            write_fsdb(Vec::new(), &[0.], &[], Thresholds::from_vdd(1.8))
+                .unwrap_err()
+                .kind(),
+            std::io::ErrorKind::Unsupported
+        );
+    }
+}
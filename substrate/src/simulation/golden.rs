@@ -0,0 +1,483 @@
+//! Comparison of simulated waveforms against stored golden references.
+//!
+//! Golden references catch analog behavioral regressions: a known-good waveform is saved
+//! once, and later simulations are compared against it with absolute and relative
+//! tolerances. Small timing differences (e.g. due to simulator time step control) can be
+//! absorbed by aligning the waveforms with a bounded time shift before comparison.
+//!
+//! ```ignore
+//! let vout = output.tran.saved(&vout_key);
+//! let diff = check_golden(&vout, "golden/vout.json", &CompareOptions::new(1e-3, 1e-2))?;
+//! assert!(diff.passed(), "{diff}");
+//! ```
+//!
+//! Setting the [`UPDATE_GOLDEN_ENV`] environment variable causes [`check_golden`] to
+//! overwrite the stored references with the simulated waveforms.
+
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+use super::export::{write_csv, Column};
+use super::waveform::{TimeWaveform, Waveform};
+
+/// The environment variable that, if set, causes [`check_golden`] to update stored references.
+pub const UPDATE_GOLDEN_ENV: &str = "SUBSTRATE_UPDATE_GOLDEN";
+
+/// An error loading or saving a golden reference.
+#[derive(thiserror::Error, Debug, Clone)]
+pub enum GoldenError {
+    /// An I/O error.
+    #[error("I/O error: {0}")]
+    Io(#[from] Arc<std::io::Error>),
+    /// The reference could not be serialized or deserialized.
+    #[error("error encoding golden reference: {0}")]
+    Encoding(#[from] Arc<serde_json::Error>),
+}
+
+impl From<std::io::Error> for GoldenError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(Arc::new(value))
+    }
+}
+
+impl From<serde_json::Error> for GoldenError {
+    fn from(value: serde_json::Error) -> Self {
+        Self::Encoding(Arc::new(value))
+    }
+}
+
+/// Options for comparing a waveform against a golden reference.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CompareOptions {
+    /// The allowed absolute error.
+    pub abs_tol: f64,
+    /// The allowed error relative to the magnitude of the reference.
+    ///
+    /// A point passes if its error is at most `abs_tol + rel_tol * |reference|`.
+    pub rel_tol: f64,
+    /// The maximum time shift applied to the simulated waveform to align it with the reference.
+    pub max_shift: f64,
+    /// The number of candidate shifts tried on each side of zero.
+    pub shift_steps: usize,
+    /// The time window over which the waveforms are compared.
+    ///
+    /// If [`None`], the entire reference is compared.
+    pub window: Option<(f64, f64)>,
+}
+
+impl CompareOptions {
+    /// Creates comparison options with the given tolerances and no time shift alignment.
+    pub fn new(abs_tol: f64, rel_tol: f64) -> Self {
+        Self {
+            abs_tol,
+            rel_tol,
+            max_shift: 0.,
+            shift_steps: 0,
+            window: None,
+        }
+    }
+
+    /// Aligns the waveforms by trying `steps` shifts on each side of zero, up to `max_shift`.
+    pub fn with_shift(mut self, max_shift: f64, steps: usize) -> Self {
+        self.max_shift = max_shift;
+        self.shift_steps = steps;
+        self
+    }
+
+    /// Restricts the comparison to the time window `[start, stop]`.
+    pub fn with_window(mut self, start: f64, stop: f64) -> Self {
+        self.window = Some((start, stop));
+        self
+    }
+
+    fn allowed(&self, reference: f64) -> f64 {
+        self.abs_tol + self.rel_tol * reference.abs()
+    }
+}
+
+/// A compared point.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DiffPoint {
+    /// The time of the point in the reference.
+    pub t: f64,
+    /// The reference value.
+    pub reference: f64,
+    /// The simulated value, after alignment.
+    ///
+    /// [`None`] if the simulated waveform does not cover the time of the point.
+    pub actual: Option<f64>,
+    /// The allowed error at this point.
+    pub allowed: f64,
+}
+
+impl DiffPoint {
+    /// The absolute error at this point.
+    ///
+    /// Infinite if the simulated waveform does not cover the time of the point.
+    pub fn error(&self) -> f64 {
+        self.actual
+            .map_or(f64::INFINITY, |actual| (actual - self.reference).abs())
+    }
+
+    /// Returns `true` if the error is within tolerance.
+    pub fn passed(&self) -> bool {
+        self.error() <= self.allowed
+    }
+}
+
+/// A contiguous window of points that are out of tolerance.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DiffWindow {
+    /// The time of the first failing point.
+    pub start: f64,
+    /// The time of the last failing point.
+    pub stop: f64,
+    /// The failing point with the largest error.
+    pub worst: DiffPoint,
+}
+
+/// The result of comparing a waveform against a golden reference.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoldenDiff {
+    /// The time shift applied to the simulated waveform to align it with the reference.
+    ///
+    /// The simulated value at time `t + shift` is compared with the reference value at time `t`.
+    pub shift: f64,
+    /// The compared points.
+    pub points: Vec<DiffPoint>,
+    /// The windows in which the simulated waveform is out of tolerance.
+    pub failures: Vec<DiffWindow>,
+}
+
+impl GoldenDiff {
+    /// Returns `true` if at least one point was compared and all compared points are within tolerance.
+    pub fn passed(&self) -> bool {
+        !self.points.is_empty() && self.failures.is_empty()
+    }
+
+    /// The compared point with the largest error, if any.
+    pub fn max_error(&self) -> Option<&DiffPoint> {
+        self.points
+            .iter()
+            .max_by(|a, b| a.error().total_cmp(&b.error()))
+    }
+
+    /// Writes the compared points as CSV, with columns `t`, `reference`, `actual`, `error`,
+    /// and `allowed`.
+    ///
+    /// Points not covered by the simulated waveform have a NaN `actual` value.
+    pub fn write_csv<W: Write>(&self, out: W) -> std::io::Result<()> {
+        let column = |f: fn(&DiffPoint) -> f64| self.points.iter().map(f).collect::<Vec<_>>();
+        let t = column(|p| p.t);
+        let reference = column(|p| p.reference);
+        let actual = column(|p| p.actual.unwrap_or(f64::NAN));
+        let error = column(DiffPoint::error);
+        let allowed = column(|p| p.allowed);
+        write_csv(
+            out,
+            &[
+                Column::new("t", &t),
+                Column::new("reference", &reference),
+                Column::new("actual", &actual),
+                Column::new("error", &error),
+                Column::new("allowed", &allowed),
+            ],
+        )
+    }
+}
+
+impl Display for GoldenDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.points.is_empty() {
+            return writeln!(f, "the golden reference has no points to compare");
+        }
+        let status = if self.passed() { "passed" } else { "FAILED" };
+        writeln!(
+            f,
+            "golden comparison {status}: {} points compared, time shift {:e}",
+            self.points.len(),
+            self.shift
+        )?;
+        if let Some(worst) = self.max_error() {
+            writeln!(
+                f,
+                "max error {:e} at t = {:e} (reference {}, actual {})",
+                worst.error(),
+                worst.t,
+                worst.reference,
+                DisplayActual(worst.actual)
+            )?;
+        }
+        for window in self.failures.iter() {
+            let worst = &window.worst;
+            writeln!(
+                f,
+                "  out of tolerance in [{:e}, {:e}]: at t = {:e}, reference {}, actual {}, error {:e} > {:e}",
+                window.start,
+                window.stop,
+                worst.t,
+                worst.reference,
+                DisplayActual(worst.actual),
+                worst.error(),
+                worst.allowed
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Displays a simulated value, or `missing` if the simulated waveform does not cover a point.
+struct DisplayActual(Option<f64>);
+
+impl Display for DisplayActual {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some(actual) => write!(f, "{actual}"),
+            None => write!(f, "missing"),
+        }
+    }
+}
+
+/// Samples `wav` at time `t + shift`, returning [`None`] if `t` is outside the waveform.
+///
+/// Shifted times beyond either end of the waveform are clamped to that end, so that
+/// the same reference points are compared regardless of the shift.
+fn sample<W: TimeWaveform<Data = f64>>(wav: &W, t: f64, shift: f64) -> Option<f64> {
+    let (first, last) = (wav.first()?, wav.last()?);
+    if t < first.t() || t > last.t() {
+        return None;
+    }
+    let t = (t + shift).clamp(first.t(), last.t());
+    if t == last.t() {
+        Some(last.x())
+    } else {
+        Some(wav.sample_at(t))
+    }
+}
+
+/// Compares `wav` against the reference waveform `reference`, evaluating `wav` at each
+/// reference time point shifted by `shift`.
+///
+/// Every reference point in the comparison window is included. Points that `wav` does
+/// not cover are mismatches.
+fn diff_points<W, R>(wav: &W, reference: &R, opts: &CompareOptions, shift: f64) -> Vec<DiffPoint>
+where
+    W: TimeWaveform<Data = f64>,
+    R: TimeWaveform<Data = f64>,
+{
+    reference
+        .values()
+        .filter(|p| {
+            opts.window
+                .is_none_or(|(start, stop)| p.t() >= start && p.t() <= stop)
+        })
+        .map(|p| DiffPoint {
+            t: p.t(),
+            reference: p.x(),
+            actual: sample(wav, p.t(), shift),
+            allowed: opts.allowed(p.x()),
+        })
+        .collect()
+}
+
+/// Compares the simulated waveform `wav` against `reference`.
+///
+/// If time shift alignment is enabled, the shift minimizing the mean squared error over
+/// the reference points is used. Reference points that the simulated waveform does not
+/// cover are reported as failures.
+pub fn compare<W, R>(wav: &W, reference: &R, opts: &CompareOptions) -> GoldenDiff
+where
+    W: TimeWaveform<Data = f64>,
+    R: TimeWaveform<Data = f64>,
+{
+    let steps = opts.shift_steps as i64;
+    let (shift, points) = (-steps..=steps)
+        .map(|i| {
+            let shift = if steps == 0 {
+                0.
+            } else {
+                opts.max_shift * i as f64 / steps as f64
+            };
+            (shift, diff_points(wav, reference, opts, shift))
+        })
+        .min_by(|(_, a), (_, b)| mean_squared_error(a).total_cmp(&mean_squared_error(b)))
+        .unwrap_or((0., Vec::new()));
+
+    let mut failures: Vec<DiffWindow> = Vec::new();
+    let mut in_window = false;
+    for point in points.iter() {
+        if point.passed() {
+            in_window = false;
+            continue;
+        }
+        match failures.last_mut() {
+            Some(window) if in_window => {
+                window.stop = point.t;
+                if point.error() > window.worst.error() {
+                    window.worst = *point;
+                }
+            }
+            _ => failures.push(DiffWindow {
+                start: point.t,
+                stop: point.t,
+                worst: *point,
+            }),
+        }
+        in_window = true;
+    }
+
+    GoldenDiff {
+        shift,
+        points,
+        failures,
+    }
+}
+
+/// The mean squared error of the points covered by the simulated waveform.
+///
+/// Whether a point is covered does not depend on the shift, so the errors of
+/// different shifts are averaged over the same reference points.
+fn mean_squared_error(points: &[DiffPoint]) -> f64 {
+    let errors = points
+        .iter()
+        .filter(|p| p.actual.is_some())
+        .map(|p| p.error().powi(2))
+        .collect::<Vec<_>>();
+    errors.iter().sum::<f64>() / errors.len().max(1) as f64
+}
+
+/// Saves `wav` as a golden reference at `path`, creating parent directories if necessary.
+pub fn save_golden<W: TimeWaveform<Data = f64>>(
+    wav: &W,
+    path: impl AsRef<Path>,
+) -> Result<(), GoldenError> {
+    let path = path.as_ref();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let wav = wav
+        .values()
+        .map(|p| (p.t(), p.x()))
+        .collect::<Waveform<f64>>();
+    let mut writer = BufWriter::new(File::create(path)?);
+    serde_json::to_writer(&mut writer, &wav)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Loads the golden reference stored at `path`.
+pub fn load_golden(path: impl AsRef<Path>) -> Result<Waveform<f64>, GoldenError> {
+    Ok(serde_json::from_reader(BufReader::new(File::open(path)?))?)
+}
+
+/// Compares `wav` against the golden reference stored at `path`.
+///
+/// If the [`UPDATE_GOLDEN_ENV`] environment variable is set, `wav` is instead saved as the new
+/// reference and compared against itself.
+pub fn check_golden<W: TimeWaveform<Data = f64>>(
+    wav: &W,
+    path: impl AsRef<Path>,
+    opts: &CompareOptions,
+) -> Result<GoldenDiff, GoldenError> {
+    let path = path.as_ref();
+    if std::env::var_os(UPDATE_GOLDEN_ENV).is_some() {
+        save_golden(wav, path)?;
+    }
+    let reference = load_golden(path)?;
+    Ok(compare(wav, &reference, opts))
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use super::*;
+    use crate::simulation::waveform::TimePoint;
+    use crate::tests::get_path;
+
+    fn ramp(delay: f64) -> Waveform<f64> {
+        (0..=100)
+            .map(|i| {
+                let t = i as f64 * 0.1;
+                (t, (t - delay).clamp(0., 5.))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn golden_comparison_aligns_time_shifts() {
+        let reference = ramp(1.);
+        let delayed = ramp(1.2);
+
+        let diff = compare(&delayed, &reference, &CompareOptions::new(1e-3, 0.));
+        assert!(!diff.passed());
+        assert_eq!(diff.failures.len(), 1);
+        let window = diff.failures[0];
+        assert_relative_eq!(window.start, 1.1);
+        assert_relative_eq!(window.stop, 6.1);
+        assert_relative_eq!(window.worst.error(), 0.2, epsilon = 1e-9);
+
+        let diff = compare(
+            &delayed,
+            &reference,
+            &CompareOptions::new(1e-3, 0.).with_shift(0.5, 5),
+        );
+        assert!(diff.passed(), "{diff}");
+        assert_relative_eq!(diff.shift, 0.2);
+    }
+
+    #[test]
+    fn golden_reference_round_trip() {
+        let path = get_path("golden_reference_round_trip", "ramp.json");
+        let reference = ramp(1.);
+        save_golden(&reference, &path).unwrap();
+        assert_eq!(load_golden(&path).unwrap().len(), reference.len());
+
+        let diff = check_golden(&ramp(1.), &path, &CompareOptions::new(1e-12, 0.)).unwrap();
+        assert!(diff.passed());
+
+        let mut perturbed = ramp(1.);
+        perturbed[50] = TimePoint::new(perturbed[50].t(), 4.5);
+        let diff = check_golden(
+            &perturbed,
+            &path,
+            &CompareOptions::new(0.1, 0.).with_window(2., 8.),
+        )
+        .unwrap();
+        assert!(!diff.passed());
+        assert_eq!(diff.points.len(), 61);
+        assert_relative_eq!(diff.failures[0].worst.t, 5.);
+
+        let mut csv = Vec::new();
+        diff.write_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert!(csv.starts_with("t,reference,actual,error,allowed\n"));
+        assert!(diff.to_string().contains("out of tolerance"));
+    }
+
+    #[test]
+    fn golden_comparison_fails_uncovered_reference_points() {
+        let reference = ramp(1.);
+        let truncated = reference
+            .values()
+            .take(51)
+            .map(|p| (p.t(), p.x()))
+            .collect::<Waveform<f64>>();
+
+        let diff = compare(
+            &truncated,
+            &reference,
+            &CompareOptions::new(1e-3, 0.).with_shift(0.5, 5),
+        );
+        assert!(!diff.passed());
+        assert_eq!(diff.points.len(), reference.len());
+        assert_relative_eq!(diff.shift, 0.);
+        assert_eq!(diff.failures.len(), 1);
+        assert_relative_eq!(diff.failures[0].start, 5.1);
+        assert_relative_eq!(diff.failures[0].stop, 10.);
+        assert_eq!(diff.failures[0].worst.actual, None);
+    }
+}
@@ -0,0 +1,104 @@
+//! Simulator log messages.
+//!
+//! Simulators report errors in terms of netlisted names, which can be difficult to relate
+//! back to the generators that produced them. The utilities in this module map names
+//! mentioned in simulator logs back to SCIR and Substrate paths.
+
+use std::fmt::Display;
+
+use scir::NetlistLibConversion;
+
+use crate::schematic::conv::RawLib;
+use crate::schematic::schema::Schema;
+use crate::types::schematic::NodePath;
+
+/// A path in a SCIR library.
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub enum ScirPath {
+    /// A path to an instance.
+    Instance(scir::InstancePath),
+    /// A path to a single-bit signal.
+    Signal(scir::SliceOnePath),
+}
+
+/// A name mentioned in a simulator log, along with the paths it corresponds to.
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub struct MentionedName {
+    /// The name as it appears in the simulator log.
+    pub name: String,
+    /// The corresponding SCIR path.
+    pub scir: ScirPath,
+    /// The corresponding path, using the names of SCIR instances and signals.
+    pub scir_name: String,
+    /// The corresponding Substrate node path, if the name refers to a Substrate node.
+    pub node: Option<NodePath>,
+}
+
+/// A message reported by a simulator.
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub struct SimulatorMessage {
+    /// The message, as it appears in the simulator log.
+    pub message: String,
+    /// The names mentioned in the message that could be mapped back to SCIR.
+    pub names: Vec<MentionedName>,
+}
+
+impl Display for SimulatorMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)?;
+        for name in self.names.iter() {
+            write!(f, "\n  `{}` refers to `{}`", name.name, name.scir_name)?;
+        }
+        Ok(())
+    }
+}
+
+impl<S: Schema> RawLib<S> {
+    /// Maps a netlisted name mentioned by a simulator back to SCIR and Substrate paths.
+    ///
+    /// `elems` should contain the hierarchical elements of the name, starting from the top cell.
+    /// If the last element names a bus signal, `index` should contain the bus index.
+    /// `conv` should be the conversion metadata produced when netlisting this library.
+    ///
+    /// Returns [`None`] if the name does not correspond to an instance or signal.
+    pub fn resolve_netlisted_name(
+        &self,
+        conv: &NetlistLibConversion,
+        name: impl Into<String>,
+        elems: &[&str],
+        index: Option<usize>,
+    ) -> Option<MentionedName> {
+        if elems.is_empty() {
+            return None;
+        }
+        let (scir, scir_name, node) = if let Some(path) = index
+            .is_none()
+            .then(|| self.scir.find_netlisted_instance_path(conv, elems))
+            .flatten()
+        {
+            let scir_name = self.scir.convert_instance_path(path.clone()).join(".");
+            (ScirPath::Instance(path), scir_name, None)
+        } else {
+            let (signal, instances) = elems.split_last()?;
+            let path = self
+                .scir
+                .find_netlisted_slice_one_path(conv, instances, signal, index)?;
+            let scir_name = self
+                .scir
+                .convert_slice_one_path(path.clone(), |name, index| match index {
+                    Some(index) => arcstr::format!("{name}[{index}]"),
+                    None => name.clone(),
+                })
+                .join(".");
+            let node = self.convert_scir_slice_one_path(&path);
+            (ScirPath::Signal(path), scir_name, node)
+        };
+
+        Some(MentionedName {
+            name: name.into(),
+            scir,
+            scir_name,
+            node,
+        })
+    }
+}
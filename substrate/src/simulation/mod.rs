@@ -4,10 +4,13 @@ use std::any::Any;
 use std::path::PathBuf;
 use std::sync::Arc;
 
+use chain::{ChainContext, ChainedAnalysis};
 use data::{Save, Saved};
 use impl_trait_for_tuples::impl_for_tuples;
+use progress::{Progress, ProgressAction, ProgressCallback};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use tracing::{span, Level};
 
 use crate::block::Block;
 use crate::context::{Context, Installation};
@@ -16,9 +19,29 @@ use crate::schematic::schema::Schema;
 use crate::schematic::{Cell, HasNestedView, NestedView, Schematic};
 use crate::types::TestbenchIo;
 
+pub mod analysis;
+pub mod assertions;
+pub mod batch;
+pub mod chain;
+pub mod characterization;
+pub mod corners;
 pub mod data;
+pub mod discovery;
+pub mod export;
+pub mod golden;
+pub mod messages;
+pub mod models;
+pub mod optimize;
 pub mod options;
+#[cfg(feature = "plot")]
+pub mod plot;
+pub mod progress;
+pub mod samples;
+pub mod seed;
+pub mod stimulus;
+pub mod testbench;
 pub mod waveform;
+pub mod yield_analysis;
 
 /// A process-voltage-temperature corner.
 ///
@@ -91,6 +114,47 @@ pub trait Simulator: Installation + Any + Send + Sync {
     }
 }
 
+/// The files written by a simulator in preparation for running a simulation.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SimArtifacts {
+    /// The path to the simulation netlist.
+    pub netlist: PathBuf,
+    /// The path to the script used to run the simulation.
+    pub run_script: PathBuf,
+}
+
+/// A simulator that supports dry runs.
+///
+/// A dry run performs all steps up to and including netlisting and run script generation,
+/// but does not invoke the simulator. This allows netlists to be validated on machines
+/// without access to the simulator.
+pub trait DryRun: Simulator {
+    /// Writes the netlist and run script for the given set of analyses without simulating.
+    fn dry_run_inputs(
+        &self,
+        ctx: &SimulationContext<Self>,
+        options: Self::Options,
+        input: Vec<Self::Input>,
+    ) -> Result<SimArtifacts, Self::Error>;
+
+    /// Writes the netlist and run script for the given, possibly composite,
+    /// analysis without simulating.
+    fn dry_run<A>(
+        &self,
+        ctx: &SimulationContext<Self>,
+        options: Self::Options,
+        input: A,
+    ) -> Result<SimArtifacts, Self::Error>
+    where
+        A: SupportedBy<Self>,
+        Self: Sized,
+    {
+        let mut inputs = Vec::new();
+        input.into_input(&mut inputs);
+        self.dry_run_inputs(ctx, options, inputs)
+    }
+}
+
 /// Substrate-defined simulation context.
 pub struct SimulationContext<S: Simulator + ?Sized> {
     /// The simulator's intended working directory.
@@ -99,6 +163,20 @@ pub struct SimulationContext<S: Simulator + ?Sized> {
     pub lib: Arc<RawLib<S::Schema>>,
     /// The global context.
     pub ctx: Context,
+    /// A callback invoked when the simulator reports progress.
+    pub progress: Option<ProgressCallback>,
+}
+
+impl<S: Simulator + ?Sized> SimulationContext<S> {
+    /// Reports simulation progress to the registered callback, if any.
+    ///
+    /// Simulators should stop the simulation if this returns [`ProgressAction::Stop`].
+    pub fn report_progress(&self, progress: &Progress) -> ProgressAction {
+        self.progress
+            .as_ref()
+            .map(|callback| callback.call(progress))
+            .unwrap_or_default()
+    }
 }
 
 /// Indicates that a particular analysis is supported by a simulator.
@@ -127,6 +205,13 @@ impl<S: Simulator, T: Testbench<S>> SimController<S, T> {
         options: S::Options,
         input: A,
     ) -> Result<A::Output, S::Error> {
+        let _guard = span!(
+            Level::INFO,
+            "simulating testbench",
+            testbench = %self.tb.block().name(),
+            work_dir = ?self.ctx.work_dir,
+        )
+        .entered();
         self.simulator.simulate(&self.ctx, options, input)
     }
 
@@ -152,6 +237,56 @@ impl<S: Simulator, T: Testbench<S>> SimController<S, T> {
         )
     }
 
+    /// Run the given chained analysis, returning the default outputs of each stage.
+    ///
+    /// Each stage is run in a separate simulator invocation. See [`chain`] for details.
+    pub fn simulate_chain<C: ChainedAnalysis<S>>(
+        &self,
+        options: S::Options,
+        chain: C,
+    ) -> Result<C::Output, S::Error> {
+        let _guard = span!(
+            Level::INFO,
+            "simulating chained analysis",
+            testbench = %self.tb.block().name(),
+            work_dir = ?self.ctx.work_dir,
+        )
+        .entered();
+        chain.simulate_chain(
+            &mut ChainContext::new(self.simulator.as_ref(), &self.ctx),
+            options,
+        )
+    }
+
+    /// Writes the netlist and run script for the given analysis without simulating.
+    ///
+    /// Signals are saved as they would be by [`SimController::simulate`],
+    /// so the resulting netlist is identical to that of a real simulation.
+    pub fn dry_run<A: SupportedBy<S>>(
+        &self,
+        mut options: S::Options,
+        input: A,
+    ) -> Result<SimArtifacts, S::Error>
+    where
+        S: DryRun,
+        T: Schematic<NestedData: HasNestedView<NestedView: Save<S, A>>>,
+    {
+        <NestedView<<T as Schematic>::NestedData> as Save<S, A>>::save(
+            &self.tb.data(),
+            &self.ctx,
+            &mut options,
+        );
+        self.simulator.dry_run(&self.ctx, options, input)
+    }
+
+    /// Registers a callback to be invoked when the simulator reports progress.
+    ///
+    /// Returning [`ProgressAction::Stop`] from the callback requests that the
+    /// simulation be stopped early. Not all simulators report progress.
+    pub fn on_progress(&mut self, f: impl Fn(&Progress) -> ProgressAction + Send + Sync + 'static) {
+        self.ctx.progress = Some(ProgressCallback::new(f));
+    }
+
     /// Set an option by mutating the given options.
     pub fn set_option<O>(&self, opt: O, options: &mut S::Options)
     where
@@ -0,0 +1,240 @@
+//! PDK-provided device model libraries.
+//!
+//! A PDK describes its simulation models as a [`ModelLibrary`]: a list of model files, each
+//! with the library sections to include in every process corner. A model file may be
+//! restricted to a set of devices, in which case it is only included in simulations of
+//! testbenches that instantiate at least one of those devices:
+//!
+//! ```ignore
+//! impl ProvidesModels<Spectre> for MyPdk {
+//!     fn models(&self) -> ModelLibrary {
+//!         ModelLibrary::new()
+//!             .file(ModelFile::new(self.root.join("models.scs")).section("{corner}_fet"))
+//!             .file(
+//!                 ModelFile::new(self.root.join("rf.scs"))
+//!                     .section("{corner}_rf")
+//!                     .devices(["rf_nmos", "rf_pmos"]),
+//!             )
+//!     }
+//! }
+//! ```
+//!
+//! A PDK corner then includes the models it requires using
+//! [`SimulationContext::include_models`].
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use arcstr::ArcStr;
+use serde::{Deserialize, Serialize};
+
+use super::{SimulationContext, Simulator};
+use crate::context::Installation;
+
+/// The placeholder replaced by the corner name in model library section names.
+pub const CORNER_PLACEHOLDER: &str = "{corner}";
+
+/// A model file to include in a simulation.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelInclude {
+    /// The path to the model file.
+    pub path: PathBuf,
+    /// The library section to include, if any.
+    pub section: Option<ArcStr>,
+}
+
+/// A model file provided by a PDK.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelFile {
+    path: PathBuf,
+    /// Section names, in which [`CORNER_PLACEHOLDER`] is replaced by the corner name.
+    sections: Vec<ArcStr>,
+    /// The lowercase names of the devices whose models are defined in this file.
+    ///
+    /// Empty if the file is required by all testbenches.
+    devices: HashSet<ArcStr>,
+}
+
+impl ModelFile {
+    /// Creates a model file at `path` that is included in its entirety by all testbenches.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            sections: Vec::new(),
+            devices: HashSet::new(),
+        }
+    }
+
+    /// Includes the given library section instead of the entire file.
+    ///
+    /// Occurrences of [`CORNER_PLACEHOLDER`] in `section` are replaced by the corner name.
+    /// If called more than once, each section is included.
+    pub fn section(mut self, section: impl Into<ArcStr>) -> Self {
+        self.sections.push(section.into());
+        self
+    }
+
+    /// Only includes this file in testbenches that instantiate at least one of the given
+    /// devices.
+    ///
+    /// Device names are matched case-insensitively against the model or subcircuit
+    /// names of the testbench's primitives.
+    pub fn devices(mut self, devices: impl IntoIterator<Item = impl Into<ArcStr>>) -> Self {
+        self.devices.extend(
+            devices
+                .into_iter()
+                .map(|device| ArcStr::from(device.into().to_lowercase())),
+        );
+        self
+    }
+
+    /// Returns `true` if this file is required by a testbench instantiating `devices`.
+    ///
+    /// Device names in `devices` must be lowercase.
+    fn required_by(&self, devices: &HashSet<ArcStr>) -> bool {
+        self.devices.is_empty() || !self.devices.is_disjoint(devices)
+    }
+
+    /// The includes of this file required for the given corner.
+    fn includes(&self, corner: &str) -> impl Iterator<Item = ModelInclude> + '_ {
+        let sections = if self.sections.is_empty() {
+            vec![None]
+        } else {
+            self.sections
+                .iter()
+                .map(|section| Some(ArcStr::from(section.replace(CORNER_PLACEHOLDER, corner))))
+                .collect()
+        };
+        sections.into_iter().map(|section| ModelInclude {
+            path: self.path.clone(),
+            section,
+        })
+    }
+}
+
+/// A library of device models provided by a PDK.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelLibrary {
+    files: Vec<ModelFile>,
+}
+
+impl ModelLibrary {
+    /// Creates an empty model library.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a model file to the library.
+    pub fn file(mut self, file: ModelFile) -> Self {
+        self.files.push(file);
+        self
+    }
+
+    /// The model files in this library.
+    pub fn files(&self) -> &[ModelFile] {
+        &self.files
+    }
+
+    /// Resolves the includes required in `corner` by a testbench instantiating `devices`.
+    ///
+    /// Includes are returned in the order in which their files were added to the library.
+    pub fn resolve<'a>(
+        &self,
+        corner: &str,
+        devices: impl IntoIterator<Item = &'a ArcStr>,
+    ) -> Vec<ModelInclude> {
+        let devices = devices
+            .into_iter()
+            .map(|device| ArcStr::from(device.to_lowercase()))
+            .collect::<HashSet<_>>();
+        self.files
+            .iter()
+            .filter(|file| file.required_by(&devices))
+            .flat_map(|file| file.includes(corner))
+            .collect()
+    }
+}
+
+/// A simulator that can include model files.
+pub trait SupportsModels: Simulator {
+    /// Returns the names of the models and subcircuits instantiated by the primitives
+    /// of `lib`.
+    fn device_models(lib: &scir::Library<Self::Schema>) -> HashSet<ArcStr>;
+
+    /// Includes the given model file in `opts`.
+    fn include_model(opts: &mut Self::Options, include: ModelInclude);
+}
+
+/// An installation, typically a PDK, that provides device models for simulator `S`.
+pub trait ProvidesModels<S: Simulator>: Installation {
+    /// The model library of this installation.
+    fn models(&self) -> ModelLibrary;
+}
+
+impl<S: SupportsModels> SimulationContext<S> {
+    /// Returns the includes of the model library provided by installation `P`
+    /// that are required by this simulation's testbench in `corner`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `P` is not installed.
+    pub fn model_includes<P: ProvidesModels<S>>(&self, corner: &str) -> Vec<ModelInclude> {
+        let provider = self
+            .ctx
+            .get_installation::<P>()
+            .expect("model library provider must be installed");
+        provider
+            .models()
+            .resolve(corner, &S::device_models(&self.lib.scir))
+    }
+
+    /// Includes the models provided by installation `P` that are required by this
+    /// simulation's testbench in `corner`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `P` is not installed.
+    pub fn include_models<P: ProvidesModels<S>>(&self, corner: &str, opts: &mut S::Options) {
+        for include in self.model_includes::<P>(corner) {
+            S::include_model(opts, include);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn model_library_resolves_corner_sections() {
+        let lib = ModelLibrary::new()
+            .file(
+                ModelFile::new("models.lib")
+                    .section("{corner}_fet")
+                    .section("{corner}_res"),
+            )
+            .file(
+                ModelFile::new("rf.lib")
+                    .section("rf_{corner}")
+                    .devices(["RF_NMOS"]),
+            )
+            .file(ModelFile::new("common.lib"));
+
+        let includes = lib.resolve("ss", &[arcstr::literal!("nmos")]);
+        assert_eq!(
+            includes
+                .iter()
+                .map(|include| (include.path.to_str().unwrap(), include.section.as_deref()))
+                .collect::<Vec<_>>(),
+            [
+                ("models.lib", Some("ss_fet")),
+                ("models.lib", Some("ss_res")),
+                ("common.lib", None),
+            ]
+        );
+
+        let includes = lib.resolve("ff", &[arcstr::literal!("rf_nmos")]);
+        assert_eq!(includes.len(), 4);
+        assert_eq!(includes[2].section.as_deref(), Some("rf_ff"));
+    }
+}
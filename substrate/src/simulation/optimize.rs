@@ -0,0 +1,504 @@
+//! Optimization of generator parameters.
+//!
+//! An [`Optimizer`] repeatedly converts a parameter vector into a block, runs a user-supplied
+//! testbench on the block, and evaluates a user-supplied cost function on the resulting
+//! measurements. Evaluations are memoized on the value of the generated block, so parameter
+//! vectors that produce identical blocks (e.g. after snapping to a manufacturing grid)
+//! are only simulated once.
+//!
+//! Two derivative-free methods are supported: [Nelder-Mead](NelderMead) for local
+//! refinement and [differential evolution](DifferentialEvolution) for global search.
+
+use std::collections::HashMap;
+
+use arcstr::ArcStr;
+use serde::{Deserialize, Serialize};
+
+use super::stimulus::StimulusRng;
+use crate::block::Block;
+
+/// A bounded optimization parameter.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Parameter {
+    /// The name of the parameter.
+    pub name: ArcStr,
+    /// The minimum allowed value.
+    pub min: f64,
+    /// The maximum allowed value.
+    pub max: f64,
+}
+
+impl Parameter {
+    /// Creates a new parameter with the given bounds.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `min` is greater than `max`.
+    pub fn new(name: impl Into<ArcStr>, min: f64, max: f64) -> Self {
+        assert!(min <= max, "parameter minimum must not exceed its maximum");
+        Self {
+            name: name.into(),
+            min,
+            max,
+        }
+    }
+
+    fn clamp(&self, x: f64) -> f64 {
+        x.clamp(self.min, self.max)
+    }
+
+    fn range(&self) -> f64 {
+        self.max - self.min
+    }
+}
+
+/// Options for the Nelder-Mead simplex method.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct NelderMead {
+    /// The maximum number of iterations.
+    pub max_iters: usize,
+    /// The initial simplex size, as a fraction of each parameter's range.
+    pub initial_step: f64,
+    /// Stops when the difference between the best and worst costs
+    /// in the simplex falls below this tolerance.
+    pub tol: f64,
+}
+
+impl Default for NelderMead {
+    fn default() -> Self {
+        Self {
+            max_iters: 100,
+            initial_step: 0.1,
+            tol: 1e-6,
+        }
+    }
+}
+
+/// Options for differential evolution (the `DE/rand/1/bin` variant).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DifferentialEvolution {
+    /// The number of candidate solutions in each generation.
+    ///
+    /// Must be at least 4.
+    pub population: usize,
+    /// The number of generations.
+    pub generations: usize,
+    /// The differential weight, typically between 0.5 and 1.
+    pub weight: f64,
+    /// The crossover probability.
+    pub crossover: f64,
+    /// The seed used to initialize the population and choose mutations.
+    pub seed: u64,
+}
+
+impl Default for DifferentialEvolution {
+    fn default() -> Self {
+        Self {
+            population: 20,
+            generations: 50,
+            weight: 0.8,
+            crossover: 0.9,
+            seed: 0,
+        }
+    }
+}
+
+/// An optimization method.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Method {
+    /// The Nelder-Mead simplex method.
+    NelderMead(NelderMead),
+    /// Differential evolution.
+    DifferentialEvolution(DifferentialEvolution),
+}
+
+impl Default for Method {
+    fn default() -> Self {
+        Self::NelderMead(NelderMead::default())
+    }
+}
+
+/// A single evaluation of the objective during optimization.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Evaluation<M> {
+    /// The parameter values, clamped to the parameter bounds.
+    pub params: Vec<f64>,
+    /// The measurements produced by the testbench.
+    pub measurements: M,
+    /// The cost of the measurements.
+    pub cost: f64,
+    /// Whether the measurements were reused from an earlier evaluation of an identical block.
+    pub cached: bool,
+}
+
+/// The result of running an [`Optimizer`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OptimizationResult<M> {
+    /// Every evaluation performed, in order.
+    pub history: Vec<Evaluation<M>>,
+    /// The index of the lowest-cost evaluation in `history`.
+    pub best: usize,
+}
+
+impl<M> OptimizationResult<M> {
+    /// The lowest-cost evaluation.
+    pub fn best(&self) -> &Evaluation<M> {
+        &self.history[self.best]
+    }
+
+    /// The number of testbench runs, excluding evaluations served from the cache.
+    pub fn simulations(&self) -> usize {
+        self.history.iter().filter(|eval| !eval.cached).count()
+    }
+}
+
+/// A driver for tuning the parameters of a generator.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Optimizer {
+    params: Vec<Parameter>,
+    method: Method,
+    initial: Option<Vec<f64>>,
+}
+
+/// Memoizes evaluations on the generated block and records history.
+struct Evaluator<'a, B, M, E> {
+    params: &'a [Parameter],
+    generate: &'a dyn Fn(&[f64]) -> B,
+    simulate: &'a dyn Fn(&B) -> Result<M, E>,
+    cost: &'a dyn Fn(&M) -> f64,
+    cache: HashMap<B, usize>,
+    history: Vec<Evaluation<M>>,
+}
+
+impl<B: Block, M: Clone, E> Evaluator<'_, B, M, E> {
+    fn eval(&mut self, x: &[f64]) -> Result<f64, E> {
+        let params = x
+            .iter()
+            .zip(self.params)
+            .map(|(&x, param)| param.clamp(x))
+            .collect::<Vec<_>>();
+        let block = (self.generate)(&params);
+        let eval = if let Some(&idx) = self.cache.get(&block) {
+            let prev = &self.history[idx];
+            Evaluation {
+                params,
+                measurements: prev.measurements.clone(),
+                cost: prev.cost,
+                cached: true,
+            }
+        } else {
+            let measurements = (self.simulate)(&block)?;
+            let cost = (self.cost)(&measurements);
+            self.cache.insert(block, self.history.len());
+            Evaluation {
+                params,
+                measurements,
+                cost,
+                cached: false,
+            }
+        };
+        let cost = eval.cost;
+        self.history.push(eval);
+        Ok(cost)
+    }
+}
+
+impl Optimizer {
+    /// Creates an optimizer over the given parameters.
+    ///
+    /// By default, optimization starts from the center of the parameter bounds.
+    pub fn new(params: Vec<Parameter>, method: Method) -> Self {
+        Self {
+            params,
+            method,
+            initial: None,
+        }
+    }
+
+    /// Sets the initial parameter values.
+    ///
+    /// For differential evolution, the initial values are included in the first generation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of values does not match the number of parameters.
+    pub fn initial(mut self, initial: Vec<f64>) -> Self {
+        assert_eq!(
+            initial.len(),
+            self.params.len(),
+            "expected one initial value per parameter"
+        );
+        self.initial = Some(initial);
+        self
+    }
+
+    /// The parameters being optimized.
+    pub fn params(&self) -> &[Parameter] {
+        &self.params
+    }
+
+    fn start(&self) -> Vec<f64> {
+        self.initial.clone().unwrap_or_else(|| {
+            self.params
+                .iter()
+                .map(|param| param.min + param.range() / 2.)
+                .collect()
+        })
+    }
+
+    /// Runs the optimizer.
+    ///
+    /// `generate` creates a block from a parameter vector, `simulate` runs a testbench on
+    /// the block and returns its measurements, and `cost` maps measurements to a cost to be
+    /// minimized. Parameter vectors are clamped to the parameter bounds before being passed
+    /// to `generate`.
+    ///
+    /// Returns the first error returned by `simulate`.
+    pub fn run<B: Block, M: Clone, E>(
+        &self,
+        generate: impl Fn(&[f64]) -> B,
+        simulate: impl Fn(&B) -> Result<M, E>,
+        cost: impl Fn(&M) -> f64,
+    ) -> Result<OptimizationResult<M>, E> {
+        let mut evaluator = Evaluator {
+            params: &self.params,
+            generate: &generate,
+            simulate: &simulate,
+            cost: &cost,
+            cache: HashMap::new(),
+            history: Vec::new(),
+        };
+        match self.method {
+            Method::NelderMead(opts) => self.nelder_mead(&mut evaluator, opts)?,
+            Method::DifferentialEvolution(opts) => {
+                self.differential_evolution(&mut evaluator, opts)?
+            }
+        }
+        let history = evaluator.history;
+        let best = history
+            .iter()
+            .enumerate()
+            .min_by(|a, b| a.1.cost.total_cmp(&b.1.cost))
+            .map(|(i, _)| i)
+            .expect("optimizer should perform at least one evaluation");
+        Ok(OptimizationResult { history, best })
+    }
+
+    fn nelder_mead<B: Block, M: Clone, E>(
+        &self,
+        evaluator: &mut Evaluator<'_, B, M, E>,
+        opts: NelderMead,
+    ) -> Result<(), E> {
+        let n = self.params.len();
+        let start = self.start();
+        let mut simplex = vec![start.clone()];
+        for (i, param) in self.params.iter().enumerate() {
+            let mut vertex = start.clone();
+            let step = opts.initial_step * param.range();
+            // Step away from the nearest bound so the vertex stays feasible.
+            vertex[i] = if vertex[i] + step <= param.max {
+                vertex[i] + step
+            } else {
+                vertex[i] - step
+            };
+            simplex.push(vertex);
+        }
+        let mut costs = simplex
+            .iter()
+            .map(|x| evaluator.eval(x))
+            .collect::<Result<Vec<_>, E>>()?;
+
+        let lerp = |a: &[f64], b: &[f64], t: f64| -> Vec<f64> {
+            a.iter()
+                .zip(b)
+                .zip(self.params.iter())
+                .map(|((a, b), param)| param.clamp(a + t * (b - a)))
+                .collect()
+        };
+
+        for _ in 0..opts.max_iters {
+            let mut order = (0..=n).collect::<Vec<_>>();
+            order.sort_by(|&a, &b| costs[a].total_cmp(&costs[b]));
+            simplex = order.iter().map(|&i| simplex[i].clone()).collect();
+            costs = order.iter().map(|&i| costs[i]).collect();
+            if costs[n] - costs[0] <= opts.tol {
+                break;
+            }
+
+            let centroid = (0..n)
+                .map(|j| simplex[..n].iter().map(|x| x[j]).sum::<f64>() / n as f64)
+                .collect::<Vec<_>>();
+            let worst = simplex[n].clone();
+
+            let reflected = lerp(&centroid, &worst, -1.);
+            let reflected_cost = evaluator.eval(&reflected)?;
+            if reflected_cost < costs[0] {
+                let expanded = lerp(&centroid, &worst, -2.);
+                let expanded_cost = evaluator.eval(&expanded)?;
+                if expanded_cost < reflected_cost {
+                    (simplex[n], costs[n]) = (expanded, expanded_cost);
+                } else {
+                    (simplex[n], costs[n]) = (reflected, reflected_cost);
+                }
+                continue;
+            }
+            if reflected_cost < costs[n - 1] {
+                (simplex[n], costs[n]) = (reflected, reflected_cost);
+                continue;
+            }
+
+            let contracted = lerp(&centroid, &worst, 0.5);
+            let contracted_cost = evaluator.eval(&contracted)?;
+            if contracted_cost < costs[n] {
+                (simplex[n], costs[n]) = (contracted, contracted_cost);
+                continue;
+            }
+
+            // Shrink all vertices towards the best vertex.
+            for i in 1..=n {
+                simplex[i] = lerp(&simplex[0], &simplex[i], 0.5);
+                costs[i] = evaluator.eval(&simplex[i])?;
+            }
+        }
+        Ok(())
+    }
+
+    fn differential_evolution<B: Block, M: Clone, E>(
+        &self,
+        evaluator: &mut Evaluator<'_, B, M, E>,
+        opts: DifferentialEvolution,
+    ) -> Result<(), E> {
+        assert!(
+            opts.population >= 4,
+            "differential evolution requires a population of at least 4"
+        );
+        let n = self.params.len();
+        let mut rng = StimulusRng::new(opts.seed);
+        let mut population = (0..opts.population)
+            .map(|i| match (&self.initial, i) {
+                (Some(initial), 0) => initial.clone(),
+                _ => self
+                    .params
+                    .iter()
+                    .map(|param| param.min + rng.next_f64() * param.range())
+                    .collect(),
+            })
+            .collect::<Vec<Vec<f64>>>();
+        let mut costs = population
+            .iter()
+            .map(|x| evaluator.eval(x))
+            .collect::<Result<Vec<_>, E>>()?;
+
+        let pick = |rng: &mut StimulusRng, exclude: &[usize]| loop {
+            let idx = (rng.next_u64() % opts.population as u64) as usize;
+            if !exclude.contains(&idx) {
+                break idx;
+            }
+        };
+
+        for _ in 0..opts.generations {
+            for i in 0..opts.population {
+                let a = pick(&mut rng, &[i]);
+                let b = pick(&mut rng, &[i, a]);
+                let c = pick(&mut rng, &[i, a, b]);
+                let forced = (rng.next_u64() % n.max(1) as u64) as usize;
+                let trial = (0..n)
+                    .map(|j| {
+                        if j == forced || rng.next_bool(opts.crossover) {
+                            self.params[j].clamp(
+                                population[a][j]
+                                    + opts.weight * (population[b][j] - population[c][j]),
+                            )
+                        } else {
+                            population[i][j]
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                let trial_cost = evaluator.eval(&trial)?;
+                if trial_cost <= costs[i] {
+                    population[i] = trial;
+                    costs[i] = trial_cost;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::types::TwoTerminalIo;
+
+    /// A block whose parameters are snapped to a 0.01 grid.
+    #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Block)]
+    #[substrate(io = "TwoTerminalIo")]
+    struct Snapped {
+        x: i64,
+        y: i64,
+    }
+
+    fn generate(params: &[f64]) -> Snapped {
+        Snapped {
+            x: (params[0] * 100.).round() as i64,
+            y: (params[1] * 100.).round() as i64,
+        }
+    }
+
+    fn cost(m: &(f64, f64)) -> f64 {
+        (m.0 - 0.3).powi(2) + (m.1 + 0.2).powi(2)
+    }
+
+    fn params() -> Vec<Parameter> {
+        vec![Parameter::new("x", -1., 1.), Parameter::new("y", -1., 1.)]
+    }
+
+    #[test]
+    fn nelder_mead_finds_minimum_and_reuses_identical_blocks() {
+        let runs = Cell::new(0);
+        let result = Optimizer::new(params(), Method::NelderMead(NelderMead::default()))
+            .run(
+                generate,
+                |block: &Snapped| {
+                    runs.set(runs.get() + 1);
+                    Ok::<_, ()>((block.x as f64 / 100., block.y as f64 / 100.))
+                },
+                cost,
+            )
+            .unwrap();
+        let best = result.best();
+        assert!((best.measurements.0 - 0.3).abs() <= 0.011);
+        assert!((best.measurements.1 + 0.2).abs() <= 0.011);
+        assert_eq!(result.simulations(), runs.get());
+        assert!(result.simulations() < result.history.len());
+    }
+
+    #[test]
+    fn differential_evolution_is_deterministic() {
+        let optimizer = Optimizer::new(
+            params(),
+            Method::DifferentialEvolution(DifferentialEvolution {
+                seed: 3,
+                ..Default::default()
+            }),
+        );
+        let run = || {
+            optimizer
+                .run(
+                    generate,
+                    |block: &Snapped| Ok::<_, ()>((block.x as f64 / 100., block.y as f64 / 100.)),
+                    cost,
+                )
+                .unwrap()
+        };
+        let result = run();
+        assert_eq!(result, run());
+        assert!(result.best().cost < 1e-3);
+        assert!(result
+            .history
+            .iter()
+            .all(|eval| eval.params.iter().all(|x| (-1. ..=1.).contains(x))));
+    }
+}
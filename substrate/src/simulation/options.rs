@@ -1,6 +1,7 @@
 //! Standard APIs for setting simulator options.
 
 use crate::simulation::{SimulationContext, Simulator};
+use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
 use std::ops::{Deref, DerefMut};
 
@@ -33,6 +34,252 @@ impl From<Decimal> for Temperature {
     }
 }
 
+/// Transient noise options.
+///
+/// Enables noise sources during transient analyses, so that jitter and other noise-induced
+/// effects can be measured from transient waveforms. Simulators without native transient noise
+/// support report an error when simulating with these options.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TransientNoise {
+    /// The maximum frequency of the noise power spectral density.
+    fmax: Decimal,
+    /// The minimum frequency of the noise power spectral density.
+    pub fmin: Option<Decimal>,
+    /// The seed of the random number generator used to generate noise.
+    pub seed: Option<u64>,
+    /// A factor by which all noise sources are scaled.
+    pub scale: Option<Decimal>,
+}
+
+/// A non-positive [`TransientNoise`] bandwidth.
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("transient noise bandwidth must be positive, got {0}")]
+pub struct InvalidNoiseBandwidth(pub Decimal);
+
+impl TransientNoise {
+    /// Creates transient noise options with noise up to frequency `fmax`.
+    ///
+    /// Returns an error if `fmax` is not positive.
+    pub fn new(fmax: Decimal) -> Result<Self, InvalidNoiseBandwidth> {
+        if fmax <= Decimal::ZERO {
+            return Err(InvalidNoiseBandwidth(fmax));
+        }
+        Ok(Self {
+            fmax,
+            fmin: None,
+            seed: None,
+            scale: None,
+        })
+    }
+
+    /// The maximum frequency of the noise power spectral density.
+    pub fn fmax(&self) -> Decimal {
+        self.fmax
+    }
+
+    /// Sets the minimum frequency of the noise power spectral density.
+    pub fn fmin(mut self, fmin: Decimal) -> Self {
+        self.fmin = Some(fmin);
+        self
+    }
+
+    /// Sets the seed of the random number generator used to generate noise.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Sets the factor by which all noise sources are scaled.
+    pub fn scale(mut self, scale: Decimal) -> Self {
+        self.scale = Some(scale);
+        self
+    }
+}
+
+/// Transient output decimation options.
+///
+/// Resamples transient waveforms onto uniformly spaced time points after simulation, before
+/// they are stored in the simulation cache, which reduces the size of cached outputs. Decimation
+/// runs once the simulator output has been parsed, so it does not reduce the peak memory used
+/// while reading the output. Simulator-side output strides, such as Spectre's `strobeperiod`,
+/// reduce the data written by the simulator but may alias high-frequency content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Decimation {
+    /// The spacing between output time points.
+    period: Decimal,
+    /// The first output time point.
+    ///
+    /// Defaults to the first simulated time point.
+    pub start: Option<Decimal>,
+    /// The time after which no output time points are produced.
+    ///
+    /// Defaults to the last simulated time point.
+    pub stop: Option<Decimal>,
+    /// Whether to low-pass filter waveforms before resampling them.
+    ///
+    /// If `true`, each output value is the average of the waveform over one period
+    /// centered at the output time point. Otherwise, waveforms are linearly interpolated
+    /// at each output time point. Defaults to `true`.
+    pub anti_alias: bool,
+}
+
+/// A non-positive [`Decimation`] period.
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("decimation period must be positive, got {0}")]
+pub struct InvalidDecimationPeriod(pub Decimal);
+
+impl Decimation {
+    /// Creates anti-aliased decimation options with output time points `period` apart.
+    ///
+    /// Returns an error if `period` is not positive.
+    pub fn new(period: Decimal) -> Result<Self, InvalidDecimationPeriod> {
+        if period <= Decimal::ZERO {
+            return Err(InvalidDecimationPeriod(period));
+        }
+        Ok(Self {
+            period,
+            start: None,
+            stop: None,
+            anti_alias: true,
+        })
+    }
+
+    /// The spacing between output time points.
+    pub fn period(&self) -> Decimal {
+        self.period
+    }
+
+    /// Sets the first output time point.
+    pub fn start(mut self, start: Decimal) -> Self {
+        self.start = Some(start);
+        self
+    }
+
+    /// Sets the time after which no output time points are produced.
+    pub fn stop(mut self, stop: Decimal) -> Self {
+        self.stop = Some(stop);
+        self
+    }
+
+    /// Sets whether to low-pass filter waveforms before resampling them.
+    pub fn anti_alias(mut self, anti_alias: bool) -> Self {
+        self.anti_alias = anti_alias;
+        self
+    }
+
+    /// Returns the output time points for a waveform simulated at time points `time`.
+    ///
+    /// If decimation would produce more output time points than `time` contains,
+    /// `time` is returned unchanged.
+    pub fn time_points(&self, time: &[f64]) -> Vec<f64> {
+        self.grid(time).unwrap_or_else(|| time.to_vec())
+    }
+
+    /// Returns the output time points, or [`None`] if there would be more of them than
+    /// simulated time points or there are no simulated time points.
+    fn grid(&self, time: &[f64]) -> Option<Vec<f64>> {
+        let period = self.period.to_f64().unwrap_or_default();
+        let (&first, &last) = (time.first()?, time.last()?);
+        let start = self
+            .start
+            .and_then(|start| start.to_f64())
+            .map_or(first, |start| start.max(first));
+        let stop = self
+            .stop
+            .and_then(|stop| stop.to_f64())
+            .map_or(last, |stop| stop.min(last));
+        if stop < start {
+            return Some(Vec::new());
+        }
+        // Tolerate rounding error so that a stop time on the grid is included.
+        // Computing the count as a float first bounds the allocation for tiny periods.
+        let n = ((stop - start) / period + 1e-9).floor();
+        if n.is_nan() || n >= time.len() as f64 {
+            return None;
+        }
+        Some(
+            (0..=n as usize)
+                .map(|k| start + k as f64 * period)
+                .collect(),
+        )
+    }
+
+    /// Resamples `values`, simulated at time points `time`, onto the
+    /// [output time points](Decimation::time_points).
+    ///
+    /// Returns `values` unchanged if `time` is empty.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `time` and `values` have different lengths.
+    pub fn resample(&self, time: &[f64], values: &[f64]) -> Vec<f64> {
+        assert_eq!(
+            time.len(),
+            values.len(),
+            "time points and values must have the same length"
+        );
+        if time.is_empty() {
+            return Vec::new();
+        }
+        let Some(points) = self.grid(time) else {
+            return values.to_vec();
+        };
+        if values.len() == 1 {
+            return vec![values[0]; points.len()];
+        }
+        if !self.anti_alias {
+            return points.iter().map(|&t| interp(time, values, t)).collect();
+        }
+
+        // The integral of the piecewise linear waveform up to each simulated time point.
+        let mut integral = Vec::with_capacity(time.len());
+        integral.push(0.);
+        for i in 1..time.len() {
+            integral.push(
+                integral[i - 1] + 0.5 * (values[i - 1] + values[i]) * (time[i] - time[i - 1]),
+            );
+        }
+        let integral_to = |t: f64| {
+            let i = segment(time, t);
+            integral[i] + 0.5 * (values[i] + interp(time, values, t)) * (t - time[i])
+        };
+
+        let half = self.period.to_f64().unwrap_or_default() / 2.;
+        let (first, last) = (time[0], time[time.len() - 1]);
+        points
+            .iter()
+            .map(|&t| {
+                let lo = (t - half).max(first);
+                let hi = (t + half).min(last);
+                if hi > lo {
+                    (integral_to(hi) - integral_to(lo)) / (hi - lo)
+                } else {
+                    interp(time, values, t)
+                }
+            })
+            .collect()
+    }
+}
+
+/// Returns the index of the first point of the segment of `time` containing `t`.
+///
+/// Times outside of `time` are assigned to the first or last segment.
+fn segment(time: &[f64], t: f64) -> usize {
+    time.partition_point(|&x| x <= t)
+        .saturating_sub(1)
+        .min(time.len() - 2)
+}
+
+/// Linearly interpolates the waveform given by `time` and `values` at time `t`.
+fn interp(time: &[f64], values: &[f64], t: f64) -> f64 {
+    let i = segment(time, t);
+    let (t0, t1) = (time[i], time[i + 1]);
+    if t1 == t0 {
+        return values[i + 1];
+    }
+    values[i] + (t - t0) / (t1 - t0) * (values[i + 1] - values[i])
+}
+
 /// Initial conditions.
 pub mod ic {
     use crate::simulation::{SimulationContext, Simulator};
@@ -109,3 +356,69 @@ pub mod ic {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+    use rust_decimal_macros::dec;
+
+    use super::Decimation;
+
+    #[test]
+    fn decimation_averages_over_each_period() {
+        // A 1 ns period square wave sampled every 0.25 ns for 10 ns.
+        let time = (0..=40).map(|i| i as f64 * 0.25e-9).collect::<Vec<_>>();
+        let values = (0..=40)
+            .map(|i| if (i / 2) % 2 == 0 { 1. } else { 0. })
+            .collect::<Vec<_>>();
+
+        let decimation = Decimation::new(dec!(2e-9))
+            .unwrap()
+            .start(dec!(1e-9))
+            .stop(dec!(9e-9));
+        let points = decimation.time_points(&time);
+        assert_eq!(points.len(), 5);
+        assert_relative_eq!(points[0], 1e-9);
+        assert_relative_eq!(points[4], 9e-9);
+
+        // Averaging over a whole number of periods removes the square wave's harmonics.
+        let averaged = decimation.resample(&time, &values);
+        assert_eq!(averaged.len(), 5);
+        for value in averaged {
+            assert_relative_eq!(value, 0.5, epsilon = 1e-9);
+        }
+
+        // Sampling instead aliases the square wave to a constant.
+        let sampled = decimation.anti_alias(false).resample(&time, &values);
+        for value in sampled {
+            assert_relative_eq!(value, 1.);
+        }
+    }
+
+    #[test]
+    fn decimation_rejects_invalid_periods() {
+        assert!(Decimation::new(dec!(0)).is_err());
+        assert!(Decimation::new(dec!(-1e-9)).is_err());
+
+        // Periods finer than the simulated time step leave waveforms unchanged.
+        let time = vec![0., 1., 2.];
+        let values = vec![0., 1., 0.];
+        let decimation = Decimation::new(dec!(1e-27)).unwrap();
+        assert_eq!(decimation.time_points(&time), time);
+        assert_eq!(decimation.resample(&time, &values), values);
+    }
+
+    #[test]
+    fn decimation_of_short_waveforms() {
+        let decimation = Decimation::new(dec!(1)).unwrap();
+        assert!(decimation.time_points(&[]).is_empty());
+        assert!(decimation.resample(&[], &[]).is_empty());
+
+        assert_eq!(decimation.time_points(&[2.]), vec![2.]);
+        assert_eq!(decimation.resample(&[2.], &[3.]), vec![3.]);
+        assert_eq!(
+            decimation.anti_alias(false).resample(&[2.], &[3.]),
+            vec![3.]
+        );
+    }
+}
@@ -0,0 +1,169 @@
+//! Quick plotting of simulation waveforms.
+//!
+//! Intended for visual inspection of results in examples and tests;
+//! not a replacement for a full-featured waveform viewer.
+
+use std::path::Path;
+
+use plotters::coord::Shift;
+use plotters::prelude::*;
+
+/// An error encountered while plotting.
+#[derive(Debug, thiserror::Error)]
+#[error("error plotting waveforms: {0}")]
+pub struct PlotError(String);
+
+impl<E: std::error::Error + Send + Sync> From<DrawingAreaErrorKind<E>> for PlotError {
+    fn from(value: DrawingAreaErrorKind<E>) -> Self {
+        Self(value.to_string())
+    }
+}
+
+impl From<std::io::Error> for PlotError {
+    fn from(value: std::io::Error) -> Self {
+        Self(value.to_string())
+    }
+}
+
+/// A named series of `(x, y)` points.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Series<'a> {
+    /// The name of the series, shown in the legend.
+    pub name: &'a str,
+    /// The x coordinates of the points.
+    pub x: &'a [f64],
+    /// The y coordinates of the points.
+    pub y: &'a [f64],
+}
+
+impl<'a> Series<'a> {
+    /// Creates a new [`Series`].
+    pub fn new(name: &'a str, x: &'a [f64], y: &'a [f64]) -> Self {
+        Self { name, x, y }
+    }
+}
+
+/// Options for rendering a plot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlotOptions {
+    /// The title of the plot.
+    pub title: Option<String>,
+    /// The label of the x axis.
+    pub x_label: String,
+    /// The label of the y axis.
+    pub y_label: String,
+    /// The size of the image, in pixels.
+    pub size: (u32, u32),
+}
+
+impl Default for PlotOptions {
+    fn default() -> Self {
+        Self {
+            title: None,
+            x_label: String::new(),
+            y_label: String::new(),
+            size: (1024, 768),
+        }
+    }
+}
+
+/// Returns the range spanned by `values`, padded so that no series lies on the plot border.
+fn axis_range<'a>(values: impl Iterator<Item = &'a f64>) -> std::ops::Range<f64> {
+    let (min, max) = values
+        .filter(|v| v.is_finite())
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| {
+            (min.min(*v), max.max(*v))
+        });
+    if min > max {
+        return 0.0..1.0;
+    }
+    let pad = if max > min {
+        0.05 * (max - min)
+    } else if min != 0. {
+        0.5 * min.abs()
+    } else {
+        0.5
+    };
+    (min - pad)..(max + pad)
+}
+
+fn draw<DB: DrawingBackend>(
+    root: DrawingArea<DB, Shift>,
+    series: &[Series<'_>],
+    opts: &PlotOptions,
+) -> Result<(), PlotError>
+where
+    DB::ErrorType: 'static,
+{
+    root.fill(&WHITE)?;
+    let x_range = axis_range(series.iter().flat_map(|s| s.x.iter()));
+    let y_range = axis_range(series.iter().flat_map(|s| s.y.iter()));
+
+    let mut builder = ChartBuilder::on(&root);
+    if let Some(title) = &opts.title {
+        builder.caption(title, ("sans-serif", 24));
+    }
+    let mut chart = builder
+        .margin(16)
+        .x_label_area_size(48)
+        .y_label_area_size(72)
+        .build_cartesian_2d(x_range, y_range)?;
+    chart
+        .configure_mesh()
+        .x_desc(&opts.x_label)
+        .y_desc(&opts.y_label)
+        .x_label_formatter(&|x| format!("{x:.3e}"))
+        .y_label_formatter(&|y| format!("{y:.3e}"))
+        .draw()?;
+
+    for (i, s) in series.iter().enumerate() {
+        let color = Palette99::pick(i);
+        chart
+            .draw_series(LineSeries::new(
+                s.x.iter().copied().zip(s.y.iter().copied()),
+                color.stroke_width(2),
+            ))?
+            .label(s.name)
+            .legend(move |(x, y)| {
+                PathElement::new(
+                    vec![(x, y), (x + 20, y)],
+                    Palette99::pick(i).stroke_width(2),
+                )
+            });
+    }
+    chart
+        .configure_series_labels()
+        .position(SeriesLabelPosition::UpperRight)
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()?;
+    root.present()?;
+    Ok(())
+}
+
+/// Plots the given series to the image at `path`.
+///
+/// The image is rendered as an SVG if `path` has an `svg` extension, and as a PNG otherwise.
+pub fn plot(
+    path: impl AsRef<Path>,
+    series: &[Series<'_>],
+    opts: &PlotOptions,
+) -> Result<(), PlotError> {
+    let path = path.as_ref();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    if path.extension().is_some_and(|ext| ext == "svg") {
+        draw(
+            SVGBackend::new(path, opts.size).into_drawing_area(),
+            series,
+            opts,
+        )
+    } else {
+        draw(
+            BitMapBackend::new(path, opts.size).into_drawing_area(),
+            series,
+            opts,
+        )
+    }
+}
@@ -0,0 +1,54 @@
+//! Simulation progress reporting.
+//!
+//! Simulator plugins may report partial results while a simulation is running.
+//! Callers can use these reports to monitor long simulations or to stop a
+//! simulation early once the result of a measurement is already determined.
+
+use std::fmt::Debug;
+use std::sync::Arc;
+
+/// Progress reported by a running simulation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Progress {
+    /// The name of the analysis in progress, as reported by the simulator.
+    pub analysis: Option<String>,
+    /// The fraction of the analysis that has completed, between 0 and 1.
+    pub fraction: f64,
+    /// The current simulation time in seconds, for time-domain analyses.
+    pub time: Option<f64>,
+}
+
+/// Indicates whether a simulation should continue after reporting progress.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum ProgressAction {
+    /// Continue the simulation.
+    #[default]
+    Continue,
+    /// Stop the simulation as soon as possible.
+    ///
+    /// Simulators that support early stopping return an error
+    /// instead of the outputs of the stopped simulation.
+    Stop,
+}
+
+/// A callback invoked each time a simulator reports progress.
+#[derive(Clone)]
+pub struct ProgressCallback(Arc<dyn Fn(&Progress) -> ProgressAction + Send + Sync>);
+
+impl ProgressCallback {
+    /// Creates a new [`ProgressCallback`] from the given function.
+    pub fn new(f: impl Fn(&Progress) -> ProgressAction + Send + Sync + 'static) -> Self {
+        Self(Arc::new(f))
+    }
+
+    /// Invokes the callback.
+    pub fn call(&self, progress: &Progress) -> ProgressAction {
+        (self.0)(progress)
+    }
+}
+
+impl Debug for ProgressCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProgressCallback").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,225 @@
+//! Real and complex sampled signal data.
+//!
+//! Time-domain analyses produce real-valued samples, while frequency-domain analyses
+//! (such as AC, S-parameter, and noise analyses) produce complex-valued samples.
+//! [`Samples`] represents either kind so that simulator plugins can share a common
+//! representation of saved signals.
+
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+use num::complex::Complex64;
+
+/// The sampled values of a single saved signal.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Samples {
+    /// Real-valued samples.
+    Real(Arc<Vec<f64>>),
+    /// Complex-valued samples.
+    Complex(Arc<Vec<Complex64>>),
+}
+
+impl Samples {
+    /// Returns the number of samples.
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Real(x) => x.len(),
+            Self::Complex(x) => x.len(),
+        }
+    }
+
+    /// Returns `true` if there are no samples.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the samples are real-valued.
+    pub fn is_real(&self) -> bool {
+        matches!(self, Self::Real(_))
+    }
+
+    /// Returns `true` if the samples are complex-valued.
+    pub fn is_complex(&self) -> bool {
+        matches!(self, Self::Complex(_))
+    }
+
+    /// Returns the real-valued samples, or [`None`] if the samples are complex.
+    pub fn as_real(&self) -> Option<&[f64]> {
+        match self {
+            Self::Real(x) => Some(x),
+            Self::Complex(_) => None,
+        }
+    }
+
+    /// Returns the complex-valued samples, or [`None`] if the samples are real.
+    pub fn as_complex(&self) -> Option<&[Complex64]> {
+        match self {
+            Self::Real(_) => None,
+            Self::Complex(x) => Some(x),
+        }
+    }
+
+    /// Returns the samples as complex numbers.
+    ///
+    /// Real samples are converted to complex numbers with zero imaginary part.
+    pub fn to_complex(&self) -> Vec<Complex64> {
+        match self {
+            Self::Real(x) => x.iter().map(|&x| Complex64::new(x, 0.)).collect(),
+            Self::Complex(x) => x.to_vec(),
+        }
+    }
+
+    /// Returns the magnitude of each sample.
+    pub fn magnitude(&self) -> Vec<f64> {
+        match self {
+            Self::Real(x) => x.iter().map(|x| x.abs()).collect(),
+            Self::Complex(x) => x.iter().map(|x| x.norm()).collect(),
+        }
+    }
+
+    /// Returns the magnitude of each sample in decibels (`20 * log10(|x|)`).
+    pub fn magnitude_db(&self) -> Vec<f64> {
+        self.magnitude()
+            .into_iter()
+            .map(|x| 20. * x.log10())
+            .collect()
+    }
+
+    /// Returns the phase of each sample in radians, in the range `(-pi, pi]`.
+    pub fn phase(&self) -> Vec<f64> {
+        match self {
+            Self::Real(x) => x.iter().map(|&x| if x < 0. { PI } else { 0. }).collect(),
+            Self::Complex(x) => x.iter().map(|x| x.arg()).collect(),
+        }
+    }
+
+    /// Returns the phase of each sample in degrees, in the range `(-180, 180]`.
+    pub fn phase_deg(&self) -> Vec<f64> {
+        self.phase().into_iter().map(f64::to_degrees).collect()
+    }
+
+    /// Returns the phase of each sample in radians, unwrapped so that
+    /// consecutive samples never differ by more than `pi`.
+    pub fn unwrapped_phase(&self) -> Vec<f64> {
+        let mut phase = self.phase();
+        let mut offset = 0.;
+        for i in 1..phase.len() {
+            let wrapped = phase[i] + offset;
+            let delta = wrapped - phase[i - 1];
+            if delta > PI {
+                offset -= 2. * PI * ((delta + PI) / (2. * PI)).floor();
+            } else if delta < -PI {
+                offset += 2. * PI * ((-delta + PI) / (2. * PI)).floor();
+            }
+            phase[i] += offset;
+        }
+        phase
+    }
+
+    /// Returns the group delay in seconds at each of the given frequencies (in Hz).
+    ///
+    /// The group delay is `-d(phase)/d(omega)`, computed from the unwrapped phase using
+    /// central differences, or one-sided differences at the ends of the sweep.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `freq` does not have the same length as the samples.
+    pub fn group_delay(&self, freq: &[f64]) -> Vec<f64> {
+        assert_eq!(
+            freq.len(),
+            self.len(),
+            "frequency vector length must equal the number of samples"
+        );
+        let phase = self.unwrapped_phase();
+        let n = phase.len();
+        if n < 2 {
+            return vec![0.; n];
+        }
+        (0..n)
+            .map(|i| {
+                let (lo, hi) = (i.saturating_sub(1), std::cmp::min(i + 1, n - 1));
+                -(phase[hi] - phase[lo]) / (2. * PI * (freq[hi] - freq[lo]))
+            })
+            .collect()
+    }
+}
+
+impl From<Vec<f64>> for Samples {
+    fn from(value: Vec<f64>) -> Self {
+        Self::Real(Arc::new(value))
+    }
+}
+
+impl From<Arc<Vec<f64>>> for Samples {
+    fn from(value: Arc<Vec<f64>>) -> Self {
+        Self::Real(value)
+    }
+}
+
+impl From<Vec<Complex64>> for Samples {
+    fn from(value: Vec<Complex64>) -> Self {
+        Self::Complex(Arc::new(value))
+    }
+}
+
+impl From<Arc<Vec<Complex64>>> for Samples {
+    fn from(value: Arc<Vec<Complex64>>) -> Self {
+        Self::Complex(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    #[test]
+    fn complex_samples_are_converted() {
+        let x = Samples::from(vec![
+            Complex64::new(10., 0.),
+            Complex64::new(0., 1.),
+            Complex64::new(-0.1, 0.),
+        ]);
+        assert!(x.is_complex());
+        assert_eq!(x.len(), 3);
+
+        let db = x.magnitude_db();
+        assert_relative_eq!(db[0], 20.);
+        assert_relative_eq!(db[1], 0.);
+        assert_relative_eq!(db[2], -20.);
+
+        let phase = x.phase_deg();
+        assert_relative_eq!(phase[0], 0.);
+        assert_relative_eq!(phase[1], 90.);
+        assert_relative_eq!(phase[2], 180.);
+
+        let real = Samples::from(vec![1., -2.]);
+        assert_eq!(
+            real.to_complex(),
+            vec![Complex64::new(1., 0.), Complex64::new(-2., 0.)]
+        );
+        assert_eq!(real.magnitude(), vec![1., 2.]);
+    }
+
+    #[test]
+    fn group_delay_of_pure_delay() {
+        // A pure delay of `tau` has phase `-2 * pi * f * tau`, which wraps several times
+        // over the sweep.
+        let tau = 1e-9;
+        let freq = (0..50).map(|i| i as f64 * 1e8).collect::<Vec<_>>();
+        let x = Samples::from(
+            freq.iter()
+                .map(|f| Complex64::from_polar(1., -2. * PI * f * tau))
+                .collect::<Vec<_>>(),
+        );
+
+        let phase = x.unwrapped_phase();
+        for (p, f) in phase.iter().zip(freq.iter()) {
+            assert_relative_eq!(*p, -2. * PI * f * tau, epsilon = 1e-9);
+        }
+        for delay in x.group_delay(&freq) {
+            assert_relative_eq!(delay, tau, epsilon = 1e-15);
+        }
+    }
+}
@@ -0,0 +1,383 @@
+//! Pseudo-random stimulus generation for transient simulations.
+//!
+//! A [`BitStimulus`] generates a sequence of bits, either from a [PRBS](Prbs) generator
+//! or from a seeded random number generator, and converts it to a piecewise linear
+//! [`Waveform`] with configurable levels, rise/fall times, and edge jitter.
+//! The resulting waveform can be used with any simulator's PWL source.
+//!
+//! All randomness is derived from an explicit seed, so a stimulus produces
+//! identical waveforms (and therefore identical netlists) every time it is generated.
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use super::waveform::{TimeWaveform, Waveform};
+
+/// A standard pseudo-random binary sequence.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Prbs {
+    /// PRBS7, with polynomial `x^7 + x^6 + 1`.
+    Prbs7,
+    /// PRBS9, with polynomial `x^9 + x^5 + 1`.
+    Prbs9,
+    /// PRBS15, with polynomial `x^15 + x^14 + 1`.
+    Prbs15,
+    /// PRBS23, with polynomial `x^23 + x^18 + 1`.
+    Prbs23,
+    /// PRBS31, with polynomial `x^31 + x^28 + 1`.
+    Prbs31,
+}
+
+impl Prbs {
+    /// The exponents of the two nonzero, non-constant terms of the generator polynomial.
+    fn taps(&self) -> (u32, u32) {
+        match self {
+            Prbs::Prbs7 => (7, 6),
+            Prbs::Prbs9 => (9, 5),
+            Prbs::Prbs15 => (15, 14),
+            Prbs::Prbs23 => (23, 18),
+            Prbs::Prbs31 => (31, 28),
+        }
+    }
+
+    /// The number of bits before the sequence repeats.
+    pub fn period(&self) -> usize {
+        (1usize << self.taps().0) - 1
+    }
+
+    /// Returns an infinite iterator over the bits of this sequence.
+    ///
+    /// The shift register is initialized from the low bits of `seed`.
+    /// Since the all-zero state is not allowed, a seed whose low bits are all zero
+    /// is replaced by the all-ones state.
+    pub fn bits(&self, seed: u64) -> PrbsBits {
+        let (order, tap) = self.taps();
+        let mask = (1u64 << order) - 1;
+        let state = match seed & mask {
+            0 => mask,
+            state => state,
+        };
+        PrbsBits {
+            state,
+            mask,
+            order,
+            tap,
+        }
+    }
+}
+
+/// An iterator over the bits of a [`Prbs`] sequence.
+///
+/// Created by [`Prbs::bits`].
+#[derive(Debug, Clone)]
+pub struct PrbsBits {
+    state: u64,
+    mask: u64,
+    order: u32,
+    tap: u32,
+}
+
+impl Iterator for PrbsBits {
+    type Item = bool;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bit = ((self.state >> (self.order - 1)) ^ (self.state >> (self.tap - 1))) & 1;
+        self.state = ((self.state << 1) | bit) & self.mask;
+        Some(bit == 1)
+    }
+}
+
+/// The increment of the [`StimulusRng`] state per generated number.
+const GOLDEN_GAMMA: u64 = 0x9e3779b97f4a7c15;
+
+/// A small, deterministic pseudo-random number generator (SplitMix64).
+///
+/// Used instead of an external RNG so that generated stimuli do not change
+/// across dependency upgrades.
+#[derive(Debug, Clone)]
+pub struct StimulusRng {
+    state: u64,
+}
+
+impl StimulusRng {
+    /// Creates a new generator with the given seed.
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Skips the next `n` pseudo-random 64-bit integers in constant time.
+    pub fn advance(&mut self, n: u64) {
+        self.state = self.state.wrapping_add(n.wrapping_mul(GOLDEN_GAMMA));
+    }
+
+    /// Returns the next pseudo-random 64-bit integer.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(GOLDEN_GAMMA);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a pseudo-random number uniformly distributed in `[0, 1)`.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Returns `true` with probability `p`.
+    pub fn next_bool(&mut self, p: f64) -> bool {
+        self.next_f64() < p
+    }
+}
+
+/// The source of the bits in a [`BitStimulus`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum BitPattern {
+    /// A pseudo-random binary sequence.
+    Prbs(Prbs),
+    /// Independent random bits, each of which is `true` with the given probability.
+    Random {
+        /// The probability that each bit is `true`.
+        density: f64,
+    },
+    /// A fixed sequence of bits, repeated as necessary.
+    Fixed(Vec<bool>),
+}
+
+/// A seeded, pseudo-random digital stimulus.
+///
+/// Bit `i` nominally begins at `delay + i * period`. When consecutive bits differ,
+/// the waveform transitions linearly over the rise or fall time, starting at the
+/// nominal bit boundary offset by a random jitter. The waveform starts at time 0
+/// at the level of the first bit.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BitStimulus {
+    pattern: BitPattern,
+    seed: u64,
+    len: usize,
+    max_run_length: Option<usize>,
+    period: f64,
+    delay: f64,
+    v_lo: f64,
+    v_hi: f64,
+    rise: f64,
+    fall: f64,
+    jitter: f64,
+}
+
+impl BitStimulus {
+    /// Creates a stimulus of `len` bits with the given pattern and bit period.
+    ///
+    /// By default, the seed is 0, bits swing from 0 to 1, rise and fall times are 5% of the
+    /// bit period, and there is no delay, jitter, or run length constraint.
+    pub fn new(pattern: BitPattern, len: usize, period: f64) -> Self {
+        Self {
+            pattern,
+            seed: 0,
+            len,
+            max_run_length: None,
+            period,
+            delay: 0.,
+            v_lo: 0.,
+            v_hi: 1.,
+            rise: period / 20.,
+            fall: period / 20.,
+            jitter: 0.,
+        }
+    }
+
+    /// Sets the seed used for random bits, PRBS initialization, and jitter.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Sets the voltages representing logical low and logical high.
+    pub fn levels(mut self, v_lo: f64, v_hi: f64) -> Self {
+        self.v_lo = v_lo;
+        self.v_hi = v_hi;
+        self
+    }
+
+    /// Sets the rise and fall times.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either time is not positive.
+    pub fn transition(mut self, rise: f64, fall: f64) -> Self {
+        assert!(
+            rise > 0. && fall > 0.,
+            "rise and fall times must be positive"
+        );
+        self.rise = rise;
+        self.fall = fall;
+        self
+    }
+
+    /// Sets the time before the first bit boundary.
+    pub fn delay(mut self, delay: f64) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Sets the peak jitter of each edge.
+    ///
+    /// Each transition starts at a random offset, uniformly distributed in
+    /// `[-jitter, jitter]`, from its nominal bit boundary. The jitter is limited
+    /// so that transitions never overlap: it is at most 49% of the time
+    /// remaining in a bit period after the slower transition.
+    pub fn jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Limits the number of consecutive identical bits.
+    ///
+    /// Any bit that would extend a run beyond `max_run_length` is inverted.
+    /// Applies to all patterns, so constrained PRBS sequences are no longer
+    /// standard PRBS sequences.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_run_length` is 0.
+    pub fn max_run_length(mut self, max_run_length: usize) -> Self {
+        assert!(max_run_length > 0, "maximum run length must be positive");
+        self.max_run_length = Some(max_run_length);
+        self
+    }
+
+    /// The total duration of the stimulus, excluding jitter.
+    pub fn duration(&self) -> f64 {
+        self.delay + self.len as f64 * self.period
+    }
+
+    /// Generates the bits of this stimulus.
+    pub fn bits(&self) -> Vec<bool> {
+        let raw: Box<dyn Iterator<Item = bool>> = match &self.pattern {
+            BitPattern::Prbs(prbs) => Box::new(prbs.bits(self.seed)),
+            BitPattern::Random { density } => {
+                let mut rng = StimulusRng::new(self.seed);
+                let density = *density;
+                Box::new(std::iter::repeat_with(move || rng.next_bool(density)))
+            }
+            BitPattern::Fixed(bits) => {
+                assert!(!bits.is_empty(), "fixed bit pattern must not be empty");
+                Box::new(bits.iter().copied().cycle())
+            }
+        };
+
+        let mut bits = Vec::with_capacity(self.len);
+        let mut run = 0;
+        for mut bit in raw.take(self.len) {
+            if let Some(&prev) = bits.last() {
+                if bit == prev {
+                    run += 1;
+                    if self.max_run_length.is_some_and(|max| run > max) {
+                        bit = !bit;
+                        run = 1;
+                    }
+                } else {
+                    run = 1;
+                }
+            } else {
+                run = 1;
+            }
+            bits.push(bit);
+        }
+        bits
+    }
+
+    /// Generates a piecewise linear waveform for this stimulus.
+    pub fn waveform(&self) -> Waveform<f64> {
+        let bits = self.bits();
+        let level = |bit: bool| if bit { self.v_hi } else { self.v_lo };
+        // Jitter is drawn from a separate stream so that it does not perturb random bits.
+        let mut rng = StimulusRng::new(!self.seed);
+        let max_jitter = (0.49 * (self.period - self.rise.max(self.fall))).max(0.);
+        let jitter = self.jitter.min(max_jitter);
+
+        let mut waveform = Waveform::new();
+        let Some(&first) = bits.first() else {
+            return waveform;
+        };
+        waveform.push(0., level(first));
+        for (i, pair) in bits.windows(2).enumerate() {
+            let (prev, next) = (pair[0], pair[1]);
+            if prev == next {
+                continue;
+            }
+            let offset = jitter * (2. * rng.next_f64() - 1.);
+            let start = self.delay + (i + 1) as f64 * self.period + offset;
+            let duration = if next { self.rise } else { self.fall };
+            waveform.push(start, level(prev));
+            waveform.push(start + duration, level(next));
+        }
+        let end = self.duration();
+        if waveform.last_t().is_none_or(|t| t < end) {
+            waveform.push(end, level(*bits.last().unwrap()));
+        }
+        waveform
+    }
+
+    /// Generates a piecewise linear waveform for this stimulus with [`Decimal`] values,
+    /// suitable for simulator PWL sources.
+    ///
+    /// Values are rounded to 15 decimal places.
+    pub fn pwl(&self) -> Waveform<Decimal> {
+        self.waveform()
+            .values()
+            .map(|pt| (to_decimal(pt.t()), to_decimal(pt.x())))
+            .collect()
+    }
+}
+
+fn to_decimal(x: f64) -> Decimal {
+    Decimal::try_from(x)
+        .expect("stimulus values should be finite")
+        .round_dp(15)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prbs7_has_full_period() {
+        let bits = Prbs::Prbs7.bits(1).take(2 * 127).collect::<Vec<_>>();
+        assert_eq!(bits[..127], bits[127..]);
+        assert_eq!(bits[..127].iter().filter(|&&b| b).count(), 64);
+        for period in 1..127 {
+            assert_ne!(bits[..127 - period], bits[period..127]);
+        }
+    }
+
+    #[test]
+    fn bit_stimulus_is_deterministic_and_constrained() {
+        let stim = BitStimulus::new(BitPattern::Random { density: 0.9 }, 200, 1e-9)
+            .seed(42)
+            .max_run_length(3);
+        let bits = stim.bits();
+        assert_eq!(bits, stim.clone().bits());
+        assert_ne!(bits, stim.clone().seed(43).bits());
+        assert!(bits.windows(4).all(|w| !w.iter().all(|&b| b == w[0])));
+    }
+
+    #[test]
+    fn bit_stimulus_waveform_has_jittered_edges() {
+        let stim = BitStimulus::new(BitPattern::Fixed(vec![false, true]), 4, 1e-9)
+            .levels(0., 1.8)
+            .transition(1e-10, 2e-10)
+            .jitter(1e-10)
+            .seed(7);
+        let wav = stim.waveform();
+        assert_eq!(wav.first_x(), Some(0.));
+        assert_eq!(wav.last_t(), Some(4e-9));
+        let edges = wav.edges(0.9).collect::<Vec<_>>();
+        assert_eq!(edges.len(), 3);
+        for (i, edge) in edges.iter().enumerate() {
+            let nominal = (i + 1) as f64 * 1e-9;
+            assert!((edge.t() - nominal).abs() < 2.1e-10);
+        }
+        assert_eq!(stim.pwl().len(), wav.len());
+    }
+}
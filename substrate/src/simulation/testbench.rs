@@ -0,0 +1,183 @@
+//! Automatically generated testbenches.
+//!
+//! A [`DutTestbench`] wraps a device under test (DUT) in a testbench cell,
+//! attaching sources to its supplies and inputs and loads to its outputs.
+//! This avoids writing a dedicated testbench block for simple characterization tasks.
+
+use std::sync::Arc;
+
+use arcstr::ArcStr;
+
+use crate::block::Block;
+use crate::error::{Error, Result};
+use crate::schematic::{CellBuilder, Instance, NestedData, Schematic};
+use crate::types::schematic::{IoNodeBundle, Node};
+use crate::types::{Direction, Flatten, HasBundleKind, HasNameTree, TestbenchIo, TwoTerminalIo};
+
+/// A testbench that wraps a device under test (DUT).
+///
+/// DUT ports are identified by their flattened names (e.g. `pwr_vdd` for the `vdd`
+/// field of a `pwr` bundle). Each source and load is instantiated between the
+/// corresponding DUT port and the testbench ground.
+///
+/// `V` is the type of the sources used for supplies and stimuli,
+/// and `L` is the type of the loads. If no loads are added, `L` must be
+/// specified explicitly (e.g. `DutTestbench::<_, Vsource, Capacitor>::new(dut)`).
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct DutTestbench<T, V, L> {
+    dut: T,
+    grounds: Vec<ArcStr>,
+    supplies: Vec<(ArcStr, V)>,
+    stimuli: Vec<(ArcStr, V)>,
+    loads: Vec<(ArcStr, L)>,
+}
+
+/// Data exposed by a [`DutTestbench`].
+#[derive(NestedData)]
+pub struct DutTestbenchData<T: Schematic> {
+    /// The device under test.
+    ///
+    /// Use the IO and nested data of this instance to probe the DUT in simulation.
+    pub dut: Instance<T>,
+}
+
+impl<T, V, L> DutTestbench<T, V, L> {
+    /// Creates a new testbench wrapping `dut`, with no sources or loads.
+    pub fn new(dut: T) -> Self {
+        Self {
+            dut,
+            grounds: Vec::new(),
+            supplies: Vec::new(),
+            stimuli: Vec::new(),
+            loads: Vec::new(),
+        }
+    }
+
+    /// Connects the given DUT port directly to the testbench ground.
+    pub fn ground(mut self, port: impl Into<ArcStr>) -> Self {
+        self.grounds.push(port.into());
+        self
+    }
+
+    /// Drives the given DUT supply port with `source`.
+    pub fn supply(mut self, port: impl Into<ArcStr>, source: V) -> Self {
+        self.supplies.push((port.into(), source));
+        self
+    }
+
+    /// Drives the given DUT input port with `source`.
+    ///
+    /// The port must be an input or inout.
+    pub fn stimulus(mut self, port: impl Into<ArcStr>, source: V) -> Self {
+        self.stimuli.push((port.into(), source));
+        self
+    }
+
+    /// Attaches `load` to the given DUT output port.
+    ///
+    /// The port must be an output or inout.
+    pub fn load(mut self, port: impl Into<ArcStr>, load: L) -> Self {
+        self.loads.push((port.into(), load));
+        self
+    }
+
+    /// The device under test.
+    pub fn dut(&self) -> &T {
+        &self.dut
+    }
+}
+
+impl<T: Block, V: Block, L: Block> Block for DutTestbench<T, V, L> {
+    type Io = TestbenchIo;
+
+    fn name(&self) -> ArcStr {
+        arcstr::format!("{}_tb", self.dut.name())
+    }
+
+    fn io(&self) -> Self::Io {
+        Default::default()
+    }
+}
+
+fn testbench_error(msg: String) -> Error {
+    Error::Anyhow(Arc::new(anyhow::anyhow!(msg)))
+}
+
+impl<T, V, L> Schematic for DutTestbench<T, V, L>
+where
+    T: Schematic + Clone,
+    V: Schematic<Schema = T::Schema> + Block<Io = TwoTerminalIo> + Clone,
+    L: Schematic<Schema = T::Schema> + Block<Io = TwoTerminalIo> + Clone,
+{
+    type Schema = T::Schema;
+    type NestedData = DutTestbenchData<T>;
+
+    fn schematic(
+        &self,
+        io: &IoNodeBundle<Self>,
+        cell: &mut CellBuilder<<Self as Schematic>::Schema>,
+    ) -> Result<Self::NestedData> {
+        let dut = cell.instantiate_named(self.dut.clone(), "xdut");
+
+        let ports = self.dut.io();
+        let names = ports
+            .kind()
+            .flat_names(None)
+            .into_iter()
+            .map(|name| arcstr::format!("{name}"))
+            .collect::<Vec<_>>();
+        let directions = Flatten::<Direction>::flatten_vec(&ports);
+        let nodes = Flatten::<Node>::flatten_vec(dut.io());
+
+        let port = |name: &ArcStr, allowed: &[Direction]| -> Result<Node> {
+            let idx = names.iter().position(|n| n == name).ok_or_else(|| {
+                testbench_error(format!(
+                    "`{}` has no port named `{name}` (ports: {})",
+                    self.dut.name(),
+                    names.join(", ")
+                ))
+            })?;
+            if !allowed.contains(&directions[idx]) {
+                return Err(testbench_error(format!(
+                    "port `{name}` of `{}` has direction {:?}, expected one of {allowed:?}",
+                    self.dut.name(),
+                    directions[idx]
+                )));
+            }
+            Ok(nodes[idx])
+        };
+
+        const ANY: &[Direction] = &[Direction::Input, Direction::Output, Direction::InOut];
+        for name in self.grounds.iter() {
+            cell.connect(port(name, ANY)?, io.vss);
+        }
+
+        let sources = self
+            .supplies
+            .iter()
+            .map(|(name, source)| (name, source, "supply", ANY))
+            .chain(self.stimuli.iter().map(|(name, source)| {
+                (
+                    name,
+                    source,
+                    "stimulus",
+                    &[Direction::Input, Direction::InOut][..],
+                )
+            }));
+        for (name, source, kind, allowed) in sources {
+            let node = port(name, allowed)?;
+            let inst = cell.instantiate_named(source.clone(), arcstr::format!("x{kind}_{name}"));
+            cell.connect(inst.io().p, node);
+            cell.connect(inst.io().n, io.vss);
+        }
+
+        for (name, load) in self.loads.iter() {
+            let node = port(name, &[Direction::Output, Direction::InOut])?;
+            let inst = cell.instantiate_named(load.clone(), arcstr::format!("xload_{name}"));
+            cell.connect(inst.io().p, node);
+            cell.connect(inst.io().n, io.vss);
+        }
+
+        Ok(DutTestbenchData { dut })
+    }
+}
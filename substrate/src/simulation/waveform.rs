@@ -738,6 +738,63 @@ impl<T> Transition<T> {
     }
 }
 
+/// Samples `wav` at time `t`, returning [`None`] if `t` is outside the time range of `wav`.
+fn sample_within<W>(wav: &W, t: W::Data) -> Option<W::Data>
+where
+    W: TimeWaveform,
+{
+    let first = wav.first()?;
+    let last = wav.last()?;
+    if t < first.t() || t > last.t() {
+        None
+    } else if t == last.t() {
+        Some(last.x())
+    } else {
+        Some(wav.sample_at(t))
+    }
+}
+
+/// Combines two waveforms point by point, sampled at the time points of `p`.
+///
+/// `n` is linearly interpolated at each time point of `p`.
+/// Time points of `p` outside the time range of `n` are skipped.
+fn combine<P, N>(
+    p: &P,
+    n: &N,
+    f: impl Fn(P::Data, P::Data) -> P::Data,
+) -> Waveform<<P as TimeWaveform>::Data>
+where
+    P: TimeWaveform,
+    N: TimeWaveform<Data = P::Data>,
+{
+    p.values()
+        .filter_map(|pt| Some((pt.t(), f(pt.x(), sample_within(n, pt.t())?))))
+        .collect()
+}
+
+/// Returns the differential waveform `p - n`, sampled at the time points of `p`.
+///
+/// `n` is linearly interpolated at each time point of `p`.
+/// Time points of `p` outside the time range of `n` are skipped.
+pub fn differential<P, N>(p: &P, n: &N) -> Waveform<<P as TimeWaveform>::Data>
+where
+    P: TimeWaveform,
+    N: TimeWaveform<Data = P::Data>,
+{
+    combine(p, n, |p, n| p - n)
+}
+
+/// Returns the common-mode waveform `(p + n) / 2`, sampled at the time points of `p`.
+///
+/// See [`differential`] for how the waveforms are aligned.
+pub fn common_mode<P, N>(p: &P, n: &N) -> Waveform<<P as TimeWaveform>::Data>
+where
+    P: TimeWaveform,
+    N: TimeWaveform<Data = P::Data>,
+{
+    combine(p, n, |p, n| (p + n) / P::Data::from(2))
+}
+
 impl<'a, T> WaveformRef<'a, T> {
     /// Creates a new waveform referencing the given `t` and `x` data.
     ///
@@ -934,6 +991,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn waveform_differential_and_common_mode() {
+        let p = Waveform::from_iter([(0., 0.), (1., 1.), (2., 2.), (3., 1.)]);
+        let n = Waveform::from_iter([(0.5, 1.), (2.5, 0.)]);
+
+        let diff = differential(&p, &n);
+        assert_eq!(diff.len(), 2);
+        assert_relative_eq!(diff.get(0).unwrap().t(), 1.);
+        assert_relative_eq!(diff.get(0).unwrap().x(), 0.25);
+        assert_relative_eq!(diff.get(1).unwrap().t(), 2.);
+        assert_relative_eq!(diff.get(1).unwrap().x(), 1.75);
+
+        let cm = common_mode(&p, &n);
+        assert_eq!(cm.len(), 2);
+        assert_relative_eq!(cm.get(0).unwrap().x(), 0.875);
+        assert_relative_eq!(cm.get(1).unwrap().x(), 1.125);
+    }
+
     #[test]
     fn waveform_sample_at() {
         let wav =
@@ -0,0 +1,476 @@
+//! Yield estimation for pass/fail measurements.
+//!
+//! Three estimators are provided:
+//! - [`YieldEstimate::from_outcomes`] estimates the failure probability from plain
+//!   Monte Carlo outcomes, such as the iterations of a simulator's Monte Carlo analysis.
+//! - [`ImportanceSampler`] draws samples of normalized random variables from a shifted and
+//!   scaled distribution and reweights the outcomes, which concentrates simulations in the
+//!   failure region and makes rare failures measurable.
+//! - [`ScaledSigma`] extrapolates the failure probability at nominal variation from Monte Carlo
+//!   runs with inflated variation.
+//!
+//! All estimates report a confidence interval at a user-specified confidence level.
+
+use serde::{Deserialize, Serialize};
+
+use super::stimulus::StimulusRng;
+
+/// An estimate of the failure probability of a design.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct YieldEstimate {
+    /// The number of samples simulated.
+    pub samples: usize,
+    /// The number of samples that failed.
+    pub failures: usize,
+    /// The estimated probability of failure.
+    pub failure_probability: f64,
+    /// A two-sided confidence interval for the probability of failure.
+    pub interval: (f64, f64),
+    /// The confidence level of `interval` (e.g. 0.95).
+    pub confidence: f64,
+}
+
+impl YieldEstimate {
+    /// Estimates the failure probability from plain Monte Carlo outcomes.
+    ///
+    /// Each outcome is `true` if the corresponding sample passed.
+    /// The confidence interval is the Wilson score interval,
+    /// which remains accurate when few or no failures are observed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `confidence` is not strictly between 0 and 1.
+    pub fn from_outcomes(outcomes: impl IntoIterator<Item = bool>, confidence: f64) -> Self {
+        let z = z_score(confidence);
+        let (samples, failures) = outcomes
+            .into_iter()
+            .fold((0, 0), |(n, f), pass| (n + 1, f + usize::from(!pass)));
+        if samples == 0 {
+            return Self {
+                samples,
+                failures,
+                failure_probability: 0.,
+                interval: (0., 1.),
+                confidence,
+            };
+        }
+        let n = samples as f64;
+        let p = failures as f64 / n;
+        let denom = 1. + z * z / n;
+        let center = (p + z * z / (2. * n)) / denom;
+        let half = z / denom * (p * (1. - p) / n + z * z / (4. * n * n)).sqrt();
+        Self {
+            samples,
+            failures,
+            failure_probability: p,
+            interval: ((center - half).max(0.), (center + half).min(1.)),
+            confidence,
+        }
+    }
+
+    /// The estimated yield, i.e. the probability that the design passes.
+    pub fn yield_fraction(&self) -> f64 {
+        1. - self.failure_probability
+    }
+
+    /// The sigma level equivalent to the estimated failure probability.
+    ///
+    /// This is the number of standard deviations `k` such that a standard normal variable
+    /// exceeds `k` with the estimated failure probability. Returns infinity if no
+    /// failures were estimated.
+    pub fn sigma(&self) -> f64 {
+        if self.failure_probability <= 0. {
+            f64::INFINITY
+        } else {
+            -inverse_normal_cdf(self.failure_probability)
+        }
+    }
+}
+
+/// Draws importance samples of independent standard normal variables.
+///
+/// Samples are drawn from a normal distribution with the given mean shift and a common
+/// standard deviation scale, both expressed in units of the original standard deviation.
+/// Each outcome is weighted by the likelihood ratio between the original and sampling
+/// distributions, giving an unbiased estimate of the original failure probability.
+///
+/// Shifting the mean towards the failure region (e.g. towards a most probable failure
+/// point found by a previous run) is the most effective way to estimate rare failures.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportanceSampler {
+    shift: Vec<f64>,
+    scale: f64,
+    seed: u64,
+}
+
+impl ImportanceSampler {
+    /// Creates a sampler over `dims` variables with no shift and unit scale.
+    pub fn new(dims: usize) -> Self {
+        Self {
+            shift: vec![0.; dims],
+            scale: 1.,
+            seed: 0,
+        }
+    }
+
+    /// Sets the mean shift of each variable, in standard deviations.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of values does not match the number of variables.
+    pub fn shift(mut self, shift: Vec<f64>) -> Self {
+        assert_eq!(
+            shift.len(),
+            self.shift.len(),
+            "expected one shift per variable"
+        );
+        self.shift = shift;
+        self
+    }
+
+    /// Sets the standard deviation scale of the sampling distribution.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `scale` is not positive.
+    pub fn scale(mut self, scale: f64) -> Self {
+        assert!(scale > 0., "sampling scale must be positive");
+        self.scale = scale;
+        self
+    }
+
+    /// Sets the seed of the random number generator.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Returns sample `index` of the samples drawn by [`ImportanceSampler::estimate`].
+    ///
+    /// Samples are drawn from a single seeded stream, so any sample can be reproduced
+    /// in isolation, for example to rerun a failing sample.
+    pub fn sample(&self, index: usize) -> Vec<f64> {
+        let mut rng = StimulusRng::new(self.seed);
+        rng.advance((index * self.shift.len() * DRAWS_PER_NORMAL) as u64);
+        let mut x = vec![0.; self.shift.len()];
+        self.draw(&mut rng, &mut x);
+        x
+    }
+
+    /// Draws the next sample from `rng` into `x`.
+    ///
+    /// Returns the log of the likelihood ratio between the original and sampling distributions.
+    fn draw(&self, rng: &mut StimulusRng, x: &mut [f64]) -> f64 {
+        let mut log_weight = x.len() as f64 * self.scale.ln();
+        for (x, shift) in x.iter_mut().zip(self.shift.iter()) {
+            let u = standard_normal(rng);
+            *x = shift + self.scale * u;
+            log_weight += 0.5 * u * u - 0.5 * *x * *x;
+        }
+        log_weight
+    }
+
+    /// Draws `samples` samples and estimates the failure probability.
+    ///
+    /// `evaluate` receives a sample of the normalized variables (one value per variable,
+    /// in units of standard deviations from nominal) and returns `true` if the design passes.
+    /// The confidence interval is a normal approximation using the sample variance of
+    /// the weighted outcomes.
+    pub fn estimate<E>(
+        &self,
+        samples: usize,
+        confidence: f64,
+        mut evaluate: impl FnMut(&[f64]) -> Result<bool, E>,
+    ) -> Result<YieldEstimate, E> {
+        let z = z_score(confidence);
+        let mut rng = StimulusRng::new(self.seed);
+        let mut x = vec![0.; self.shift.len()];
+        let (mut failures, mut sum, mut sum_sq) = (0, 0., 0.);
+        for _ in 0..samples {
+            let log_weight = self.draw(&mut rng, &mut x);
+            if !evaluate(&x)? {
+                let weight = log_weight.exp();
+                failures += 1;
+                sum += weight;
+                sum_sq += weight * weight;
+            }
+        }
+        let n = samples.max(1) as f64;
+        let p = sum / n;
+        let var = (sum_sq / n - p * p).max(0.) / n;
+        let half = z * var.sqrt();
+        Ok(YieldEstimate {
+            samples,
+            failures,
+            failure_probability: p,
+            interval: ((p - half).max(0.), (p + half).min(1.)),
+            confidence,
+        })
+    }
+}
+
+/// Scaled-sigma sampling.
+///
+/// Given Monte Carlo estimates obtained with the standard deviation of all random variables
+/// inflated by each of several scale factors, fits the model `ln P(s) = a + b ln(s) - c / s^2` to the observed
+/// failure probabilities and extrapolates it to `s = 1`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScaledSigma {
+    /// The estimates at each scale factor.
+    pub points: Vec<(f64, YieldEstimate)>,
+}
+
+/// The result of a [`ScaledSigma`] extrapolation.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ScaledSigmaEstimate {
+    /// The extrapolated failure probability at nominal variation.
+    pub failure_probability: f64,
+    /// The interval obtained by extrapolating the bounds of the confidence intervals at
+    /// each scale factor.
+    ///
+    /// This reflects the sampling uncertainty of the inputs, but not the error of the model.
+    pub interval: (f64, f64),
+    /// The fitted model coefficients `(a, b, c)`.
+    pub coefficients: (f64, f64, f64),
+}
+
+impl ScaledSigma {
+    /// Creates a new scaled-sigma analysis from estimates at the given scale factors.
+    pub fn new(points: Vec<(f64, YieldEstimate)>) -> Self {
+        Self { points }
+    }
+
+    /// Extrapolates the failure probability to nominal variation.
+    ///
+    /// Returns [`None`] if fewer than three scale factors observed at least one failure,
+    /// or if the scale factors are not distinct enough to fit the model.
+    pub fn extrapolate(&self) -> Option<ScaledSigmaEstimate> {
+        let fit = |select: fn(&YieldEstimate) -> f64| {
+            let points = self
+                .points
+                .iter()
+                .filter(|(_, est)| est.failures > 0 && select(est) > 0.)
+                .map(|(s, est)| (*s, select(est).ln()))
+                .collect::<Vec<_>>();
+            fit_scaled_sigma(&points)
+        };
+        let coefficients = fit(|est| est.failure_probability)?;
+        let at_nominal = |(a, _, c): (f64, f64, f64)| (a - c).exp().min(1.);
+        let p = at_nominal(coefficients);
+        let lo = fit(|est| est.interval.0).map(at_nominal).unwrap_or(0.);
+        let hi = fit(|est| est.interval.1).map(at_nominal).unwrap_or(1.);
+        Some(ScaledSigmaEstimate {
+            failure_probability: p,
+            interval: (lo.min(p), hi.max(p)),
+            coefficients,
+        })
+    }
+}
+
+/// Least-squares fit of `y = a + b ln(s) - c / s^2` to `(s, y)` points.
+fn fit_scaled_sigma(points: &[(f64, f64)]) -> Option<(f64, f64, f64)> {
+    if points.len() < 3 {
+        return None;
+    }
+    // Normal equations of the linear least-squares problem.
+    let mut ata = [[0.; 3]; 3];
+    let mut atb = [0.; 3];
+    for &(s, y) in points {
+        let row = [1., s.ln(), -1. / (s * s)];
+        for ((ata_row, b), &ri) in ata.iter_mut().zip(atb.iter_mut()).zip(&row) {
+            for (a, &rj) in ata_row.iter_mut().zip(&row) {
+                *a += ri * rj;
+            }
+            *b += ri * y;
+        }
+    }
+    let [a, b, c] = solve3(ata, atb)?;
+    Some((a, b, c))
+}
+
+/// Solves a 3x3 linear system using Gaussian elimination with partial pivoting.
+fn solve3(mut m: [[f64; 3]; 3], mut v: [f64; 3]) -> Option<[f64; 3]> {
+    for col in 0..3 {
+        let pivot = (col..3).max_by(|&a, &b| m[a][col].abs().total_cmp(&m[b][col].abs()))?;
+        if m[pivot][col].abs() < 1e-12 {
+            return None;
+        }
+        m.swap(col, pivot);
+        v.swap(col, pivot);
+        let (pivot_row, pivot_v) = (m[col], v[col]);
+        for (m_row, v_row) in m.iter_mut().zip(v.iter_mut()).skip(col + 1) {
+            let factor = m_row[col] / pivot_row[col];
+            for (x, p) in m_row.iter_mut().zip(pivot_row).skip(col) {
+                *x -= factor * p;
+            }
+            *v_row -= factor * pivot_v;
+        }
+    }
+    let mut x = [0.; 3];
+    for row in (0..3).rev() {
+        let sum = (row + 1..3).map(|k| m[row][k] * x[k]).sum::<f64>();
+        x[row] = (v[row] - sum) / m[row][row];
+    }
+    Some(x)
+}
+
+/// The number of random numbers consumed by [`standard_normal`].
+const DRAWS_PER_NORMAL: usize = 2;
+
+/// Draws a standard normal sample using the Box-Muller transform.
+fn standard_normal(rng: &mut StimulusRng) -> f64 {
+    let u1 = 1. - rng.next_f64();
+    let u2 = rng.next_f64();
+    (-2. * u1.ln()).sqrt() * (2. * std::f64::consts::PI * u2).cos()
+}
+
+/// The two-sided z-score for the given confidence level.
+fn z_score(confidence: f64) -> f64 {
+    assert!(
+        confidence > 0. && confidence < 1.,
+        "confidence level must be strictly between 0 and 1"
+    );
+    inverse_normal_cdf(0.5 + confidence / 2.)
+}
+
+/// The inverse of the standard normal cumulative distribution function.
+///
+/// Uses Acklam's rational approximation, which has a relative error below `1.2e-9`.
+pub fn inverse_normal_cdf(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969683028665376e1,
+        2.209460984245205e2,
+        -2.759285104469687e2,
+        1.383577518672690e2,
+        -3.066479806614716e1,
+        2.506628277459239,
+    ];
+    const B: [f64; 5] = [
+        -5.447609879822406e1,
+        1.615858368580409e2,
+        -1.556989798598866e2,
+        6.680131188771972e1,
+        -1.328068155288572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784894002430293e-3,
+        -3.223964580411365e-1,
+        -2.400758277161838,
+        -2.549732539343734,
+        4.374664141464968,
+        2.938163982698783,
+    ];
+    const D: [f64; 4] = [
+        7.784695709041462e-3,
+        3.224671290700398e-1,
+        2.445134137142996,
+        3.754408661907416,
+    ];
+    const P_LOW: f64 = 0.02425;
+
+    if p <= 0. {
+        return f64::NEG_INFINITY;
+    }
+    if p >= 1. {
+        return f64::INFINITY;
+    }
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.)
+    };
+    if p < P_LOW {
+        tail((-2. * p.ln()).sqrt())
+    } else if p <= 1. - P_LOW {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.)
+    } else {
+        -tail((-2. * (1. - p).ln()).sqrt())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    #[test]
+    fn monte_carlo_estimate_has_wilson_interval() {
+        let outcomes = (0..1000).map(|i| i % 100 != 0);
+        let est = YieldEstimate::from_outcomes(outcomes, 0.95);
+        assert_eq!(est.failures, 10);
+        assert_relative_eq!(est.failure_probability, 0.01);
+        assert_relative_eq!(est.yield_fraction(), 0.99);
+        assert!(est.interval.0 < 0.01 && 0.01 < est.interval.1);
+        assert_relative_eq!(est.interval.0, 0.00544, epsilon = 1e-4);
+        assert_relative_eq!(est.interval.1, 0.01831, epsilon = 1e-4);
+
+        let none = YieldEstimate::from_outcomes([true; 100], 0.95);
+        assert_eq!(none.failure_probability, 0.);
+        assert!(none.interval.1 > 0.);
+        assert_eq!(none.sigma(), f64::INFINITY);
+    }
+
+    #[test]
+    fn importance_sampling_estimates_rare_failures() {
+        // A design that fails when a standard normal variable exceeds 4 sigma,
+        // which has a failure probability of about 3.17e-5.
+        let expected = 3.167e-5;
+        let est = ImportanceSampler::new(1)
+            .shift(vec![4.])
+            .seed(1)
+            .estimate(4000, 0.95, |x| Ok::<_, ()>(x[0] < 4.))
+            .unwrap();
+        assert_relative_eq!(est.failure_probability, expected, max_relative = 0.1);
+        assert!(est.interval.0 < expected && expected < est.interval.1);
+        assert_relative_eq!(est.sigma(), 4., epsilon = 0.05);
+    }
+
+    #[test]
+    fn importance_samples_can_be_reproduced() {
+        let sampler = ImportanceSampler::new(3).shift(vec![1., 2., 3.]).seed(5);
+        let mut samples = Vec::new();
+        sampler
+            .estimate(10, 0.95, |x| {
+                samples.push(x.to_vec());
+                Ok::<_, ()>(true)
+            })
+            .unwrap();
+        for (i, sample) in samples.iter().enumerate() {
+            assert_eq!(&sampler.sample(i), sample);
+        }
+    }
+
+    #[test]
+    fn scaled_sigma_extrapolates_to_nominal() {
+        // Exact failure probabilities of a 4-sigma threshold at each scale factor.
+        let points = [
+            (1.5, 3.830e-3),
+            (2.0, 2.275e-2),
+            (2.5, 5.480e-2),
+            (3.0, 9.121e-2),
+        ]
+        .into_iter()
+        .map(|(s, p)| {
+            let n = 1_000_000;
+            let failures = (p * n as f64).round() as usize;
+            let est = YieldEstimate::from_outcomes((0..n).map(|i| i >= failures), 0.95);
+            (s, est)
+        })
+        .collect();
+        let est = ScaledSigma::new(points).extrapolate().unwrap();
+        // The model is approximate, so the extrapolation is only accurate to within a small factor.
+        assert_relative_eq!(est.failure_probability, 3.167e-5, max_relative = 0.2);
+        assert!(est.interval.0 <= est.failure_probability);
+        assert!(est.failure_probability <= est.interval.1);
+    }
+
+    #[test]
+    fn inverse_normal_cdf_matches_known_quantiles() {
+        assert_relative_eq!(inverse_normal_cdf(0.975), 1.959964, epsilon = 1e-6);
+        assert_relative_eq!(inverse_normal_cdf(0.5), 0., epsilon = 1e-9);
+        assert_relative_eq!(inverse_normal_cdf(3.167e-5), -4., epsilon = 1e-3);
+    }
+}
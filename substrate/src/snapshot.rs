@@ -0,0 +1,287 @@
+//! Serializable snapshots of elaborated designs.
+//!
+//! A [`Snapshot`] bundles the SCIR library and LayIR library of an elaborated design,
+//! along with metadata, into a single versioned file. Snapshots can be written by one
+//! process (e.g. on a generation machine) and loaded by another (e.g. on a simulation or
+//! export machine) without access to the original generators.
+//!
+//! ```ignore
+//! ctx.export_snapshot(block)?.save("build/inverter.snapshot.json")?;
+//!
+//! // In another process:
+//! let snapshot = Snapshot::<Sky130Schema, Sky130Layer>::load("build/inverter.snapshot.json")?;
+//! Spice.write_scir_netlist_to_file(snapshot.schematic.as_ref().unwrap(), "inverter.sp", opts)?;
+//! ```
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+use arcstr::ArcStr;
+use indexmap::IndexMap;
+use scir::schema::Schema;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::value::RawValue;
+
+/// The version of the snapshot file format written by this version of Substrate.
+///
+/// Incremented whenever the format changes in a way that older versions cannot read.
+pub const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+
+/// The identifier stored in every snapshot file.
+const SNAPSHOT_FORMAT: &str = "substrate-snapshot";
+
+/// An error saving or loading a [`Snapshot`].
+#[derive(thiserror::Error, Debug, Clone)]
+pub enum SnapshotError {
+    /// An I/O error.
+    #[error("I/O error: {0}")]
+    Io(#[from] Arc<std::io::Error>),
+    /// The snapshot could not be serialized or deserialized.
+    #[error("error encoding snapshot: {0}")]
+    Encoding(#[from] Arc<serde_json::Error>),
+    /// The file is not a Substrate snapshot.
+    #[error("file is not a Substrate snapshot")]
+    InvalidFormat {
+        /// The error encountered decoding the snapshot header, if the header was malformed.
+        #[source]
+        source: Option<Arc<serde_json::Error>>,
+    },
+    /// The snapshot was written with an unsupported format version.
+    #[error("unsupported snapshot format version {found} (expected {expected})")]
+    UnsupportedVersion {
+        /// The version of the snapshot file.
+        found: u32,
+        /// The version supported by this version of Substrate.
+        expected: u32,
+    },
+}
+
+impl From<std::io::Error> for SnapshotError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(Arc::new(value))
+    }
+}
+
+impl From<serde_json::Error> for SnapshotError {
+    fn from(value: serde_json::Error) -> Self {
+        Self::Encoding(Arc::new(value))
+    }
+}
+
+/// Metadata describing a [`Snapshot`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotMetadata {
+    /// The name of the top cell of the design, if any.
+    pub top: Option<ArcStr>,
+    /// The version of Substrate that created the snapshot.
+    pub substrate_version: ArcStr,
+    /// Arbitrary user-defined properties.
+    pub properties: IndexMap<ArcStr, ArcStr>,
+}
+
+impl SnapshotMetadata {
+    /// Creates metadata for a snapshot of the design with top cell `top`.
+    pub fn new(top: impl Into<ArcStr>) -> Self {
+        Self {
+            top: Some(top.into()),
+            ..Default::default()
+        }
+    }
+}
+
+/// A serializable snapshot of an elaborated design.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "S::Primitive: Serialize, L: Serialize",
+    deserialize = "S::Primitive: DeserializeOwned, L: DeserializeOwned"
+))]
+pub struct Snapshot<S: Schema + ?Sized, L> {
+    /// Metadata describing the snapshot.
+    pub metadata: SnapshotMetadata,
+    /// The SCIR library of the design, if any.
+    pub schematic: Option<scir::Library<S>>,
+    /// The LayIR library of the design, if any.
+    pub layout: Option<layir::Library<L>>,
+}
+
+/// The on-disk representation of a snapshot.
+#[derive(Serialize)]
+struct SnapshotFileRef<'a, T> {
+    format: &'a str,
+    version: u32,
+    snapshot: &'a T,
+}
+
+/// The header of a snapshot file, used to check compatibility before decoding the snapshot.
+///
+/// The snapshot itself is kept as raw JSON, so that it is only decoded once
+/// the header has been checked.
+#[derive(Deserialize)]
+struct SnapshotHeader<'a> {
+    format: String,
+    version: u32,
+    #[serde(borrow)]
+    snapshot: &'a RawValue,
+}
+
+impl<S: Schema + ?Sized, L> Snapshot<S, L> {
+    /// Creates an empty snapshot with the given metadata.
+    ///
+    /// The Substrate version in `metadata` is set to the current version of Substrate.
+    pub fn new(mut metadata: SnapshotMetadata) -> Self {
+        metadata.substrate_version = arcstr::literal!(env!("CARGO_PKG_VERSION"));
+        Self {
+            metadata,
+            schematic: None,
+            layout: None,
+        }
+    }
+
+    /// Sets the SCIR library of the snapshot.
+    pub fn with_schematic(mut self, schematic: scir::Library<S>) -> Self {
+        self.schematic = Some(schematic);
+        self
+    }
+
+    /// Sets the LayIR library of the snapshot.
+    pub fn with_layout(mut self, layout: layir::Library<L>) -> Self {
+        self.layout = Some(layout);
+        self
+    }
+}
+
+impl<S: Schema + ?Sized, L: Serialize> Snapshot<S, L>
+where
+    S::Primitive: Serialize,
+{
+    /// Writes the snapshot to `writer`.
+    pub fn write(&self, writer: impl Write) -> Result<(), SnapshotError> {
+        serde_json::to_writer(
+            writer,
+            &SnapshotFileRef {
+                format: SNAPSHOT_FORMAT,
+                version: SNAPSHOT_FORMAT_VERSION,
+                snapshot: self,
+            },
+        )?;
+        Ok(())
+    }
+
+    /// Writes the snapshot to a file at `path`, creating parent directories if necessary.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), SnapshotError> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+impl<S: Schema + ?Sized, L: DeserializeOwned> Snapshot<S, L>
+where
+    S::Primitive: DeserializeOwned,
+{
+    /// Reads a snapshot from `reader`.
+    ///
+    /// Returns an error if the snapshot was written with a different format version.
+    /// The SCIR library is validated after it is read.
+    pub fn read(reader: impl std::io::Read) -> Result<Self, SnapshotError> {
+        let json = std::io::read_to_string(reader)?;
+        let header: SnapshotHeader =
+            serde_json::from_str(&json).map_err(|err| SnapshotError::InvalidFormat {
+                source: Some(Arc::new(err)),
+            })?;
+        if header.format != SNAPSHOT_FORMAT {
+            return Err(SnapshotError::InvalidFormat { source: None });
+        }
+        if header.version != SNAPSHOT_FORMAT_VERSION {
+            return Err(SnapshotError::UnsupportedVersion {
+                found: header.version,
+                expected: SNAPSHOT_FORMAT_VERSION,
+            });
+        }
+        Ok(serde_json::from_str(header.snapshot.get())?)
+    }
+
+    /// Reads a snapshot from the file at `path`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SnapshotError> {
+        Self::read(BufReader::new(File::open(path)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geometry::rect::Rect;
+    use layir::{Cell, LibraryBuilder, Shape};
+    use scir::schema::StringSchema;
+
+    use super::*;
+    use crate::tests::get_path;
+
+    fn snapshot() -> Snapshot<StringSchema, u8> {
+        let mut scir = scir::LibraryBuilder::<StringSchema>::new();
+        let mut cell = scir::Cell::new("top");
+        let vdd = cell.add_node("vdd");
+        cell.expose_port(vdd, scir::Direction::InOut);
+        let top = scir.add_cell(cell);
+        scir.set_top(top);
+
+        let mut layout = LibraryBuilder::new();
+        let mut cell = Cell::new("top");
+        cell.add_element(Shape::new(1, Rect::from_sides(0, 0, 100, 200)));
+        layout.add_cell(cell);
+
+        let mut metadata = SnapshotMetadata::new("top");
+        metadata
+            .properties
+            .insert(arcstr::literal!("corner"), arcstr::literal!("tt"));
+        Snapshot::new(metadata)
+            .with_schematic(scir.build().unwrap())
+            .with_layout(layout.build().unwrap())
+    }
+
+    #[test]
+    fn snapshot_round_trip() {
+        let path = get_path("snapshot_round_trip", "design.snapshot.json");
+        let snapshot = snapshot();
+        snapshot.save(&path).unwrap();
+
+        let loaded = Snapshot::<StringSchema, u8>::load(&path).unwrap();
+        assert_eq!(loaded.metadata, snapshot.metadata);
+        assert_eq!(loaded.metadata.substrate_version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(loaded.layout, snapshot.layout);
+        let scir = loaded.schematic.unwrap();
+        let top = scir.top_cell().unwrap();
+        assert_eq!(scir.cell(top).name(), "top");
+    }
+
+    #[test]
+    fn snapshot_rejects_other_versions() {
+        let mut bytes = Vec::new();
+        snapshot().write(&mut bytes).unwrap();
+        let json = String::from_utf8(bytes).unwrap().replacen(
+            &format!("\"version\":{SNAPSHOT_FORMAT_VERSION}"),
+            "\"version\":999",
+            1,
+        );
+        assert!(matches!(
+            Snapshot::<StringSchema, u8>::read(json.as_bytes()),
+            Err(SnapshotError::UnsupportedVersion { found: 999, .. })
+        ));
+        assert!(matches!(
+            Snapshot::<StringSchema, u8>::read("{}".as_bytes()),
+            Err(SnapshotError::InvalidFormat { source: Some(_) })
+        ));
+        assert!(matches!(
+            Snapshot::<StringSchema, u8>::read(
+                r#"{"format":"other","version":1,"snapshot":{}}"#.as_bytes()
+            ),
+            Err(SnapshotError::InvalidFormat { source: None })
+        ));
+    }
+}
@@ -1,8 +1,11 @@
 //! Built-in implementations of IO traits.
 
-use crate::types::codegen::HasDefaultLayoutBundle;
+use crate::schematic::schema::Schema;
+use crate::schematic::CellBuilder;
+use crate::simulation::waveform::{self, TimeWaveform, Waveform};
+use crate::types::codegen::{HasDefaultLayoutBundle, HasView, View};
 use schematic::{
-    HasNodeBundle, HasTerminalBundle, Node, NodeBundle, SchematicBundleKind, Terminal,
+    HasNodeBundle, HasSupplies, HasTerminalBundle, Node, NodeBundle, SchematicBundleKind, Terminal,
     TerminalBundle,
 };
 
@@ -480,6 +483,26 @@ impl From<usize> for NameBuf {
     }
 }
 
+impl SupplyKind {
+    /// The name of the global net to which supplies of this kind are connected
+    /// if their cell does not [set a rail](CellBuilder::set_rails) of this kind.
+    pub fn global_net(&self) -> &'static str {
+        match self {
+            Self::Power => "vdd!",
+            Self::Ground => "vss!",
+        }
+    }
+}
+
+impl Display for SupplyKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Power => write!(f, "power"),
+            Self::Ground => write!(f, "ground"),
+        }
+    }
+}
+
 impl Display for NameFragment {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -613,6 +636,163 @@ impl<T: HasBundleKind> ArrayBundle<T> {
         }
         Self { kind, elems }
     }
+
+    /// Returns an iterator over the elements of the array.
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.elems.iter()
+    }
+
+    /// Returns a new array bundle containing the elements in `range`.
+    ///
+    /// Unlike indexing with a range, which returns a slice, the returned bundle
+    /// can be passed directly to [`CellBuilder::connect`](crate::schematic::CellBuilder::connect).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds.
+    pub fn slice<R>(&self, range: R) -> Self
+    where
+        T: Clone,
+        R: SliceIndex<[T], Output = [T]>,
+    {
+        Self {
+            elems: self.elems[range].to_vec(),
+            kind: self.kind.clone(),
+        }
+    }
+
+    /// Returns a new array bundle containing the elements of `self` followed by the elements of `other`.
+    ///
+    /// See [`concat`] for concatenating more than two bundles.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the two bundles have elements of different kinds.
+    pub fn concat(&self, other: &Self) -> Self
+    where
+        T: Clone,
+    {
+        concat([self, other])
+    }
+}
+
+/// Concatenates the given array bundles, in order, into a single array bundle.
+///
+/// The first bundle's elements are placed at the lowest indices of the result.
+///
+/// # Panics
+///
+/// Panics if `bundles` is empty or if the bundles have elements of different kinds.
+pub fn concat<'a, T>(bundles: impl IntoIterator<Item = &'a ArrayBundle<T>>) -> ArrayBundle<T>
+where
+    T: HasBundleKind + Clone + 'a,
+{
+    let mut bundles = bundles.into_iter();
+    let first = bundles
+        .next()
+        .expect("must concatenate at least one array bundle");
+    let mut elems = first.elems.clone();
+    for bundle in bundles {
+        assert_eq!(
+            first.kind, bundle.kind,
+            "cannot concatenate array bundles with elements of different kinds"
+        );
+        elems.extend(bundle.elems.iter().cloned());
+    }
+    ArrayBundle {
+        elems,
+        kind: first.kind.clone(),
+    }
+}
+
+impl<V> DiffView<V>
+where
+    InOut<Signal>: HasView<V>,
+{
+    /// Returns this pair with its polarity reversed.
+    ///
+    /// The positive signal of the returned pair is the negative signal of `self`, and vice versa.
+    pub fn flip(self) -> Self {
+        Self {
+            p: self.n,
+            n: self.p,
+        }
+    }
+}
+
+impl<V> DiffView<V>
+where
+    InOut<Signal>: HasView<V>,
+    View<InOut<Signal>, V>: Flatten<Node> + HasBundleKind<BundleKind = Signal> + Clone,
+{
+    /// Connects both signals of this pair to `node`.
+    ///
+    /// Useful for tying a differential input to a common-mode bias node.
+    #[track_caller]
+    pub fn connect_common_mode<S, N>(&self, cell: &mut CellBuilder<S>, node: N)
+    where
+        S: Schema + ?Sized,
+        N: Flatten<Node> + HasBundleKind<BundleKind = Signal> + Clone,
+    {
+        cell.connect(self.p.clone(), node.clone());
+        cell.connect(self.n.clone(), node);
+    }
+}
+
+impl<V> DiffView<V>
+where
+    InOut<Signal>: HasView<V>,
+    View<InOut<Signal>, V>: TimeWaveform,
+{
+    /// Returns the differential waveform `p - n` of a saved pair of signals.
+    ///
+    /// See [`waveform::differential`] for how the waveforms are aligned.
+    /// To compute differential currents from saved terminals, pass the saved currents
+    /// of each terminal to [`waveform::differential`] directly.
+    pub fn differential(&self) -> Waveform<<View<InOut<Signal>, V> as TimeWaveform>::Data> {
+        waveform::differential(&self.p, &self.n)
+    }
+
+    /// Returns the common-mode waveform `(p + n) / 2` of a saved pair of signals.
+    ///
+    /// See [`waveform::differential`] for how the waveforms are aligned.
+    pub fn common_mode(&self) -> Waveform<<View<InOut<Signal>, V> as TimeWaveform>::Data> {
+        waveform::common_mode(&self.p, &self.n)
+    }
+}
+
+impl<V> HasSupplies for PowerIoView<V>
+where
+    InOut<Signal>: HasView<V>,
+    View<InOut<Signal>, V>: Flatten<Node>,
+{
+    fn supplies(&self) -> Vec<(SupplyKind, Node)> {
+        self.vdd
+            .flatten_vec()
+            .into_iter()
+            .map(|node| (SupplyKind::Power, node))
+            .chain(
+                self.vss
+                    .flatten_vec()
+                    .into_iter()
+                    .map(|node| (SupplyKind::Ground, node)),
+            )
+            .collect()
+    }
+}
+
+impl<V> HasSupplies for TestbenchIoView<V>
+where
+    InOut<Signal>: HasView<V>,
+    View<InOut<Signal>, V>: Flatten<Node>,
+{
+    fn supplies(&self) -> Vec<(SupplyKind, Node)> {
+        self.vss
+            .flatten_vec()
+            .into_iter()
+            .map(|node| (SupplyKind::Ground, node))
+            .collect()
+    }
 }
 
 #[cfg(test)]
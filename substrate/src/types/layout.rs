@@ -10,7 +10,7 @@ use geometry::prelude::{Bbox, Transformation};
 use geometry::rect::Rect;
 use geometry::transform::{TransformRef, TranslateRef};
 use geometry::union::BoundingUnion;
-use layir::Shape;
+use layir::{Shape, Text};
 use std::collections::HashMap;
 use tracing::Level;
 
@@ -26,6 +26,11 @@ pub struct PortGeometry<L> {
     pub unnamed_shapes: Vec<Shape<L>>,
     /// A set of named shapes contained by the port.
     pub named_shapes: HashMap<ArcStr, Shape<L>>,
+    /// Text labels attached to the port.
+    ///
+    /// If empty, each shape of the port is labeled with the port's name at its center
+    /// when the port is exported.
+    pub labels: Vec<Text<L>>,
 }
 
 impl<L> PortGeometry<L> {
@@ -35,9 +40,23 @@ impl<L> PortGeometry<L> {
             primary: primary.into(),
             unnamed_shapes: Default::default(),
             named_shapes: Default::default(),
+            labels: Default::default(),
         }
     }
 
+    /// Attaches a text label to the port.
+    ///
+    /// The label's layer, position, and orientation are exported as given.
+    /// Its text should usually be the name of the port.
+    pub fn add_label(&mut self, label: Text<L>) {
+        self.labels.push(label);
+    }
+
+    /// Returns an iterator over the text labels attached to a [`PortGeometry`].
+    pub fn labels(&self) -> impl Iterator<Item = &Text<L>> {
+        self.labels.iter()
+    }
+
     /// Returns an iterator over all shapes in a [`PortGeometry`].
     pub fn shapes(&self) -> impl Iterator<Item = &Shape<L>> {
         std::iter::once(&self.primary)
@@ -58,6 +77,7 @@ impl<L> PortGeometry<L> {
                 self.unnamed_shapes.push(old_shape);
             }
         }
+        self.labels.extend(other.labels);
     }
 }
 
@@ -89,10 +109,15 @@ impl<L: Clone> TryFrom<layir::Port<L>> for PortGeometry<L> {
         let mut shapes = port.elements().filter_map(|elt| elt.get_shape().cloned());
         let primary = shapes.next().ok_or(LayoutError::EmptyPort)?;
         let unnamed_shapes = shapes.collect();
+        let labels = port
+            .elements()
+            .filter_map(|elt| elt.get_text().cloned())
+            .collect();
         Ok(PortGeometry {
             primary,
             unnamed_shapes,
             named_shapes: Default::default(),
+            labels,
         })
     }
 }
@@ -129,6 +154,7 @@ pub struct PortGeometryBuilder<L> {
     primary: Option<Shape<L>>,
     unnamed_shapes: Vec<Shape<L>>,
     named_shapes: HashMap<ArcStr, Shape<L>>,
+    labels: Vec<Text<L>>,
 }
 
 impl<L> Default for PortGeometryBuilder<L> {
@@ -137,6 +163,7 @@ impl<L> Default for PortGeometryBuilder<L> {
             primary: None,
             unnamed_shapes: Vec::new(),
             named_shapes: HashMap::new(),
+            labels: Vec::new(),
         }
     }
 }
@@ -176,9 +203,17 @@ impl<L> PortGeometryBuilder<L> {
             })?,
             unnamed_shapes: self.unnamed_shapes,
             named_shapes: self.named_shapes,
+            labels: self.labels,
         })
     }
 
+    /// Attaches a text label to the port.
+    ///
+    /// See [`PortGeometry::add_label`].
+    pub fn add_label(&mut self, label: Text<L>) {
+        self.labels.push(label);
+    }
+
     /// Merges [`PortGeometry`] `other` into `self`, overwriting the primary and corresponding named shapes
     /// and moving their old values to the collection of unnamed shapes.
     pub fn merge(&mut self, other: impl Into<PortGeometry<L>>) {
@@ -193,6 +228,7 @@ impl<L> PortGeometryBuilder<L> {
                 self.unnamed_shapes.push(old_shape);
             }
         }
+        self.labels.extend(other.labels);
     }
 
     /// Sets the primary shape of this port, moving the current primary
@@ -254,6 +290,7 @@ impl<L: Clone> Flatten<PortGeometry<L>> for Shape<L> {
             primary: self.clone(),
             unnamed_shapes: Vec::new(),
             named_shapes: HashMap::new(),
+            labels: Vec::new(),
         }));
     }
 }
@@ -283,6 +320,7 @@ impl<L: Clone> TranslateRef for PortGeometry<L> {
                 .iter()
                 .map(|(k, v)| (k.clone(), v.translate_ref(p)))
                 .collect(),
+            labels: self.labels.translate_ref(p),
         }
     }
 }
@@ -297,6 +335,7 @@ impl<L: Clone> TransformRef for PortGeometry<L> {
                 .iter()
                 .map(|(k, v)| (k.clone(), v.transform_ref(trans)))
                 .collect(),
+            labels: self.labels.transform_ref(trans),
         }
     }
 }
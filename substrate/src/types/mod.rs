@@ -15,6 +15,7 @@ use crate::{
     schematic::{CellId, InstanceId, InstancePath},
 };
 
+pub use impls::concat;
 pub use scir::Direction;
 
 #[doc(hidden)]
@@ -236,6 +237,15 @@ pub struct ArrayBundle<T: HasBundleKind> {
     kind: T::BundleKind,
 }
 
+/// The kind of a supply rail.
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum SupplyKind {
+    /// A positive power supply, such as VDD.
+    Power,
+    /// A ground or negative supply, such as VSS.
+    Ground,
+}
+
 // END TYPES
 
 // BEGIN COMMON IO TYPES
@@ -279,13 +289,29 @@ pub struct PowerIo {
 }
 
 /// A pair of differential signals.
+///
+/// Views of a [`Diff`] provide helpers for working with differential signals,
+/// such as [`DiffView::flip`] for reversing polarity and [`DiffView::differential`]
+/// for computing differential waveforms from simulation outputs.
 // TODO: Create proc macro for defining un-directioned (non-IO) bundle types directly.
 #[derive(Debug, Default, Copy, Clone, Io)]
-pub struct DiffPair {
+pub struct Diff {
     /// The positive signal.
     pub p: InOut<Signal>,
     /// The negative signal.
     pub n: InOut<Signal>,
 }
 
+/// A pair of differential signals.
+#[deprecated(note = "use `Diff` instead")]
+pub type DiffPair = Diff;
+
+/// The bundle kind of a [`DiffPair`].
+#[deprecated(note = "use `DiffKind` instead")]
+pub type DiffPairKind = DiffKind;
+
+/// A view of a [`DiffPair`].
+#[deprecated(note = "use `DiffView` instead")]
+pub type DiffPairView<V> = DiffView<V>;
+
 // END COMMON IO TYPES
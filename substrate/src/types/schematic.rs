@@ -10,7 +10,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashMap};
 use std::ops::Deref;
 
-use super::{Array, ArrayBundle, BundleKind, HasBundleKind, Io, Signal, Unflatten};
+use super::{Array, ArrayBundle, BundleKind, HasBundleKind, Io, Signal, SupplyKind, Unflatten};
 
 /// A type that has a bundle of nodes.
 pub trait HasNodeBundle: HasBundleKind + Sized + Send + Sync {
@@ -42,6 +42,16 @@ pub trait SchematicBundleKind: BundleKind + HasNodeBundle + HasTerminalBundle {
     ) -> TerminalBundle<Self>;
 }
 
+/// A bundle containing supply rails.
+///
+/// Implemented by the node and terminal bundles of supply IOs such as [`PowerIo`](super::PowerIo).
+/// See [`CellBuilder::set_rails`](crate::schematic::CellBuilder::set_rails) for how supplies
+/// are connected and checked.
+pub trait HasSupplies {
+    /// Returns the supply nodes in this bundle along with their kinds.
+    fn supplies(&self) -> Vec<(SupplyKind, Node)>;
+}
+
 /// A schematic bundle kind that can be viewed as another bundle kind `T`.
 pub trait DataView<T: SchematicBundleKind>: SchematicBundleKind {
     /// Views a node bundle as a node bundle of a different kind.
@@ -497,6 +507,10 @@ impl ena::unify::UnifyKey for Node {
 pub(crate) struct NodeContext {
     uf: NodeUf,
     connections_data: Vec<Option<NodeConnectionsData>>,
+    /// The location at which each node was created.
+    ///
+    /// IO nodes are not attributed to any location.
+    sources: Vec<Option<SourceInfo>>,
 }
 
 #[derive(Clone, Debug)]
@@ -564,6 +578,7 @@ impl NodeContext {
         Self {
             uf: Default::default(),
             connections_data: vec![],
+            sources: vec![],
         }
     }
 
@@ -587,6 +602,8 @@ impl NodeContext {
             usize::try_from(ena::unify::UnifyKey::index(&id)).unwrap(),
             self.connections_data.len()
         );
+        self.sources
+            .push((priority != NodePriority::Io).then(|| source_info.clone()));
         self.connections_data.push(Some(
             direction
                 .map(|direction| NodeConnectionsData::from_single(direction, source_info))
@@ -606,9 +623,11 @@ impl NodeContext {
         id
     }
 
+    /// Consumes the context, returning the union-find structure of its nodes
+    /// and the locations at which they were created, indexed by node.
     #[inline]
-    pub fn into_uf(self) -> NodeUf {
-        self.uf
+    pub(crate) fn into_parts(self) -> (NodeUf, Vec<Option<SourceInfo>>) {
+        (self.uf, self.sources)
     }
 
     fn nodes_directed(
@@ -0,0 +1,291 @@
+//! Typed physical quantities with SI prefixes.
+//!
+//! Simulation inputs are specified as [`Quantity`] values tagged with their unit,
+//! so that a frequency cannot accidentally be used where a time is expected.
+//! Quantities can be parsed from SPICE-style strings such as `"1.2n"` or `"10 MHz"`.
+
+use std::fmt::{Debug, Display, Formatter};
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+use derive_where::derive_where;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// A physical unit.
+pub trait Unit {
+    /// The symbol of the unit (e.g. `V` for volts).
+    const SYMBOL: &'static str;
+}
+
+/// Volts.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub struct Volts;
+
+impl Unit for Volts {
+    const SYMBOL: &'static str = "V";
+}
+
+/// Amperes.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub struct Amps;
+
+impl Unit for Amps {
+    const SYMBOL: &'static str = "A";
+}
+
+/// Seconds.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub struct Seconds;
+
+impl Unit for Seconds {
+    const SYMBOL: &'static str = "s";
+}
+
+/// Hertz.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub struct Hertz;
+
+impl Unit for Hertz {
+    const SYMBOL: &'static str = "Hz";
+}
+
+/// A quantity measured in unit `U`.
+///
+/// The value is stored in base units (e.g. seconds rather than nanoseconds).
+#[derive(Serialize, Deserialize)]
+#[derive_where(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(transparent)]
+pub struct Quantity<U> {
+    value: Decimal,
+    #[serde(skip)]
+    unit: PhantomData<fn() -> U>,
+}
+
+/// A voltage, in volts.
+pub type Voltage = Quantity<Volts>;
+/// A current, in amperes.
+pub type Current = Quantity<Amps>;
+/// A time, in seconds.
+pub type Time = Quantity<Seconds>;
+/// A frequency, in hertz.
+pub type Frequency = Quantity<Hertz>;
+
+/// SI prefixes and their corresponding powers of 10.
+const SI_PREFIXES: &[(&str, i32)] = &[
+    ("a", -18),
+    ("f", -15),
+    ("p", -12),
+    ("n", -9),
+    ("u", -6),
+    ("µ", -6),
+    ("m", -3),
+    ("", 0),
+    ("k", 3),
+    ("M", 6),
+    ("G", 9),
+    ("T", 12),
+];
+
+/// Returns `10^exp` as a [`Decimal`].
+fn pow10(exp: i32) -> Decimal {
+    if exp >= 0 {
+        Decimal::from(10i64.pow(exp as u32))
+    } else {
+        Decimal::new(1, (-exp) as u32)
+    }
+}
+
+impl<U> Quantity<U> {
+    /// Creates a new quantity with the given value, in base units.
+    ///
+    /// There is intentionally no conversion from a bare [`Decimal`], so that the unit of
+    /// every quantity is spelled out where it is created.
+    #[inline]
+    pub const fn new(value: Decimal) -> Self {
+        Self {
+            value,
+            unit: PhantomData,
+        }
+    }
+
+    /// Returns the value of this quantity, in base units.
+    #[inline]
+    pub const fn value(&self) -> Decimal {
+        self.value
+    }
+
+    /// Returns the value of this quantity, in base units, as an [`f64`].
+    pub fn to_f64(&self) -> f64 {
+        self.value.to_f64().unwrap()
+    }
+
+    /// Creates a quantity from an [`f64`] value, in base units.
+    ///
+    /// Returns [`None`] if `value` is not finite or is out of range.
+    pub fn from_f64(value: f64) -> Option<Self> {
+        Decimal::from_f64_retain(value).map(Self::new)
+    }
+}
+
+impl<U> From<Quantity<U>> for Decimal {
+    fn from(value: Quantity<U>) -> Self {
+        value.value
+    }
+}
+
+impl<U: Unit> Debug for Quantity<U> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self}")
+    }
+}
+
+impl<U: Unit> Display for Quantity<U> {
+    /// Formats the quantity using the largest SI prefix that keeps the mantissa at least 1,
+    /// followed by the unit symbol (e.g. `1.2ns`).
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let abs = self.value.abs();
+        let (prefix, exp) = SI_PREFIXES
+            .iter()
+            .filter(|(prefix, _)| *prefix != "µ")
+            .rev()
+            .find(|(_, exp)| abs >= pow10(*exp))
+            .filter(|_| !abs.is_zero())
+            .copied()
+            .unwrap_or(("", 0));
+        let mantissa = (self.value / pow10(exp)).normalize();
+        write!(f, "{mantissa}{prefix}{}", U::SYMBOL)
+    }
+}
+
+/// An error encountered while parsing a [`Quantity`].
+#[derive(Debug, Clone, Eq, PartialEq, thiserror::Error)]
+pub enum ParseQuantityError {
+    /// The numeric part of the quantity is invalid.
+    #[error("invalid number in quantity `{0}`")]
+    InvalidNumber(String),
+    /// The suffix of the quantity is not an SI prefix followed by the expected unit.
+    #[error("invalid suffix `{suffix}` in quantity `{input}` (expected unit {expected})")]
+    InvalidSuffix {
+        /// The string being parsed.
+        input: String,
+        /// The unrecognized suffix.
+        suffix: String,
+        /// The symbol of the expected unit.
+        expected: &'static str,
+    },
+}
+
+impl<U: Unit> FromStr for Quantity<U> {
+    type Err = ParseQuantityError;
+
+    /// Parses a quantity such as `1.2n`, `1.2ns`, `10 MHz`, `3.3`, or `1e-9`.
+    ///
+    /// Following SI conventions, `m` denotes milli and `M` denotes mega.
+    /// The SPICE-style `meg` suffix is also accepted for mega.
+    /// The unit symbol is optional, but must match `U` if present. Unit symbols are matched
+    /// case-insensitively (e.g. `3.3v`), except that a lone suffix that is also an SI prefix
+    /// is parsed as the prefix (e.g. `2a` is 2 attoamperes, not 2 amperes).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let input = s.trim();
+        let split = input
+            .char_indices()
+            .find(|&(i, c)| {
+                !(c.is_ascii_digit()
+                    || matches!(c, '.' | '+' | '-')
+                    || (matches!(c, 'e' | 'E')
+                        && input[i + 1..]
+                            .starts_with(|c: char| c.is_ascii_digit() || c == '+' || c == '-')))
+            })
+            .map(|(i, _)| i)
+            .unwrap_or(input.len());
+        let (number, suffix) = input.split_at(split);
+        let number = if number.contains(['e', 'E']) {
+            Decimal::from_scientific(number)
+        } else {
+            Decimal::from_str(number)
+        }
+        .map_err(|_| ParseQuantityError::InvalidNumber(input.to_string()))?;
+
+        let suffix = suffix.trim_start();
+        let invalid = || ParseQuantityError::InvalidSuffix {
+            input: input.to_string(),
+            suffix: suffix.to_string(),
+            expected: U::SYMBOL,
+        };
+        let is_unit = |rest: &str| rest.is_empty() || rest.eq_ignore_ascii_case(U::SYMBOL);
+
+        let exp = if suffix.is_empty() || suffix == U::SYMBOL {
+            0
+        } else if suffix
+            .get(..3)
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case("meg"))
+        {
+            if !is_unit(&suffix[3..]) {
+                return Err(invalid());
+            }
+            6
+        } else {
+            SI_PREFIXES
+                .iter()
+                .filter(|(prefix, _)| !prefix.is_empty())
+                .find(|(prefix, _)| {
+                    suffix
+                        .strip_prefix(prefix)
+                        .is_some_and(|rest| is_unit(rest))
+                })
+                .map(|(_, exp)| *exp)
+                // An unprefixed unit symbol in a different case (e.g. `3.3v`).
+                .or_else(|| suffix.eq_ignore_ascii_case(U::SYMBOL).then_some(0))
+                .ok_or_else(invalid)?
+        };
+
+        Ok(Self::new(number * pow10(exp)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    #[test]
+    fn quantities_are_parsed() {
+        assert_eq!("1.2n".parse::<Time>().unwrap().value(), dec!(1.2e-9));
+        assert_eq!("1.2ns".parse::<Time>().unwrap().value(), dec!(1.2e-9));
+        assert_eq!("1e-9".parse::<Time>().unwrap().value(), dec!(1e-9));
+        assert_eq!("3.3".parse::<Voltage>().unwrap().value(), dec!(3.3));
+        assert_eq!("3.3 V".parse::<Voltage>().unwrap().value(), dec!(3.3));
+        assert_eq!("3.3V".parse::<Voltage>().unwrap().value(), dec!(3.3));
+        assert_eq!("3.3v".parse::<Voltage>().unwrap().value(), dec!(3.3));
+        assert_eq!("3.3mv".parse::<Voltage>().unwrap().value(), dec!(0.0033));
+        assert_eq!("3.3mV".parse::<Voltage>().unwrap().value(), dec!(0.0033));
+        assert_eq!("1.2S".parse::<Time>().unwrap().value(), dec!(1.2));
+        assert_eq!("10hz".parse::<Frequency>().unwrap().value(), dec!(10));
+        assert_eq!("5m".parse::<Current>().unwrap().value(), dec!(0.005));
+        assert_eq!("2A".parse::<Current>().unwrap().value(), dec!(2));
+        assert_eq!("10 MHz".parse::<Frequency>().unwrap().value(), dec!(1e7));
+        assert_eq!("10meg".parse::<Frequency>().unwrap().value(), dec!(1e7));
+        assert_eq!("-2.5uV".parse::<Voltage>().unwrap().value(), dec!(-2.5e-6));
+
+        assert!(matches!(
+            "1.2nV".parse::<Time>(),
+            Err(ParseQuantityError::InvalidSuffix { .. })
+        ));
+        assert!(matches!(
+            "n".parse::<Time>(),
+            Err(ParseQuantityError::InvalidNumber(_))
+        ));
+    }
+
+    #[test]
+    fn quantities_are_displayed_with_si_prefixes() {
+        assert_eq!(Time::new(dec!(1.2e-9)).to_string(), "1.2ns");
+        assert_eq!(Frequency::new(dec!(1e7)).to_string(), "10MHz");
+        assert_eq!(Voltage::new(dec!(1.8)).to_string(), "1.8V");
+        assert_eq!(Current::new(dec!(-0.005)).to_string(), "-5mA");
+        assert_eq!(Voltage::new(dec!(0)).to_string(), "0V");
+    }
+}
@@ -0,0 +1,232 @@
+//! ngspice AC small-signal analysis options and data structures.
+
+use crate::tran::{CurrentSaveKey, Tran, VoltageSaveKey};
+use crate::Ngspice;
+use arcstr::ArcStr;
+use num::complex::Complex64;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::Arc;
+use substrate::simulation::analysis as common;
+use substrate::simulation::data::{Save, SaveFreq, SaveOutput, SignalLookupError};
+use substrate::simulation::samples::Samples;
+use substrate::simulation::{Analysis, SimulationContext, Simulator, SupportedBy};
+use substrate::types::schematic::{NestedNode, NestedTerminal};
+use substrate::units::Frequency;
+
+/// Frequency sweep kinds.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Sweep {
+    /// Linear sweep with the given total number of points.
+    Linear(usize),
+    /// Logarithmic sweep with the given number of points **per decade**.
+    Decade(usize),
+    /// Logarithmic sweep with the given number of points **per octave**.
+    Octave(usize),
+}
+
+impl From<common::Sweep> for Sweep {
+    fn from(value: common::Sweep) -> Self {
+        match value {
+            common::Sweep::Linear(n) => Self::Linear(n),
+            common::Sweep::Decade(n) => Self::Decade(n),
+        }
+    }
+}
+
+impl Sweep {
+    pub(crate) fn netlist<W: Write>(&self, out: &mut W) -> std::io::Result<()> {
+        match self {
+            Self::Linear(n) => write!(out, "lin {n}"),
+            Self::Decade(n) => write!(out, "dec {n}"),
+            Self::Octave(n) => write!(out, "oct {n}"),
+        }
+    }
+}
+
+/// An AC analysis.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Ac {
+    /// Start frequency.
+    pub start: Frequency,
+    /// Stop frequency.
+    pub stop: Frequency,
+    /// The sweep kind and number of points.
+    pub sweep: Sweep,
+}
+
+/// The result of an AC analysis.
+#[derive(Debug, Clone)]
+pub struct Output {
+    /// The frequency points of the AC simulation.
+    pub freq: Arc<Vec<f64>>,
+    /// A map from signal name to values.
+    pub raw_values: HashMap<ArcStr, Arc<Vec<Complex64>>>,
+    /// A map from a save ID to a raw value identifier.
+    pub(crate) saved_values: HashMap<u64, ArcStr>,
+}
+
+impl Output {
+    /// Returns the complex samples of the signal with the given raw (netlisted) name.
+    ///
+    /// If no such signal was saved, the returned error lists similarly named saved signals.
+    pub fn raw_samples(&self, name: &str) -> Result<Samples, SignalLookupError> {
+        let x = self
+            .raw_values
+            .get(name)
+            .ok_or_else(|| SignalLookupError::not_found(name, self.raw_values.keys()))?;
+        Ok(Samples::Complex(x.clone()))
+    }
+}
+
+impl Ac {
+    pub(crate) fn netlist<W: Write>(&self, out: &mut W) -> std::io::Result<()> {
+        write!(out, ".ac ")?;
+        self.sweep.netlist(out)?;
+        write!(out, " {} {}", self.start.value(), self.stop.value())
+    }
+}
+
+impl Analysis for Ac {
+    type Output = Output;
+}
+
+impl SupportedBy<Ngspice> for Ac {
+    fn into_input(self, inputs: &mut Vec<<Ngspice as Simulator>::Input>) {
+        inputs.push(self.into());
+    }
+    fn from_output(
+        outputs: &mut impl Iterator<Item = <Ngspice as Simulator>::Output>,
+    ) -> <Self as Analysis>::Output {
+        let item = outputs.next().unwrap();
+        item.try_into().unwrap()
+    }
+}
+
+impl Save<Ngspice, Ac> for SaveOutput {
+    type SaveKey = ();
+    type Saved = Output;
+
+    fn save(
+        &self,
+        _ctx: &SimulationContext<Ngspice>,
+        _opts: &mut <Ngspice as Simulator>::Options,
+    ) -> <Self as Save<Ngspice, Ac>>::SaveKey {
+    }
+
+    fn from_saved(
+        output: &<Ac as Analysis>::Output,
+        _key: &<Self as Save<Ngspice, Ac>>::SaveKey,
+    ) -> <Self as Save<Ngspice, Ac>>::Saved {
+        output.clone()
+    }
+}
+
+impl Save<Ngspice, Ac> for SaveFreq {
+    type SaveKey = ();
+    type Saved = Arc<Vec<f64>>;
+
+    fn save(
+        &self,
+        _ctx: &SimulationContext<Ngspice>,
+        _opts: &mut <Ngspice as Simulator>::Options,
+    ) -> <Self as Save<Ngspice, Ac>>::SaveKey {
+    }
+
+    fn from_saved(
+        output: &<Ac as Analysis>::Output,
+        _key: &<Self as Save<Ngspice, Ac>>::SaveKey,
+    ) -> <Self as Save<Ngspice, Ac>>::Saved {
+        output.freq.clone()
+    }
+}
+
+impl From<common::Ac> for Ac {
+    fn from(value: common::Ac) -> Self {
+        Self {
+            start: value.start,
+            stop: value.stop,
+            sweep: value.sweep.into(),
+        }
+    }
+}
+
+impl SupportedBy<Ngspice> for common::Ac {
+    fn into_input(self, inputs: &mut Vec<<Ngspice as Simulator>::Input>) {
+        Ac::from(self).into_input(inputs);
+    }
+    fn from_output(
+        outputs: &mut impl Iterator<Item = <Ngspice as Simulator>::Output>,
+    ) -> <Self as Analysis>::Output {
+        let output = Ac::from_output(outputs);
+        common::AcOutput {
+            freq: output.freq,
+            raw_values: output.raw_values,
+            saved_values: output.saved_values,
+        }
+    }
+}
+
+impl Save<Ngspice, common::Ac> for SaveOutput {
+    type SaveKey = ();
+    type Saved = common::AcOutput;
+
+    fn save(
+        &self,
+        _ctx: &SimulationContext<Ngspice>,
+        _opts: &mut <Ngspice as Simulator>::Options,
+    ) -> <Self as Save<Ngspice, common::Ac>>::SaveKey {
+    }
+
+    fn from_saved(
+        output: &<common::Ac as Analysis>::Output,
+        _key: &<Self as Save<Ngspice, common::Ac>>::SaveKey,
+    ) -> <Self as Save<Ngspice, common::Ac>>::Saved {
+        output.clone()
+    }
+}
+
+impl Save<Ngspice, common::Ac> for NestedNode {
+    type SaveKey = VoltageSaveKey;
+    type Saved = Arc<Vec<Complex64>>;
+
+    fn save(
+        &self,
+        ctx: &SimulationContext<Ngspice>,
+        opts: &mut <Ngspice as Simulator>::Options,
+    ) -> <Self as Save<Ngspice, common::Ac>>::SaveKey {
+        // ngspice saves are shared by all analyses.
+        <NestedNode as Save<Ngspice, Tran>>::save(self, ctx, opts)
+    }
+
+    fn from_saved(
+        output: &<common::Ac as Analysis>::Output,
+        key: &<Self as Save<Ngspice, common::Ac>>::SaveKey,
+    ) -> <Self as Save<Ngspice, common::Ac>>::Saved {
+        output.saved(key)
+    }
+}
+
+impl Save<Ngspice, common::Ac> for NestedTerminal {
+    type SaveKey = (VoltageSaveKey, CurrentSaveKey);
+    type Saved = common::AcTerminal;
+
+    fn save(
+        &self,
+        ctx: &SimulationContext<Ngspice>,
+        opts: &mut <Ngspice as Simulator>::Options,
+    ) -> <Self as Save<Ngspice, common::Ac>>::SaveKey {
+        <NestedTerminal as Save<Ngspice, Tran>>::save(self, ctx, opts)
+    }
+
+    fn from_saved(
+        output: &<common::Ac as Analysis>::Output,
+        key: &<Self as Save<Ngspice, common::Ac>>::SaveKey,
+    ) -> <Self as Save<Ngspice, common::Ac>>::Saved {
+        common::AcTerminal {
+            v: output.saved(&key.0),
+            i: output.saved(&key.1),
+        }
+    }
+}
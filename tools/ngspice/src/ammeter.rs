@@ -0,0 +1,144 @@
+//! Automatic insertion of 0V ammeters for saving subcircuit terminal currents.
+//!
+//! ngspice can only probe the terminal currents of top level instances. When
+//! [`Options::insert_ammeters`](crate::Options::insert_ammeters) is enabled, each terminal
+//! whose current is saved is instead connected to its net through a 0V voltage source
+//! by the netlister. The branch current of the voltage source is saved in place of the
+//! terminal current, so currents can be saved at any depth of the hierarchy.
+
+use std::collections::HashMap;
+
+use arcstr::ArcStr;
+use scir::{
+    ChildId, InstancePath, InstancePathElement, Library, NetlistLibConversion, SignalPathTail,
+    SliceOnePath,
+};
+use simulator_common::saves::SaveKeys;
+use spice::netlist::{Ammeter, NetlistOptions};
+
+use crate::error::{Error, Result};
+use crate::{Ngspice, ProbeStmt, SavedData};
+
+/// The ammeters in series with saved terminals.
+pub(crate) struct Ammeters {
+    /// The ammeters to insert while netlisting.
+    ammeters: Vec<Ammeter>,
+    /// A map from each saved terminal to the path of the cell containing its ammeter
+    /// and the name of the ammeter.
+    paths: HashMap<SliceOnePath, (InstancePath, ArcStr)>,
+}
+
+impl Ammeters {
+    /// Places an ammeter in series with each terminal whose current is saved in `saves`.
+    ///
+    /// Terminals of the same instance that are reached through different instances of
+    /// the parent cell share a single ammeter in the parent cell.
+    ///
+    /// Returns an error if a saved terminal does not exist in `lib`.
+    pub(crate) fn new(lib: &Library<Ngspice>, saves: &SaveKeys<SavedData>) -> Result<Self> {
+        let mut terminals = saves
+            .keys()
+            .filter_map(|save| match save {
+                SavedData::Probe(ProbeStmt::ScirCurrent(path)) => Some(path),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        // Sorting the terminals makes repeated netlist invocations
+        // assign the same names to the inserted ammeters.
+        terminals.sort();
+
+        let mut ammeters = Vec::new();
+        let mut inserted = HashMap::new();
+        let mut paths = HashMap::new();
+
+        for terminal in terminals {
+            let invalid = |reason: &str| {
+                Error::Ammeter(format!(
+                    "cannot measure the current of terminal {terminal:?}: {reason}"
+                ))
+            };
+
+            let annotated = lib.annotate_instance_path(terminal.instances().clone());
+            let (elem, parents) = annotated
+                .instances
+                .split_last()
+                .ok_or_else(|| invalid("only instance terminals can have their currents saved"))?;
+            let parent = match parents.last() {
+                Some(parent) => parent.child.and_then(ChildId::into_cell),
+                None => annotated.top.get_id().copied(),
+            }
+            .ok_or_else(|| invalid("instance path does not exist"))?;
+            let child = elem
+                .child
+                .ok_or_else(|| invalid("instance path does not exist"))?;
+            let port = match (child, terminal.tail()) {
+                (ChildId::Cell(id), SignalPathTail::Id(slice)) => lib
+                    .cell(id)
+                    .try_signal(slice.signal())
+                    .ok_or_else(|| invalid("terminal does not exist"))?
+                    .name
+                    .clone(),
+                (_, SignalPathTail::Name(slice)) => slice.signal().clone(),
+                (ChildId::Primitive(_), SignalPathTail::Id(_)) => {
+                    return Err(invalid("primitive terminals must be referenced by name"));
+                }
+            };
+            let index = terminal.tail().index().unwrap_or_default();
+            let instance = match &elem.elem {
+                InstancePathElement::Id(id) => lib.cell(parent).instance(*id).name().clone(),
+                InstancePathElement::Name(name) => name.clone(),
+            };
+
+            let key = (parent, instance.clone(), port.clone(), index);
+            let name = match inserted.get(&key) {
+                Some(name) => ArcStr::clone(name),
+                None => {
+                    let name = arcstr::format!("substrate_ammeter_{}", inserted.len());
+                    ammeters.push(Ammeter {
+                        cell: parent,
+                        instance,
+                        port,
+                        index,
+                        name: name.clone(),
+                    });
+                    inserted.insert(key, name.clone());
+                    name
+                }
+            };
+
+            let mut path = terminal.instances().clone();
+            path.pop();
+            paths.insert(terminal.clone(), (path, name));
+        }
+
+        Ok(Self { ammeters, paths })
+    }
+
+    /// Adds the ammeters to the given netlist options.
+    pub(crate) fn insert<'a>(&self, mut opts: NetlistOptions<'a>) -> NetlistOptions<'a> {
+        for ammeter in self.ammeters.iter() {
+            opts = opts.insert_ammeter(ammeter.clone());
+        }
+        opts
+    }
+
+    /// Returns the ngspice name of the branch current of the ammeter in series with
+    /// the terminal saved by `save`, if any.
+    pub(crate) fn current(
+        &self,
+        save: &SavedData,
+        lib: &Library<Ngspice>,
+        conv: &NetlistLibConversion,
+    ) -> Option<ArcStr> {
+        let SavedData::Probe(ProbeStmt::ScirCurrent(terminal)) = save else {
+            return None;
+        };
+        let (path, name) = self.paths.get(terminal)?;
+        let path = lib.convert_instance_path_with_conv(conv, path.clone());
+        Some(if path.is_empty() {
+            arcstr::format!("@v{name}[i]")
+        } else {
+            arcstr::format!("@v.{}.v{name}[i]", path.join("."))
+        })
+    }
+}
@@ -5,7 +5,9 @@ use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use substrate::block::Block;
 use substrate::schematic::{CellBuilder, PrimitiveBinding, Schematic};
+use substrate::simulation::options::TransientNoise;
 use substrate::types::TwoTerminalIo;
+use substrate::units::{Current, Time, Voltage};
 
 /// Data associated with a pulse [`Vsource`].
 #[derive(Serialize, Deserialize, Debug, Copy, Clone, Hash, PartialEq, Eq)]
@@ -15,32 +17,66 @@ pub struct Pulse {
     /// The one value of the pulse.
     pub val1: Decimal,
     /// The period of the pulse.
-    pub period: Option<Decimal>,
+    pub period: Option<Time>,
     /// Rise time.
-    pub rise: Option<Decimal>,
+    pub rise: Option<Time>,
     /// Fall time.
-    pub fall: Option<Decimal>,
+    pub fall: Option<Time>,
     /// The pulse width.
-    pub width: Option<Decimal>,
+    pub width: Option<Time>,
     /// Waveform delay.
-    pub delay: Option<Decimal>,
+    pub delay: Option<Time>,
     /// Number of pulses.
     pub num_pulses: Option<Decimal>,
 }
 
+/// Data associated with a transient noise [`Vsource`] or [`Isource`].
+///
+/// ngspice does not simulate device noise in transient analyses, so transient noise
+/// is injected by dedicated noise sources instead.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub struct TrNoise {
+    /// The rms amplitude of white noise.
+    pub rms: Decimal,
+    /// The time between noise samples.
+    pub step: Time,
+    /// The exponent of 1/f noise, between 0 and 2.
+    pub alpha: Option<Decimal>,
+    /// The amplitude of 1/f noise.
+    pub amp_1f: Option<Decimal>,
+}
+
+impl TrNoise {
+    /// Creates a white noise source with rms amplitude `rms`, band-limited and scaled
+    /// according to `noise`.
+    ///
+    /// Noise is sampled at twice [`TransientNoise::fmax`], and `rms` is multiplied by
+    /// [`TransientNoise::scale`] if set.
+    pub fn white(rms: Decimal, noise: &TransientNoise) -> Self {
+        Self {
+            rms: rms * noise.scale.unwrap_or(Decimal::ONE),
+            step: Time::new(Decimal::ONE / (Decimal::TWO * noise.fmax())),
+            alpha: None,
+            amp_1f: None,
+        }
+    }
+}
+
 /// A voltage source.
 #[derive(Serialize, Deserialize, Debug, Copy, Clone, Hash, PartialEq, Eq, Block)]
 #[substrate(io = "TwoTerminalIo")]
 pub enum Vsource {
     /// A dc voltage source.
-    Dc(Decimal),
+    Dc(Voltage),
     /// A pulse voltage source.
     Pulse(Pulse),
+    /// A transient noise voltage source.
+    TrNoise(TrNoise),
 }
 
 impl Vsource {
     /// Creates a new DC voltage source.
-    pub fn dc(value: Decimal) -> Self {
+    pub fn dc(value: Voltage) -> Self {
         Self::Dc(value)
     }
 
@@ -48,6 +84,11 @@ impl Vsource {
     pub fn pulse(value: Pulse) -> Self {
         Self::Pulse(value)
     }
+
+    /// Creates a new transient noise voltage source.
+    pub fn trnoise(value: TrNoise) -> Self {
+        Self::TrNoise(value)
+    }
 }
 
 impl Schematic for Vsource {
@@ -71,14 +112,16 @@ impl Schematic for Vsource {
 #[substrate(io = "TwoTerminalIo")]
 pub enum Isource {
     /// A dc current source.
-    Dc(Decimal),
+    Dc(Current),
     /// A pulse current source.
     Pulse(Pulse),
+    /// A transient noise current source.
+    TrNoise(TrNoise),
 }
 
 impl Isource {
     /// Creates a new DC current source.
-    pub fn dc(value: Decimal) -> Self {
+    pub fn dc(value: Current) -> Self {
         Self::Dc(value)
     }
 
@@ -86,6 +129,11 @@ impl Isource {
     pub fn pulse(value: Pulse) -> Self {
         Self::Pulse(value)
     }
+
+    /// Creates a new transient noise current source.
+    pub fn trnoise(value: TrNoise) -> Self {
+        Self::TrNoise(value)
+    }
 }
 
 impl Schematic for Isource {
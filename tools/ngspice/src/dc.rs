@@ -0,0 +1,156 @@
+//! ngspice DC operating point analysis options and data structures.
+
+use crate::tran::{CurrentSaveKey, Tran, VoltageSaveKey};
+use crate::Ngspice;
+use arcstr::ArcStr;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Write;
+use substrate::simulation::analysis as common;
+use substrate::simulation::data::{Save, SaveOutput, SignalLookupError};
+use substrate::simulation::{Analysis, SimulationContext, Simulator, SupportedBy};
+use substrate::types::schematic::{NestedNode, NestedTerminal};
+
+/// A DC operating point analysis.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct DcOp;
+
+/// The result of a [`DcOp`] analysis.
+#[derive(Debug, Clone)]
+pub struct OpOutput {
+    /// A map from signal name to value.
+    pub raw_values: HashMap<ArcStr, f64>,
+    /// A map from a save ID to a raw value identifier.
+    pub(crate) saved_values: HashMap<u64, ArcStr>,
+}
+
+impl OpOutput {
+    /// Returns the value of the signal with the given raw (netlisted) name.
+    ///
+    /// If no such signal was saved, the returned error lists similarly named saved signals.
+    pub fn raw_value(&self, name: &str) -> Result<f64, SignalLookupError> {
+        self.raw_values
+            .get(name)
+            .copied()
+            .ok_or_else(|| SignalLookupError::not_found(name, self.raw_values.keys()))
+    }
+}
+
+impl DcOp {
+    pub(crate) fn netlist<W: Write>(&self, out: &mut W) -> std::io::Result<()> {
+        write!(out, ".op")
+    }
+}
+
+impl Analysis for DcOp {
+    type Output = OpOutput;
+}
+
+impl SupportedBy<Ngspice> for DcOp {
+    fn into_input(self, inputs: &mut Vec<<Ngspice as Simulator>::Input>) {
+        inputs.push(self.into());
+    }
+    fn from_output(
+        outputs: &mut impl Iterator<Item = <Ngspice as Simulator>::Output>,
+    ) -> <Self as Analysis>::Output {
+        let item = outputs.next().unwrap();
+        item.try_into().unwrap()
+    }
+}
+
+impl Save<Ngspice, DcOp> for SaveOutput {
+    type SaveKey = ();
+    type Saved = OpOutput;
+
+    fn save(
+        &self,
+        _ctx: &SimulationContext<Ngspice>,
+        _opts: &mut <Ngspice as Simulator>::Options,
+    ) -> <Self as Save<Ngspice, DcOp>>::SaveKey {
+    }
+
+    fn from_saved(
+        output: &<DcOp as Analysis>::Output,
+        _key: &<Self as Save<Ngspice, DcOp>>::SaveKey,
+    ) -> <Self as Save<Ngspice, DcOp>>::Saved {
+        output.clone()
+    }
+}
+
+impl SupportedBy<Ngspice> for common::Dc {
+    fn into_input(self, inputs: &mut Vec<<Ngspice as Simulator>::Input>) {
+        DcOp.into_input(inputs);
+    }
+    fn from_output(
+        outputs: &mut impl Iterator<Item = <Ngspice as Simulator>::Output>,
+    ) -> <Self as Analysis>::Output {
+        let output = DcOp::from_output(outputs);
+        common::DcOutput {
+            raw_values: output.raw_values,
+            saved_values: output.saved_values,
+        }
+    }
+}
+
+impl Save<Ngspice, common::Dc> for SaveOutput {
+    type SaveKey = ();
+    type Saved = common::DcOutput;
+
+    fn save(
+        &self,
+        _ctx: &SimulationContext<Ngspice>,
+        _opts: &mut <Ngspice as Simulator>::Options,
+    ) -> <Self as Save<Ngspice, common::Dc>>::SaveKey {
+    }
+
+    fn from_saved(
+        output: &<common::Dc as Analysis>::Output,
+        _key: &<Self as Save<Ngspice, common::Dc>>::SaveKey,
+    ) -> <Self as Save<Ngspice, common::Dc>>::Saved {
+        output.clone()
+    }
+}
+
+impl Save<Ngspice, common::Dc> for NestedNode {
+    type SaveKey = VoltageSaveKey;
+    type Saved = f64;
+
+    fn save(
+        &self,
+        ctx: &SimulationContext<Ngspice>,
+        opts: &mut <Ngspice as Simulator>::Options,
+    ) -> <Self as Save<Ngspice, common::Dc>>::SaveKey {
+        // ngspice saves are shared by all analyses.
+        <NestedNode as Save<Ngspice, Tran>>::save(self, ctx, opts)
+    }
+
+    fn from_saved(
+        output: &<common::Dc as Analysis>::Output,
+        key: &<Self as Save<Ngspice, common::Dc>>::SaveKey,
+    ) -> <Self as Save<Ngspice, common::Dc>>::Saved {
+        output.saved(key)
+    }
+}
+
+impl Save<Ngspice, common::Dc> for NestedTerminal {
+    type SaveKey = (VoltageSaveKey, CurrentSaveKey);
+    type Saved = common::DcTerminal;
+
+    fn save(
+        &self,
+        ctx: &SimulationContext<Ngspice>,
+        opts: &mut <Ngspice as Simulator>::Options,
+    ) -> <Self as Save<Ngspice, common::Dc>>::SaveKey {
+        <NestedTerminal as Save<Ngspice, Tran>>::save(self, ctx, opts)
+    }
+
+    fn from_saved(
+        output: &<common::Dc as Analysis>::Output,
+        key: &<Self as Save<Ngspice, common::Dc>>::SaveKey,
+    ) -> <Self as Save<Ngspice, common::Dc>>::Saved {
+        common::DcTerminal {
+            v: output.saved(&key.0),
+            i: output.saved(&key.1),
+        }
+    }
+}
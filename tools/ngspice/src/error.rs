@@ -2,6 +2,8 @@
 
 use std::sync::Arc;
 
+use substrate::simulation::discovery::DiscoveryError;
+use substrate::simulation::messages::SimulatorMessage;
 use thiserror::Error as ThisError;
 
 /// The result type returned by ngspice library functions.
@@ -19,9 +21,33 @@ pub enum Error {
     /// Error invoking ngspice.
     #[error("error running ngspice")]
     NgspiceError,
+    /// Error locating ngspice or determining its version.
+    #[error(transparent)]
+    Discovery(#[from] DiscoveryError),
+    /// ngspice reported errors during simulation.
+    ///
+    /// Names mentioned in each error are mapped back to SCIR and Substrate paths where possible.
+    #[error(
+        "ngspice simulation failed:\n{}",
+        .0.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n")
+    )]
+    SimulationFailed(Vec<SimulatorMessage>),
+    /// A simulation option cannot be honored by ngspice.
+    #[error("unsupported simulation option: {0}")]
+    UnsupportedOption(String),
+    /// A path referenced by an analysis does not exist in the simulated library,
+    /// or was not resolved before netlisting.
+    #[error("cannot resolve path referenced by analysis: {0}")]
+    UnresolvedPath(String),
+    /// A saved terminal current cannot be measured using an ammeter.
+    #[error("{0}")]
+    Ammeter(String),
     /// Error parsing output rawfile.
     #[error("error parsing output rawfile")]
     RawfileParse(#[from] nutlex::error::Error),
+    /// The output rawfile has no plot for the analysis at the given index.
+    #[error("output rawfile has no plot for analysis {0}")]
+    MissingPlot(usize),
     /// Error generating results.
     #[error("error generating ngspice results")]
     Generator(#[from] Arc<Error>),
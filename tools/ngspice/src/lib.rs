@@ -3,34 +3,58 @@
 
 use std::collections::{HashMap, HashSet};
 use std::io::Write;
-#[cfg(any(unix, target_os = "redox"))]
-use std::os::unix::prelude::PermissionsExt;
 use std::path::PathBuf;
 use std::sync::Arc;
 
-use crate::blocks::Vsource;
+use crate::ac::Ac;
+use crate::ammeter::Ammeters;
+use crate::blocks::{TrNoise, Vsource};
+use crate::dc::DcOp;
+use crate::noise::Noise;
+use crate::sens::{Sens, SensValue};
 use crate::tran::Tran;
 use arcstr::ArcStr;
 use blocks::Isource;
 use cache::error::TryInnerError;
-use cache::CacheableWithState;
 use error::*;
-use nutlex::parser::Data;
+use num::complex::Complex64;
+use nutlex::parser::{Analysis as Plot, Data};
 use scir::schema::{FromSchema, NoSchema, NoSchemaError};
 use scir::{ChildId, Library, NetlistLibConversion, SignalInfo, SignalPathTail, SliceOnePath};
 use serde::{Deserialize, Serialize};
+use simulator_common::cached::run_cached;
+use simulator_common::decimate::decimate_tran;
+use simulator_common::paths::scir_node_path;
+use simulator_common::saves::SaveKeys;
+use simulator_common::script::{script_command, shell_quote};
 use spice::netlist::{
     HasSpiceLikeNetlist, Include, NetlistKind, NetlistOptions, NetlisterInstance, RenameGround,
 };
 use spice::Spice;
 use substrate::context::Installation;
 use substrate::execute::Executor;
+use substrate::schematic::conv::RawLib;
 use substrate::schematic::schema::Schema;
-use substrate::simulation::{SimulationContext, Simulator};
+use substrate::schematic::{NestedInstance, Schematic};
+use substrate::simulation::analysis::{InstanceRef, NodeRef};
+use substrate::simulation::data::{NestedPorts, NodeAliases, Save};
+use substrate::simulation::discovery::{
+    find_executable, find_optional_executable, probe_tool, ToolInfo,
+};
+use substrate::simulation::models::{ModelInclude, SupportsModels};
+use substrate::simulation::options::{Decimation, SimOption, TransientNoise};
+use substrate::simulation::{Analysis, DryRun, SimArtifacts, SimulationContext, Simulator};
 use templates::{write_run_script, RunScriptContext};
+use tracing::{span, Level};
 
+pub mod ac;
+pub(crate) mod ammeter;
 pub mod blocks;
+pub mod dc;
 pub mod error;
+pub(crate) mod log;
+pub mod noise;
+pub mod sens;
 pub(crate) mod templates;
 #[cfg(test)]
 mod tests;
@@ -66,6 +90,8 @@ pub enum SaveStmt {
     ScirVoltage(SliceOnePath),
     /// A SCIR signal path representing a resistor whose current should be saved.
     ResistorCurrent(scir::InstancePath),
+    /// A SCIR instance path representing a voltage source whose current should be saved.
+    VsourceCurrent(scir::InstancePath),
     /// An instance path followed by a raw tail path.
     InstanceTail(InstanceTail),
 }
@@ -109,6 +135,13 @@ impl SaveStmt {
                     instance_path(lib, conv, scir)
                 )
             }
+            SaveStmt::VsourceCurrent(scir) => {
+                arcstr::format!(
+                    "@{}{}[i]",
+                    if scir.len() == 1 { "" } else { "V." },
+                    instance_path(lib, conv, scir)
+                )
+            }
             SaveStmt::InstanceTail(itail) => arcstr::format!(
                 "v({}.{})",
                 instance_path(lib, conv, &itail.instance),
@@ -125,7 +158,7 @@ impl SaveStmt {
         match self {
             SaveStmt::Raw(raw) => raw.clone(),
             SaveStmt::ScirVoltage(_) => self.to_save_string(lib, conv),
-            SaveStmt::ResistorCurrent(_) => {
+            SaveStmt::ResistorCurrent(_) | SaveStmt::VsourceCurrent(_) => {
                 arcstr::format!("i({})", self.to_save_string(lib, conv).to_lowercase())
             }
             SaveStmt::InstanceTail(_) => self.to_save_string(lib, conv),
@@ -242,11 +275,22 @@ pub struct Ngspice {}
 /// ngspice per-simulation options.
 ///
 /// A single simulation contains zero or more analyses.
+///
+/// ngspice cannot checkpoint transient analyses, so unlike Spectre there is no option to
+/// resume an interrupted simulation.
 #[derive(Debug, Clone, Default)]
 pub struct Options {
     includes: HashSet<Include>,
-    saves: HashMap<SavedData, u64>,
-    next_save_key: u64,
+    saves: SaveKeys<SavedData>,
+    ammeters: bool,
+    /// The seed of the random number generator used by transient noise sources.
+    seed: Option<u64>,
+    /// Whether transient outputs are interpolated onto the transient step.
+    interp: bool,
+    /// Decimation applied to transient outputs after simulation.
+    decimation: Option<Decimation>,
+    /// Transient device noise options, which ngspice cannot honor.
+    transient_noise: Option<TransientNoise>,
 }
 
 impl Options {
@@ -260,41 +304,116 @@ impl Options {
     }
 
     fn save_inner(&mut self, save: impl Into<SavedData>) -> u64 {
-        let save = save.into();
-
-        if let Some(key) = self.saves.get(&save) {
-            *key
-        } else {
-            let save_key = self.next_save_key;
-            self.next_save_key += 1;
-            self.saves.insert(save, save_key);
-            save_key
-        }
+        self.saves.save(save.into())
     }
 
     /// Marks a transient voltage to be saved in all transient analyses.
     pub fn save_tran_voltage(&mut self, save: impl Into<SaveStmt>) -> tran::VoltageSaveKey {
-        tran::VoltageSaveKey(self.save_inner(save.into()))
+        tran::VoltageSaveKey::new(self.save_inner(save.into()))
     }
 
     /// Marks a transient current to be saved in all transient analyses.
     pub fn save_tran_current(&mut self, save: impl Into<SaveStmt>) -> tran::CurrentSaveKey {
-        tran::CurrentSaveKey(vec![self.save_inner(save.into())])
+        tran::CurrentSaveKey::new(self.save_inner(save.into()))
+    }
+
+    /// Saves the voltage and current of every terminal of `instance` in analyses of type `A`.
+    ///
+    /// Buses are expanded, so that each bit is saved individually. Currents are saved as
+    /// described in [`Options::insert_ammeters`]. The saved outputs can be recovered using
+    /// [`Save::from_saved`]:
+    ///
+    /// ```ignore
+    /// let key = opts.save_ports::<Tran, _>(ctx, &dut);
+    /// // ...
+    /// let ports = <NestedPorts as Save<Ngspice, Tran>>::from_saved(&output, &key);
+    /// let vout = &ports.get("out").unwrap().v;
+    /// ```
+    pub fn save_ports<A, T>(
+        &mut self,
+        ctx: &SimulationContext<Ngspice>,
+        instance: &NestedInstance<T>,
+    ) -> <NestedPorts as Save<Ngspice, A>>::SaveKey
+    where
+        A: Analysis,
+        T: Schematic,
+        NestedPorts: Save<Ngspice, A>,
+    {
+        <NestedPorts as Save<Ngspice, A>>::save(&NestedPorts::new(instance), ctx, self)
+    }
+
+    /// Whether to save terminal currents using automatically inserted 0V voltage sources.
+    ///
+    /// ngspice can only probe the terminal currents of top level instances. If enabled,
+    /// each terminal whose current is saved is reconnected to its net through a 0V voltage
+    /// source at netlist time, and the current through the source is saved instead.
+    /// This allows saving terminal currents of instances at any depth of the hierarchy.
+    ///
+    /// Disabled by default.
+    pub fn insert_ammeters(&mut self, insert: bool) {
+        self.ammeters = insert;
+    }
+
+    /// Sets the seed of the random number generator used by transient noise sources.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = Some(seed);
+    }
+
+    /// Whether to interpolate transient outputs onto multiples of the transient step.
+    ///
+    /// If enabled, ngspice writes output only at multiples of [`Tran::step`] rather than at
+    /// every internal time point, which reduces the size of the output but may alias
+    /// high-frequency content. See [`Options::set_decimation`] for anti-aliased decimation.
+    ///
+    /// Disabled by default.
+    pub fn set_interp(&mut self, interp: bool) {
+        self.interp = interp;
+    }
+
+    /// Decimates the outputs of all transient analyses after simulation.
+    ///
+    /// Outputs are decimated before they are stored in the simulation cache. This reduces the
+    /// size of cached outputs, but not the memory used while reading the ngspice output.
+    pub fn set_decimation(&mut self, decimation: Decimation) {
+        self.decimation = Some(decimation);
     }
 
     /// Marks a transient current to be saved in all transient analyses.
     pub fn probe_tran_current(&mut self, save: impl Into<ProbeStmt>) -> tran::CurrentSaveKey {
-        tran::CurrentSaveKey(vec![self.save_inner(save.into())])
+        tran::CurrentSaveKey::new(self.save_inner(save.into()))
+    }
+}
+
+/// Enables transient device noise, which ngspice does not support.
+///
+/// ngspice has no native transient device noise, so simulations with this option fail with
+/// [`Error::UnsupportedOption`]. Instead, add noise sources created using [`TrNoise::white`]
+/// and set their seed using [`Options::set_seed`].
+impl SimOption<Ngspice> for TransientNoise {
+    fn set_option(
+        self,
+        opts: &mut <Ngspice as Simulator>::Options,
+        _ctx: &SimulationContext<Ngspice>,
+    ) {
+        opts.transient_noise = Some(self);
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
-struct CachedSim {
-    simulation_netlist: Vec<u8>,
+impl SimOption<Ngspice> for Decimation {
+    fn set_option(
+        self,
+        opts: &mut <Ngspice as Simulator>::Options,
+        _ctx: &SimulationContext<Ngspice>,
+    ) {
+        opts.set_decimation(self)
+    }
 }
 
 struct CachedSimState {
     input: Vec<Input>,
+    /// The command used to invoke ngspice.
+    executable: String,
+    decimation: Option<Decimation>,
     netlist: PathBuf,
     output_file: PathBuf,
     log: PathBuf,
@@ -304,93 +423,226 @@ struct CachedSimState {
     executor: Arc<dyn Executor>,
 }
 
-impl CacheableWithState<CachedSimState> for CachedSim {
-    type Output = Vec<HashMap<String, Vec<f64>>>;
-    type Error = Arc<Error>;
+/// The raw data produced by a single ngspice analysis.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum CachedData {
+    Tran(HashMap<String, Vec<f64>>),
+    Ac {
+        freq: Vec<f64>,
+        signals: HashMap<String, Vec<Complex64>>,
+    },
+    DcOp(HashMap<String, f64>),
+    Noise {
+        freq: Vec<f64>,
+        output_noise: Vec<f64>,
+        input_noise: Vec<f64>,
+    },
+    DcSens(HashMap<String, f64>),
+    AcSens {
+        freq: Vec<f64>,
+        values: HashMap<String, Vec<Complex64>>,
+    },
+}
 
-    fn generate_with_state(
-        &self,
-        state: CachedSimState,
-    ) -> std::result::Result<Self::Output, Self::Error> {
-        let inner = || -> Result<Self::Output> {
-            let CachedSimState {
-                input,
-                netlist,
-                output_file,
-                log,
-                err_log,
-                run_script,
-                work_dir,
-                executor,
-            } = state;
-            write_run_script(
-                RunScriptContext {
-                    netlist: &netlist,
-                    raw_output_file: &output_file,
-                    log_path: &log,
-                    err_path: &err_log,
-                    bashrc: None,
-                    flags: "",
-                },
-                &run_script,
-            )?;
-
-            let mut perms = std::fs::metadata(&run_script)?.permissions();
-            #[cfg(any(unix, target_os = "redox"))]
-            perms.set_mode(0o744);
-            std::fs::set_permissions(&run_script, perms)?;
-
-            let mut command = std::process::Command::new("/bin/bash");
-            command.arg(&run_script).current_dir(&work_dir);
-            executor
-                .execute(command, Default::default())
-                .map_err(|_| Error::NgspiceError)?;
-
-            let contents = std::fs::read(&output_file)?;
-            let rawfile = nutlex::parse(
-                &contents,
-                nutlex::Options {
-                    endianness: nutlex::ByteOrder::LittleEndian,
-                },
-            )?;
-
-            let mut raw_outputs = Vec::with_capacity(input.len());
-
-            for (an, results) in input.iter().zip(rawfile.analyses.into_iter()) {
-                match an {
-                    Input::Tran(_) => match results.data {
-                        Data::Real(real) => raw_outputs.push(HashMap::from_iter(
-                            results
-                                .variables
-                                .into_iter()
-                                .map(|var| (var.name.to_string(), real[var.idx].clone())),
-                        )),
-                        _ => {
-                            return Err(Error::NgspiceError);
-                        }
-                    },
+impl CachedData {
+    /// Extracts the data produced by the given analysis from its rawfile plot.
+    fn from_plot(input: &Input, plot: Plot<'_>) -> Result<Self> {
+        let Plot {
+            variables, data, ..
+        } = plot;
+        Ok(match (input, data) {
+            (Input::Tran(_), Data::Real(real)) => CachedData::Tran(HashMap::from_iter(
+                variables
+                    .into_iter()
+                    .map(|var| (var.name.to_string(), real[var.idx].clone())),
+            )),
+            (Input::Ac(_), Data::Complex(complex)) => {
+                let mut signals = HashMap::from_iter(variables.into_iter().map(|var| {
+                    let signal = &complex[var.idx];
+                    let values = signal
+                        .real
+                        .iter()
+                        .zip(signal.imag.iter())
+                        .map(|(&re, &im)| Complex64::new(re, im))
+                        .collect::<Vec<_>>();
+                    (var.name.to_string(), values)
+                }));
+                let freq = signals
+                    .remove("frequency")
+                    .ok_or(Error::NgspiceError)?
+                    .into_iter()
+                    .map(|f| f.re)
+                    .collect();
+                CachedData::Ac { freq, signals }
+            }
+            (Input::DcOp(_), Data::Real(real)) => CachedData::DcOp(
+                variables
+                    .into_iter()
+                    .map(|var| {
+                        let value = real[var.idx].first().ok_or(Error::NgspiceError)?;
+                        Ok((var.name.to_string(), *value))
+                    })
+                    .collect::<Result<_>>()?,
+            ),
+            (Input::Noise(_), Data::Real(real)) => {
+                let signal = |name: &str| {
+                    variables
+                        .iter()
+                        .find(|var| var.name == name)
+                        .map(|var| real[var.idx].clone())
+                        .ok_or(Error::NgspiceError)
+                };
+                CachedData::Noise {
+                    freq: signal("frequency")?,
+                    output_noise: signal("onoise_spectrum")?,
+                    input_noise: signal("inoise_spectrum")?,
                 }
             }
+            (Input::Sens(_), Data::Real(real)) => CachedData::DcSens(
+                variables
+                    .into_iter()
+                    .map(|var| {
+                        let value = real[var.idx].first().ok_or(Error::NgspiceError)?;
+                        Ok((var.name.to_string(), *value))
+                    })
+                    .collect::<Result<_>>()?,
+            ),
+            (Input::Sens(_), Data::Complex(complex)) => {
+                let mut values = HashMap::from_iter(variables.into_iter().map(|var| {
+                    let signal = &complex[var.idx];
+                    let values = signal
+                        .real
+                        .iter()
+                        .zip(signal.imag.iter())
+                        .map(|(&re, &im)| Complex64::new(re, im))
+                        .collect::<Vec<_>>();
+                    (var.name.to_string(), values)
+                }));
+                let freq = values
+                    .remove("frequency")
+                    .ok_or(Error::NgspiceError)?
+                    .into_iter()
+                    .map(|f| f.re)
+                    .collect();
+                CachedData::AcSens { freq, values }
+            }
+            _ => return Err(Error::NgspiceError),
+        })
+    }
+}
 
-            Ok(raw_outputs)
-        };
-        inner().map_err(Arc::new)
+impl CachedSimState {
+    /// Runs the simulation and parses its outputs.
+    fn run(self) -> Result<Vec<CachedData>> {
+        let CachedSimState {
+            input,
+            executable,
+            decimation,
+            netlist,
+            output_file,
+            log,
+            err_log,
+            run_script,
+            work_dir,
+            executor,
+        } = self;
+        write_run_script(
+            RunScriptContext {
+                executable: &executable,
+                netlist: &netlist,
+                raw_output_file: &output_file,
+                log_path: &log,
+                err_path: &err_log,
+                bashrc: None,
+                flags: "",
+            },
+            &run_script,
+        )?;
+
+        let command = script_command(&run_script, &work_dir);
+        tracing::info!(?run_script, "running ngspice");
+        executor
+            .execute(command, Default::default())
+            .map_err(|_| Error::NgspiceError)?;
+
+        let contents = std::fs::read(&output_file)?;
+        let rawfile = nutlex::parse(
+            &contents,
+            nutlex::Options {
+                endianness: nutlex::ByteOrder::LittleEndian,
+            },
+        )?;
+
+        let mut raw_outputs = Vec::with_capacity(input.len());
+
+        // ngspice may write plots that do not correspond to a requested analysis,
+        // so each analysis is matched to the next plot with the expected name.
+        let mut plots = rawfile.analyses.into_iter();
+        for (i, an) in input.iter().enumerate() {
+            let _guard = span!(Level::INFO, "parsing ngspice analysis", analysis = i).entered();
+            let results = plots
+                .find(|plot| plot.plotname.starts_with(an.plotname()))
+                .ok_or(Error::MissingPlot(i))?;
+            let mut data = CachedData::from_plot(an, results)?;
+            if let (CachedData::Tran(values), Some(decimation)) = (&mut data, &decimation) {
+                *values = decimate_tran(std::mem::take(values), decimation);
+            }
+            raw_outputs.push(data);
+        }
+
+        Ok(raw_outputs)
     }
 }
 
+/// The environment variable used to specify the path to the ngspice executable.
+pub const NGSPICE_PATH_ENV: &str = "SUBSTRATE_NGSPICE_PATH";
+
 impl Ngspice {
-    fn simulate(
+    /// Locates the ngspice executable and determines its version.
+    ///
+    /// See [`find_executable`] for the locations that are searched.
+    /// The version can be used to selectively enable features that require newer versions of ngspice.
+    pub fn probe() -> Result<ToolInfo> {
+        Ok(probe_tool(
+            "ngspice",
+            NGSPICE_PATH_ENV,
+            &["-v"],
+            "ngspice-",
+        )?)
+    }
+
+    /// Returns the error preventing the ngspice executable from being located, if any.
+    fn discovery_error() -> Option<Error> {
+        find_executable("ngspice", NGSPICE_PATH_ENV)
+            .err()
+            .map(Error::Discovery)
+    }
+
+    /// Returns the command used to invoke ngspice in run scripts.
+    ///
+    /// If ngspice cannot be located, it is invoked by name.
+    fn executable() -> Result<String> {
+        Ok(find_optional_executable("ngspice", NGSPICE_PATH_ENV)?
+            .map(|path| shell_quote(&path.to_string_lossy()))
+            .unwrap_or_else(|| "ngspice".to_string()))
+    }
+
+    /// Writes the simulation netlist to the working directory.
+    ///
+    /// Returns the netlist conversion metadata, the inserted ammeters (if enabled),
+    /// the path to the netlist, and the contents of the netlist.
+    fn write_netlist(
         &self,
         ctx: &SimulationContext<Ngspice>,
-        options: Options,
-        input: Vec<Input>,
-    ) -> Result<Vec<Output>> {
+        options: &Options,
+        input: &[Input],
+    ) -> Result<(NetlistLibConversion, Option<Ammeters>, PathBuf, Vec<u8>)> {
         std::fs::create_dir_all(&ctx.work_dir)?;
         let netlist = ctx.work_dir.join("netlist.spice");
         let mut f = std::fs::File::create(&netlist)?;
         let mut w = Vec::new();
 
-        let mut includes = options.includes.into_iter().collect::<Vec<_>>();
+        let mut includes = options.includes.iter().cloned().collect::<Vec<_>>();
         includes.extend(ctx.lib.scir.primitives().filter_map(|(_, p)| {
             if let Primitive::Spice(spice::Primitive::RawInstanceWithInclude { netlist, .. }) = p {
                 Some(netlist.clone().into())
@@ -398,6 +650,11 @@ impl Ngspice {
                 None
             }
         }));
+        let ammeters = options
+            .ammeters
+            .then(|| Ammeters::new(&ctx.lib.scir, &options.saves))
+            .transpose()?;
+        let lib = &ctx.lib.scir;
         let mut saves = options.saves.keys().cloned().collect::<Vec<_>>();
         // Sorting the include list makes repeated netlist invocations
         // produce the same output. If we were to iterate over the HashSet directly,
@@ -405,29 +662,67 @@ impl Ngspice {
         includes.sort();
         saves.sort();
 
-        let netlister = NetlisterInstance::new(
-            self,
-            &ctx.lib.scir,
-            &mut w,
-            NetlistOptions::new(
-                NetlistKind::Testbench(RenameGround::Yes(arcstr::literal!("0"))),
-                &includes,
-            ),
+        let mut netlist_options = NetlistOptions::new(
+            NetlistKind::Testbench(RenameGround::Yes(arcstr::literal!("0"))),
+            &includes,
         );
+        if let Some(ammeters) = &ammeters {
+            netlist_options = ammeters.insert(netlist_options);
+        }
+        let netlister = NetlisterInstance::new(self, lib, &mut w, netlist_options);
         let conv = netlister.export()?;
 
         writeln!(w)?;
         for save in saves {
-            save.netlist(&mut w, &ctx.lib.scir, &conv)?;
+            match ammeters
+                .as_ref()
+                .and_then(|ammeters| ammeters.current(&save, lib, &conv))
+            {
+                Some(current) => write!(w, ".save {}", current.to_lowercase())?,
+                None => save.netlist(&mut w, lib, &conv)?,
+            }
             writeln!(w)?;
         }
 
+        if let Some(seed) = options.seed {
+            writeln!(w, ".options seed={seed}")?;
+        }
+        if options.interp {
+            writeln!(w, ".options interp")?;
+        }
+
         writeln!(w)?;
-        for an in input.iter() {
+        for (i, an) in input.iter().enumerate() {
+            let _guard = span!(Level::INFO, "netlisting ngspice analysis", analysis = i).entered();
+            // Paths referenced by analyses can only be named once the library is netlisted.
+            let mut an = an.clone();
+            an.resolve(&ctx.lib, &conv)?;
             an.netlist(&mut w)?;
             writeln!(w)?;
         }
         f.write_all(&w)?;
+        // Decimation is applied before outputs are cached, so it is part of the cache key.
+        if let Some(decimation) = &options.decimation {
+            write!(w, "{decimation:?}")?;
+        }
+
+        Ok((conv, ammeters, netlist, w))
+    }
+
+    fn simulate(
+        &self,
+        ctx: &SimulationContext<Ngspice>,
+        options: Options,
+        input: Vec<Input>,
+    ) -> Result<Vec<Output>> {
+        if let Some(noise) = &options.transient_noise {
+            return Err(Error::UnsupportedOption(format!(
+                "ngspice does not simulate transient device noise (requested up to {} Hz); \
+                 use `TrNoise::white` sources instead",
+                noise.fmax()
+            )));
+        }
+        let (conv, ammeters, netlist, w) = self.write_netlist(ctx, &options, &input)?;
 
         let output_file = ctx.work_dir.join("data.raw");
         let log = ctx.work_dir.join("ngspice.log");
@@ -436,49 +731,126 @@ impl Ngspice {
         let work_dir = ctx.work_dir.clone();
         let executor = ctx.ctx.executor.clone();
 
-        let raw_outputs = ctx
-            .ctx
-            .cache
-            .get_with_state(
-                "ngspice.simulation.outputs",
-                CachedSim {
-                    simulation_netlist: w,
-                },
-                CachedSimState {
-                    input,
-                    netlist,
-                    output_file,
-                    log,
-                    err_log,
-                    run_script,
-                    work_dir,
-                    executor,
-                },
-            )
-            .try_inner()
-            .map_err(|e| match e {
-                TryInnerError::CacheError(e) => Error::Caching(e),
-                TryInnerError::GeneratorError(e) => Error::Generator(e.clone()),
-            })?
-            .clone();
-
+        let state = CachedSimState {
+            input,
+            executable: Ngspice::executable()?,
+            decimation: options.decimation,
+            netlist,
+            output_file,
+            log,
+            err_log,
+            run_script,
+            work_dir,
+            executor,
+        };
+        let raw_outputs = run_cached(&ctx.ctx.cache, "ngspice.simulation.outputs", w, move || {
+            state.run()
+        })
+        .try_inner()
+        .map_err(|e| match e {
+            TryInnerError::CacheError(e) => Error::Caching(e),
+            TryInnerError::GeneratorError(e) => match &**e {
+                Error::NgspiceError => log::simulation_error(
+                    &ctx.lib,
+                    &conv,
+                    &[
+                        ctx.work_dir.join("ngspice.err").as_path(),
+                        ctx.work_dir.join("ngspice.log").as_path(),
+                    ],
+                )
+                .or_else(Ngspice::discovery_error)
+                .unwrap_or_else(|| Error::Generator(e.clone())),
+                _ => Error::Generator(e.clone()),
+            },
+        })?
+        .clone();
+
+        let aliases = Arc::new(node_aliases(&ctx.lib.scir, &conv, &options.saves));
         let conv = Arc::new(conv);
+        let saved_values: HashMap<u64, ArcStr> = options
+            .saves
+            .iter()
+            .map(|(k, v)| {
+                let data = match ammeters
+                    .as_ref()
+                    .and_then(|ammeters| ammeters.current(k, &ctx.lib.scir, &conv))
+                {
+                    Some(current) => arcstr::format!("i({})", current.to_lowercase()),
+                    None => k.to_data_string(&ctx.lib.scir, &conv),
+                };
+                (*v, data)
+            })
+            .collect();
         let outputs = raw_outputs
             .into_iter()
-            .map(|mut raw_values| {
-                tran::Output {
+            .map(|raw_output| match raw_output {
+                CachedData::Tran(mut raw_values) => tran::Output {
                     time: Arc::new(raw_values.remove("time").unwrap()),
                     raw_values: raw_values
                         .into_iter()
                         .map(|(k, v)| (ArcStr::from(k), Arc::new(v)))
                         .collect(),
-                    saved_values: options
-                        .saves
-                        .iter()
-                        .map(|(k, v)| (*v, k.to_data_string(&ctx.lib.scir, &conv)))
+                    saved_values: saved_values.clone(),
+                    resolver: Some(tran::PathResolver {
+                        lib: ctx.lib.clone(),
+                        conv: conv.clone(),
+                        aliases: aliases.clone(),
+                    }),
+                }
+                .into(),
+                CachedData::Ac { freq, signals } => ac::Output {
+                    freq: Arc::new(freq),
+                    raw_values: signals
+                        .into_iter()
+                        .map(|(k, v)| (ArcStr::from(k), Arc::new(v)))
                         .collect(),
+                    saved_values: saved_values.clone(),
                 }
-                .into()
+                .into(),
+                CachedData::DcOp(raw_values) => dc::OpOutput {
+                    raw_values: raw_values
+                        .into_iter()
+                        .map(|(k, v)| (ArcStr::from(k), v))
+                        .collect(),
+                    saved_values: saved_values.clone(),
+                }
+                .into(),
+                CachedData::Noise {
+                    freq,
+                    output_noise,
+                    input_noise,
+                } => noise::Output {
+                    freq: Arc::new(freq),
+                    output_noise: Arc::new(output_noise),
+                    input_noise: Arc::new(input_noise),
+                }
+                .into(),
+                CachedData::DcSens(values) => sens::Output {
+                    freq: None,
+                    raw_values: values
+                        .into_iter()
+                        .map(|(k, v)| (ArcStr::from(k), SensValue::Dc(v)))
+                        .collect(),
+                    resolver: Some(tran::PathResolver {
+                        lib: ctx.lib.clone(),
+                        conv: conv.clone(),
+                        aliases: aliases.clone(),
+                    }),
+                }
+                .into(),
+                CachedData::AcSens { freq, values } => sens::Output {
+                    freq: Some(Arc::new(freq)),
+                    raw_values: values
+                        .into_iter()
+                        .map(|(k, v)| (ArcStr::from(k), SensValue::Ac(Arc::new(v))))
+                        .collect(),
+                    resolver: Some(tran::PathResolver {
+                        lib: ctx.lib.clone(),
+                        conv: conv.clone(),
+                        aliases: aliases.clone(),
+                    }),
+                }
+                .into(),
             })
             .collect();
 
@@ -488,6 +860,20 @@ impl Ngspice {
 
 impl scir::schema::Schema for Ngspice {
     type Primitive = Primitive;
+
+    fn multiply_primitive(primitive: &Primitive, m: u64) -> Option<Primitive> {
+        match primitive {
+            Primitive::Spice(p) => p.multiplied(m).map(Primitive::Spice),
+            Primitive::Vsource(_) | Primitive::Isource(_) => None,
+        }
+    }
+
+    fn supports_instance_params(primitive: &Primitive) -> bool {
+        match primitive {
+            Primitive::Spice(p) => p.accepts_params(),
+            Primitive::Vsource(_) | Primitive::Isource(_) => false,
+        }
+    }
 }
 
 impl FromSchema<NoSchema> for Ngspice {
@@ -547,6 +933,54 @@ impl Simulator for Ngspice {
     }
 }
 
+impl SupportsModels for Ngspice {
+    fn device_models(lib: &Library<Ngspice>) -> HashSet<ArcStr> {
+        lib.primitives()
+            .filter_map(|(_, primitive)| match primitive {
+                Primitive::Spice(primitive) => primitive.model(),
+                Primitive::Vsource(_) | Primitive::Isource(_) => None,
+            })
+            .cloned()
+            .collect()
+    }
+
+    fn include_model(opts: &mut Options, include: ModelInclude) {
+        match include.section {
+            Some(section) => opts.include_section(include.path, section),
+            None => opts.include(include.path),
+        }
+    }
+}
+
+impl DryRun for Ngspice {
+    fn dry_run_inputs(
+        &self,
+        ctx: &SimulationContext<Self>,
+        options: Self::Options,
+        input: Vec<Self::Input>,
+    ) -> Result<SimArtifacts> {
+        let (_, _, netlist, _) = self.write_netlist(ctx, &options, &input)?;
+        let run_script = ctx.work_dir.join("simulate.sh");
+        write_run_script(
+            RunScriptContext {
+                executable: &Ngspice::executable()?,
+                netlist: &netlist,
+                raw_output_file: &ctx.work_dir.join("data.raw"),
+                log_path: &ctx.work_dir.join("ngspice.log"),
+                err_path: &ctx.work_dir.join("ngspice.err"),
+                bashrc: None,
+                flags: "",
+            },
+            &run_script,
+        )?;
+
+        Ok(SimArtifacts {
+            netlist,
+            run_script,
+        })
+    }
+}
+
 pub(crate) fn instance_path(
     lib: &Library<Ngspice>,
     conv: &NetlistLibConversion,
@@ -556,6 +990,77 @@ pub(crate) fn instance_path(
         .join(".")
 }
 
+/// Replaces a Substrate node path referenced by an analysis with the netlisted name of the node.
+pub(crate) fn resolve_node(
+    lib: &RawLib<Ngspice>,
+    conv: &NetlistLibConversion,
+    node: &mut NodeRef,
+) -> Result<()> {
+    if let NodeRef::Path(path) = node {
+        let scir =
+            scir_node_path(lib, path).ok_or_else(|| Error::UnresolvedPath(format!("{path:?}")))?;
+        *node = NodeRef::Raw(node_voltage_path(&lib.scir, conv, &scir).into());
+    }
+    Ok(())
+}
+
+/// Replaces a Substrate instance path referenced by an analysis with the netlisted name
+/// of the instance.
+pub(crate) fn resolve_instance(
+    lib: &RawLib<Ngspice>,
+    conv: &NetlistLibConversion,
+    instance: &mut InstanceRef,
+) -> Result<()> {
+    if let InstanceRef::Path(path) = instance {
+        let scir = lib
+            .convert_instance_path(path)
+            .ok_or_else(|| Error::UnresolvedPath(format!("{path:?}")))?;
+        *instance = InstanceRef::Raw(instance_path(&lib.scir, conv, &scir).into());
+    }
+    Ok(())
+}
+
+/// Returns the netlisted name of a node that has been [resolved](resolve_node).
+pub(crate) fn raw_node(node: &NodeRef) -> Result<&ArcStr> {
+    match node {
+        NodeRef::Raw(name) => Ok(name),
+        NodeRef::Path(path) => Err(Error::UnresolvedPath(format!("{path:?}"))),
+    }
+}
+
+/// Returns the netlisted name of an instance that has been [resolved](resolve_instance).
+pub(crate) fn raw_instance(instance: &InstanceRef) -> Result<&ArcStr> {
+    match instance {
+        InstanceRef::Raw(name) => Ok(name),
+        InstanceRef::Path(path) => Err(Error::UnresolvedPath(format!("{path:?}"))),
+    }
+}
+
+/// Returns the names of merged nodes under which saved voltages can also be looked up.
+///
+/// Voltages are saved under the name of the topmost node they are connected to,
+/// so the names of all nodes [merged](scir::LibraryBuilder::merged_paths) with each
+/// saved node are recorded as aliases of that topmost node.
+fn node_aliases(
+    lib: &Library<Ngspice>,
+    conv: &NetlistLibConversion,
+    saves: &SaveKeys<SavedData>,
+) -> NodeAliases {
+    let mut aliases = NodeAliases::new();
+    for (save, _) in saves {
+        if let SavedData::Save(stmt @ SaveStmt::ScirVoltage(path)) = save {
+            let name = stmt.to_data_string(lib, conv);
+            for merged in lib.merged_paths(path.clone()) {
+                aliases.insert(
+                    arcstr::format!("v({})", node_voltage_path(lib, conv, &merged)),
+                    name.clone(),
+                );
+            }
+        }
+    }
+    aliases
+}
+
 pub(crate) fn node_voltage_path(
     lib: &Library<Ngspice>,
     conv: &NetlistLibConversion,
@@ -640,6 +1145,14 @@ pub(crate) fn node_current_path(
 pub enum Input {
     /// Transient simulation input.
     Tran(Tran),
+    /// AC simulation input.
+    Ac(Ac),
+    /// DC operating point simulation input.
+    DcOp(DcOp),
+    /// Noise simulation input.
+    Noise(Noise),
+    /// Sensitivity analysis input.
+    Sens(Sens),
 }
 
 impl From<Tran> for Input {
@@ -648,11 +1161,43 @@ impl From<Tran> for Input {
     }
 }
 
+impl From<Ac> for Input {
+    fn from(value: Ac) -> Self {
+        Self::Ac(value)
+    }
+}
+
+impl From<DcOp> for Input {
+    fn from(value: DcOp) -> Self {
+        Self::DcOp(value)
+    }
+}
+
+impl From<Noise> for Input {
+    fn from(value: Noise) -> Self {
+        Self::Noise(value)
+    }
+}
+
+impl From<Sens> for Input {
+    fn from(value: Sens) -> Self {
+        Self::Sens(value)
+    }
+}
+
 /// Outputs directly produced by ngspice.
 #[derive(Debug, Clone)]
 pub enum Output {
     /// Transient simulation output.
     Tran(tran::Output),
+    /// AC simulation output.
+    Ac(ac::Output),
+    /// DC operating point simulation output.
+    DcOp(dc::OpOutput),
+    /// Noise simulation output.
+    Noise(noise::Output),
+    /// Sensitivity analysis output.
+    Sens(sens::Output),
 }
 
 impl From<tran::Output> for Output {
@@ -666,28 +1211,130 @@ impl TryFrom<Output> for tran::Output {
     fn try_from(value: Output) -> Result<Self> {
         match value {
             Output::Tran(t) => Ok(t),
+            _ => Err(Error::NgspiceError),
+        }
+    }
+}
+
+impl From<ac::Output> for Output {
+    fn from(value: ac::Output) -> Self {
+        Self::Ac(value)
+    }
+}
+
+impl TryFrom<Output> for ac::Output {
+    type Error = Error;
+    fn try_from(value: Output) -> Result<Self> {
+        match value {
+            Output::Ac(ac) => Ok(ac),
+            _ => Err(Error::NgspiceError),
+        }
+    }
+}
+
+impl From<dc::OpOutput> for Output {
+    fn from(value: dc::OpOutput) -> Self {
+        Self::DcOp(value)
+    }
+}
+
+impl TryFrom<Output> for dc::OpOutput {
+    type Error = Error;
+    fn try_from(value: Output) -> Result<Self> {
+        match value {
+            Output::DcOp(op) => Ok(op),
+            _ => Err(Error::NgspiceError),
+        }
+    }
+}
+
+impl From<noise::Output> for Output {
+    fn from(value: noise::Output) -> Self {
+        Self::Noise(value)
+    }
+}
+
+impl TryFrom<Output> for noise::Output {
+    type Error = Error;
+    fn try_from(value: Output) -> Result<Self> {
+        match value {
+            Output::Noise(noise) => Ok(noise),
+            _ => Err(Error::NgspiceError),
+        }
+    }
+}
+
+impl From<sens::Output> for Output {
+    fn from(value: sens::Output) -> Self {
+        Self::Sens(value)
+    }
+}
+
+impl TryFrom<Output> for sens::Output {
+    type Error = Error;
+    fn try_from(value: Output) -> Result<Self> {
+        match value {
+            Output::Sens(sens) => Ok(sens),
+            _ => Err(Error::NgspiceError),
         }
     }
 }
 
 impl Input {
+    /// Resolves the Substrate paths referenced by this input to raw (netlisted) names.
+    fn resolve(&mut self, lib: &RawLib<Ngspice>, conv: &NetlistLibConversion) -> Result<()> {
+        match self {
+            Self::Noise(noise) => noise.resolve(lib, conv),
+            Self::Sens(sens) => sens.resolve(lib, conv),
+            Self::Tran(_) | Self::Ac(_) | Self::DcOp(_) => Ok(()),
+        }
+    }
+
     fn netlist<W: Write>(&self, out: &mut W) -> Result<()> {
         match self {
             Self::Tran(t) => t.netlist(out),
+            Self::Ac(ac) => Ok(ac.netlist(out)?),
+            Self::DcOp(op) => Ok(op.netlist(out)?),
+            Self::Noise(noise) => noise.netlist(out),
+            Self::Sens(sens) => sens.netlist(out),
+        }
+    }
+
+    /// The prefix of the name of the rawfile plot written by this analysis.
+    fn plotname(&self) -> &'static str {
+        match self {
+            Self::Tran(_) => "Transient Analysis",
+            Self::Ac(_) => "AC Analysis",
+            Self::DcOp(_) => "Operating Point",
+            Self::Noise(_) => "Noise Spectral Density",
+            Self::Sens(_) => "Sensitivity Analysis",
         }
     }
 }
 
 impl Tran {
     fn netlist<W: Write>(&self, out: &mut W) -> Result<()> {
-        write!(out, ".tran {} {}", self.step, self.stop)?;
-        if let Some(ref start) = self.start {
-            write!(out, "{start}")?;
+        write!(out, ".tran {} {}", self.step.value(), self.stop.value())?;
+        if let Some(start) = self.start {
+            write!(out, " {}", start.value())?;
         }
         Ok(())
     }
 }
 
+impl TrNoise {
+    fn netlist<W: Write>(&self, out: &mut W) -> std::io::Result<()> {
+        write!(
+            out,
+            " TRNOISE({} {} {} {})",
+            self.rms,
+            self.step.value(),
+            self.alpha.unwrap_or_default(),
+            self.amp_1f.unwrap_or_default(),
+        )
+    }
+}
+
 impl HasSpiceLikeNetlist for Ngspice {
     fn write_prelude<W: Write>(&self, out: &mut W, _lib: &Library<Self>) -> std::io::Result<()> {
         writeln!(out, "* Substrate SPICE library")?;
@@ -712,6 +1359,14 @@ impl HasSpiceLikeNetlist for Ngspice {
         Spice.write_start_subckt(out, name, ports)
     }
 
+    fn legalize_name(&self, name: &ArcStr) -> ArcStr {
+        Spice.legalize_name(name)
+    }
+
+    fn case_insensitive_names(&self) -> bool {
+        Spice.case_insensitive_names()
+    }
+
     fn write_end_subckt<W: Write>(&self, out: &mut W, name: &ArcStr) -> std::io::Result<()> {
         Spice.write_end_subckt(out, name)
     }
@@ -747,7 +1402,7 @@ impl HasSpiceLikeNetlist for Ngspice {
                 }
                 match vsource {
                     Vsource::Dc(dc) => {
-                        write!(out, " DC {}", dc)?;
+                        write!(out, " DC {}", dc.value())?;
                     }
                     Vsource::Pulse(pulse) => {
                         write!(
@@ -755,14 +1410,15 @@ impl HasSpiceLikeNetlist for Ngspice {
                             " PULSE({} {} {} {} {} {} {} {})",
                             pulse.val0,
                             pulse.val1,
-                            pulse.delay.unwrap_or_default(),
-                            pulse.rise.unwrap_or_default(),
-                            pulse.fall.unwrap_or_default(),
-                            pulse.width.unwrap_or_default(),
-                            pulse.period.unwrap_or_default(),
+                            pulse.delay.unwrap_or_default().value(),
+                            pulse.rise.unwrap_or_default().value(),
+                            pulse.fall.unwrap_or_default().value(),
+                            pulse.width.unwrap_or_default().value(),
+                            pulse.period.unwrap_or_default().value(),
                             pulse.num_pulses.unwrap_or_default(),
                         )?;
                     }
+                    Vsource::TrNoise(trnoise) => trnoise.netlist(out)?,
                 }
                 Ok(name)
             }
@@ -776,7 +1432,7 @@ impl HasSpiceLikeNetlist for Ngspice {
                 }
                 match isource {
                     Isource::Dc(dc) => {
-                        write!(out, " DC {}", dc)?;
+                        write!(out, " DC {}", dc.value())?;
                     }
                     Isource::Pulse(pulse) => {
                         write!(
@@ -784,14 +1440,15 @@ impl HasSpiceLikeNetlist for Ngspice {
                             " PULSE({} {} {} {} {} {} {} {})",
                             pulse.val0,
                             pulse.val1,
-                            pulse.delay.unwrap_or_default(),
-                            pulse.rise.unwrap_or_default(),
-                            pulse.fall.unwrap_or_default(),
-                            pulse.width.unwrap_or_default(),
-                            pulse.period.unwrap_or_default(),
+                            pulse.delay.unwrap_or_default().value(),
+                            pulse.rise.unwrap_or_default().value(),
+                            pulse.fall.unwrap_or_default().value(),
+                            pulse.width.unwrap_or_default().value(),
+                            pulse.period.unwrap_or_default().value(),
                             pulse.num_pulses.unwrap_or_default(),
                         )?;
                     }
+                    Isource::TrNoise(trnoise) => trnoise.netlist(out)?,
                 }
                 Ok(name)
             }
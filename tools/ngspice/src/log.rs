@@ -0,0 +1,110 @@
+//! ngspice log parsing.
+
+use std::path::Path;
+
+use scir::NetlistLibConversion;
+use substrate::schematic::conv::RawLib;
+use substrate::simulation::messages::{MentionedName, SimulatorMessage};
+
+use crate::error::Error;
+use crate::Ngspice;
+
+/// Returns the error messages in ngspice output.
+///
+/// Warnings are not errors, even if they mention one of the error patterns
+/// (e.g. a singular matrix that ngspice recovers from by stepping).
+pub(crate) fn error_messages(log: &str) -> Vec<String> {
+    log.lines()
+        .map(str::trim)
+        .filter(|line| {
+            let line = line.to_ascii_lowercase();
+            !line.starts_with("warning")
+                && [
+                    "error",
+                    "singular matrix",
+                    "trouble with",
+                    "timestep too small",
+                ]
+                .iter()
+                .any(|pat| line.contains(pat))
+        })
+        .map(str::to_string)
+        .collect()
+}
+
+/// Splits an ngspice name into its hierarchical elements and bus index.
+///
+/// Terminal suffixes (e.g. `xr1:p`) are ignored.
+pub(crate) fn split_name(name: &str) -> (Vec<String>, Option<usize>) {
+    let name = name.split(':').next().unwrap_or_default();
+    let mut elems = name.split('.').map(str::to_string).collect::<Vec<_>>();
+
+    let last = elems.last_mut().unwrap();
+    let index = last
+        .strip_suffix(']')
+        .and_then(|rest| rest.rsplit_once('['))
+        .and_then(|(signal, index)| Some((signal.to_string(), index.parse().ok()?)));
+    let index = index.map(|(signal, index)| {
+        *last = signal;
+        index
+    });
+    (elems, index)
+}
+
+/// Maps the names mentioned in an ngspice message back to SCIR and Substrate paths.
+///
+/// Hierarchical names, quoted names, and names following the word `node` are considered.
+pub(crate) fn mentioned_names(
+    lib: &RawLib<Ngspice>,
+    conv: &NetlistLibConversion,
+    message: &str,
+) -> Vec<MentionedName> {
+    let mut names: Vec<MentionedName> = Vec::new();
+    let mut prev = "";
+    for token in message.split_whitespace() {
+        let quoted = token.starts_with(['"', '\'', '`']);
+        let name = token.trim_matches(|c: char| "\"'`,;:()".contains(c));
+        let after_node = prev.eq_ignore_ascii_case("node");
+        prev = token;
+        if name.is_empty() || !(quoted || after_node || name.contains('.')) {
+            continue;
+        }
+        if names.iter().any(|mentioned| mentioned.name == name) {
+            continue;
+        }
+
+        let (elems, index) = split_name(name);
+        let elems = elems.iter().map(String::as_str).collect::<Vec<_>>();
+        // ngspice prefixes flattened device names with the device type (e.g. `r.xdut.rr1`).
+        let resolved = lib
+            .resolve_netlisted_name(conv, name, &elems, index)
+            .or_else(|| match elems.split_first() {
+                Some((prefix, rest)) if prefix.len() == 1 && !rest.is_empty() => {
+                    lib.resolve_netlisted_name(conv, name, rest, index)
+                }
+                _ => None,
+            });
+        names.extend(resolved);
+    }
+    names
+}
+
+/// Creates an error describing a failed simulation from the ngspice logs at `logs`.
+///
+/// Returns [`None`] if none of the logs could be read or if they do not contain any errors.
+pub(crate) fn simulation_error(
+    lib: &RawLib<Ngspice>,
+    conv: &NetlistLibConversion,
+    logs: &[&Path],
+) -> Option<Error> {
+    let messages = logs
+        .iter()
+        .filter_map(|log| std::fs::read_to_string(log).ok())
+        .flat_map(|log| error_messages(&log))
+        .map(|message| SimulatorMessage {
+            names: mentioned_names(lib, conv, &message),
+            message,
+        })
+        .collect::<Vec<_>>();
+    (!messages.is_empty()).then_some(Error::SimulationFailed(messages))
+}
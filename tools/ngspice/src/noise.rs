@@ -0,0 +1,158 @@
+//! ngspice small-signal noise analysis options and data structures.
+
+use crate::ac::Sweep;
+use crate::error::Result;
+use crate::{raw_instance, raw_node, resolve_instance, resolve_node, Ngspice};
+use scir::NetlistLibConversion;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::sync::Arc;
+use substrate::schematic::conv::RawLib;
+use substrate::simulation::analysis::{self as common, InstanceRef, NodeRef};
+use substrate::simulation::data::{Save, SaveOutput};
+use substrate::simulation::{Analysis, SimulationContext, Simulator, SupportedBy};
+use substrate::units::Frequency;
+
+/// A small-signal noise analysis.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Noise {
+    /// The node at which output noise is measured.
+    pub output: NodeRef,
+    /// The node with respect to which output noise is measured.
+    ///
+    /// Defaults to ground.
+    pub reference: Option<NodeRef>,
+    /// The independent source to which noise is referred.
+    ///
+    /// Must be a top-level voltage or current source.
+    pub input_source: InstanceRef,
+    /// Start frequency.
+    pub start: Frequency,
+    /// Stop frequency.
+    pub stop: Frequency,
+    /// The sweep kind and number of points.
+    pub sweep: Sweep,
+}
+
+/// The result of a [`Noise`] analysis.
+#[derive(Debug, Clone)]
+pub struct Output {
+    /// The frequency points of the noise simulation.
+    pub freq: Arc<Vec<f64>>,
+    /// The output noise spectral density, in units per square root hertz.
+    pub output_noise: Arc<Vec<f64>>,
+    /// The input-referred noise spectral density, in units per square root hertz.
+    pub input_noise: Arc<Vec<f64>>,
+}
+
+impl Noise {
+    /// Resolves the Substrate paths referenced by this analysis to raw (netlisted) names.
+    pub(crate) fn resolve(
+        &mut self,
+        lib: &RawLib<Ngspice>,
+        conv: &NetlistLibConversion,
+    ) -> Result<()> {
+        resolve_node(lib, conv, &mut self.output)?;
+        if let Some(reference) = &mut self.reference {
+            resolve_node(lib, conv, reference)?;
+        }
+        resolve_instance(lib, conv, &mut self.input_source)
+    }
+
+    /// Writes the `.noise` statement.
+    ///
+    /// Returns an error if this analysis has not been [resolved](Noise::resolve).
+    pub(crate) fn netlist<W: Write>(&self, out: &mut W) -> Result<()> {
+        write!(out, ".noise v({}", raw_node(&self.output)?)?;
+        if let Some(reference) = &self.reference {
+            write!(out, ",{}", raw_node(reference)?)?;
+        }
+        write!(out, ") {} ", raw_instance(&self.input_source)?)?;
+        self.sweep.netlist(out)?;
+        write!(out, " {} {}", self.start.value(), self.stop.value())?;
+        Ok(())
+    }
+}
+
+impl Analysis for Noise {
+    type Output = Output;
+}
+
+impl SupportedBy<Ngspice> for Noise {
+    fn into_input(self, inputs: &mut Vec<<Ngspice as Simulator>::Input>) {
+        inputs.push(self.into());
+    }
+    fn from_output(
+        outputs: &mut impl Iterator<Item = <Ngspice as Simulator>::Output>,
+    ) -> <Self as Analysis>::Output {
+        let item = outputs.next().unwrap();
+        item.try_into().unwrap()
+    }
+}
+
+impl Save<Ngspice, Noise> for SaveOutput {
+    type SaveKey = ();
+    type Saved = Output;
+
+    fn save(
+        &self,
+        _ctx: &SimulationContext<Ngspice>,
+        _opts: &mut <Ngspice as Simulator>::Options,
+    ) -> <Self as Save<Ngspice, Noise>>::SaveKey {
+    }
+
+    fn from_saved(
+        output: &<Noise as Analysis>::Output,
+        _key: &<Self as Save<Ngspice, Noise>>::SaveKey,
+    ) -> <Self as Save<Ngspice, Noise>>::Saved {
+        output.clone()
+    }
+}
+
+impl Save<Ngspice, common::Noise> for SaveOutput {
+    type SaveKey = ();
+    type Saved = common::NoiseOutput;
+
+    fn save(
+        &self,
+        _ctx: &SimulationContext<Ngspice>,
+        _opts: &mut <Ngspice as Simulator>::Options,
+    ) -> <Self as Save<Ngspice, common::Noise>>::SaveKey {
+    }
+
+    fn from_saved(
+        output: &<common::Noise as Analysis>::Output,
+        _key: &<Self as Save<Ngspice, common::Noise>>::SaveKey,
+    ) -> <Self as Save<Ngspice, common::Noise>>::Saved {
+        output.clone()
+    }
+}
+
+impl From<common::Noise> for Noise {
+    fn from(value: common::Noise) -> Self {
+        Self {
+            output: value.output,
+            reference: None,
+            input_source: value.input_source,
+            start: value.start,
+            stop: value.stop,
+            sweep: value.sweep.into(),
+        }
+    }
+}
+
+impl SupportedBy<Ngspice> for common::Noise {
+    fn into_input(self, inputs: &mut Vec<<Ngspice as Simulator>::Input>) {
+        Noise::from(self).into_input(inputs);
+    }
+    fn from_output(
+        outputs: &mut impl Iterator<Item = <Ngspice as Simulator>::Output>,
+    ) -> <Self as Analysis>::Output {
+        let output = Noise::from_output(outputs);
+        common::NoiseOutput {
+            freq: output.freq,
+            output_noise: output.output_noise,
+            input_noise: output.input_noise,
+        }
+    }
+}
@@ -0,0 +1,232 @@
+//! ngspice sensitivity analysis options and data structures.
+
+use crate::ac::Ac;
+use crate::error::Result;
+use crate::tran::PathResolver;
+use crate::{raw_instance, raw_node, resolve_instance, resolve_node, Ngspice};
+use arcstr::ArcStr;
+use scir::NetlistLibConversion;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::Arc;
+use substrate::schematic::conv::RawLib;
+use substrate::schematic::InstancePath;
+use substrate::simulation::analysis::{InstanceRef, NodeRef};
+use substrate::simulation::data::SignalLookupError;
+use substrate::simulation::{Analysis, Simulator, SupportedBy};
+use substrate::types::schematic::{NestedNode, NodePath};
+
+pub use simulator_common::sens::SensValue;
+
+/// The output variable of a sensitivity analysis.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum SensOutput {
+    /// The voltage of a node.
+    Voltage {
+        /// The node whose voltage is measured.
+        node: NodeRef,
+        /// The node with respect to which the voltage is measured.
+        ///
+        /// Defaults to ground.
+        reference: Option<NodeRef>,
+    },
+    /// The current through an independent voltage source.
+    ///
+    /// The source must be a top-level instance.
+    Current(InstanceRef),
+}
+
+impl SensOutput {
+    /// The voltage of `node` with respect to ground.
+    pub fn voltage(node: impl Into<NodeRef>) -> Self {
+        Self::Voltage {
+            node: node.into(),
+            reference: None,
+        }
+    }
+
+    /// The voltage of `node` with respect to `reference`.
+    pub fn differential(node: impl Into<NodeRef>, reference: impl Into<NodeRef>) -> Self {
+        Self::Voltage {
+            node: node.into(),
+            reference: Some(reference.into()),
+        }
+    }
+
+    /// The current through the independent voltage source `source`.
+    pub fn current(source: impl Into<InstanceRef>) -> Self {
+        Self::Current(source.into())
+    }
+}
+
+impl From<NodeRef> for SensOutput {
+    fn from(value: NodeRef) -> Self {
+        Self::voltage(value)
+    }
+}
+
+impl From<NodePath> for SensOutput {
+    fn from(value: NodePath) -> Self {
+        Self::voltage(value)
+    }
+}
+
+impl From<&NestedNode> for SensOutput {
+    fn from(value: &NestedNode) -> Self {
+        Self::voltage(value)
+    }
+}
+
+/// A sensitivity analysis.
+///
+/// Computes the sensitivity of a single output variable to every device parameter,
+/// either at the DC operating point or across an AC sweep.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Sens {
+    /// The output variable.
+    pub output: SensOutput,
+    /// The AC sweep across which to compute sensitivities.
+    ///
+    /// If [`None`], sensitivities are computed at the DC operating point.
+    pub ac: Option<Ac>,
+}
+
+impl Sens {
+    /// Computes the DC sensitivities of `output`.
+    pub fn dc(output: impl Into<SensOutput>) -> Self {
+        Self {
+            output: output.into(),
+            ac: None,
+        }
+    }
+
+    /// Computes the AC sensitivities of `output` across the given sweep.
+    pub fn ac(output: impl Into<SensOutput>, ac: Ac) -> Self {
+        Self {
+            output: output.into(),
+            ac: Some(ac),
+        }
+    }
+
+    /// Resolves the Substrate paths referenced by this analysis to raw (netlisted) names.
+    pub(crate) fn resolve(
+        &mut self,
+        lib: &RawLib<Ngspice>,
+        conv: &NetlistLibConversion,
+    ) -> Result<()> {
+        match &mut self.output {
+            SensOutput::Voltage { node, reference } => {
+                resolve_node(lib, conv, node)?;
+                if let Some(reference) = reference {
+                    resolve_node(lib, conv, reference)?;
+                }
+                Ok(())
+            }
+            SensOutput::Current(source) => resolve_instance(lib, conv, source),
+        }
+    }
+
+    /// Writes the `.sens` statement.
+    ///
+    /// Returns an error if this analysis has not been [resolved](Sens::resolve).
+    pub(crate) fn netlist<W: Write>(&self, out: &mut W) -> Result<()> {
+        match &self.output {
+            SensOutput::Voltage { node, reference } => {
+                write!(out, ".sens v({}", raw_node(node)?)?;
+                if let Some(reference) = reference {
+                    write!(out, ",{}", raw_node(reference)?)?;
+                }
+                write!(out, ")")?;
+            }
+            SensOutput::Current(source) => write!(out, ".sens i({})", raw_instance(source)?)?,
+        }
+        if let Some(ac) = &self.ac {
+            write!(out, " ac ")?;
+            ac.sweep.netlist(out)?;
+            write!(out, " {} {}", ac.start.value(), ac.stop.value())?;
+        }
+        Ok(())
+    }
+}
+
+/// The result of a [`Sens`] analysis.
+#[derive(Debug, Clone)]
+pub struct Output {
+    /// The frequency points of the AC sweep, if sensitivities were computed across one.
+    pub freq: Option<Arc<Vec<f64>>>,
+    /// A map from raw parameter name to sensitivity.
+    ///
+    /// ngspice names the sensitivity to the principal parameter of a device (e.g. the
+    /// resistance of a resistor) after the device itself, and the sensitivities to other
+    /// parameters `{device}_{param}`.
+    pub raw_values: HashMap<ArcStr, SensValue>,
+    /// The netlist metadata used to resolve hierarchical paths.
+    ///
+    /// [`None`] if the output was not produced by a simulation.
+    pub(crate) resolver: Option<PathResolver>,
+}
+
+impl Output {
+    /// Returns the sensitivity to the parameter with the given raw name.
+    pub fn raw(&self, name: &str) -> std::result::Result<&SensValue, SignalLookupError> {
+        self.raw_values
+            .get(name)
+            .ok_or_else(|| SignalLookupError::not_found(name, self.raw_values.keys()))
+    }
+
+    /// Returns the raw (netlisted) name of the Substrate instance at `path`.
+    fn instance_name(&self, path: &InstancePath) -> std::result::Result<String, SignalLookupError> {
+        let unresolved = || SignalLookupError::Unresolved(format!("{path:?}"));
+        let resolver = self.resolver.as_ref().ok_or_else(unresolved)?;
+        let scir = resolver
+            .lib
+            .convert_instance_path(path)
+            .ok_or_else(unresolved)?;
+        Ok(crate::instance_path(&resolver.lib.scir, &resolver.conv, &scir).to_lowercase())
+    }
+
+    /// Returns the sensitivity to the principal parameter of the Substrate instance at `path`.
+    ///
+    /// Paths are typically obtained using [`NestedInstance::path`](substrate::schematic::NestedInstance::path).
+    pub fn instance(
+        &self,
+        path: &InstancePath,
+    ) -> std::result::Result<&SensValue, SignalLookupError> {
+        self.raw(&self.instance_name(path)?)
+    }
+
+    /// Returns the sensitivity to parameter `param` of the Substrate instance at `path`.
+    pub fn instance_param(
+        &self,
+        path: &InstancePath,
+        param: &str,
+    ) -> std::result::Result<&SensValue, SignalLookupError> {
+        self.raw(&format!(
+            "{}_{}",
+            self.instance_name(path)?,
+            param.to_lowercase()
+        ))
+    }
+
+    /// Returns all sensitivities, ordered from largest to smallest magnitude.
+    pub fn dominant(&self) -> Vec<(&ArcStr, &SensValue)> {
+        simulator_common::sens::dominant(&self.raw_values)
+    }
+}
+
+impl Analysis for Sens {
+    type Output = Output;
+}
+
+impl SupportedBy<Ngspice> for Sens {
+    fn into_input(self, inputs: &mut Vec<<Ngspice as Simulator>::Input>) {
+        inputs.push(self.into());
+    }
+    fn from_output(
+        outputs: &mut impl Iterator<Item = <Ngspice as Simulator>::Output>,
+    ) -> <Self as Analysis>::Output {
+        let item = outputs.next().unwrap();
+        item.try_into().unwrap()
+    }
+}
@@ -2,23 +2,19 @@ use std::path::{Path, PathBuf};
 
 use lazy_static::lazy_static;
 use serde::Serialize;
-use tera::{Context, Tera};
+use simulator_common::script::{load_templates, write_script};
+use tera::Tera;
 
 pub(crate) const TEMPLATES_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/templates");
 
 lazy_static! {
-    pub(crate) static ref TEMPLATES: Tera = {
-        match Tera::new(&format!("{TEMPLATES_PATH}/*")) {
-            Ok(t) => t,
-            Err(e) => {
-                panic!("Encountered errors while parsing Tera templates: {e}");
-            }
-        }
-    };
+    pub(crate) static ref TEMPLATES: Tera = load_templates(TEMPLATES_PATH);
 }
 
 #[derive(Debug, Copy, Clone, Serialize)]
 pub(crate) struct RunScriptContext<'a> {
+    /// The shell-quoted command used to invoke ngspice.
+    pub(crate) executable: &'a str,
     pub(crate) netlist: &'a PathBuf,
     pub(crate) raw_output_file: &'a PathBuf,
     pub(crate) log_path: &'a PathBuf,
@@ -27,13 +23,10 @@ pub(crate) struct RunScriptContext<'a> {
     pub(crate) flags: &'a str,
 }
 
+/// Writes an executable run script to `path`.
 pub(crate) fn write_run_script(
     ctx: RunScriptContext,
     path: impl AsRef<Path>,
 ) -> crate::error::Result<()> {
-    let ctx = Context::from_serialize(ctx)?;
-    let mut f = std::fs::File::create(path.as_ref())?;
-    TEMPLATES.render_to("simulate.sh", &ctx, &mut f)?;
-
-    Ok(())
+    write_script(&TEMPLATES, "simulate.sh", &ctx, path)
 }
@@ -1,18 +1,26 @@
 use std::path::PathBuf;
 
 use crate::blocks::Vsource;
+use crate::dc::{DcOp, OpOutput};
 use crate::tran::Tran;
-use crate::{Ngspice, Options};
+use crate::{Ngspice, Options, SaveStmt};
 use approx::relative_eq;
+use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
+use scir::{NamedSliceOne, SliceOnePath};
 use serde::{Deserialize, Serialize};
 use spice::Resistor;
 use substrate::block::Block;
 use substrate::context::Context;
-use substrate::schematic::{CellBuilder, ConvertSchema, NestedData, Schematic};
+use substrate::schematic::conv::ConvertedNodePath;
+use substrate::schematic::{CellBuilder, ConvertSchema, Instance, NestedData, Schematic};
+use substrate::simulation::batch::BatchSim;
+use substrate::simulation::chain::Then;
+use substrate::simulation::data::SignalLookupError;
 use substrate::simulation::waveform::TimeWaveform;
-use substrate::types::schematic::Terminal;
-use substrate::types::{Signal, TestbenchIo};
+use substrate::types::schematic::{Node, Terminal};
+use substrate::types::{Signal, TestbenchIo, TwoTerminalIo};
+use substrate::units::{Frequency, Time, Voltage};
 
 const BUILD_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/build");
 
@@ -25,74 +33,304 @@ fn ngspice_ctx() -> Context {
     Context::builder().install(Ngspice::default()).build()
 }
 
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize, Block)]
+#[substrate(io = "TestbenchIo")]
+struct ResistorTb;
+
+#[derive(NestedData)]
+struct ResistorTbData {
+    r1: Terminal,
+    r2: Terminal,
+    r3: Terminal,
+}
+
+impl Schematic for ResistorTb {
+    type Schema = Ngspice;
+    type NestedData = ResistorTbData;
+    fn schematic(
+        &self,
+        io: &substrate::types::schematic::IoNodeBundle<Self>,
+        cell: &mut CellBuilder<<Self as Schematic>::Schema>,
+    ) -> substrate::error::Result<Self::NestedData> {
+        let vdd = cell.signal("vdd", Signal);
+        let r1 = cell.instantiate(ConvertSchema::<_, Ngspice>::new(Resistor::new(dec!(100))));
+        let r2 = cell.instantiate(ConvertSchema::<_, Ngspice>::new(Resistor::new(dec!(100))));
+        let r3 = cell.instantiate(ConvertSchema::<_, Ngspice>::new(Resistor::new(dec!(100))));
+
+        cell.connect(r1.io().p, vdd);
+        cell.connect(r1.io().n, r2.io().p);
+        cell.connect(r2.io().n, io.vss);
+        cell.connect(r1.io().n, r3.io().p);
+        cell.connect(r3.io().n, io.vss);
+
+        let vsource = cell.instantiate(Vsource::dc(Voltage::new(dec!(1.8))));
+        cell.connect(vsource.io().p, vdd);
+        cell.connect(vsource.io().n, io.vss);
+
+        Ok(ResistorTbData {
+            r1: r1.io().p,
+            r2: r2.io().p,
+            r3: r3.io().p,
+        })
+    }
+}
+
 #[test]
 fn ngspice_can_save_voltages_and_currents() {
+    let test_name = "ngspice_can_save_voltages_and_currents";
+    let sim_dir = get_path(test_name, "sim/");
+    let ctx = ngspice_ctx();
+    let sim = ctx
+        .get_sim_controller(ResistorTb, sim_dir)
+        .expect("failed to get sim controller");
+
+    let output = sim
+        .simulate(
+            Options::default(),
+            Tran {
+                step: Time::new(dec!(2e-10)),
+                stop: Time::new(dec!(2e-9)),
+                ..Default::default()
+            },
+        )
+        .expect("failed to run simulation");
+
+    for (actual, expected) in [
+        (&output.r1.i, 1.8 / 150.),
+        (&output.r2.i, 1.8 / 300.),
+        (&output.r3.i, 1.8 / 300.),
+        (&output.r2.v, 1.8 / 3.),
+    ] {
+        actual.values().for_each(|pt| {
+            let val = pt.x();
+            assert!(
+                relative_eq!(val, expected),
+                "found {val}, expected {expected}"
+            )
+        });
+    }
+}
+
+#[test]
+fn ngspice_can_look_up_waveforms_by_path() {
+    let test_name = "ngspice_can_look_up_waveforms_by_path";
+    let sim_dir = get_path(test_name, "sim/");
+    let ctx = ngspice_ctx();
+    let sim = ctx
+        .get_sim_controller(ResistorTb, sim_dir)
+        .expect("failed to get sim controller");
+
+    let r2 = sim.tb.data().r2.as_ref().path();
+    let lib = ctx.export_scir(ResistorTb).expect("failed to export SCIR");
+    let mut opts = Options::default();
+    opts.save_tran_voltage(SaveStmt::ScirVoltage(
+        match lib.convert_node_path(&r2).unwrap() {
+            ConvertedNodePath::Cell(path) => path,
+            ConvertedNodePath::Primitive {
+                instances, port, ..
+            } => SliceOnePath::new(instances, NamedSliceOne::new(port)),
+        },
+    ));
+    let output = sim
+        .simulate_default(
+            opts,
+            Tran {
+                step: Time::new(dec!(2e-10)),
+                stop: Time::new(dec!(2e-9)),
+                ..Default::default()
+            },
+        )
+        .expect("failed to run simulation");
+    output
+        .node_voltage(&r2)
+        .expect("failed to look up voltage")
+        .values()
+        .for_each(|pt| assert!(relative_eq!(pt.x(), 1.8 / 3.)));
+    assert!(matches!(
+        output.raw_waveform("missing"),
+        Err(SignalLookupError::NotFound { .. })
+    ));
+}
+
+#[test]
+fn ngspice_resolves_voltages_of_merged_nodes() {
+    #[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize, Block)]
+    #[substrate(io = "TwoTerminalIo")]
+    struct Divider;
+
+    impl Schematic for Divider {
+        type Schema = Ngspice;
+        type NestedData = ();
+        fn schematic(
+            &self,
+            io: &substrate::types::schematic::IoNodeBundle<Self>,
+            cell: &mut CellBuilder<<Self as Schematic>::Schema>,
+        ) -> substrate::error::Result<Self::NestedData> {
+            let r1 = cell.instantiate(ConvertSchema::<_, Ngspice>::new(Resistor::new(dec!(100))));
+            let r2 = cell.instantiate(ConvertSchema::<_, Ngspice>::new(Resistor::new(dec!(100))));
+            cell.connect(r1.io().p, io.p);
+            cell.connect(r1.io().n, r2.io().p);
+            cell.connect(r2.io().n, io.n);
+            Ok(())
+        }
+    }
+
     #[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize, Block)]
     #[substrate(io = "TestbenchIo")]
-    struct ResistorTb;
+    struct DividerTb;
 
     #[derive(NestedData)]
-    struct ResistorTbData {
-        r1: Terminal,
-        r2: Terminal,
-        r3: Terminal,
+    struct DividerTbData {
+        vdd: Node,
+        dut_p: Terminal,
     }
 
-    impl Schematic for ResistorTb {
+    impl Schematic for DividerTb {
         type Schema = Ngspice;
-        type NestedData = ResistorTbData;
+        type NestedData = DividerTbData;
         fn schematic(
             &self,
             io: &substrate::types::schematic::IoNodeBundle<Self>,
             cell: &mut CellBuilder<<Self as Schematic>::Schema>,
         ) -> substrate::error::Result<Self::NestedData> {
             let vdd = cell.signal("vdd", Signal);
+            let dut = cell.instantiate(Divider);
+            cell.connect(dut.io().p, vdd);
+            cell.connect(dut.io().n, io.vss);
+
+            let vsource = cell.instantiate(Vsource::dc(Voltage::new(dec!(1.8))));
+            cell.connect(vsource.io().p, vdd);
+            cell.connect(vsource.io().n, io.vss);
+            Ok(DividerTbData {
+                vdd,
+                dut_p: dut.io().p,
+            })
+        }
+    }
+
+    let test_name = "ngspice_resolves_voltages_of_merged_nodes";
+    let sim_dir = get_path(test_name, "sim/");
+    let ctx = ngspice_ctx();
+    let sim = ctx
+        .get_sim_controller(DividerTb, sim_dir)
+        .expect("failed to get sim controller");
+
+    // Only the testbench node is saved, but the port of the divider it connects to
+    // can be looked up as well.
+    let lib = ctx.export_scir(DividerTb).expect("failed to export SCIR");
+    let vdd = sim.tb.data().vdd.path();
+    let dut_p = sim.tb.data().dut_p.as_ref().path();
+    let mut opts = Options::default();
+    opts.save_tran_voltage(SaveStmt::ScirVoltage(
+        match lib.convert_node_path(&vdd).unwrap() {
+            ConvertedNodePath::Cell(path) => path,
+            ConvertedNodePath::Primitive { .. } => panic!("expected a cell node"),
+        },
+    ));
+    let output = sim
+        .simulate_default(
+            opts,
+            Tran {
+                step: Time::new(dec!(2e-10)),
+                stop: Time::new(dec!(2e-9)),
+                ..Default::default()
+            },
+        )
+        .expect("failed to run simulation");
+
+    let aliases = output.aliases().expect("simulation output has no aliases");
+    assert!(!aliases.is_empty());
+    for (alias, name) in aliases.iter() {
+        assert!(!output.raw_values.contains_key(alias));
+        assert_eq!(output.raw_waveform(alias), output.raw_waveform(name));
+    }
+    for path in [&vdd, &dut_p] {
+        output
+            .node_voltage(path)
+            .expect("failed to look up voltage")
+            .values()
+            .for_each(|pt| assert!(relative_eq!(pt.x(), 1.8)));
+    }
+}
+
+#[test]
+fn ngspice_can_save_subcircuit_terminal_currents_with_ammeters() {
+    #[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize, Block)]
+    #[substrate(io = "TwoTerminalIo")]
+    struct Divider;
+
+    #[derive(NestedData)]
+    struct DividerData {
+        r2: Terminal,
+    }
+
+    impl Schematic for Divider {
+        type Schema = Ngspice;
+        type NestedData = DividerData;
+        fn schematic(
+            &self,
+            io: &substrate::types::schematic::IoNodeBundle<Self>,
+            cell: &mut CellBuilder<<Self as Schematic>::Schema>,
+        ) -> substrate::error::Result<Self::NestedData> {
             let r1 = cell.instantiate(ConvertSchema::<_, Ngspice>::new(Resistor::new(dec!(100))));
             let r2 = cell.instantiate(ConvertSchema::<_, Ngspice>::new(Resistor::new(dec!(100))));
-            let r3 = cell.instantiate(ConvertSchema::<_, Ngspice>::new(Resistor::new(dec!(100))));
-
-            cell.connect(r1.io().p, vdd);
+            cell.connect(r1.io().p, io.p);
             cell.connect(r1.io().n, r2.io().p);
-            cell.connect(r2.io().n, io.vss);
-            cell.connect(r1.io().n, r3.io().p);
-            cell.connect(r3.io().n, io.vss);
+            cell.connect(r2.io().n, io.n);
+            Ok(DividerData { r2: r2.io().p })
+        }
+    }
+
+    #[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize, Block)]
+    #[substrate(io = "TestbenchIo")]
+    struct DividerTb;
+
+    #[derive(NestedData)]
+    struct DividerTbData {
+        dut: Instance<Divider>,
+    }
+
+    impl Schematic for DividerTb {
+        type Schema = Ngspice;
+        type NestedData = DividerTbData;
+        fn schematic(
+            &self,
+            io: &substrate::types::schematic::IoNodeBundle<Self>,
+            cell: &mut CellBuilder<<Self as Schematic>::Schema>,
+        ) -> substrate::error::Result<Self::NestedData> {
+            let vdd = cell.signal("vdd", Signal);
+            let dut = cell.instantiate(Divider);
+            cell.connect(dut.io().p, vdd);
+            cell.connect(dut.io().n, io.vss);
 
-            let vsource = cell.instantiate(Vsource::dc(dec!(1.8)));
+            let vsource = cell.instantiate(Vsource::dc(Voltage::new(dec!(1.8))));
             cell.connect(vsource.io().p, vdd);
             cell.connect(vsource.io().n, io.vss);
-
-            Ok(ResistorTbData {
-                r1: r1.io().p,
-                r2: r2.io().p,
-                r3: r3.io().p,
-            })
+            Ok(DividerTbData { dut })
         }
     }
 
-    let test_name = "ngspice_can_save_voltages_and_currents";
+    let test_name = "ngspice_can_save_subcircuit_terminal_currents_with_ammeters";
     let sim_dir = get_path(test_name, "sim/");
     let ctx = ngspice_ctx();
     let sim = ctx
-        .get_sim_controller(ResistorTb, sim_dir)
+        .get_sim_controller(DividerTb, sim_dir)
         .expect("failed to get sim controller");
 
+    let mut opts = Options::default();
+    opts.insert_ammeters(true);
     let output = sim
         .simulate(
-            Options::default(),
+            opts,
             Tran {
-                step: dec!(2e-10),
-                stop: dec!(2e-9),
+                step: Time::new(dec!(2e-10)),
+                stop: Time::new(dec!(2e-9)),
                 ..Default::default()
             },
         )
         .expect("failed to run simulation");
 
-    for (actual, expected) in [
-        (&output.r1.i, 1.8 / 150.),
-        (&output.r2.i, 1.8 / 300.),
-        (&output.r3.i, 1.8 / 300.),
-        (&output.r2.v, 1.8 / 3.),
-    ] {
+    for (actual, expected) in [(&output.dut.r2.i, 1.8 / 200.), (&output.dut.r2.v, 0.9)] {
         actual.values().for_each(|pt| {
             let val = pt.x();
             assert!(
@@ -102,3 +340,396 @@ fn ngspice_can_save_voltages_and_currents() {
         });
     }
 }
+
+#[test]
+fn ngspice_log_names_are_split() {
+    use crate::log::{error_messages, split_name};
+
+    assert_eq!(
+        split_name("xdut.xr1:p"),
+        (vec!["xdut".to_string(), "xr1".to_string()], None)
+    );
+    assert_eq!(
+        split_name("xdut.out[3]"),
+        (vec!["xdut".to_string(), "out".to_string()], Some(3))
+    );
+    assert_eq!(split_name("vdd"), (vec!["vdd".to_string()], None));
+
+    let log = "Circuit: test\nWarning: singular matrix:  check node xdut.int\nError: singular matrix:  check node xdut.out\n\ntran simulation(s) aborted\n";
+    assert_eq!(
+        error_messages(log),
+        vec!["Error: singular matrix:  check node xdut.out".to_string()]
+    );
+}
+
+#[test]
+fn netlist_ngspice_common_analyses() {
+    use substrate::simulation::analysis::{self as common, Ac, Dc, Noise, Sweep};
+    use substrate::simulation::SupportedBy;
+
+    let mut inputs = Vec::new();
+    SupportedBy::<Ngspice>::into_input(common::Tran::new(Time::new(dec!(1e-9))), &mut inputs);
+    SupportedBy::<Ngspice>::into_input(
+        Ac {
+            start: Frequency::new(dec!(1)),
+            stop: Frequency::new(dec!(1e9)),
+            sweep: Sweep::Decade(10),
+        },
+        &mut inputs,
+    );
+    SupportedBy::<Ngspice>::into_input(Dc, &mut inputs);
+    SupportedBy::<Ngspice>::into_input(
+        Noise {
+            output: "out".into(),
+            input_source: "vin".into(),
+            start: Frequency::new(dec!(1)),
+            stop: Frequency::new(dec!(1e6)),
+            sweep: Sweep::Linear(100),
+        },
+        &mut inputs,
+    );
+
+    let netlists = inputs
+        .iter()
+        .map(|input| {
+            let mut buf = Vec::new();
+            input.netlist(&mut buf).unwrap();
+            String::from_utf8(buf).unwrap()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        netlists,
+        vec![
+            ".tran 0.000000000001 0.000000001",
+            ".ac dec 10 1 1000000000",
+            ".op",
+            ".noise v(out) vin lin 100 1 1000000",
+        ]
+    );
+}
+
+#[test]
+fn ngspice_noise_resolves_substrate_paths() {
+    use crate::error::Error;
+    use spice::netlist::{NetlistKind, NetlistOptions, NetlisterInstance, RenameGround};
+    use substrate::simulation::analysis::{self as common, Sweep};
+    use substrate::simulation::SupportedBy;
+
+    #[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize, Block)]
+    #[substrate(io = "TestbenchIo")]
+    struct NoiseTb;
+
+    #[derive(NestedData)]
+    struct NoiseTbData {
+        vout: Node,
+        vin: Instance<Vsource>,
+    }
+
+    impl Schematic for NoiseTb {
+        type Schema = Ngspice;
+        type NestedData = NoiseTbData;
+        fn schematic(
+            &self,
+            io: &substrate::types::schematic::IoNodeBundle<Self>,
+            cell: &mut CellBuilder<<Self as Schematic>::Schema>,
+        ) -> substrate::error::Result<Self::NestedData> {
+            let vout = cell.signal("vout", Signal);
+            let vin = cell.instantiate_named(Vsource::dc(Voltage::new(dec!(0))), "vin");
+            cell.connect(vin.io().n, io.vss);
+
+            let r = cell.instantiate(ConvertSchema::<_, Ngspice>::new(Resistor::new(dec!(100))));
+            cell.connect(r.io().p, vin.io().p);
+            cell.connect(r.io().n, vout);
+
+            Ok(NoiseTbData { vout, vin })
+        }
+    }
+
+    let ctx = ngspice_ctx();
+    let tb = ctx.generate_schematic(NoiseTb);
+    let data = tb.cell().data();
+    let lib = ctx.export_scir(NoiseTb).unwrap();
+    let conv = NetlisterInstance::new(
+        &Ngspice::default(),
+        &lib.scir,
+        &mut Vec::new(),
+        NetlistOptions::new(NetlistKind::Testbench(RenameGround::Yes("0".into())), &[]),
+    )
+    .export()
+    .unwrap();
+
+    let mut inputs = Vec::new();
+    SupportedBy::<Ngspice>::into_input(
+        common::Noise {
+            output: (&data.vout).into(),
+            input_source: data.vin.path().into(),
+            start: Frequency::new(dec!(1)),
+            stop: Frequency::new(dec!(1e6)),
+            sweep: Sweep::Decade(10),
+        },
+        &mut inputs,
+    );
+    let mut input = inputs.pop().unwrap();
+
+    // Paths must be resolved before netlisting.
+    assert!(matches!(
+        input.netlist(&mut Vec::new()),
+        Err(Error::UnresolvedPath(_))
+    ));
+
+    input.resolve(&lib, &conv).unwrap();
+    let mut netlist = Vec::new();
+    input.netlist(&mut netlist).unwrap();
+    assert_eq!(
+        String::from_utf8(netlist).unwrap(),
+        ".noise v(vout) Vvin dec 10 1 1000000"
+    );
+}
+
+#[test]
+fn ngspice_can_compute_dc_sensitivities() {
+    use crate::error::Error;
+    use crate::sens::{Sens, SensOutput};
+    use spice::netlist::{NetlistKind, NetlistOptions, NetlisterInstance, RenameGround};
+
+    #[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize, Block)]
+    #[substrate(io = "TestbenchIo")]
+    struct DividerTb;
+
+    #[derive(NestedData)]
+    struct DividerTbData {
+        out: Node,
+        vdd: Instance<Vsource>,
+    }
+
+    impl Schematic for DividerTb {
+        type Schema = Ngspice;
+        type NestedData = DividerTbData;
+        fn schematic(
+            &self,
+            io: &substrate::types::schematic::IoNodeBundle<Self>,
+            cell: &mut CellBuilder<<Self as Schematic>::Schema>,
+        ) -> substrate::error::Result<Self::NestedData> {
+            let vdd = cell.signal("vdd", Signal);
+            let out = cell.signal("out", Signal);
+            let r1 = cell.instantiate(ConvertSchema::<_, Ngspice>::new(Resistor::new(dec!(100))));
+            let r2 = cell.instantiate(ConvertSchema::<_, Ngspice>::new(Resistor::new(dec!(100))));
+            cell.connect(r1.io().p, vdd);
+            cell.connect(r1.io().n, out);
+            cell.connect(r2.io().p, out);
+            cell.connect(r2.io().n, io.vss);
+
+            let vsource = cell.instantiate_named(Vsource::dc(Voltage::new(dec!(1.8))), "vdd");
+            cell.connect(vsource.io().p, vdd);
+            cell.connect(vsource.io().n, io.vss);
+            Ok(DividerTbData { out, vdd: vsource })
+        }
+    }
+
+    let netlist = |sens: Sens| {
+        let mut buf = Vec::new();
+        crate::Input::from(sens)
+            .netlist(&mut buf)
+            .map(|_| String::from_utf8(buf).unwrap())
+    };
+    assert_eq!(
+        netlist(Sens::dc(SensOutput::voltage("out"))).unwrap(),
+        ".sens v(out)"
+    );
+    assert_eq!(
+        netlist(Sens::dc(SensOutput::differential("outp", "outn"))).unwrap(),
+        ".sens v(outp,outn)"
+    );
+
+    // Substrate paths are resolved to netlisted names.
+    let ctx = ngspice_ctx();
+    let tb = ctx.generate_schematic(DividerTb);
+    let data = tb.cell().data();
+    let lib = ctx.export_scir(DividerTb).unwrap();
+    let conv = NetlisterInstance::new(
+        &Ngspice::default(),
+        &lib.scir,
+        &mut Vec::new(),
+        NetlistOptions::new(NetlistKind::Testbench(RenameGround::Yes("0".into())), &[]),
+    )
+    .export()
+    .unwrap();
+    for (sens, expected) in [
+        (Sens::dc(&data.out), ".sens v(out)"),
+        (
+            Sens::dc(SensOutput::current(data.vdd.path())),
+            ".sens i(Vvdd)",
+        ),
+    ] {
+        let mut input = crate::Input::from(sens);
+        assert!(matches!(
+            input.netlist(&mut Vec::new()),
+            Err(Error::UnresolvedPath(_))
+        ));
+        input.resolve(&lib, &conv).unwrap();
+        let mut buf = Vec::new();
+        input.netlist(&mut buf).unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap(), expected);
+    }
+
+    let test_name = "ngspice_can_compute_dc_sensitivities";
+    let sim_dir = get_path(test_name, "sim/");
+    let sim = ctx
+        .get_sim_controller(DividerTb, &sim_dir)
+        .expect("failed to get sim controller");
+    let out = &sim.tb.data().out;
+    let sens = sim
+        .simulate_default(Options::default(), Sens::dc(out))
+        .expect("failed to run simulation");
+
+    // The output is most sensitive to the supply voltage, followed by the two resistors,
+    // whose sensitivities are equal and opposite.
+    let dominant = sens
+        .dominant()
+        .into_iter()
+        .map(|(_, value)| value.dc())
+        .collect::<Vec<_>>();
+    assert!(relative_eq!(dominant[0], 0.5, max_relative = 1e-6));
+    assert!(relative_eq!(dominant[1].abs(), 4.5e-3, max_relative = 1e-6));
+    assert!(relative_eq!(dominant[1], -dominant[2], max_relative = 1e-6));
+}
+
+#[test]
+fn ngspice_can_chain_dependent_analyses() {
+    #[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize, Block)]
+    #[substrate(io = "TestbenchIo")]
+    struct DividerTb;
+
+    impl Schematic for DividerTb {
+        type Schema = Ngspice;
+        type NestedData = ();
+        fn schematic(
+            &self,
+            io: &substrate::types::schematic::IoNodeBundle<Self>,
+            cell: &mut CellBuilder<<Self as Schematic>::Schema>,
+        ) -> substrate::error::Result<Self::NestedData> {
+            let vdd = cell.signal("vdd", Signal);
+            let r1 = cell.instantiate(ConvertSchema::<_, Ngspice>::new(Resistor::new(dec!(100))));
+            let r2 = cell.instantiate(ConvertSchema::<_, Ngspice>::new(Resistor::new(dec!(100))));
+            cell.connect(r1.io().p, vdd);
+            cell.connect(r1.io().n, r2.io().p);
+            cell.connect(r2.io().n, io.vss);
+
+            let vsource = cell.instantiate(Vsource::dc(Voltage::new(dec!(1.8))));
+            cell.connect(vsource.io().p, vdd);
+            cell.connect(vsource.io().n, io.vss);
+            Ok(())
+        }
+    }
+
+    let test_name = "ngspice_can_chain_dependent_analyses";
+    let sim_dir = get_path(test_name, "sim/");
+    let ctx = ngspice_ctx();
+    let sim = ctx
+        .get_sim_controller(DividerTb, &sim_dir)
+        .expect("failed to get sim controller");
+
+    // Run the transient analysis for 1ns per volt of the highest operating point voltage.
+    let chain = Then::new(DcOp, |op: &OpOutput, _: &mut Options| {
+        let vmax = op.raw_values.values().copied().fold(f64::MIN, f64::max);
+        Tran {
+            step: Time::new(dec!(1e-10)),
+            stop: Time::new(Decimal::try_from(vmax).unwrap() * dec!(1e-9)),
+            ..Default::default()
+        }
+    });
+    let (_, tran) = sim
+        .simulate_chain(Options::default(), chain)
+        .expect("failed to run simulation");
+
+    let stop = tran.time.last().copied().expect("no transient time points");
+    assert!(relative_eq!(stop, 1.8e-9, max_relative = 1e-6));
+    assert!(sim_dir.join("stage0").is_dir());
+    assert!(sim_dir.join("stage1").is_dir());
+}
+
+#[test]
+fn ngspice_can_simulate_batches() {
+    #[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize, Block)]
+    #[substrate(io = "TestbenchIo")]
+    struct DividerTb {
+        vdd: Decimal,
+    }
+
+    impl Schematic for DividerTb {
+        type Schema = Ngspice;
+        type NestedData = Node;
+        fn schematic(
+            &self,
+            io: &substrate::types::schematic::IoNodeBundle<Self>,
+            cell: &mut CellBuilder<<Self as Schematic>::Schema>,
+        ) -> substrate::error::Result<Self::NestedData> {
+            let vdd = cell.signal("vdd", Signal);
+            let out = cell.signal("out", Signal);
+            let r1 = cell.instantiate(ConvertSchema::<_, Ngspice>::new(Resistor::new(dec!(100))));
+            let r2 = cell.instantiate(ConvertSchema::<_, Ngspice>::new(Resistor::new(dec!(100))));
+            cell.connect(r1.io().p, vdd);
+            cell.connect(r1.io().n, out);
+            cell.connect(r2.io().p, out);
+            cell.connect(r2.io().n, io.vss);
+
+            let vsource = cell.instantiate(Vsource::dc(Voltage::new(self.vdd)));
+            cell.connect(vsource.io().p, vdd);
+            cell.connect(vsource.io().n, io.vss);
+            Ok(out)
+        }
+    }
+
+    let test_name = "ngspice_can_simulate_batches";
+    let ctx = Context::builder()
+        .install(Ngspice::default())
+        .simulation_parallelism(2)
+        .build();
+
+    let supplies = [dec!(1.0), dec!(1.2), dec!(1.5), dec!(1.8)];
+    let outputs = ctx
+        .simulate_batch(supplies.iter().enumerate().map(|(i, &vdd)| {
+            BatchSim::new(
+                DividerTb { vdd },
+                get_path(test_name, &format!("sim{i}/")),
+                Options::default(),
+                Tran {
+                    step: Time::new(dec!(2e-10)),
+                    stop: Time::new(dec!(2e-9)),
+                    ..Default::default()
+                },
+            )
+        }))
+        .expect("failed to generate testbenches");
+
+    assert_eq!(outputs.len(), supplies.len());
+    for (vdd, output) in supplies.iter().zip(outputs) {
+        let expected = f64::try_from(*vdd).unwrap() / 2.;
+        output
+            .expect("failed to run simulation")
+            .values()
+            .for_each(|pt| assert!(relative_eq!(pt.x(), expected)));
+    }
+}
+
+#[test]
+fn ngspice_trnoise_sources_follow_transient_noise_options() {
+    use crate::blocks::TrNoise;
+    use substrate::simulation::options::TransientNoise;
+
+    let noise = TransientNoise::new(dec!(1e9))
+        .unwrap()
+        .seed(42)
+        .scale(dec!(2));
+    assert!(TransientNoise::new(dec!(0)).is_err());
+    let trnoise = TrNoise::white(dec!(1e-3), &noise);
+    assert_eq!(trnoise.rms, dec!(2e-3));
+    assert_eq!(trnoise.step, Time::new(dec!(5e-10)));
+
+    let mut buf = Vec::new();
+    trnoise.netlist(&mut buf).unwrap();
+    assert_eq!(
+        String::from_utf8(buf).unwrap(),
+        " TRNOISE(0.002 0.0000000005 0 0)"
+    );
+}
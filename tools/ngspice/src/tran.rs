@@ -1,66 +1,47 @@
 //! ngspice transient analysis options and data structures.
 
-use crate::{InstanceTail, Ngspice, ProbeStmt, SaveStmt};
+use crate::{InstanceTail, Ngspice, ProbeStmt, SaveStmt, SavedData};
 use arcstr::ArcStr;
-use rust_decimal::Decimal;
-use scir::{NamedSliceOne, SliceOnePath};
+use scir::{Library, NamedSliceOne, NetlistLibConversion, SliceOnePath};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use simulator_common::tran::VoltageNames;
 use std::sync::Arc;
 use substrate::schematic::conv::ConvertedNodePath;
+use substrate::simulation::analysis as common;
 use substrate::simulation::data::{Save, SaveOutput, SaveTime};
-use substrate::simulation::waveform::{TimePoint, TimeWaveform, WaveformRef};
 use substrate::simulation::{Analysis, SimulationContext, Simulator, SupportedBy};
 use substrate::types::schematic::{NestedNode, NestedTerminal, RawNestedNode};
+use substrate::units::Time;
+
+pub use simulator_common::tran::OutputWaveform;
+pub use substrate::simulation::data::{CurrentSaveKey, VoltageSaveKey};
 
 /// A transient analysis.
 #[derive(Clone, Default, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Tran {
-    /// Suggested computing increment (sec).
-    pub step: Decimal,
-    /// Stop time (sec).
-    pub stop: Decimal,
-    /// Start time (sec).
+    /// Suggested computing increment.
+    pub step: Time,
+    /// Stop time.
+    pub stop: Time,
+    /// Start time.
     ///
     /// Defaults to 0.
-    pub start: Option<Decimal>,
+    pub start: Option<Time>,
 }
 
 /// The result of a transient analysis.
-#[derive(Debug, Clone)]
-pub struct Output {
-    /// The time points of the transient simulation.
-    pub time: Arc<Vec<f64>>,
-    /// A map from signal name to values.
-    pub raw_values: HashMap<ArcStr, Arc<Vec<f64>>>,
-    /// A map from a save ID to a raw value identifier.
-    pub(crate) saved_values: HashMap<u64, ArcStr>,
-}
-
-/// An output transient waveform.
-#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
-pub struct OutputWaveform {
-    /// Time samples.
-    pub t: Arc<Vec<f64>>,
-    /// Values corresponding to time samples in `t`.
-    pub x: Arc<Vec<f64>>,
-}
+pub type Output = simulator_common::tran::Output<Ngspice>;
 
-impl OutputWaveform {
-    /// Converts an [`OutputWaveform`] to a [`WaveformRef`].
-    pub fn as_ref(&self) -> WaveformRef<'_, f64> {
-        WaveformRef::new(self.t.as_ref(), self.x.as_ref())
-    }
-}
-
-impl TimeWaveform for OutputWaveform {
-    type Data = f64;
-    fn get(&self, idx: usize) -> Option<TimePoint<f64>> {
-        self.as_ref().get(idx)
-    }
+/// Netlist metadata used to resolve hierarchical paths to simulator signal names.
+pub(crate) type PathResolver = simulator_common::tran::PathResolver<Ngspice>;
 
-    fn len(&self) -> usize {
-        self.t.len()
+impl VoltageNames for Ngspice {
+    fn voltage_names(
+        lib: &Library<Ngspice>,
+        conv: &NetlistLibConversion,
+        path: &SliceOnePath,
+    ) -> Vec<ArcStr> {
+        vec![SavedData::from(SaveStmt::ScirVoltage(path.clone())).to_data_string(lib, conv)]
     }
 }
 
@@ -99,10 +80,6 @@ impl Save<Ngspice, Tran> for SaveTime {
     }
 }
 
-/// An identifier for a saved transient voltage.
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Serialize, Deserialize)]
-pub struct VoltageSaveKey(pub(crate) u64);
-
 impl Save<Ngspice, Tran> for NestedNode {
     type SaveKey = VoltageSaveKey;
     type Saved = OutputWaveform;
@@ -129,7 +106,7 @@ impl Save<Ngspice, Tran> for NestedNode {
             t: output.time.clone(),
             x: output
                 .raw_values
-                .get(output.saved_values.get(&key.0).unwrap())
+                .get(output.saved_values.get(&key.id()).unwrap())
                 .unwrap()
                 .clone(),
         }
@@ -156,7 +133,7 @@ impl Save<Ngspice, Tran> for RawNestedNode {
         output: &<Tran as Analysis>::Output,
         key: &<Self as Save<Ngspice, Tran>>::SaveKey,
     ) -> <Self as Save<Ngspice, Tran>>::Saved {
-        let name = output.saved_values.get(&key.0).unwrap();
+        let name = output.saved_values.get(&key.id()).unwrap();
         OutputWaveform {
             t: output.time.clone(),
             x: output.raw_values.get(name).unwrap().clone(),
@@ -183,7 +160,7 @@ impl Save<Ngspice, Tran> for NestedTerminal {
     ) -> <Self as Save<Ngspice, Tran>>::SaveKey {
         (
             <NestedNode as Save<Ngspice, Tran>>::save(self, ctx, opts),
-            CurrentSaveKey(
+            CurrentSaveKey::from_ids(
                 ctx.lib
                     .convert_terminal_path(&self.path())
                     .unwrap()
@@ -198,9 +175,9 @@ impl Save<Ngspice, Tran> for NestedTerminal {
                                 NamedSliceOne::new(port.clone()),
                             ),
                         }))
-                        .0
-                    })
-                    .collect(),
+                        .ids()
+                        .to_vec()
+                    }),
             ),
         )
     }
@@ -213,13 +190,13 @@ impl Save<Ngspice, Tran> for NestedTerminal {
             t: output.time.clone(),
             x: output
                 .raw_values
-                .get(output.saved_values.get(&key.0 .0).unwrap())
+                .get(output.saved_values.get(&key.0.id()).unwrap())
                 .unwrap()
                 .clone(),
         };
         let currents: Vec<Arc<Vec<f64>>> = key
             .1
-             .0
+            .ids()
             .iter()
             .map(|key| {
                 output
@@ -246,10 +223,6 @@ impl Save<Ngspice, Tran> for NestedTerminal {
     }
 }
 
-/// An identifier for a saved transient current.
-#[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
-pub struct CurrentSaveKey(pub(crate) Vec<u64>);
-
 impl Analysis for Tran {
     type Output = Output;
 }
@@ -265,3 +238,91 @@ impl SupportedBy<Ngspice> for Tran {
         item.try_into().unwrap()
     }
 }
+
+impl From<common::Tran> for Tran {
+    fn from(value: common::Tran) -> Self {
+        Self {
+            step: value.step_or_default(),
+            stop: value.stop,
+            start: value.start,
+        }
+    }
+}
+
+impl SupportedBy<Ngspice> for common::Tran {
+    fn into_input(self, inputs: &mut Vec<<Ngspice as Simulator>::Input>) {
+        Tran::from(self).into_input(inputs);
+    }
+    fn from_output(
+        outputs: &mut impl Iterator<Item = <Ngspice as Simulator>::Output>,
+    ) -> <Self as Analysis>::Output {
+        let output = Tran::from_output(outputs);
+        common::TranOutput {
+            time: output.time,
+            raw_values: output.raw_values,
+            saved_values: output.saved_values,
+        }
+    }
+}
+
+impl Save<Ngspice, common::Tran> for SaveOutput {
+    type SaveKey = ();
+    type Saved = common::TranOutput;
+
+    fn save(
+        &self,
+        _ctx: &SimulationContext<Ngspice>,
+        _opts: &mut <Ngspice as Simulator>::Options,
+    ) -> <Self as Save<Ngspice, common::Tran>>::SaveKey {
+    }
+
+    fn from_saved(
+        output: &<common::Tran as Analysis>::Output,
+        _key: &<Self as Save<Ngspice, common::Tran>>::SaveKey,
+    ) -> <Self as Save<Ngspice, common::Tran>>::Saved {
+        output.clone()
+    }
+}
+
+impl Save<Ngspice, common::Tran> for NestedNode {
+    type SaveKey = VoltageSaveKey;
+    type Saved = common::TranWaveform;
+
+    fn save(
+        &self,
+        ctx: &SimulationContext<Ngspice>,
+        opts: &mut <Ngspice as Simulator>::Options,
+    ) -> <Self as Save<Ngspice, common::Tran>>::SaveKey {
+        <NestedNode as Save<Ngspice, Tran>>::save(self, ctx, opts)
+    }
+
+    fn from_saved(
+        output: &<common::Tran as Analysis>::Output,
+        key: &<Self as Save<Ngspice, common::Tran>>::SaveKey,
+    ) -> <Self as Save<Ngspice, common::Tran>>::Saved {
+        output.saved(key)
+    }
+}
+
+impl Save<Ngspice, common::Tran> for NestedTerminal {
+    type SaveKey = (VoltageSaveKey, CurrentSaveKey);
+    type Saved = common::TranTerminal;
+
+    fn save(
+        &self,
+        ctx: &SimulationContext<Ngspice>,
+        opts: &mut <Ngspice as Simulator>::Options,
+    ) -> <Self as Save<Ngspice, common::Tran>>::SaveKey {
+        <NestedTerminal as Save<Ngspice, Tran>>::save(self, ctx, opts)
+    }
+
+    fn from_saved(
+        output: &<common::Tran as Analysis>::Output,
+        key: &<Self as Save<Ngspice, common::Tran>>::SaveKey,
+    ) -> <Self as Save<Ngspice, common::Tran>>::Saved {
+        common::TranTerminal {
+            v: output.saved(&key.0),
+            i: output.saved(&key.1),
+        }
+    }
+}
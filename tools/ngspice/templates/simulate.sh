@@ -8,7 +8,7 @@ source {{ bashrc }}
 
 set -e
 
-ngspice \
+{{ executable }} \
   -b -r {{ raw_output_file }} \
   {{ flags }} \
   {{ netlist }} \
@@ -0,0 +1,125 @@
+//! Cached simulation execution.
+//!
+//! Simulations are keyed by the contents of their netlist. If a simulation with an identical
+//! netlist has already been run (in this process or, with a persistent cache, a previous one),
+//! its parsed outputs are returned without invoking the simulator again.
+
+use std::sync::{Arc, Mutex};
+
+use cache::{CacheHandle, CacheableWithState};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use substrate::cache::Cache;
+
+/// The cache key of a simulation.
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
+struct CachedSim {
+    simulation_netlist: Vec<u8>,
+}
+
+type RunFn<O, E> = Box<dyn FnOnce() -> Result<O, E> + Send>;
+
+/// The state used to run a simulation on a cache miss.
+struct CachedSimState<O, E> {
+    run: Mutex<Option<RunFn<O, E>>>,
+    /// The span in which the simulation was requested.
+    span: tracing::Span,
+}
+
+impl<O, E> CacheableWithState<CachedSimState<O, E>> for CachedSim
+where
+    O: Send + Sync + Serialize + DeserializeOwned + 'static,
+    E: Send + Sync + 'static,
+{
+    type Output = O;
+    type Error = Arc<E>;
+
+    fn generate_with_state(
+        &self,
+        state: CachedSimState<O, E>,
+    ) -> Result<Self::Output, Self::Error> {
+        let _guard = state.span.enter();
+        let run = state
+            .run
+            .lock()
+            .unwrap()
+            .take()
+            .expect("simulation should only be run once");
+        run().map_err(Arc::new)
+    }
+}
+
+/// Runs a simulation of the netlist with contents `netlist`, unless its outputs are already cached.
+///
+/// `run` should write and execute the run script, then parse and return the simulator's raw
+/// outputs. Outputs are stored in `cache` under `namespace`, which should be unique to the
+/// simulator (e.g. `"spectre.simulation.outputs"`). Errors are not cached.
+///
+/// `run` is executed within the [`tracing::Span`] in which this function was called.
+pub fn run_cached<O, E>(
+    cache: &Cache,
+    namespace: impl Into<String>,
+    netlist: Vec<u8>,
+    run: impl FnOnce() -> Result<O, E> + Send + 'static,
+) -> CacheHandle<Result<O, Arc<E>>>
+where
+    O: Send + Sync + Serialize + DeserializeOwned + 'static,
+    E: Send + Sync + 'static,
+{
+    cache.get_with_state(
+        namespace,
+        CachedSim {
+            simulation_netlist: netlist,
+        },
+        CachedSimState {
+            run: Mutex::new(Some(Box::new(run))),
+            span: tracing::Span::current(),
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    #[test]
+    fn run_cached_runs_each_netlist_once() {
+        let cache = Cache::default();
+        let runs = Arc::new(AtomicUsize::new(0));
+        let run = |netlist: &str, output: u64| {
+            let runs = runs.clone();
+            *run_cached(
+                &cache,
+                "test.simulation.outputs",
+                netlist.as_bytes().to_vec(),
+                move || {
+                    runs.fetch_add(1, Ordering::SeqCst);
+                    Ok::<_, ()>(output)
+                },
+            )
+            .unwrap_inner()
+        };
+
+        assert_eq!(run("netlist a", 1), 1);
+        assert_eq!(run("netlist a", 2), 1);
+        assert_eq!(run("netlist b", 3), 3);
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn run_cached_reruns_failed_simulations() {
+        let cache = Cache::default();
+        let netlist = b"netlist".to_vec();
+
+        let failed = run_cached(&cache, "test.simulation.outputs", netlist.clone(), || {
+            Err::<u64, _>("stopped")
+        });
+        assert_eq!(**failed.unwrap_err_inner(), "stopped");
+
+        let rerun = run_cached(&cache, "test.simulation.outputs", netlist, || {
+            Ok::<_, &str>(1)
+        });
+        assert_eq!(*rerun.unwrap_inner(), 1);
+    }
+}
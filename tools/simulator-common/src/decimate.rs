@@ -0,0 +1,55 @@
+//! Decimation of raw transient outputs.
+
+use std::collections::HashMap;
+
+use substrate::simulation::options::Decimation;
+
+/// The name under which the time points of raw transient outputs are stored.
+pub const TIME: &str = "time";
+
+/// Resamples raw transient signals onto the time points given by `decimation`.
+///
+/// The time points of the signals must be stored under the name [`TIME`].
+/// Signals are returned unchanged if there are no time points.
+pub fn decimate_tran(
+    mut values: HashMap<String, Vec<f64>>,
+    decimation: &Decimation,
+) -> HashMap<String, Vec<f64>> {
+    let Some(time) = values.remove(TIME) else {
+        return values;
+    };
+    let mut decimated = HashMap::from_iter(
+        values
+            .into_iter()
+            .map(|(name, x)| (name, decimation.resample(&time, &x))),
+    );
+    decimated.insert(TIME.to_string(), decimation.time_points(&time));
+    decimated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn tran_signals_are_decimated_together() {
+        let values = HashMap::from_iter([
+            (TIME.to_string(), vec![0., 1., 2., 3., 4.]),
+            ("out".to_string(), vec![0., 2., 0., 2., 0.]),
+        ]);
+        let decimated = decimate_tran(values, &Decimation::new(dec!(2)).unwrap());
+        assert_eq!(decimated[TIME], vec![0., 2., 4.]);
+        assert_eq!(decimated["out"], vec![1., 1., 1.]);
+    }
+
+    #[test]
+    fn empty_tran_signals_are_unchanged() {
+        let values = HashMap::from_iter([
+            (TIME.to_string(), Vec::new()),
+            ("out".to_string(), Vec::new()),
+        ]);
+        let decimated = decimate_tran(values.clone(), &Decimation::new(dec!(2)).unwrap());
+        assert_eq!(decimated, values);
+    }
+}
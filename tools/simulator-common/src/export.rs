@@ -0,0 +1,85 @@
+//! Tabular export of simulation outputs.
+//!
+//! Output types collect the signals to export into a [`Table`], naming each column after the
+//! hierarchical SCIR path of its signal, and write the table using the
+//! [exporters](substrate::simulation::export) provided by Substrate.
+
+use std::io::Write;
+use std::sync::Arc;
+
+use num::complex::Complex64;
+use scir::NamedPath;
+use substrate::simulation::data::SignalLookupError;
+#[cfg(feature = "parquet")]
+use substrate::simulation::export;
+use substrate::simulation::export::{write_csv, Column};
+
+/// Returns the name under which the signal at the SCIR path `path` is exported.
+///
+/// The name consists of the elements of `path` joined by `.`.
+pub fn column_name(path: &NamedPath) -> String {
+    path.join(".")
+}
+
+/// Converts a signal lookup error into an I/O error, for use by exporters.
+pub fn lookup_error(err: SignalLookupError) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::NotFound, err)
+}
+
+/// A table of named columns of simulation data.
+#[derive(Debug, Clone, Default)]
+pub struct Table {
+    names: Vec<String>,
+    values: Vec<Arc<Vec<f64>>>,
+}
+
+impl Table {
+    /// Creates a table whose first column is the independent variable `x`, such as time or
+    /// frequency.
+    pub fn new(name: impl Into<String>, x: Arc<Vec<f64>>) -> Self {
+        let mut table = Self::default();
+        table.push(name, x);
+        table
+    }
+
+    /// Adds a column of real values.
+    pub fn push(&mut self, name: impl Into<String>, values: Arc<Vec<f64>>) {
+        self.names.push(name.into());
+        self.values.push(values);
+    }
+
+    /// Adds two columns, `re(<name>)` and `im(<name>)`, containing the real and imaginary
+    /// parts of `values`.
+    pub fn push_complex(&mut self, name: &str, values: &[Complex64]) {
+        self.push(
+            format!("re({name})"),
+            Arc::new(values.iter().map(|v| v.re).collect()),
+        );
+        self.push(
+            format!("im({name})"),
+            Arc::new(values.iter().map(|v| v.im).collect()),
+        );
+    }
+
+    fn columns(&self) -> Vec<Column<'_>> {
+        self.names
+            .iter()
+            .zip(&self.values)
+            .map(|(name, values)| Column::new(name, values))
+            .collect()
+    }
+
+    /// Writes the table to `out` in CSV format.
+    pub fn write_csv<W: Write>(&self, out: W) -> std::io::Result<()> {
+        write_csv(out, &self.columns())
+    }
+
+    /// Writes the table to `out` as an Apache Parquet file.
+    #[cfg(feature = "parquet")]
+    pub fn write_parquet<W: Write + Send>(
+        &self,
+        out: W,
+    ) -> Result<(), parquet::errors::ParquetError> {
+        export::write_parquet(out, &self.columns())
+    }
+}
@@ -0,0 +1,50 @@
+//! Shared scaffolding for Substrate simulator plugins.
+//!
+//! A simulator plugin implements [`Simulator`](substrate::simulation::Simulator) by writing a
+//! netlist, rendering a run script, executing it, and parsing the simulator's raw outputs.
+//! Only netlisting and output parsing are specific to a simulator; this crate provides the rest:
+//!
+//! * [`script`] loads run script templates, renders executable scripts, and builds the command
+//!   used to run them with the context's [`Executor`](substrate::execute::Executor).
+//! * [`cached`] runs a simulation at most once per unique netlist, storing parsed outputs in
+//!   the context's [`Cache`](substrate::cache::Cache).
+//! * [`saves`] assigns stable keys to saved signals so that raw outputs can be mapped back
+//!   to the signals requested by a testbench.
+//! * [`names`] interns hierarchical signal names, so that outputs with many saved signals
+//!   can be stored compactly.
+//! * [`decimate`] resamples long transient outputs before they are cached.
+//! * [`export`] collects output signals into tables named by hierarchical path for export.
+//! * [`paths`] converts the Substrate paths referenced by analyses to SCIR paths.
+//! * [`sens`] holds sensitivity analysis results and ranks them by magnitude.
+//! * [`tran`] looks up transient waveforms by hierarchical path and exports or plots them.
+//!   Plugins implement [`tran::VoltageNames`] to describe how their simulator names node voltages.
+//!
+//! A typical `simulate_inputs` implementation looks like the following:
+//!
+//! ```ignore
+//! let (netlist, contents) = self.write_netlist(ctx, &options, &input)?;
+//! let run_script = ctx.work_dir.join("simulate.sh");
+//! let work_dir = ctx.work_dir.clone();
+//! let executor = ctx.ctx.executor.clone();
+//!
+//! let raw_outputs = cached::run_cached(&ctx.ctx.cache, "mysim.simulation.outputs", contents, move || {
+//!     script::write_script::<Error>(&TEMPLATES, "simulate.sh", &RunScriptContext { .. }, &run_script)?;
+//!     executor
+//!         .execute(script::script_command(&run_script, &work_dir), Default::default())
+//!         .map_err(|_| Error::SimulationFailed)?;
+//!     parse_outputs(&work_dir)
+//! })
+//! .try_inner()?
+//! .clone();
+//! ```
+#![warn(missing_docs)]
+
+pub mod cached;
+pub mod decimate;
+pub mod export;
+pub mod names;
+pub mod paths;
+pub mod saves;
+pub mod script;
+pub mod sens;
+pub mod tran;
@@ -0,0 +1,218 @@
+//! Hierarchy-aware interning of simulator signal names.
+//!
+//! Saved signals deep in a design hierarchy have long names that mostly consist of a few
+//! shared instance path prefixes. A [`NameTable`] stores each hierarchical name as a chain of
+//! path segments, so shared prefixes are stored once, and identifies each name by a compact
+//! [`NameId`]. An [`Interned`] map keys its values by [`NameId`], with the [`NameTable`] kept
+//! alongside as a sidecar that restores the full names when the map is expanded.
+//!
+//! Interning reduces the size of stored (e.g. cached) outputs. Expanding an [`Interned`] map
+//! allocates every full name again, so expanded maps are no smaller than uninterned ones.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+pub use scir::query::SEPARATOR;
+
+/// The identifier of a name interned in a [`NameTable`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd, Serialize, Deserialize)]
+pub struct NameId(u32);
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Segment {
+    parent: Option<NameId>,
+    name: String,
+}
+
+/// A table of interned hierarchical names.
+///
+/// Names are split into segments at each [`SEPARATOR`]. Every distinct prefix of an interned
+/// name is itself interned, so names sharing a prefix share storage.
+#[derive(Clone, Debug, Default)]
+pub struct NameTable {
+    segments: Vec<Segment>,
+    /// The children of each segment (or of the root, for [`None`]), keyed by segment name.
+    children: HashMap<Option<NameId>, HashMap<String, NameId>>,
+}
+
+impl NameTable {
+    /// Creates an empty name table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Interns `name`, returning its identifier.
+    ///
+    /// Interning the same name more than once returns the same identifier.
+    pub fn intern(&mut self, name: &str) -> NameId {
+        let mut parent = None;
+        for segment in name.split(SEPARATOR) {
+            let children = self.children.entry(parent).or_default();
+            let id = match children.get(segment) {
+                Some(id) => *id,
+                None => {
+                    let id = NameId(self.segments.len() as u32);
+                    self.segments.push(Segment {
+                        parent,
+                        name: segment.to_string(),
+                    });
+                    children.insert(segment.to_string(), id);
+                    id
+                }
+            };
+            parent = Some(id);
+        }
+        parent.expect("split always yields at least one segment")
+    }
+
+    /// Returns the identifier of `name`, if it has been interned.
+    ///
+    /// Does not allocate.
+    pub fn get(&self, name: &str) -> Option<NameId> {
+        let mut parent = None;
+        for segment in name.split(SEPARATOR) {
+            parent = Some(*self.children.get(&parent)?.get(segment)?);
+        }
+        parent
+    }
+
+    /// Returns the full name identified by `id`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` was not produced by this table.
+    pub fn resolve(&self, id: NameId) -> String {
+        let mut segments = Vec::new();
+        let mut next = Some(id);
+        while let Some(id) = next {
+            let segment = &self.segments[id.0 as usize];
+            segments.push(segment.name.as_str());
+            next = segment.parent;
+        }
+        segments.reverse();
+        segments.join(&SEPARATOR.to_string())
+    }
+
+    /// The number of distinct segments stored in the table.
+    pub fn len(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// Returns `true` if no names have been interned.
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+}
+
+impl Serialize for NameTable {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.segments.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for NameTable {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let segments = Vec::<Segment>::deserialize(deserializer)?;
+        let mut children: HashMap<_, HashMap<_, _>> = HashMap::new();
+        for (i, segment) in segments.iter().enumerate() {
+            children
+                .entry(segment.parent)
+                .or_default()
+                .insert(segment.name.clone(), NameId(i as u32));
+        }
+        Ok(Self { segments, children })
+    }
+}
+
+/// A map from hierarchical names to values, with names interned in a sidecar [`NameTable`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Interned<V> {
+    names: NameTable,
+    values: Vec<(NameId, V)>,
+}
+
+impl<V> Default for Interned<V> {
+    fn default() -> Self {
+        Self {
+            names: NameTable::new(),
+            values: Vec::new(),
+        }
+    }
+}
+
+impl<V> Interned<V> {
+    /// The table of interned names.
+    pub fn names(&self) -> &NameTable {
+        &self.names
+    }
+
+    /// The number of values in the map.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns `true` if the map contains no values.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+impl<N: AsRef<str>, V> FromIterator<(N, V)> for Interned<V> {
+    fn from_iter<I: IntoIterator<Item = (N, V)>>(iter: I) -> Self {
+        let mut interned = Self::default();
+        for (name, value) in iter {
+            let id = interned.names.intern(name.as_ref());
+            interned.values.push((id, value));
+        }
+        interned
+    }
+}
+
+impl<V> IntoIterator for Interned<V> {
+    type Item = (String, V);
+    type IntoIter = std::vec::IntoIter<(String, V)>;
+
+    /// Expands the map, restoring the full name of each value.
+    fn into_iter(self) -> Self::IntoIter {
+        let Self { names, values } = self;
+        values
+            .into_iter()
+            .map(|(id, value)| (names.resolve(id), value))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_share_hierarchical_prefixes() {
+        let mut names = NameTable::new();
+        let a = names.intern("xtop.xbank.xcol0.q");
+        let b = names.intern("xtop.xbank.xcol0.qb");
+        let c = names.intern("xtop.xbank.xcol1.q");
+        assert_eq!(names.intern("xtop.xbank.xcol0.q"), a);
+        assert_eq!(names.get("xtop.xbank.xcol1.q"), Some(c));
+        assert_eq!(names.get("xtop.xbank.xcol2.q"), None);
+        assert_eq!(names.resolve(b), "xtop.xbank.xcol0.qb");
+        assert_eq!(names.len(), 6);
+
+        let map = [("vout", 1), ("xdut.x\\.y.n", 2), ("xdut.m0:d", 3)]
+            .into_iter()
+            .collect::<Interned<_>>();
+        let json = serde_json::to_string(&map).unwrap();
+        let map: Interned<i32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(map.len(), 3);
+        assert_eq!(
+            map.into_iter().collect::<HashMap<_, _>>(),
+            HashMap::from_iter([
+                ("vout".to_string(), 1),
+                ("xdut.x\\.y.n".to_string(), 2),
+                ("xdut.m0:d".to_string(), 3),
+            ])
+        );
+    }
+}
@@ -0,0 +1,19 @@
+//! Conversion of the Substrate paths referenced by analyses to SCIR paths.
+
+use scir::{NamedSliceOne, SliceOnePath};
+use substrate::schematic::conv::{ConvertedNodePath, RawLib};
+use substrate::schematic::schema::Schema;
+use substrate::types::schematic::NodePath;
+
+/// Returns the SCIR path of the Substrate node at `path`.
+///
+/// Nodes that correspond to ports of primitive instances are referenced by port name.
+/// Returns [`None`] if `path` does not exist in `lib`.
+pub fn scir_node_path<S: Schema>(lib: &RawLib<S>, path: &NodePath) -> Option<SliceOnePath> {
+    Some(match lib.convert_node_path(path)? {
+        ConvertedNodePath::Cell(path) => path,
+        ConvertedNodePath::Primitive {
+            instances, port, ..
+        } => SliceOnePath::new(instances, NamedSliceOne::new(port)),
+    })
+}
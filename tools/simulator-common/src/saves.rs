@@ -0,0 +1,96 @@
+//! Keys for saved simulation outputs.
+//!
+//! Testbenches request signals to be saved before a simulation is run, and receive a key
+//! that is later used to retrieve the corresponding waveform from the simulation output.
+
+use std::collections::hash_map::Iter;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Assigns a unique, stable key to each saved signal.
+///
+/// Saving the same signal more than once returns the same key.
+#[derive(Debug, Clone)]
+pub struct SaveKeys<T> {
+    keys: HashMap<T, u64>,
+    next_key: u64,
+}
+
+impl<T> Default for SaveKeys<T> {
+    fn default() -> Self {
+        Self {
+            keys: HashMap::new(),
+            next_key: 0,
+        }
+    }
+}
+
+impl<T: Hash + Eq> SaveKeys<T> {
+    /// Creates an empty set of saved signals.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Marks `save` to be saved, returning its key.
+    pub fn save(&mut self, save: T) -> u64 {
+        if let Some(key) = self.keys.get(&save) {
+            *key
+        } else {
+            let key = self.next_key;
+            self.next_key += 1;
+            self.keys.insert(save, key);
+            key
+        }
+    }
+
+    /// Returns the key of `save`, if it has been saved.
+    pub fn get(&self, save: &T) -> Option<u64> {
+        self.keys.get(save).copied()
+    }
+
+    /// Iterates over saved signals.
+    pub fn keys(&self) -> impl Iterator<Item = &T> {
+        self.keys.keys()
+    }
+
+    /// Iterates over saved signals and their keys.
+    pub fn iter(&self) -> Iter<'_, T, u64> {
+        self.keys.iter()
+    }
+
+    /// The number of saved signals.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Returns `true` if no signals have been saved.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a SaveKeys<T> {
+    type Item = (&'a T, &'a u64);
+    type IntoIter = Iter<'a, T, u64>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.keys.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn save_keys_are_unique_and_stable() {
+        let mut saves = SaveKeys::new();
+        let a = saves.save("a");
+        let b = saves.save("b");
+        assert_ne!(a, b);
+        assert_eq!(saves.save("a"), a);
+        assert_eq!(saves.get(&"b"), Some(b));
+        assert_eq!(saves.get(&"c"), None);
+        assert_eq!(saves.len(), 2);
+    }
+}
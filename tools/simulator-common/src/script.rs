@@ -0,0 +1,153 @@
+//! Run script templating and execution.
+//!
+//! Simulator plugins typically invoke the simulator via a Bash script rendered from a
+//! [Tera](tera) template shipped with the plugin, so that users can inspect and re-run
+//! the exact command used for a simulation.
+
+#[cfg(any(unix, target_os = "redox"))]
+use std::os::unix::prelude::PermissionsExt;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use serde::Serialize;
+use tera::{Context, Tera};
+
+pub use tera;
+
+/// Loads all templates in the directory `dir`.
+///
+/// Templates are referred to by their file name (e.g. `simulate.sh`). Plugins usually
+/// store the result in a static:
+///
+/// ```ignore
+/// lazy_static! {
+///     static ref TEMPLATES: Tera =
+///         load_templates(concat!(env!("CARGO_MANIFEST_DIR"), "/templates"));
+/// }
+/// ```
+///
+/// # Panics
+///
+/// Panics if any template fails to parse.
+pub fn load_templates(dir: impl AsRef<Path>) -> Tera {
+    match Tera::new(&format!("{}/*", dir.as_ref().display())) {
+        Ok(t) => t,
+        Err(e) => {
+            panic!("Encountered errors while parsing Tera templates: {e}");
+        }
+    }
+}
+
+/// Renders the template named `template` with the given context to an executable script at `path`.
+///
+/// The error type is chosen by the caller, so that plugins can propagate failures using
+/// their own error types.
+pub fn write_script<E: From<std::io::Error> + From<tera::Error>>(
+    templates: &Tera,
+    template: &str,
+    ctx: &impl Serialize,
+    path: impl AsRef<Path>,
+) -> Result<(), E> {
+    let path = path.as_ref();
+    let ctx = Context::from_serialize(ctx)?;
+    let mut f = std::fs::File::create(path)?;
+    templates.render_to(template, &ctx, &mut f)?;
+    drop(f);
+    make_executable(path)?;
+    Ok(())
+}
+
+/// Makes the file at `path` executable by its owner.
+///
+/// Does nothing on platforms without Unix permissions.
+pub fn make_executable(path: impl AsRef<Path>) -> std::io::Result<()> {
+    let path = path.as_ref();
+    #[allow(unused_mut)]
+    let mut perms = std::fs::metadata(path)?.permissions();
+    #[cfg(any(unix, target_os = "redox"))]
+    perms.set_mode(0o744);
+    std::fs::set_permissions(path, perms)
+}
+
+/// Returns a command that runs the Bash script at `script` from the directory `work_dir`.
+///
+/// Standard input is closed so that simulators never block waiting for interactive input.
+/// The command should be passed to an [`Executor`](substrate::execute::Executor).
+pub fn script_command(script: impl AsRef<Path>, work_dir: impl AsRef<Path>) -> Command {
+    let mut command = Command::new("/bin/bash");
+    command
+        .arg(script.as_ref())
+        .current_dir(work_dir)
+        .stdin(Stdio::null());
+    command
+}
+
+/// Quotes `value` for use as a single word in a Bash script.
+///
+/// Useful for passing user-provided strings (e.g. job names) to run script templates.
+pub fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct ScriptContext<'a> {
+        message: &'a str,
+    }
+
+    #[derive(Debug)]
+    #[allow(dead_code)]
+    enum TestError {
+        Io(std::io::Error),
+        Template(tera::Error),
+    }
+
+    impl From<std::io::Error> for TestError {
+        fn from(value: std::io::Error) -> Self {
+            Self::Io(value)
+        }
+    }
+
+    impl From<tera::Error> for TestError {
+        fn from(value: tera::Error) -> Self {
+            Self::Template(value)
+        }
+    }
+
+    #[test]
+    fn write_script_renders_executable_script() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("build")
+            .join("write_script_renders_executable_script");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("run.sh");
+
+        let mut templates = Tera::default();
+        templates
+            .add_raw_template("run.sh", "echo {{ message }}\n")
+            .unwrap();
+        write_script::<TestError>(
+            &templates,
+            "run.sh",
+            &ScriptContext { message: "hello" },
+            &path,
+        )
+        .unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "echo hello\n");
+        #[cfg(unix)]
+        assert_eq!(
+            std::fs::metadata(&path).unwrap().permissions().mode() & 0o777,
+            0o744
+        );
+    }
+
+    #[test]
+    fn shell_quote_escapes_single_quotes() {
+        assert_eq!(shell_quote("inv"), "'inv'");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+    }
+}
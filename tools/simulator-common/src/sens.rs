@@ -0,0 +1,94 @@
+//! Sensitivity analysis results shared by simulators.
+
+use std::sync::Arc;
+
+use num::complex::Complex64;
+use serde::{Deserialize, Serialize};
+
+/// The sensitivity of an output to a single parameter.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum SensValue {
+    /// A DC sensitivity.
+    Dc(f64),
+    /// An AC sensitivity at each frequency of the sweep.
+    Ac(Arc<Vec<Complex64>>),
+}
+
+impl SensValue {
+    /// Returns the DC sensitivity.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is an AC sensitivity.
+    pub fn dc(&self) -> f64 {
+        match self {
+            Self::Dc(value) => *value,
+            Self::Ac(_) => panic!("expected a DC sensitivity"),
+        }
+    }
+
+    /// Returns the AC sensitivity at each frequency.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is a DC sensitivity.
+    pub fn ac(&self) -> &Arc<Vec<Complex64>> {
+        match self {
+            Self::Ac(values) => values,
+            Self::Dc(_) => panic!("expected an AC sensitivity"),
+        }
+    }
+
+    /// The magnitude of the sensitivity.
+    ///
+    /// For AC sensitivities, this is the largest magnitude across all frequencies.
+    pub fn magnitude(&self) -> f64 {
+        match self {
+            Self::Dc(value) => value.abs(),
+            Self::Ac(values) => values.iter().map(|v| v.norm()).fold(0., f64::max),
+        }
+    }
+}
+
+/// Orders sensitivities from largest to smallest [magnitude](SensValue::magnitude).
+///
+/// Sensitivities of equal magnitude are ordered by parameter name.
+pub fn dominant<'a, K: Ord>(
+    values: impl IntoIterator<Item = (&'a K, &'a SensValue)>,
+) -> Vec<(&'a K, &'a SensValue)> {
+    let mut values = values.into_iter().collect::<Vec<_>>();
+    values.sort_by(|(a_name, a), (b_name, b)| {
+        b.magnitude()
+            .total_cmp(&a.magnitude())
+            .then_with(|| a_name.cmp(b_name))
+    });
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn dominant_sensitivities_are_ordered_by_magnitude() {
+        let values = HashMap::from([
+            ("r0:r", SensValue::Dc(1e-3)),
+            ("m0:w", SensValue::Dc(-2e5)),
+            ("m1:w", SensValue::Dc(2e5)),
+            (
+                "c0:c",
+                SensValue::Ac(Arc::new(vec![
+                    Complex64::new(0., 1e2),
+                    Complex64::new(3e5, 4e5),
+                ])),
+            ),
+        ]);
+        let names = dominant(values.iter())
+            .into_iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["c0:c", "m0:w", "m1:w", "r0:r"]);
+    }
+}
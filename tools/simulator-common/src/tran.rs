@@ -0,0 +1,391 @@
+//! Transient analysis outputs shared by simulators.
+//!
+//! A simulator plugin exposes its transient results as an [`Output`], implementing
+//! [`VoltageNames`] to describe how the voltage of a SCIR node is named in the simulator's
+//! raw outputs. Lookup by Substrate and SCIR paths, export and plotting are provided here.
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::Arc;
+
+use arcstr::ArcStr;
+use scir::{NamedPath, NetlistLibConversion, SliceOnePath};
+use serde::{Deserialize, Serialize};
+use substrate::schematic::conv::RawLib;
+use substrate::schematic::schema::Schema;
+use substrate::simulation::data::{NodeAliases, SignalLookupError};
+use substrate::simulation::export::{write_fsdb, write_vcd, Thresholds, VcdSignal};
+#[cfg(feature = "plot")]
+use substrate::simulation::plot;
+use substrate::simulation::samples::Samples;
+use substrate::simulation::waveform::{TimePoint, TimeWaveform, WaveformRef};
+use substrate::types::schematic::NodePath;
+
+use crate::export::{column_name, lookup_error, Table};
+use crate::paths::scir_node_path;
+
+/// A schema whose simulator can report the voltages of SCIR nodes.
+pub trait VoltageNames: Schema + Sized {
+    /// Returns the raw (netlisted) names under which the voltage of the SCIR node at `path`
+    /// may have been saved, in order of preference.
+    ///
+    /// Must return at least one name.
+    fn voltage_names(
+        lib: &scir::Library<Self>,
+        conv: &NetlistLibConversion,
+        path: &SliceOnePath,
+    ) -> Vec<ArcStr>;
+}
+
+/// Netlist metadata used to resolve hierarchical paths to simulator signal names.
+pub struct PathResolver<S: Schema> {
+    /// The netlisted library.
+    pub lib: Arc<RawLib<S>>,
+    /// The netlister's conversion metadata for `lib`.
+    pub conv: Arc<NetlistLibConversion>,
+    /// The aliases of saved signals.
+    pub aliases: Arc<NodeAliases>,
+}
+
+impl<S: Schema> Clone for PathResolver<S> {
+    fn clone(&self) -> Self {
+        Self {
+            lib: self.lib.clone(),
+            conv: self.conv.clone(),
+            aliases: self.aliases.clone(),
+        }
+    }
+}
+
+impl<S: Schema> std::fmt::Debug for PathResolver<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PathResolver").finish_non_exhaustive()
+    }
+}
+
+impl<S: Schema> PathResolver<S> {
+    /// Returns the SCIR path of the Substrate node at `path`.
+    pub fn scir_path(&self, path: &NodePath) -> Result<SliceOnePath, SignalLookupError> {
+        scir_node_path(&self.lib, path)
+            .ok_or_else(|| SignalLookupError::Unresolved(format!("{path:?}")))
+    }
+
+    /// Returns the hierarchical SCIR name of the node at `path`.
+    ///
+    /// The name consists of the names of the SCIR instances containing the node, followed by
+    /// the name of the node within its parent cell.
+    pub fn scir_name(&self, path: SliceOnePath) -> NamedPath {
+        self.lib
+            .scir
+            .convert_slice_one_path(path, |name, index| match index {
+                Some(index) => arcstr::format!("{name}[{index}]"),
+                None => name.clone(),
+            })
+    }
+}
+
+impl<S: VoltageNames> PathResolver<S> {
+    /// Looks up the voltage of the SCIR node at `path` in `values`, a map from raw (netlisted)
+    /// signal names to saved data.
+    ///
+    /// Each name under which the simulator may have saved the voltage is tried in turn, as are
+    /// the [aliases](NodeAliases) of each name.
+    pub fn voltage<'a, V>(
+        &self,
+        values: &'a HashMap<ArcStr, V>,
+        path: &SliceOnePath,
+    ) -> Result<&'a V, SignalLookupError> {
+        let names = S::voltage_names(&self.lib.scir, &self.conv, path);
+        names
+            .iter()
+            .find_map(|name| {
+                values
+                    .get(name)
+                    .or_else(|| values.get(self.aliases.get(name)?))
+            })
+            .ok_or_else(|| {
+                let name = names
+                    .first()
+                    .expect("simulators must name the voltage of every node");
+                SignalLookupError::not_found(name.clone(), values.keys())
+            })
+    }
+}
+
+/// The result of a transient analysis.
+pub struct Output<S: Schema> {
+    /// The time points of the transient simulation.
+    pub time: Arc<Vec<f64>>,
+    /// A map from signal name to values.
+    pub raw_values: HashMap<ArcStr, Arc<Vec<f64>>>,
+    /// A map from a save ID to a raw value identifier.
+    pub saved_values: HashMap<u64, ArcStr>,
+    /// The netlist metadata used to resolve hierarchical paths.
+    ///
+    /// [`None`] if the output was not produced by a simulation.
+    pub resolver: Option<PathResolver<S>>,
+}
+
+impl<S: Schema> Clone for Output<S> {
+    fn clone(&self) -> Self {
+        Self {
+            time: self.time.clone(),
+            raw_values: self.raw_values.clone(),
+            saved_values: self.saved_values.clone(),
+            resolver: self.resolver.clone(),
+        }
+    }
+}
+
+impl<S: Schema> std::fmt::Debug for Output<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Output")
+            .field("time", &self.time)
+            .field("raw_values", &self.raw_values)
+            .field("saved_values", &self.saved_values)
+            .field("resolver", &self.resolver)
+            .finish()
+    }
+}
+
+impl<S: Schema> Output<S> {
+    /// Returns the aliases of saved signals, if the output was produced by a simulation.
+    ///
+    /// Signals can be looked up by any of their aliases.
+    pub fn aliases(&self) -> Option<&NodeAliases> {
+        self.resolver
+            .as_ref()
+            .map(|resolver| resolver.aliases.as_ref())
+    }
+
+    /// Returns the waveform of the signal with the given raw (netlisted) name.
+    ///
+    /// If no such signal was saved, the returned error lists similarly named saved signals.
+    pub fn raw_waveform(&self, name: &str) -> Result<OutputWaveform, SignalLookupError> {
+        let x = self
+            .raw_values
+            .get(name)
+            .or_else(|| self.raw_values.get(self.aliases()?.get(name)?))
+            .ok_or_else(|| SignalLookupError::not_found(name, self.raw_values.keys()))?;
+        Ok(OutputWaveform {
+            t: self.time.clone(),
+            x: x.clone(),
+        })
+    }
+
+    /// Returns the samples of the signal with the given raw (netlisted) name.
+    ///
+    /// Transient samples are always real-valued.
+    pub fn raw_samples(&self, name: &str) -> Result<Samples, SignalLookupError> {
+        Ok(Samples::Real(self.raw_waveform(name)?.x))
+    }
+
+    fn resolver(&self, path: impl std::fmt::Debug) -> Result<&PathResolver<S>, SignalLookupError> {
+        self.resolver
+            .as_ref()
+            .ok_or_else(|| SignalLookupError::Unresolved(format!("{path:?}")))
+    }
+}
+
+impl<S: VoltageNames> Output<S> {
+    /// Returns the voltage waveform of the SCIR node at `path`.
+    ///
+    /// The node must have been saved during simulation.
+    pub fn scir_voltage(&self, path: &SliceOnePath) -> Result<OutputWaveform, SignalLookupError> {
+        let x = self.resolver(path)?.voltage(&self.raw_values, path)?;
+        Ok(OutputWaveform {
+            t: self.time.clone(),
+            x: x.clone(),
+        })
+    }
+
+    /// Returns the voltage waveform of the Substrate node at `path`.
+    ///
+    /// Paths are typically obtained using
+    /// [`NestedNode::path`](substrate::types::schematic::NestedNode::path).
+    /// The node must have been saved during simulation.
+    pub fn node_voltage(&self, path: &NodePath) -> Result<OutputWaveform, SignalLookupError> {
+        self.scir_voltage(&self.resolver(path)?.scir_path(path)?)
+    }
+
+    /// Returns the hierarchical SCIR names and voltage waveforms of the Substrate nodes at `paths`.
+    ///
+    /// See [`PathResolver::scir_name`] for how names are formed.
+    pub fn named_voltages(
+        &self,
+        paths: &[NodePath],
+    ) -> Result<Vec<(NamedPath, OutputWaveform)>, SignalLookupError> {
+        paths
+            .iter()
+            .map(|path| {
+                let resolver = self.resolver(path)?;
+                let path = resolver.scir_path(path)?;
+                let waveform = self.scir_voltage(&path)?;
+                Ok((resolver.scir_name(path), waveform))
+            })
+            .collect()
+    }
+
+    fn vcd_signals(&self, paths: &[NodePath]) -> std::io::Result<Vec<(NamedPath, OutputWaveform)>> {
+        self.named_voltages(paths).map_err(lookup_error)
+    }
+
+    /// Returns the voltages of the Substrate nodes at `paths`, named as described in
+    /// [`column_name`].
+    fn named_columns(&self, paths: &[NodePath]) -> std::io::Result<Vec<(String, Arc<Vec<f64>>)>> {
+        Ok(self
+            .named_voltages(paths)
+            .map_err(lookup_error)?
+            .into_iter()
+            .map(|(name, waveform)| (column_name(&name), waveform.x))
+            .collect())
+    }
+
+    fn export_table(&self, paths: &[NodePath]) -> std::io::Result<Table> {
+        let mut table = Table::new("time", self.time.clone());
+        for (name, values) in self.named_columns(paths)? {
+            table.push(name, values);
+        }
+        Ok(table)
+    }
+
+    /// Writes the time points and the voltages of the Substrate nodes at `paths` to `out` in
+    /// CSV format.
+    ///
+    /// Each voltage is written to a column named after the hierarchical SCIR path of its node,
+    /// as described in [`column_name`]. All nodes must have been saved during simulation.
+    pub fn write_csv<W: Write>(&self, out: W, paths: &[NodePath]) -> std::io::Result<()> {
+        self.export_table(paths)?.write_csv(out)
+    }
+
+    /// Writes the time points and the voltages of the Substrate nodes at `paths` to `out` as an
+    /// Apache Parquet file.
+    ///
+    /// See [`Output::write_csv`] for how columns are named.
+    #[cfg(feature = "parquet")]
+    pub fn write_parquet<W: Write + Send>(
+        &self,
+        out: W,
+        paths: &[NodePath],
+    ) -> Result<(), parquet::errors::ParquetError> {
+        self.export_table(paths)?.write_parquet(out)
+    }
+
+    /// Plots the voltages of the Substrate nodes at `nodes` against time to the image at `path`.
+    ///
+    /// Voltages are named in the legend as described in [`column_name`].
+    /// All nodes must have been saved during simulation. See [`plot::plot`] for supported formats.
+    #[cfg(feature = "plot")]
+    pub fn plot(
+        &self,
+        path: impl AsRef<std::path::Path>,
+        nodes: &[NodePath],
+    ) -> Result<(), plot::PlotError> {
+        let signals = self.named_columns(nodes)?;
+        let series = signals
+            .iter()
+            .map(|(name, values)| plot::Series::new(name, &self.time, values))
+            .collect::<Vec<_>>();
+        plot::plot(
+            path,
+            &series,
+            &plot::PlotOptions {
+                x_label: "time (s)".to_string(),
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Plots the voltages of the Substrate nodes at `y` against the voltage of the node at `x`
+    /// to the image at `path`.
+    ///
+    /// See [`Output::plot`] for how voltages are named.
+    #[cfg(feature = "plot")]
+    pub fn plot_xy(
+        &self,
+        path: impl AsRef<std::path::Path>,
+        x: &NodePath,
+        y: &[NodePath],
+    ) -> Result<(), plot::PlotError> {
+        let (x_label, xs) = self.named_columns(std::slice::from_ref(x))?.remove(0);
+        let signals = self.named_columns(y)?;
+        let series = signals
+            .iter()
+            .map(|(name, values)| plot::Series::new(name, &xs, values))
+            .collect::<Vec<_>>();
+        plot::plot(
+            path,
+            &series,
+            &plot::PlotOptions {
+                x_label,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Writes the voltages of the Substrate nodes at `paths` to `out` in Value Change Dump (VCD)
+    /// format.
+    ///
+    /// Voltages are converted to logic levels using `thresholds`. Each node is written within
+    /// nested VCD scopes named after the SCIR instances containing it.
+    /// All nodes must have been saved during simulation.
+    pub fn write_vcd<W: Write>(
+        &self,
+        out: W,
+        paths: &[NodePath],
+        thresholds: Thresholds,
+    ) -> std::io::Result<()> {
+        let signals = self.vcd_signals(paths)?;
+        let signals = signals
+            .iter()
+            .map(|(name, waveform)| VcdSignal::new(name, &waveform.x))
+            .collect::<Vec<_>>();
+        write_vcd(out, &self.time, &signals, thresholds)
+    }
+
+    /// Writes the voltages of the Substrate nodes at `paths` to `out` in Fast Signal Database
+    /// (FSDB) format.
+    ///
+    /// Writing FSDB is not yet supported, so this always returns an error.
+    /// See [`write_fsdb`] for details.
+    pub fn write_fsdb<W: Write>(
+        &self,
+        out: W,
+        paths: &[NodePath],
+        thresholds: Thresholds,
+    ) -> std::io::Result<()> {
+        let signals = self.vcd_signals(paths)?;
+        let signals = signals
+            .iter()
+            .map(|(name, waveform)| VcdSignal::new(name, &waveform.x))
+            .collect::<Vec<_>>();
+        write_fsdb(out, &self.time, &signals, thresholds)
+    }
+}
+
+/// An output transient waveform.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub struct OutputWaveform {
+    /// Time samples.
+    pub t: Arc<Vec<f64>>,
+    /// Values corresponding to time samples in `t`.
+    pub x: Arc<Vec<f64>>,
+}
+
+impl OutputWaveform {
+    /// Converts an [`OutputWaveform`] to a [`WaveformRef`].
+    pub fn as_ref(&self) -> WaveformRef<'_, f64> {
+        WaveformRef::new(&self.t, &self.x)
+    }
+}
+
+impl TimeWaveform for OutputWaveform {
+    type Data = f64;
+    fn get(&self, idx: usize) -> Option<TimePoint<f64>> {
+        self.as_ref().get(idx)
+    }
+
+    fn len(&self) -> usize {
+        self.t.len()
+    }
+}
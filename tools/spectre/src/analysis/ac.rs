@@ -3,31 +3,38 @@
 use crate::{InstanceTail, SimSignal, Spectre};
 use arcstr::ArcStr;
 use num::complex::Complex64;
-use rust_decimal::Decimal;
 use scir::{NamedSliceOne, SliceOnePath};
 use serde::{Deserialize, Serialize};
+use simulator_common::export::{column_name, lookup_error, Table};
 use std::collections::HashMap;
+use std::io::Write;
 use std::sync::Arc;
 use substrate::{
     schematic::conv::ConvertedNodePath,
     simulation::{
-        data::{Save, SaveFreq, SaveOutput},
+        analysis as common,
+        data::{Save, SaveFreq, SaveOutput, SignalLookupError},
+        samples::Samples,
         Analysis, SimulationContext, Simulator, SupportedBy,
     },
-    types::schematic::{NestedNode, NestedTerminal, RawNestedNode},
+    types::schematic::{NestedNode, NestedTerminal, NodePath, RawNestedNode},
+    units::Frequency,
 };
 
 use super::Sweep;
+use crate::analysis::tran::PathResolver;
+
+pub use substrate::simulation::data::{CurrentSaveKey, VoltageSaveKey};
 
 /// An AC analysis.
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Ac {
-    /// Start frequency (Hz).
+    /// Start frequency.
     ///
     /// Defaults to 0.
-    pub start: Decimal,
-    /// Stop frequency (Hz).
-    pub stop: Decimal,
+    pub start: Frequency,
+    /// Stop frequency.
+    pub stop: Frequency,
     /// The sweep kind and number of points.
     pub sweep: Sweep,
 }
@@ -41,15 +48,74 @@ pub struct Output {
     pub raw_values: HashMap<ArcStr, Arc<Vec<Complex64>>>,
     /// A map from a save ID to a raw value identifier.
     pub(crate) saved_values: HashMap<u64, ArcStr>,
+    /// The netlist metadata used to resolve hierarchical paths.
+    ///
+    /// [`None`] if the output was not produced by a simulation.
+    pub(crate) resolver: Option<PathResolver>,
 }
 
-/// An identifier for a saved AC voltage.
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Serialize, Deserialize)]
-pub struct VoltageSaveKey(pub(crate) u64);
+impl Output {
+    /// Returns the complex samples of the signal with the given raw (netlisted) name.
+    ///
+    /// If no such signal was saved, the returned error lists similarly named saved signals.
+    pub fn raw_samples(&self, name: &str) -> Result<Samples, SignalLookupError> {
+        let x = self
+            .raw_values
+            .get(name)
+            .ok_or_else(|| SignalLookupError::not_found(name, self.raw_values.keys()))?;
+        Ok(Samples::Complex(x.clone()))
+    }
+
+    fn resolver(&self, path: &NodePath) -> Result<&PathResolver, SignalLookupError> {
+        self.resolver
+            .as_ref()
+            .ok_or_else(|| SignalLookupError::Unresolved(format!("{path:?}")))
+    }
+
+    /// Returns the complex voltage of the Substrate node at `path`.
+    ///
+    /// The node must have been saved during simulation.
+    pub fn node_voltage(&self, path: &NodePath) -> Result<&Arc<Vec<Complex64>>, SignalLookupError> {
+        let resolver = self.resolver(path)?;
+        resolver.voltage(&self.raw_values, &resolver.scir_path(path)?)
+    }
+
+    fn export_table(&self, paths: &[NodePath]) -> std::io::Result<Table> {
+        let mut table = Table::new("freq", self.freq.clone());
+        for path in paths {
+            let resolver = self.resolver(path).map_err(lookup_error)?;
+            let scir = resolver.scir_path(path).map_err(lookup_error)?;
+            let values = resolver
+                .voltage(&self.raw_values, &scir)
+                .map_err(lookup_error)?;
+            table.push_complex(&column_name(&resolver.scir_name(scir)), values);
+        }
+        Ok(table)
+    }
 
-/// An identifier for a saved AC current.
-#[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
-pub struct CurrentSaveKey(pub(crate) Vec<u64>);
+    /// Writes the frequency points and the voltages of the Substrate nodes at `paths` to `out`
+    /// in CSV format.
+    ///
+    /// Each voltage is written as two columns, `re(<name>)` and `im(<name>)`, containing its
+    /// real and imaginary parts, where `<name>` is the hierarchical SCIR path of its node as
+    /// described in [`column_name`]. All nodes must have been saved during simulation.
+    pub fn write_csv<W: Write>(&self, out: W, paths: &[NodePath]) -> std::io::Result<()> {
+        self.export_table(paths)?.write_csv(out)
+    }
+
+    /// Writes the frequency points and the voltages of the Substrate nodes at `paths` to `out`
+    /// as an Apache Parquet file.
+    ///
+    /// See [`Output::write_csv`] for how columns are named.
+    #[cfg(feature = "parquet")]
+    pub fn write_parquet<W: Write + Send>(
+        &self,
+        out: W,
+        paths: &[NodePath],
+    ) -> Result<(), parquet::errors::ParquetError> {
+        self.export_table(paths)?.write_parquet(out)
+    }
+}
 
 impl Analysis for Ac {
     type Output = Output;
@@ -67,6 +133,94 @@ impl SupportedBy<Spectre> for Ac {
     }
 }
 
+impl From<common::Ac> for Ac {
+    fn from(value: common::Ac) -> Self {
+        Self {
+            start: value.start,
+            stop: value.stop,
+            sweep: value.sweep.into(),
+        }
+    }
+}
+
+impl SupportedBy<Spectre> for common::Ac {
+    fn into_input(self, inputs: &mut Vec<<Spectre as Simulator>::Input>) {
+        Ac::from(self).into_input(inputs);
+    }
+    fn from_output(
+        outputs: &mut impl Iterator<Item = <Spectre as Simulator>::Output>,
+    ) -> <Self as Analysis>::Output {
+        let output = Ac::from_output(outputs);
+        common::AcOutput {
+            freq: output.freq,
+            raw_values: output.raw_values,
+            saved_values: output.saved_values,
+        }
+    }
+}
+
+impl Save<Spectre, common::Ac> for SaveOutput {
+    type SaveKey = ();
+    type Saved = common::AcOutput;
+
+    fn save(
+        &self,
+        _ctx: &SimulationContext<Spectre>,
+        _opts: &mut <Spectre as Simulator>::Options,
+    ) -> <Self as Save<Spectre, common::Ac>>::SaveKey {
+    }
+
+    fn from_saved(
+        output: &<common::Ac as Analysis>::Output,
+        _key: &<Self as Save<Spectre, common::Ac>>::SaveKey,
+    ) -> <Self as Save<Spectre, common::Ac>>::Saved {
+        output.clone()
+    }
+}
+
+impl Save<Spectre, common::Ac> for NestedNode {
+    type SaveKey = VoltageSaveKey;
+    type Saved = Arc<Vec<Complex64>>;
+
+    fn save(
+        &self,
+        ctx: &SimulationContext<Spectre>,
+        opts: &mut <Spectre as Simulator>::Options,
+    ) -> <Self as Save<Spectre, common::Ac>>::SaveKey {
+        <NestedNode as Save<Spectre, Ac>>::save(self, ctx, opts)
+    }
+
+    fn from_saved(
+        output: &<common::Ac as Analysis>::Output,
+        key: &<Self as Save<Spectre, common::Ac>>::SaveKey,
+    ) -> <Self as Save<Spectre, common::Ac>>::Saved {
+        output.saved(key)
+    }
+}
+
+impl Save<Spectre, common::Ac> for NestedTerminal {
+    type SaveKey = (VoltageSaveKey, CurrentSaveKey);
+    type Saved = common::AcTerminal;
+
+    fn save(
+        &self,
+        ctx: &SimulationContext<Spectre>,
+        opts: &mut <Spectre as Simulator>::Options,
+    ) -> <Self as Save<Spectre, common::Ac>>::SaveKey {
+        <NestedTerminal as Save<Spectre, Ac>>::save(self, ctx, opts)
+    }
+
+    fn from_saved(
+        output: &<common::Ac as Analysis>::Output,
+        key: &<Self as Save<Spectre, common::Ac>>::SaveKey,
+    ) -> <Self as Save<Spectre, common::Ac>>::Saved {
+        common::AcTerminal {
+            v: output.saved(&key.0),
+            i: output.saved(&key.1),
+        }
+    }
+}
+
 impl Save<Spectre, Ac> for SaveOutput {
     type SaveKey = ();
     type Saved = Output;
@@ -130,7 +284,7 @@ impl Save<Spectre, Ac> for NestedNode {
     ) -> <Self as Save<Spectre, Ac>>::Saved {
         output
             .raw_values
-            .get(output.saved_values.get(&key.0).unwrap())
+            .get(output.saved_values.get(&key.id()).unwrap())
             .unwrap()
             .clone()
     }
@@ -158,7 +312,7 @@ impl Save<Spectre, Ac> for RawNestedNode {
     ) -> <Self as Save<Spectre, Ac>>::Saved {
         output
             .raw_values
-            .get(output.saved_values.get(&key.0).unwrap())
+            .get(output.saved_values.get(&key.id()).unwrap())
             .unwrap()
             .clone()
     }
@@ -183,7 +337,7 @@ impl Save<Spectre, Ac> for NestedTerminal {
     ) -> <Self as Save<Spectre, Ac>>::SaveKey {
         (
             <NestedNode as Save<Spectre, Ac>>::save(self, ctx, opts),
-            CurrentSaveKey(
+            CurrentSaveKey::from_ids(
                 ctx.lib
                     .convert_terminal_path(&self.path())
                     .unwrap()
@@ -198,9 +352,9 @@ impl Save<Spectre, Ac> for NestedTerminal {
                                 NamedSliceOne::new(port.clone()),
                             ),
                         }))
-                        .0
-                    })
-                    .collect(),
+                        .ids()
+                        .to_vec()
+                    }),
             ),
         )
     }
@@ -211,12 +365,12 @@ impl Save<Spectre, Ac> for NestedTerminal {
     ) -> <Self as Save<Spectre, Ac>>::Saved {
         let v = output
             .raw_values
-            .get(output.saved_values.get(&key.0 .0).unwrap())
+            .get(output.saved_values.get(&key.0.id()).unwrap())
             .unwrap()
             .clone();
         let currents: Vec<Arc<Vec<Complex64>>> = key
             .1
-             .0
+            .ids()
             .iter()
             .map(|key| {
                 output
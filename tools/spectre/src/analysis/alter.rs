@@ -0,0 +1,137 @@
+//! Spectre alter group data structures.
+//!
+//! Alter groups allow a single Spectre invocation to rerun a set of analyses
+//! under several parameter or corner variations, avoiding the cost of
+//! renetlisting and restarting the simulator for each variation.
+
+use crate::{Input, Spectre};
+use arcstr::ArcStr;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::ops::Deref;
+use std::path::PathBuf;
+use substrate::simulation::data::Save;
+use substrate::simulation::{Analysis, Simulator, SupportedBy};
+use substrate::types::schematic::{NestedNode, NestedTerminal, RawNestedNode};
+use type_dispatch::impl_dispatch;
+
+/// A single modification applied by an alter group.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Alteration {
+    /// Sets a netlist parameter to the given value.
+    ///
+    /// The parameter must already be declared in the netlist.
+    Param {
+        /// The name of the parameter.
+        name: ArcStr,
+        /// The new value of the parameter.
+        value: Decimal,
+    },
+    /// Includes the given section of a model library.
+    ///
+    /// Typically used to switch process corners.
+    Section {
+        /// The path to the model library.
+        path: PathBuf,
+        /// The section to include.
+        section: ArcStr,
+    },
+    /// A raw Spectre statement to place within the alter group.
+    Raw(ArcStr),
+}
+
+/// A set of analyses to run once per alter group.
+///
+/// Each alter group is applied relative to the original netlist;
+/// alterations do not accumulate across groups.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Alter<A> {
+    /// The alter groups to simulate.
+    ///
+    /// The analyses are run once for each group.
+    pub groups: Vec<Vec<Alteration>>,
+    /// The analysis to run.
+    pub analysis: A,
+}
+
+/// The output of an [`Alter`] analysis.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Output<T>(pub(crate) Vec<T>);
+
+impl<T> Deref for Output<T> {
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> Output<T> {
+    /// Returns the underlying vector of outputs for each alter group,
+    /// in the order the groups were specified.
+    pub fn into_inner(self) -> Vec<T> {
+        self.0
+    }
+}
+
+impl<A: SupportedBy<Spectre>> From<Alter<A>> for Alter<Vec<Input>> {
+    fn from(value: Alter<A>) -> Self {
+        let mut analysis = Vec::new();
+        value.analysis.into_input(&mut analysis);
+        Alter {
+            groups: value.groups,
+            analysis,
+        }
+    }
+}
+
+#[impl_dispatch({NestedNode; RawNestedNode; NestedTerminal})]
+impl<T, A: Analysis> Save<Spectre, Alter<A>> for T
+where
+    T: Save<Spectre, A>,
+{
+    type SaveKey = <T as Save<Spectre, A>>::SaveKey;
+    type Saved = Vec<<T as Save<Spectre, A>>::Saved>;
+
+    fn save(
+        &self,
+        ctx: &substrate::simulation::SimulationContext<Spectre>,
+        opts: &mut <Spectre as Simulator>::Options,
+    ) -> <Self as Save<Spectre, Alter<A>>>::SaveKey {
+        self.save(ctx, opts)
+    }
+
+    fn from_saved(
+        output: &<Alter<A> as Analysis>::Output,
+        key: &<Self as Save<Spectre, Alter<A>>>::SaveKey,
+    ) -> <Self as Save<Spectre, Alter<A>>>::Saved {
+        output
+            .0
+            .iter()
+            .map(|output| T::from_saved(output, key))
+            .collect()
+    }
+}
+
+impl<A: Analysis> Analysis for Alter<A> {
+    type Output = Output<A::Output>;
+}
+
+impl<A: SupportedBy<Spectre>> SupportedBy<Spectre> for Alter<A> {
+    fn into_input(self, inputs: &mut Vec<<Spectre as Simulator>::Input>) {
+        inputs.push(self.into());
+    }
+    fn from_output(
+        outputs: &mut impl Iterator<Item = <Spectre as Simulator>::Output>,
+    ) -> <Self as Analysis>::Output {
+        let item = outputs.next().unwrap();
+        let output: Output<Vec<crate::Output>> = item.try_into().unwrap();
+        Output(
+            output
+                .0
+                .into_iter()
+                .map(|out| A::from_output(&mut out.into_iter()))
+                .collect(),
+        )
+    }
+}
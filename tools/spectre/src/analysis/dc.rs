@@ -8,12 +8,15 @@ use std::collections::HashMap;
 use substrate::{
     schematic::conv::ConvertedNodePath,
     simulation::{
+        analysis as common,
         data::{Save, SaveOutput},
         Analysis, SimulationContext, Simulator, SupportedBy,
     },
     types::schematic::{NestedNode, NestedTerminal, RawNestedNode},
 };
 
+pub use substrate::simulation::data::{CurrentSaveKey, VoltageSaveKey};
+
 /// A DC operating point analysis.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DcOp;
@@ -27,14 +30,6 @@ pub struct OpOutput {
     pub(crate) saved_values: HashMap<u64, ArcStr>,
 }
 
-/// An identifier for a saved DC voltage.
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Serialize, Deserialize)]
-pub struct VoltageSaveKey(pub(crate) u64);
-
-/// An identifier for a saved DC current.
-#[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
-pub struct CurrentSaveKey(pub(crate) Vec<u64>);
-
 impl Analysis for DcOp {
     type Output = OpOutput;
 }
@@ -51,6 +46,83 @@ impl SupportedBy<Spectre> for DcOp {
     }
 }
 
+impl SupportedBy<Spectre> for common::Dc {
+    fn into_input(self, inputs: &mut Vec<<Spectre as Simulator>::Input>) {
+        DcOp.into_input(inputs);
+    }
+    fn from_output(
+        outputs: &mut impl Iterator<Item = <Spectre as Simulator>::Output>,
+    ) -> <Self as Analysis>::Output {
+        let output = DcOp::from_output(outputs);
+        common::DcOutput {
+            raw_values: output.raw_values,
+            saved_values: output.saved_values,
+        }
+    }
+}
+
+impl Save<Spectre, common::Dc> for SaveOutput {
+    type SaveKey = ();
+    type Saved = common::DcOutput;
+
+    fn save(
+        &self,
+        _ctx: &SimulationContext<Spectre>,
+        _opts: &mut <Spectre as Simulator>::Options,
+    ) -> <Self as Save<Spectre, common::Dc>>::SaveKey {
+    }
+
+    fn from_saved(
+        output: &<common::Dc as Analysis>::Output,
+        _key: &<Self as Save<Spectre, common::Dc>>::SaveKey,
+    ) -> <Self as Save<Spectre, common::Dc>>::Saved {
+        output.clone()
+    }
+}
+
+impl Save<Spectre, common::Dc> for NestedNode {
+    type SaveKey = VoltageSaveKey;
+    type Saved = f64;
+
+    fn save(
+        &self,
+        ctx: &SimulationContext<Spectre>,
+        opts: &mut <Spectre as Simulator>::Options,
+    ) -> <Self as Save<Spectre, common::Dc>>::SaveKey {
+        <NestedNode as Save<Spectre, DcOp>>::save(self, ctx, opts)
+    }
+
+    fn from_saved(
+        output: &<common::Dc as Analysis>::Output,
+        key: &<Self as Save<Spectre, common::Dc>>::SaveKey,
+    ) -> <Self as Save<Spectre, common::Dc>>::Saved {
+        output.saved(key)
+    }
+}
+
+impl Save<Spectre, common::Dc> for NestedTerminal {
+    type SaveKey = (VoltageSaveKey, CurrentSaveKey);
+    type Saved = common::DcTerminal;
+
+    fn save(
+        &self,
+        ctx: &SimulationContext<Spectre>,
+        opts: &mut <Spectre as Simulator>::Options,
+    ) -> <Self as Save<Spectre, common::Dc>>::SaveKey {
+        <NestedTerminal as Save<Spectre, DcOp>>::save(self, ctx, opts)
+    }
+
+    fn from_saved(
+        output: &<common::Dc as Analysis>::Output,
+        key: &<Self as Save<Spectre, common::Dc>>::SaveKey,
+    ) -> <Self as Save<Spectre, common::Dc>>::Saved {
+        common::DcTerminal {
+            v: output.saved(&key.0),
+            i: output.saved(&key.1),
+        }
+    }
+}
+
 impl Save<Spectre, DcOp> for SaveOutput {
     type SaveKey = ();
     type Saved = OpOutput;
@@ -95,7 +167,7 @@ impl Save<Spectre, DcOp> for NestedNode {
     ) -> <Self as Save<Spectre, DcOp>>::Saved {
         *output
             .raw_values
-            .get(output.saved_values.get(&key.0).unwrap())
+            .get(output.saved_values.get(&key.id()).unwrap())
             .unwrap()
     }
 }
@@ -122,7 +194,7 @@ impl Save<Spectre, DcOp> for RawNestedNode {
     ) -> <Self as Save<Spectre, DcOp>>::Saved {
         *output
             .raw_values
-            .get(output.saved_values.get(&key.0).unwrap())
+            .get(output.saved_values.get(&key.id()).unwrap())
             .unwrap()
     }
 }
@@ -146,7 +218,7 @@ impl Save<Spectre, DcOp> for NestedTerminal {
     ) -> <Self as Save<Spectre, DcOp>>::SaveKey {
         (
             <NestedNode as Save<Spectre, DcOp>>::save(self, ctx, opts),
-            CurrentSaveKey(
+            CurrentSaveKey::from_ids(
                 ctx.lib
                     .convert_terminal_path(&self.path())
                     .unwrap()
@@ -161,9 +233,9 @@ impl Save<Spectre, DcOp> for NestedTerminal {
                                 NamedSliceOne::new(port.clone()),
                             ),
                         }))
-                        .0
-                    })
-                    .collect(),
+                        .ids()
+                        .to_vec()
+                    }),
             ),
         )
     }
@@ -174,11 +246,11 @@ impl Save<Spectre, DcOp> for NestedTerminal {
     ) -> <Self as Save<Spectre, DcOp>>::Saved {
         let v = *output
             .raw_values
-            .get(output.saved_values.get(&key.0 .0).unwrap())
+            .get(output.saved_values.get(&key.0.id()).unwrap())
             .unwrap();
         let i = key
             .1
-             .0
+            .ids()
             .iter()
             .map(|key| {
                 output
@@ -0,0 +1,547 @@
+//! Spectre `info` statements and device operating region reporting.
+//!
+//! An [`Info`] analysis reports information about the circuit, such as the operating point
+//! parameters of each device. Operating point information is typically requested
+//! after a DC operating point analysis:
+//!
+//! ```ignore
+//! let (_, info) = sim.simulate_default(opts, (DcOp, Info::new(InfoWhat::OpPoint)))?;
+//! for (inst, region) in info.regions() {
+//!     println!("{inst}: {region}");
+//! }
+//! let region = info.region(&sim.tb.data().m1.path())?;
+//! assert!(region.is_some_and(|region| region.is_saturated()));
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{Display, Formatter};
+use std::io::Write;
+
+use arcstr::ArcStr;
+use num::complex::Complex64;
+use serde::{Deserialize, Serialize};
+use substrate::schematic::InstancePath;
+use substrate::simulation::data::SignalLookupError;
+use substrate::simulation::{Analysis, Simulator, SupportedBy};
+
+use crate::analysis::tran::PathResolver;
+use crate::error::{Error, Result};
+use crate::Spectre;
+
+/// The information reported by an [`Info`] analysis.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum InfoWhat {
+    /// Operating point parameters of each device.
+    OpPoint,
+    /// Model parameters.
+    Models,
+    /// Terminal connections of each instance.
+    Terminals,
+}
+
+impl Display for InfoWhat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::OpPoint => write!(f, "oppoint"),
+            Self::Models => write!(f, "models"),
+            Self::Terminals => write!(f, "terminals"),
+        }
+    }
+}
+
+/// A Spectre `info` statement.
+///
+/// Results are written to the raw output directory and parsed into an [`Output`].
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Info {
+    /// The information to report.
+    pub what: InfoWhat,
+}
+
+impl Info {
+    /// Creates a new `info` statement reporting `what`.
+    pub fn new(what: InfoWhat) -> Self {
+        Self { what }
+    }
+
+    pub(crate) fn netlist<W: Write>(&self, out: &mut W) -> Result<()> {
+        write!(out, "info what={} where=rawfile", self.what)?;
+        Ok(())
+    }
+}
+
+/// A value reported by an [`Info`] analysis.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum InfoValue {
+    /// A real number.
+    Real(f64),
+    /// A complex number.
+    Complex(Complex64),
+    /// A string.
+    Str(ArcStr),
+}
+
+impl InfoValue {
+    /// Returns the value as a real number, if it is one.
+    pub fn as_real(&self) -> Option<f64> {
+        match self {
+            Self::Real(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as a string, if it is one.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::Str(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+/// The information reported for a single instance or model.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct InfoEntry {
+    /// The Spectre primitive or type of the entry (e.g. `bsim4`), if reported.
+    pub primitive: Option<ArcStr>,
+    /// A map from parameter name to value.
+    pub values: HashMap<ArcStr, InfoValue>,
+}
+
+impl InfoEntry {
+    /// Returns the value of the parameter with the given name.
+    pub fn get(&self, name: &str) -> Option<&InfoValue> {
+        self.values.get(name)
+    }
+
+    /// Returns the operating region of the device, if reported.
+    pub fn region(&self) -> Option<OpRegion> {
+        OpRegion::from_value(self.get("region")?)
+    }
+}
+
+/// The operating region of a transistor.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd, Serialize, Deserialize)]
+pub enum OpRegion {
+    /// The device is off.
+    Off,
+    /// The device is in the triode (linear) region.
+    Triode,
+    /// The device is in saturation.
+    Saturation,
+    /// The device is in subthreshold.
+    Subthreshold,
+    /// The device is in breakdown.
+    Breakdown,
+}
+
+impl OpRegion {
+    /// Converts a reported `region` parameter to an operating region.
+    ///
+    /// Spectre reports regions either by name or by index (0 through 4, in the order of
+    /// the variants of [`OpRegion`]).
+    pub fn from_value(value: &InfoValue) -> Option<Self> {
+        match value {
+            InfoValue::Real(index) => match *index as i64 {
+                0 => Some(Self::Off),
+                1 => Some(Self::Triode),
+                2 => Some(Self::Saturation),
+                3 => Some(Self::Subthreshold),
+                4 => Some(Self::Breakdown),
+                _ => None,
+            },
+            InfoValue::Str(name) => match name.to_lowercase().as_str() {
+                "off" | "cutoff" => Some(Self::Off),
+                "triode" | "linear" => Some(Self::Triode),
+                "sat" | "saturation" => Some(Self::Saturation),
+                "subth" | "subthreshold" => Some(Self::Subthreshold),
+                "breakdown" => Some(Self::Breakdown),
+                _ => None,
+            },
+            InfoValue::Complex(_) => None,
+        }
+    }
+
+    /// Returns `true` if the device is in saturation.
+    pub fn is_saturated(&self) -> bool {
+        *self == Self::Saturation
+    }
+
+    /// Returns `true` if the device is in the triode region.
+    pub fn is_triode(&self) -> bool {
+        *self == Self::Triode
+    }
+}
+
+impl Display for OpRegion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Off => write!(f, "off"),
+            Self::Triode => write!(f, "triode"),
+            Self::Saturation => write!(f, "saturation"),
+            Self::Subthreshold => write!(f, "subthreshold"),
+            Self::Breakdown => write!(f, "breakdown"),
+        }
+    }
+}
+
+/// The result of an [`Info`] analysis.
+#[derive(Debug, Clone)]
+pub struct Output {
+    /// A map from raw (netlisted) instance or model name to the reported information.
+    pub entries: HashMap<ArcStr, InfoEntry>,
+    /// The netlist metadata used to resolve hierarchical paths.
+    ///
+    /// [`None`] if the output was not produced by a simulation.
+    pub(crate) resolver: Option<PathResolver>,
+}
+
+impl Output {
+    /// Returns the information reported for the given raw (netlisted) name.
+    ///
+    /// If no such entry was reported, the returned error lists similarly named entries.
+    pub fn raw_entry(&self, name: &str) -> std::result::Result<&InfoEntry, SignalLookupError> {
+        self.entries
+            .get(name)
+            .ok_or_else(|| SignalLookupError::not_found(name, self.entries.keys()))
+    }
+
+    /// Returns the information reported for the Substrate instance at `path`.
+    ///
+    /// Paths are typically obtained using [`NestedInstance::path`](substrate::schematic::NestedInstance::path).
+    pub fn instance(
+        &self,
+        path: &InstancePath,
+    ) -> std::result::Result<&InfoEntry, SignalLookupError> {
+        let unresolved = || SignalLookupError::Unresolved(format!("{path:?}"));
+        let resolver = self.resolver.as_ref().ok_or_else(unresolved)?;
+        let scir = resolver
+            .lib
+            .convert_instance_path(path)
+            .ok_or_else(unresolved)?;
+        self.raw_entry(&Spectre::instance_path(
+            &resolver.lib.scir,
+            &resolver.conv,
+            &scir,
+        ))
+    }
+
+    /// Returns the operating region of the Substrate instance at `path`.
+    ///
+    /// Returns `Ok(None)` if the instance was found but no region was reported for it.
+    pub fn region(
+        &self,
+        path: &InstancePath,
+    ) -> std::result::Result<Option<OpRegion>, SignalLookupError> {
+        Ok(self.instance(path)?.region())
+    }
+
+    /// Returns the operating region of every device for which a region was reported,
+    /// keyed by raw (netlisted) instance name.
+    pub fn regions(&self) -> BTreeMap<&ArcStr, OpRegion> {
+        self.entries
+            .iter()
+            .filter_map(|(name, entry)| Some((name, entry.region()?)))
+            .collect()
+    }
+}
+
+impl Analysis for Info {
+    type Output = Output;
+}
+
+impl SupportedBy<Spectre> for Info {
+    fn into_input(self, inputs: &mut Vec<<Spectre as Simulator>::Input>) {
+        inputs.push(self.into());
+    }
+    fn from_output(
+        outputs: &mut impl Iterator<Item = <Spectre as Simulator>::Output>,
+    ) -> <Self as Analysis>::Output {
+        let item = outputs.next().unwrap();
+        item.try_into().unwrap()
+    }
+}
+
+/// A token of an ASCII PSF file.
+#[derive(Clone, Debug, PartialEq)]
+enum Token<'a> {
+    Str(&'a str),
+    Word(&'a str),
+    Open,
+    Close,
+}
+
+fn tokenize(psf: &str) -> Result<Vec<Token<'_>>> {
+    let mut tokens = Vec::new();
+    let mut rest = psf;
+    loop {
+        rest = rest.trim_start();
+        let Some(c) = rest.chars().next() else {
+            return Ok(tokens);
+        };
+        match c {
+            '(' => {
+                tokens.push(Token::Open);
+                rest = &rest[1..];
+            }
+            ')' => {
+                tokens.push(Token::Close);
+                rest = &rest[1..];
+            }
+            '"' => {
+                let mut end = None;
+                let mut escaped = false;
+                for (i, c) in rest.char_indices().skip(1) {
+                    match c {
+                        '\\' if !escaped => escaped = true,
+                        '"' if !escaped => {
+                            end = Some(i);
+                            break;
+                        }
+                        _ => escaped = false,
+                    }
+                }
+                let end = end.ok_or(Error::Parse)?;
+                tokens.push(Token::Str(&rest[1..end]));
+                rest = &rest[end + 1..];
+            }
+            _ => {
+                let end = rest
+                    .find(|c: char| c.is_whitespace() || c == '(' || c == ')' || c == '"')
+                    .unwrap_or(rest.len());
+                tokens.push(Token::Word(&rest[..end]));
+                rest = &rest[end..];
+            }
+        }
+    }
+}
+
+/// Parses ASCII PSF `info` output into a map from entry name to entry.
+pub(crate) fn parse_info(psf: &str) -> Result<HashMap<ArcStr, InfoEntry>> {
+    let tokens = tokenize(psf)?;
+    let mut tokens = tokens.into_iter().peekable();
+    let mut types: HashMap<&str, Option<Vec<Field<'_>>>> = HashMap::new();
+    let mut entries = HashMap::new();
+
+    let is_section = |token: &Token<'_>| {
+        matches!(
+            token,
+            Token::Word("HEADER" | "TYPE" | "SWEEP" | "TRACE" | "VALUE" | "END")
+        )
+    };
+
+    while let Some(token) = tokens.next() {
+        match token {
+            Token::Word("END") => break,
+            Token::Word("TYPE") => {
+                while let Some(Token::Str(name)) = tokens.peek().cloned() {
+                    tokens.next();
+                    types.insert(name, parse_kinds(&mut tokens)?);
+                }
+            }
+            Token::Word("VALUE") => {
+                while let Some(Token::Str(name)) = tokens.peek().cloned() {
+                    tokens.next();
+                    // Entries of a declared type are followed by the type name.
+                    let primitive = match tokens.peek() {
+                        Some(Token::Str(primitive)) if types.contains_key(primitive) => {
+                            let primitive = *primitive;
+                            tokens.next();
+                            Some(primitive)
+                        }
+                        _ => None,
+                    };
+                    let fields = primitive.and_then(|primitive| types[primitive].as_ref());
+                    let values = match fields {
+                        Some(fields) => {
+                            let mut values = HashMap::new();
+                            parse_struct_values(&mut tokens, fields, "", &mut values)?;
+                            skip_props(&mut tokens)?;
+                            values
+                        }
+                        // Scalar values are keyed by the entry name.
+                        None => parse_values(&mut tokens)?
+                            .into_iter()
+                            .map(|value| (ArcStr::from(name), value))
+                            .collect(),
+                    };
+                    entries.insert(
+                        ArcStr::from(name),
+                        InfoEntry {
+                            primitive: primitive.map(ArcStr::from),
+                            values,
+                        },
+                    );
+                }
+            }
+            // Skip the contents of other sections.
+            _ => {
+                while tokens.peek().is_some_and(|token| !is_section(token)) {
+                    tokens.next();
+                }
+            }
+        }
+    }
+
+    Ok(entries)
+}
+
+/// A field of a PSF struct type.
+struct Field<'a> {
+    name: &'a str,
+    /// The fields of the field's type, if it is a struct.
+    fields: Option<Vec<Field<'a>>>,
+}
+
+/// Parses a list of PSF type kinds.
+///
+/// Returns the fields of the type if it is a struct.
+fn parse_kinds<'a>(
+    tokens: &mut std::iter::Peekable<impl Iterator<Item = Token<'a>>>,
+) -> Result<Option<Vec<Field<'a>>>> {
+    let mut fields = None;
+    while let Some(Token::Word(kind)) = tokens.peek().cloned() {
+        tokens.next();
+        match kind {
+            "STRUCT" => {
+                expect_open(tokens)?;
+                let mut struct_fields = Vec::new();
+                while let Some(Token::Str(name)) = tokens.peek().cloned() {
+                    tokens.next();
+                    struct_fields.push(Field {
+                        name,
+                        fields: parse_kinds(tokens)?,
+                    });
+                }
+                expect_close(tokens)?;
+                fields = Some(struct_fields);
+            }
+            "PROP" | "ARRAY" => skip_group(tokens)?,
+            _ => {}
+        }
+    }
+    Ok(fields)
+}
+
+/// Parses the value of a PSF struct with the given fields into `values`.
+///
+/// Nested struct fields are flattened into their parent, keyed by `<field>.<nested field>`.
+/// Returns an error if the number of values does not match the number of fields.
+fn parse_struct_values<'a>(
+    tokens: &mut std::iter::Peekable<impl Iterator<Item = Token<'a>>>,
+    fields: &[Field<'a>],
+    prefix: &str,
+    values: &mut HashMap<ArcStr, InfoValue>,
+) -> Result<()> {
+    expect_open(tokens)?;
+    for field in fields {
+        skip_props(tokens)?;
+        let name = arcstr::format!("{prefix}{}", field.name);
+        match &field.fields {
+            Some(fields) => parse_struct_values(tokens, fields, &format!("{name}."), values)?,
+            None => {
+                let value = match tokens.next().ok_or(Error::Parse)? {
+                    Token::Open => match parse_scalars_until_close(tokens)?.as_slice() {
+                        [re, im] => InfoValue::Complex(Complex64::new(*re, *im)),
+                        _ => return Err(Error::Parse),
+                    },
+                    token => parse_scalar(token)?,
+                };
+                values.insert(name, value);
+            }
+        }
+    }
+    skip_props(tokens)?;
+    expect_close(tokens)
+}
+
+/// Parses the value(s) of a single PSF `VALUE` entry.
+fn parse_values<'a>(
+    tokens: &mut std::iter::Peekable<impl Iterator<Item = Token<'a>>>,
+) -> Result<Vec<InfoValue>> {
+    let mut values = Vec::new();
+    match tokens.next().ok_or(Error::Parse)? {
+        Token::Open => loop {
+            match tokens.next().ok_or(Error::Parse)? {
+                Token::Close => break,
+                Token::Open => {
+                    let parts = parse_scalars_until_close(tokens)?;
+                    values.push(match parts.as_slice() {
+                        [re, im] => InfoValue::Complex(Complex64::new(*re, *im)),
+                        _ => return Err(Error::Parse),
+                    });
+                }
+                Token::Word("PROP") => skip_group(tokens)?,
+                token => values.push(parse_scalar(token)?),
+            }
+        },
+        token => values.push(parse_scalar(token)?),
+    }
+    if tokens.peek() == Some(&Token::Word("PROP")) {
+        tokens.next();
+        skip_group(tokens)?;
+    }
+    Ok(values)
+}
+
+fn parse_scalar(token: Token<'_>) -> Result<InfoValue> {
+    Ok(match token {
+        Token::Str(value) => InfoValue::Str(ArcStr::from(value)),
+        Token::Word(value) => InfoValue::Real(value.parse::<f64>().map_err(|_| Error::Parse)?),
+        Token::Open | Token::Close => return Err(Error::Parse),
+    })
+}
+
+fn parse_scalars_until_close<'a>(
+    tokens: &mut std::iter::Peekable<impl Iterator<Item = Token<'a>>>,
+) -> Result<Vec<f64>> {
+    let mut values = Vec::new();
+    loop {
+        match tokens.next().ok_or(Error::Parse)? {
+            Token::Close => return Ok(values),
+            Token::Word(value) => values.push(value.parse().map_err(|_| Error::Parse)?),
+            _ => return Err(Error::Parse),
+        }
+    }
+}
+
+fn expect_open<'a>(
+    tokens: &mut std::iter::Peekable<impl Iterator<Item = Token<'a>>>,
+) -> Result<()> {
+    match tokens.next() {
+        Some(Token::Open) => Ok(()),
+        _ => Err(Error::Parse),
+    }
+}
+
+fn expect_close<'a>(
+    tokens: &mut std::iter::Peekable<impl Iterator<Item = Token<'a>>>,
+) -> Result<()> {
+    match tokens.next() {
+        Some(Token::Close) => Ok(()),
+        _ => Err(Error::Parse),
+    }
+}
+
+/// Skips any `PROP` groups at the front of `tokens`.
+fn skip_props<'a>(tokens: &mut std::iter::Peekable<impl Iterator<Item = Token<'a>>>) -> Result<()> {
+    while tokens.peek() == Some(&Token::Word("PROP")) {
+        tokens.next();
+        skip_group(tokens)?;
+    }
+    Ok(())
+}
+
+/// Skips a parenthesized group, including any nested groups.
+fn skip_group<'a>(tokens: &mut std::iter::Peekable<impl Iterator<Item = Token<'a>>>) -> Result<()> {
+    expect_open(tokens)?;
+    let mut depth = 1;
+    while depth > 0 {
+        match tokens.next().ok_or(Error::Parse)? {
+            Token::Open => depth += 1,
+            Token::Close => depth -= 1,
+            _ => {}
+        }
+    }
+    Ok(())
+}
@@ -1,10 +1,24 @@
 //! Spectre analyses.
 
+use std::io::Write;
+
 use serde::{Deserialize, Serialize};
+use substrate::simulation::analysis as common;
 
 pub mod ac;
+pub mod alter;
 pub mod dc;
+pub mod info;
 pub mod montecarlo;
+pub mod noise;
+pub mod pac;
+pub mod pnoise;
+pub mod pss;
+pub mod refine;
+pub mod reliability;
+pub mod sens;
+pub mod stb;
+pub mod sweep;
 pub mod tran;
 
 /// Sweep kinds.
@@ -17,3 +31,23 @@ pub enum Sweep {
     /// Logarithmic sweep with the given number of points **per decade**.
     Decade(usize),
 }
+
+impl From<common::Sweep> for Sweep {
+    fn from(value: common::Sweep) -> Self {
+        match value {
+            common::Sweep::Linear(n) => Sweep::Linear(n),
+            common::Sweep::Decade(n) => Sweep::Decade(n),
+        }
+    }
+}
+
+impl Sweep {
+    /// Writes the sweep parameter of a frequency-domain analysis.
+    pub(crate) fn netlist<W: Write>(&self, out: &mut W) -> std::io::Result<()> {
+        match self {
+            Sweep::Linear(pts) => write!(out, " lin={pts}"),
+            Sweep::Logarithmic(pts) => write!(out, " log={pts}"),
+            Sweep::Decade(pts) => write!(out, " dec={pts}"),
+        }
+    }
+}
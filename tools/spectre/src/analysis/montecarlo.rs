@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
 use std::ops::Deref;
 use substrate::simulation::data::Save;
+use substrate::simulation::yield_analysis::YieldEstimate;
 use substrate::simulation::{Analysis, Simulator, SupportedBy};
 use substrate::types::schematic::{NestedNode, NestedTerminal, RawNestedNode};
 use type_dispatch::impl_dispatch;
@@ -50,15 +51,65 @@ pub struct MonteCarlo<A> {
     pub analysis: A,
 }
 
+/// The random number generator settings of a single Monte Carlo iteration.
+///
+/// Spectre draws the random values of each iteration from the starting seed and the
+/// iteration number, so an iteration can be reproduced in isolation using
+/// [`MonteCarlo::rerun`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Iteration {
+    /// The Spectre iteration number, starting from 1.
+    pub run: usize,
+    /// The starting seed of the Monte Carlo analysis.
+    ///
+    /// [`None`] if no seed was specified, in which case Spectre's default seed was used.
+    pub seed: Option<u64>,
+}
+
+impl<A> MonteCarlo<A> {
+    /// The iterations performed by this analysis, in order.
+    pub fn iterations(&self) -> Vec<Iteration> {
+        let firstrun = self.firstrun.unwrap_or(1);
+        (firstrun..firstrun + self.numruns)
+            .map(|run| Iteration {
+                run,
+                seed: self.seed,
+            })
+            .collect()
+    }
+
+    /// Returns an analysis that reruns only the given iteration.
+    ///
+    /// The iteration sees the same statistical variations as it did in the original analysis,
+    /// provided the testbench and variations are unchanged.
+    pub fn rerun(&self, iteration: Iteration) -> Self
+    where
+        A: Clone,
+    {
+        Self {
+            variations: self.variations,
+            numruns: 1,
+            seed: iteration.seed,
+            firstrun: Some(iteration.run),
+            analysis: self.analysis.clone(),
+        }
+    }
+}
+
 /// A Monte Carlo simulation output.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct Output<T>(pub(crate) Vec<T>);
+pub struct Output<T> {
+    /// The settings of each iteration.
+    pub(crate) iterations: Vec<Iteration>,
+    /// The output of each iteration.
+    pub(crate) outputs: Vec<T>,
+}
 
 impl<T> Deref for Output<T> {
     type Target = Vec<T>;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.outputs
     }
 }
 
@@ -66,7 +117,42 @@ impl<T> Output<T> {
     /// Returns the underlying vector of outputs for each
     /// iteration of the Monte Carlo simulation.
     pub fn into_inner(self) -> Vec<T> {
-        self.0
+        self.outputs
+    }
+
+    /// The settings of each iteration, in the same order as the outputs.
+    ///
+    /// Record these alongside derived results so that any iteration can be rerun
+    /// with [`MonteCarlo::rerun`].
+    pub fn iterations(&self) -> &[Iteration] {
+        &self.iterations
+    }
+
+    /// Returns the iterations in which the design fails.
+    ///
+    /// `pass` returns `true` if the design passes in the given iteration.
+    pub fn failures(&self, mut pass: impl FnMut(&T) -> bool) -> Vec<Iteration> {
+        self.iterations
+            .iter()
+            .zip(self.outputs.iter())
+            .filter(|(_, output)| !pass(output))
+            .map(|(iteration, _)| *iteration)
+            .collect()
+    }
+
+    /// Estimates the failure probability from the iterations of the Monte Carlo simulation.
+    ///
+    /// `pass` returns `true` if the design passes in the given iteration.
+    pub fn yield_estimate(&self, confidence: f64, pass: impl FnMut(&T) -> bool) -> YieldEstimate {
+        YieldEstimate::from_outcomes(self.outputs.iter().map(pass), confidence)
+    }
+
+    /// Applies `f` to the output of each iteration, preserving the iteration settings.
+    pub(crate) fn map<U>(self, f: impl FnMut(T) -> U) -> Output<U> {
+        Output {
+            iterations: self.iterations,
+            outputs: self.outputs.into_iter().map(f).collect(),
+        }
     }
 }
 
@@ -90,7 +176,7 @@ where
     T: Save<Spectre, A>,
 {
     type SaveKey = <T as Save<Spectre, A>>::SaveKey;
-    type Saved = Vec<<T as Save<Spectre, A>>::Saved>;
+    type Saved = Output<<T as Save<Spectre, A>>::Saved>;
 
     fn save(
         &self,
@@ -104,11 +190,14 @@ where
         output: &<MonteCarlo<A> as Analysis>::Output,
         key: &<Self as Save<Spectre, MonteCarlo<A>>>::SaveKey,
     ) -> <Self as Save<Spectre, MonteCarlo<A>>>::Saved {
-        output
-            .0
-            .iter()
-            .map(|output| T::from_saved(output, key))
-            .collect()
+        Output {
+            iterations: output.iterations.clone(),
+            outputs: output
+                .outputs
+                .iter()
+                .map(|output| T::from_saved(output, key))
+                .collect(),
+        }
     }
 }
 
@@ -152,12 +241,6 @@ impl<A: SupportedBy<Spectre>> SupportedBy<Spectre> for MonteCarlo<A> {
     ) -> <Self as Analysis>::Output {
         let item = outputs.next().unwrap();
         let output: Output<Vec<crate::Output>> = item.try_into().unwrap();
-        Output(
-            output
-                .0
-                .into_iter()
-                .map(|out| A::from_output(&mut out.into_iter()))
-                .collect(),
-        )
+        output.map(|out| A::from_output(&mut out.into_iter()))
     }
 }
@@ -0,0 +1,220 @@
+//! Spectre small-signal noise analysis options and data structures.
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::Arc;
+
+use arcstr::ArcStr;
+use scir::NetlistLibConversion;
+use serde::{Deserialize, Serialize};
+use substrate::schematic::conv::RawLib;
+use substrate::simulation::analysis::{self as common, InstanceRef, NodeRef};
+use substrate::simulation::data::{Save, SaveOutput, SignalLookupError};
+use substrate::simulation::{Analysis, SimulationContext, Simulator, SupportedBy};
+use substrate::units::Frequency;
+
+use super::Sweep;
+use crate::error::Result;
+use crate::{raw_instance, raw_node, resolve_instance, resolve_node, Spectre};
+
+/// The name of the output noise signal written by Spectre.
+pub(crate) const OUTPUT_NOISE: &str = "out";
+/// The name of the input-referred noise signal written by Spectre.
+pub(crate) const INPUT_NOISE: &str = "in";
+
+/// A small-signal noise analysis.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Noise {
+    /// The positive output node.
+    pub output: NodeRef,
+    /// The negative output node.
+    ///
+    /// Defaults to ground.
+    pub reference: Option<NodeRef>,
+    /// The independent source to which noise is referred.
+    ///
+    /// If [`None`], input-referred noise is not computed.
+    pub input_source: Option<InstanceRef>,
+    /// Start frequency.
+    pub start: Frequency,
+    /// Stop frequency.
+    pub stop: Frequency,
+    /// The sweep kind and number of points.
+    pub sweep: Sweep,
+}
+
+impl Noise {
+    /// Creates a new noise analysis at the given output node.
+    pub fn new(
+        output: impl Into<NodeRef>,
+        start: Frequency,
+        stop: Frequency,
+        sweep: Sweep,
+    ) -> Self {
+        Self {
+            output: output.into(),
+            reference: None,
+            input_source: None,
+            start,
+            stop,
+            sweep,
+        }
+    }
+
+    /// Measures noise differentially between the output node and `reference`.
+    pub fn reference(mut self, reference: impl Into<NodeRef>) -> Self {
+        self.reference = Some(reference.into());
+        self
+    }
+
+    /// Refers noise to the given independent source.
+    pub fn input_source(mut self, input_source: impl Into<InstanceRef>) -> Self {
+        self.input_source = Some(input_source.into());
+        self
+    }
+
+    /// Resolves the Substrate paths referenced by this analysis to raw (netlisted) names.
+    pub(crate) fn resolve(
+        &mut self,
+        lib: &RawLib<Spectre>,
+        conv: &NetlistLibConversion,
+    ) -> Result<()> {
+        resolve_node(lib, conv, &mut self.output)?;
+        if let Some(reference) = &mut self.reference {
+            resolve_node(lib, conv, reference)?;
+        }
+        if let Some(input_source) = &mut self.input_source {
+            resolve_instance(lib, conv, input_source)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the `noise` statement.
+    ///
+    /// Returns an error if this analysis has not been [resolved](Noise::resolve).
+    pub(crate) fn netlist<W: Write>(&self, out: &mut W) -> Result<()> {
+        let reference = match &self.reference {
+            Some(reference) => raw_node(reference)?.as_str(),
+            None => "0",
+        };
+        write!(
+            out,
+            "({} {reference}) noise start={} stop={}",
+            raw_node(&self.output)?,
+            self.start.value(),
+            self.stop.value()
+        )?;
+        self.sweep.netlist(out)?;
+        if let Some(input_source) = &self.input_source {
+            write!(out, " iprobe={}", raw_instance(input_source)?)?;
+        }
+        Ok(())
+    }
+}
+
+/// The result of a [`Noise`] analysis.
+#[derive(Debug, Clone)]
+pub struct Output {
+    /// The frequency points of the noise analysis.
+    pub freq: Arc<Vec<f64>>,
+    /// A map from signal name to noise spectral density, in units per square root hertz.
+    pub raw_values: HashMap<ArcStr, Arc<Vec<f64>>>,
+}
+
+impl Output {
+    /// Returns the noise spectral density of the signal with the given raw name.
+    pub fn raw(&self, name: &str) -> std::result::Result<&Arc<Vec<f64>>, SignalLookupError> {
+        self.raw_values
+            .get(name)
+            .ok_or_else(|| SignalLookupError::not_found(name, self.raw_values.keys()))
+    }
+
+    /// The output noise spectral density, in units per square root hertz.
+    pub fn output_noise(&self) -> std::result::Result<&Arc<Vec<f64>>, SignalLookupError> {
+        self.raw(OUTPUT_NOISE)
+    }
+
+    /// The input-referred noise spectral density, in units per square root hertz.
+    ///
+    /// Only computed if the analysis has an [input source](Noise::input_source).
+    pub fn input_noise(&self) -> std::result::Result<&Arc<Vec<f64>>, SignalLookupError> {
+        self.raw(INPUT_NOISE)
+    }
+}
+
+impl Analysis for Noise {
+    type Output = Output;
+}
+
+impl SupportedBy<Spectre> for Noise {
+    fn into_input(self, inputs: &mut Vec<<Spectre as Simulator>::Input>) {
+        inputs.push(self.into());
+    }
+    fn from_output(
+        outputs: &mut impl Iterator<Item = <Spectre as Simulator>::Output>,
+    ) -> <Self as Analysis>::Output {
+        let item = outputs.next().unwrap();
+        item.try_into().unwrap()
+    }
+}
+
+impl Save<Spectre, Noise> for SaveOutput {
+    type SaveKey = ();
+    type Saved = Output;
+
+    fn save(
+        &self,
+        _ctx: &SimulationContext<Spectre>,
+        _opts: &mut <Spectre as Simulator>::Options,
+    ) -> <Self as Save<Spectre, Noise>>::SaveKey {
+    }
+
+    fn from_saved(
+        output: &<Noise as Analysis>::Output,
+        _key: &<Self as Save<Spectre, Noise>>::SaveKey,
+    ) -> <Self as Save<Spectre, Noise>>::Saved {
+        output.clone()
+    }
+}
+
+impl From<common::Noise> for Noise {
+    fn from(value: common::Noise) -> Self {
+        Self::new(value.output, value.start, value.stop, value.sweep.into())
+            .input_source(value.input_source)
+    }
+}
+
+impl SupportedBy<Spectre> for common::Noise {
+    fn into_input(self, inputs: &mut Vec<<Spectre as Simulator>::Input>) {
+        Noise::from(self).into_input(inputs);
+    }
+    fn from_output(
+        outputs: &mut impl Iterator<Item = <Spectre as Simulator>::Output>,
+    ) -> <Self as Analysis>::Output {
+        let output = Noise::from_output(outputs);
+        common::NoiseOutput {
+            freq: output.freq.clone(),
+            output_noise: output.output_noise().unwrap().clone(),
+            input_noise: output.input_noise().unwrap().clone(),
+        }
+    }
+}
+
+impl Save<Spectre, common::Noise> for SaveOutput {
+    type SaveKey = ();
+    type Saved = common::NoiseOutput;
+
+    fn save(
+        &self,
+        _ctx: &SimulationContext<Spectre>,
+        _opts: &mut <Spectre as Simulator>::Options,
+    ) -> <Self as Save<Spectre, common::Noise>>::SaveKey {
+    }
+
+    fn from_saved(
+        output: &<common::Noise as Analysis>::Output,
+        _key: &<Self as Save<Spectre, common::Noise>>::SaveKey,
+    ) -> <Self as Save<Spectre, common::Noise>>::Saved {
+        output.clone()
+    }
+}
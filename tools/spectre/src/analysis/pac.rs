@@ -0,0 +1,157 @@
+//! Spectre periodic AC analysis options and data structures.
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::Arc;
+
+use arcstr::ArcStr;
+use num::complex::Complex64;
+use psfparser::analysis::ac::AcData;
+use serde::{Deserialize, Serialize};
+use substrate::simulation::data::SignalLookupError;
+use substrate::simulation::{Analysis, Simulator, SupportedBy};
+use substrate::units::Frequency;
+
+use super::Sweep;
+use crate::error::{Error, Result};
+use crate::Spectre;
+
+/// A periodic AC analysis.
+///
+/// Computes the small-signal response of a circuit linearized around the periodic
+/// steady state computed by the most recent preceding [`Pss`](super::pss::Pss) analysis.
+/// The response at each sideband `k` is at the swept input frequency shifted by `k`
+/// times the fundamental frequency.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Pac {
+    /// Start frequency.
+    pub start: Frequency,
+    /// Stop frequency.
+    pub stop: Frequency,
+    /// The sweep kind and number of points.
+    pub sweep: Sweep,
+    /// The sidebands at which to save the response.
+    ///
+    /// Must not be empty.
+    pub sidebands: Vec<i64>,
+}
+
+impl Pac {
+    /// Creates a new periodic AC analysis saving the response at the given sidebands.
+    pub fn new(
+        start: Frequency,
+        stop: Frequency,
+        sweep: Sweep,
+        sidebands: impl IntoIterator<Item = i64>,
+    ) -> Self {
+        Self {
+            start,
+            stop,
+            sweep,
+            sidebands: sidebands.into_iter().collect(),
+        }
+    }
+
+    /// Writes the `pac` statement.
+    pub(crate) fn netlist<W: Write>(&self, out: &mut W) -> Result<()> {
+        write!(
+            out,
+            "pac start={} stop={}",
+            self.start.value(),
+            self.stop.value()
+        )?;
+        self.sweep.netlist(out)?;
+        let sidebands = self
+            .sidebands
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        write!(out, " sidebands=[{}]", sidebands.join(" "))?;
+        Ok(())
+    }
+}
+
+/// The swept input frequencies and the response at each sideband of a [`Pac`] analysis.
+pub(crate) type SidebandData = (Vec<f64>, Vec<(i64, HashMap<String, Vec<Complex64>>)>);
+
+/// Splits the raw output of a [`Pac`] analysis into the response at each of `sidebands`.
+///
+/// Spectre writes the response at every sideband to a single file, sweeping the input
+/// frequency once per sideband in the order in which the sidebands were requested.
+/// Returns an error if the output does not contain one identical sweep per sideband.
+pub(crate) fn split_sidebands(sidebands: &[i64], data: AcData) -> Result<SidebandData> {
+    if sidebands.is_empty() || data.freq.len() % sidebands.len() != 0 {
+        return Err(Error::Parse);
+    }
+    let n = data.freq.len() / sidebands.len();
+    let freq = data.freq[..n].to_vec();
+    if data.freq.chunks(n).any(|sweep| sweep != freq) {
+        return Err(Error::Parse);
+    }
+    let mut values = sidebands
+        .iter()
+        .map(|&sideband| (sideband, HashMap::new()))
+        .collect::<Vec<_>>();
+    for (name, signal) in data.signals {
+        if signal.len() != data.freq.len() {
+            return Err(Error::Parse);
+        }
+        for ((_, values), signal) in values.iter_mut().zip(signal.chunks(n)) {
+            values.insert(name.clone(), signal.to_vec());
+        }
+    }
+    Ok((freq, values))
+}
+
+/// The result of a [`Pac`] analysis.
+#[derive(Debug, Clone)]
+pub struct Output {
+    /// The swept input frequency points.
+    pub freq: Arc<Vec<f64>>,
+    /// A map from sideband to a map from signal name to values.
+    pub raw_values: HashMap<i64, HashMap<ArcStr, Arc<Vec<Complex64>>>>,
+}
+
+impl Output {
+    /// Returns the signals saved at the given sideband, keyed by raw (netlisted) name.
+    pub fn sideband(
+        &self,
+        sideband: i64,
+    ) -> std::result::Result<&HashMap<ArcStr, Arc<Vec<Complex64>>>, SignalLookupError> {
+        self.raw_values
+            .get(&sideband)
+            .ok_or_else(|| SignalLookupError::NotFound {
+                name: arcstr::format!("sideband {sideband}"),
+                near_misses: Vec::new(),
+            })
+    }
+
+    /// Returns the response of the signal with the given raw (netlisted) name at the
+    /// given sideband.
+    pub fn raw_samples(
+        &self,
+        sideband: i64,
+        name: &str,
+    ) -> std::result::Result<&Arc<Vec<Complex64>>, SignalLookupError> {
+        let values = self.sideband(sideband)?;
+        values
+            .get(name)
+            .ok_or_else(|| SignalLookupError::not_found(name, values.keys()))
+    }
+}
+
+impl Analysis for Pac {
+    type Output = Output;
+}
+
+impl SupportedBy<Spectre> for Pac {
+    fn into_input(self, inputs: &mut Vec<<Spectre as Simulator>::Input>) {
+        inputs.push(self.into());
+    }
+    fn from_output(
+        outputs: &mut impl Iterator<Item = <Spectre as Simulator>::Output>,
+    ) -> <Self as Analysis>::Output {
+        let item = outputs.next().unwrap();
+        item.try_into().unwrap()
+    }
+}
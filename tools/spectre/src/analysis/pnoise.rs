@@ -0,0 +1,156 @@
+//! Spectre periodic noise analysis options and data structures.
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::Arc;
+
+use arcstr::ArcStr;
+use scir::NetlistLibConversion;
+use serde::{Deserialize, Serialize};
+use substrate::schematic::conv::RawLib;
+use substrate::simulation::analysis::NodeRef;
+use substrate::simulation::data::SignalLookupError;
+use substrate::simulation::{Analysis, Simulator, SupportedBy};
+use substrate::units::Frequency;
+
+use super::Sweep;
+use crate::error::Result;
+use crate::{raw_node, resolve_node, Spectre};
+
+/// The name of the output noise signal written by Spectre.
+pub(crate) const OUTPUT_NOISE: &str = "out";
+
+/// A periodic noise analysis.
+///
+/// Computes the noise at an output of a circuit linearized around the periodic
+/// steady state computed by the most recent preceding [`Pss`](super::pss::Pss) analysis,
+/// including noise folded from sidebands of the fundamental frequency.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Pnoise {
+    /// The positive output node.
+    pub output: NodeRef,
+    /// The negative output node.
+    ///
+    /// Defaults to ground.
+    pub reference: Option<NodeRef>,
+    /// Start frequency.
+    pub start: Frequency,
+    /// Stop frequency.
+    pub stop: Frequency,
+    /// The sweep kind and number of points.
+    pub sweep: Sweep,
+    /// The maximum sideband included when computing noise.
+    pub maxsideband: usize,
+}
+
+impl Pnoise {
+    /// Creates a new periodic noise analysis at the given output node.
+    pub fn new(
+        output: impl Into<NodeRef>,
+        start: Frequency,
+        stop: Frequency,
+        sweep: Sweep,
+        maxsideband: usize,
+    ) -> Self {
+        Self {
+            output: output.into(),
+            reference: None,
+            start,
+            stop,
+            sweep,
+            maxsideband,
+        }
+    }
+
+    /// Measures noise differentially between the output node and `reference`.
+    pub fn reference(mut self, reference: impl Into<NodeRef>) -> Self {
+        self.reference = Some(reference.into());
+        self
+    }
+
+    /// Resolves the Substrate paths referenced by this analysis to raw (netlisted) names.
+    pub(crate) fn resolve(
+        &mut self,
+        lib: &RawLib<Spectre>,
+        conv: &NetlistLibConversion,
+    ) -> Result<()> {
+        resolve_node(lib, conv, &mut self.output)?;
+        if let Some(reference) = &mut self.reference {
+            resolve_node(lib, conv, reference)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the `pnoise` statement.
+    ///
+    /// Returns an error if this analysis has not been [resolved](Pnoise::resolve).
+    pub(crate) fn netlist<W: Write>(&self, out: &mut W) -> Result<()> {
+        let reference = match &self.reference {
+            Some(reference) => raw_node(reference)?.as_str(),
+            None => "0",
+        };
+        write!(
+            out,
+            "({} {reference}) pnoise start={} stop={}",
+            raw_node(&self.output)?,
+            self.start.value(),
+            self.stop.value()
+        )?;
+        self.sweep.netlist(out)?;
+        write!(out, " maxsideband={}", self.maxsideband)?;
+        Ok(())
+    }
+}
+
+/// The result of a [`Pnoise`] analysis.
+#[derive(Debug, Clone)]
+pub struct Output {
+    /// The frequency points of the noise analysis.
+    pub freq: Arc<Vec<f64>>,
+    /// A map from signal name to noise spectral density, in units per square root hertz.
+    pub raw_values: HashMap<ArcStr, Arc<Vec<f64>>>,
+}
+
+impl Output {
+    /// Returns the noise spectral density of the signal with the given raw name.
+    pub fn raw(&self, name: &str) -> std::result::Result<&Arc<Vec<f64>>, SignalLookupError> {
+        self.raw_values
+            .get(name)
+            .ok_or_else(|| SignalLookupError::not_found(name, self.raw_values.keys()))
+    }
+
+    /// The output noise spectral density, in units per square root hertz.
+    pub fn output_noise(&self) -> std::result::Result<&Arc<Vec<f64>>, SignalLookupError> {
+        self.raw(OUTPUT_NOISE)
+    }
+
+    /// The root-mean-square output noise integrated across the entire frequency sweep.
+    ///
+    /// The noise power spectral density is integrated using the trapezoidal rule.
+    pub fn integrated_output_noise(&self) -> std::result::Result<f64, SignalLookupError> {
+        let density = self.output_noise()?;
+        let power = self
+            .freq
+            .windows(2)
+            .zip(density.windows(2))
+            .map(|(f, v)| 0.5 * (v[0] * v[0] + v[1] * v[1]) * (f[1] - f[0]))
+            .sum::<f64>();
+        Ok(power.sqrt())
+    }
+}
+
+impl Analysis for Pnoise {
+    type Output = Output;
+}
+
+impl SupportedBy<Spectre> for Pnoise {
+    fn into_input(self, inputs: &mut Vec<<Spectre as Simulator>::Input>) {
+        inputs.push(self.into());
+    }
+    fn from_output(
+        outputs: &mut impl Iterator<Item = <Spectre as Simulator>::Output>,
+    ) -> <Self as Analysis>::Output {
+        let item = outputs.next().unwrap();
+        item.try_into().unwrap()
+    }
+}
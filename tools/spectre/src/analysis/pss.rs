@@ -0,0 +1,230 @@
+//! Spectre periodic steady-state analysis options and data structures.
+//!
+//! A [`Pss`] analysis computes the periodic steady-state response of a circuit driven by
+//! periodic sources, or of an autonomous circuit such as an oscillator. Periodic small-signal
+//! analyses ([`Pac`](super::pac::Pac) and [`Pnoise`](super::pnoise::Pnoise)) are linearized
+//! around the steady state computed by the most recent preceding [`Pss`] analysis, so they must
+//! be simulated after one:
+//!
+//! ```ignore
+//! let pss = Pss::driven("1GHz".parse()?, 10);
+//! let pnoise = Pnoise::new("out", "1kHz".parse()?, "100MHz".parse()?, Sweep::Decade(10), 10);
+//! let (pss, pnoise) = sim.simulate(opts, (pss, pnoise))?;
+//! let hd2 = pss.harmonic("out", 2)?.norm() / pss.harmonic("out", 1)?.norm();
+//! ```
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::Arc;
+
+use arcstr::ArcStr;
+use num::complex::Complex64;
+use scir::NetlistLibConversion;
+use serde::{Deserialize, Serialize};
+use substrate::schematic::conv::RawLib;
+use substrate::simulation::analysis::NodeRef;
+use substrate::simulation::data::SignalLookupError;
+use substrate::simulation::{Analysis, Simulator, SupportedBy};
+use substrate::units::{Frequency, Time};
+
+use crate::error::Result;
+use crate::{raw_node, resolve_node, ErrPreset, Spectre};
+
+/// The method used to compute the periodic steady state.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum PssMethod {
+    /// The time-domain shooting method.
+    #[default]
+    Shooting,
+    /// The frequency-domain harmonic balance method.
+    ///
+    /// Typically faster than shooting for weakly nonlinear circuits.
+    HarmonicBalance,
+}
+
+/// A periodic steady-state analysis.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Pss {
+    /// The fundamental frequency.
+    ///
+    /// For autonomous circuits, this is an estimate of the oscillation frequency.
+    pub fund: Frequency,
+    /// The number of harmonics of the fundamental to compute and save.
+    pub harms: usize,
+    /// The method used to compute the periodic steady state.
+    pub method: PssMethod,
+    /// The time to simulate before searching for the periodic steady state.
+    pub tstab: Option<Time>,
+    /// The error preset.
+    pub errpreset: Option<ErrPreset>,
+    /// The positive and negative nodes of an oscillator.
+    ///
+    /// If [`Some`], the circuit is treated as autonomous and the fundamental frequency is
+    /// determined by the simulator.
+    pub oscillator: Option<(NodeRef, NodeRef)>,
+}
+
+impl Pss {
+    /// Creates a periodic steady-state analysis of a circuit driven at frequency `fund`.
+    pub fn driven(fund: Frequency, harms: usize) -> Self {
+        Self {
+            fund,
+            harms,
+            method: PssMethod::default(),
+            tstab: None,
+            errpreset: None,
+            oscillator: None,
+        }
+    }
+
+    /// Creates a periodic steady-state analysis of an oscillator whose output is
+    /// taken between nodes `p` and `n`.
+    ///
+    /// `fund` is an estimate of the oscillation frequency.
+    pub fn oscillator(
+        p: impl Into<NodeRef>,
+        n: impl Into<NodeRef>,
+        fund: Frequency,
+        harms: usize,
+    ) -> Self {
+        Self {
+            oscillator: Some((p.into(), n.into())),
+            ..Self::driven(fund, harms)
+        }
+    }
+
+    /// Sets the method used to compute the periodic steady state.
+    pub fn method(mut self, method: PssMethod) -> Self {
+        self.method = method;
+        self
+    }
+
+    /// Sets the time to simulate before searching for the periodic steady state.
+    pub fn tstab(mut self, tstab: Time) -> Self {
+        self.tstab = Some(tstab);
+        self
+    }
+
+    /// Sets the error preset.
+    pub fn errpreset(mut self, errpreset: ErrPreset) -> Self {
+        self.errpreset = Some(errpreset);
+        self
+    }
+
+    /// Resolves the Substrate paths referenced by this analysis to raw (netlisted) names.
+    pub(crate) fn resolve(
+        &mut self,
+        lib: &RawLib<Spectre>,
+        conv: &NetlistLibConversion,
+    ) -> Result<()> {
+        if let Some((p, n)) = &mut self.oscillator {
+            resolve_node(lib, conv, p)?;
+            resolve_node(lib, conv, n)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the `pss` statement.
+    ///
+    /// Returns an error if this analysis has not been [resolved](Pss::resolve).
+    pub(crate) fn netlist<W: Write>(&self, out: &mut W) -> Result<()> {
+        if let Some((p, n)) = &self.oscillator {
+            write!(out, "({} {}) ", raw_node(p)?, raw_node(n)?)?;
+        }
+        write!(out, "pss fund={} harms={}", self.fund.value(), self.harms)?;
+        if let Some(tstab) = self.tstab {
+            write!(out, " tstab={}", tstab.value())?;
+        }
+        if let Some(errpreset) = self.errpreset {
+            write!(out, " errpreset={errpreset}")?;
+        }
+        if self.method == PssMethod::HarmonicBalance {
+            write!(out, " flexbalance=yes")?;
+        }
+        Ok(())
+    }
+}
+
+/// The result of a [`Pss`] analysis.
+#[derive(Debug, Clone)]
+pub struct Output {
+    /// The frequency of each saved harmonic, starting with DC.
+    pub harmonics: Arc<Vec<f64>>,
+    /// A map from signal name to the complex amplitude of each saved harmonic.
+    pub raw_harmonics: HashMap<ArcStr, Arc<Vec<Complex64>>>,
+    /// The time points of a single period of the steady-state waveforms.
+    pub time: Arc<Vec<f64>>,
+    /// A map from signal name to the values of a single period of its steady-state waveform.
+    pub raw_values: HashMap<ArcStr, Arc<Vec<f64>>>,
+}
+
+impl Output {
+    /// The fundamental frequency of the periodic steady state.
+    ///
+    /// For autonomous circuits, this is the oscillation frequency found by the simulator.
+    /// Returns [`None`] if no harmonics above DC were saved.
+    pub fn fundamental(&self) -> Option<f64> {
+        self.harmonics.get(1).copied()
+    }
+
+    /// The period of the periodic steady state.
+    pub fn period(&self) -> Option<f64> {
+        self.fundamental().map(|f| 1. / f)
+    }
+
+    /// Returns the complex amplitudes of each harmonic of the signal with the given
+    /// raw (netlisted) name.
+    pub fn raw_harmonics(
+        &self,
+        name: &str,
+    ) -> std::result::Result<&Arc<Vec<Complex64>>, SignalLookupError> {
+        self.raw_harmonics
+            .get(name)
+            .ok_or_else(|| SignalLookupError::not_found(name, self.raw_harmonics.keys()))
+    }
+
+    /// Returns the complex amplitude of harmonic `k` of the signal with the given
+    /// raw (netlisted) name.
+    ///
+    /// Harmonic 0 is the DC component.
+    pub fn harmonic(
+        &self,
+        name: &str,
+        k: usize,
+    ) -> std::result::Result<Complex64, SignalLookupError> {
+        self.raw_harmonics(name)?
+            .get(k)
+            .copied()
+            .ok_or_else(|| SignalLookupError::NotFound {
+                name: arcstr::format!("harmonic {k} of {name}"),
+                near_misses: Vec::new(),
+            })
+    }
+
+    /// Returns a single period of the steady-state waveform of the signal with the
+    /// given raw (netlisted) name.
+    pub fn raw_waveform(
+        &self,
+        name: &str,
+    ) -> std::result::Result<&Arc<Vec<f64>>, SignalLookupError> {
+        self.raw_values
+            .get(name)
+            .ok_or_else(|| SignalLookupError::not_found(name, self.raw_values.keys()))
+    }
+}
+
+impl Analysis for Pss {
+    type Output = Output;
+}
+
+impl SupportedBy<Spectre> for Pss {
+    fn into_input(self, inputs: &mut Vec<<Spectre as Simulator>::Input>) {
+        inputs.push(self.into());
+    }
+    fn from_output(
+        outputs: &mut impl Iterator<Item = <Spectre as Simulator>::Output>,
+    ) -> <Self as Analysis>::Output {
+        let item = outputs.next().unwrap();
+        item.try_into().unwrap()
+    }
+}
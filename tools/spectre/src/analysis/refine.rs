@@ -0,0 +1,373 @@
+//! Transient accuracy auto-refinement.
+//!
+//! A transient simulation is first run with the given settings. Its output is then checked for
+//! suspicious discontinuities: non-monotonic time points, and signals slewing faster than a
+//! configured limit. If any are found, the transient is rerun with tightened tolerances and the
+//! refined samples around each suspicious region are merged into the returned output.
+
+use crate::analysis::tran::{self, Tran};
+use crate::{ErrPreset, Options, Spectre};
+use arcstr::ArcStr;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use substrate::simulation::{SimController, Testbench};
+use substrate::units::Time;
+
+/// Settings for transient accuracy auto-refinement.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Refinement {
+    /// The maximum plausible slew rate of any checked signal, in units per second.
+    ///
+    /// Segments of a waveform that slew faster than this are considered suspicious.
+    pub slew_limit: f64,
+    /// The raw (netlisted) names of the signals to check.
+    ///
+    /// If empty, all saved signals are checked.
+    pub signals: Vec<ArcStr>,
+    /// The error preset used for the refined simulation.
+    pub errpreset: ErrPreset,
+    /// The maximum time step used for the refined simulation.
+    ///
+    /// If [`None`], the maximum time step of the original analysis is kept.
+    pub max_step: Option<Time>,
+    /// The time, in seconds, by which each suspicious region is extended on both sides
+    /// before merging refined samples.
+    pub margin: f64,
+    /// The minimum absolute change in any checked signal for a refined region
+    /// to be flagged as changed.
+    pub tolerance: f64,
+}
+
+impl Refinement {
+    /// Creates a refinement that checks all saved signals against the given slew limit.
+    ///
+    /// Refined simulations use the conservative error preset.
+    pub fn new(slew_limit: f64) -> Self {
+        Self {
+            slew_limit,
+            signals: Vec::new(),
+            errpreset: ErrPreset::Conservative,
+            max_step: None,
+            margin: 0.,
+            tolerance: 1e-3,
+        }
+    }
+
+    /// Restricts checking to the signal with the given raw name.
+    ///
+    /// May be called multiple times to check several signals.
+    pub fn signal(mut self, signal: impl Into<ArcStr>) -> Self {
+        self.signals.push(signal.into());
+        self
+    }
+
+    /// Sets the maximum time step of the refined simulation.
+    pub fn max_step(mut self, max_step: Time) -> Self {
+        self.max_step = Some(max_step);
+        self
+    }
+
+    /// Sets the error preset of the refined simulation.
+    pub fn errpreset(mut self, errpreset: ErrPreset) -> Self {
+        self.errpreset = errpreset;
+        self
+    }
+
+    /// Sets the padding added around each suspicious region, in seconds.
+    pub fn margin(mut self, margin: f64) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Sets the change above which a refined region is flagged as changed.
+    pub fn tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Returns the transient analysis used for the refined simulation of `tran`.
+    pub fn refined_tran(&self, tran: &Tran) -> Tran {
+        Tran {
+            errpreset: Some(self.errpreset),
+            max_step: self.max_step.or(tran.max_step),
+            ..tran.clone()
+        }
+    }
+
+    fn checks(&self, signal: &str) -> bool {
+        self.signals.is_empty() || self.signals.iter().any(|s| s == signal)
+    }
+}
+
+/// The reason a region of a transient output is considered suspicious.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Suspicion {
+    /// Time does not strictly increase.
+    NonMonotonicTime,
+    /// A signal slews faster than the configured limit.
+    Slew {
+        /// The raw name of the signal.
+        signal: ArcStr,
+        /// The observed slew rate, in units per second.
+        slew: f64,
+    },
+}
+
+/// A region of a transient output flagged during post-processing.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SuspiciousRegion {
+    /// The start time of the region.
+    pub start: f64,
+    /// The end time of the region.
+    pub end: f64,
+    /// Why the region was flagged.
+    pub reason: Suspicion,
+}
+
+/// A window of a transient output replaced by refined samples.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RefinedRegion {
+    /// The start time of the window.
+    pub start: f64,
+    /// The end time of the window.
+    pub end: f64,
+    /// The largest absolute difference between the original and refined values
+    /// of any checked signal within the window.
+    pub max_change: f64,
+    /// Whether `max_change` exceeds the refinement tolerance.
+    pub changed: bool,
+}
+
+/// The output of an auto-refined transient simulation.
+#[derive(Debug, Clone)]
+pub struct RefinedOutput {
+    /// The transient output, with refined samples merged in.
+    pub output: tran::Output,
+    /// The suspicious regions detected in the original output.
+    pub suspicious: Vec<SuspiciousRegion>,
+    /// The windows replaced by refined samples.
+    ///
+    /// Empty if no suspicious regions were detected.
+    pub regions: Vec<RefinedRegion>,
+}
+
+impl RefinedOutput {
+    /// Returns `true` if any refined region changed by more than the refinement tolerance.
+    pub fn changed(&self) -> bool {
+        self.regions.iter().any(|region| region.changed)
+    }
+}
+
+/// Detects suspicious discontinuities in a transient output.
+///
+/// Regions are returned in order of increasing start time.
+pub fn detect(output: &tran::Output, refinement: &Refinement) -> Vec<SuspiciousRegion> {
+    let t = &output.time;
+    let mut regions = Vec::new();
+    for (i, w) in t.windows(2).enumerate() {
+        if w[1] <= w[0] {
+            regions.push(SuspiciousRegion {
+                start: w[1].min(w[0]),
+                end: w[0].max(w[1]),
+                reason: Suspicion::NonMonotonicTime,
+            });
+            continue;
+        }
+        let dt = w[1] - w[0];
+        for (name, x) in output.raw_values.iter() {
+            if !refinement.checks(name) {
+                continue;
+            }
+            let slew = (x[i + 1] - x[i]).abs() / dt;
+            if slew > refinement.slew_limit {
+                regions.push(SuspiciousRegion {
+                    start: w[0],
+                    end: w[1],
+                    reason: Suspicion::Slew {
+                        signal: name.clone(),
+                        slew,
+                    },
+                });
+            }
+        }
+    }
+    regions.sort_by(|a, b| a.start.total_cmp(&b.start));
+    regions
+}
+
+/// Pads the suspicious regions by `margin` and merges overlapping regions into windows.
+pub(crate) fn windows(suspicious: &[SuspiciousRegion], margin: f64) -> Vec<(f64, f64)> {
+    let mut windows: Vec<(f64, f64)> = Vec::new();
+    for region in suspicious {
+        let (start, end) = (region.start - margin, region.end + margin);
+        match windows.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => windows.push((start, end)),
+        }
+    }
+    windows
+}
+
+/// Linearly interpolates `x` at time `t`, clamping outside the sampled range.
+fn interpolate(time: &[f64], x: &[f64], t: f64) -> f64 {
+    let idx = time.partition_point(|&ti| ti <= t);
+    if idx == 0 {
+        return x[0];
+    }
+    if idx == time.len() {
+        return x[idx - 1];
+    }
+    let (t0, t1) = (time[idx - 1], time[idx]);
+    if t1 <= t0 {
+        return x[idx - 1];
+    }
+    x[idx - 1] + (x[idx] - x[idx - 1]) * (t - t0) / (t1 - t0)
+}
+
+/// Merges the samples of `refined` that fall within `windows` into `original`.
+///
+/// Samples of `original` within a window are discarded. Signals missing from `refined`
+/// are interpolated from `original` at the refined time points.
+pub fn merge(
+    original: &tran::Output,
+    refined: &tran::Output,
+    windows: &[(f64, f64)],
+    refinement: &Refinement,
+) -> (tran::Output, Vec<RefinedRegion>) {
+    let in_window = |t: f64| {
+        windows
+            .iter()
+            .position(|&(start, end)| t >= start && t <= end)
+    };
+
+    // Each merged sample is identified by its source and index.
+    let mut samples = Vec::new();
+    let mut original_idx = 0;
+    let mut refined_idx = 0;
+    while original_idx < original.time.len() || refined_idx < refined.time.len() {
+        let next_original = original.time.get(original_idx).copied();
+        let next_refined = refined.time.get(refined_idx).copied();
+        match (next_original, next_refined) {
+            (Some(to), Some(tr)) if to <= tr => {
+                if in_window(to).is_none() {
+                    samples.push((false, original_idx));
+                }
+                original_idx += 1;
+            }
+            (Some(to), None) => {
+                if in_window(to).is_none() {
+                    samples.push((false, original_idx));
+                }
+                original_idx += 1;
+            }
+            (_, Some(tr)) => {
+                if in_window(tr).is_some() {
+                    samples.push((true, refined_idx));
+                }
+                refined_idx += 1;
+            }
+            (None, None) => unreachable!(),
+        }
+    }
+
+    let time = samples
+        .iter()
+        .map(|&(from_refined, i)| {
+            if from_refined {
+                refined.time[i]
+            } else {
+                original.time[i]
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut regions = windows
+        .iter()
+        .map(|&(start, end)| RefinedRegion {
+            start,
+            end,
+            max_change: 0.,
+            changed: false,
+        })
+        .collect::<Vec<_>>();
+
+    let mut raw_values = HashMap::with_capacity(original.raw_values.len());
+    for (name, x) in original.raw_values.iter() {
+        let refined_x = refined.raw_values.get(name);
+        let values = samples
+            .iter()
+            .map(|&(from_refined, i)| {
+                if !from_refined {
+                    return x[i];
+                }
+                let t = refined.time[i];
+                let before = interpolate(&original.time, x, t);
+                let Some(refined_x) = refined_x else {
+                    return before;
+                };
+                if refinement.checks(name) {
+                    let region = &mut regions[in_window(t).unwrap()];
+                    region.max_change = region.max_change.max((refined_x[i] - before).abs());
+                }
+                refined_x[i]
+            })
+            .collect::<Vec<_>>();
+        raw_values.insert(name.clone(), Arc::new(values));
+    }
+
+    for region in regions.iter_mut() {
+        region.changed = region.max_change > refinement.tolerance;
+    }
+
+    (
+        tran::Output {
+            time: Arc::new(time),
+            raw_values,
+            saved_values: original.saved_values.clone(),
+            resolver: original.resolver.clone(),
+        },
+        regions,
+    )
+}
+
+/// Runs a transient analysis, rerunning it with tightened tolerances if
+/// suspicious discontinuities are detected.
+///
+/// The rerun simulates the full transient with the settings given by
+/// [`Refinement::refined_tran`], but only samples within the (padded) suspicious
+/// regions are merged into the returned output.
+pub fn simulate_refined<T: Testbench<Spectre>>(
+    sim: &SimController<Spectre, T>,
+    options: Options,
+    tran: Tran,
+    refinement: &Refinement,
+) -> crate::error::Result<RefinedOutput> {
+    let refined_tran = refinement.refined_tran(&tran);
+    let output = sim.simulate_default(options.clone(), tran)?;
+    let suspicious = detect(&output, refinement);
+    if suspicious.is_empty() {
+        return Ok(RefinedOutput {
+            output,
+            suspicious,
+            regions: Vec::new(),
+        });
+    }
+
+    tracing::info!(
+        regions = suspicious.len(),
+        "rerunning transient with tightened tolerances"
+    );
+    let refined = sim.simulate_default(options, refined_tran)?;
+    let (output, regions) = merge(
+        &output,
+        &refined,
+        &windows(&suspicious, refinement.margin),
+        refinement,
+    );
+    Ok(RefinedOutput {
+        output,
+        suspicious,
+        regions,
+    })
+}
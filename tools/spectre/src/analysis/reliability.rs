@@ -0,0 +1,172 @@
+//! Spectre reliability (aging) analysis data structures.
+//!
+//! A reliability analysis runs its analyses twice: once on the fresh circuit, during which
+//! device stress is accumulated, and once on the circuit aged by the configured amount of
+//! time. The outputs of both phases are returned together so that degradation can be
+//! measured directly.
+
+use crate::{Input, Spectre};
+use arcstr::ArcStr;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use substrate::simulation::data::Save;
+use substrate::simulation::{Analysis, Simulator, SupportedBy};
+use substrate::types::schematic::{NestedNode, NestedTerminal, RawNestedNode};
+use type_dispatch::impl_dispatch;
+
+/// The device aging model used by a reliability analysis.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum AgeMethod {
+    /// AgeMOS model-parameter aging.
+    AgeMos,
+    /// TMI-based aging, for models that support it.
+    Tmi,
+}
+
+/// A control statement within a reliability block.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ReliabilityControl {
+    /// Selects the device aging model.
+    AgeMethod(AgeMethod),
+    /// Stops aging a device once its degradation reaches the given value.
+    DeltaD(Decimal),
+    /// A raw Spectre statement to place within the reliability block.
+    ///
+    /// Useful for installation-specific options such as aging model files
+    /// or mechanism-specific settings (e.g. HCI or NBTI parameters).
+    Raw(ArcStr),
+}
+
+/// A fresh/aged reliability analysis.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Reliability<A> {
+    /// The age at which the aged circuit is simulated, in years.
+    pub age: Decimal,
+    /// Additional reliability control statements.
+    pub controls: Vec<ReliabilityControl>,
+    /// The analysis to run on the fresh and aged circuits.
+    ///
+    /// The fresh run of this analysis also serves as the stress analysis.
+    pub analysis: A,
+}
+
+impl<A> Reliability<A> {
+    /// Creates a reliability analysis that ages the circuit by `age` years.
+    pub fn new(age: Decimal, analysis: A) -> Self {
+        Self {
+            age,
+            controls: Vec::new(),
+            analysis,
+        }
+    }
+
+    /// Adds a reliability control statement.
+    pub fn control(mut self, control: ReliabilityControl) -> Self {
+        self.controls.push(control);
+        self
+    }
+}
+
+/// The output of a [`Reliability`] analysis.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Output<T> {
+    /// The output of the fresh (stress) simulation.
+    pub fresh: T,
+    /// The output of the aged simulation.
+    pub aged: T,
+}
+
+/// The change in a measurement between the fresh and aged circuits.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Degradation {
+    /// The value of the measurement in the fresh circuit.
+    pub fresh: f64,
+    /// The value of the measurement in the aged circuit.
+    pub aged: f64,
+}
+
+impl Degradation {
+    /// The absolute change from the fresh value to the aged value.
+    pub fn delta(&self) -> f64 {
+        self.aged - self.fresh
+    }
+
+    /// The change from the fresh value to the aged value, relative to the fresh value.
+    pub fn relative(&self) -> f64 {
+        self.delta() / self.fresh
+    }
+}
+
+impl<T> Output<T> {
+    /// Applies `f` to both the fresh and aged outputs.
+    pub fn map<U>(self, mut f: impl FnMut(T) -> U) -> Output<U> {
+        Output {
+            fresh: f(self.fresh),
+            aged: f(self.aged),
+        }
+    }
+
+    /// Evaluates `measure` on the fresh and aged outputs.
+    pub fn degradation(&self, mut measure: impl FnMut(&T) -> f64) -> Degradation {
+        Degradation {
+            fresh: measure(&self.fresh),
+            aged: measure(&self.aged),
+        }
+    }
+}
+
+impl<A: SupportedBy<Spectre>> From<Reliability<A>> for Reliability<Vec<Input>> {
+    fn from(value: Reliability<A>) -> Self {
+        let mut analysis = Vec::new();
+        value.analysis.into_input(&mut analysis);
+        Reliability {
+            age: value.age,
+            controls: value.controls,
+            analysis,
+        }
+    }
+}
+
+#[impl_dispatch({NestedNode; RawNestedNode; NestedTerminal})]
+impl<T, A: Analysis> Save<Spectre, Reliability<A>> for T
+where
+    T: Save<Spectre, A>,
+{
+    type SaveKey = <T as Save<Spectre, A>>::SaveKey;
+    type Saved = Output<<T as Save<Spectre, A>>::Saved>;
+
+    fn save(
+        &self,
+        ctx: &substrate::simulation::SimulationContext<Spectre>,
+        opts: &mut <Spectre as Simulator>::Options,
+    ) -> <Self as Save<Spectre, Reliability<A>>>::SaveKey {
+        self.save(ctx, opts)
+    }
+
+    fn from_saved(
+        output: &<Reliability<A> as Analysis>::Output,
+        key: &<Self as Save<Spectre, Reliability<A>>>::SaveKey,
+    ) -> <Self as Save<Spectre, Reliability<A>>>::Saved {
+        Output {
+            fresh: T::from_saved(&output.fresh, key),
+            aged: T::from_saved(&output.aged, key),
+        }
+    }
+}
+
+impl<A: Analysis> Analysis for Reliability<A> {
+    type Output = Output<A::Output>;
+}
+
+impl<A: SupportedBy<Spectre>> SupportedBy<Spectre> for Reliability<A> {
+    fn into_input(self, inputs: &mut Vec<<Spectre as Simulator>::Input>) {
+        inputs.push(self.into());
+    }
+    fn from_output(
+        outputs: &mut impl Iterator<Item = <Spectre as Simulator>::Output>,
+    ) -> <Self as Analysis>::Output {
+        let item = outputs.next().unwrap();
+        let output: Output<Vec<crate::Output>> = item.try_into().unwrap();
+        output.map(|out| A::from_output(&mut out.into_iter()))
+    }
+}
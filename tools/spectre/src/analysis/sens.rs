@@ -0,0 +1,314 @@
+//! Spectre sensitivity analysis options and data structures.
+//!
+//! A [`Sens`] analysis computes the sensitivity of one or more output variables to device
+//! and model parameters at a DC operating point or across an AC sweep. Outputs and
+//! parameters may be given as Substrate paths, sensitivities can be looked up by Substrate
+//! instance path, and ranked to identify the parameters that dominate an output:
+//!
+//! ```ignore
+//! let data = sim.tb.data();
+//! let sens = sim.simulate_default(opts, Sens::dc([&data.out]))?;
+//! let dvout_dw = sens.instance(0, &data.m1.path(), "w")?.dc();
+//! for (param, value) in sens.dominant(0).iter().take(5) {
+//!     println!("{param}: {}", value.magnitude());
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::Arc;
+
+use arcstr::ArcStr;
+use scir::NetlistLibConversion;
+use serde::{Deserialize, Serialize};
+use substrate::schematic::conv::RawLib;
+use substrate::schematic::InstancePath;
+use substrate::simulation::analysis::{InstanceRef, NodeRef};
+use substrate::simulation::data::SignalLookupError;
+use substrate::simulation::{Analysis, Simulator, SupportedBy};
+use substrate::types::schematic::{NestedNode, NodePath};
+
+use crate::analysis::ac::Ac;
+use crate::analysis::dc::DcOp;
+use crate::analysis::tran::PathResolver;
+use crate::error::Result;
+use crate::{raw_instance, raw_node, resolve_instance, resolve_node, Spectre};
+
+pub use simulator_common::sens::SensValue;
+
+/// An output variable of a sensitivity analysis.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum SensOutput {
+    /// The voltage of a node.
+    Node(NodeRef),
+    /// An operating point parameter of an instance, such as the drain current of a transistor.
+    Param {
+        /// The instance.
+        instance: InstanceRef,
+        /// The name of the operating point parameter.
+        param: ArcStr,
+    },
+}
+
+impl SensOutput {
+    /// The voltage of `node`.
+    pub fn node(node: impl Into<NodeRef>) -> Self {
+        Self::Node(node.into())
+    }
+
+    /// The operating point parameter `param` of `instance`.
+    pub fn param(instance: impl Into<InstanceRef>, param: impl Into<ArcStr>) -> Self {
+        Self::Param {
+            instance: instance.into(),
+            param: param.into(),
+        }
+    }
+
+    fn resolve(&mut self, lib: &RawLib<Spectre>, conv: &NetlistLibConversion) -> Result<()> {
+        match self {
+            Self::Node(node) => resolve_node(lib, conv, node),
+            Self::Param { instance, .. } => resolve_instance(lib, conv, instance),
+        }
+    }
+
+    fn netlist<W: Write>(&self, out: &mut W) -> Result<()> {
+        match self {
+            Self::Node(node) => write!(out, "{}", raw_node(node)?)?,
+            Self::Param { instance, param } => write!(out, "{}:{param}", raw_instance(instance)?)?,
+        }
+        Ok(())
+    }
+}
+
+impl From<NodeRef> for SensOutput {
+    fn from(value: NodeRef) -> Self {
+        Self::Node(value)
+    }
+}
+
+impl From<NodePath> for SensOutput {
+    fn from(value: NodePath) -> Self {
+        Self::node(value)
+    }
+}
+
+impl From<&NestedNode> for SensOutput {
+    fn from(value: &NestedNode) -> Self {
+        Self::node(value)
+    }
+}
+
+/// A device or model parameter with respect to which sensitivities are computed.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct SensParam {
+    /// The instance or model.
+    ///
+    /// Models can only be referred to by their raw (netlisted) names.
+    pub instance: InstanceRef,
+    /// The name of the parameter.
+    pub param: ArcStr,
+}
+
+impl SensParam {
+    /// Creates a new [`SensParam`] referring to `param` of `instance`.
+    pub fn new(instance: impl Into<InstanceRef>, param: impl Into<ArcStr>) -> Self {
+        Self {
+            instance: instance.into(),
+            param: param.into(),
+        }
+    }
+}
+
+/// The analysis at which sensitivities are computed.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum SensAnalysis {
+    /// Sensitivities at the DC operating point.
+    Dc,
+    /// Sensitivities at each frequency of an AC sweep.
+    Ac(Ac),
+}
+
+/// A sensitivity analysis.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Sens {
+    /// The output variables.
+    pub outputs: Vec<SensOutput>,
+    /// The parameters with respect to which sensitivities are computed.
+    ///
+    /// If empty, Spectre computes sensitivities to all device and model parameters.
+    pub params: Vec<SensParam>,
+    /// The analysis at which sensitivities are computed.
+    pub analysis: SensAnalysis,
+}
+
+impl Sens {
+    /// Computes the DC sensitivities of `outputs` to all device and model parameters.
+    pub fn dc(outputs: impl IntoIterator<Item = impl Into<SensOutput>>) -> Self {
+        Self {
+            outputs: outputs.into_iter().map(Into::into).collect(),
+            params: Vec::new(),
+            analysis: SensAnalysis::Dc,
+        }
+    }
+
+    /// Computes the AC sensitivities of `outputs` to all device and model parameters
+    /// across the given AC sweep.
+    pub fn ac(outputs: impl IntoIterator<Item = impl Into<SensOutput>>, ac: Ac) -> Self {
+        Self {
+            outputs: outputs.into_iter().map(Into::into).collect(),
+            params: Vec::new(),
+            analysis: SensAnalysis::Ac(ac),
+        }
+    }
+
+    /// Restricts the computed sensitivities to the given parameters.
+    pub fn params(mut self, params: impl IntoIterator<Item = SensParam>) -> Self {
+        self.params.extend(params);
+        self
+    }
+
+    /// Resolves the Substrate paths referenced by this analysis to raw (netlisted) names.
+    pub(crate) fn resolve(
+        &mut self,
+        lib: &RawLib<Spectre>,
+        conv: &NetlistLibConversion,
+    ) -> Result<()> {
+        for output in self.outputs.iter_mut() {
+            output.resolve(lib, conv)?;
+        }
+        for param in self.params.iter_mut() {
+            resolve_instance(lib, conv, &mut param.instance)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the underlying analysis and one `sens` statement per output.
+    ///
+    /// The sensitivities of output `i` are written to the raw output file of the
+    /// statement named [`output_name(name, i)`](output_name).
+    ///
+    /// Returns an error if this analysis has not been [resolved](Sens::resolve).
+    pub(crate) fn netlist<W: Write>(&self, out: &mut W, name: &str) -> Result<()> {
+        let analysis = analysis_name(name);
+        write!(out, "{analysis} ")?;
+        match &self.analysis {
+            SensAnalysis::Dc => DcOp.netlist(out)?,
+            SensAnalysis::Ac(ac) => ac.netlist(out)?,
+        }
+        for (i, output) in self.outputs.iter().enumerate() {
+            write!(out, "\n{} sens (", output_name(name, i))?;
+            output.netlist(out)?;
+            write!(out, ")")?;
+            if !self.params.is_empty() {
+                write!(out, " to (")?;
+                for (j, param) in self.params.iter().enumerate() {
+                    if j > 0 {
+                        write!(out, " ")?;
+                    }
+                    write!(out, "{}:{}", raw_instance(&param.instance)?, param.param)?;
+                }
+                write!(out, ")")?;
+            }
+            write!(out, " for ({analysis})")?;
+        }
+        Ok(())
+    }
+}
+
+/// The name of the analysis underlying the sensitivity analysis named `name`.
+pub(crate) fn analysis_name(name: &str) -> String {
+    format!("{name}_an")
+}
+
+/// The name of the `sens` statement computing the sensitivities of output `idx`.
+pub(crate) fn output_name(name: &str, idx: usize) -> String {
+    format!("{name}_sens_{idx}")
+}
+
+/// The result of a [`Sens`] analysis.
+#[derive(Debug, Clone)]
+pub struct Output {
+    /// The frequency points of the AC sweep, if sensitivities were computed across one.
+    pub freq: Option<Arc<Vec<f64>>>,
+    /// The sensitivities of each output, in the order in which the outputs were given
+    /// to the analysis, keyed by raw `inst:param` name.
+    pub raw_values: Vec<HashMap<ArcStr, SensValue>>,
+    /// The netlist metadata used to resolve hierarchical paths.
+    ///
+    /// [`None`] if the output was not produced by a simulation.
+    pub(crate) resolver: Option<PathResolver>,
+}
+
+impl Output {
+    /// Returns the sensitivities of output `idx` of the analysis, keyed by raw `inst:param` name.
+    pub fn output(
+        &self,
+        idx: usize,
+    ) -> std::result::Result<&HashMap<ArcStr, SensValue>, SignalLookupError> {
+        self.raw_values.get(idx).ok_or_else(|| {
+            SignalLookupError::Unresolved(format!(
+                "sensitivity output {idx} (the analysis has {} outputs)",
+                self.raw_values.len()
+            ))
+        })
+    }
+
+    /// Returns the sensitivity of output `idx` to the parameter with the given raw
+    /// `inst:param` name.
+    pub fn raw(
+        &self,
+        idx: usize,
+        name: &str,
+    ) -> std::result::Result<&SensValue, SignalLookupError> {
+        let values = self.output(idx)?;
+        values
+            .get(name)
+            .ok_or_else(|| SignalLookupError::not_found(name, values.keys()))
+    }
+
+    /// Returns the sensitivity of output `idx` to parameter `param` of the Substrate
+    /// instance at `path`.
+    ///
+    /// Paths are typically obtained using [`NestedInstance::path`](substrate::schematic::NestedInstance::path).
+    pub fn instance(
+        &self,
+        idx: usize,
+        path: &InstancePath,
+        param: &str,
+    ) -> std::result::Result<&SensValue, SignalLookupError> {
+        let unresolved = || SignalLookupError::Unresolved(format!("{path:?}"));
+        let resolver = self.resolver.as_ref().ok_or_else(unresolved)?;
+        let scir = resolver
+            .lib
+            .convert_instance_path(path)
+            .ok_or_else(unresolved)?;
+        let instance = Spectre::instance_path(&resolver.lib.scir, &resolver.conv, &scir);
+        self.raw(idx, &format!("{instance}:{param}"))
+    }
+
+    /// Returns the sensitivities of output `idx`, ordered from largest to smallest magnitude.
+    ///
+    /// Returns an empty list if the analysis has no output `idx`.
+    pub fn dominant(&self, idx: usize) -> Vec<(&ArcStr, &SensValue)> {
+        self.raw_values
+            .get(idx)
+            .map(simulator_common::sens::dominant)
+            .unwrap_or_default()
+    }
+}
+
+impl Analysis for Sens {
+    type Output = Output;
+}
+
+impl SupportedBy<Spectre> for Sens {
+    fn into_input(self, inputs: &mut Vec<<Spectre as Simulator>::Input>) {
+        inputs.push(self.into());
+    }
+    fn from_output(
+        outputs: &mut impl Iterator<Item = <Spectre as Simulator>::Output>,
+    ) -> <Self as Analysis>::Output {
+        let item = outputs.next().unwrap();
+        item.try_into().unwrap()
+    }
+}
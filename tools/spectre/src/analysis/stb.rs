@@ -0,0 +1,213 @@
+//! Spectre stability analysis options and data structures.
+//!
+//! An [`Stb`] analysis computes the loop gain of a feedback loop broken at a probe
+//! instance (typically an `iprobe`) across a frequency sweep. The phase and gain margins
+//! of the loop are computed from the loop gain:
+//!
+//! ```ignore
+//! // `probe` is the `iprobe` instance breaking the loop.
+//! let probe = sim.tb.data().probe.path();
+//! let (start, stop) = ("1Hz".parse()?, "1GHz".parse()?);
+//! let stb = sim.simulate_default(opts, Stb::new(start, stop, Sweep::Decade(20), probe))?;
+//! assert!(stb.phase_margin().unwrap() > 60.);
+//! assert!(stb.gain_margin().unwrap() > 10.);
+//! ```
+
+use std::io::Write;
+use std::sync::Arc;
+
+use num::complex::Complex64;
+use scir::NetlistLibConversion;
+use serde::{Deserialize, Serialize};
+use substrate::schematic::conv::RawLib;
+use substrate::simulation::analysis::InstanceRef;
+use substrate::simulation::{Analysis, Simulator, SupportedBy};
+use substrate::units::Frequency;
+
+use super::Sweep;
+use crate::error::Result;
+use crate::{raw_instance, resolve_instance, Spectre};
+
+/// The name of the loop gain signal written by Spectre.
+pub(crate) const LOOP_GAIN: &str = "loopGain";
+
+/// A stability analysis.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Stb {
+    /// Start frequency.
+    pub start: Frequency,
+    /// Stop frequency.
+    pub stop: Frequency,
+    /// The sweep kind and number of points.
+    pub sweep: Sweep,
+    /// The probe instance at which the loop is broken.
+    pub probe: InstanceRef,
+}
+
+impl Stb {
+    /// Creates a new stability analysis that breaks the loop at `probe`.
+    pub fn new(
+        start: Frequency,
+        stop: Frequency,
+        sweep: Sweep,
+        probe: impl Into<InstanceRef>,
+    ) -> Self {
+        Self {
+            start,
+            stop,
+            sweep,
+            probe: probe.into(),
+        }
+    }
+
+    /// Resolves the probe instance to its raw (netlisted) name.
+    pub(crate) fn resolve(
+        &mut self,
+        lib: &RawLib<Spectre>,
+        conv: &NetlistLibConversion,
+    ) -> Result<()> {
+        resolve_instance(lib, conv, &mut self.probe)
+    }
+
+    /// Writes the `stb` statement.
+    ///
+    /// Returns an error if the probe has not been [resolved](Stb::resolve) to a raw name.
+    pub(crate) fn netlist<W: Write>(&self, out: &mut W) -> Result<()> {
+        let probe = raw_instance(&self.probe)?;
+        write!(
+            out,
+            "stb start={} stop={}",
+            self.start.value(),
+            self.stop.value()
+        )?;
+        self.sweep.netlist(out)?;
+        write!(out, " probe={probe}")?;
+        Ok(())
+    }
+}
+
+/// The result of an [`Stb`] analysis.
+///
+/// The loop gain follows the Spectre sign convention, in which a negative feedback
+/// loop has a positive loop gain at low frequencies. Margins are computed from the
+/// loop gain by linear interpolation between frequency points, using a logarithmic
+/// frequency axis where possible.
+#[derive(Debug, Clone)]
+pub struct Output {
+    /// The frequency points of the stability analysis.
+    pub freq: Arc<Vec<f64>>,
+    /// The loop gain at each frequency point.
+    pub loop_gain: Arc<Vec<Complex64>>,
+}
+
+impl Output {
+    /// The magnitude of the loop gain at each frequency point, in decibels.
+    pub fn magnitude_db(&self) -> Vec<f64> {
+        self.loop_gain
+            .iter()
+            .map(|g| 20. * g.norm().log10())
+            .collect()
+    }
+
+    /// The phase of the loop gain at each frequency point, in degrees.
+    ///
+    /// The phase is unwrapped, so consecutive points never differ by more than 180 degrees.
+    pub fn phase_deg(&self) -> Vec<f64> {
+        let mut phase: Vec<f64> = Vec::with_capacity(self.loop_gain.len());
+        for g in self.loop_gain.iter() {
+            let mut p = g.arg().to_degrees();
+            if let Some(prev) = phase.last() {
+                p -= 360. * ((p - prev) / 360.).round();
+            }
+            phase.push(p);
+        }
+        phase
+    }
+
+    /// The frequency at which the magnitude of the loop gain first falls below 0 dB.
+    ///
+    /// Returns [`None`] if the loop gain never crosses 0 dB.
+    pub fn unity_gain_freq(&self) -> Option<f64> {
+        let mag = self.magnitude_db();
+        crossing(&self.freq, &mag, 0.).map(|(_, f)| f)
+    }
+
+    /// The phase margin of the loop, in degrees.
+    ///
+    /// Equal to 180 degrees plus the phase of the loop gain at the
+    /// [unity gain frequency](Output::unity_gain_freq).
+    pub fn phase_margin(&self) -> Option<f64> {
+        let mag = self.magnitude_db();
+        let (idx, f) = crossing(&self.freq, &mag, 0.)?;
+        Some(180. + interpolate(&self.freq, &self.phase_deg(), idx, f))
+    }
+
+    /// The frequency at which the phase of the loop gain first falls below -180 degrees.
+    ///
+    /// Returns [`None`] if the phase never crosses -180 degrees.
+    pub fn phase_crossover_freq(&self) -> Option<f64> {
+        crossing(&self.freq, &self.phase_deg(), -180.).map(|(_, f)| f)
+    }
+
+    /// The gain margin of the loop, in decibels.
+    ///
+    /// Equal to the negated magnitude of the loop gain at the
+    /// [phase crossover frequency](Output::phase_crossover_freq).
+    pub fn gain_margin(&self) -> Option<f64> {
+        let (idx, f) = crossing(&self.freq, &self.phase_deg(), -180.)?;
+        Some(-interpolate(&self.freq, &self.magnitude_db(), idx, f))
+    }
+}
+
+/// Converts a frequency to the axis along which values are interpolated.
+fn axis(freq: &[f64], f: f64) -> f64 {
+    if freq.first().is_some_and(|&f0| f0 > 0.) {
+        f.log10()
+    } else {
+        f
+    }
+}
+
+/// Returns the first frequency at which `values` falls from above `level` to
+/// at or below `level`, along with the index of the point preceding the crossing.
+fn crossing(freq: &[f64], values: &[f64], level: f64) -> Option<(usize, f64)> {
+    let idx = values
+        .windows(2)
+        .position(|w| w[0] > level && w[1] <= level)?;
+    let (x0, x1) = (axis(freq, freq[idx]), axis(freq, freq[idx + 1]));
+    let (y0, y1) = (values[idx], values[idx + 1]);
+    let x = x0 + (level - y0) * (x1 - x0) / (y1 - y0);
+    Some((idx, unaxis(freq, x)))
+}
+
+/// Inverts [`axis`].
+fn unaxis(freq: &[f64], x: f64) -> f64 {
+    if freq.first().is_some_and(|&f0| f0 > 0.) {
+        10f64.powf(x)
+    } else {
+        x
+    }
+}
+
+/// Interpolates `values` at frequency `f`, which lies between points `idx` and `idx + 1`.
+fn interpolate(freq: &[f64], values: &[f64], idx: usize, f: f64) -> f64 {
+    let (x0, x1) = (axis(freq, freq[idx]), axis(freq, freq[idx + 1]));
+    let t = (axis(freq, f) - x0) / (x1 - x0);
+    values[idx] + t * (values[idx + 1] - values[idx])
+}
+
+impl Analysis for Stb {
+    type Output = Output;
+}
+
+impl SupportedBy<Spectre> for Stb {
+    fn into_input(self, inputs: &mut Vec<<Spectre as Simulator>::Input>) {
+        inputs.push(self.into());
+    }
+    fn from_output(
+        outputs: &mut impl Iterator<Item = <Spectre as Simulator>::Output>,
+    ) -> <Self as Analysis>::Output {
+        let item = outputs.next().unwrap();
+        item.try_into().unwrap()
+    }
+}
@@ -0,0 +1,128 @@
+//! Spectre parametric sweep data structures.
+//!
+//! A parametric sweep reruns a set of analyses once for each value of a netlist parameter
+//! within a single Spectre invocation. Sweeps may be nested to sweep several parameters.
+
+use crate::{Input, Spectre};
+use arcstr::ArcStr;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::ops::Deref;
+use substrate::simulation::data::Save;
+use substrate::simulation::{Analysis, Simulator, SupportedBy};
+use substrate::types::schematic::{NestedNode, NestedTerminal, RawNestedNode};
+use type_dispatch::impl_dispatch;
+
+/// A set of analyses to run once per value of a swept parameter.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ParamSweep<A> {
+    /// The name of the swept parameter.
+    ///
+    /// The parameter must already be declared in the netlist.
+    /// The special parameter `temp` sweeps the simulation temperature.
+    pub param: ArcStr,
+    /// The values of the swept parameter.
+    ///
+    /// The analyses are run once for each value.
+    pub values: Vec<Decimal>,
+    /// The analysis to run.
+    pub analysis: A,
+}
+
+impl<A> ParamSweep<A> {
+    /// Creates a sweep of the parameter `param` over the given values.
+    pub fn new(
+        param: impl Into<ArcStr>,
+        values: impl IntoIterator<Item = Decimal>,
+        analysis: A,
+    ) -> Self {
+        Self {
+            param: param.into(),
+            values: values.into_iter().collect(),
+            analysis,
+        }
+    }
+}
+
+/// The output of a [`ParamSweep`] analysis.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Output<T>(pub(crate) Vec<T>);
+
+impl<T> Deref for Output<T> {
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> Output<T> {
+    /// Returns the underlying vector of outputs for each swept value,
+    /// in the order the values were specified.
+    pub fn into_inner(self) -> Vec<T> {
+        self.0
+    }
+}
+
+impl<A: SupportedBy<Spectre>> From<ParamSweep<A>> for ParamSweep<Vec<Input>> {
+    fn from(value: ParamSweep<A>) -> Self {
+        let mut analysis = Vec::new();
+        value.analysis.into_input(&mut analysis);
+        ParamSweep {
+            param: value.param,
+            values: value.values,
+            analysis,
+        }
+    }
+}
+
+#[impl_dispatch({NestedNode; RawNestedNode; NestedTerminal})]
+impl<T, A: Analysis> Save<Spectre, ParamSweep<A>> for T
+where
+    T: Save<Spectre, A>,
+{
+    type SaveKey = <T as Save<Spectre, A>>::SaveKey;
+    type Saved = Vec<<T as Save<Spectre, A>>::Saved>;
+
+    fn save(
+        &self,
+        ctx: &substrate::simulation::SimulationContext<Spectre>,
+        opts: &mut <Spectre as Simulator>::Options,
+    ) -> <Self as Save<Spectre, ParamSweep<A>>>::SaveKey {
+        self.save(ctx, opts)
+    }
+
+    fn from_saved(
+        output: &<ParamSweep<A> as Analysis>::Output,
+        key: &<Self as Save<Spectre, ParamSweep<A>>>::SaveKey,
+    ) -> <Self as Save<Spectre, ParamSweep<A>>>::Saved {
+        output
+            .0
+            .iter()
+            .map(|output| T::from_saved(output, key))
+            .collect()
+    }
+}
+
+impl<A: Analysis> Analysis for ParamSweep<A> {
+    type Output = Output<A::Output>;
+}
+
+impl<A: SupportedBy<Spectre>> SupportedBy<Spectre> for ParamSweep<A> {
+    fn into_input(self, inputs: &mut Vec<<Spectre as Simulator>::Input>) {
+        inputs.push(self.into());
+    }
+    fn from_output(
+        outputs: &mut impl Iterator<Item = <Spectre as Simulator>::Output>,
+    ) -> <Self as Analysis>::Output {
+        let item = outputs.next().unwrap();
+        let output: Output<Vec<crate::Output>> = item.try_into().unwrap();
+        Output(
+            output
+                .0
+                .into_iter()
+                .map(|out| A::from_output(&mut out.into_iter()))
+                .collect(),
+        )
+    }
+}
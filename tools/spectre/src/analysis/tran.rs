@@ -3,29 +3,37 @@
 use crate::{ErrPreset, InstanceTail, SimSignal, Spectre};
 use arcstr::ArcStr;
 use rust_decimal::Decimal;
-use scir::{NamedSliceOne, SliceOnePath};
+use scir::{Library, NamedSliceOne, NetlistLibConversion, SliceOnePath};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use simulator_common::tran::VoltageNames;
 use std::sync::Arc;
 use substrate::schematic::conv::ConvertedNodePath;
+use substrate::simulation::analysis as common;
 use substrate::simulation::data::{Save, SaveOutput, SaveTime};
-use substrate::simulation::waveform::{TimePoint, TimeWaveform, WaveformRef};
+use substrate::simulation::options::TransientNoise;
 use substrate::simulation::{Analysis, SimulationContext, Simulator, SupportedBy};
 use substrate::types::schematic::{NestedNode, NestedTerminal, RawNestedNode};
+use substrate::units::Time;
+
+pub use simulator_common::tran::OutputWaveform;
+pub use substrate::simulation::data::{CurrentSaveKey, VoltageSaveKey};
 
 /// A transient analysis.
 #[derive(Clone, Default, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Tran {
-    /// Stop time (sec).
-    pub stop: Decimal,
-    /// Start time (sec).
+    /// Stop time.
+    pub stop: Time,
+    /// Start time.
     ///
     /// Defaults to 0.
-    pub start: Option<Decimal>,
+    pub start: Option<Time>,
 
     /// The error preset.
     pub errpreset: Option<ErrPreset>,
 
+    /// The maximum time step taken by the simulator.
+    pub max_step: Option<Time>,
+
     /// The maximum frequency for noise power spectral density.
     ///
     /// A nonzero value turns on noise sources during transient analysis.
@@ -35,43 +43,88 @@ pub struct Tran {
 
     /// The minimum frequency for noise power spectral density.
     pub noise_fmin: Option<Decimal>,
-}
 
-/// The result of a transient analysis.
-#[derive(Debug, Clone)]
-pub struct Output {
-    /// The time points of the transient simulation.
-    pub time: Arc<Vec<f64>>,
-    /// A map from signal name to values.
-    pub raw_values: HashMap<ArcStr, Arc<Vec<f64>>>,
-    /// A map from a save ID to a raw value identifier.
-    pub(crate) saved_values: HashMap<u64, ArcStr>,
-}
+    /// The seed of the random number generator used to generate noise.
+    pub noise_seed: Option<u64>,
 
-/// An output transient waveform.
-#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
-pub struct OutputWaveform {
-    /// Time samples.
-    pub t: Arc<Vec<f64>>,
-    /// Values corresponding to time samples in `t`.
-    pub x: Arc<Vec<f64>>,
+    /// A factor by which all noise sources are scaled.
+    pub noise_scale: Option<Decimal>,
+
+    /// The interval at which output time points are written.
+    ///
+    /// If set, the simulator writes output only at multiples of the strobe period rather
+    /// than at every time step. Reduces the size of the output, but may alias high-frequency
+    /// content. See [`Decimation`](substrate::simulation::options::Decimation) for anti-aliased
+    /// decimation after simulation.
+    pub strobe_period: Option<Time>,
+
+    /// The delay of the strobed output time points relative to the start time.
+    pub strobe_delay: Option<Time>,
+
+    /// The time before which no output is written.
+    pub output_start: Option<Time>,
 }
 
-impl OutputWaveform {
-    /// Converts an [`OutputWaveform`] to a [`WaveformRef`].
-    pub fn as_ref(&self) -> WaveformRef<'_, f64> {
-        WaveformRef::new(&self.t, &self.x)
+impl Tran {
+    /// Enables noise sources during this transient analysis.
+    pub fn noise(mut self, noise: TransientNoise) -> Self {
+        self.apply_noise(&noise);
+        self
     }
-}
 
-impl TimeWaveform for OutputWaveform {
-    type Data = f64;
-    fn get(&self, idx: usize) -> Option<TimePoint<f64>> {
-        self.as_ref().get(idx)
+    /// Writes output only at multiples of `period`.
+    pub fn strobe(mut self, period: Time) -> Self {
+        self.strobe_period = Some(period);
+        self
     }
 
-    fn len(&self) -> usize {
-        self.t.len()
+    /// Delays the strobed output time points by `delay` relative to the start time.
+    ///
+    /// Only has an effect if a [strobe period](Tran::strobe) is set.
+    pub fn strobe_delay(mut self, delay: Time) -> Self {
+        self.strobe_delay = Some(delay);
+        self
+    }
+
+    /// Writes no output before time `start`.
+    pub fn output_start(mut self, start: Time) -> Self {
+        self.output_start = Some(start);
+        self
+    }
+
+    /// Sets the transient noise parameters of this analysis from `noise`.
+    pub(crate) fn apply_noise(&mut self, noise: &TransientNoise) {
+        self.noise_fmax = Some(noise.fmax());
+        self.noise_fmin = noise.fmin;
+        self.noise_seed = noise.seed;
+        self.noise_scale = noise.scale;
+    }
+}
+
+/// The result of a transient analysis.
+pub type Output = simulator_common::tran::Output<Spectre>;
+
+/// Netlist metadata used to resolve hierarchical paths to simulator signal names.
+pub(crate) type PathResolver = simulator_common::tran::PathResolver<Spectre>;
+
+impl VoltageNames for Spectre {
+    fn voltage_names(
+        lib: &Library<Spectre>,
+        conv: &NetlistLibConversion,
+        path: &SliceOnePath,
+    ) -> Vec<ArcStr> {
+        let name = ArcStr::from(Spectre::node_voltage_path(lib, conv, path));
+        // The node may have been saved under the name of a node it was merged with.
+        let simplified = ArcStr::from(Spectre::node_voltage_path(
+            lib,
+            conv,
+            &lib.simplify_path(path.clone()),
+        ));
+        if simplified == name {
+            vec![name]
+        } else {
+            vec![name, simplified]
+        }
     }
 }
 
@@ -113,10 +166,6 @@ impl Save<Spectre, Tran> for SaveTime {
     }
 }
 
-/// An identifier for a saved transient voltage.
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Serialize, Deserialize)]
-pub struct VoltageSaveKey(pub(crate) u64);
-
 impl Save<Spectre, Tran> for NestedNode {
     type SaveKey = VoltageSaveKey;
     type Saved = OutputWaveform;
@@ -144,7 +193,7 @@ impl Save<Spectre, Tran> for NestedNode {
             t: output.time.clone(),
             x: output
                 .raw_values
-                .get(output.saved_values.get(&key.0).unwrap())
+                .get(output.saved_values.get(&key.id()).unwrap())
                 .unwrap()
                 .clone(),
         }
@@ -175,17 +224,13 @@ impl Save<Spectre, Tran> for RawNestedNode {
             t: output.time.clone(),
             x: output
                 .raw_values
-                .get(output.saved_values.get(&key.0).unwrap())
+                .get(output.saved_values.get(&key.id()).unwrap())
                 .unwrap()
                 .clone(),
         }
     }
 }
 
-/// An identifier for a saved transient current.
-#[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
-pub struct CurrentSaveKey(pub(crate) Vec<u64>);
-
 /// Data saved from a nested terminal in a transient simulation.
 pub struct NestedTerminalOutput {
     /// The voltage at the terminal.
@@ -205,7 +250,7 @@ impl Save<Spectre, Tran> for NestedTerminal {
     ) -> <Self as Save<Spectre, Tran>>::SaveKey {
         (
             <NestedNode as Save<Spectre, Tran>>::save(self, ctx, opts),
-            CurrentSaveKey(
+            CurrentSaveKey::from_ids(
                 ctx.lib
                     .convert_terminal_path(&self.path())
                     .unwrap()
@@ -220,9 +265,9 @@ impl Save<Spectre, Tran> for NestedTerminal {
                                 NamedSliceOne::new(port.clone()),
                             ),
                         }))
-                        .0
-                    })
-                    .collect(),
+                        .ids()
+                        .to_vec()
+                    }),
             ),
         )
     }
@@ -235,13 +280,13 @@ impl Save<Spectre, Tran> for NestedTerminal {
             t: output.time.clone(),
             x: output
                 .raw_values
-                .get(output.saved_values.get(&key.0 .0).unwrap())
+                .get(output.saved_values.get(&key.0.id()).unwrap())
                 .unwrap()
                 .clone(),
         };
         let currents: Vec<Arc<Vec<f64>>> = key
             .1
-             .0
+            .ids()
             .iter()
             .map(|key| {
                 output
@@ -283,3 +328,91 @@ impl SupportedBy<Spectre> for Tran {
         item.try_into().unwrap()
     }
 }
+
+impl From<common::Tran> for Tran {
+    fn from(value: common::Tran) -> Self {
+        Self {
+            stop: value.stop,
+            start: value.start,
+            ..Default::default()
+        }
+    }
+}
+
+impl SupportedBy<Spectre> for common::Tran {
+    fn into_input(self, inputs: &mut Vec<<Spectre as Simulator>::Input>) {
+        Tran::from(self).into_input(inputs);
+    }
+    fn from_output(
+        outputs: &mut impl Iterator<Item = <Spectre as Simulator>::Output>,
+    ) -> <Self as Analysis>::Output {
+        let output = Tran::from_output(outputs);
+        common::TranOutput {
+            time: output.time,
+            raw_values: output.raw_values,
+            saved_values: output.saved_values,
+        }
+    }
+}
+
+impl Save<Spectre, common::Tran> for SaveOutput {
+    type SaveKey = ();
+    type Saved = common::TranOutput;
+
+    fn save(
+        &self,
+        _ctx: &SimulationContext<Spectre>,
+        _opts: &mut <Spectre as Simulator>::Options,
+    ) -> <Self as Save<Spectre, common::Tran>>::SaveKey {
+    }
+
+    fn from_saved(
+        output: &<common::Tran as Analysis>::Output,
+        _key: &<Self as Save<Spectre, common::Tran>>::SaveKey,
+    ) -> <Self as Save<Spectre, common::Tran>>::Saved {
+        output.clone()
+    }
+}
+
+impl Save<Spectre, common::Tran> for NestedNode {
+    type SaveKey = VoltageSaveKey;
+    type Saved = common::TranWaveform;
+
+    fn save(
+        &self,
+        ctx: &SimulationContext<Spectre>,
+        opts: &mut <Spectre as Simulator>::Options,
+    ) -> <Self as Save<Spectre, common::Tran>>::SaveKey {
+        <NestedNode as Save<Spectre, Tran>>::save(self, ctx, opts)
+    }
+
+    fn from_saved(
+        output: &<common::Tran as Analysis>::Output,
+        key: &<Self as Save<Spectre, common::Tran>>::SaveKey,
+    ) -> <Self as Save<Spectre, common::Tran>>::Saved {
+        output.saved(key)
+    }
+}
+
+impl Save<Spectre, common::Tran> for NestedTerminal {
+    type SaveKey = (VoltageSaveKey, CurrentSaveKey);
+    type Saved = common::TranTerminal;
+
+    fn save(
+        &self,
+        ctx: &SimulationContext<Spectre>,
+        opts: &mut <Spectre as Simulator>::Options,
+    ) -> <Self as Save<Spectre, common::Tran>>::SaveKey {
+        <NestedTerminal as Save<Spectre, Tran>>::save(self, ctx, opts)
+    }
+
+    fn from_saved(
+        output: &<common::Tran as Analysis>::Output,
+        key: &<Self as Save<Spectre, common::Tran>>::SaveKey,
+    ) -> <Self as Save<Spectre, common::Tran>>::Saved {
+        common::TranTerminal {
+            v: output.saved(&key.0),
+            i: output.saved(&key.1),
+        }
+    }
+}
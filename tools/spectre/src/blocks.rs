@@ -9,6 +9,7 @@ use substrate::block::Block;
 use substrate::schematic::{CellBuilder, PrimitiveBinding, Schematic};
 use substrate::simulation::waveform::{TimeWaveform, Waveform};
 use substrate::types::{Array, InOut, Io, Signal, TwoTerminalIo};
+use substrate::units::{Current, Time, Voltage};
 
 use crate::{Primitive, Spectre};
 
@@ -20,22 +21,22 @@ pub struct Pulse {
     /// The one value of the pulse.
     pub val1: Decimal,
     /// The period of the pulse.
-    pub period: Option<Decimal>,
+    pub period: Option<Time>,
     /// Rise time.
-    pub rise: Option<Decimal>,
+    pub rise: Option<Time>,
     /// Fall time.
-    pub fall: Option<Decimal>,
+    pub fall: Option<Time>,
     /// The pulse width.
-    pub width: Option<Decimal>,
+    pub width: Option<Time>,
     /// Waveform delay.
-    pub delay: Option<Decimal>,
+    pub delay: Option<Time>,
 }
 
 /// A voltage source.
 #[derive(Serialize, Deserialize, Debug, Clone, Hash, PartialEq, Eq)]
 pub enum Vsource {
     /// A dc voltage source.
-    Dc(Decimal),
+    Dc(Voltage),
     /// An AC small-signal current source.
     Ac(AcSource),
     /// A pulse voltage source.
@@ -47,7 +48,7 @@ pub enum Vsource {
 impl Vsource {
     /// Creates a new DC voltage source.
     #[inline]
-    pub fn dc(value: Decimal) -> Self {
+    pub fn dc(value: Voltage) -> Self {
         Self::Dc(value)
     }
 
@@ -91,26 +92,26 @@ impl Schematic for Vsource {
         match self {
             Vsource::Dc(dc) => {
                 params.push((literal!("type"), ParamValue::String(literal!("dc"))));
-                params.push((literal!("dc"), ParamValue::Numeric(*dc)));
+                params.push((literal!("dc"), ParamValue::Numeric(dc.value())));
             }
             Vsource::Pulse(pulse) => {
                 params.push((literal!("type"), ParamValue::String(literal!("pulse"))));
                 params.push((literal!("val0"), ParamValue::Numeric(pulse.val0)));
                 params.push((literal!("val1"), ParamValue::Numeric(pulse.val1)));
                 if let Some(period) = pulse.period {
-                    params.push((literal!("period"), ParamValue::Numeric(period)));
+                    params.push((literal!("period"), ParamValue::Numeric(period.value())));
                 }
                 if let Some(rise) = pulse.rise {
-                    params.push((literal!("rise"), ParamValue::Numeric(rise)));
+                    params.push((literal!("rise"), ParamValue::Numeric(rise.value())));
                 }
                 if let Some(fall) = pulse.fall {
-                    params.push((literal!("fall"), ParamValue::Numeric(fall)));
+                    params.push((literal!("fall"), ParamValue::Numeric(fall.value())));
                 }
                 if let Some(width) = pulse.width {
-                    params.push((literal!("width"), ParamValue::Numeric(width)));
+                    params.push((literal!("width"), ParamValue::Numeric(width.value())));
                 }
                 if let Some(delay) = pulse.delay {
-                    params.push((literal!("delay"), ParamValue::Numeric(delay)));
+                    params.push((literal!("delay"), ParamValue::Numeric(delay.value())));
                 }
             }
             Vsource::Pwl(waveform) => {
@@ -164,7 +165,7 @@ pub struct AcSource {
 #[derive(Serialize, Deserialize, Debug, Copy, Clone, Hash, PartialEq, Eq)]
 pub enum Isource {
     /// A DC current source.
-    Dc(Decimal),
+    Dc(Current),
     /// An AC small-signal current source.
     Ac(AcSource),
     /// A pulse current source.
@@ -174,7 +175,7 @@ pub enum Isource {
 impl Isource {
     /// Creates a new DC current source.
     #[inline]
-    pub fn dc(value: Decimal) -> Self {
+    pub fn dc(value: Current) -> Self {
         Self::Dc(value)
     }
 
@@ -218,26 +219,26 @@ impl Schematic for Isource {
         match self {
             Isource::Dc(dc) => {
                 params.push((literal!("type"), ParamValue::String(literal!("dc"))));
-                params.push((literal!("dc"), ParamValue::Numeric(*dc)));
+                params.push((literal!("dc"), ParamValue::Numeric(dc.value())));
             }
             Isource::Pulse(pulse) => {
                 params.push((literal!("type"), ParamValue::String(literal!("pulse"))));
                 params.push((literal!("val0"), ParamValue::Numeric(pulse.val0)));
                 params.push((literal!("val1"), ParamValue::Numeric(pulse.val1)));
                 if let Some(period) = pulse.period {
-                    params.push((literal!("period"), ParamValue::Numeric(period)));
+                    params.push((literal!("period"), ParamValue::Numeric(period.value())));
                 }
                 if let Some(rise) = pulse.rise {
-                    params.push((literal!("rise"), ParamValue::Numeric(rise)));
+                    params.push((literal!("rise"), ParamValue::Numeric(rise.value())));
                 }
                 if let Some(fall) = pulse.fall {
-                    params.push((literal!("fall"), ParamValue::Numeric(fall)));
+                    params.push((literal!("fall"), ParamValue::Numeric(fall.value())));
                 }
                 if let Some(width) = pulse.width {
-                    params.push((literal!("width"), ParamValue::Numeric(width)));
+                    params.push((literal!("width"), ParamValue::Numeric(width.value())));
                 }
                 if let Some(delay) = pulse.delay {
-                    params.push((literal!("delay"), ParamValue::Numeric(delay)));
+                    params.push((literal!("delay"), ParamValue::Numeric(delay.value())));
                 }
             }
             Isource::Ac(ac) => {
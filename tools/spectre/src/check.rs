@@ -0,0 +1,131 @@
+//! Netlist syntax checking.
+
+use std::path::{Path, PathBuf};
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use scir::{Library, NetlistLibConversion};
+use simulator_common::script::script_command;
+use substrate::execute::Executor;
+
+use crate::error::{Error, Result, SyntaxError};
+use crate::log::error_messages;
+use crate::templates::{write_check_script, CheckScriptContext};
+use crate::Spectre;
+
+/// Checks the syntax of the netlist at `netlist` by running Spectre in check-only mode.
+///
+/// `executable` is the shell-quoted command used to invoke Spectre.
+/// `contents` should be the contents of the netlist, and `conv` should be the
+/// conversion metadata produced when netlisting `lib`. Any syntax errors are
+/// mapped back to the SCIR cells and instances that produced them.
+pub(crate) fn check_netlist(
+    executable: &str,
+    lib: &Library<Spectre>,
+    conv: &NetlistLibConversion,
+    netlist: &PathBuf,
+    contents: &str,
+    work_dir: &Path,
+    executor: &dyn Executor,
+) -> Result<()> {
+    let log = work_dir.join("check.log");
+    let check_script = work_dir.join("check.sh");
+    write_check_script(
+        CheckScriptContext {
+            executable,
+            netlist,
+            log_path: &log,
+            bashrc: None,
+        },
+        &check_script,
+    )?;
+
+    let command = script_command(&check_script, work_dir);
+    if executor.execute(command, Default::default()).is_ok() {
+        return Ok(());
+    }
+
+    let log = std::fs::read_to_string(&log).unwrap_or_default();
+    let mut errors = parse_check_log(&log);
+    if errors.is_empty() {
+        return Err(Error::SpectreError);
+    }
+    for error in errors.iter_mut() {
+        locate_error(lib, conv, contents, error);
+    }
+    Err(Error::NetlistSyntax(errors))
+}
+
+/// Parses the errors reported in a Spectre log.
+pub(crate) fn parse_check_log(log: &str) -> Vec<SyntaxError> {
+    lazy_static! {
+        static ref LOCATION: Regex = Regex::new(r#"^"[^"]*"\s+(\d+):\s*(.*)$"#).unwrap();
+    }
+
+    error_messages(log)
+        .into_iter()
+        .map(|message| match LOCATION.captures(&message) {
+            Some(caps) => SyntaxError {
+                line: caps[1].parse().ok(),
+                message: caps[2].to_string(),
+                ..Default::default()
+            },
+            None => SyntaxError {
+                message,
+                ..Default::default()
+            },
+        })
+        .collect()
+}
+
+/// Maps the netlist line of `error` back to the SCIR cell and instance that produced it.
+pub(crate) fn locate_error(
+    lib: &Library<Spectre>,
+    conv: &NetlistLibConversion,
+    netlist: &str,
+    error: &mut SyntaxError,
+) {
+    let lines = netlist.lines().collect::<Vec<_>>();
+    let Some(idx) = error
+        .line
+        .and_then(|line| line.checked_sub(1))
+        .filter(|idx| *idx < lines.len())
+    else {
+        return;
+    };
+    error.text = Some(lines[idx].trim().to_string());
+
+    // Statements may be continued across lines with a trailing backslash.
+    let mut start = idx;
+    while start > 0 && lines[start - 1].trim_end().ends_with('\\') {
+        start -= 1;
+    }
+
+    let mut subckt = None;
+    for line in &lines[..start] {
+        let mut tokens = line.split_whitespace();
+        match tokens.next() {
+            Some("subckt") => subckt = tokens.next(),
+            Some("ends") => subckt = None,
+            _ => (),
+        }
+    }
+
+    // Statements outside of any subcircuit belong to the inlined testbench top cell.
+    let Some(cell_id) = subckt.map_or(lib.top_cell(), |name| lib.try_cell_id_named(name)) else {
+        return;
+    };
+    let cell = lib.cell(cell_id);
+    error.cell = Some(cell.name().clone());
+
+    let Some(name) = lines[start].split_whitespace().next() else {
+        return;
+    };
+    error.instance = conv.cells.get(&cell_id).and_then(|cell_conv| {
+        cell_conv
+            .instances
+            .iter()
+            .find(|(_, netlisted)| netlisted.as_str() == name)
+            .map(|(id, _)| cell.instance(*id).name().clone())
+    });
+}
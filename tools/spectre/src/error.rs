@@ -1,9 +1,16 @@
 //! Spectre errors.
 
+use std::fmt::Display;
+use std::path::PathBuf;
 use std::sync::Arc;
 
+use arcstr::ArcStr;
+use substrate::simulation::discovery::DiscoveryError;
+use substrate::simulation::messages::SimulatorMessage;
 use thiserror::Error as ThisError;
 
+use crate::output::OutputKind;
+
 /// The result type returned by Spectre library functions.
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -19,6 +26,50 @@ pub enum Error {
     /// Error invoking Spectre.
     #[error("error running Spectre")]
     SpectreError,
+    /// Error locating Spectre or determining its version.
+    #[error(transparent)]
+    Discovery(#[from] DiscoveryError),
+    /// Spectre reported errors during simulation.
+    ///
+    /// Names mentioned in each error are mapped back to SCIR and Substrate paths where possible.
+    #[error(
+        "Spectre simulation failed:\n{}",
+        .0.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n")
+    )]
+    SimulationFailed(Vec<SimulatorMessage>),
+    /// Syntax errors found while checking a netlist.
+    #[error("netlist syntax check failed with {} error(s)", .0.len())]
+    NetlistSyntax(Vec<SyntaxError>),
+    /// The simulation was stopped early by a progress callback.
+    #[error("Spectre simulation stopped early")]
+    Stopped,
+    /// Error converting a PSF or PSF-XL output file to ASCII PSF.
+    #[error("error converting PSF output file {0:?} to ASCII PSF")]
+    PsfConversion(PathBuf),
+    /// No output file was found for an analysis.
+    #[error("no {kind} output found for analysis `{analysis}` in {dir:?}")]
+    MissingOutput {
+        /// The name of the analysis.
+        analysis: String,
+        /// The kind of output that was expected.
+        kind: OutputKind,
+        /// The raw output directory that was searched.
+        dir: PathBuf,
+    },
+    /// A managed include did not match its expected checksum.
+    #[error("checksum of include {path:?} is {actual}, expected {expected}")]
+    ChecksumMismatch {
+        /// The path to the included file.
+        path: PathBuf,
+        /// The expected hex SHA-256 checksum.
+        expected: String,
+        /// The actual hex SHA-256 checksum.
+        actual: String,
+    },
+    /// A path referenced by an analysis does not exist in the simulated library,
+    /// or was not resolved before netlisting.
+    #[error("cannot resolve path referenced by analysis: {0}")]
+    UnresolvedPath(String),
     /// Error parsing output files.
     #[error("error parsing Spectre output file")]
     Parse,
@@ -29,3 +80,39 @@ pub enum Error {
     #[error("error generating spectre results")]
     Caching(#[from] Arc<cache::error::Error>),
 }
+
+/// A syntax error reported by Spectre while checking a netlist.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct SyntaxError {
+    /// The error message reported by Spectre.
+    pub message: String,
+    /// The 1-indexed line of the netlist containing the error, if known.
+    pub line: Option<usize>,
+    /// The contents of the offending netlist line, if known.
+    pub text: Option<String>,
+    /// The name of the SCIR cell containing the offending line, if known.
+    pub cell: Option<ArcStr>,
+    /// The name of the SCIR instance that produced the offending line, if known.
+    pub instance: Option<ArcStr>,
+}
+
+impl Display for SyntaxError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)?;
+        if let Some(line) = self.line {
+            write!(f, " (netlist line {line}")?;
+            if let Some(text) = &self.text {
+                write!(f, ": `{text}`")?;
+            }
+            write!(f, ")")?;
+        }
+        match (&self.cell, &self.instance) {
+            (Some(cell), Some(instance)) => {
+                write!(f, " produced by instance `{instance}` of cell `{cell}`")?
+            }
+            (Some(cell), None) => write!(f, " in cell `{cell}`")?,
+            _ => (),
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,171 @@
+//! Managed includes for protected and vendor-encrypted model files.
+//!
+//! Commercial model decks are often distributed as Verilog-A files, files encrypted with
+//! `spectre_encrypt`, or netlists whose contents must not be echoed to the simulation log.
+//! A [`ManagedInclude`] describes how such a file is included, whether it is copied into
+//! the simulation working directory, and the checksum it must match:
+//!
+//! ```ignore
+//! opts.include_managed(
+//!     ManagedInclude::new("/vendor/models/io.scs")
+//!         .section("tt")
+//!         .kind(IncludeKind::Protected)
+//!         .copy_to_work_dir()
+//!         .sha256("9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08"),
+//! );
+//! ```
+//!
+//! Checksums are verified and files are copied each time a netlist is written,
+//! before the simulation runs.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use arcstr::ArcStr;
+use sha2::{Digest, Sha256};
+
+use crate::error::{Error, Result};
+
+/// The directory within the simulation working directory to which includes are copied.
+const INCLUDE_DIR: &str = "includes";
+
+/// How a managed include is written to the netlist.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum IncludeKind {
+    /// A Spectre or SPICE netlist, included with `include`.
+    ///
+    /// Files encrypted with `spectre_encrypt` are decrypted by Spectre and should use this kind.
+    #[default]
+    Netlist,
+    /// A netlist whose contents are hidden from the simulation log.
+    ///
+    /// The include is wrapped in `protect` and `unprotect` statements.
+    Protected,
+    /// A Verilog-A model, included with `ahdl_include`.
+    ///
+    /// Library sections are not supported.
+    Ahdl,
+}
+
+/// Whether a managed include is copied into the simulation working directory.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum CopyPolicy {
+    /// Include the file from its original location.
+    #[default]
+    Reference,
+    /// Copy the file into the `includes` directory of the simulation working directory
+    /// and include the copy.
+    ///
+    /// Only the file itself is copied, so files it includes by relative path must
+    /// also be managed includes.
+    CopyToWorkDir,
+}
+
+/// An include that requires special handling.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct ManagedInclude {
+    /// The path to include.
+    pub path: PathBuf,
+    /// The section of the provided file to include.
+    pub section: Option<ArcStr>,
+    /// How the include is written to the netlist.
+    pub kind: IncludeKind,
+    /// Whether the file is copied into the simulation working directory.
+    pub copy: CopyPolicy,
+    /// The expected lowercase hex SHA-256 checksum of the file, if any.
+    pub sha256: Option<String>,
+}
+
+impl ManagedInclude {
+    /// Creates a new [`ManagedInclude`] of the file at `path`.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            section: None,
+            kind: IncludeKind::default(),
+            copy: CopyPolicy::default(),
+            sha256: None,
+        }
+    }
+
+    /// Returns a new [`ManagedInclude`] with the given section.
+    pub fn section(mut self, section: impl Into<ArcStr>) -> Self {
+        self.section = Some(section.into());
+        self
+    }
+
+    /// Returns a new [`ManagedInclude`] of the given kind.
+    pub fn kind(mut self, kind: IncludeKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Returns a new [`ManagedInclude`] that is copied into the simulation working directory.
+    pub fn copy_to_work_dir(mut self) -> Self {
+        self.copy = CopyPolicy::CopyToWorkDir;
+        self
+    }
+
+    /// Returns a new [`ManagedInclude`] that must have the given hex SHA-256 checksum.
+    pub fn sha256(mut self, checksum: impl Into<String>) -> Self {
+        self.sha256 = Some(checksum.into().to_lowercase());
+        self
+    }
+
+    /// Verifies the checksum of the included file and copies it into `work_dir` if required.
+    ///
+    /// `index` distinguishes copies of files with the same name. Returns the path that
+    /// should be included in the netlist.
+    pub(crate) fn resolve(&self, work_dir: &Path, index: usize) -> Result<PathBuf> {
+        if self.kind == IncludeKind::Ahdl && self.section.is_some() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("Verilog-A include {:?} cannot have a section", self.path),
+            )
+            .into());
+        }
+        if let Some(expected) = &self.sha256 {
+            let actual = format!("{:x}", Sha256::digest(std::fs::read(&self.path)?));
+            if &actual != expected {
+                return Err(Error::ChecksumMismatch {
+                    path: self.path.clone(),
+                    expected: expected.clone(),
+                    actual,
+                });
+            }
+        }
+
+        match self.copy {
+            CopyPolicy::Reference => Ok(self.path.clone()),
+            CopyPolicy::CopyToWorkDir => {
+                let file_name = self.path.file_name().ok_or_else(|| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::InvalidInput,
+                        format!("include path {:?} does not name a file", self.path),
+                    )
+                })?;
+                let dir = work_dir.join(INCLUDE_DIR).join(index.to_string());
+                std::fs::create_dir_all(&dir)?;
+                let dst = dir.join(file_name);
+                std::fs::copy(&self.path, &dst)?;
+                Ok(dst)
+            }
+        }
+    }
+
+    /// Writes the include statement for the file at `path` to `out`.
+    pub(crate) fn netlist<W: Write>(&self, out: &mut W, path: &Path) -> Result<()> {
+        if self.kind == IncludeKind::Protected {
+            writeln!(out, "protect")?;
+        }
+        match (self.kind, &self.section) {
+            (IncludeKind::Ahdl, _) => writeln!(out, "ahdl_include {:?}", path)?,
+            (_, Some(section)) => writeln!(out, "include {:?} section={}", path, section)?,
+            (_, None) => writeln!(out, "include {:?}", path)?,
+        }
+        if self.kind == IncludeKind::Protected {
+            writeln!(out, "unprotect")?;
+        }
+        Ok(())
+    }
+}
@@ -4,27 +4,45 @@
 use std::collections::{HashMap, HashSet};
 use std::fmt::{Display, Formatter};
 use std::io::Write;
-#[cfg(any(unix, target_os = "redox"))]
-use std::os::unix::prelude::PermissionsExt;
 use std::path::{Path, PathBuf};
 use std::process::Stdio;
 use std::sync::Arc;
 
 use crate::analysis::ac::Ac;
+use crate::analysis::alter;
+use crate::analysis::alter::{Alter, Alteration};
 use crate::analysis::montecarlo;
 use crate::analysis::montecarlo::MonteCarlo;
+use crate::analysis::noise;
+use crate::analysis::noise::Noise;
+use crate::analysis::pac;
+use crate::analysis::pac::Pac;
+use crate::analysis::pnoise;
+use crate::analysis::pnoise::Pnoise;
+use crate::analysis::pss;
+use crate::analysis::pss::Pss;
+use crate::analysis::reliability;
+use crate::analysis::reliability::{Reliability, ReliabilityControl};
+use crate::analysis::sens;
+use crate::analysis::sens::{Sens, SensAnalysis, SensValue};
+use crate::analysis::stb;
+use crate::analysis::stb::Stb;
+use crate::analysis::sweep;
+use crate::analysis::sweep::ParamSweep;
 
 use analysis::dc::DcOp;
+use analysis::info::Info;
 use analysis::tran;
 use analysis::tran::Tran;
-use analysis::{ac, dc, Sweep};
+use analysis::{ac, dc, info};
 use arcstr::ArcStr;
 use cache::error::TryInnerError;
-use cache::CacheableWithState;
 use error::*;
+use include::ManagedInclude;
 use itertools::Itertools;
 use lazy_static::lazy_static;
 use num::complex::Complex64;
+use output::{OutputKind, OutputNaming};
 use psfparser::analysis::ac::AcData;
 use psfparser::analysis::dc::DcData;
 use psfparser::analysis::transient::TransientData;
@@ -33,27 +51,51 @@ use rust_decimal::Decimal;
 use scir::netlist::ConvertibleNetlister;
 use scir::schema::{FromSchema, NoSchema, NoSchemaError};
 use scir::{
-    Library, NamedSliceOne, NetlistLibConversion, ParamValue, SignalInfo, Slice, SliceOnePath,
+    Expr, Library, NamedSliceOne, NetlistLibConversion, Param, ParamValue, SignalInfo, Slice,
+    SliceOnePath,
 };
 use serde::{Deserialize, Serialize};
+use simulator_common::cached::run_cached;
+use simulator_common::decimate::decimate_tran;
+use simulator_common::names::{Interned, SEPARATOR};
+use simulator_common::paths::scir_node_path;
+use simulator_common::saves::SaveKeys;
+use simulator_common::script::{script_command, shell_quote};
 use spice::netlist::{
     HasSpiceLikeNetlist, Include, NetlistKind, NetlistOptions, NetlisterInstance, RenameGround,
 };
 use spice::{BlackboxContents, BlackboxElement, Spice};
 use substrate::context::Installation;
 use substrate::execute::Executor;
-use substrate::schematic::conv::ConvertedNodePath;
+use substrate::schematic::conv::{ConvertedNodePath, RawLib};
 use substrate::schematic::schema::Schema;
+use substrate::schematic::{NestedInstance, Schematic};
+use substrate::simulation::analysis::{InstanceRef, NodeRef};
+use substrate::simulation::data::{NestedPorts, NodeAliases, Save};
+use substrate::simulation::discovery::{
+    find_executable, find_optional_executable, probe_executable, probe_tool, ToolInfo, Version,
+};
+use substrate::simulation::models::{ModelInclude, SupportsModels};
 use substrate::simulation::options::ic::InitialCondition;
-use substrate::simulation::options::{ic, SimOption, Temperature};
-use substrate::simulation::{SimulationContext, Simulator, SupportedBy};
+use substrate::simulation::options::{ic, Decimation, SimOption, Temperature, TransientNoise};
+use substrate::simulation::progress::ProgressCallback;
+use substrate::simulation::{
+    Analysis, DryRun, SimArtifacts, SimulationContext, Simulator, SupportedBy,
+};
 use substrate::types::schematic::NodePath;
 use templates::{write_run_script, RunScriptContext};
+use tracing::{span, Level};
 use type_dispatch::impl_dispatch;
 
 pub mod analysis;
 pub mod blocks;
+pub(crate) mod check;
 pub mod error;
+pub mod include;
+pub(crate) mod log;
+pub mod output;
+pub(crate) mod progress;
+pub mod report;
 pub(crate) mod templates;
 #[cfg(test)]
 mod tests;
@@ -195,14 +237,136 @@ pub struct Spectre {}
 #[derive(Debug, Clone, Default)]
 pub struct Options {
     includes: HashSet<Include>,
-    saves: HashMap<SimSignal, u64>,
+    /// Includes of protected or encrypted files.
+    managed_includes: HashSet<ManagedInclude>,
+    saves: SaveKeys<SimSignal>,
     ics: HashMap<SimSignal, Decimal>,
-    next_save_key: u64,
     /// The simulation temperature.
     temp: Option<Decimal>,
     save: Option<SaveOption>,
     /// Override the default Spectre flags.
     override_flags: Option<String>,
+    /// Whether to check the netlist syntax before simulating.
+    check_syntax: bool,
+    /// Checkpointing options for transient analyses.
+    checkpoint: Option<Checkpoint>,
+    /// Whether to resume from the most recent checkpoint.
+    recover: bool,
+    /// The raw output format written by Spectre.
+    format: OutputFormat,
+    /// The raw output directory, relative to the simulation working directory.
+    raw_dir: Option<PathBuf>,
+    /// The naming scheme of raw output files.
+    naming: OutputNaming,
+    /// Metadata describing the simulation job.
+    job: JobMetadata,
+    /// License queueing options.
+    license_queue: Option<LicenseQueue>,
+    /// Transient noise options applied to transient analyses that do not enable noise.
+    transient_noise: Option<TransientNoise>,
+    /// The file to which the DC operating point is saved.
+    op_save: Option<PathBuf>,
+    /// The file from which the initial DC operating point guess is restored.
+    op_restore: Option<PathBuf>,
+    /// Whether to compress the names of saved signals.
+    compress_saves: bool,
+    /// Decimation applied to transient outputs after simulation.
+    decimation: Option<Decimation>,
+}
+
+/// The name of the DC analysis that saves and restores operating points.
+const OP_SNAPSHOT: &str = "op_snapshot";
+/// The file, relative to the simulation working directory, to which Spectre writes
+/// the saved operating point.
+const OP_SAVE_FILE: &str = "op_save.ns";
+/// The file, relative to the simulation working directory, from which Spectre reads
+/// the restored operating point.
+const OP_RESTORE_FILE: &str = "op_restore.ns";
+
+/// The raw output format written by Spectre.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum OutputFormat {
+    /// Binary PSF.
+    #[default]
+    PsfBin,
+    /// PSF-XL.
+    ///
+    /// Faster to write than binary PSF for large transient simulations. Spectre only writes
+    /// transient results in PSF-XL; other analyses are still written as binary PSF.
+    /// PSF-XL files are converted to ASCII PSF using Cadence's `psf` utility before being
+    /// parsed, so `psf` must be available on the `PATH`. Requires Spectre 15.1 or newer.
+    PsfXl,
+}
+
+impl Display for OutputFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::PsfBin => write!(f, "psfbin"),
+            Self::PsfXl => write!(f, "psfxl"),
+        }
+    }
+}
+
+/// Checkpointing options for transient analyses.
+///
+/// Checkpoints allow long transient simulations to be resumed after a crash
+/// (see [`Options::recover`]).
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct Checkpoint {
+    /// The wall-clock interval between checkpoints, in seconds.
+    pub clock: Option<Decimal>,
+    /// The simulation time interval between checkpoints, in seconds.
+    pub period: Option<Decimal>,
+}
+
+impl Checkpoint {
+    fn netlist<W: Write>(&self, out: &mut W) -> Result<()> {
+        if let Some(clock) = self.clock {
+            write!(out, " ckptclock={clock}")?;
+        }
+        if let Some(period) = self.period {
+            write!(out, " ckptperiod={period}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Metadata describing a Spectre job.
+///
+/// Job metadata is exported to the run script's environment as `SUBSTRATE_JOB_DESIGN`
+/// and `SUBSTRATE_JOB_TAG`, so that executors and site-specific wrappers can use it
+/// for accounting. It does not affect the simulation cache key.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct JobMetadata {
+    /// The name of the design being simulated.
+    pub design: Option<String>,
+    /// A user-defined tag identifying the job.
+    pub tag: Option<String>,
+}
+
+/// License queueing options.
+///
+/// By default, Spectre fails immediately if no license is available. With license
+/// queueing enabled, Spectre instead waits for a license to be released.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct LicenseQueue {
+    /// The maximum time to wait for a license, in seconds.
+    ///
+    /// If [`None`], Spectre waits indefinitely.
+    pub timeout: Option<u64>,
+    /// The interval between attempts to check out a license, in seconds.
+    pub sleep: Option<u64>,
+}
+
+impl LicenseQueue {
+    fn flags(&self) -> String {
+        // A timeout of 0 instructs Spectre to wait indefinitely.
+        let mut flags = format!(" +lqtimeout {}", self.timeout.unwrap_or(0));
+        if let Some(sleep) = self.sleep {
+            flags.push_str(&format!(" +lqsleep {sleep}"));
+        }
+        flags
+    }
 }
 
 /// The allowed values of the `save` option.
@@ -258,18 +422,15 @@ impl Options {
     pub fn include_section(&mut self, path: impl Into<PathBuf>, section: impl Into<ArcStr>) {
         self.includes.insert(Include::new(path).section(section));
     }
+    /// Include the given protected, encrypted, or Verilog-A file.
+    ///
+    /// See [`include`](crate::include) for details.
+    pub fn include_managed(&mut self, include: ManagedInclude) {
+        self.managed_includes.insert(include);
+    }
 
     fn save_inner(&mut self, save: impl Into<SimSignal>) -> u64 {
-        let save = save.into();
-
-        if let Some(key) = self.saves.get(&save) {
-            *key
-        } else {
-            let save_key = self.next_save_key;
-            self.next_save_key += 1;
-            self.saves.insert(save, save_key);
-            save_key
-        }
+        self.saves.save(save.into())
     }
 
     fn set_ic_inner(&mut self, key: impl Into<SimSignal>, value: Decimal) {
@@ -278,32 +439,56 @@ impl Options {
 
     /// Marks a transient voltage to be saved in all transient analyses.
     pub fn save_tran_voltage(&mut self, save: impl Into<SimSignal>) -> tran::VoltageSaveKey {
-        tran::VoltageSaveKey(self.save_inner(save))
+        tran::VoltageSaveKey::new(self.save_inner(save))
     }
 
     /// Marks a transient current to be saved in all transient analyses.
     pub fn save_tran_current(&mut self, save: impl Into<SimSignal>) -> tran::CurrentSaveKey {
-        tran::CurrentSaveKey(vec![self.save_inner(save)])
+        tran::CurrentSaveKey::new(self.save_inner(save))
+    }
+
+    /// Saves the voltage and current of every terminal of `instance` in analyses of type `A`.
+    ///
+    /// Buses are expanded, so that each bit is saved individually. The saved outputs can be
+    /// recovered using [`Save::from_saved`]:
+    ///
+    /// ```ignore
+    /// let key = opts.save_ports::<Tran, _>(ctx, &dut);
+    /// // ...
+    /// let ports = <NestedPorts as Save<Spectre, Tran>>::from_saved(&output, &key);
+    /// let vout = &ports.get("out").unwrap().v;
+    /// ```
+    pub fn save_ports<A, T>(
+        &mut self,
+        ctx: &SimulationContext<Spectre>,
+        instance: &NestedInstance<T>,
+    ) -> <NestedPorts as Save<Spectre, A>>::SaveKey
+    where
+        A: Analysis,
+        T: Schematic,
+        NestedPorts: Save<Spectre, A>,
+    {
+        <NestedPorts as Save<Spectre, A>>::save(&NestedPorts::new(instance), ctx, self)
     }
 
     /// Marks an AC voltage to be saved in all AC analyses.
     pub fn save_ac_voltage(&mut self, save: impl Into<SimSignal>) -> ac::VoltageSaveKey {
-        ac::VoltageSaveKey(self.save_inner(save))
+        ac::VoltageSaveKey::new(self.save_inner(save))
     }
 
     /// Marks an AC current to be saved in all AC analyses.
     pub fn save_ac_current(&mut self, save: impl Into<SimSignal>) -> ac::CurrentSaveKey {
-        ac::CurrentSaveKey(vec![self.save_inner(save)])
+        ac::CurrentSaveKey::new(self.save_inner(save))
     }
 
     /// Marks a DC voltage to be saved in all DC analyses.
     pub fn save_dc_voltage(&mut self, save: impl Into<SimSignal>) -> dc::VoltageSaveKey {
-        dc::VoltageSaveKey(self.save_inner(save))
+        dc::VoltageSaveKey::new(self.save_inner(save))
     }
 
     /// Marks a DC current to be saved in all DC analyses.
     pub fn save_dc_current(&mut self, save: impl Into<SimSignal>) -> dc::CurrentSaveKey {
-        dc::CurrentSaveKey(vec![self.save_inner(save)])
+        dc::CurrentSaveKey::new(self.save_inner(save))
     }
 
     /// Set the simulation temperature.
@@ -322,6 +507,141 @@ impl Options {
     pub fn set_flags(&mut self, flags: impl Into<String>) {
         self.override_flags = Some(flags.into());
     }
+
+    /// Sets whether to check the syntax of the netlist before running the simulation.
+    ///
+    /// If enabled, Spectre is first run in check-only mode. Any syntax errors are
+    /// returned as an [`Error::NetlistSyntax`], with each error mapped back to the
+    /// SCIR instance that produced the offending netlist line where possible.
+    pub fn check_syntax(&mut self, check: bool) {
+        self.check_syntax = check;
+    }
+
+    /// Periodically checkpoints all transient analyses.
+    ///
+    /// Checkpointing does not affect the simulation cache key, so a run that is
+    /// resumed from a checkpoint maps to the same cache entry as the original run.
+    pub fn set_checkpoint(&mut self, checkpoint: Checkpoint) {
+        self.checkpoint = Some(checkpoint);
+    }
+
+    /// Sets whether to resume transient analyses from the most recent checkpoint.
+    ///
+    /// Has no effect if no checkpoint exists in the simulation's working directory.
+    pub fn recover(&mut self, recover: bool) {
+        self.recover = recover;
+    }
+
+    /// Sets the raw output format written by Spectre.
+    ///
+    /// Does not affect the simulation cache key, as all formats are parsed into the same outputs.
+    pub fn set_format(&mut self, format: OutputFormat) {
+        self.format = format;
+    }
+
+    /// Sets the directory to which Spectre writes raw outputs.
+    ///
+    /// Relative paths are resolved relative to the simulation working directory.
+    /// Defaults to `psf`.
+    pub fn set_raw_dir(&mut self, dir: impl Into<PathBuf>) {
+        self.raw_dir = Some(dir.into());
+    }
+
+    /// Sets the naming scheme used to locate raw output files.
+    ///
+    /// Does not affect the simulation cache key.
+    pub fn set_output_naming(&mut self, naming: OutputNaming) {
+        self.naming = naming;
+    }
+
+    /// The directory to which Spectre writes raw outputs for a simulation in `work_dir`.
+    fn raw_output_dir(&self, work_dir: &Path) -> PathBuf {
+        work_dir.join(self.raw_dir.as_deref().unwrap_or(Path::new("psf")))
+    }
+
+    /// Sets the metadata describing the simulation job.
+    pub fn set_job(&mut self, job: JobMetadata) {
+        self.job = job;
+    }
+
+    /// Queues for licenses instead of failing when none are available.
+    ///
+    /// License queue flags are passed to Spectre even if the default flags are overridden
+    /// using [`Options::set_flags`].
+    pub fn queue_licenses(&mut self, queue: LicenseQueue) {
+        self.license_queue = Some(queue);
+    }
+
+    /// Saves the DC operating point of the simulation to `path`.
+    ///
+    /// The saved file can be passed to [`Options::restore_op`] to speed up convergence of
+    /// similar testbenches. The operating point is stored in the simulation cache, so the
+    /// file is written even if the simulation results are cached.
+    pub fn save_op(&mut self, path: impl Into<PathBuf>) {
+        self.op_save = Some(path.into());
+    }
+
+    /// Uses the DC operating point saved to `path` by [`Options::save_op`] as nodesets
+    /// for the initial DC solution.
+    ///
+    /// Subsequent analyses start from the resulting solution. The contents of the file
+    /// are part of the simulation cache key.
+    pub fn restore_op(&mut self, path: impl Into<PathBuf>) {
+        self.op_restore = Some(path.into());
+    }
+
+    /// Sets whether to compress the names of saved signals.
+    ///
+    /// If enabled, saved signals that share a parent instance are saved by a single
+    /// `save` statement, and the names of signals in transient and AC outputs are
+    /// interned by hierarchy before being stored in the simulation cache. Outputs are
+    /// looked up by their full names as usual.
+    ///
+    /// Spectre requires full names in `save` statements, so grouping reduces the number of
+    /// statements but not the size of the netlist. Interning reduces the size of cached
+    /// outputs; outputs are expanded to full names when they are loaded, so it does not
+    /// reduce the memory used by simulation outputs.
+    ///
+    /// Useful for simulations that save thousands of signals. Disabled by default.
+    pub fn compress_saves(&mut self, compress: bool) {
+        self.compress_saves = compress;
+    }
+
+    /// Enables noise sources in all transient analyses.
+    ///
+    /// Transient analyses that set [`Tran::noise_fmax`] keep their own noise parameters.
+    pub fn set_transient_noise(&mut self, noise: TransientNoise) {
+        self.transient_noise = Some(noise);
+    }
+
+    /// Decimates the outputs of all transient analyses after simulation.
+    ///
+    /// Outputs are decimated before they are stored in the simulation cache. This reduces the
+    /// size of cached outputs, but not the memory used while reading the Spectre output.
+    /// To reduce the data written by Spectre itself, see [`Tran::strobe`].
+    pub fn set_decimation(&mut self, decimation: Decimation) {
+        self.decimation = Some(decimation);
+    }
+}
+
+impl SimOption<Spectre> for Decimation {
+    fn set_option(
+        self,
+        opts: &mut <Spectre as Simulator>::Options,
+        _ctx: &SimulationContext<Spectre>,
+    ) {
+        opts.set_decimation(self)
+    }
+}
+
+impl SimOption<Spectre> for TransientNoise {
+    fn set_option(
+        self,
+        opts: &mut <Spectre as Simulator>::Options,
+        _ctx: &SimulationContext<Spectre>,
+    ) {
+        opts.set_transient_noise(self)
+    }
 }
 
 impl SimOption<Spectre> for Temperature {
@@ -402,13 +722,10 @@ impl<T> SimOption<Spectre> for InitialCondition<T, ic::Voltage> {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
-struct CachedSim {
-    simulation_netlist: Vec<u8>,
-}
-
 struct CachedSimState {
     input: Vec<Input>,
+    /// The command used to invoke Spectre.
+    executable: String,
     netlist: PathBuf,
     output_path: PathBuf,
     log: PathBuf,
@@ -417,6 +734,24 @@ struct CachedSimState {
     executor: Arc<dyn Executor>,
     /// Override the default Spectre flags.
     override_flags: Option<String>,
+    /// Whether to resume from the most recent checkpoint.
+    recover: bool,
+    /// The raw output format written by Spectre.
+    format: OutputFormat,
+    /// The naming scheme of raw output files.
+    naming: OutputNaming,
+    /// A callback to which Spectre progress is reported.
+    progress: Option<ProgressCallback>,
+    /// Metadata describing the simulation job.
+    job: JobMetadata,
+    /// License queueing options.
+    license_queue: Option<LicenseQueue>,
+    /// Whether the DC operating point is saved.
+    op_save: bool,
+    /// Whether to intern the names of saved signals.
+    intern_names: bool,
+    /// Decimation applied to transient outputs.
+    decimation: Option<Decimation>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -427,17 +762,71 @@ enum CachedData {
         signals: HashMap<String, Vec<Complex64>>,
     },
     DcOp(HashMap<String, f64>),
-    // The outer vec has length `numruns`.
+    Info(HashMap<ArcStr, info::InfoEntry>),
+    // One map from parameter name to sensitivity per output, in the order of the outputs.
+    DcSens(Vec<HashMap<String, f64>>),
+    AcSens {
+        freq: Vec<f64>,
+        values: Vec<HashMap<String, Vec<Complex64>>>,
+    },
+    Stb {
+        freq: Vec<f64>,
+        loop_gain: Vec<Complex64>,
+    },
+    Pss {
+        harmonics: Vec<f64>,
+        freq_values: HashMap<String, Vec<Complex64>>,
+        time: Vec<f64>,
+        time_values: HashMap<String, Vec<f64>>,
+    },
+    // Each sideband maps to a map from signal name to values.
+    Pac {
+        freq: Vec<f64>,
+        values: Vec<(i64, HashMap<String, Vec<Complex64>>)>,
+    },
+    Pnoise {
+        freq: Vec<f64>,
+        values: HashMap<String, Vec<f64>>,
+    },
+    Noise {
+        freq: Vec<f64>,
+        values: HashMap<String, Vec<f64>>,
+    },
+    // Transient and AC outputs with names interned by hierarchy.
+    InternedTran(Interned<Vec<f64>>),
+    InternedAc {
+        freq: Vec<f64>,
+        signals: Interned<Vec<Complex64>>,
+    },
+    // A saved DC operating point. Not produced by an analysis; appended to the
+    // analysis outputs if the operating point is saved.
+    Nodeset(String),
+    // The outer vec has length `numruns`, as does `iterations`.
+    // The inner vec length equals the length of the inner analysis.
+    MonteCarlo {
+        iterations: Vec<montecarlo::Iteration>,
+        data: Vec<Vec<CachedData>>,
+    },
+    // The outer vec has one entry per alter group.
+    // The inner vec length equals the length of the inner analysis.
+    Alter(Vec<Vec<CachedData>>),
+    // Each vec length equals the length of the inner analysis.
+    Reliability {
+        fresh: Vec<CachedData>,
+        aged: Vec<CachedData>,
+    },
+    // The outer vec has one entry per swept value.
     // The inner vec length equals the length of the inner analysis.
-    MonteCarlo(Vec<Vec<CachedData>>),
+    ParamSweep(Vec<Vec<CachedData>>),
 }
 
 impl CachedData {
     fn into_output(
         self,
         ctx: &SimulationContext<Spectre>,
-        conv: &NetlistLibConversion,
-        saves: &HashMap<SimSignal, u64>,
+        conv: &Arc<NetlistLibConversion>,
+        saves: &SaveKeys<SimSignal>,
+        aliases: &Arc<NodeAliases>,
     ) -> Output {
         match self {
             CachedData::Tran(mut raw_values) => tran::Output {
@@ -450,6 +839,11 @@ impl CachedData {
                     .iter()
                     .map(|(k, v)| (*v, k.to_string(&ctx.lib.scir, conv)))
                     .collect(),
+                resolver: Some(tran::PathResolver {
+                    lib: ctx.lib.clone(),
+                    conv: conv.clone(),
+                    aliases: aliases.clone(),
+                }),
             }
             .into(),
             CachedData::Ac { freq, signals } => ac::Output {
@@ -462,6 +856,11 @@ impl CachedData {
                     .iter()
                     .map(|(k, v)| (*v, k.to_string(&ctx.lib.scir, conv)))
                     .collect(),
+                resolver: Some(tran::PathResolver {
+                    lib: ctx.lib.clone(),
+                    conv: conv.clone(),
+                    aliases: aliases.clone(),
+                }),
             }
             .into(),
             CachedData::DcOp(values) => dc::OpOutput {
@@ -475,11 +874,146 @@ impl CachedData {
                     .collect(),
             }
             .into(),
-            CachedData::MonteCarlo(data) => Output::MonteCarlo(montecarlo::Output(
+            CachedData::Info(entries) => info::Output {
+                entries,
+                resolver: Some(tran::PathResolver {
+                    lib: ctx.lib.clone(),
+                    conv: conv.clone(),
+                    aliases: aliases.clone(),
+                }),
+            }
+            .into(),
+            CachedData::DcSens(values) => sens::Output {
+                freq: None,
+                raw_values: values
+                    .into_iter()
+                    .map(|values| {
+                        values
+                            .into_iter()
+                            .map(|(k, v)| (ArcStr::from(k), SensValue::Dc(v)))
+                            .collect()
+                    })
+                    .collect(),
+                resolver: Some(tran::PathResolver {
+                    lib: ctx.lib.clone(),
+                    conv: conv.clone(),
+                    aliases: aliases.clone(),
+                }),
+            }
+            .into(),
+            CachedData::AcSens { freq, values } => sens::Output {
+                freq: Some(Arc::new(freq)),
+                raw_values: values
+                    .into_iter()
+                    .map(|values| {
+                        values
+                            .into_iter()
+                            .map(|(k, v)| (ArcStr::from(k), SensValue::Ac(Arc::new(v))))
+                            .collect()
+                    })
+                    .collect(),
+                resolver: Some(tran::PathResolver {
+                    lib: ctx.lib.clone(),
+                    conv: conv.clone(),
+                    aliases: aliases.clone(),
+                }),
+            }
+            .into(),
+            CachedData::Stb { freq, loop_gain } => stb::Output {
+                freq: Arc::new(freq),
+                loop_gain: Arc::new(loop_gain),
+            }
+            .into(),
+            CachedData::Pss {
+                harmonics,
+                freq_values,
+                time,
+                time_values,
+            } => pss::Output {
+                harmonics: Arc::new(harmonics),
+                raw_harmonics: freq_values
+                    .into_iter()
+                    .map(|(k, v)| (ArcStr::from(k), Arc::new(v)))
+                    .collect(),
+                time: Arc::new(time),
+                raw_values: time_values
+                    .into_iter()
+                    .map(|(k, v)| (ArcStr::from(k), Arc::new(v)))
+                    .collect(),
+            }
+            .into(),
+            CachedData::Pac { freq, values } => pac::Output {
+                freq: Arc::new(freq),
+                raw_values: values
+                    .into_iter()
+                    .map(|(sideband, values)| {
+                        let values = values
+                            .into_iter()
+                            .map(|(k, v)| (ArcStr::from(k), Arc::new(v)))
+                            .collect();
+                        (sideband, values)
+                    })
+                    .collect(),
+            }
+            .into(),
+            CachedData::Pnoise { freq, values } => pnoise::Output {
+                freq: Arc::new(freq),
+                raw_values: values
+                    .into_iter()
+                    .map(|(k, v)| (ArcStr::from(k), Arc::new(v)))
+                    .collect(),
+            }
+            .into(),
+            CachedData::Noise { freq, values } => noise::Output {
+                freq: Arc::new(freq),
+                raw_values: values
+                    .into_iter()
+                    .map(|(k, v)| (ArcStr::from(k), Arc::new(v)))
+                    .collect(),
+            }
+            .into(),
+            CachedData::InternedTran(signals) => CachedData::Tran(signals.into_iter().collect())
+                .into_output(ctx, conv, saves, aliases),
+            CachedData::InternedAc { freq, signals } => CachedData::Ac {
+                freq,
+                signals: signals.into_iter().collect(),
+            }
+            .into_output(ctx, conv, saves, aliases),
+            CachedData::Nodeset(_) => {
+                unreachable!("saved operating points are not analysis outputs")
+            }
+            CachedData::MonteCarlo { iterations, data } => Output::MonteCarlo(montecarlo::Output {
+                iterations,
+                outputs: data
+                    .into_iter()
+                    .map(|data| {
+                        data.into_iter()
+                            .map(|d| d.into_output(ctx, conv, saves, aliases))
+                            .collect()
+                    })
+                    .collect(),
+            }),
+            CachedData::Alter(data) => Output::Alter(alter::Output(
+                data.into_iter()
+                    .map(|data| {
+                        data.into_iter()
+                            .map(|d| d.into_output(ctx, conv, saves, aliases))
+                            .collect()
+                    })
+                    .collect(),
+            )),
+            CachedData::Reliability { fresh, aged } => {
+                Output::Reliability(reliability::Output { fresh, aged }.map(|data| {
+                    data.into_iter()
+                        .map(|d| d.into_output(ctx, conv, saves, aliases))
+                        .collect()
+                }))
+            }
+            CachedData::ParamSweep(data) => Output::ParamSweep(sweep::Output(
                 data.into_iter()
                     .map(|data| {
                         data.into_iter()
-                            .map(|d| d.into_output(ctx, conv, saves))
+                            .map(|d| d.into_output(ctx, conv, saves, aliases))
                             .collect()
                     })
                     .collect(),
@@ -488,63 +1022,93 @@ impl CachedData {
     }
 }
 
-impl CacheableWithState<CachedSimState> for CachedSim {
-    type Output = Vec<CachedData>;
-    type Error = Arc<Error>;
-
-    fn generate_with_state(
-        &self,
-        state: CachedSimState,
-    ) -> std::result::Result<Self::Output, Self::Error> {
-        let inner = || -> Result<Self::Output> {
-            let CachedSimState {
-                input,
-                netlist,
-                output_path,
-                log,
-                run_script,
-                work_dir,
-                executor,
-                override_flags,
-            } = state;
-            write_run_script(
-                RunScriptContext {
-                    netlist: &netlist,
-                    raw_output_path: &output_path,
-                    log_path: &log,
-                    bashrc: None,
-                    format: "psfbin",
-                    flags: override_flags.as_deref().unwrap_or("++aps +mt"),
-                },
-                &run_script,
-            )?;
+impl CachedSimState {
+    /// Runs the simulation and parses its outputs.
+    fn run(self) -> Result<Vec<CachedData>> {
+        let CachedSimState {
+            input,
+            executable,
+            netlist,
+            output_path,
+            log,
+            run_script,
+            work_dir,
+            executor,
+            override_flags,
+            recover,
+            format,
+            naming,
+            progress,
+            job,
+            license_queue,
+            op_save,
+            intern_names,
+            decimation,
+        } = self;
+        let stop_path = work_dir.join("spectre.stop");
+        let status_path = work_dir.join(report::STATUS_FILE);
+        let flags = run_flags(override_flags.as_deref(), recover, license_queue.as_ref());
+        let design = job.design.as_deref().map(shell_quote);
+        let tag = job.tag.as_deref().map(shell_quote);
+        write_run_script(
+            RunScriptContext {
+                executable: &executable,
+                netlist: &netlist,
+                raw_output_path: &output_path,
+                log_path: &log,
+                bashrc: None,
+                format: &format.to_string(),
+                flags: &flags,
+                stop_path: progress.as_ref().map(|_| &stop_path),
+                status_path: &status_path,
+                design: design.as_deref(),
+                tag: tag.as_deref(),
+            },
+            &run_script,
+        )?;
 
-            let mut perms = std::fs::metadata(&run_script)?.permissions();
-            #[cfg(any(unix, target_os = "redox"))]
-            perms.set_mode(0o744);
-            std::fs::set_permissions(&run_script, perms)?;
-
-            let mut command = std::process::Command::new("/bin/bash");
-            command
-                .arg(&run_script)
-                .current_dir(&work_dir)
-                .stdin(Stdio::null());
-            executor
-                .execute(command, Default::default())
-                .map_err(|_| Error::SpectreError)?;
-
-            let mut raw_outputs = Vec::with_capacity(input.len());
-
-            for (i, input) in input.iter().enumerate() {
-                raw_outputs.push(parse_analysis(
-                    &output_path,
-                    &subanalysis_name("analysis", i),
-                    input,
-                )?);
+        let command = script_command(&run_script, &work_dir);
+        tracing::info!(?run_script, "running Spectre");
+        let status = if let Some(callback) = progress {
+            // Remove any log left over from a previous run so that stale
+            // progress is not reported.
+            let _ = std::fs::remove_file(&log);
+            let (status, stopped) = progress::with_progress(&log, &stop_path, &callback, || {
+                executor.execute(command, Default::default())
+            });
+            if stopped {
+                return Err(Error::Stopped);
             }
-            Ok(raw_outputs)
+            status
+        } else {
+            executor.execute(command, Default::default())
+        };
+        status.map_err(|_| Error::SpectreError)?;
+
+        let mut raw_outputs = Vec::with_capacity(input.len());
+
+        let reader = PsfReader {
+            output_dir: &output_path,
+            naming: &naming,
+            executor: &*executor,
+            intern_names,
+            decimation,
         };
-        inner().map_err(Arc::new)
+        for (i, input) in input.iter().enumerate() {
+            let _guard = span!(Level::INFO, "parsing Spectre analysis", analysis = i).entered();
+            raw_outputs.push(parse_analysis(
+                &reader,
+                "",
+                &subanalysis_name("analysis", i),
+                input,
+            )?);
+        }
+        if op_save {
+            raw_outputs.push(CachedData::Nodeset(std::fs::read_to_string(
+                work_dir.join(OP_SAVE_FILE),
+            )?));
+        }
+        Ok(raw_outputs)
     }
 }
 
@@ -562,19 +1126,129 @@ impl ConvertibleNetlister<Spectre> for Spectre {
     }
 }
 
+/// Replaces a Substrate node path referenced by an analysis with the netlisted name of the node.
+pub(crate) fn resolve_node(
+    lib: &RawLib<Spectre>,
+    conv: &NetlistLibConversion,
+    node: &mut NodeRef,
+) -> Result<()> {
+    if let NodeRef::Path(path) = node {
+        let scir =
+            scir_node_path(lib, path).ok_or_else(|| Error::UnresolvedPath(format!("{path:?}")))?;
+        *node = NodeRef::Raw(Spectre::node_voltage_path(&lib.scir, conv, &scir).into());
+    }
+    Ok(())
+}
+
+/// Replaces a Substrate instance path referenced by an analysis with the netlisted name
+/// of the instance.
+pub(crate) fn resolve_instance(
+    lib: &RawLib<Spectre>,
+    conv: &NetlistLibConversion,
+    instance: &mut InstanceRef,
+) -> Result<()> {
+    if let InstanceRef::Path(path) = instance {
+        let scir = lib
+            .convert_instance_path(path)
+            .ok_or_else(|| Error::UnresolvedPath(format!("{path:?}")))?;
+        *instance = InstanceRef::Raw(Spectre::instance_path(&lib.scir, conv, &scir).into());
+    }
+    Ok(())
+}
+
+/// Returns the netlisted name of a node that has been [resolved](resolve_node).
+pub(crate) fn raw_node(node: &NodeRef) -> Result<&ArcStr> {
+    match node {
+        NodeRef::Raw(name) => Ok(name),
+        NodeRef::Path(path) => Err(Error::UnresolvedPath(format!("{path:?}"))),
+    }
+}
+
+/// Returns the netlisted name of an instance that has been [resolved](resolve_instance).
+pub(crate) fn raw_instance(instance: &InstanceRef) -> Result<&ArcStr> {
+    match instance {
+        InstanceRef::Raw(name) => Ok(name),
+        InstanceRef::Path(path) => Err(Error::UnresolvedPath(format!("{path:?}"))),
+    }
+}
+
+/// Returns the flags used to invoke Spectre.
+fn run_flags(
+    override_flags: Option<&str>,
+    recover: bool,
+    license_queue: Option<&LicenseQueue>,
+) -> String {
+    let mut flags = override_flags.unwrap_or("++aps +mt").to_string();
+    if recover {
+        flags.push_str(" +recover");
+    }
+    if let Some(license_queue) = license_queue {
+        flags.push_str(&license_queue.flags());
+    }
+    flags
+}
+
+/// The environment variable used to specify the path to the Spectre executable.
+pub const SPECTRE_PATH_ENV: &str = "SUBSTRATE_SPECTRE_PATH";
+
+/// The first version of Spectre that can write [`OutputFormat::PsfXl`] output.
+const PSFXL_MIN_VERSION: [u32; 2] = [15, 1];
+
 impl Spectre {
-    fn simulate(
+    /// Locates the Spectre executable and determines its version.
+    ///
+    /// See [`find_executable`] for the locations that are searched.
+    /// The version can be used to selectively enable features that require newer versions of Spectre.
+    pub fn probe() -> Result<ToolInfo> {
+        Ok(probe_tool(
+            "spectre",
+            SPECTRE_PATH_ENV,
+            &["-version"],
+            "version ",
+        )?)
+    }
+
+    /// Returns the error preventing the Spectre executable from being located, if any.
+    fn discovery_error() -> Option<Error> {
+        find_executable("spectre", SPECTRE_PATH_ENV)
+            .err()
+            .map(Error::Discovery)
+    }
+
+    /// Returns the command used to invoke Spectre in run scripts.
+    ///
+    /// Checks that the installed version of Spectre supports the given output format.
+    /// If Spectre cannot be located, it is invoked by name.
+    pub(crate) fn executable(format: OutputFormat) -> Result<String> {
+        let Some(path) = find_optional_executable("spectre", SPECTRE_PATH_ENV)? else {
+            return Ok("spectre".to_string());
+        };
+        if format == OutputFormat::PsfXl {
+            probe_executable(path.clone(), &["-version"], "version ")?.require_version(
+                "Spectre",
+                "PSF-XL output",
+                &Version::new(PSFXL_MIN_VERSION),
+            )?;
+        }
+        Ok(shell_quote(&path.to_string_lossy()))
+    }
+
+    /// Writes the simulation netlist to the working directory.
+    ///
+    /// Returns the netlist conversion metadata, the path to the netlist, the contents of the
+    /// netlist, and the netlist contents that should be used as the simulation cache key.
+    fn write_netlist(
         &self,
         ctx: &SimulationContext<Self>,
-        options: Options,
-        input: Vec<Input>,
-    ) -> Result<Vec<Output>> {
+        options: &Options,
+        input: &[Input],
+    ) -> Result<(NetlistLibConversion, PathBuf, Vec<u8>, Vec<u8>)> {
         std::fs::create_dir_all(&ctx.work_dir)?;
         let netlist = ctx.work_dir.join("netlist.scs");
         let mut f = std::fs::File::create(&netlist)?;
         let mut w = Vec::new();
 
-        let mut includes = options.includes.into_iter().collect::<Vec<_>>();
+        let mut includes = options.includes.iter().cloned().collect::<Vec<_>>();
         let mut saves = options.saves.keys().cloned().collect::<Vec<_>>();
         let mut ics = options
             .ics
@@ -598,11 +1272,27 @@ impl Spectre {
         )?;
 
         writeln!(w)?;
+        let mut managed_includes = options.managed_includes.iter().collect::<Vec<_>>();
+        managed_includes.sort();
+        for (i, include) in managed_includes.into_iter().enumerate() {
+            let path = include.resolve(&ctx.work_dir, i)?;
+            include.netlist(&mut w, &path)?;
+        }
         if let Some(temp) = options.temp {
             writeln!(w, "settemp1 options temp={}", temp)?;
         }
-        for save in saves {
-            writeln!(w, "save {}", save.to_string(&ctx.lib.scir, &conv))?;
+        let saves = saves
+            .into_iter()
+            .map(|save| save.to_string(&ctx.lib.scir, &conv))
+            .collect::<Vec<_>>();
+        if options.compress_saves {
+            for group in group_saves(&saves) {
+                writeln!(w, "save {}", group.join(" "))?;
+            }
+        } else {
+            for save in saves {
+                writeln!(w, "save {}", save)?;
+            }
         }
         if let Some(save) = options.save {
             writeln!(w, "setsave1 options save={}", save)?;
@@ -610,55 +1300,152 @@ impl Spectre {
         for (k, v) in ics {
             writeln!(w, "ic {}={}", k.to_string(&ctx.lib.scir, &conv), v)?;
         }
+        let op_restore = options.op_restore.as_ref().map(std::fs::read).transpose()?;
+        if op_restore.is_some() || options.op_save.is_some() {
+            write!(w, "{OP_SNAPSHOT} dc")?;
+            if let Some(nodesets) = &op_restore {
+                std::fs::write(ctx.work_dir.join(OP_RESTORE_FILE), nodesets)?;
+                write!(w, " readns=\"{OP_RESTORE_FILE}\"")?;
+            }
+            if options.op_save.is_some() {
+                write!(w, " write=\"{OP_SAVE_FILE}\"")?;
+            }
+            writeln!(w)?;
+        }
 
         writeln!(w)?;
+        // Checkpointing parameters are excluded from the cache key so that
+        // resumed runs share a cache entry with the original run.
+        let mut contents = w.clone();
         for (i, an) in input.iter().enumerate() {
-            an.netlist(&mut w, &subanalysis_name("analysis", i))?;
+            let _guard = span!(Level::INFO, "netlisting Spectre analysis", analysis = i).entered();
+            // Instances referenced by analyses can only be named once the library is netlisted,
+            // and simulation-wide options are applied to a copy of each analysis.
+            let mut an = an.clone();
+            an.resolve(&ctx.lib, &conv, options)?;
+            let name = subanalysis_name("analysis", i);
+            an.netlist(&mut w, &name, None)?;
             writeln!(w)?;
+            an.netlist(&mut contents, &name, options.checkpoint.as_ref())?;
+            writeln!(contents)?;
+        }
+        f.write_all(&contents)?;
+        // Restored nodesets are read from a separate file, so they are added to the cache key.
+        if let Some(nodesets) = op_restore {
+            w.extend(nodesets);
+        }
+        // Decimation is applied before outputs are cached, so it is part of the cache key.
+        if let Some(decimation) = &options.decimation {
+            write!(w, "{decimation:?}")?;
         }
-        f.write_all(&w)?;
 
-        let output_path = ctx.work_dir.join("psf");
-        let log = ctx.work_dir.join("spectre.log");
-        let run_script = ctx.work_dir.join("simulate.sh");
-        let work_dir = ctx.work_dir.clone();
-        let executor = ctx.ctx.executor.clone();
+        Ok((conv, netlist, contents, w))
+    }
 
-        let raw_outputs = ctx
-            .ctx
-            .cache
-            .get_with_state(
-                "spectre.simulation.outputs",
-                CachedSim {
-                    simulation_netlist: w,
-                },
-                CachedSimState {
-                    input,
-                    netlist,
-                    output_path,
-                    log,
-                    run_script,
-                    work_dir,
-                    executor,
-                    override_flags: options.override_flags.clone(),
-                },
-            )
-            .try_inner()
-            .map_err(|e| match e {
-                TryInnerError::CacheError(e) => Error::Caching(e),
-                TryInnerError::GeneratorError(e) => Error::Generator(e.clone()),
+    fn simulate(
+        &self,
+        ctx: &SimulationContext<Self>,
+        options: Options,
+        input: Vec<Input>,
+    ) -> Result<Vec<Output>> {
+        let (conv, netlist, contents, w) = self.write_netlist(ctx, &options, &input)?;
+        let executable = Spectre::executable(options.format)?;
+
+        if options.check_syntax {
+            check::check_netlist(
+                &executable,
+                &ctx.lib.scir,
+                &conv,
+                &netlist,
+                &String::from_utf8_lossy(&contents),
+                &ctx.work_dir,
+                &*ctx.ctx.executor,
+            )?;
+        }
+
+        let output_path = options.raw_output_dir(&ctx.work_dir);
+        // Cache hits do not run Spectre, so the report of an earlier run must not be left behind.
+        report::RunReport::clear(&ctx.work_dir)?;
+        let log = ctx.work_dir.join(report::LOG_FILE);
+        let run_script = ctx.work_dir.join("simulate.sh");
+        let work_dir = ctx.work_dir.clone();
+        let executor = ctx.ctx.executor.clone();
+
+        let state = CachedSimState {
+            input,
+            executable,
+            netlist,
+            output_path,
+            log: log.clone(),
+            run_script,
+            work_dir,
+            executor,
+            override_flags: options.override_flags.clone(),
+            recover: options.recover,
+            format: options.format,
+            naming: options.naming.clone(),
+            progress: ctx.progress.clone(),
+            job: options.job.clone(),
+            license_queue: options.license_queue,
+            op_save: options.op_save.is_some(),
+            intern_names: options.compress_saves,
+            decimation: options.decimation,
+        };
+        let mut raw_outputs =
+            run_cached(&ctx.ctx.cache, "spectre.simulation.outputs", w, move || {
+                state.run()
+            })
+            .try_inner()
+            .map_err(|e| match e {
+                TryInnerError::CacheError(e) => Error::Caching(e),
+                TryInnerError::GeneratorError(e) => match &**e {
+                    Error::SpectreError => log::simulation_error(&ctx.lib, &conv, &log)
+                        .or_else(Spectre::discovery_error)
+                        .unwrap_or_else(|| Error::Generator(e.clone())),
+                    // Stopped simulations are not cached, so stopping one only affects this call.
+                    Error::Stopped => Error::Stopped,
+                    _ => Error::Generator(e.clone()),
+                },
             })?
             .clone();
+        if let Some(path) = &options.op_save {
+            let Some(CachedData::Nodeset(nodesets)) = raw_outputs.pop() else {
+                unreachable!("saved operating point must follow the analysis outputs");
+            };
+            std::fs::write(path, nodesets)?;
+        }
 
+        let aliases = Arc::new(Spectre::node_aliases(&ctx.lib.scir, &conv, &options.saves));
         let conv = Arc::new(conv);
         let outputs = raw_outputs
             .into_iter()
-            .map(|raw_values| raw_values.into_output(ctx, &conv, &options.saves))
+            .map(|raw_values| raw_values.into_output(ctx, &conv, &options.saves, &aliases))
             .collect();
 
         Ok(outputs)
     }
 
+    /// Returns the names of merged nodes under which saved voltages can also be looked up.
+    ///
+    /// All nodes [merged](scir::LibraryBuilder::merged_paths) with each saved node are
+    /// recorded as aliases of the saved node.
+    fn node_aliases(
+        lib: &Library<Spectre>,
+        conv: &NetlistLibConversion,
+        saves: &SaveKeys<SimSignal>,
+    ) -> NodeAliases {
+        let mut aliases = NodeAliases::new();
+        for (save, _) in saves {
+            if let SimSignal::ScirVoltage(path) = save {
+                let name = ArcStr::from(Spectre::node_voltage_path(lib, conv, path));
+                for merged in lib.merged_paths(path.clone()) {
+                    aliases.insert(Spectre::node_voltage_path(lib, conv, &merged), name.clone());
+                }
+            }
+        }
+        aliases
+    }
+
     /// Escapes the given identifier to be Spectre-compatible.
     pub fn escape_identifier(node_name: &str) -> String {
         // The name "0" is reserved, as it represents global ground.
@@ -739,6 +1526,45 @@ impl Spectre {
 
 impl scir::schema::Schema for Spectre {
     type Primitive = Primitive;
+
+    fn multiply_primitive(primitive: &Primitive, m: u64) -> Option<Primitive> {
+        match primitive {
+            Primitive::RawInstance {
+                cell,
+                ports,
+                params,
+            } => {
+                let mut params = params.clone();
+                match params
+                    .iter_mut()
+                    .find(|(key, _)| key.eq_ignore_ascii_case("m"))
+                {
+                    Some((_, ParamValue::Numeric(prev))) => *prev *= Decimal::from(m),
+                    Some((_, ParamValue::String(_))) => return None,
+                    None => params.push((arcstr::literal!("m"), ParamValue::Numeric(m.into()))),
+                }
+                Some(Primitive::RawInstance {
+                    cell: cell.clone(),
+                    ports: ports.clone(),
+                    params,
+                })
+            }
+            Primitive::Spice(p) => p.multiplied(m).map(Primitive::Spice),
+            Primitive::IbisInstance { .. }
+            | Primitive::SpfInstance { .. }
+            | Primitive::BlackboxInstance { .. } => None,
+        }
+    }
+
+    fn supports_instance_params(primitive: &Primitive) -> bool {
+        match primitive {
+            Primitive::RawInstance { .. } => true,
+            Primitive::Spice(p) => p.accepts_params(),
+            Primitive::IbisInstance { .. }
+            | Primitive::SpfInstance { .. }
+            | Primitive::BlackboxInstance { .. } => false,
+        }
+    }
 }
 
 impl FromSchema<NoSchema> for Spectre {
@@ -809,6 +1635,67 @@ impl Simulator for Spectre {
     }
 }
 
+impl SupportsModels for Spectre {
+    fn device_models(lib: &Library<Spectre>) -> HashSet<ArcStr> {
+        lib.primitives()
+            .filter_map(|(_, primitive)| match primitive {
+                Primitive::RawInstance { cell, .. } => Some(cell),
+                Primitive::Spice(primitive) => primitive.model(),
+                Primitive::IbisInstance { .. }
+                | Primitive::SpfInstance { .. }
+                | Primitive::BlackboxInstance { .. } => None,
+            })
+            .cloned()
+            .collect()
+    }
+
+    fn include_model(opts: &mut Options, include: ModelInclude) {
+        match include.section {
+            Some(section) => opts.include_section(include.path, section),
+            None => opts.include(include.path),
+        }
+    }
+}
+
+impl DryRun for Spectre {
+    fn dry_run_inputs(
+        &self,
+        ctx: &SimulationContext<Self>,
+        options: Self::Options,
+        input: Vec<Self::Input>,
+    ) -> Result<SimArtifacts> {
+        let (_, netlist, _, _) = self.write_netlist(ctx, &options, &input)?;
+        let run_script = ctx.work_dir.join("simulate.sh");
+        let design = options.job.design.as_deref().map(shell_quote);
+        let tag = options.job.tag.as_deref().map(shell_quote);
+        write_run_script(
+            RunScriptContext {
+                executable: &Spectre::executable(options.format)?,
+                netlist: &netlist,
+                raw_output_path: &options.raw_output_dir(&ctx.work_dir),
+                log_path: &ctx.work_dir.join("spectre.log"),
+                bashrc: None,
+                format: &options.format.to_string(),
+                flags: &run_flags(
+                    options.override_flags.as_deref(),
+                    options.recover,
+                    options.license_queue.as_ref(),
+                ),
+                stop_path: None,
+                status_path: &ctx.work_dir.join(report::STATUS_FILE),
+                design: design.as_deref(),
+                tag: tag.as_deref(),
+            },
+            &run_script,
+        )?;
+
+        Ok(SimArtifacts {
+            netlist,
+            run_script,
+        })
+    }
+}
+
 /// Inputs directly supported by Spectre.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Input {
@@ -818,8 +1705,28 @@ pub enum Input {
     Ac(Ac),
     /// A DC operating point input.
     DcOp(DcOp),
+    /// An `info` statement input.
+    Info(Info),
+    /// A sensitivity analysis input.
+    Sens(Sens),
+    /// A stability analysis input.
+    Stb(Stb),
+    /// A periodic steady-state analysis input.
+    Pss(Pss),
+    /// A periodic AC analysis input.
+    Pac(Pac),
+    /// A periodic noise analysis input.
+    Pnoise(Pnoise),
+    /// A noise analysis input.
+    Noise(Noise),
     /// A Monte Carlo input.
     MonteCarlo(MonteCarlo<Vec<Input>>),
+    /// An alter group input.
+    Alter(Alter<Vec<Input>>),
+    /// A reliability (aging) input.
+    Reliability(Reliability<Vec<Input>>),
+    /// A parametric sweep input.
+    ParamSweep(ParamSweep<Vec<Input>>),
 }
 
 impl From<Tran> for Input {
@@ -840,12 +1747,72 @@ impl From<DcOp> for Input {
     }
 }
 
+impl From<Info> for Input {
+    fn from(value: Info) -> Self {
+        Self::Info(value)
+    }
+}
+
+impl From<Sens> for Input {
+    fn from(value: Sens) -> Self {
+        Self::Sens(value)
+    }
+}
+
+impl From<Stb> for Input {
+    fn from(value: Stb) -> Self {
+        Self::Stb(value)
+    }
+}
+
+impl From<Pss> for Input {
+    fn from(value: Pss) -> Self {
+        Self::Pss(value)
+    }
+}
+
+impl From<Pac> for Input {
+    fn from(value: Pac) -> Self {
+        Self::Pac(value)
+    }
+}
+
+impl From<Pnoise> for Input {
+    fn from(value: Pnoise) -> Self {
+        Self::Pnoise(value)
+    }
+}
+
+impl From<Noise> for Input {
+    fn from(value: Noise) -> Self {
+        Self::Noise(value)
+    }
+}
+
 impl<A: SupportedBy<Spectre>> From<MonteCarlo<A>> for Input {
     fn from(value: MonteCarlo<A>) -> Self {
         Self::MonteCarlo(value.into())
     }
 }
 
+impl<A: SupportedBy<Spectre>> From<Alter<A>> for Input {
+    fn from(value: Alter<A>) -> Self {
+        Self::Alter(value.into())
+    }
+}
+
+impl<A: SupportedBy<Spectre>> From<ParamSweep<A>> for Input {
+    fn from(value: ParamSweep<A>) -> Self {
+        Self::ParamSweep(value.into())
+    }
+}
+
+impl<A: SupportedBy<Spectre>> From<Reliability<A>> for Input {
+    fn from(value: Reliability<A>) -> Self {
+        Self::Reliability(value.into())
+    }
+}
+
 /// Outputs directly produced by Spectre.
 #[derive(Debug, Clone)]
 pub enum Output {
@@ -855,8 +1822,28 @@ pub enum Output {
     Ac(ac::Output),
     /// DC operating point simulation output.
     DcOp(analysis::dc::OpOutput),
+    /// `info` statement output.
+    Info(info::Output),
+    /// Sensitivity analysis output.
+    Sens(sens::Output),
+    /// Stability analysis output.
+    Stb(stb::Output),
+    /// Periodic steady-state analysis output.
+    Pss(pss::Output),
+    /// Periodic AC analysis output.
+    Pac(pac::Output),
+    /// Periodic noise analysis output.
+    Pnoise(pnoise::Output),
+    /// Noise analysis output.
+    Noise(noise::Output),
     /// Monte Carlo simulation output.
     MonteCarlo(montecarlo::Output<Vec<Output>>),
+    /// Alter group simulation output.
+    Alter(alter::Output<Vec<Output>>),
+    /// Reliability simulation output.
+    Reliability(reliability::Output<Vec<Output>>),
+    /// Parametric sweep simulation output.
+    ParamSweep(sweep::Output<Vec<Output>>),
 }
 
 impl From<tran::Output> for Output {
@@ -907,6 +1894,118 @@ impl TryFrom<Output> for analysis::dc::OpOutput {
     }
 }
 
+impl From<info::Output> for Output {
+    fn from(value: info::Output) -> Self {
+        Self::Info(value)
+    }
+}
+
+impl TryFrom<Output> for info::Output {
+    type Error = Error;
+    fn try_from(value: Output) -> Result<Self> {
+        match value {
+            Output::Info(info) => Ok(info),
+            _ => Err(Error::SpectreError),
+        }
+    }
+}
+
+impl From<sens::Output> for Output {
+    fn from(value: sens::Output) -> Self {
+        Self::Sens(value)
+    }
+}
+
+impl TryFrom<Output> for sens::Output {
+    type Error = Error;
+    fn try_from(value: Output) -> Result<Self> {
+        match value {
+            Output::Sens(sens) => Ok(sens),
+            _ => Err(Error::SpectreError),
+        }
+    }
+}
+
+impl From<stb::Output> for Output {
+    fn from(value: stb::Output) -> Self {
+        Self::Stb(value)
+    }
+}
+
+impl TryFrom<Output> for stb::Output {
+    type Error = Error;
+    fn try_from(value: Output) -> Result<Self> {
+        match value {
+            Output::Stb(stb) => Ok(stb),
+            _ => Err(Error::SpectreError),
+        }
+    }
+}
+
+impl From<pss::Output> for Output {
+    fn from(value: pss::Output) -> Self {
+        Self::Pss(value)
+    }
+}
+
+impl TryFrom<Output> for pss::Output {
+    type Error = Error;
+    fn try_from(value: Output) -> Result<Self> {
+        match value {
+            Output::Pss(pss) => Ok(pss),
+            _ => Err(Error::SpectreError),
+        }
+    }
+}
+
+impl From<pac::Output> for Output {
+    fn from(value: pac::Output) -> Self {
+        Self::Pac(value)
+    }
+}
+
+impl TryFrom<Output> for pac::Output {
+    type Error = Error;
+    fn try_from(value: Output) -> Result<Self> {
+        match value {
+            Output::Pac(pac) => Ok(pac),
+            _ => Err(Error::SpectreError),
+        }
+    }
+}
+
+impl From<pnoise::Output> for Output {
+    fn from(value: pnoise::Output) -> Self {
+        Self::Pnoise(value)
+    }
+}
+
+impl TryFrom<Output> for pnoise::Output {
+    type Error = Error;
+    fn try_from(value: Output) -> Result<Self> {
+        match value {
+            Output::Pnoise(pnoise) => Ok(pnoise),
+            _ => Err(Error::SpectreError),
+        }
+    }
+}
+
+impl From<noise::Output> for Output {
+    fn from(value: noise::Output) -> Self {
+        Self::Noise(value)
+    }
+}
+
+impl TryFrom<Output> for noise::Output {
+    type Error = Error;
+    fn try_from(value: Output) -> Result<Self> {
+        match value {
+            Output::Noise(noise) => Ok(noise),
+            _ => Err(Error::SpectreError),
+        }
+    }
+}
+
 impl From<montecarlo::Output<Vec<Output>>> for Output {
     fn from(value: montecarlo::Output<Vec<Output>>) -> Self {
         Self::MonteCarlo(value)
@@ -923,45 +2022,174 @@ impl TryFrom<Output> for montecarlo::Output<Vec<Output>> {
     }
 }
 
+impl From<alter::Output<Vec<Output>>> for Output {
+    fn from(value: alter::Output<Vec<Output>>) -> Self {
+        Self::Alter(value)
+    }
+}
+
+impl TryFrom<Output> for alter::Output<Vec<Output>> {
+    type Error = Error;
+    fn try_from(value: Output) -> Result<Self> {
+        match value {
+            Output::Alter(alter) => Ok(alter),
+            _ => Err(Error::SpectreError),
+        }
+    }
+}
+
+impl From<reliability::Output<Vec<Output>>> for Output {
+    fn from(value: reliability::Output<Vec<Output>>) -> Self {
+        Self::Reliability(value)
+    }
+}
+
+impl TryFrom<Output> for reliability::Output<Vec<Output>> {
+    type Error = Error;
+    fn try_from(value: Output) -> Result<Self> {
+        match value {
+            Output::Reliability(rel) => Ok(rel),
+            _ => Err(Error::SpectreError),
+        }
+    }
+}
+
+impl From<sweep::Output<Vec<Output>>> for Output {
+    fn from(value: sweep::Output<Vec<Output>>) -> Self {
+        Self::ParamSweep(value)
+    }
+}
+
+impl TryFrom<Output> for sweep::Output<Vec<Output>> {
+    type Error = Error;
+    fn try_from(value: Output) -> Result<Self> {
+        match value {
+            Output::ParamSweep(sweep) => Ok(sweep),
+            _ => Err(Error::SpectreError),
+        }
+    }
+}
+
 impl Input {
-    fn netlist<W: Write>(&self, out: &mut W, name: &str) -> Result<()> {
+    /// Resolves SCIR and Substrate paths referenced by this input to raw (netlisted) names
+    /// and applies simulation-wide analysis options.
+    fn resolve(
+        &mut self,
+        lib: &RawLib<Spectre>,
+        conv: &NetlistLibConversion,
+        options: &Options,
+    ) -> Result<()> {
+        let inner = match self {
+            Self::Stb(stb) => return stb.resolve(lib, conv),
+            Self::Noise(noise) => return noise.resolve(lib, conv),
+            Self::Sens(sens) => return sens.resolve(lib, conv),
+            Self::Pss(pss) => return pss.resolve(lib, conv),
+            Self::Pnoise(pnoise) => return pnoise.resolve(lib, conv),
+            Self::Tran(tran) => {
+                if let (None, Some(noise)) = (tran.noise_fmax, &options.transient_noise) {
+                    tran.apply_noise(noise);
+                }
+                return Ok(());
+            }
+            Self::MonteCarlo(mc) => &mut mc.analysis,
+            Self::Alter(alter) => &mut alter.analysis,
+            Self::Reliability(rel) => &mut rel.analysis,
+            Self::ParamSweep(sweep) => &mut sweep.analysis,
+            Self::Ac(_) | Self::DcOp(_) | Self::Info(_) | Self::Pac(_) => return Ok(()),
+        };
+        for an in inner {
+            an.resolve(lib, conv, options)?;
+        }
+        Ok(())
+    }
+
+    fn netlist<W: Write>(
+        &self,
+        out: &mut W,
+        name: &str,
+        checkpoint: Option<&Checkpoint>,
+    ) -> Result<()> {
+        // Alter groups are top-level statements followed by their analyses,
+        // so they are not prefixed by the analysis name.
+        if let Self::Alter(alter) = self {
+            return alter.netlist(out, name, checkpoint);
+        }
+        // Sensitivities are computed by `sens` statements that refer to a separately
+        // named analysis.
+        if let Self::Sens(sens) = self {
+            return sens.netlist(out, name);
+        }
         write!(out, "{name} ")?;
         match self {
-            Self::Tran(t) => t.netlist(out),
+            Self::Tran(t) => {
+                t.netlist(out)?;
+                if let Some(checkpoint) = checkpoint {
+                    checkpoint.netlist(out)?;
+                }
+                Ok(())
+            }
             Input::Ac(ac) => ac.netlist(out),
             Input::DcOp(dcop) => dcop.netlist(out),
-            Self::MonteCarlo(mc) => mc.netlist(out, name),
+            Input::Info(info) => info.netlist(out),
+            Input::Stb(stb) => stb.netlist(out),
+            Input::Pss(pss) => pss.netlist(out),
+            Input::Pac(pac) => pac.netlist(out),
+            Input::Pnoise(pnoise) => pnoise.netlist(out),
+            Input::Noise(noise) => noise.netlist(out),
+            Self::MonteCarlo(mc) => mc.netlist(out, name, checkpoint),
+            Self::Reliability(rel) => rel.netlist(out, name, checkpoint),
+            Self::ParamSweep(sweep) => sweep.netlist(out, name, checkpoint),
+            Self::Alter(_) | Self::Sens(_) => unreachable!(),
         }
     }
 }
 
 impl Tran {
     fn netlist<W: Write>(&self, out: &mut W) -> Result<()> {
-        write!(out, "tran stop={}", self.stop)?;
-        if let Some(ref start) = self.start {
-            write!(out, " start={start}")?;
+        write!(out, "tran stop={}", self.stop.value())?;
+        if let Some(start) = self.start {
+            write!(out, " start={}", start.value())?;
         }
         if let Some(errpreset) = self.errpreset {
             write!(out, " errpreset={errpreset}")?;
         }
+        if let Some(max_step) = self.max_step {
+            write!(out, " maxstep={}", max_step.value())?;
+        }
         if let Some(noisefmax) = self.noise_fmax {
             write!(out, " noisefmax={noisefmax}")?;
         }
         if let Some(noisefmin) = self.noise_fmin {
             write!(out, " noisefmin={noisefmin}")?;
         }
+        if let Some(noiseseed) = self.noise_seed {
+            write!(out, " noiseseed={noiseseed}")?;
+        }
+        if let Some(noisescale) = self.noise_scale {
+            write!(out, " noisescale={noisescale}")?;
+        }
+        if let Some(strobeperiod) = self.strobe_period {
+            write!(out, " strobeperiod={}", strobeperiod.value())?;
+        }
+        if let Some(strobedelay) = self.strobe_delay {
+            write!(out, " strobedelay={}", strobedelay.value())?;
+        }
+        if let Some(outputstart) = self.output_start {
+            write!(out, " outputstart={}", outputstart.value())?;
+        }
         Ok(())
     }
 }
 
 impl Ac {
     fn netlist<W: Write>(&self, out: &mut W) -> Result<()> {
-        write!(out, "ac start={} stop={}", self.start, self.stop)?;
-        match self.sweep {
-            Sweep::Linear(pts) => write!(out, " lin={pts}")?,
-            Sweep::Logarithmic(pts) => write!(out, " log={pts}")?,
-            Sweep::Decade(pts) => write!(out, " dec={pts}")?,
-        };
+        write!(
+            out,
+            "ac start={} stop={}",
+            self.start.value(),
+            self.stop.value()
+        )?;
+        self.sweep.netlist(out)?;
         Ok(())
     }
 }
@@ -977,61 +2205,361 @@ fn subanalysis_name(prefix: &str, idx: usize) -> String {
     format!("{prefix}_{idx}")
 }
 
-fn parse_analysis(output_dir: &Path, name: &str, analysis: &Input) -> Result<CachedData> {
+fn alter_group_name(prefix: &str, idx: usize) -> String {
+    format!("{prefix}_alter_{idx}")
+}
+
+fn reliability_phase_name(prefix: &str, aged: bool) -> String {
+    if aged {
+        format!("{prefix}_aged")
+    } else {
+        format!("{prefix}_fresh")
+    }
+}
+
+/// Groups saved signal names by the instance that contains them.
+///
+/// Groups are ordered by instance path, and names within a group are sorted.
+/// Names are not shortened, since Spectre resolves each saved name from the top level.
+fn group_saves(saves: &[ArcStr]) -> Vec<Vec<&str>> {
+    let mut groups = std::collections::BTreeMap::<&str, Vec<&str>>::new();
+    for save in saves {
+        let parent = save.rsplit_once(SEPARATOR).map_or("", |(parent, _)| parent);
+        groups.entry(parent).or_default().push(save);
+    }
+    groups
+        .into_values()
+        .map(|mut group| {
+            group.sort();
+            group
+        })
+        .collect()
+}
+
+/// Reads raw PSF outputs from a Spectre output directory.
+struct PsfReader<'a> {
+    output_dir: &'a Path,
+    naming: &'a OutputNaming,
+    executor: &'a dyn Executor,
+    /// Whether to intern the names of signals in transient and AC outputs.
+    intern_names: bool,
+    /// Decimation applied to transient outputs.
+    decimation: Option<Decimation>,
+}
+
+/// A parsed PSF file in either of the formats supported by `psfparser`.
+enum Psf {
+    Ascii(String),
+    Binary(Vec<u8>),
+}
+
+/// The magic bytes at the start of an ASCII PSF file.
+const PSF_ASCII_MAGIC: &[u8] = b"HEADER";
+/// The signature near the end of a binary PSF file.
+///
+/// The signature is followed only by the 4-byte size of the file's data sections.
+const PSF_BINARY_SIGNATURE: &[u8] = b"Clarissa";
+
+/// Returns `true` if `bytes` is a binary PSF file.
+fn is_binary_psf(bytes: &[u8]) -> bool {
+    bytes.len() >= PSF_BINARY_SIGNATURE.len() + 4
+        && bytes[..bytes.len() - 4].ends_with(PSF_BINARY_SIGNATURE)
+}
+
+impl PsfReader<'_> {
+    /// Returns the name of the file containing outputs of the given kind for the analysis
+    /// named `name`.
+    fn locate(&self, name: &str, kind: OutputKind) -> Result<String> {
+        self.naming.locate(self.output_dir, name, kind)
+    }
+
+    /// Reads the PSF file with the given name, detecting its format.
+    ///
+    /// ASCII and binary PSF are identified by their magic bytes and read directly.
+    /// Any other file is assumed to be PSF-XL, and is converted to ASCII PSF using
+    /// Cadence's `psf` utility.
+    fn read(&self, file_name: &str) -> Result<Psf> {
+        let bytes = std::fs::read(self.output_dir.join(file_name))?;
+        if bytes.starts_with(PSF_ASCII_MAGIC) {
+            return Ok(Psf::Ascii(
+                String::from_utf8(bytes).map_err(|_| Error::Parse)?,
+            ));
+        }
+        if is_binary_psf(&bytes) {
+            return Ok(Psf::Binary(bytes));
+        }
+        Ok(Psf::Ascii(self.convert_to_ascii(file_name)?))
+    }
+
+    /// Reads the PSF file with the given name as ASCII PSF.
+    ///
+    /// Files in any other format are converted to ASCII PSF using Cadence's `psf` utility.
+    fn read_ascii(&self, file_name: &str) -> Result<String> {
+        let bytes = std::fs::read(self.output_dir.join(file_name))?;
+        if bytes.starts_with(PSF_ASCII_MAGIC) {
+            return String::from_utf8(bytes).map_err(|_| Error::Parse);
+        }
+        self.convert_to_ascii(file_name)
+    }
+
+    /// Converts the PSF file with the given name to ASCII PSF using Cadence's `psf` utility.
+    fn convert_to_ascii(&self, file_name: &str) -> Result<String> {
+        let path = self.output_dir.join(file_name);
+        let ascii_path = self.output_dir.join(format!("{file_name}.ascii"));
+        tracing::info!(?path, "converting PSF output to ASCII PSF");
+        let mut command = std::process::Command::new("psf");
+        command
+            .arg("-i")
+            .arg(&path)
+            .arg("-o")
+            .arg(&ascii_path)
+            .stdin(Stdio::null());
+        self.executor
+            .execute(command, Default::default())
+            .map_err(|_| Error::PsfConversion(path))?;
+        Ok(std::fs::read_to_string(ascii_path)?)
+    }
+
+    fn read_tran(&self, file_name: &str) -> Result<HashMap<String, Vec<f64>>> {
+        Ok(match self.read(file_name)? {
+            Psf::Ascii(psf) => {
+                let ast = psfparser::ascii::parse(&psf).map_err(|_| Error::Parse)?;
+                TransientData::from_ascii(&ast).signals
+            }
+            Psf::Binary(psf) => {
+                let ast = psfparser::binary::parse(&psf).map_err(|_| Error::Parse)?;
+                TransientData::from_binary(ast).signals
+            }
+        })
+    }
+
+    fn read_ac(&self, file_name: &str) -> Result<AcData> {
+        Ok(match self.read(file_name)? {
+            Psf::Ascii(psf) => {
+                let ast = psfparser::ascii::parse(&psf).map_err(|_| Error::Parse)?;
+                AcData::from_ascii(&ast)
+            }
+            Psf::Binary(psf) => {
+                let ast = psfparser::binary::parse(&psf).map_err(|_| Error::Parse)?;
+                AcData::from_binary(ast)
+            }
+        })
+    }
+
+    fn read_dc(&self, file_name: &str) -> Result<DcData> {
+        Ok(match self.read(file_name)? {
+            Psf::Ascii(psf) => {
+                let ast = psfparser::ascii::parse(&psf).map_err(|_| Error::Parse)?;
+                DcData::from_ascii(&ast)
+            }
+            Psf::Binary(psf) => {
+                let ast = psfparser::binary::parse(&psf).map_err(|_| Error::Parse)?;
+                DcData::from_binary(ast)
+            }
+        })
+    }
+}
+
+/// Parses the outputs of the analysis with the given name.
+///
+/// `prefix` is prepended to the names of all output files. It is non-empty for analyses
+/// nested within a parametric sweep, as Spectre writes the results of each swept value
+/// with a distinct prefix.
+fn parse_analysis(
+    reader: &PsfReader,
+    prefix: &str,
+    name: &str,
+    analysis: &Input,
+) -> Result<CachedData> {
     Ok(if let Input::MonteCarlo(analysis) = analysis {
+        let iterations = analysis.iterations();
         let mut data = Vec::new();
-        for iter in 1..analysis.numruns + 1 {
+        for iter in iterations.iter().map(|iteration| iteration.run) {
             let mut mc_data = Vec::new();
             for i in 0..analysis.analysis.len() {
                 // FIXME: loops should be swapped
                 let new_name = subanalysis_name(&format!("{}-{:0>3}_{}", name, iter, name), i);
                 mc_data.push(parse_analysis(
-                    output_dir,
+                    reader,
+                    prefix,
                     &new_name,
                     &analysis.analysis[i],
                 )?)
             }
             data.push(mc_data);
         }
-        CachedData::MonteCarlo(data)
-    } else {
-        let file_name = match analysis {
-            Input::Tran(_) => {
-                format!("{name}.tran.tran")
+        CachedData::MonteCarlo { iterations, data }
+    } else if let Input::Alter(analysis) = analysis {
+        let mut data = Vec::new();
+        for g in 0..analysis.groups.len() {
+            let group_name = alter_group_name(name, g);
+            let mut group_data = Vec::new();
+            for (i, an) in analysis.analysis.iter().enumerate() {
+                group_data.push(parse_analysis(
+                    reader,
+                    prefix,
+                    &subanalysis_name(&group_name, i),
+                    an,
+                )?);
             }
-            Input::Ac(_) => format!("{name}.ac"),
-            Input::DcOp(_) => format!("{name}.dc"),
-            Input::MonteCarlo(_) => unreachable!(),
-        };
-        let psf_path = output_dir.join(file_name);
-        let psf = std::fs::read(psf_path)?;
-        let ast = psfparser::binary::parse(&psf).map_err(|_| Error::Parse)?;
-
+            data.push(group_data);
+        }
+        CachedData::Alter(data)
+    } else if let Input::Reliability(analysis) = analysis {
+        let mut phases = [false, true]
+            .map(|aged| {
+                let phase_name = reliability_phase_name(name, aged);
+                analysis
+                    .analysis
+                    .iter()
+                    .enumerate()
+                    .map(|(i, an)| {
+                        parse_analysis(reader, prefix, &subanalysis_name(&phase_name, i), an)
+                    })
+                    .collect::<Result<Vec<_>>>()
+            })
+            .into_iter();
+        CachedData::Reliability {
+            fresh: phases.next().unwrap()?,
+            aged: phases.next().unwrap()?,
+        }
+    } else if let Input::Sens(analysis) = analysis {
+        let files = (0..analysis.outputs.len())
+            .map(|i| {
+                let stem = format!("{prefix}{}", sens::output_name(name, i));
+                reader.locate(&stem, OutputKind::Sens)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        match analysis.analysis {
+            SensAnalysis::Dc => CachedData::DcSens(
+                files
+                    .iter()
+                    .map(|file| match reader.read_dc(file)? {
+                        DcData::Op(op) => Ok(op.signals),
+                        // Sensitivities are computed at a single operating point.
+                        DcData::Sweep(_) => Err(Error::Parse),
+                    })
+                    .collect::<Result<_>>()?,
+            ),
+            SensAnalysis::Ac(_) => {
+                let mut freq = Vec::new();
+                let mut values = Vec::with_capacity(files.len());
+                for file in files {
+                    let data = reader.read_ac(&file)?;
+                    freq = data.freq;
+                    values.push(data.signals);
+                }
+                CachedData::AcSens { freq, values }
+            }
+        }
+    } else if let Input::ParamSweep(analysis) = analysis {
+        let mut data = Vec::with_capacity(analysis.values.len());
+        for iter in 0..analysis.values.len() {
+            // Spectre writes the results of each swept value with a distinct prefix.
+            let sweep_prefix = format!("{prefix}{name}-{iter:0>3}_");
+            let mut sweep_data = Vec::with_capacity(analysis.analysis.len());
+            for (i, an) in analysis.analysis.iter().enumerate() {
+                sweep_data.push(parse_analysis(
+                    reader,
+                    &sweep_prefix,
+                    &subanalysis_name(name, i),
+                    an,
+                )?);
+            }
+            data.push(sweep_data);
+        }
+        CachedData::ParamSweep(data)
+    } else {
+        let stem = format!("{prefix}{name}");
         match analysis {
             Input::Tran(_) => {
-                let values = TransientData::from_binary(ast).signals;
-                CachedData::Tran(values)
+                let mut values = reader.read_tran(&reader.locate(&stem, OutputKind::Tran)?)?;
+                if let Some(decimation) = &reader.decimation {
+                    values = decimate_tran(values, decimation);
+                }
+                if reader.intern_names {
+                    CachedData::InternedTran(values.into_iter().collect())
+                } else {
+                    CachedData::Tran(values)
+                }
             }
             Input::Ac(_) => {
-                let values = AcData::from_binary(ast);
-                CachedData::Ac {
-                    freq: values.freq,
-                    signals: values.signals,
+                let values = reader.read_ac(&reader.locate(&stem, OutputKind::Ac)?)?;
+                if reader.intern_names {
+                    CachedData::InternedAc {
+                        freq: values.freq,
+                        signals: values.signals.into_iter().collect(),
+                    }
+                } else {
+                    CachedData::Ac {
+                        freq: values.freq,
+                        signals: values.signals,
+                    }
                 }
             }
             Input::DcOp(_) => {
-                let values = DcData::from_binary(ast).unwrap_op().signals;
+                let values = reader
+                    .read_dc(&reader.locate(&stem, OutputKind::Dc)?)?
+                    .unwrap_op()
+                    .signals;
                 CachedData::DcOp(values)
             }
-            Input::MonteCarlo(_) => {
-                unreachable!()
+            Input::Info(_) => CachedData::Info(info::parse_info(
+                &reader.read_ascii(&reader.locate(&stem, OutputKind::Info)?)?,
+            )?),
+            Input::Pss(_) => {
+                let freq = reader.read_ac(&reader.locate(&stem, OutputKind::PssFreq)?)?;
+                let mut time_values =
+                    reader.read_tran(&reader.locate(&stem, OutputKind::PssTime)?)?;
+                CachedData::Pss {
+                    harmonics: freq.freq,
+                    freq_values: freq.signals,
+                    time: time_values.remove("time").ok_or(Error::Parse)?,
+                    time_values,
+                }
+            }
+            Input::Pac(pac) => {
+                let data = reader.read_ac(&reader.locate(&stem, OutputKind::Pac)?)?;
+                let (freq, values) = pac::split_sidebands(&pac.sidebands, data)?;
+                CachedData::Pac { freq, values }
+            }
+            Input::Pnoise(_) => {
+                let mut values = reader.read_tran(&reader.locate(&stem, OutputKind::Pnoise)?)?;
+                CachedData::Pnoise {
+                    freq: values.remove("freq").ok_or(Error::Parse)?,
+                    values,
+                }
             }
+            Input::Noise(_) => {
+                let mut values = reader.read_tran(&reader.locate(&stem, OutputKind::Noise)?)?;
+                CachedData::Noise {
+                    freq: values.remove("freq").ok_or(Error::Parse)?,
+                    values,
+                }
+            }
+            Input::Stb(_) => {
+                let mut values = reader.read_ac(&reader.locate(&stem, OutputKind::Stb)?)?;
+                CachedData::Stb {
+                    freq: values.freq,
+                    loop_gain: values.signals.remove(stb::LOOP_GAIN).ok_or(Error::Parse)?,
+                }
+            }
+            Input::Sens(_)
+            | Input::MonteCarlo(_)
+            | Input::Alter(_)
+            | Input::Reliability(_)
+            | Input::ParamSweep(_) => unreachable!(),
         }
     })
 }
 
 impl MonteCarlo<Vec<Input>> {
-    fn netlist<W: Write>(&self, out: &mut W, name: &str) -> Result<()> {
+    fn netlist<W: Write>(
+        &self,
+        out: &mut W,
+        name: &str,
+        checkpoint: Option<&Checkpoint>,
+    ) -> Result<()> {
         write!(
             out,
             "montecarlo variations={} numruns={} savefamilyplots=yes",
@@ -1048,14 +2576,156 @@ impl MonteCarlo<Vec<Input>> {
         for (i, an) in self.analysis.iter().enumerate() {
             let name = subanalysis_name(name, i);
             write!(out, "\n\t")?;
-            an.netlist(out, &name)?;
+            an.netlist(out, &name, checkpoint)?;
+        }
+        write!(out, "\n}}")?;
+
+        Ok(())
+    }
+}
+
+impl Alter<Vec<Input>> {
+    fn netlist<W: Write>(
+        &self,
+        out: &mut W,
+        name: &str,
+        checkpoint: Option<&Checkpoint>,
+    ) -> Result<()> {
+        for (g, group) in self.groups.iter().enumerate() {
+            if g > 0 {
+                writeln!(out)?;
+            }
+            let group_name = alter_group_name(name, g);
+            write!(out, "{group_name} altergroup {{")?;
+            for alteration in group.iter() {
+                write!(out, "\n\t")?;
+                alteration.netlist(out)?;
+            }
+            write!(out, "\n}}")?;
+
+            for (i, an) in self.analysis.iter().enumerate() {
+                writeln!(out)?;
+                an.netlist(out, &subanalysis_name(&group_name, i), checkpoint)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Reliability<Vec<Input>> {
+    fn netlist<W: Write>(
+        &self,
+        out: &mut W,
+        name: &str,
+        checkpoint: Option<&Checkpoint>,
+    ) -> Result<()> {
+        write!(out, "reliability {{")?;
+        write!(out, "\n\tage time=[{}y]", self.age)?;
+        for control in self.controls.iter() {
+            write!(out, "\n\t")?;
+            control.netlist(out)?;
+        }
+        // The fresh analyses accumulate stress; the aged analyses are
+        // simulated with the resulting degraded device parameters.
+        for aged in [false, true] {
+            let phase_name = reliability_phase_name(name, aged);
+            for (i, an) in self.analysis.iter().enumerate() {
+                write!(out, "\n\t")?;
+                an.netlist(out, &subanalysis_name(&phase_name, i), checkpoint)?;
+            }
         }
         write!(out, "\n}}")?;
+        Ok(())
+    }
+}
+
+impl ParamSweep<Vec<Input>> {
+    fn netlist<W: Write>(
+        &self,
+        out: &mut W,
+        name: &str,
+        checkpoint: Option<&Checkpoint>,
+    ) -> Result<()> {
+        write!(
+            out,
+            "sweep param={} values=[{}] {{",
+            self.param,
+            self.values.iter().join(" ")
+        )?;
+        for (i, an) in self.analysis.iter().enumerate() {
+            write!(out, "\n\t")?;
+            an.netlist(out, &subanalysis_name(name, i), checkpoint)?;
+        }
+        write!(out, "\n}}")?;
+        Ok(())
+    }
+}
 
+impl ReliabilityControl {
+    fn netlist<W: Write>(&self, out: &mut W) -> Result<()> {
+        match self {
+            Self::AgeMethod(method) => write!(
+                out,
+                "agemethod type={}",
+                match method {
+                    reliability::AgeMethod::AgeMos => "agemos",
+                    reliability::AgeMethod::Tmi => "tmi",
+                }
+            )?,
+            Self::DeltaD(value) => write!(out, "deltad value={value}")?,
+            Self::Raw(raw) => write!(out, "{raw}")?,
+        }
         Ok(())
     }
 }
 
+impl Alteration {
+    fn netlist<W: Write>(&self, out: &mut W) -> Result<()> {
+        match self {
+            Self::Param { name, value } => write!(out, "parameters {name}={value}")?,
+            Self::Section { path, section } => write!(out, "include {:?} section={section}", path)?,
+            Self::Raw(raw) => write!(out, "{raw}")?,
+        }
+        Ok(())
+    }
+}
+
+/// Words that are reserved in the Spectre netlist language.
+const SPECTRE_RESERVED_WORDS: &[&str] = &[
+    "ahdl_include",
+    "altergroup",
+    "correlate",
+    "else",
+    "end",
+    "endlibrary",
+    "endsection",
+    "ends",
+    "export",
+    "for",
+    "function",
+    "global",
+    "if",
+    "include",
+    "inline",
+    "library",
+    "local",
+    "march",
+    "model",
+    "parameters",
+    "paramset",
+    "real",
+    "return",
+    "save",
+    "section",
+    "sens",
+    "simulator",
+    "statistics",
+    "subckt",
+    "to",
+    "truncate",
+    "vary",
+];
+
 impl HasSpiceLikeNetlist for Spectre {
     fn write_prelude<W: Write>(&self, out: &mut W, lib: &Library<Spectre>) -> std::io::Result<()> {
         writeln!(out, "// Substrate Spectre library\n")?;
@@ -1065,7 +2735,6 @@ impl HasSpiceLikeNetlist for Spectre {
             out,
             "// Be careful when editing manually: this file may be overwritten.\n"
         )?;
-        writeln!(out, "global 0\n")?;
 
         // find all unique IBIS models and include them
         let ibis = lib
@@ -1121,6 +2790,42 @@ impl HasSpiceLikeNetlist for Spectre {
         Ok(())
     }
 
+    fn legalize_name(&self, name: &ArcStr) -> ArcStr {
+        // Illegal characters are escaped when names are written,
+        // but reserved words cannot be used as names even when escaped.
+        if SPECTRE_RESERVED_WORDS.contains(&name.as_str()) {
+            arcstr::format!("{}_", name)
+        } else {
+            name.clone()
+        }
+    }
+
+    fn write_supply<W: Write>(
+        &self,
+        out: &mut W,
+        name: &ArcStr,
+        pos: &ArcStr,
+        neg: &ArcStr,
+        voltage: Decimal,
+    ) -> std::io::Result<()> {
+        write!(
+            out,
+            "{} ( {} {} ) vsource type=dc dc={}",
+            Spectre::escape_identifier(&format!("v{name}")),
+            pos,
+            neg,
+            voltage
+        )
+    }
+
+    fn write_globals<W: Write>(&self, out: &mut W, globals: &[&ArcStr]) -> std::io::Result<()> {
+        write!(out, "global 0")?;
+        for global in globals {
+            write!(out, " {}", Spectre::escape_identifier(global))?;
+        }
+        writeln!(out)
+    }
+
     fn write_include<W: Write>(&self, out: &mut W, include: &Include) -> std::io::Result<()> {
         if let Some(section) = &include.section {
             write!(out, "include {:?} section={}", include.path, section)?;
@@ -1150,10 +2855,39 @@ impl HasSpiceLikeNetlist for Spectre {
         Ok(())
     }
 
+    fn write_subckt_params<W: Write>(
+        &self,
+        out: &mut W,
+        params: &[(&ArcStr, &Param)],
+    ) -> std::io::Result<()> {
+        write!(out, "\nparameters")?;
+        for (name, param) in params {
+            write!(out, " {}={}", name, param.default)?;
+        }
+        Ok(())
+    }
+
     fn write_end_subckt<W: Write>(&self, out: &mut W, name: &ArcStr) -> std::io::Result<()> {
         write!(out, "ends {}", name)
     }
 
+    fn write_attribute<W: Write>(
+        &self,
+        out: &mut W,
+        key: &ArcStr,
+        value: &ArcStr,
+    ) -> std::io::Result<()> {
+        if value.is_empty() {
+            write!(out, "// {key}")
+        } else {
+            write!(out, "// {key}={value}")
+        }
+    }
+
+    fn write_comment<W: Write>(&self, out: &mut W, comment: &str) -> std::io::Result<()> {
+        write!(out, "// {comment}")
+    }
+
     fn write_instance<W: Write>(
         &self,
         out: &mut W,
@@ -1173,6 +2907,17 @@ impl HasSpiceLikeNetlist for Spectre {
         Ok(name)
     }
 
+    fn write_instance_params<W: Write>(
+        &self,
+        out: &mut W,
+        params: &[(&ArcStr, &Expr)],
+    ) -> std::io::Result<()> {
+        for (name, value) in params {
+            write!(out, " {}={}", name, value)?;
+        }
+        Ok(())
+    }
+
     fn write_primitive_inst<W: Write>(
         &self,
         out: &mut W,
@@ -0,0 +1,109 @@
+//! Spectre log parsing.
+
+use std::path::Path;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use scir::NetlistLibConversion;
+use substrate::schematic::conv::RawLib;
+use substrate::simulation::messages::{MentionedName, SimulatorMessage};
+
+use crate::error::Error;
+use crate::Spectre;
+
+lazy_static! {
+    static ref ERROR: Regex = Regex::new(r"^ERROR(?: \([^)]*\))?:\s*(.*)$").unwrap();
+    static ref HEADER: Regex = Regex::new(r"^(ERROR|WARNING|Notice)\b").unwrap();
+    static ref QUOTED: Regex = Regex::new(r#"`([^'`]+)'|"([^"]+)""#).unwrap();
+    static ref BUS_INDEX: Regex = Regex::new(r"^(.*)\[(\d+)\]$").unwrap();
+}
+
+/// Returns the error messages in a Spectre log.
+///
+/// Messages that span multiple lines are joined into a single line.
+pub(crate) fn error_messages(log: &str) -> Vec<String> {
+    let mut messages = Vec::new();
+    let mut lines = log.lines().map(str::trim).peekable();
+    while let Some(line) = lines.next() {
+        let Some(caps) = ERROR.captures(line) else {
+            continue;
+        };
+        let mut message = caps[1].to_string();
+        while let Some(next) = lines.next_if(|next| !next.is_empty() && !HEADER.is_match(next)) {
+            if !message.is_empty() {
+                message.push(' ');
+            }
+            message.push_str(next);
+        }
+        messages.push(message);
+    }
+    messages
+}
+
+/// Splits a Spectre name into its unescaped hierarchical elements and bus index.
+///
+/// Terminal suffixes (e.g. `xr1:p`) are ignored.
+pub(crate) fn split_name(name: &str) -> (Vec<String>, Option<usize>) {
+    let mut elems = vec![String::new()];
+    let mut chars = name.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                if let Some(c) = chars.next() {
+                    elems.last_mut().unwrap().push(c);
+                }
+            }
+            '.' => elems.push(String::new()),
+            ':' => break,
+            c => elems.last_mut().unwrap().push(c),
+        }
+    }
+
+    let last = elems.last_mut().unwrap();
+    let index = BUS_INDEX.captures(last).and_then(|caps| {
+        let index = caps[2].parse().ok()?;
+        let signal = caps[1].to_string();
+        Some((signal, index))
+    });
+    let index = index.map(|(signal, index)| {
+        *last = signal;
+        index
+    });
+    (elems, index)
+}
+
+/// Maps the names quoted in a Spectre message back to SCIR and Substrate paths.
+pub(crate) fn mentioned_names(
+    lib: &RawLib<Spectre>,
+    conv: &NetlistLibConversion,
+    message: &str,
+) -> Vec<MentionedName> {
+    QUOTED
+        .captures_iter(message)
+        .filter_map(|caps| {
+            let name = caps.get(1).or_else(|| caps.get(2))?.as_str();
+            let (elems, index) = split_name(name);
+            let elems = elems.iter().map(String::as_str).collect::<Vec<_>>();
+            lib.resolve_netlisted_name(conv, name, &elems, index)
+        })
+        .collect()
+}
+
+/// Creates an error describing a failed simulation from the Spectre log at `log`.
+///
+/// Returns [`None`] if the log could not be read or does not contain any errors.
+pub(crate) fn simulation_error(
+    lib: &RawLib<Spectre>,
+    conv: &NetlistLibConversion,
+    log: &Path,
+) -> Option<Error> {
+    let log = std::fs::read_to_string(log).ok()?;
+    let messages = error_messages(&log)
+        .into_iter()
+        .map(|message| SimulatorMessage {
+            names: mentioned_names(lib, conv, &message),
+            message,
+        })
+        .collect::<Vec<_>>();
+    (!messages.is_empty()).then_some(Error::SimulationFailed(messages))
+}
@@ -0,0 +1,204 @@
+//! Locating the raw output files written by Spectre.
+//!
+//! Spectre writes the results of each analysis to a file in the raw output directory
+//! whose name is derived from the analysis name and type (e.g. `analysis_0.tran.tran`).
+//! The exact names vary between Spectre versions and output formats, so each analysis
+//! type has a configurable file name pattern. If no file matches the pattern, the raw
+//! output directory is scanned for a file with the analysis name and type:
+//!
+//! ```ignore
+//! let mut naming = OutputNaming::default();
+//! naming.set_pattern(OutputKind::Tran, "{name}.tran");
+//! opts.set_output_naming(naming);
+//! ```
+
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+
+use crate::error::{Error, Result};
+
+/// The placeholder replaced by the analysis name in output file name patterns.
+pub const NAME_PLACEHOLDER: &str = "{name}";
+
+/// The type of output file written by an analysis.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum OutputKind {
+    /// Transient analysis results.
+    Tran,
+    /// AC analysis results.
+    Ac,
+    /// DC analysis results.
+    Dc,
+    /// Results of an `info` statement.
+    Info,
+    /// Results of a `sens` statement.
+    Sens,
+    /// Stability analysis results.
+    Stb,
+    /// Periodic steady-state results in the time domain.
+    PssTime,
+    /// Periodic steady-state results in the frequency domain.
+    PssFreq,
+    /// Periodic AC analysis results.
+    Pac,
+    /// Periodic noise analysis results.
+    Pnoise,
+    /// Noise analysis results.
+    Noise,
+}
+
+impl OutputKind {
+    /// The file extension identifying outputs of this kind.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Tran => "tran",
+            Self::Ac => "ac",
+            Self::Dc => "dc",
+            Self::Info => "info",
+            Self::Sens => "sens",
+            Self::Stb => "stb",
+            Self::PssTime => "td",
+            Self::PssFreq => "fd",
+            Self::Pac => "pac",
+            Self::Pnoise => "pnoise",
+            Self::Noise => "noise",
+        }
+    }
+}
+
+impl Display for OutputKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.extension())
+    }
+}
+
+/// The naming scheme of the raw output files written by Spectre.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct OutputNaming {
+    tran: String,
+    ac: String,
+    dc: String,
+    info: String,
+    sens: String,
+    stb: String,
+    pss_time: String,
+    pss_freq: String,
+    pac: String,
+    pnoise: String,
+    noise: String,
+    /// Whether to scan the raw output directory if no file matches a pattern.
+    discover: bool,
+}
+
+impl Default for OutputNaming {
+    fn default() -> Self {
+        Self {
+            tran: "{name}.tran.tran".to_string(),
+            ac: "{name}.ac".to_string(),
+            dc: "{name}.dc".to_string(),
+            info: "{name}.info".to_string(),
+            sens: "{name}.sens".to_string(),
+            stb: "{name}.stb".to_string(),
+            pss_time: "{name}.td.pss".to_string(),
+            pss_freq: "{name}.fd.pss".to_string(),
+            pac: "{name}.pac".to_string(),
+            pnoise: "{name}.pnoise".to_string(),
+            noise: "{name}.noise".to_string(),
+            discover: true,
+        }
+    }
+}
+
+impl OutputNaming {
+    /// The file name pattern of outputs of the given kind.
+    pub fn pattern(&self, kind: OutputKind) -> &str {
+        match kind {
+            OutputKind::Tran => &self.tran,
+            OutputKind::Ac => &self.ac,
+            OutputKind::Dc => &self.dc,
+            OutputKind::Info => &self.info,
+            OutputKind::Sens => &self.sens,
+            OutputKind::Stb => &self.stb,
+            OutputKind::PssTime => &self.pss_time,
+            OutputKind::PssFreq => &self.pss_freq,
+            OutputKind::Pac => &self.pac,
+            OutputKind::Pnoise => &self.pnoise,
+            OutputKind::Noise => &self.noise,
+        }
+    }
+
+    /// Sets the file name pattern of outputs of the given kind.
+    ///
+    /// Occurrences of [`NAME_PLACEHOLDER`] in `pattern` are replaced by the analysis name.
+    pub fn set_pattern(&mut self, kind: OutputKind, pattern: impl Into<String>) {
+        let pattern = pattern.into();
+        match kind {
+            OutputKind::Tran => self.tran = pattern,
+            OutputKind::Ac => self.ac = pattern,
+            OutputKind::Dc => self.dc = pattern,
+            OutputKind::Info => self.info = pattern,
+            OutputKind::Sens => self.sens = pattern,
+            OutputKind::Stb => self.stb = pattern,
+            OutputKind::PssTime => self.pss_time = pattern,
+            OutputKind::PssFreq => self.pss_freq = pattern,
+            OutputKind::Pac => self.pac = pattern,
+            OutputKind::Pnoise => self.pnoise = pattern,
+            OutputKind::Noise => self.noise = pattern,
+        }
+    }
+
+    /// Sets whether to scan the raw output directory for outputs that do not match
+    /// their file name pattern.
+    ///
+    /// Enabled by default.
+    pub fn discover(&mut self, discover: bool) {
+        self.discover = discover;
+    }
+
+    /// Returns the name of the file in `dir` containing outputs of the given kind for
+    /// the analysis named `name`.
+    ///
+    /// If no file matches the pattern for `kind` and discovery is enabled, returns the
+    /// shortest file name of the form `{name}.{extension}[.*]`, ignoring ASCII PSF files
+    /// produced by converting other outputs. Ties are broken alphabetically.
+    pub(crate) fn locate(&self, dir: &Path, name: &str, kind: OutputKind) -> Result<String> {
+        let expected = self.pattern(kind).replace(NAME_PLACEHOLDER, name);
+        if dir.join(&expected).is_file() || !self.discover {
+            return Ok(expected);
+        }
+
+        let mut candidates = Vec::new();
+        for entry in std::fs::read_dir(dir)? {
+            let entry = entry?;
+            if !entry.file_type()?.is_file() {
+                continue;
+            }
+            let Ok(file_name) = entry.file_name().into_string() else {
+                continue;
+            };
+            let matches = file_name
+                .strip_prefix(name)
+                .and_then(|rest| rest.strip_prefix('.'))
+                .is_some_and(|rest| rest.split('.').next() == Some(kind.extension()));
+            if matches && !file_name.ends_with(".ascii") {
+                candidates.push(file_name);
+            }
+        }
+        candidates.sort_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)));
+
+        let file_name = candidates
+            .into_iter()
+            .next()
+            .ok_or_else(|| Error::MissingOutput {
+                analysis: name.to_string(),
+                kind,
+                dir: dir.to_path_buf(),
+            })?;
+        tracing::debug!(
+            expected,
+            found = file_name,
+            "discovered Spectre output file not matching naming pattern"
+        );
+        Ok(file_name)
+    }
+}
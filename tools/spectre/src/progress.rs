@@ -0,0 +1,103 @@
+//! Spectre progress reporting.
+
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use substrate::simulation::progress::{Progress, ProgressAction, ProgressCallback};
+
+/// The interval at which the Spectre log is polled for progress updates.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+lazy_static! {
+    static ref PROGRESS: Regex = Regex::new(
+        r"^\s*(\w+):\s*time\s*=\s*([-+0-9.eE]+)\s*([afpnumkMG]?)s\s*\(\s*([0-9.]+)\s*%\)"
+    )
+    .unwrap();
+}
+
+/// Parses a progress line from a Spectre log.
+///
+/// Spectre reports the progress of time-domain analyses with lines of the form
+/// `tran: time = 1.25 ns (12.5 %), step = ...`.
+pub(crate) fn parse_progress(line: &str) -> Option<Progress> {
+    let caps = PROGRESS.captures(line)?;
+    let time = caps[2].parse::<f64>().ok()?;
+    let scale = match &caps[3] {
+        "a" => 1e-18,
+        "f" => 1e-15,
+        "p" => 1e-12,
+        "n" => 1e-9,
+        "u" => 1e-6,
+        "m" => 1e-3,
+        "k" => 1e3,
+        "M" => 1e6,
+        "G" => 1e9,
+        _ => 1.,
+    };
+    let percent = caps[4].parse::<f64>().ok()?;
+    Some(Progress {
+        analysis: Some(caps[1].to_string()),
+        fraction: percent / 100.,
+        time: Some(time * scale),
+    })
+}
+
+/// Runs `run` while tailing the Spectre log at `log`, reporting progress to `callback`.
+///
+/// If the callback requests that the simulation be stopped, the file at `stop_path` is
+/// created; the run script stops Spectre once it sees this file. Returns the output of
+/// `run` and whether the simulation was stopped.
+pub(crate) fn with_progress<T>(
+    log: &Path,
+    stop_path: &Path,
+    callback: &ProgressCallback,
+    run: impl FnOnce() -> T,
+) -> (T, bool) {
+    let done = AtomicBool::new(false);
+    let stopped = AtomicBool::new(false);
+    let out = std::thread::scope(|s| {
+        s.spawn(|| {
+            let mut offset = 0;
+            let mut pending = String::new();
+            loop {
+                let finished = done.load(Ordering::Acquire);
+                if let Ok(contents) = std::fs::read(log) {
+                    if contents.len() > offset {
+                        pending.push_str(&String::from_utf8_lossy(&contents[offset..]));
+                        offset = contents.len();
+                    }
+                }
+                while let Some(idx) = pending.find('\n') {
+                    let line = pending.drain(..=idx).collect::<String>();
+                    let Some(progress) = parse_progress(&line) else {
+                        continue;
+                    };
+                    if !stopped.load(Ordering::Acquire)
+                        && callback.call(&progress) == ProgressAction::Stop
+                    {
+                        stopped.store(true, Ordering::Release);
+                        request_stop(stop_path);
+                    }
+                }
+                if finished {
+                    break;
+                }
+                std::thread::sleep(POLL_INTERVAL);
+            }
+        });
+        let out = run();
+        done.store(true, Ordering::Release);
+        out
+    });
+    (out, stopped.into_inner())
+}
+
+/// Asks the run script to stop Spectre by creating the file at `stop_path`.
+fn request_stop(stop_path: &Path) {
+    if let Err(e) = std::fs::write(stop_path, "") {
+        tracing::warn!("unable to stop Spectre: {e}");
+    }
+}
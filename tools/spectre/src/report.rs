@@ -0,0 +1,151 @@
+//! Reports of completed Spectre runs.
+//!
+//! After a simulation runs, its working directory contains the Spectre log and the exit
+//! status of Spectre. [`RunReport::read`] collects these into structured data:
+//!
+//! ```ignore
+//! let output = sim.simulate(opts, tran)?;
+//! let report = RunReport::read(&sim_dir)?;
+//! println!("waited {:?} for licenses", report.license_wait);
+//! ```
+//!
+//! Simulations served from the cache do not invoke Spectre, so no report is written
+//! for them. Reports of earlier runs are removed from the working directory before each
+//! simulation, so [`RunReport::read`] never returns a stale report.
+
+use std::path::Path;
+use std::time::Duration;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::error::Result;
+
+/// The name of the file to which the run script writes the exit status of Spectre.
+pub(crate) const STATUS_FILE: &str = "spectre.status";
+
+/// The name of the Spectre log file.
+pub(crate) const LOG_FILE: &str = "spectre.log";
+
+lazy_static! {
+    static ref TIME_USED: Regex =
+        Regex::new(r"^Time used:\s*CPU\s*=\s*([^,]+),\s*elapsed\s*=\s*([^,]+)").unwrap();
+    static ref LICENSING: Regex =
+        Regex::new(r"^Time spent in licensing:\s*elapsed\s*=\s*([^,]+)").unwrap();
+    static ref PEAK_MEMORY: Regex =
+        Regex::new(r"^Peak (?:resident )?memory used\s*=\s*([0-9.]+)\s*([kMG])bytes").unwrap();
+    static ref COMPLETES: Regex =
+        Regex::new(r"^spectre completes with (\d+) errors?, (\d+) warnings?, and (\d+) notices?")
+            .unwrap();
+    static ref DURATION: Regex = Regex::new(r"([0-9]*\.?[0-9]+)\s*(ms|us|ns|h|m|s)\b").unwrap();
+}
+
+/// A structured report of a completed Spectre run.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RunReport {
+    /// The exit status of Spectre, if known.
+    pub exit_status: Option<i32>,
+    /// The time spent waiting for and checking out licenses.
+    pub license_wait: Option<Duration>,
+    /// Statistics reported by Spectre.
+    pub stats: RunStats,
+}
+
+/// Statistics reported by Spectre at the end of a run.
+///
+/// Fields are [`None`] if the corresponding statistic was not found in the log,
+/// for example because Spectre terminated early.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RunStats {
+    /// The total CPU time used.
+    pub cpu_time: Option<Duration>,
+    /// The total wall-clock time elapsed.
+    pub elapsed: Option<Duration>,
+    /// The peak memory used, in megabytes.
+    pub peak_memory_mb: Option<f64>,
+    /// The number of errors reported.
+    pub errors: Option<usize>,
+    /// The number of warnings reported.
+    pub warnings: Option<usize>,
+    /// The number of notices reported.
+    pub notices: Option<usize>,
+}
+
+impl RunReport {
+    /// Reads the report of the Spectre run in the simulation working directory `work_dir`.
+    ///
+    /// Returns an error if the Spectre log cannot be read, including if the
+    /// simulation was served from the cache.
+    pub fn read(work_dir: impl AsRef<Path>) -> Result<Self> {
+        let work_dir = work_dir.as_ref();
+        let log = std::fs::read_to_string(work_dir.join(LOG_FILE))?;
+        let exit_status = std::fs::read_to_string(work_dir.join(STATUS_FILE))
+            .ok()
+            .and_then(|status| status.trim().parse().ok());
+        Ok(Self::from_log(&log, exit_status))
+    }
+
+    /// Removes the report of any earlier run from the working directory `work_dir`.
+    pub(crate) fn clear(work_dir: &Path) -> Result<()> {
+        for file in [LOG_FILE, STATUS_FILE] {
+            match std::fs::remove_file(work_dir.join(file)) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Creates a report from the contents of a Spectre log and the exit status of Spectre.
+    pub fn from_log(log: &str, exit_status: Option<i32>) -> Self {
+        let mut report = Self {
+            exit_status,
+            ..Default::default()
+        };
+        for line in log.lines().map(str::trim) {
+            // Spectre reports the time used by each analysis; the last report
+            // is the aggregate over the whole run.
+            if let Some(caps) = TIME_USED.captures(line) {
+                report.stats.cpu_time = parse_duration(&caps[1]);
+                report.stats.elapsed = parse_duration(&caps[2]);
+            } else if let Some(caps) = LICENSING.captures(line) {
+                report.license_wait = parse_duration(&caps[1]);
+            } else if let Some(caps) = PEAK_MEMORY.captures(line) {
+                let scale = match &caps[2] {
+                    "k" => 1e-3,
+                    "G" => 1e3,
+                    _ => 1.,
+                };
+                report.stats.peak_memory_mb = caps[1].parse::<f64>().ok().map(|mem| mem * scale);
+            } else if let Some(caps) = COMPLETES.captures(line) {
+                report.stats.errors = caps[1].parse().ok();
+                report.stats.warnings = caps[2].parse().ok();
+                report.stats.notices = caps[3].parse().ok();
+            }
+        }
+        report
+    }
+
+    /// Returns `true` if Spectre exited successfully.
+    pub fn success(&self) -> bool {
+        self.exit_status == Some(0)
+    }
+}
+
+/// Parses a duration reported by Spectre, such as `52.1 ms` or `1m 5.2s`.
+pub(crate) fn parse_duration(s: &str) -> Option<Duration> {
+    let mut total = None;
+    for caps in DURATION.captures_iter(s) {
+        let value = caps[1].parse::<f64>().ok()?;
+        let scale = match &caps[2] {
+            "h" => 3600.,
+            "m" => 60.,
+            "ms" => 1e-3,
+            "us" => 1e-6,
+            "ns" => 1e-9,
+            _ => 1.,
+        };
+        *total.get_or_insert(Duration::ZERO) += Duration::from_secs_f64(value * scale);
+    }
+    total
+}
@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::sync::{Arc, Mutex};
 
@@ -24,9 +24,10 @@ use substrate::{
     schematic::{CellBuilder, NestedData, PrimitiveBinding, Schematic},
     simulation::SimController,
     types::{
-        schematic::{IoNodeBundle, Node},
+        schematic::{IoNodeBundle, Node, NodePath},
         InOut, Io, Signal, TestbenchIo, TwoTerminalIo,
     },
+    units::{Frequency, Time, Voltage},
 };
 
 use crate::analysis::ac::Ac;
@@ -114,7 +115,7 @@ fn spectre_can_include_sections() {
             cell.connect(dut.io().n, res.io().p);
             cell.connect(io.vss, res.io().n);
 
-            let vsource = cell.instantiate(Vsource::dc(dec!(1.8)));
+            let vsource = cell.instantiate(Vsource::dc(Voltage::new(dec!(1.8))));
             cell.connect(vsource.io().p, vdd);
             cell.connect(vsource.io().n, io.vss);
 
@@ -129,7 +130,7 @@ fn spectre_can_include_sections() {
             .simulate(
                 opts,
                 Tran {
-                    stop: dec!(2e-9),
+                    stop: Time::new(dec!(2e-9)),
                     errpreset: Some(ErrPreset::Conservative),
                     ..Default::default()
                 },
@@ -231,7 +232,7 @@ fn spectre_can_save_paths_with_flattened_instances() {
             cell.connect(dut.io().p, vdd);
             cell.connect(dut.io().n, io.vss);
 
-            let vsource = cell.instantiate(Vsource::dc(dec!(1.8)));
+            let vsource = cell.instantiate(Vsource::dc(Voltage::new(dec!(1.8))));
             cell.connect(vsource.io().p, vdd);
             cell.connect(vsource.io().n, io.vss);
 
@@ -249,7 +250,7 @@ fn spectre_can_save_paths_with_flattened_instances() {
         .simulate(
             Options::default(),
             Tran {
-                stop: dec!(2e-9),
+                stop: Time::new(dec!(2e-9)),
                 errpreset: Some(crate::ErrPreset::Conservative),
                 ..Default::default()
             },
@@ -328,12 +329,12 @@ fn simulate_rc_tb(
             opts,
             (
                 Tran {
-                    stop: dec!(10e-6),
+                    stop: Time::new(dec!(10e-6)),
                     ..Default::default()
                 },
                 Ac {
-                    start: dec!(1e6),
-                    stop: dec!(2e6),
+                    start: Frequency::new(dec!(1e6)),
+                    stop: Frequency::new(dec!(2e6)),
                     sweep: Sweep::Linear(10),
                 },
                 DcOp,
@@ -478,3 +479,1456 @@ fn netlist_spectre_vdivider() {
     assert_eq!(string.matches("vdivider").count(), 2);
     assert_eq!(string.matches("resistor r=100").count(), 3);
 }
+
+#[test]
+fn spectre_check_log_maps_to_scir_instances() {
+    let lib = vdivider();
+    let mut buf: Vec<u8> = Vec::new();
+    let includes = Vec::new();
+    let conv = NetlisterInstance::new(
+        &Spectre {},
+        &lib,
+        &mut buf,
+        NetlistOptions::new(NetlistKind::Cells, &includes),
+    )
+    .export()
+    .unwrap();
+    let netlist = String::from_utf8(buf).unwrap();
+    let line = netlist
+        .lines()
+        .position(|line| line.trim_start().starts_with("xr2"))
+        .unwrap()
+        + 1;
+
+    let log = format!(
+        "ERROR (SFE-23): \"netlist.scs\" {line}: The instance `xr2' is referencing an undefined\n    model or subcircuit, `resistor'.\n\nERROR (SFE-1): Unexpected error.\n"
+    );
+    let mut errors = crate::check::parse_check_log(&log);
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0].line, Some(line));
+    assert_eq!(
+        errors[0].message,
+        "The instance `xr2' is referencing an undefined model or subcircuit, `resistor'."
+    );
+    assert_eq!(errors[1].line, None);
+    assert_eq!(errors[1].message, "Unexpected error.");
+
+    crate::check::locate_error(&lib, &conv, &netlist, &mut errors[0]);
+    assert_eq!(errors[0].cell.as_deref(), Some("vdivider"));
+    assert_eq!(errors[0].instance.as_deref(), Some("r2"));
+}
+
+#[test]
+fn spectre_log_names_are_split() {
+    use crate::log::split_name;
+
+    assert_eq!(
+        split_name("xdut.xr1:p"),
+        (vec!["xdut".to_string(), "xr1".to_string()], None)
+    );
+    assert_eq!(
+        split_name("xdut.out\\[3\\]"),
+        (vec!["xdut".to_string(), "out".to_string()], Some(3))
+    );
+    assert_eq!(split_name("vdd"), (vec!["vdd".to_string()], None));
+
+    let log = "\nERROR (SPECTRE-16): Node `xdut.out' has no DC path to ground.\n    Check the netlist.\n\nWARNING (SPECTRE-1): ignored.\n";
+    assert_eq!(
+        crate::log::error_messages(log),
+        vec!["Node `xdut.out' has no DC path to ground. Check the netlist.".to_string()]
+    );
+}
+
+#[test]
+fn netlist_spectre_alter_groups() {
+    use crate::analysis::alter::{Alter, Alteration};
+    use crate::Input;
+
+    let input = Input::from(Alter {
+        groups: vec![
+            vec![Alteration::Param {
+                name: "vdd".into(),
+                value: dec!(1.8),
+            }],
+            vec![
+                Alteration::Section {
+                    path: PathBuf::from("models.scs"),
+                    section: "ff".into(),
+                },
+                Alteration::Param {
+                    name: "temp".into(),
+                    value: dec!(-40),
+                },
+            ],
+        ],
+        analysis: vec![
+            Input::from(DcOp),
+            Input::from(Tran {
+                stop: Time::new(dec!(1e-9)),
+                ..Default::default()
+            }),
+        ],
+    });
+
+    let mut buf = Vec::new();
+    input.netlist(&mut buf, "analysis_0", None).unwrap();
+    let netlist = String::from_utf8(buf).unwrap();
+    println!("{netlist}");
+
+    assert_eq!(
+        netlist,
+        "analysis_0_alter_0 altergroup {\n\tparameters vdd=1.8\n}\n\
+         analysis_0_alter_0_0 dc\n\
+         analysis_0_alter_0_1 tran stop=0.000000001\n\
+         analysis_0_alter_1 altergroup {\n\tinclude \"models.scs\" section=ff\n\tparameters temp=-40\n}\n\
+         analysis_0_alter_1_0 dc\n\
+         analysis_0_alter_1_1 tran stop=0.000000001"
+    );
+}
+
+#[test]
+fn netlist_spectre_reliability() {
+    use crate::analysis::reliability::{AgeMethod, Reliability, ReliabilityControl};
+    use crate::Input;
+
+    let input = Input::from(
+        Reliability::new(
+            dec!(10),
+            vec![
+                Input::from(DcOp),
+                Input::from(Tran {
+                    stop: Time::new(dec!(1e-9)),
+                    ..Default::default()
+                }),
+            ],
+        )
+        .control(ReliabilityControl::AgeMethod(AgeMethod::AgeMos))
+        .control(ReliabilityControl::DeltaD(dec!(0.1))),
+    );
+
+    let mut buf = Vec::new();
+    input.netlist(&mut buf, "analysis_0", None).unwrap();
+    let netlist = String::from_utf8(buf).unwrap();
+    println!("{netlist}");
+
+    assert_eq!(
+        netlist,
+        "analysis_0 reliability {\n\
+         \tage time=[10y]\n\
+         \tagemethod type=agemos\n\
+         \tdeltad value=0.1\n\
+         \tanalysis_0_fresh_0 dc\n\
+         \tanalysis_0_fresh_1 tran stop=0.000000001\n\
+         \tanalysis_0_aged_0 dc\n\
+         \tanalysis_0_aged_1 tran stop=0.000000001\n\
+         }"
+    );
+}
+
+#[test]
+fn netlist_spectre_param_sweep() {
+    use crate::analysis::sweep::ParamSweep;
+    use crate::Input;
+
+    let input = Input::from(ParamSweep::new(
+        "vdd",
+        [dec!(1.6), dec!(1.8)],
+        vec![
+            Input::from(DcOp),
+            Input::from(ParamSweep::new(
+                "temp",
+                [dec!(-40), dec!(125)],
+                vec![Input::from(Tran {
+                    stop: Time::new(dec!(1e-9)),
+                    ..Default::default()
+                })],
+            )),
+        ],
+    ));
+
+    let mut buf = Vec::new();
+    input.netlist(&mut buf, "analysis_0", None).unwrap();
+    let netlist = String::from_utf8(buf).unwrap();
+    println!("{netlist}");
+
+    assert_eq!(
+        netlist,
+        "analysis_0 sweep param=vdd values=[1.6 1.8] {\n\
+         \tanalysis_0_0 dc\n\
+         \tanalysis_0_1 sweep param=temp values=[-40 125] {\n\
+         \tanalysis_0_1_0 tran stop=0.000000001\n\
+         }\n\
+         }"
+    );
+}
+
+#[test]
+fn spectre_saves_are_compressed() {
+    use crate::group_saves;
+    use simulator_common::names::Interned;
+
+    let saves = ["xdut.xb.n1", "vout", "xdut.xa.n2", "xdut.xa.n1", "vin"].map(ArcStr::from);
+    assert_eq!(
+        group_saves(&saves),
+        vec![
+            vec!["vin", "vout"],
+            vec!["xdut.xa.n1", "xdut.xa.n2"],
+            vec!["xdut.xb.n1"],
+        ]
+    );
+
+    let values = std::collections::HashMap::from_iter([
+        ("xdut.xa.n1".to_string(), vec![0., 1.]),
+        ("xdut.xa.n2".to_string(), vec![1., 0.]),
+        ("time".to_string(), vec![0., 1e-9]),
+    ]);
+    let interned = values.clone().into_iter().collect::<Interned<_>>();
+    assert_eq!(interned.names().len(), 4);
+    assert_eq!(
+        interned
+            .into_iter()
+            .collect::<std::collections::HashMap<_, _>>(),
+        values
+    );
+}
+
+#[test]
+fn spectre_reads_ascii_and_binary_psf() {
+    use crate::PsfReader;
+
+    let reader = PsfReader {
+        output_dir: &PathBuf::from(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../../libs/psfparser/examples"
+        )),
+        naming: &Default::default(),
+        executor: &LocalExecutor,
+        intern_names: false,
+        decimation: None,
+    };
+    let ascii = reader.read_tran("sram_tiny_ascii.tran.tran").unwrap();
+    let binary = reader.read_tran("sram_tiny_bin.tran.tran").unwrap();
+    assert_eq!(ascii.len(), binary.len());
+    for (name, values) in ascii.iter() {
+        assert_eq!(values.len(), binary[name].len());
+    }
+
+    let dc = reader.read_dc("dcop.bin.dc").unwrap();
+    assert!(!dc.unwrap_op().signals.is_empty());
+}
+
+#[test]
+fn spectre_detects_psf_format_from_magic_bytes() {
+    use crate::is_binary_psf;
+
+    let examples = PathBuf::from(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../libs/psfparser/examples"
+    ));
+    for (file, binary) in [
+        ("sram_tiny_bin.tran.tran", true),
+        ("dcop.bin.dc", true),
+        ("AcZout.ac", true),
+        ("sram_tiny_ascii.tran.tran", false),
+        ("frequencySweep.ac", false),
+    ] {
+        let bytes = std::fs::read(examples.join(file)).unwrap();
+        assert_eq!(is_binary_psf(&bytes), binary, "{file}");
+    }
+    // Truncated or foreign files (e.g. PSF-XL) must not be mistaken for binary PSF.
+    assert!(!is_binary_psf(b""));
+    assert!(!is_binary_psf(b"Clarissa"));
+    assert!(!is_binary_psf(&[0xff; 64]));
+}
+
+fn refine_test_output(time: Vec<f64>, x: Vec<f64>) -> crate::analysis::tran::Output {
+    crate::analysis::tran::Output {
+        time: Arc::new(time),
+        raw_values: std::collections::HashMap::from_iter([(ArcStr::from("out"), Arc::new(x))]),
+        saved_values: std::collections::HashMap::new(),
+        resolver: None,
+    }
+}
+
+#[test]
+fn refine_detects_slew_and_non_monotonic_time() {
+    use crate::analysis::refine::{detect, Refinement, Suspicion};
+
+    let coarse = refine_test_output(vec![0., 1., 2., 2., 3.], vec![0., 0., 10., 10., 10.]);
+    let suspicious = detect(&coarse, &Refinement::new(5.));
+    assert_eq!(suspicious.len(), 2);
+    assert!(matches!(suspicious[0].reason, Suspicion::Slew { .. }));
+    assert_eq!((suspicious[0].start, suspicious[0].end), (1., 2.));
+    assert_eq!(suspicious[1].reason, Suspicion::NonMonotonicTime);
+}
+
+#[test]
+fn refine_merges_refined_window() {
+    use crate::analysis::refine::{detect, merge, windows, Refinement};
+
+    let refinement = Refinement::new(5.).margin(0.5);
+    let coarse = refine_test_output(vec![0., 1., 2., 3., 4.], vec![0., 0., 10., 10., 10.]);
+    let fine = refine_test_output(
+        vec![0., 1., 1.5, 1.6, 1.7, 2., 3., 4.],
+        vec![0., 0., 0., 5., 10., 10., 10., 10.],
+    );
+    let suspicious = detect(&coarse, &refinement);
+    let windows = windows(&suspicious, refinement.margin);
+    assert_eq!(windows, vec![(0.5, 2.5)]);
+
+    let (merged, regions) = merge(&coarse, &fine, &windows, &refinement);
+    assert_eq!(*merged.time, vec![0., 1., 1.5, 1.6, 1.7, 2., 3., 4.]);
+    assert_eq!(
+        **merged.raw_values.get("out").unwrap(),
+        vec![0., 0., 0., 5., 10., 10., 10., 10.]
+    );
+    assert_eq!(regions.len(), 1);
+    assert!(regions[0].changed);
+    assert_eq!(regions[0].max_change, 5.);
+}
+
+#[test]
+fn netlist_spectre_tran_checkpoint() {
+    use crate::{Checkpoint, Input};
+
+    let input = Input::from(Tran {
+        stop: Time::new(dec!(1e-6)),
+        ..Default::default()
+    });
+    let checkpoint = Checkpoint {
+        clock: Some(dec!(600)),
+        period: Some(Time::new(dec!(1e-7))),
+    };
+
+    let mut buf = Vec::new();
+    input
+        .netlist(&mut buf, "analysis_0", Some(&checkpoint))
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(buf).unwrap(),
+        "analysis_0 tran stop=0.000001 ckptclock=600 ckptperiod=0.0000001"
+    );
+
+    // Checkpoints only apply to transient analyses.
+    let mut buf = Vec::new();
+    Input::from(DcOp)
+        .netlist(&mut buf, "analysis_1", Some(&checkpoint))
+        .unwrap();
+    assert_eq!(String::from_utf8(buf).unwrap(), "analysis_1 dc");
+}
+
+#[test]
+fn netlist_spectre_tran_noise() {
+    use crate::Input;
+    use substrate::simulation::options::TransientNoise;
+
+    let input = Input::from(
+        Tran {
+            stop: Time::new(dec!(1e-6)),
+            ..Default::default()
+        }
+        .noise(
+            TransientNoise::new(dec!(1e10))
+                .unwrap()
+                .seed(7)
+                .scale(dec!(1.5)),
+        ),
+    );
+    let mut buf = Vec::new();
+    input.netlist(&mut buf, "analysis_0", None).unwrap();
+    assert_eq!(
+        String::from_utf8(buf).unwrap(),
+        "analysis_0 tran stop=0.000001 noisefmax=10000000000 noiseseed=7 noisescale=1.5"
+    );
+}
+
+#[test]
+fn netlist_spectre_tran_strobe() {
+    use crate::Input;
+
+    let input = Input::from(
+        Tran {
+            stop: Time::new(dec!(1e-6)),
+            ..Default::default()
+        }
+        .strobe(Time::new(dec!(1e-9)))
+        .strobe_delay(Time::new(dec!(2e-10)))
+        .output_start(Time::new(dec!(5e-7))),
+    );
+    let mut buf = Vec::new();
+    input.netlist(&mut buf, "analysis_0", None).unwrap();
+    assert_eq!(
+        String::from_utf8(buf).unwrap(),
+        "analysis_0 tran stop=0.000001 strobeperiod=0.000000001 strobedelay=0.0000000002 outputstart=0.0000005"
+    );
+}
+
+#[test]
+fn spectre_progress_is_parsed() {
+    use crate::progress::parse_progress;
+
+    let progress =
+        parse_progress("    tran: time = 12.5 ns     (1.25 %), step = 1.023 ps     (102 u%)")
+            .unwrap();
+    assert_eq!(progress.analysis.as_deref(), Some("tran"));
+    assert_relative_eq!(progress.fraction, 0.0125);
+    assert_relative_eq!(progress.time.unwrap(), 12.5e-9);
+
+    let progress = parse_progress("tran: time = 0 s (0 %), step = 1 fs (1 %)").unwrap();
+    assert_relative_eq!(progress.fraction, 0.);
+    assert_relative_eq!(progress.time.unwrap(), 0.);
+
+    assert!(parse_progress("Notice from spectre during transient analysis `tran'.").is_none());
+}
+
+/// Returns the path of the output node of an [`RcTb`] and the metadata needed to resolve it
+/// in simulation outputs, without running a simulation.
+fn rc_tb_resolver() -> (NodePath, crate::analysis::tran::PathResolver) {
+    let ctx = spectre_ctx();
+    let vout = ctx
+        .generate_schematic(RcTb::new(dec!(0)))
+        .cell()
+        .data()
+        .path();
+    let lib = ctx.export_scir(RcTb::new(dec!(0))).unwrap();
+    let conv = NetlisterInstance::new(
+        &Spectre::default(),
+        &lib.scir,
+        &mut Vec::new(),
+        NetlistOptions::new(
+            NetlistKind::Testbench(spice::netlist::RenameGround::Yes("0".into())),
+            &[],
+        ),
+    )
+    .export()
+    .unwrap();
+    let resolver = crate::analysis::tran::PathResolver {
+        lib: Arc::new(lib),
+        conv: Arc::new(conv),
+        aliases: Default::default(),
+    };
+    (vout, resolver)
+}
+
+#[test]
+fn spectre_outputs_export_to_csv() {
+    use std::collections::HashMap;
+
+    use crate::analysis::{ac, tran};
+
+    let (vout, resolver) = rc_tb_resolver();
+    let mut output = tran::Output {
+        time: Arc::new(vec![0., 1e-9]),
+        raw_values: HashMap::from_iter([(ArcStr::from("vout"), Arc::new(vec![0., 1.8]))]),
+        saved_values: HashMap::new(),
+        resolver: Some(resolver.clone()),
+    };
+
+    // Columns name each node by its SCIR path.
+    let mut buf = Vec::new();
+    output.write_csv(&mut buf, &[vout.clone()]).unwrap();
+    assert_eq!(
+        String::from_utf8(buf).unwrap(),
+        "time,vout\n0e0,0e0\n1e-9,1.8e0\n"
+    );
+
+    assert_eq!(output.raw_waveform("vout").unwrap().x[1], 1.8);
+    assert_eq!(
+        output.raw_waveform("vot").unwrap_err().to_string(),
+        "signal `vot` was not saved; similar saved signals: `vout`"
+    );
+    output.raw_values.clear();
+    assert!(output.write_csv(Vec::new(), &[vout.clone()]).is_err());
+    output.resolver = None;
+    assert!(output.write_csv(Vec::new(), &[vout.clone()]).is_err());
+
+    let output = ac::Output {
+        freq: Arc::new(vec![1e3]),
+        raw_values: HashMap::from_iter([(
+            ArcStr::from("vout"),
+            Arc::new(vec![Complex64::new(0.5, -0.25)]),
+        )]),
+        saved_values: HashMap::new(),
+        resolver: Some(resolver),
+    };
+    assert_eq!(
+        output.node_voltage(&vout).unwrap()[0],
+        Complex64::new(0.5, -0.25)
+    );
+    let mut buf = Vec::new();
+    output.write_csv(&mut buf, &[vout]).unwrap();
+    assert_eq!(
+        String::from_utf8(buf).unwrap(),
+        "freq,re(vout),im(vout)\n1e3,5e-1,-2.5e-1\n"
+    );
+}
+
+#[test]
+fn spectre_tran_output_exports_to_vcd() {
+    use std::collections::HashMap;
+
+    use substrate::simulation::export::Thresholds;
+
+    use crate::analysis::tran;
+
+    let output = tran::Output {
+        time: Arc::new(vec![0., 1e-12, 2e-12, 3e-12]),
+        raw_values: HashMap::from_iter([
+            (ArcStr::from("xdut.out"), Arc::new(vec![0., 0.9, 1.8, 1.8])),
+            (ArcStr::from("clk"), Arc::new(vec![1.8, 1.8, 0., 0.])),
+        ]),
+        saved_values: HashMap::new(),
+        resolver: None,
+    };
+
+    // Scopes come from SCIR paths, so raw names such as `xdut.out` are not exported.
+    let mut buf = Vec::new();
+    output
+        .write_vcd(&mut buf, &[], Thresholds::from_vdd(1.8))
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(buf).unwrap(),
+        "$timescale 1fs $end\n\
+         $scope module top $end\n\
+         $upscope $end\n\
+         $enddefinitions $end\n"
+    );
+
+    assert_eq!(
+        output
+            .write_fsdb(Vec::new(), &[], Thresholds::from_vdd(1.8))
+            .unwrap_err()
+            .kind(),
+        std::io::ErrorKind::Unsupported
+    );
+}
+
+#[cfg(feature = "plot")]
+#[test]
+fn spectre_tran_output_plots() {
+    use std::collections::HashMap;
+
+    use crate::analysis::tran;
+
+    let (vout, resolver) = rc_tb_resolver();
+    let output = tran::Output {
+        time: Arc::new(vec![0., 1e-9, 2e-9]),
+        raw_values: HashMap::from_iter([(ArcStr::from("vout"), Arc::new(vec![1.8, 0.9, 0.]))]),
+        saved_values: HashMap::new(),
+        resolver: Some(resolver),
+    };
+
+    // Legends name each node by its SCIR path.
+    let svg = get_path("spectre_tran_output_plots", "tran.svg");
+    output.plot(&svg, &[vout.clone()]).unwrap();
+    assert!(std::fs::read_to_string(&svg).unwrap().contains("vout"));
+
+    let png = get_path("spectre_tran_output_plots", "xy.png");
+    output.plot_xy(&png, &vout, &[vout.clone()]).unwrap();
+    assert!(png.exists());
+}
+
+#[test]
+fn spectre_dry_run_writes_netlist_and_run_script() {
+    let test_name = "spectre_dry_run_writes_netlist_and_run_script";
+    let sim_dir = PathBuf::from(BUILD_DIR).join(test_name).join("sim/");
+    let ctx = spectre_ctx();
+    let sim = ctx
+        .get_sim_controller(RcTb::new(dec!(0)), &sim_dir)
+        .expect("failed to create sim controller");
+
+    let artifacts = sim
+        .dry_run(
+            Options::default(),
+            Tran {
+                stop: Time::new(dec!(10e-6)),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+    assert_eq!(artifacts.netlist, sim_dir.join("netlist.scs"));
+    assert_eq!(artifacts.run_script, sim_dir.join("simulate.sh"));
+    let netlist = std::fs::read_to_string(&artifacts.netlist).unwrap();
+    assert!(netlist.contains("save vout"));
+    assert!(netlist.contains("analysis_0 tran stop=0.00001"));
+    let run_script = std::fs::read_to_string(&artifacts.run_script).unwrap();
+    assert!(run_script.contains("netlist.scs"));
+    assert!(!sim_dir.join("psf").exists());
+}
+
+#[test]
+fn spectre_operating_points_are_saved_and_restored() {
+    let test_name = "spectre_operating_points_are_saved_and_restored";
+    let sim_dir = PathBuf::from(BUILD_DIR).join(test_name).join("sim/");
+    let nodesets = get_path(test_name, "restore.ns");
+    std::fs::create_dir_all(nodesets.parent().unwrap()).unwrap();
+    std::fs::write(&nodesets, "vout 0.9\n").unwrap();
+    let ctx = spectre_ctx();
+    let sim = ctx
+        .get_sim_controller(RcTb::new(dec!(0)), &sim_dir)
+        .expect("failed to create sim controller");
+
+    let mut opts = Options::default();
+    opts.save_op(get_path(test_name, "save.ns"));
+    opts.restore_op(&nodesets);
+    let artifacts = sim
+        .dry_run(
+            opts,
+            Tran {
+                stop: Time::new(dec!(10e-6)),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+    let netlist = std::fs::read_to_string(&artifacts.netlist).unwrap();
+    assert!(netlist.contains("op_snapshot dc readns=\"op_restore.ns\" write=\"op_save.ns\""));
+    assert_eq!(
+        std::fs::read_to_string(sim_dir.join("op_restore.ns")).unwrap(),
+        "vout 0.9\n"
+    );
+}
+
+#[test]
+fn spectre_run_script_includes_job_metadata_and_license_queue() {
+    use crate::{JobMetadata, LicenseQueue};
+
+    let test_name = "spectre_run_script_includes_job_metadata_and_license_queue";
+    let sim_dir = PathBuf::from(BUILD_DIR).join(test_name).join("sim/");
+    let ctx = spectre_ctx();
+    let sim = ctx
+        .get_sim_controller(RcTb::new(dec!(0)), &sim_dir)
+        .expect("failed to create sim controller");
+
+    let mut opts = Options::default();
+    opts.set_flags("+aps");
+    opts.set_job(JobMetadata {
+        design: Some("rc tb".to_string()),
+        tag: Some("nightly".to_string()),
+    });
+    opts.queue_licenses(LicenseQueue {
+        timeout: None,
+        sleep: Some(30),
+    });
+    let artifacts = sim
+        .dry_run(
+            opts,
+            Tran {
+                stop: Time::new(dec!(10e-6)),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+    let run_script = std::fs::read_to_string(&artifacts.run_script).unwrap();
+    assert!(run_script.contains("export SUBSTRATE_JOB_DESIGN='rc tb'"));
+    assert!(run_script.contains("export SUBSTRATE_JOB_TAG='nightly'"));
+    assert!(run_script.contains("+aps +lqtimeout 0 +lqsleep 30"));
+    assert!(run_script.contains("spectre.status"));
+}
+
+#[test]
+fn spectre_run_report_is_parsed() {
+    use crate::report::RunReport;
+
+    let log = "\
+Intrinsic tran analysis time:    CPU = 1.2 s, elapsed = 1.3 s.
+Time used: CPU = 52.1 ms, elapsed = 60 ms.
+
+Aggregate audit (10:00:01 AM, Mon Jan 1, 2024):
+Time used: CPU = 1m 1.5s, elapsed = 1m 2s, util. = 98.1%.
+Time spent in licensing: elapsed = 39 ms.
+Peak resident memory used = 93.8 Mbytes.
+spectre completes with 0 errors, 2 warnings, and 4 notices.
+";
+    let report = RunReport::from_log(log, Some(0));
+    assert!(report.success());
+    assert_relative_eq!(report.license_wait.unwrap().as_secs_f64(), 0.039);
+    assert_relative_eq!(report.stats.cpu_time.unwrap().as_secs_f64(), 61.5);
+    assert_relative_eq!(report.stats.elapsed.unwrap().as_secs_f64(), 62.);
+    assert_relative_eq!(report.stats.peak_memory_mb.unwrap(), 93.8);
+    assert_eq!(report.stats.errors, Some(0));
+    assert_eq!(report.stats.warnings, Some(2));
+    assert_eq!(report.stats.notices, Some(4));
+
+    let report = RunReport::from_log(
+        "spectre terminated prematurely due to fatal error.",
+        Some(1),
+    );
+    assert!(!report.success());
+    assert_eq!(report.stats, Default::default());
+}
+
+#[test]
+fn spectre_run_report_is_cleared() {
+    use crate::report::{RunReport, LOG_FILE, STATUS_FILE};
+
+    let work_dir = get_path("spectre_run_report_is_cleared", "sim/");
+    std::fs::create_dir_all(&work_dir).unwrap();
+    std::fs::write(
+        work_dir.join(LOG_FILE),
+        "spectre completes with 0 errors, 0 warnings, and 0 notices.",
+    )
+    .unwrap();
+    std::fs::write(work_dir.join(STATUS_FILE), "0").unwrap();
+    assert!(RunReport::read(&work_dir).unwrap().success());
+
+    RunReport::clear(&work_dir).unwrap();
+    assert!(RunReport::read(&work_dir).is_err());
+    // Clearing a directory without a report is not an error.
+    RunReport::clear(&work_dir).unwrap();
+}
+
+#[test]
+fn spectre_info_oppoint_is_parsed() {
+    use crate::analysis::info::{parse_info, InfoValue, OpRegion};
+
+    let psf = r#"HEADER
+"PSFversion" "1.00"
+"analysis type" "info"
+TYPE
+"bsim4" STRUCT(
+"ids" FLOAT DOUBLE PROP(
+"units" "A"
+)
+"vgs" FLOAT DOUBLE
+"region" STRING *
+)
+"resistor" STRUCT(
+"i" FLOAT DOUBLE
+"res" FLOAT DOUBLE
+)
+"bjt" STRUCT(
+"ic" FLOAT DOUBLE
+"caps" STRUCT(
+"cbe" FLOAT DOUBLE
+"cbc" COMPLEX DOUBLE
+)
+"region" STRING *
+)
+VALUE
+"xdut.mn" "bsim4" (
+1.5e-05 0.6 "sat" )
+"xdut.mp" "bsim4" (
+-2e-06 -0.9 "triode" ) PROP(
+"model" "pch"
+)
+"r0" "resistor" (
+0.001 1000.0 )
+"q0" "bjt" (
+1e-3 ( 2e-15 (3e-15 4e-16) ) "fwd" )
+END
+"#;
+    let entries = parse_info(psf).expect("failed to parse info");
+    assert_eq!(entries.len(), 4);
+
+    let mn = &entries["xdut.mn"];
+    assert_eq!(mn.primitive.as_deref(), Some("bsim4"));
+    assert_relative_eq!(mn.get("ids").and_then(InfoValue::as_real).unwrap(), 1.5e-5);
+    assert_eq!(mn.region(), Some(OpRegion::Saturation));
+    assert!(mn.region().unwrap().is_saturated());
+
+    let mp = &entries["xdut.mp"];
+    assert_relative_eq!(mp.get("vgs").and_then(InfoValue::as_real).unwrap(), -0.9);
+    assert!(mp.region().unwrap().is_triode());
+
+    let r0 = &entries["r0"];
+    assert_relative_eq!(r0.get("res").and_then(InfoValue::as_real).unwrap(), 1000.);
+    assert_eq!(r0.region(), None);
+
+    // Nested struct fields are flattened into their parent.
+    let q0 = &entries["q0"];
+    assert_eq!(q0.values.len(), 4);
+    assert_relative_eq!(
+        q0.get("caps.cbe").and_then(InfoValue::as_real).unwrap(),
+        2e-15
+    );
+    assert_eq!(
+        q0.get("caps.cbc"),
+        Some(&InfoValue::Complex(Complex64::new(3e-15, 4e-16)))
+    );
+    assert_eq!(q0.get("region").and_then(InfoValue::as_str), Some("fwd"));
+
+    // Values must match the fields of their type.
+    let mismatched = psf.replace("0.001 1000.0 )", "0.001 )");
+    assert!(parse_info(&mismatched).is_err());
+    let mismatched = psf.replace("0.001 1000.0 )", "0.001 1000.0 2.0 )");
+    assert!(parse_info(&mismatched).is_err());
+}
+
+#[test]
+fn spectre_dut_testbench_attaches_sources_and_loads() {
+    use substrate::simulation::testbench::DutTestbench;
+    use substrate::types::{Input, Output};
+
+    #[derive(Io, Clone, Default)]
+    struct RcFilterIo {
+        vss: InOut<Signal>,
+        vin: Input<Signal>,
+        vout: Output<Signal>,
+    }
+
+    #[derive(Debug, Copy, Clone, Hash, Eq, PartialEq, Block)]
+    #[substrate(io = "RcFilterIo")]
+    struct RcFilter;
+
+    impl Schematic for RcFilter {
+        type Schema = Spectre;
+        type NestedData = ();
+        fn schematic(
+            &self,
+            io: &IoNodeBundle<Self>,
+            cell: &mut CellBuilder<<Self as Schematic>::Schema>,
+        ) -> substrate::error::Result<Self::NestedData> {
+            let r = cell.instantiate(Resistor::new(dec!(1000)));
+            cell.connect(r.io().p, io.vin);
+            cell.connect(r.io().n, io.vout);
+
+            let c = cell.instantiate(Capacitor::new(dec!(1e-9)));
+            cell.connect(c.io().p, io.vout);
+            cell.connect(c.io().n, io.vss);
+            Ok(())
+        }
+    }
+
+    let ctx = spectre_ctx();
+    let tb = DutTestbench::new(RcFilter)
+        .ground("vss")
+        .stimulus("vin", Vsource::dc(Voltage::new(dec!(1.8))))
+        .load("vout", Capacitor::new(dec!(1e-12)));
+    let lib = ctx.export_scir(tb).expect("failed to export testbench");
+    let top = lib.scir.cell(lib.scir.top_cell().unwrap());
+    let mut names = top
+        .instances()
+        .map(|(_, inst)| inst.name().to_string())
+        .collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(names, ["xdut", "xload_vout", "xstimulus_vin"]);
+
+    // Stimuli cannot be attached to outputs.
+    let tb = DutTestbench::<_, _, Capacitor>::new(RcFilter)
+        .stimulus("vout", Vsource::dc(Voltage::new(dec!(0))));
+    assert!(ctx.export_scir(tb).is_err());
+}
+
+#[test]
+fn spectre_output_files_are_discovered() {
+    use crate::error::Error;
+    use crate::output::{OutputKind, OutputNaming};
+
+    let dir = get_path("spectre_output_files_are_discovered", "psf");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    for file in [
+        "analysis_0.tran",
+        "analysis_0.tran.ascii",
+        "analysis_1.ac",
+        "analysis_10.dc",
+        "analysis_2.tran.tran.tran",
+        "analysis_2.tran.tran.tran.psfxl",
+    ] {
+        std::fs::write(dir.join(file), "").unwrap();
+    }
+
+    let naming = OutputNaming::default();
+    assert_eq!(
+        naming.locate(&dir, "analysis_0", OutputKind::Tran).unwrap(),
+        "analysis_0.tran"
+    );
+    assert_eq!(
+        naming.locate(&dir, "analysis_1", OutputKind::Ac).unwrap(),
+        "analysis_1.ac"
+    );
+    assert_eq!(
+        naming.locate(&dir, "analysis_2", OutputKind::Tran).unwrap(),
+        "analysis_2.tran.tran.tran"
+    );
+    assert!(matches!(
+        naming.locate(&dir, "analysis_1", OutputKind::Dc),
+        Err(Error::MissingOutput {
+            kind: OutputKind::Dc,
+            ..
+        })
+    ));
+
+    let mut naming = OutputNaming::default();
+    naming.set_pattern(OutputKind::Tran, "{name}.tran.tran.tran");
+    naming.discover(false);
+    assert_eq!(
+        naming.locate(&dir, "analysis_0", OutputKind::Tran).unwrap(),
+        "analysis_0.tran.tran.tran"
+    );
+}
+
+#[test]
+fn spectre_managed_includes_are_verified_and_copied() {
+    use crate::error::Error;
+    use crate::include::{IncludeKind, ManagedInclude};
+
+    let work_dir = get_path("spectre_managed_includes_are_verified_and_copied", "sim");
+    let _ = std::fs::remove_dir_all(&work_dir);
+    std::fs::create_dir_all(&work_dir).unwrap();
+    let model = work_dir.join("models.scs");
+    std::fs::write(&model, "simulator lang=spectre\n").unwrap();
+
+    let include = ManagedInclude::new(&model)
+        .section("tt")
+        .kind(IncludeKind::Protected)
+        .copy_to_work_dir();
+    assert!(matches!(
+        include.clone().sha256("0".repeat(64)).resolve(&work_dir, 0),
+        Err(Error::ChecksumMismatch { .. })
+    ));
+
+    // Checksums are compared case-insensitively.
+    let include =
+        include.sha256("050D088F04A34128775C0E282E9C9A8906363FF14FC7354B5DF4833915D0B4AF");
+    let path = include.resolve(&work_dir, 0).unwrap();
+    assert_eq!(path, work_dir.join("includes/0/models.scs"));
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "simulator lang=spectre\n"
+    );
+
+    let mut netlist = Vec::new();
+    include.netlist(&mut netlist, &path).unwrap();
+    assert_eq!(
+        String::from_utf8(netlist).unwrap(),
+        format!("protect\ninclude {path:?} section=tt\nunprotect\n")
+    );
+
+    let include = ManagedInclude::new("model.va").kind(IncludeKind::Ahdl);
+    let mut netlist = Vec::new();
+    include
+        .netlist(&mut netlist, Path::new("model.va"))
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(netlist).unwrap(),
+        "ahdl_include \"model.va\"\n"
+    );
+}
+
+#[test]
+fn spectre_monte_carlo_iterations_can_be_rerun() {
+    use crate::analysis::montecarlo::{Iteration, MonteCarlo, Variations};
+
+    let mc = MonteCarlo {
+        variations: Variations::Mismatch,
+        numruns: 3,
+        seed: Some(1234),
+        firstrun: Some(5),
+        analysis: DcOp,
+    };
+    assert_eq!(
+        mc.iterations(),
+        [5, 6, 7].map(|run| Iteration {
+            run,
+            seed: Some(1234)
+        })
+    );
+
+    let rerun = mc.rerun(mc.iterations()[1]);
+    assert_eq!(rerun.numruns, 1);
+    assert_eq!(rerun.seed, Some(1234));
+    assert_eq!(rerun.firstrun, Some(6));
+    assert_eq!(rerun.variations, Variations::Mismatch);
+    assert_eq!(rerun.iterations(), [mc.iterations()[1]]);
+}
+
+#[test]
+fn spectre_sens_is_netlisted_and_ranked() {
+    use crate::analysis::sens::{Output, Sens, SensOutput, SensParam, SensValue};
+    use crate::Input;
+
+    let sens = Sens::dc([SensOutput::node("out"), SensOutput::param("xdut.mn", "ids")])
+        .params([SensParam::new("xdut.mn", "w"), SensParam::new("r0", "r")]);
+    let mut netlist = Vec::new();
+    Input::from(sens)
+        .netlist(&mut netlist, "analysis_0", None)
+        .expect("failed to netlist sensitivity analysis");
+    assert_eq!(
+        String::from_utf8(netlist).unwrap(),
+        "analysis_0_an dc\n\
+         analysis_0_sens_0 sens (out) to (xdut.mn:w r0:r) for (analysis_0_an)\n\
+         analysis_0_sens_1 sens (xdut.mn:ids) to (xdut.mn:w r0:r) for (analysis_0_an)"
+    );
+
+    let output = Output {
+        freq: None,
+        raw_values: vec![[
+            (arcstr::literal!("xdut.mn:w"), SensValue::Dc(-2e5)),
+            (arcstr::literal!("r0:r"), SensValue::Dc(1e-3)),
+            (arcstr::literal!("xdut.mp:w"), SensValue::Dc(3e5)),
+        ]
+        .into_iter()
+        .collect()],
+        resolver: None,
+    };
+    assert_relative_eq!(output.raw(0, "r0:r").unwrap().dc(), 1e-3);
+    assert!(output.raw(0, "r1:r").is_err());
+    assert!(output.output(1).is_err());
+    assert!(output.dominant(1).is_empty());
+    assert_eq!(
+        output
+            .dominant(0)
+            .into_iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>(),
+        ["xdut.mp:w", "xdut.mn:w", "r0:r"]
+    );
+}
+
+#[test]
+fn spectre_sens_resolves_substrate_paths() {
+    use crate::analysis::sens::{Sens, SensOutput, SensParam};
+    use crate::error::Error;
+    use crate::Input;
+    use spice::netlist::RenameGround;
+
+    #[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Serialize, Deserialize, Block)]
+    #[substrate(io = "TestbenchIo")]
+    struct SensTb;
+
+    #[derive(NestedData)]
+    struct SensTbData {
+        out: Node,
+        vdd: substrate::schematic::Instance<Vsource>,
+        r0: substrate::schematic::Instance<Resistor>,
+    }
+
+    impl Schematic for SensTb {
+        type Schema = Spectre;
+        type NestedData = SensTbData;
+        fn schematic(
+            &self,
+            io: &IoNodeBundle<Self>,
+            cell: &mut CellBuilder<<Self as Schematic>::Schema>,
+        ) -> substrate::error::Result<Self::NestedData> {
+            let out = cell.signal("out", Signal);
+            let vdd = cell.instantiate_named(Vsource::dc(Voltage::new(dec!(1.8))), "vdd");
+            cell.connect(vdd.io().n, io.vss);
+
+            let r0 = cell.instantiate_named(Resistor::new(dec!(1000)), "r0");
+            cell.connect(r0.io().p, vdd.io().p);
+            cell.connect(r0.io().n, out);
+
+            let r1 = cell.instantiate(Resistor::new(dec!(1000)));
+            cell.connect(r1.io().p, out);
+            cell.connect(r1.io().n, io.vss);
+
+            Ok(SensTbData { out, vdd, r0 })
+        }
+    }
+
+    let ctx = spectre_ctx();
+    let tb = ctx.generate_schematic(SensTb);
+    let data = tb.cell().data();
+    let lib = ctx.export_scir(SensTb).unwrap();
+    let conv = NetlisterInstance::new(
+        &Spectre::default(),
+        &lib.scir,
+        &mut Vec::new(),
+        NetlistOptions::new(NetlistKind::Testbench(RenameGround::Yes("0".into())), &[]),
+    )
+    .export()
+    .unwrap();
+
+    let mut input = Input::from(
+        Sens::dc([
+            SensOutput::from(&data.out),
+            SensOutput::param(data.vdd.path(), "i"),
+        ])
+        .params([SensParam::new(data.r0.path(), "r")]),
+    );
+
+    // Paths must be resolved before netlisting.
+    assert!(matches!(
+        input.netlist(&mut Vec::new(), "analysis_0", None),
+        Err(Error::UnresolvedPath(_))
+    ));
+
+    input.resolve(&lib, &conv, &Options::default()).unwrap();
+    let mut netlist = Vec::new();
+    input.netlist(&mut netlist, "analysis_0", None).unwrap();
+    assert_eq!(
+        String::from_utf8(netlist).unwrap(),
+        "analysis_0_an dc\n\
+         analysis_0_sens_0 sens (out) to (r0:r) for (analysis_0_an)\n\
+         analysis_0_sens_1 sens (vdd:i) to (r0:r) for (analysis_0_an)"
+    );
+}
+
+#[test]
+fn spectre_stb_is_netlisted_and_margins_are_computed() {
+    use crate::analysis::stb::{Output, Stb};
+    use crate::Input;
+
+    let stb = Stb::new(
+        Frequency::new(dec!(1)),
+        Frequency::new(dec!(1e9)),
+        Sweep::Decade(20),
+        "xdut.iprb0",
+    );
+    let mut netlist = Vec::new();
+    Input::from(stb)
+        .netlist(&mut netlist, "analysis_0", None)
+        .expect("failed to netlist stability analysis");
+    assert_eq!(
+        String::from_utf8(netlist).unwrap(),
+        "analysis_0 stb start=1 stop=1000000000 dec=20 probe=xdut.iprb0"
+    );
+
+    // A three-pole loop with a DC gain of 60 dB.
+    let freq = (0..=180)
+        .map(|i| 10f64.powf(i as f64 / 20.))
+        .collect::<Vec<_>>();
+    let loop_gain = freq
+        .iter()
+        .map(|&f| {
+            let pole = |p: f64| Complex64::new(1., f / p);
+            Complex64::new(1e3, 0.) / (pole(1e3) * pole(1e6) * pole(1e7))
+        })
+        .collect::<Vec<_>>();
+    let output = Output {
+        freq: Arc::new(freq),
+        loop_gain: Arc::new(loop_gain),
+    };
+    assert_relative_eq!(output.magnitude_db()[0], 60., max_relative = 1e-3);
+    assert_relative_eq!(
+        output.unity_gain_freq().unwrap(),
+        7.9e5,
+        max_relative = 0.02
+    );
+    assert_relative_eq!(output.phase_margin().unwrap(), 47.2, epsilon = 0.5);
+    assert_relative_eq!(
+        output.phase_crossover_freq().unwrap(),
+        3.17e6,
+        max_relative = 0.02
+    );
+    assert_relative_eq!(output.gain_margin().unwrap(), 20.9, epsilon = 0.5);
+    assert!(output.phase_deg().windows(2).all(|w| w[1] <= w[0]));
+}
+
+#[test]
+fn spectre_stb_resolves_substrate_probe_paths() {
+    use crate::analysis::stb::Stb;
+    use crate::blocks::Iprobe;
+    use crate::error::Error;
+    use crate::Input;
+    use spice::netlist::RenameGround;
+
+    #[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Serialize, Deserialize, Block)]
+    #[substrate(io = "TestbenchIo")]
+    struct StbTb;
+
+    #[derive(NestedData)]
+    struct StbTbData {
+        probe: substrate::schematic::Instance<Iprobe>,
+    }
+
+    impl Schematic for StbTb {
+        type Schema = Spectre;
+        type NestedData = StbTbData;
+        fn schematic(
+            &self,
+            io: &IoNodeBundle<Self>,
+            cell: &mut CellBuilder<<Self as Schematic>::Schema>,
+        ) -> substrate::error::Result<Self::NestedData> {
+            let out = cell.signal("out", Signal);
+            let probe = cell.instantiate_named(Iprobe, "iprb0");
+            cell.connect(probe.io().p, out);
+            cell.connect(probe.io().n, io.vss);
+
+            let r = cell.instantiate(Resistor::new(dec!(1000)));
+            cell.connect(r.io().p, out);
+            cell.connect(r.io().n, io.vss);
+
+            Ok(StbTbData { probe })
+        }
+    }
+
+    let ctx = spectre_ctx();
+    let tb = ctx.generate_schematic(StbTb);
+    let data = tb.cell().data();
+    let lib = ctx.export_scir(StbTb).unwrap();
+    let conv = NetlisterInstance::new(
+        &Spectre::default(),
+        &lib.scir,
+        &mut Vec::new(),
+        NetlistOptions::new(NetlistKind::Testbench(RenameGround::Yes("0".into())), &[]),
+    )
+    .export()
+    .unwrap();
+
+    let mut input = Input::from(Stb::new(
+        Frequency::new(dec!(1)),
+        Frequency::new(dec!(1e9)),
+        Sweep::Decade(20),
+        data.probe.path(),
+    ));
+
+    // The probe must be resolved before netlisting.
+    assert!(matches!(
+        input.netlist(&mut Vec::new(), "analysis_0", None),
+        Err(Error::UnresolvedPath(_))
+    ));
+
+    input.resolve(&lib, &conv, &Options::default()).unwrap();
+    let mut netlist = Vec::new();
+    input.netlist(&mut netlist, "analysis_0", None).unwrap();
+    assert_eq!(
+        String::from_utf8(netlist).unwrap(),
+        "analysis_0 stb start=1 stop=1000000000 dec=20 probe=iprb0"
+    );
+}
+
+#[test]
+fn spectre_periodic_analyses_are_netlisted() {
+    use crate::analysis::pac::Pac;
+    use crate::analysis::pnoise::{Output, Pnoise};
+    use crate::analysis::pss::{Pss, PssMethod};
+    use crate::Input;
+
+    let netlist = |input: Input| {
+        let mut netlist = Vec::new();
+        input
+            .netlist(&mut netlist, "analysis_0", None)
+            .expect("failed to netlist periodic analysis");
+        String::from_utf8(netlist).unwrap()
+    };
+
+    assert_eq!(
+        netlist(
+            Pss::driven(Frequency::new(dec!(1e9)), 8)
+                .tstab(Time::new(dec!(1e-8)))
+                .into()
+        ),
+        "analysis_0 pss fund=1000000000 harms=8 tstab=0.00000001"
+    );
+    assert_eq!(
+        netlist(
+            Pss::oscillator("outp", "outn", Frequency::new(dec!(2e9)), 5)
+                .method(PssMethod::HarmonicBalance)
+                .into()
+        ),
+        "analysis_0 (outp outn) pss fund=2000000000 harms=5 flexbalance=yes"
+    );
+    assert_eq!(
+        netlist(
+            Pac::new(
+                Frequency::new(dec!(1e3)),
+                Frequency::new(dec!(1e6)),
+                Sweep::Decade(10),
+                [-1, 1]
+            )
+            .into()
+        ),
+        "analysis_0 pac start=1000 stop=1000000 dec=10 sidebands=[-1 1]"
+    );
+    assert_eq!(
+        netlist(
+            Pnoise::new(
+                "outp",
+                Frequency::new(dec!(1e3)),
+                Frequency::new(dec!(1e6)),
+                Sweep::Linear(100),
+                7
+            )
+            .reference("outn")
+            .into()
+        ),
+        "analysis_0 (outp outn) pnoise start=1000 stop=1000000 lin=100 maxsideband=7"
+    );
+
+    let output = Output {
+        freq: Arc::new(vec![0., 1e3, 2e3]),
+        raw_values: [(arcstr::literal!("out"), Arc::new(vec![1e-6; 3]))]
+            .into_iter()
+            .collect(),
+    };
+    assert_relative_eq!(
+        output.integrated_output_noise().unwrap(),
+        1e-6 * 2e3f64.sqrt()
+    );
+    assert!(output.raw("in").is_err());
+}
+
+#[test]
+fn spectre_periodic_outputs_are_parsed() {
+    use crate::analysis::pac::Pac;
+    use crate::analysis::pnoise::Pnoise;
+    use crate::error::Error;
+    use crate::{parse_analysis, CachedData, Input, PsfReader};
+
+    let dir = get_path("spectre_periodic_outputs_are_parsed", "psf/");
+    std::fs::create_dir_all(&dir).unwrap();
+    let header = "HEADER\n\"PSFversion\" \"1.00\"\nTYPE\n\
+                  \"sweep\" FLOAT DOUBLE PROP(\n\"key\" \"sweep\"\n)\n";
+    // The input frequency is swept once per sideband, in the order of the sidebands.
+    std::fs::write(
+        dir.join("analysis_0.pac"),
+        format!(
+            "{header}\"V\" COMPLEX DOUBLE PROP(\n\"key\" \"node\"\n)\n\
+             SWEEP\n\"freq\" \"sweep\"\nTRACE\n\"out\" \"V\"\nVALUE\n\
+             \"freq\" 1000.00\n\"out\" (1.00000 0.00000)\n\
+             \"freq\" 2000.00\n\"out\" (2.00000 0.00000)\n\
+             \"freq\" 1000.00\n\"out\" (0.00000 3.00000)\n\
+             \"freq\" 2000.00\n\"out\" (0.00000 4.00000)\nEND\n"
+        ),
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("analysis_1.pnoise"),
+        format!(
+            "{header}\"V/sqrt(Hz)\" FLOAT DOUBLE PROP(\n\"key\" \"node\"\n)\n\
+             SWEEP\n\"freq\" \"sweep\"\nTRACE\n\"out\" \"V/sqrt(Hz)\"\nVALUE\n\
+             \"freq\" 1000.00\n\"out\" 1.00000e-06\n\
+             \"freq\" 2000.00\n\"out\" 2.00000e-06\nEND\n"
+        ),
+    )
+    .unwrap();
+
+    let reader = PsfReader {
+        output_dir: &dir,
+        naming: &Default::default(),
+        executor: &LocalExecutor,
+        intern_names: false,
+        decimation: None,
+    };
+    let start = Frequency::new(dec!(1e3));
+    let stop = Frequency::new(dec!(2e3));
+
+    let pac = Input::from(Pac::new(start, stop, Sweep::Linear(2), [-1, 1]));
+    let Ok(CachedData::Pac { freq, values }) = parse_analysis(&reader, "", "analysis_0", &pac)
+    else {
+        panic!("failed to parse periodic AC output");
+    };
+    assert_eq!(freq, [1e3, 2e3]);
+    assert_eq!(values.len(), 2);
+    assert_eq!(values[0].0, -1);
+    assert_eq!(
+        values[0].1["out"],
+        [Complex64::new(1., 0.), Complex64::new(2., 0.)]
+    );
+    assert_eq!(values[1].0, 1);
+    assert_eq!(
+        values[1].1["out"],
+        [Complex64::new(0., 3.), Complex64::new(0., 4.)]
+    );
+
+    // The output must contain one sweep per sideband.
+    let pac = Input::from(Pac::new(start, stop, Sweep::Linear(2), [-1, 0, 1]));
+    assert!(matches!(
+        parse_analysis(&reader, "", "analysis_0", &pac),
+        Err(Error::Parse)
+    ));
+
+    let pnoise = Input::from(Pnoise::new("out", start, stop, Sweep::Linear(2), 7));
+    let Ok(CachedData::Pnoise { freq, values }) =
+        parse_analysis(&reader, "", "analysis_1", &pnoise)
+    else {
+        panic!("failed to parse periodic noise output");
+    };
+    assert_eq!(freq, [1e3, 2e3]);
+    assert_eq!(values["out"], [1e-6, 2e-6]);
+}
+
+#[test]
+fn spectre_noise_resolves_substrate_paths() {
+    use crate::analysis::noise::Noise;
+    use crate::error::Error;
+    use crate::Input;
+    use spice::netlist::RenameGround;
+    use substrate::simulation::analysis as common;
+    use substrate::simulation::SupportedBy;
+
+    #[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Serialize, Deserialize, Block)]
+    #[substrate(io = "TestbenchIo")]
+    struct NoiseTb;
+
+    #[derive(NestedData)]
+    struct NoiseTbData {
+        vout: Node,
+        vin: substrate::schematic::Instance<Vsource>,
+    }
+
+    impl Schematic for NoiseTb {
+        type Schema = Spectre;
+        type NestedData = NoiseTbData;
+        fn schematic(
+            &self,
+            io: &IoNodeBundle<Self>,
+            cell: &mut CellBuilder<<Self as Schematic>::Schema>,
+        ) -> substrate::error::Result<Self::NestedData> {
+            let vout = cell.signal("vout", Signal);
+            let vin = cell.instantiate_named(Vsource::dc(Voltage::new(dec!(0))), "vin");
+            cell.connect(vin.io().n, io.vss);
+
+            let r = cell.instantiate(Resistor::new(dec!(1000)));
+            cell.connect(r.io().p, vin.io().p);
+            cell.connect(r.io().n, vout);
+
+            let c = cell.instantiate(Capacitor::new(dec!(1e-9)));
+            cell.connect(c.io().p, vout);
+            cell.connect(c.io().n, io.vss);
+
+            Ok(NoiseTbData { vout, vin })
+        }
+    }
+
+    let ctx = spectre_ctx();
+    let tb = ctx.generate_schematic(NoiseTb);
+    let data = tb.cell().data();
+    let lib = ctx.export_scir(NoiseTb).unwrap();
+    let conv = NetlisterInstance::new(
+        &Spectre::default(),
+        &lib.scir,
+        &mut Vec::new(),
+        NetlistOptions::new(NetlistKind::Testbench(RenameGround::Yes("0".into())), &[]),
+    )
+    .export()
+    .unwrap();
+
+    let mut inputs = Vec::new();
+    SupportedBy::<Spectre>::into_input(
+        common::Noise {
+            output: (&data.vout).into(),
+            input_source: data.vin.path().into(),
+            start: Frequency::new(dec!(1)),
+            stop: Frequency::new(dec!(1e6)),
+            sweep: common::Sweep::Decade(10),
+        },
+        &mut inputs,
+    );
+    let mut input = inputs.pop().unwrap();
+
+    // Paths must be resolved before netlisting.
+    assert!(matches!(
+        input.netlist(&mut Vec::new(), "analysis_0", None),
+        Err(Error::UnresolvedPath(_))
+    ));
+
+    input.resolve(&lib, &conv, &Options::default()).unwrap();
+    let mut netlist = Vec::new();
+    input.netlist(&mut netlist, "analysis_0", None).unwrap();
+    assert_eq!(
+        String::from_utf8(netlist).unwrap(),
+        "analysis_0 (vout 0) noise start=1 stop=1000000 dec=10 iprobe=vin"
+    );
+
+    let raw: Input = Noise::new(
+        "outp",
+        Frequency::new(dec!(1)),
+        Frequency::new(dec!(1e3)),
+        Sweep::Linear(10),
+    )
+    .reference("outn")
+    .into();
+    let mut netlist = Vec::new();
+    raw.netlist(&mut netlist, "analysis_1", None).unwrap();
+    assert_eq!(
+        String::from_utf8(netlist).unwrap(),
+        "analysis_1 (outp outn) noise start=1 stop=1000 lin=10"
+    );
+}
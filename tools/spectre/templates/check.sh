@@ -0,0 +1,14 @@
+#!/bin/bash
+
+set -x
+
+{% if bashrc -%}
+source {{ bashrc }}
+{%- endif %}
+
+set -e
+
+{{ executable }} \
+  -checkonly \
+  =log {{ log_path }} \
+  {{ netlist }}
@@ -8,9 +8,45 @@ source {{ bashrc }}
 
 set -e
 
-spectre \
+{% if design -%}
+export SUBSTRATE_JOB_DESIGN={{ design }}
+{% endif -%}
+{% if tag -%}
+export SUBSTRATE_JOB_TAG={{ tag }}
+{% endif -%}
+
+{% if stop_path -%}
+rm -f {{ stop_path }}
+{% endif -%}
+{{ executable }} \
   -format {{ format }} \
   -raw {{ raw_output_path }} \
   =log {{ log_path }} \
   {{ flags }} \
-  {{ netlist }}
+  {{ netlist }} &
+pid=$!
+
+{% if stop_path -%}
+# Stop the simulation when the stop file is created. The stop file is watched here rather than
+# by Substrate so that simulations can be stopped on any host the executor runs them on.
+(
+  set +x
+  while kill -0 $pid 2> /dev/null; do
+    if [ -e {{ stop_path }} ]; then
+      kill $pid
+      break
+    fi
+    sleep 1
+  done
+) &
+watcher=$!
+{% endif -%}
+
+status=0
+wait $pid || status=$?
+{% if stop_path -%}
+kill $watcher 2> /dev/null || true
+{% endif -%}
+
+echo $status > {{ status_path }}
+exit $status